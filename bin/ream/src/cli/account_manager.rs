@@ -1,14 +1,32 @@
-use clap::Parser;
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::Address;
+use clap::{Parser, ValueEnum};
+use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
+
+use crate::cli::constants::DEFAULT_NETWORK;
 
 const DEFAULT_ACTIVATION_EPOCH: u32 = 0;
 const DEFAULT_NUM_ACTIVE_EPOCHS: u32 = 1 << 18;
 
-#[derive(Debug, Parser, Default)]
+/// The signature scheme of the generated keys: hash-based signature keys of the lean chain, or
+/// EIP-2335 BLS keystores of beacon chain validators together with their deposit data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeyScheme {
+    Hashsig,
+    Bls,
+}
+
+#[derive(Debug, Parser)]
 pub struct AccountManagerConfig {
     /// Verbosity level
     #[arg(short, long, default_value_t = 3)]
     pub verbosity: u8,
 
+    /// Signature scheme of the generated keys
+    #[arg(long, value_enum, default_value_t = KeyScheme::Hashsig)]
+    pub scheme: KeyScheme,
+
     /// Account lifetime in 2 ** lifetime slots
     #[arg(short, long, default_value_t = 18, value_parser = clap::value_parser!(u32).range(18..))]
     pub lifetime: u32,
@@ -36,4 +54,30 @@ pub struct AccountManagerConfig {
     /// Path for keystore directory (relative to data-dir if not absolute)
     #[arg(long)]
     pub keystore_path: Option<String>,
+
+    /// Index of the first validator to derive a BLS key for
+    #[arg(long, default_value_t = 0)]
+    pub validator_start_index: u32,
+
+    /// Number of validators to derive BLS keys for
+    #[arg(long, default_value_t = 1)]
+    pub num_validators: u32,
+
+    /// Password to encrypt the BLS keystores with
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Path to a file containing the password to encrypt the BLS keystores with
+    #[arg(long)]
+    pub password_file: Option<PathBuf>,
+
+    /// Choose mainnet, holesky, sepolia, hoodi, dev or provide a path to a YAML config file, for
+    /// the fork version of the deposit data
+    #[arg(long, default_value = DEFAULT_NETWORK, value_parser = beacon_network_parser)]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    /// Execution address to withdraw to. If not set, the withdrawal credentials commit to the BLS
+    /// withdrawal key of each validator
+    #[arg(long)]
+    pub withdrawal_address: Option<Address>,
 }
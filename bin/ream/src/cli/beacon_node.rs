@@ -1,16 +1,29 @@
-use std::{net::IpAddr, path::PathBuf, sync::Arc};
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
+use alloy_primitives::U256;
 use clap::Parser;
+use ream_api_types_beacon::id::ValidatorID;
 use ream_consensus_misc::checkpoint::Checkpoint;
 use ream_network_manager::config::ManagerConfig;
 use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
 use ream_p2p::bootnodes::Bootnodes;
 use url::Url;
 
-use crate::cli::constants::{
-    DEFAULT_DISABLE_DISCOVERY, DEFAULT_DISCOVERY_PORT, DEFAULT_HTTP_ADDRESS,
-    DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_NETWORK, DEFAULT_SOCKET_ADDRESS,
-    DEFAULT_SOCKET_PORT,
+use crate::cli::{
+    constants::{
+        DEFAULT_DISABLE_DISCOVERY, DEFAULT_DISABLE_QUIC, DEFAULT_DISABLE_UPNP,
+        DEFAULT_DISCOVERY_PORT, DEFAULT_FREEZER_RESTORE_POINT_SLOTS, DEFAULT_HTTP_ADDRESS,
+        DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_METRICS_ADDRESS,
+        DEFAULT_METRICS_ENABLED, DEFAULT_METRICS_PORT, DEFAULT_NETWORK, DEFAULT_PREFER_QUIC,
+        DEFAULT_QUIC_PORT, DEFAULT_REQUEST_TIMEOUT, DEFAULT_SOCKET_ADDRESS, DEFAULT_SOCKET_PORT,
+        DEFAULT_TARGET_PEERS,
+    },
+    validator_node::duration_parser,
 };
 
 #[derive(Debug, Parser)]
@@ -36,18 +49,57 @@ pub struct BeaconNodeConfig {
     #[arg(long, default_value_t = DEFAULT_HTTP_ALLOW_ORIGIN)]
     pub http_allow_origin: bool,
 
+    #[arg(
+        long,
+        help = "The file with the bearer token authenticating requests to the admin API under /ream/v1/admin, which is disabled without it"
+    )]
+    pub admin_token_file: Option<PathBuf>,
+
     #[arg(long, help = "Set P2P socket address", default_value_t = DEFAULT_SOCKET_ADDRESS)]
     pub socket_address: IpAddr,
 
+    #[arg(
+        long,
+        help = "Set an IPv6 P2P socket address to listen on next to an IPv4 socket address (dual-stack)"
+    )]
+    pub socket_address_v6: Option<Ipv6Addr>,
+
     #[arg(long, help = "Set P2P socket port (TCP)", default_value_t = DEFAULT_SOCKET_PORT)]
     pub socket_port: u16,
 
+    #[arg(long, help = "Set P2P QUIC port (UDP)", default_value_t = DEFAULT_QUIC_PORT)]
+    pub quic_port: u16,
+
+    #[arg(long, help = "Disable the QUIC transport and only use TCP", default_value_t = DEFAULT_DISABLE_QUIC)]
+    pub disable_quic: bool,
+
+    #[arg(long, help = "Dial peers over QUIC before TCP when they support both", default_value_t = DEFAULT_PREFER_QUIC, conflicts_with = "disable_quic")]
+    pub prefer_quic: bool,
+
     #[arg(long, help = "Discovery 5 listening port (UDP)", default_value_t = DEFAULT_DISCOVERY_PORT)]
     pub discovery_port: u16,
 
+    #[arg(
+        long,
+        help = "Set Discovery 5 listening address [default: the P2P socket address]"
+    )]
+    pub discovery_address: Option<IpAddr>,
+
     #[arg(long, help = "Disable Discv5", default_value_t = DEFAULT_DISABLE_DISCOVERY)]
     pub disable_discovery: bool,
 
+    #[arg(long, help = "Disable UPnP port mapping on the local gateway", default_value_t = DEFAULT_DISABLE_UPNP)]
+    pub disable_upnp: bool,
+
+    #[arg(
+        long,
+        help = "Set the public IP address advertised to peers instead of the socket address or the discovered external address"
+    )]
+    pub advertised_address: Option<IpAddr>,
+
+    #[arg(long, help = "The number of connected peers to maintain", default_value_t = DEFAULT_TARGET_PEERS)]
+    pub target_peers: usize,
+
     #[arg(
         default_value = "default",
         long,
@@ -77,6 +129,53 @@ pub struct BeaconNodeConfig {
         requires = "execution_endpoint"
     )]
     pub execution_jwt_secret: Option<PathBuf>,
+
+    #[arg(
+        long = "mev-relay-url",
+        value_delimiter = ',',
+        help = "Comma-separated HTTP urls of the MEV relays which bids are requested from and blinded blocks published through the beacon api are unblinded with. A relay url of the form https://0x<public key>@host only accepts bids signed by that key"
+    )]
+    pub mev_relay_urls: Vec<Url>,
+
+    #[arg(long, help = "Set HTTP request timeout for MEV relay calls", default_value = DEFAULT_REQUEST_TIMEOUT, value_parser = duration_parser)]
+    pub builder_request_timeout: Duration,
+
+    #[arg(
+        long,
+        help = "The minimum value in wei of the bids accepted from the MEV relays",
+        default_value = "0"
+    )]
+    pub builder_min_bid: U256,
+
+    #[arg(long = "metrics", visible_alias = "enable-metrics", help = "Enable metrics", default_value_t = DEFAULT_METRICS_ENABLED)]
+    pub enable_metrics: bool,
+
+    #[arg(long, help = "Set metrics address", default_value_t = DEFAULT_METRICS_ADDRESS)]
+    pub metrics_address: IpAddr,
+
+    #[arg(long, help = "Set metrics port", default_value_t = DEFAULT_METRICS_PORT)]
+    pub metrics_port: u16,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Comma-separated indices or 0x-prefixed public keys of validators whose performance is tracked from the imported blocks"
+    )]
+    pub monitor_validators: Vec<ValidatorID>,
+
+    #[arg(
+        long,
+        help = "Move the finalized blocks and states out of the database into the append-only freezer in the data directory"
+    )]
+    pub freezer: bool,
+
+    #[arg(
+        long,
+        help = "The freezer keeps the first finalized state of every this many slots, the other finalized states are deleted",
+        default_value_t = DEFAULT_FREEZER_RESTORE_POINT_SLOTS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub freezer_restore_point_slots: u64,
 }
 
 impl From<BeaconNodeConfig> for ManagerConfig {
@@ -86,13 +185,22 @@ impl From<BeaconNodeConfig> for ManagerConfig {
             http_port: config.http_port,
             http_allow_origin: config.http_allow_origin,
             socket_address: config.socket_address,
+            socket_address_v6: config.socket_address_v6,
+            discovery_address: config.discovery_address,
+            advertised_address: config.advertised_address,
             socket_port: config.socket_port,
+            quic_port: config.quic_port,
+            disable_quic: config.disable_quic,
+            prefer_quic: config.prefer_quic,
             discovery_port: config.discovery_port,
             disable_discovery: config.disable_discovery,
+            disable_upnp: config.disable_upnp,
+            target_peers: config.target_peers,
             bootnodes: config.bootnodes,
             checkpoint_sync_url: config.checkpoint_sync_url,
             execution_endpoint: config.execution_endpoint,
             execution_jwt_secret: config.execution_jwt_secret,
+            monitor_validators: config.monitor_validators,
         }
     }
 }
@@ -1,16 +1,35 @@
 use std::net::{IpAddr, Ipv4Addr};
 
 pub const DEFAULT_BEACON_API_ENDPOINT: &str = "http://localhost:5052";
+pub const DEFAULT_DB_CACHE_SIZE_MIB: usize = 1_024;
 pub const DEFAULT_DISABLE_DISCOVERY: bool = false;
+pub const DEFAULT_DISABLE_QUIC: bool = false;
+pub const DEFAULT_DISABLE_UPNP: bool = false;
 pub const DEFAULT_DISCOVERY_PORT: u16 = 9000;
+/// The gas limit validators register with the builders, as recommended for the execution clients
+pub const DEFAULT_GAS_LIMIT: u64 = 36_000_000;
+pub const DEFAULT_GENESIS_DELAY: u64 = 30;
+pub const DEFAULT_GENESIS_NETWORK: &str = "dev";
+pub const DEFAULT_GENESIS_VALIDATOR_COUNT: u64 = 64;
 pub const DEFAULT_HTTP_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 pub const DEFAULT_HTTP_ALLOW_ORIGIN: bool = false;
 pub const DEFAULT_HTTP_PORT: u16 = 5052;
 pub const DEFAULT_KEY_MANAGER_HTTP_PORT: u16 = 8008;
+/// The lean node listens on QUIC at the socket port, so discv5 needs a different UDP port
+pub const DEFAULT_LEAN_DISCOVERY_PORT: u16 = 9100;
 pub const DEFAULT_METRICS_ENABLED: bool = false;
 pub const DEFAULT_METRICS_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 pub const DEFAULT_METRICS_PORT: u16 = 8080;
 pub const DEFAULT_NETWORK: &str = "mainnet";
+pub const DEFAULT_PREFER_QUIC: bool = false;
+pub const DEFAULT_PROVER_WORKERS: usize = 1;
+pub const DEFAULT_QUIC_PORT: u16 = 9001;
+pub const DEFAULT_REQUEST_RETRIES: u32 = 2;
+pub const DEFAULT_REQUEST_RETRY_BACKOFF_MS: u64 = 250;
 pub const DEFAULT_REQUEST_TIMEOUT: &str = "60";
 pub const DEFAULT_SOCKET_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
 pub const DEFAULT_SOCKET_PORT: u16 = 9000;
+pub const DEFAULT_TARGET_PEERS: usize = 100;
+pub const DEFAULT_FREEZER_RESTORE_POINT_SLOTS: u64 = 2_048;
+/// The validator node usually runs next to a beacon node, so its metrics use a different port
+pub const DEFAULT_VALIDATOR_METRICS_PORT: u16 = 8081;
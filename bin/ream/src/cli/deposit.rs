@@ -0,0 +1,88 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::Address;
+use clap::Parser;
+use ream_consensus_misc::constants::beacon::MIN_ACTIVATION_BALANCE;
+use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
+use url::Url;
+
+use crate::cli::constants::DEFAULT_NETWORK;
+
+#[derive(Debug, Parser)]
+pub struct DepositConfig {
+    /// Verbosity level
+    #[arg(short, long, default_value_t = 3)]
+    pub verbosity: u8,
+
+    #[arg(
+        long,
+        help = "Choose mainnet, holesky, sepolia, hoodi, dev or provide a path to a YAML config file",
+        default_value = DEFAULT_NETWORK,
+        value_parser = beacon_network_parser
+    )]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    #[arg(
+        long,
+        help = "The directory of the keystores of the validators to deposit for"
+    )]
+    pub import_keystores: PathBuf,
+
+    #[arg(
+        long,
+        group = "password_source",
+        help = "The plaintext password file to use for keystores"
+    )]
+    pub password_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        group = "password_source",
+        help = "The password to use for keystores. It's recommended to use password-file over this in order to prevent your keystore password from appearing in the shell history"
+    )]
+    pub password: Option<String>,
+
+    #[arg(long, help = "The execution address to withdraw to")]
+    pub withdrawal_address: Address,
+
+    #[arg(
+        long,
+        help = "Use compounding (0x02) withdrawal credentials instead of 0x01 credentials"
+    )]
+    pub compounding: bool,
+
+    #[arg(
+        long,
+        help = "The amount to deposit per validator in gwei",
+        default_value_t = MIN_ACTIVATION_BALANCE
+    )]
+    pub amount: u64,
+
+    #[arg(
+        long,
+        help = "The directory to write the deposit data to",
+        default_value = "."
+    )]
+    pub output_dir: PathBuf,
+
+    #[arg(
+        long,
+        requires_all = ["execution_jwt_secret", "eth1_private_key_file"],
+        help = "Submit the deposits through the execution client at this engine API endpoint"
+    )]
+    pub execution_endpoint: Option<Url>,
+
+    #[arg(
+        long,
+        requires = "execution_endpoint",
+        help = "The JWT secret to authenticate with the execution client"
+    )]
+    pub execution_jwt_secret: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "execution_endpoint",
+        help = "The file with the hex encoded private key of the funded execution account paying for the deposits"
+    )]
+    pub eth1_private_key_file: Option<PathBuf>,
+}
@@ -0,0 +1,165 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::{anyhow, ensure};
+use discv5::multiaddr::{Multiaddr, Protocol};
+use libp2p_identity::{Keypair, PeerId, secp256k1};
+use ream_network_spec::networks::LeanNetworkSpec;
+use ream_p2p::{bootnodes::Bootnodes, network::lean::parse_private_key};
+use ream_validator_lean::registry::LeanKeystore;
+use serde::Deserialize;
+
+/// A lean devnet defined in a single file: the network spec, and the libp2p key, address and
+/// validators of every node.
+///
+/// Example:
+/// ```yaml
+/// config:
+///   GENESIS_TIME: 1754000000
+///   NUM_VALIDATORS: 4
+/// nodes:
+///   ream_0:
+///     private_key: "1111111111111111111111111111111111111111111111111111111111111111"
+///     address: /ip4/127.0.0.1/udp/9000/quic-v1
+///     validators: [0, 2]
+///   zeam_0:
+///     private_key: "2222222222222222222222222222222222222222222222222222222222222222"
+///     address: /ip4/127.0.0.1/udp/9001/quic-v1
+///     validators: [1, 3]
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct DevnetConfig {
+    pub config: LeanNetworkSpec,
+    pub nodes: BTreeMap<String, DevnetNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DevnetNode {
+    /// The hex encoded secp256k1 libp2p key of the node
+    pub private_key: String,
+    /// The QUIC address the node listens on and the other nodes dial it at
+    pub address: Multiaddr,
+    pub validators: Vec<u64>,
+}
+
+impl DevnetConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| anyhow!("Failed to read devnet config {}: {err}", path.display()))?;
+        let devnet_config = serde_yaml::from_str::<DevnetConfig>(&content)
+            .map_err(|err| anyhow!("Failed to parse devnet config YAML: {err}"))?;
+        devnet_config.validate()?;
+        Ok(devnet_config)
+    }
+
+    /// Checks that every validator is assigned to at most one node, and that the keys and
+    /// addresses of the nodes are usable.
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut assigned_validators = HashSet::new();
+        for (node_id, node) in &self.nodes {
+            node.peer_id()
+                .map_err(|err| anyhow!("Invalid private key of node {node_id}: {err}"))?;
+            node.socket_port()
+                .map_err(|err| anyhow!("Invalid address of node {node_id}: {err}"))?;
+            for validator_id in &node.validators {
+                ensure!(
+                    *validator_id < self.config.num_validators,
+                    "Validator {validator_id} of node {node_id} is out of the {} validators",
+                    self.config.num_validators
+                );
+                ensure!(
+                    assigned_validators.insert(*validator_id),
+                    "Validator {validator_id} is assigned to more than one node"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn node(&self, node_id: &str) -> anyhow::Result<&DevnetNode> {
+        self.nodes.get(node_id).ok_or_else(|| {
+            anyhow!(
+                "Node ID '{node_id}' not found in devnet config. Available nodes: {:?}",
+                self.nodes.keys().collect::<Vec<_>>()
+            )
+        })
+    }
+
+    /// The addresses of every node other than `node_id`, to be dialed on startup.
+    pub fn bootnodes(&self, node_id: &str) -> anyhow::Result<Bootnodes> {
+        Ok(Bootnodes::Multiaddr(
+            self.nodes
+                .iter()
+                .filter(|(id, _)| id.as_str() != node_id)
+                .map(|(_, node)| Ok(node.address.clone().with(Protocol::P2p(node.peer_id()?))))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        ))
+    }
+}
+
+impl DevnetNode {
+    pub fn private_key(&self) -> anyhow::Result<secp256k1::SecretKey> {
+        parse_private_key(&self.private_key)
+    }
+
+    pub fn peer_id(&self) -> anyhow::Result<PeerId> {
+        let keypair = Keypair::from(secp256k1::Keypair::from(self.private_key()?));
+        Ok(PeerId::from(keypair.public()))
+    }
+
+    /// The UDP port of the node's QUIC address.
+    pub fn socket_port(&self) -> anyhow::Result<u16> {
+        self.address
+            .iter()
+            .find_map(|protocol| match protocol {
+                Protocol::Udp(port) => Some(port),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow!("{} has no UDP port", self.address))
+    }
+
+    pub fn keystores(&self) -> Vec<LeanKeystore> {
+        self.validators
+            .iter()
+            .map(|&validator_id| LeanKeystore { validator_id })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_devnet_config() {
+        let devnet_config = DevnetConfig::load(Path::new("./assets/lean/devnet.yml")).unwrap();
+        assert_eq!(devnet_config.config.num_validators, 4);
+
+        let node = devnet_config.node("ream_0").unwrap();
+        assert_eq!(node.socket_port().unwrap(), 9000);
+        assert_eq!(
+            node.keystores()
+                .iter()
+                .map(|keystore| keystore.validator_id)
+                .collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+        assert!(devnet_config.node("lighthouse_0").is_err());
+
+        let Bootnodes::Multiaddr(bootnodes) = devnet_config.bootnodes("ream_0").unwrap() else {
+            panic!("Expected the addresses of the other nodes");
+        };
+        let zeam = devnet_config.node("zeam_0").unwrap();
+        assert_eq!(
+            bootnodes,
+            vec![
+                zeam.address
+                    .clone()
+                    .with(Protocol::P2p(zeam.peer_id().unwrap()))
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::B256;
+use clap::{Parser, Subcommand, ValueEnum};
+use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
+
+use crate::cli::constants::{
+    DEFAULT_GENESIS_DELAY, DEFAULT_GENESIS_NETWORK, DEFAULT_GENESIS_VALIDATOR_COUNT,
+};
+
+#[derive(Debug, Parser)]
+pub struct GenesisConfig {
+    #[command(subcommand)]
+    pub command: GenesisCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GenesisCommand {
+    /// Generate a genesis state and the keys of its validators for a local devnet
+    #[command(name = "generate")]
+    Generate(GenerateGenesisConfig),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GenesisChain {
+    Beacon,
+    Lean,
+}
+
+#[derive(Debug, Parser)]
+pub struct GenerateGenesisConfig {
+    #[arg(
+        long,
+        help = "The chain to generate the genesis state of",
+        value_enum,
+        default_value_t = GenesisChain::Beacon
+    )]
+    pub chain: GenesisChain,
+
+    #[arg(
+        long,
+        help = "Choose mainnet, holesky, sepolia, hoodi, dev or provide a path to a YAML config file. Only used for the beacon chain",
+        default_value = DEFAULT_GENESIS_NETWORK,
+        value_parser = beacon_network_parser
+    )]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    #[arg(
+        long,
+        help = "The number of genesis validators",
+        default_value_t = DEFAULT_GENESIS_VALIDATOR_COUNT
+    )]
+    pub validator_count: u64,
+
+    #[arg(
+        long,
+        help = "The genesis time in seconds since the UNIX epoch. Defaults to the current time plus the genesis delay"
+    )]
+    pub genesis_time: Option<u64>,
+
+    #[arg(
+        long,
+        help = "The delay in seconds from now to the genesis time, if no genesis time is given",
+        default_value_t = DEFAULT_GENESIS_DELAY
+    )]
+    pub genesis_delay: u64,
+
+    #[arg(
+        long,
+        help = "Derive the validator keys from this mnemonic instead of the interop keys"
+    )]
+    pub mnemonic: Option<String>,
+
+    #[arg(
+        long,
+        help = "The eth1 block hash the genesis state is built on",
+        default_value_t = B256::repeat_byte(0x42)
+    )]
+    pub eth1_block_hash: B256,
+
+    #[arg(
+        long,
+        help = "The password to encrypt the validator keystores with. Defaults to a random password. It is written to password.txt in the output directory"
+    )]
+    pub keystore_password: Option<String>,
+
+    #[arg(
+        long,
+        help = "The directory to write the genesis state and the validator keys to"
+    )]
+    pub output_dir: PathBuf,
+}
@@ -1,14 +1,23 @@
 use std::{net::IpAddr, path::PathBuf, sync::Arc};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use ream_network_spec::{cli::lean_network_parser, networks::LeanNetworkSpec};
 use ream_p2p::bootnodes::Bootnodes;
 
 use crate::cli::constants::{
-    DEFAULT_HTTP_ADDRESS, DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_METRICS_ADDRESS,
-    DEFAULT_METRICS_ENABLED, DEFAULT_METRICS_PORT, DEFAULT_SOCKET_ADDRESS, DEFAULT_SOCKET_PORT,
+    DEFAULT_DISABLE_DISCOVERY, DEFAULT_HTTP_ADDRESS, DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT,
+    DEFAULT_LEAN_DISCOVERY_PORT, DEFAULT_METRICS_ADDRESS, DEFAULT_METRICS_ENABLED,
+    DEFAULT_METRICS_PORT, DEFAULT_PROVER_WORKERS, DEFAULT_SOCKET_ADDRESS, DEFAULT_SOCKET_PORT,
 };
 
+/// The zkVM backend proving the lean state transition. Each backend is only available if ream is
+/// built with the feature of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProverBackend {
+    Risc0,
+    Sp1,
+}
+
 #[derive(Debug, Parser)]
 pub struct LeanNodeConfig {
     /// Verbosity level
@@ -18,9 +27,17 @@ pub struct LeanNodeConfig {
     #[arg(
       long,
       help = "Provide a path to a YAML config file, or use 'ephemery' for the Ephemery network",
+      required_unless_present = "devnet_config",
       value_parser = lean_network_parser
   )]
-    pub network: Arc<LeanNetworkSpec>,
+    pub network: Option<Arc<LeanNetworkSpec>>,
+
+    #[arg(
+        long,
+        help = "The path to a devnet YAML file defining the network spec and the key, address and validators of every node. The node is configured from its entry selected by --node-id",
+        conflicts_with_all = ["network", "bootnodes", "validator_registry_path", "private_key_path"]
+    )]
+    pub devnet_config: Option<PathBuf>,
 
     #[arg(
         default_value = "default",
@@ -29,16 +46,32 @@ pub struct LeanNodeConfig {
     )]
     pub bootnodes: Bootnodes,
 
-    #[arg(long, help = "The path to the validator registry")]
-    pub validator_registry_path: PathBuf,
+    #[arg(
+        long,
+        help = "The path to the validator registry, which is reloaded when it changes",
+        required_unless_present = "devnet_config"
+    )]
+    pub validator_registry_path: Option<PathBuf>,
 
     #[arg(
         default_value = "ream",
         long,
-        help = "Node identifier for validator registry (e.g., 'ream_0', 'zeam_0')"
+        help = "Node identifier for the validator registry or the devnet config (e.g., 'ream_0', 'zeam_0')"
     )]
     pub node_id: String,
 
+    #[arg(
+        long,
+        help = "The path to a YAML file mapping validator IDs to their hash-based signature public keys, used to verify the votes received from the network"
+    )]
+    pub validator_public_keys_path: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "The path to a YAML file mapping the validator IDs of this node to their hash-based signature attestation and block private keys, used to sign votes and blocks"
+    )]
+    pub validator_private_keys_path: Option<PathBuf>,
+
     #[arg(long, help = "The path to the hex encoded secp256k1 libp2p key")]
     pub private_key_path: Option<PathBuf>,
 
@@ -48,6 +81,12 @@ pub struct LeanNodeConfig {
     #[arg(long, help = "Set P2P socket port (QUIC)", default_value_t = DEFAULT_SOCKET_PORT)]
     pub socket_port: u16,
 
+    #[arg(long, help = "Discovery 5 listening port (UDP)", default_value_t = DEFAULT_LEAN_DISCOVERY_PORT)]
+    pub discovery_port: u16,
+
+    #[arg(long, help = "Disable Discv5", default_value_t = DEFAULT_DISABLE_DISCOVERY)]
+    pub disable_discovery: bool,
+
     #[arg(long, help = "Set HTTP address", default_value_t = DEFAULT_HTTP_ADDRESS)]
     pub http_address: IpAddr,
 
@@ -57,7 +96,17 @@ pub struct LeanNodeConfig {
     #[arg(long, default_value_t = DEFAULT_HTTP_ALLOW_ORIGIN)]
     pub http_allow_origin: bool,
 
-    #[arg(long = "metrics", help = "Enable metrics", default_value_t = DEFAULT_METRICS_ENABLED)]
+    #[arg(
+        long,
+        value_enum,
+        help = "Prove the state transition of every imported block with the given zkVM backend, serving the proofs at /lean/v0/proofs/{block_root}"
+    )]
+    pub prover: Option<ProverBackend>,
+
+    #[arg(long, help = "The number of blocks proven at the same time", default_value_t = DEFAULT_PROVER_WORKERS)]
+    pub prover_workers: usize,
+
+    #[arg(long = "metrics", visible_alias = "enable-metrics", help = "Enable metrics", default_value_t = DEFAULT_METRICS_ENABLED)]
     pub enable_metrics: bool,
 
     #[arg(long, help = "Set metrics address", default_value_t = DEFAULT_METRICS_ADDRESS)]
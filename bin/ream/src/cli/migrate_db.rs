@@ -0,0 +1,11 @@
+use clap::Parser;
+use ream_storage::db::config::DBBackend;
+
+#[derive(Debug, Parser)]
+pub struct MigrateDBConfig {
+    #[arg(
+        long,
+        help = "The database backend to copy the database into: redb, rocksdb or mdbx. The database to copy from is selected with --db.backend"
+    )]
+    pub to: DBBackend,
+}
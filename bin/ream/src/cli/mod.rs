@@ -1,9 +1,15 @@
 pub mod account_manager;
 pub mod beacon_node;
 pub mod constants;
+pub mod deposit;
+pub mod devnet_config;
 pub mod generate_private_key;
+pub mod genesis;
 pub mod import_keystores;
 pub mod lean_node;
+pub mod migrate_db;
+pub mod ssz;
+pub mod transition;
 pub mod validator_node;
 pub mod voluntary_exit;
 
@@ -11,11 +17,16 @@ use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 use ream_node::version::FULL_VERSION;
+use ream_storage::db::config::{DBBackend, DBConfig, DBDurability, DEFAULT_MAX_WRITE_BUFFER};
+use url::Url;
 
 use crate::cli::{
     account_manager::AccountManagerConfig, beacon_node::BeaconNodeConfig,
-    generate_private_key::GeneratePrivateKeyConfig, lean_node::LeanNodeConfig,
-    validator_node::ValidatorNodeConfig, voluntary_exit::VoluntaryExitConfig,
+    constants::DEFAULT_DB_CACHE_SIZE_MIB, deposit::DepositConfig,
+    generate_private_key::GeneratePrivateKeyConfig, genesis::GenesisConfig,
+    lean_node::LeanNodeConfig, migrate_db::MigrateDBConfig, ssz::SszConfig,
+    transition::TransitionConfig, validator_node::ValidatorNodeConfig,
+    voluntary_exit::VoluntaryExitConfig,
 };
 
 #[derive(Debug, Parser)]
@@ -39,6 +50,58 @@ pub struct Cli {
 
     #[arg(long, help = "Purges the database.")]
     pub purge_db: bool,
+
+    #[arg(
+        long,
+        help = "Export tracing spans of the block processing pipeline to this OTLP/HTTP endpoint, e.g. http://localhost:4318/v1/traces"
+    )]
+    pub tracing_endpoint: Option<Url>,
+
+    #[arg(
+        long = "db.backend",
+        help = "The key-value store of the database: redb, rocksdb or mdbx. rocksdb and mdbx need ream to be built with the feature of the same name",
+        default_value_t = DBBackend::Redb
+    )]
+    pub db_backend: DBBackend,
+
+    #[arg(
+        long = "db.cache-size",
+        help = "The size of the database cache in MiB",
+        default_value_t = DEFAULT_DB_CACHE_SIZE_MIB
+    )]
+    pub db_cache_size: usize,
+
+    #[arg(
+        long = "db.max-write-buffer",
+        help = "The number of commits kept in memory during bulk imports, such as range sync, before they are written to disk",
+        default_value_t = DEFAULT_MAX_WRITE_BUFFER
+    )]
+    pub db_max_write_buffer: usize,
+
+    #[arg(
+        long = "db.durability",
+        help = "When commits are written to disk: immediate, eventual (faster, but the latest commits are lost on a crash) or paranoid",
+        default_value_t = DBDurability::Immediate
+    )]
+    pub db_durability: DBDurability,
+
+    #[arg(
+        long = "db.compact",
+        help = "Compact the database file on startup to release the space of deleted data"
+    )]
+    pub db_compact: bool,
+}
+
+impl Cli {
+    pub fn db_config(&self) -> DBConfig {
+        DBConfig {
+            backend: self.db_backend,
+            cache_size: self.db_cache_size * 1_024 * 1_024,
+            max_write_buffer: self.db_max_write_buffer,
+            durability: self.db_durability,
+            compact_on_startup: self.db_compact,
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -63,9 +126,29 @@ pub enum Commands {
     #[command(name = "voluntary_exit")]
     VoluntaryExit(Box<VoluntaryExitConfig>),
 
+    /// Sign deposits for validators and optionally submit them to the deposit contract
+    #[command(name = "deposit")]
+    Deposit(Box<DepositConfig>),
+
     /// Generate a secp256k1 keypair for lean node
     #[command(name = "generate_private_key")]
     GeneratePrivateKey(Box<GeneratePrivateKeyConfig>),
+
+    /// Generate genesis states for local devnets
+    #[command(name = "genesis")]
+    Genesis(Box<GenesisConfig>),
+
+    /// Copy the database into another database backend
+    #[command(name = "migrate_db")]
+    MigrateDB(Box<MigrateDBConfig>),
+
+    /// Run the state transition on SSZ files, for debugging consensus failures
+    #[command(name = "transition")]
+    Transition(Box<TransitionConfig>),
+
+    /// Inspect SSZ encoded containers
+    #[command(name = "ssz")]
+    Ssz(Box<SszConfig>),
 }
 
 #[cfg(test)]
@@ -75,11 +158,22 @@ mod tests {
         time::Duration,
     };
 
+    use alloy_primitives::Address;
+    use ream_api_types_beacon::id::ValidatorID;
     use ream_network_spec::networks::Network;
     use url::Url;
 
     use super::*;
-    use crate::cli::constants::DEFAULT_BEACON_API_ENDPOINT;
+    use crate::cli::{
+        account_manager::KeyScheme,
+        constants::{
+            DEFAULT_BEACON_API_ENDPOINT, DEFAULT_GENESIS_DELAY, DEFAULT_REQUEST_RETRIES,
+            DEFAULT_VALIDATOR_METRICS_PORT,
+        },
+        genesis::{GenesisChain, GenesisCommand},
+        ssz::{OutputFormat, SszCommand, SszType},
+        transition::TransitionCommand,
+    };
 
     #[test]
     fn test_cli_lean_node_command() {
@@ -102,6 +196,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_lean_node_devnet_config() {
+        let cli = Cli::parse_from([
+            "program",
+            "lean_node",
+            "--devnet-config",
+            "./assets/lean/devnet.yml",
+            "--node-id",
+            "ream_0",
+        ]);
+
+        match cli.command {
+            Commands::LeanNode(config) => {
+                assert_eq!(
+                    config.devnet_config,
+                    Some(PathBuf::from("./assets/lean/devnet.yml"))
+                );
+                assert_eq!(config.node_id, "ream_0");
+                assert!(config.network.is_none());
+                assert!(config.validator_registry_path.is_none());
+            }
+            _ => unreachable!("This test should only validate the lean node cli"),
+        }
+
+        // The devnet config replaces the network flags
+        assert!(
+            Cli::try_parse_from([
+                "program",
+                "lean_node",
+                "--devnet-config",
+                "./assets/lean/devnet.yml",
+                "--network",
+                "ephemery",
+            ])
+            .is_err()
+        );
+        assert!(Cli::try_parse_from(["program", "lean_node"]).is_err());
+    }
+
     #[test]
     fn test_cli_beacon_node_command() {
         let cli = Cli::parse_from([
@@ -115,6 +248,11 @@ mod tests {
             "9001",
             "--discovery-port",
             "9002",
+            "--enable-metrics",
+            "--metrics-port",
+            "9090",
+            "--monitor-validators",
+            "1,7",
         ]);
 
         match cli.command {
@@ -127,6 +265,12 @@ mod tests {
                 );
                 assert_eq!(config.socket_port, 9001);
                 assert_eq!(config.discovery_port, 9002);
+                assert!(config.enable_metrics);
+                assert_eq!(config.metrics_port, 9090);
+                assert_eq!(
+                    config.monitor_validators,
+                    vec![ValidatorID::Index(1), ValidatorID::Index(7)]
+                );
             }
             _ => unreachable!("This test should only validate the beacon node cli"),
         }
@@ -140,7 +284,7 @@ mod tests {
             "--verbosity",
             "2",
             "--beacon-api-endpoint",
-            "http://localhost:5052",
+            "http://localhost:5052,http://localhost:5053",
             "--request-timeout",
             "3",
             "--import-keystores",
@@ -155,10 +299,16 @@ mod tests {
             Commands::ValidatorNode(config) => {
                 assert_eq!(config.verbosity, 2);
                 assert_eq!(
-                    config.beacon_api_endpoint,
-                    Url::parse(DEFAULT_BEACON_API_ENDPOINT).expect("Invalid URL")
+                    config.beacon_api_endpoints,
+                    vec![
+                        Url::parse(DEFAULT_BEACON_API_ENDPOINT).expect("Invalid URL"),
+                        Url::parse("http://localhost:5053").expect("Invalid URL"),
+                    ]
                 );
                 assert_eq!(config.request_timeout, Duration::from_secs(3));
+                assert_eq!(config.request_retries, DEFAULT_REQUEST_RETRIES);
+                assert!(!config.enable_metrics);
+                assert_eq!(config.metrics_port, DEFAULT_VALIDATOR_METRICS_PORT);
             }
             _ => unreachable!("This test should only validate the validator node cli"),
         }
@@ -192,4 +342,300 @@ mod tests {
             _ => unreachable!("This test should only validate the account manager cli"),
         }
     }
+
+    #[test]
+    fn test_cli_account_manager_bls_scheme() {
+        let cli = Cli::parse_from([
+            "program",
+            "account_manager",
+            "--scheme",
+            "bls",
+            "--validator-start-index",
+            "4",
+            "--num-validators",
+            "2",
+            "--password",
+            "password",
+            "--network",
+            "hoodi",
+            "--withdrawal-address",
+            "0x0000000000000000000000000000000000000001",
+        ]);
+
+        match cli.command {
+            Commands::AccountManager(config) => {
+                assert_eq!(config.scheme, KeyScheme::Bls);
+                assert_eq!(config.validator_start_index, 4);
+                assert_eq!(config.num_validators, 2);
+                assert_eq!(config.password.as_deref(), Some("password"));
+                assert_eq!(config.network.network, Network::Hoodi);
+                assert_eq!(config.withdrawal_address, Some(Address::with_last_byte(1)));
+            }
+            _ => unreachable!("This test should only validate the account manager cli"),
+        }
+    }
+
+    #[test]
+    fn test_cli_genesis_generate_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "genesis",
+            "generate",
+            "--chain",
+            "lean",
+            "--validator-count",
+            "8",
+            "--genesis-time",
+            "1700000000",
+            "--keystore-password",
+            "devnet",
+            "--output-dir",
+            "./devnet",
+        ]);
+
+        match cli.command {
+            Commands::Genesis(config) => match config.command {
+                GenesisCommand::Generate(config) => {
+                    assert_eq!(config.chain, GenesisChain::Lean);
+                    assert_eq!(config.validator_count, 8);
+                    assert_eq!(config.genesis_time, Some(1700000000));
+                    assert_eq!(config.genesis_delay, DEFAULT_GENESIS_DELAY);
+                    assert_eq!(config.mnemonic, None);
+                    assert_eq!(config.keystore_password.as_deref(), Some("devnet"));
+                    assert_eq!(config.output_dir, PathBuf::from("./devnet"));
+                }
+            },
+            _ => unreachable!("This test should only validate the genesis cli"),
+        }
+    }
+
+    #[test]
+    fn test_cli_voluntary_exit_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "voluntary_exit",
+            "--import-keystores",
+            "./keystores",
+            "--password",
+            "password123",
+            "--validator-index",
+            "1,2,5",
+            "--epoch",
+            "100",
+            "--output-dir",
+            "./exits",
+        ]);
+
+        match cli.command {
+            Commands::VoluntaryExit(config) => {
+                assert_eq!(config.import_keystores, Some(PathBuf::from("./keystores")));
+                assert_eq!(config.validator_index, vec![1, 2, 5]);
+                assert!(!config.all);
+                assert_eq!(config.epoch, Some(100));
+                assert_eq!(config.output_dir, Some(PathBuf::from("./exits")));
+                assert_eq!(config.submit_exit_file, None);
+            }
+            _ => unreachable!("This test should only validate the voluntary_exit cli"),
+        }
+
+        let cli = Cli::parse_from([
+            "program",
+            "voluntary_exit",
+            "--submit-exit-file",
+            "./exits/exit_1_100.json",
+        ]);
+        match cli.command {
+            Commands::VoluntaryExit(config) => {
+                assert_eq!(config.import_keystores, None);
+                assert!(config.validator_index.is_empty());
+                assert_eq!(
+                    config.submit_exit_file,
+                    Some(PathBuf::from("./exits/exit_1_100.json"))
+                );
+            }
+            _ => unreachable!("This test should only validate the voluntary_exit cli"),
+        }
+
+        assert!(
+            Cli::try_parse_from([
+                "program",
+                "voluntary_exit",
+                "--import-keystores",
+                "./keystores",
+                "--all",
+                "--validator-index",
+                "1",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_deposit_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "deposit",
+            "--import-keystores",
+            "./keystores",
+            "--password-file",
+            "./password.txt",
+            "--withdrawal-address",
+            "0x4242424242424242424242424242424242424242",
+            "--compounding",
+            "--amount",
+            "64000000000",
+            "--execution-endpoint",
+            "http://localhost:8551",
+            "--execution-jwt-secret",
+            "./jwt.hex",
+            "--eth1-private-key-file",
+            "./eth1_key.hex",
+        ]);
+
+        match cli.command {
+            Commands::Deposit(config) => {
+                assert_eq!(config.import_keystores, PathBuf::from("./keystores"));
+                assert_eq!(config.withdrawal_address, Address::repeat_byte(0x42));
+                assert!(config.compounding);
+                assert_eq!(config.amount, 64_000_000_000);
+                assert_eq!(config.output_dir, PathBuf::from("."));
+                assert_eq!(
+                    config.execution_endpoint,
+                    Some(Url::parse("http://localhost:8551").unwrap())
+                );
+            }
+            _ => unreachable!("This test should only validate the deposit cli"),
+        }
+
+        // Submitting the deposits requires the key of the execution account paying for them
+        assert!(
+            Cli::try_parse_from([
+                "program",
+                "deposit",
+                "--import-keystores",
+                "./keystores",
+                "--withdrawal-address",
+                "0x4242424242424242424242424242424242424242",
+                "--execution-endpoint",
+                "http://localhost:8551",
+                "--execution-jwt-secret",
+                "./jwt.hex",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_db_config() {
+        let cli = Cli::parse_from([
+            "program",
+            "--db.cache-size",
+            "256",
+            "--db.durability",
+            "eventual",
+            "--db.compact",
+            "beacon_node",
+        ]);
+
+        let db_config = cli.db_config();
+        assert_eq!(db_config.backend, DBBackend::Redb);
+        assert_eq!(db_config.cache_size, 256 * 1_024 * 1_024);
+        assert_eq!(db_config.max_write_buffer, DEFAULT_MAX_WRITE_BUFFER);
+        assert_eq!(db_config.durability, DBDurability::Eventual);
+        assert!(db_config.compact_on_startup);
+
+        assert!(
+            Cli::try_parse_from(["program", "--db.durability", "never", "beacon_node"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_migrate_db_command() {
+        let cli = Cli::parse_from(["program", "migrate_db", "--to", "rocksdb"]);
+
+        assert_eq!(cli.db_config().backend, DBBackend::Redb);
+        match cli.command {
+            Commands::MigrateDB(config) => {
+                assert_eq!(config.to, DBBackend::RocksDB);
+            }
+            _ => unreachable!("This test should only validate the migrate_db cli"),
+        }
+
+        assert!(Cli::try_parse_from(["program", "migrate_db", "--to", "sqlite"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_transition_blocks_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "transition",
+            "blocks",
+            "--network",
+            "sepolia",
+            "--pre",
+            "pre.ssz",
+            "--blocks",
+            "block_1.ssz",
+            "block_2.ssz_snappy",
+            "--post",
+            "post.ssz",
+        ]);
+
+        match cli.command {
+            Commands::Transition(config) => match config.command {
+                TransitionCommand::Blocks(config) => {
+                    assert_eq!(config.network.network, Network::Sepolia);
+                    assert_eq!(config.pre, PathBuf::from("pre.ssz"));
+                    assert_eq!(
+                        config.blocks,
+                        vec![
+                            PathBuf::from("block_1.ssz"),
+                            PathBuf::from("block_2.ssz_snappy")
+                        ]
+                    );
+                    assert_eq!(config.post, Some(PathBuf::from("post.ssz")));
+                    assert!(!config.no_verify);
+                }
+                TransitionCommand::Slots(_) => {
+                    unreachable!("This test should only validate the transition blocks cli")
+                }
+            },
+            _ => unreachable!("This test should only validate the transition cli"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["program", "transition", "blocks", "--pre", "pre.ssz"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_ssz_inspect_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "ssz",
+            "inspect",
+            "state.ssz_snappy",
+            "--type",
+            "BeaconState",
+            "--field",
+            "finalized_checkpoint.root",
+        ]);
+
+        match cli.command {
+            Commands::Ssz(config) => match config.command {
+                SszCommand::Inspect(config) => {
+                    assert_eq!(config.file, PathBuf::from("state.ssz_snappy"));
+                    assert_eq!(config.ssz_type, SszType::BeaconState);
+                    assert_eq!(config.format, OutputFormat::Json);
+                    assert_eq!(config.field.as_deref(), Some("finalized_checkpoint.root"));
+                }
+            },
+            _ => unreachable!("This test should only validate the ssz cli"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["program", "ssz", "inspect", "state.ssz", "--type", "State"])
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+#[derive(Debug, Parser)]
+pub struct SszConfig {
+    #[command(subcommand)]
+    pub command: SszCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SszCommand {
+    /// Decode an SSZ file and print it with its tree hash root, or prove one of its fields
+    #[command(name = "inspect")]
+    Inspect(InspectConfig),
+}
+
+/// The containers which `ream ssz inspect` can decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "PascalCase")]
+pub enum SszType {
+    BeaconState,
+    SignedBeaconBlock,
+    BeaconBlock,
+    BeaconBlockBody,
+    SignedBeaconBlockHeader,
+    BeaconBlockHeader,
+    Attestation,
+    AttestationData,
+    Checkpoint,
+    SyncCommittee,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Parser)]
+pub struct InspectConfig {
+    #[arg(help = "The SSZ file, or snappy compressed if it ends with .ssz_snappy")]
+    pub file: PathBuf,
+
+    #[arg(
+        long = "type",
+        help = "The type of the container in the file",
+        value_enum
+    )]
+    pub ssz_type: SszType,
+
+    #[arg(
+        long,
+        help = "The format to print in",
+        value_enum,
+        default_value_t = OutputFormat::Json
+    )]
+    pub format: OutputFormat,
+
+    #[arg(
+        long,
+        help = "Print the generalized index and the Merkle proof of the field at this dot separated path instead of the value, e.g. finalized_checkpoint.root"
+    )]
+    pub field: Option<String>,
+}
@@ -0,0 +1,80 @@
+use std::{path::PathBuf, sync::Arc};
+
+use clap::{Parser, Subcommand};
+use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
+
+use crate::cli::constants::DEFAULT_NETWORK;
+
+#[derive(Debug, Parser)]
+pub struct TransitionConfig {
+    #[command(subcommand)]
+    pub command: TransitionCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TransitionCommand {
+    /// Apply blocks to a state with the Electra state transition
+    #[command(name = "blocks")]
+    Blocks(TransitionBlocksConfig),
+
+    /// Advance a state through empty slots
+    #[command(name = "slots")]
+    Slots(TransitionSlotsConfig),
+}
+
+#[derive(Debug, Parser)]
+pub struct TransitionBlocksConfig {
+    #[arg(
+        long,
+        help = "Choose mainnet, holesky, sepolia, hoodi, dev or provide a path to a YAML config file",
+        default_value = DEFAULT_NETWORK,
+        value_parser = beacon_network_parser
+    )]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    #[arg(
+        long,
+        help = "The pre-state as an SSZ file, or snappy compressed if it ends with .ssz_snappy"
+    )]
+    pub pre: PathBuf,
+
+    #[arg(
+        long,
+        help = "The signed blocks to apply in order, as SSZ files or snappy compressed if they end with .ssz_snappy",
+        num_args = 1..,
+        required = true
+    )]
+    pub blocks: Vec<PathBuf>,
+
+    #[arg(long, help = "Write the post-state to this SSZ file")]
+    pub post: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Skip the verification of the block signatures and state roots"
+    )]
+    pub no_verify: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct TransitionSlotsConfig {
+    #[arg(
+        long,
+        help = "Choose mainnet, holesky, sepolia, hoodi, dev or provide a path to a YAML config file",
+        default_value = DEFAULT_NETWORK,
+        value_parser = beacon_network_parser
+    )]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    #[arg(
+        long,
+        help = "The pre-state as an SSZ file, or snappy compressed if it ends with .ssz_snappy"
+    )]
+    pub pre: PathBuf,
+
+    #[arg(long, help = "The number of slots to advance the state by")]
+    pub slots: u64,
+
+    #[arg(long, help = "Write the post-state to this SSZ file")]
+    pub post: Option<PathBuf>,
+}
@@ -1,13 +1,16 @@
 use std::{net::IpAddr, path::PathBuf, sync::Arc, time::Duration};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use clap::Parser;
 use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
+use ream_validator_beacon::graffiti::parse_graffiti;
 use url::Url;
 
 use crate::cli::constants::{
-    DEFAULT_BEACON_API_ENDPOINT, DEFAULT_HTTP_ADDRESS, DEFAULT_KEY_MANAGER_HTTP_PORT,
-    DEFAULT_NETWORK, DEFAULT_REQUEST_TIMEOUT,
+    DEFAULT_BEACON_API_ENDPOINT, DEFAULT_GAS_LIMIT, DEFAULT_HTTP_ADDRESS,
+    DEFAULT_KEY_MANAGER_HTTP_PORT, DEFAULT_METRICS_ADDRESS, DEFAULT_METRICS_ENABLED,
+    DEFAULT_NETWORK, DEFAULT_REQUEST_RETRIES, DEFAULT_REQUEST_RETRY_BACKOFF_MS,
+    DEFAULT_REQUEST_TIMEOUT, DEFAULT_VALIDATOR_METRICS_PORT,
 };
 
 #[derive(Debug, Parser)]
@@ -16,12 +19,23 @@ pub struct ValidatorNodeConfig {
     #[arg(short, long, default_value_t = 3)]
     pub verbosity: u8,
 
-    #[arg(long, help = "Set HTTP url of the beacon api endpoint", default_value = DEFAULT_BEACON_API_ENDPOINT)]
-    pub beacon_api_endpoint: Url,
+    #[arg(
+        long = "beacon-api-endpoint",
+        help = "Set HTTP urls of the beacon api endpoints, separated by commas. The first synced endpoint is used and the others are fallbacks; blocks and signatures are published to all of them",
+        default_value = DEFAULT_BEACON_API_ENDPOINT,
+        value_delimiter = ','
+    )]
+    pub beacon_api_endpoints: Vec<Url>,
 
     #[arg(long, help = "Set HTTP request timeout for beacon api calls", default_value = DEFAULT_REQUEST_TIMEOUT, value_parser = duration_parser)]
     pub request_timeout: Duration,
 
+    #[arg(long, help = "Set how many times a beacon api call is retried after server errors, timeouts and connection failures on all endpoints", default_value_t = DEFAULT_REQUEST_RETRIES)]
+    pub request_retries: u32,
+
+    #[arg(long, help = "Set the wait in milliseconds before the first retry of a beacon api call, which doubles with every retry", default_value_t = DEFAULT_REQUEST_RETRY_BACKOFF_MS)]
+    pub request_retry_backoff_ms: u64,
+
     #[arg(long, help = "Set HTTP address of the key manager server", default_value_t = DEFAULT_HTTP_ADDRESS)]
     pub key_manager_http_address: IpAddr,
 
@@ -45,6 +59,13 @@ pub struct ValidatorNodeConfig {
     )]
     pub suggested_fee_recipient: Address,
 
+    #[arg(
+        long,
+        help = "The gas limit of the blocks built by the builders, registered with them when `enable_builder` is passed",
+        default_value_t = DEFAULT_GAS_LIMIT
+    )]
+    pub gas_limit: u64,
+
     #[arg(
         long,
         group = "password_source",
@@ -59,7 +80,10 @@ pub struct ValidatorNodeConfig {
     )]
     pub password: Option<String>,
 
-    #[arg(long, help = "Enable external block builder")]
+    #[arg(
+        long,
+        help = "Enable external block builder. The validators are registered with the builders through the beacon node every epoch"
+    )]
     pub enable_builder: bool,
 
     #[arg(
@@ -68,6 +92,32 @@ pub struct ValidatorNodeConfig {
         requires = "enable_builder"
     )]
     pub mev_relay_url: Option<Url>,
+
+    #[arg(
+        long,
+        help = "Set the graffiti (up to 32 bytes) included in proposed blocks. Defaults to the ream version",
+        value_parser = graffiti_parser
+    )]
+    pub graffiti: Option<B256>,
+
+    #[arg(
+        long,
+        help = "Path to a graffiti file which is re-read on every proposal. Lines are `default: <graffiti>` or `<public key>: <graffiti>`, and take precedence over `--graffiti`"
+    )]
+    pub graffiti_file: Option<PathBuf>,
+
+    #[arg(long = "metrics", visible_alias = "enable-metrics", help = "Enable metrics", default_value_t = DEFAULT_METRICS_ENABLED)]
+    pub enable_metrics: bool,
+
+    #[arg(long, help = "Set metrics address", default_value_t = DEFAULT_METRICS_ADDRESS)]
+    pub metrics_address: IpAddr,
+
+    #[arg(long, help = "Set metrics port", default_value_t = DEFAULT_VALIDATOR_METRICS_PORT)]
+    pub metrics_port: u16,
+}
+
+pub fn graffiti_parser(graffiti: &str) -> Result<B256, String> {
+    parse_graffiti(graffiti).map_err(|err| err.to_string())
 }
 
 pub fn duration_parser(duration_string: &str) -> Result<Duration, String> {
@@ -29,8 +29,12 @@ pub struct VoluntaryExitConfig {
     )]
     pub network: Arc<BeaconNetworkSpec>,
 
-    #[arg(long, help = "The directory for importing keystores")]
-    pub import_keystores: PathBuf,
+    #[arg(
+        long,
+        required_unless_present = "submit_exit_file",
+        help = "The directory for importing keystores"
+    )]
+    pub import_keystores: Option<PathBuf>,
 
     #[arg(
         long,
@@ -46,9 +50,52 @@ pub struct VoluntaryExitConfig {
     )]
     pub password: Option<String>,
 
-    #[arg(long, help = "The validator index to exit")]
-    pub validator_index: u64,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        required_unless_present_any = ["all", "submit_exit_file"],
+        help = "The comma-separated indices of the validators to exit"
+    )]
+    pub validator_index: Vec<u64>,
 
-    #[arg(long, help = "Wait until the validator has fully exited")]
+    #[arg(
+        long,
+        conflicts_with = "validator_index",
+        help = "Exit every active validator with a keystore in the imported directory"
+    )]
+    pub all: bool,
+
+    #[arg(
+        long,
+        help = "The epoch at which the exits become valid. Defaults to the current epoch"
+    )]
+    pub epoch: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Write the signed exits to JSON files in this directory instead of broadcasting them"
+    )]
+    pub output_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "import_keystores",
+            "password_file",
+            "password",
+            "validator_index",
+            "all",
+            "epoch",
+            "output_dir",
+        ],
+        help = "Broadcast the signed exit in a JSON file written with --output-dir"
+    )]
+    pub submit_exit_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "output_dir",
+        help = "Wait until the validators have fully exited"
+    )]
     pub wait: bool,
 }
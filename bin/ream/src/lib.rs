@@ -1 +1,3 @@
 pub mod cli;
+pub mod ssz_inspect;
+pub mod telemetry;
@@ -1,5 +1,6 @@
 use std::{
-    env, fs,
+    collections::HashMap,
+    fs,
     net::SocketAddr,
     path::{Path, PathBuf},
     process,
@@ -7,36 +8,61 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use alloy_primitives::{FixedBytes, hex};
+use alloy_primitives::{B256, FixedBytes, hex};
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::anyhow;
 use bip39::Mnemonic;
 use clap::Parser;
 use libp2p_identity::secp256k1;
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
-use ream::cli::{
-    Cli, Commands,
-    account_manager::AccountManagerConfig,
-    beacon_node::BeaconNodeConfig,
-    generate_private_key::GeneratePrivateKeyConfig,
-    import_keystores::{load_keystore_directory, load_password_from_config, process_password},
-    lean_node::LeanNodeConfig,
-    validator_node::ValidatorNodeConfig,
-    voluntary_exit::VoluntaryExitConfig,
+use ream::{
+    cli::{
+        Cli, Commands,
+        account_manager::{AccountManagerConfig, KeyScheme},
+        beacon_node::BeaconNodeConfig,
+        deposit::DepositConfig,
+        devnet_config::DevnetConfig,
+        generate_private_key::GeneratePrivateKeyConfig,
+        genesis::{GenerateGenesisConfig, GenesisChain, GenesisCommand},
+        import_keystores::{load_keystore_directory, load_password_from_config, process_password},
+        lean_node::{LeanNodeConfig, ProverBackend},
+        migrate_db::MigrateDBConfig,
+        ssz::{InspectConfig, OutputFormat, SszCommand},
+        transition::{TransitionCommand, TransitionConfig},
+        validator_node::ValidatorNodeConfig,
+        voluntary_exit::VoluntaryExitConfig,
+    },
+    ssz_inspect,
+    telemetry::init_tracing,
 };
 use ream_account_manager::{message_types::MessageType, seed::derive_seed_with_user_input};
-use ream_api_types_beacon::id::ValidatorID;
+use ream_api_types_beacon::{id::ValidatorID, validator::ValidatorStatus};
 use ream_api_types_common::id::ID;
+use ream_bls::{PrivateKey, PublicKey, traits::PublicKeyDerivable};
 use ream_chain_lean::{
     genesis as lean_genesis, lean_chain::LeanChain, messages::LeanChainServiceMessage,
-    p2p_request::LeanP2PRequest, service::LeanChainService,
+    p2p_request::LeanP2PRequest, service::LeanChainService, vote_verifier::VoteVerifier,
 };
 use ream_checkpoint_sync::initialize_db_from_checkpoint;
+use ream_consensus_beacon::{
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    execution_engine::mock_engine::MockExecutionEngine,
+    voluntary_exit::SignedVoluntaryExit,
+};
 use ream_consensus_lean::block::SignedBlock;
-use ream_consensus_misc::{
-    constants::beacon::set_genesis_validator_root, misc::compute_epoch_at_slot,
+use ream_consensus_misc::constants::beacon::{
+    COMPOUNDING_WITHDRAWAL_PREFIX, ETH1_ADDRESS_WITHDRAWAL_PREFIX, INTERVALS_PER_SLOT,
+    MAX_EFFECTIVE_BALANCE_ELECTRA, MIN_ACTIVATION_BALANCE, SLOTS_PER_EPOCH,
+    set_genesis_validator_root,
 };
+use ream_execution_engine::ExecutionEngine;
 use ream_executor::ReamExecutor;
-use ream_keystore::keystore::EncryptedKeystore;
+use ream_keystore::{
+    eip2333::{derive_private_key_from_path, signing_key_path, withdrawal_key_path},
+    interop::{interop_private_key, private_key_from_seed},
+    keystore::{CipherParams, EncryptedKeystore, KdfParams, Keystore},
+};
 use ream_network_manager::service::NetworkManagerService;
 use ream_network_spec::networks::{
     beacon_network_spec, set_beacon_network_spec, set_lean_network_spec,
@@ -47,27 +73,54 @@ use ream_p2p::{
         configurations::LeanGossipsubConfig,
         topics::{LeanGossipTopic, LeanGossipTopicKind},
     },
-    network::lean::{LeanNetworkConfig, LeanNetworkService},
+    network::lean::{LeanNetworkConfig, LeanNetworkService, load_private_key},
 };
 use ream_post_quantum_crypto::hashsig::private_key::PrivateKey as HashSigPrivateKey;
-use ream_rpc_beacon::{config::RpcServerConfig, start_server};
+#[cfg(feature = "risc0")]
+use ream_prover::risc0::Risc0Prover;
+#[cfg(feature = "sp1")]
+use ream_prover::sp1::Sp1Prover;
+use ream_prover::{prover::Prover, service::ProverService};
+use ream_prover_program::LeanProgramInput;
+use ream_rpc_beacon::{
+    config::RpcServerConfig,
+    handlers::admin::{AdminToken, LogFilterHandle},
+    start_server,
+};
 use ream_rpc_lean::{config::LeanRpcServerConfig, start_lean_server};
+use ream_signing::signer::Signer;
+use ream_slot_clock::SlotClock;
 use ream_storage::{
-    db::{ReamDB, reset_db},
+    db::{ReamDB, beacon::BeaconDB, config::DBConfig, reset_db},
     dir::setup_data_dir,
-    tables::table::Table,
 };
 use ream_sync::rwlock::Writer;
 use ream_validator_beacon::{
-    beacon_api_client::BeaconApiClient, validator::ValidatorService,
-    voluntary_exit::process_voluntary_exit,
+    beacon_api_client::{BeaconApiClient, http_client::ContentType, retry::RetryConfig},
+    builder::{
+        builder_client::{BuilderClient, BuilderConfig},
+        validator_registration::ProposerConfig,
+    },
+    deposit::{
+        DepositDataJson, address_withdrawal_credentials, bls_withdrawal_credentials,
+        sign_deposit_data, submit_deposits,
+    },
+    graffiti::GraffitiProvider,
+    validator::ValidatorService,
+    voluntary_exit::{
+        sign_voluntary_exit, submit_voluntary_exit, voluntary_exit_signing_context, wait_for_exits,
+    },
 };
 use ream_validator_lean::{
-    registry::load_validator_registry, service::ValidatorService as LeanValidatorService,
+    registry::{
+        NodeValidatorMapping, RegistryWatcher, load_validator_private_keys,
+        load_validator_public_keys, load_validator_registry,
+    },
+    service::ValidatorService as LeanValidatorService,
 };
+use ssz::{Decode, Encode};
 use tokio::{sync::mpsc, time::Instant};
-use tracing::{error, info};
-use tracing_subscriber::EnvFilter;
+use tracing::{error, info, warn};
 
 pub const APP_NAME: &str = "ream";
 
@@ -75,17 +128,12 @@ pub const APP_NAME: &str = "ream";
 /// appropriate node type (beacon node, validator node, or account manager) based on the command
 /// line arguments. Handles graceful shutdown on Ctrl-C.
 fn main() {
-    // Set the default log level to `info` if not set
-    let rust_log = env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
-    let env_filter = match rust_log.is_empty() {
-        true => EnvFilter::builder().parse_lossy("info,actix_server=warn,discv5=error"),
-        false => EnvFilter::builder().parse_lossy(rust_log),
-    };
-
-    tracing_subscriber::fmt().with_env_filter(env_filter).init();
-
     let cli = Cli::parse();
 
+    let (tracer_provider, log_filter_handle) =
+        init_tracing(APP_NAME, cli.tracing_endpoint.as_ref())
+            .expect("Failed to initialize tracing");
+
     let executor = ReamExecutor::new().expect("unable to create executor");
     let executor_clone = executor.clone();
     let ream_dir = setup_data_dir(APP_NAME, cli.data_dir.clone(), cli.ephemeral)
@@ -95,14 +143,18 @@ fn main() {
         reset_db(&ream_dir).expect("Unable to delete database");
     }
 
-    let ream_db = ReamDB::new(ream_dir.clone()).expect("unable to init Ream Database");
+    let db_config = cli.db_config();
+    let ream_db =
+        ReamDB::new(ream_dir.clone(), db_config.clone()).expect("unable to init Ream Database");
 
     match cli.command {
         Commands::LeanNode(config) => {
             executor_clone.spawn(async move { run_lean_node(*config, executor, ream_db).await });
         }
         Commands::BeaconNode(config) => {
-            executor_clone.spawn(async move { run_beacon_node(*config, executor, ream_db).await });
+            executor_clone.spawn(async move {
+                run_beacon_node(*config, executor, ream_db, Some(log_filter_handle)).await
+            });
         }
         Commands::ValidatorNode(config) => {
             executor_clone.spawn(async move { run_validator_node(*config, executor).await });
@@ -113,9 +165,28 @@ fn main() {
         Commands::VoluntaryExit(config) => {
             executor_clone.spawn(async move { run_voluntary_exit(*config).await });
         }
+        Commands::Deposit(config) => {
+            executor_clone.spawn(async move { run_deposit(*config).await });
+        }
         Commands::GeneratePrivateKey(config) => {
             executor_clone.spawn(async move { run_generate_private_key(*config).await });
         }
+        Commands::Genesis(config) => match config.command {
+            GenesisCommand::Generate(config) => {
+                executor_clone.spawn(async move { run_generate_genesis(config).await });
+            }
+        },
+        Commands::MigrateDB(config) => {
+            executor_clone.spawn(async move { run_migrate_db(*config, ream_db, db_config).await });
+        }
+        Commands::Transition(config) => {
+            executor_clone.spawn(async move { run_transition(*config).await });
+        }
+        Commands::Ssz(config) => match config.command {
+            SszCommand::Inspect(config) => {
+                executor_clone.spawn(async move { run_ssz_inspect(config).await });
+            }
+        },
     }
 
     executor_clone.runtime().block_on(async {
@@ -128,6 +199,12 @@ fn main() {
 
     executor_clone.shutdown_runtime();
 
+    if let Some(tracer_provider) = tracer_provider
+        && let Err(err) = tracer_provider.shutdown()
+    {
+        eprintln!("Failed to flush the tracing spans: {err:?}");
+    }
+
     process::exit(0);
 }
 
@@ -153,7 +230,51 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
         );
     }
 
-    set_lean_network_spec(config.network);
+    // A devnet config replaces the network spec, bootnodes, validator registry and libp2p key
+    // flags with the entry of this node.
+    let (network_spec, bootnodes, keystores, private_key, socket_port) = match &config.devnet_config
+    {
+        Some(path) => {
+            let devnet_config = DevnetConfig::load(path).expect("Failed to load devnet config");
+            let node = devnet_config
+                .node(&config.node_id)
+                .expect("Failed to find the node in the devnet config");
+            (
+                Arc::new(devnet_config.config.clone()),
+                devnet_config
+                    .bootnodes(&config.node_id)
+                    .expect("Failed to build the devnet bootnodes"),
+                node.keystores(),
+                Some(
+                    node.private_key()
+                        .expect("Failed to parse the node private key"),
+                ),
+                node.socket_port()
+                    .expect("Failed to get the node socket port"),
+            )
+        }
+        None => (
+            config
+                .network
+                .clone()
+                .expect("--network is required without --devnet-config"),
+            config.bootnodes.clone(),
+            load_validator_registry(
+                config
+                    .validator_registry_path
+                    .as_ref()
+                    .expect("--validator-registry-path is required without --devnet-config"),
+                &config.node_id,
+            )
+            .expect("Failed to load validator registry"),
+            config
+                .private_key_path
+                .as_ref()
+                .map(|path| load_private_key(path).expect("Failed to load the private key")),
+            config.socket_port,
+        ),
+    };
+    set_lean_network_spec(network_spec);
 
     // Initialize the lean database
     let lean_db = ream_db
@@ -162,26 +283,57 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
 
     info!("ream lean database has been initialized");
 
-    // Initialize the lean chain with genesis block and state.
+    // Initialize the lean chain with genesis block and state, or restore it from the database if
+    // the node has run before.
     let (genesis_block, genesis_state) = lean_genesis::setup_genesis();
-    let (lean_chain_writer, lean_chain_reader) = Writer::new(LeanChain::new(
+    let lean_chain = LeanChain::load(
         SignedBlock {
             message: genesis_block,
             signature: FixedBytes::default(),
         },
         genesis_state,
-        lean_db,
-    ));
+        lean_db.clone(),
+    )
+    .await
+    .expect("Failed to load the lean chain");
+    let (lean_chain_writer, lean_chain_reader) = Writer::new(lean_chain);
 
     // Initialize the services that will run in the lean node.
     let (chain_sender, chain_receiver) = mpsc::unbounded_channel::<LeanChainServiceMessage>();
     let (outbound_p2p_sender, outbound_p2p_receiver) = mpsc::unbounded_channel::<LeanP2PRequest>();
 
+    // Votes from the network are only verified if the validator public keys are provided.
+    let vote_verifier = config.validator_public_keys_path.as_ref().map(|path| {
+        VoteVerifier::new(
+            load_validator_public_keys(path).expect("Failed to load validator public keys"),
+        )
+    });
+
+    // The imported blocks are proven in the background if a prover backend is given, and their
+    // proofs are served by the RPC once they are stored.
+    let prover_sender = config.prover.map(|backend| {
+        let (prover_sender, prover_receiver) = mpsc::unbounded_channel::<LeanProgramInput>();
+        let prover_service = ProverService::new(
+            new_prover(backend).expect("Failed to set up the prover"),
+            lean_db,
+            prover_receiver,
+            config.prover_workers,
+        );
+        executor.spawn(async move {
+            if let Err(err) = prover_service.start().await {
+                panic!("Prover service exited with error: {err:?}");
+            }
+        });
+        prover_sender
+    });
+
     let chain_service = LeanChainService::new(
         lean_chain_writer,
         chain_receiver,
         chain_sender.clone(),
         outbound_p2p_sender,
+        vote_verifier,
+        prover_sender,
     )
     .await;
 
@@ -206,8 +358,10 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
         Arc::new(LeanNetworkConfig {
             gossipsub_config,
             socket_address: config.socket_address,
-            socket_port: config.socket_port,
-            private_key_path: config.private_key_path,
+            socket_port,
+            discovery_port: config.discovery_port,
+            disable_discovery: config.disable_discovery,
+            private_key,
         }),
         lean_chain_reader.clone(),
         executor.clone(),
@@ -219,10 +373,28 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
 
     let peer_table = network_service.peer_table();
 
-    let keystores = load_validator_registry(&config.validator_registry_path, &config.node_id)
-        .expect("Failed to load validator registry");
-    let validator_service =
-        LeanValidatorService::new(lean_chain_reader.clone(), keystores, chain_sender).await;
+    // The registry given by --validator-registry-path is reloaded when it changes, so validators
+    // can be added or rotated without restarting the node.
+    let registry_watcher = config.validator_registry_path.clone().map(|path| {
+        RegistryWatcher::new(path, config.node_id.clone())
+            .expect("Failed to watch the validator registry")
+    });
+    // Blocks and votes are only signed if the validator private keys are provided.
+    let private_keys = config
+        .validator_private_keys_path
+        .as_ref()
+        .map(|path| {
+            load_validator_private_keys(path).expect("Failed to load validator private keys")
+        })
+        .unwrap_or_default();
+    let validator_service = LeanValidatorService::new(
+        lean_chain_reader.clone(),
+        keystores,
+        private_keys,
+        chain_sender,
+        registry_watcher,
+    )
+    .await;
 
     let server_config = LeanRpcServerConfig::new(
         config.http_address,
@@ -237,7 +409,7 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
         }
     });
     let network_future = executor.spawn(async move {
-        if let Err(err) = network_service.start(config.bootnodes).await {
+        if let Err(err) = network_service.start(bootnodes).await {
             panic!("Network service exited with error: {err:?}");
         }
     });
@@ -266,6 +438,21 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
     }
 }
 
+/// Sets up the prover of `backend`, which is only available if ream is built with the feature of
+/// the same name.
+fn new_prover(backend: ProverBackend) -> anyhow::Result<Box<dyn Prover + Send + Sync>> {
+    match backend {
+        #[cfg(feature = "risc0")]
+        ProverBackend::Risc0 => Ok(Box::new(Risc0Prover)),
+        #[cfg(not(feature = "risc0"))]
+        ProverBackend::Risc0 => Err(anyhow!("ream was built without the risc0 feature")),
+        #[cfg(feature = "sp1")]
+        ProverBackend::Sp1 => Ok(Box::new(Sp1Prover::new())),
+        #[cfg(not(feature = "sp1"))]
+        ProverBackend::Sp1 => Err(anyhow!("ream was built without the sp1 feature")),
+    }
+}
+
 /// Runs the beacon node.
 ///
 /// This function initializes the beacon node by setting up the network specification,
@@ -274,9 +461,24 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor, ream_
 /// At the end of setup, it starts 2 services:
 /// 1. The HTTP server that serves Beacon API, Engine API.
 /// 2. The P2P network that handles peer discovery (discv5), gossiping (gossipsub) and Req/Resp API.
-pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor, ream_db: ReamDB) {
+pub async fn run_beacon_node(
+    config: BeaconNodeConfig,
+    executor: ReamExecutor,
+    ream_db: ReamDB,
+    log_filter_handle: Option<LogFilterHandle>,
+) {
     info!("starting up beacon node...");
 
+    // Initialize prometheus metrics
+    if config.enable_metrics {
+        let address = SocketAddr::new(config.metrics_address, config.metrics_port);
+        prometheus_exporter::start(address).expect("Failed to start prometheus exporter");
+        info!(
+            "Metrics started on {}:{}",
+            config.metrics_address, config.metrics_port
+        );
+    }
+
     set_beacon_network_spec(config.network.clone());
 
     // Initialize the beacon database
@@ -286,6 +488,18 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor, r
 
     info!("ream beacon database has been initialized");
 
+    match beacon_db.warm_up_hot_state_cache() {
+        Ok(0) => {}
+        Ok(loaded_states) => info!("Loaded {loaded_states} hot states from the last shutdown"),
+        Err(err) => warn!("Failed to warm up the hot state cache: {err:?}"),
+    }
+    let shutdown_db = beacon_db.clone();
+    executor.spawn_on_shutdown(async move {
+        if let Err(err) = shutdown_db.persist_hot_state_roots() {
+            error!("Failed to persist the hot state roots: {err:?}");
+        }
+    });
+
     let _is_ws_verified = initialize_db_from_checkpoint(
         beacon_db.clone(),
         config.checkpoint_sync_url.clone(),
@@ -296,28 +510,54 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor, r
 
     info!("Database Initialization completed");
 
-    let oldest_root = beacon_db
-        .slot_index_provider()
-        .get_oldest_root()
-        .expect("Failed to access slot index provider")
-        .expect("No oldest root found");
+    // The genesis validators root is the same in every state, and the latest one is already in
+    // the hot state cache
     set_genesis_validator_root(
         beacon_db
-            .beacon_state_provider()
-            .get(oldest_root)
-            .expect("Failed to access beacon state provider")
-            .expect("No beacon state found")
+            .get_latest_state()
+            .expect("Failed to read the latest beacon state")
             .genesis_validators_root,
     );
 
+    if config.freezer {
+        let freezer_db = beacon_db.clone();
+        let restore_point_slots = config.freezer_restore_point_slots;
+        executor.spawn(async move { run_freezer_migration(freezer_db, restore_point_slots).await });
+    }
+
     let operation_pool = Arc::new(OperationPool::default());
 
+    let admin_token = config.admin_token_file.as_ref().map(|admin_token_file| {
+        AdminToken::new(
+            &fs::read_to_string(admin_token_file).expect("Failed to read the admin token file"),
+        )
+        .expect("The admin token file is empty")
+    });
     let server_config = RpcServerConfig::new(
         config.http_address,
         config.http_port,
         config.http_allow_origin,
+        admin_token,
     );
 
+    let builder_client = (!config.mev_relay_urls.is_empty()).then(|| {
+        Arc::new(
+            BuilderClient::new(
+                BuilderConfig {
+                    builder_enabled: true,
+                    mev_relay_urls: config.mev_relay_urls.clone(),
+                    min_bid: config.builder_min_bid,
+                },
+                config.builder_request_timeout,
+                ContentType::Json,
+            )
+            .expect("Failed to create builder client"),
+        )
+    });
+    if let Some(builder_client) = builder_client.clone() {
+        executor.spawn(async move { run_relay_status_checks(builder_client).await });
+    }
+
     let network_manager = NetworkManagerService::new(
         executor.clone(),
         config.into(),
@@ -332,6 +572,14 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor, r
 
     let execution_engine = network_manager.beacon_chain.execution_engine.clone();
 
+    let p2p_sender = network_manager.p2p_sender.clone();
+
+    let beacon_chain = network_manager.beacon_chain.clone();
+
+    let admin_sender = network_manager.admin_sender.clone();
+
+    let sync_progress = network_manager.sync_progress.clone();
+
     let network_future = executor.spawn(async move {
         network_manager.start().await;
     });
@@ -343,6 +591,12 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor, r
             network_state,
             operation_pool,
             execution_engine,
+            p2p_sender,
+            beacon_chain,
+            builder_client,
+            admin_sender,
+            sync_progress,
+            log_filter_handle,
         )
         .await
     });
@@ -357,6 +611,40 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor, r
     }
 }
 
+/// Moves the finalized chain from the database to the freezer once per epoch.
+async fn run_freezer_migration(beacon_db: BeaconDB, restore_point_slots: u64) {
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        beacon_network_spec().seconds_per_slot * SLOTS_PER_EPOCH,
+    ));
+    loop {
+        interval.tick().await;
+        let beacon_db = beacon_db.clone();
+        match tokio::task::spawn_blocking(move || beacon_db.migrate_to_freezer(restore_point_slots))
+            .await
+        {
+            Ok(Ok(0)) => {}
+            Ok(Ok(migrated_blocks)) => {
+                info!("Moved {migrated_blocks} finalized blocks to the freezer")
+            }
+            Ok(Err(err)) => error!("Failed to move the finalized chain to the freezer: {err:?}"),
+            Err(err) => error!("The freezer migration task failed: {err:?}"),
+        }
+    }
+}
+
+/// Checks the status of the MEV relays once per slot, so bids are only requested from the healthy
+/// ones.
+async fn run_relay_status_checks(builder_client: Arc<BuilderClient>) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(beacon_network_spec().seconds_per_slot));
+    loop {
+        interval.tick().await;
+        if builder_client.update_relay_status().await == 0 {
+            warn!("None of the MEV relays is healthy");
+        }
+    }
+}
+
 /// Runs the validator node.
 ///
 /// This function initializes the validator node by setting up the network specification,
@@ -365,6 +653,16 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor, r
 pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecutor) {
     info!("starting up validator node...");
 
+    // Initialize prometheus metrics
+    if config.enable_metrics {
+        let address = SocketAddr::new(config.metrics_address, config.metrics_port);
+        prometheus_exporter::start(address).expect("Failed to start prometheus exporter");
+        info!(
+            "Metrics started on {}:{}",
+            config.metrics_address, config.metrics_port
+        );
+    }
+
     set_beacon_network_spec(config.network.clone());
 
     let password = process_password(
@@ -372,7 +670,7 @@ pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecu
             .expect("Failed to load password"),
     );
 
-    let keystores = load_keystore_directory(&config.import_keystores)
+    let signers = load_keystore_directory(&config.import_keystores)
         .expect("Failed to load keystore directory")
         .into_iter()
         .map(|encrypted_keystore| {
@@ -380,13 +678,24 @@ pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecu
                 .decrypt(password.as_bytes())
                 .expect("Could not decrypt a keystore")
         })
+        .map(|keystore| Arc::new(keystore) as Arc<dyn Signer>)
         .collect::<Vec<_>>();
 
     let validator_service = ValidatorService::new(
-        keystores,
-        config.suggested_fee_recipient,
-        config.beacon_api_endpoint,
+        signers,
+        ProposerConfig {
+            fee_recipient: config.suggested_fee_recipient,
+            gas_limit: config.gas_limit,
+            enable_builder: config.enable_builder,
+        },
+        config.beacon_api_endpoints,
         config.request_timeout,
+        RetryConfig {
+            max_retries: config.request_retries,
+            initial_backoff: Duration::from_millis(config.request_retry_backoff_ms),
+            ..Default::default()
+        },
+        GraffitiProvider::new(config.graffiti, config.graffiti_file),
         executor,
     )
     .expect("Failed to create validator service");
@@ -407,7 +716,7 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_dir: PathBuf
     );
 
     // Get seed phrase or generate a new one
-    let seed_phrase = config.seed_phrase.unwrap_or_else(|| {
+    let seed_phrase = config.seed_phrase.clone().unwrap_or_else(|| {
         let mnemonic = Mnemonic::generate(24).expect("Failed to generate mnemonic");
         let seed_phrase = mnemonic.words().collect::<Vec<_>>().join(" ");
         info!("{}", "=".repeat(89));
@@ -440,48 +749,55 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_dir: PathBuf
     // Measure key generation time
     let start_time = Instant::now();
 
-    // Generate keys sequentially for each message type
-    for (index, message_type) in MessageType::iter().enumerate() {
-        info!(
-            "Generating lean consensus validator keys for index {index}, message type: {message_type}..."
-        );
-
-        let seed = derive_seed_with_user_input(
-            &seed_phrase,
-            index as u32,
-            config.passphrase.as_deref().unwrap_or(""),
-        );
-
-        let (public_key, _private_key) = HashSigPrivateKey::generate_key_pair(
-            &mut <ChaCha20Rng as SeedableRng>::from_seed(seed),
-            config.activation_epoch as usize,
-            config.num_active_epochs as usize,
-        );
-
-        info!(
-            "Public key for {message_type}: {}",
-            // This should never panic
-            serde_json::to_string_pretty(&public_key).expect("Failed to serialize public key")
-        );
-
-        // Create keystore file using Keystore
-        let keystore = EncryptedKeystore::from_seed_phrase(
-            &seed_phrase,
-            config.lifetime,
-            config.activation_epoch,
-            Some(format!("Ream validator keystore for {message_type}")),
-            Some(format!("m/44'/60'/0'/0/{index}")),
-        );
-
-        // Write keystore to file with enum name
-        let filename = message_type.to_string();
-        let keystore_file_path = keystore_dir.join(filename);
-        let keystore_json =
-            ::serde_json::to_string_pretty(&keystore).expect("Failed to serialize keystore");
-
-        fs::write(&keystore_file_path, keystore_json).expect("Failed to write keystore file");
-
-        info!("Keystore written to path: {}", keystore_file_path.display());
+    match config.scheme {
+        KeyScheme::Hashsig => {
+            // Generate keys sequentially for each message type
+            for (index, message_type) in MessageType::iter().enumerate() {
+                info!(
+                    "Generating lean consensus validator keys for index {index}, message type: {message_type}..."
+                );
+
+                let seed = derive_seed_with_user_input(
+                    &seed_phrase,
+                    index as u32,
+                    config.passphrase.as_deref().unwrap_or(""),
+                );
+
+                let (public_key, _private_key) = HashSigPrivateKey::generate_key_pair(
+                    &mut <ChaCha20Rng as SeedableRng>::from_seed(seed),
+                    config.activation_epoch as usize,
+                    config.num_active_epochs as usize,
+                );
+
+                info!(
+                    "Public key for {message_type}: {}",
+                    // This should never panic
+                    serde_json::to_string_pretty(&public_key)
+                        .expect("Failed to serialize public key")
+                );
+
+                // Create keystore file using Keystore
+                let keystore = EncryptedKeystore::from_seed_phrase(
+                    &seed_phrase,
+                    config.lifetime,
+                    config.activation_epoch,
+                    Some(format!("Ream validator keystore for {message_type}")),
+                    Some(format!("m/44'/60'/0'/0/{index}")),
+                );
+
+                // Write keystore to file with enum name
+                let filename = message_type.to_string();
+                let keystore_file_path = keystore_dir.join(filename);
+                let keystore_json = ::serde_json::to_string_pretty(&keystore)
+                    .expect("Failed to serialize keystore");
+
+                fs::write(&keystore_file_path, keystore_json)
+                    .expect("Failed to write keystore file");
+
+                info!("Keystore written to path: {}", keystore_file_path.display());
+            }
+        }
+        KeyScheme::Bls => generate_bls_keystores(&config, &seed_phrase, &keystore_dir),
     }
     let duration = start_time.elapsed();
     info!("Key generation complete, took {:?}", duration);
@@ -491,72 +807,371 @@ pub async fn run_account_manager(config: AccountManagerConfig, ream_dir: PathBuf
     process::exit(0);
 }
 
+/// Derives the BLS keys of the validators from the seed phrase at their EIP-2334 paths, and writes
+/// them as EIP-2335 keystores together with the deposit data for the staking launchpad.
+fn generate_bls_keystores(config: &AccountManagerConfig, seed_phrase: &str, keystore_dir: &Path) {
+    let password = process_password(
+        load_password_from_config(config.password_file.as_ref(), config.password.clone())
+            .expect("Failed to load the keystore password"),
+    );
+    let seed = seed_phrase
+        .parse::<Mnemonic>()
+        .expect("Invalid mnemonic phrase")
+        .to_seed(config.passphrase.as_deref().unwrap_or(""));
+    let fork_version = config.network.genesis_fork_version;
+    let network_name = config.network.network.to_string();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the UNIX epoch")
+        .as_secs();
+
+    let mut deposit_data = vec![];
+    for index in config.validator_start_index..config.validator_start_index + config.num_validators
+    {
+        info!("Generating BLS keystore for validator {index}...");
+
+        let signing_path = signing_key_path(index);
+        let private_key = derive_private_key_from_path(&seed, &signing_path)
+            .expect("Failed to derive the signing key");
+        let public_key = private_key
+            .public_key()
+            .expect("Failed to derive the public key");
+
+        let withdrawal_credentials = match config.withdrawal_address {
+            Some(withdrawal_address) => {
+                address_withdrawal_credentials(ETH1_ADDRESS_WITHDRAWAL_PREFIX, withdrawal_address)
+            }
+            None => bls_withdrawal_credentials(
+                &derive_private_key_from_path(&seed, &withdrawal_key_path(index))
+                    .expect("Failed to derive the withdrawal key")
+                    .public_key()
+                    .expect("Failed to derive the withdrawal public key"),
+            ),
+        };
+
+        deposit_data.push(DepositDataJson::new(
+            &sign_deposit_data(
+                &private_key,
+                withdrawal_credentials,
+                MIN_ACTIVATION_BALANCE,
+                fork_version,
+            )
+            .expect("Failed to sign the deposit data"),
+            fork_version,
+            &network_name,
+        ));
+
+        let keystore = EncryptedKeystore::encrypt(
+            &Keystore {
+                public_key,
+                private_key,
+            },
+            password.as_bytes(),
+            &signing_path,
+        )
+        .expect("Failed to encrypt the keystore");
+        let keystore_file_path = keystore_dir.join(format!(
+            "keystore-{}-{timestamp}.json",
+            signing_path.replace('/', "_")
+        ));
+        keystore
+            .save_to_file(&keystore_file_path)
+            .expect("Failed to write keystore file");
+        info!("Keystore written to path: {}", keystore_file_path.display());
+    }
+
+    let deposit_data_file_path = keystore_dir.join(format!("deposit_data-{timestamp}.json"));
+    fs::write(
+        &deposit_data_file_path,
+        serde_json::to_string(&deposit_data).expect("Failed to serialize the deposit data"),
+    )
+    .expect("Failed to write the deposit data file");
+    info!(
+        "Deposit data written to path: {}",
+        deposit_data_file_path.display()
+    );
+}
+
 /// Runs the voluntary exit process.
 ///
-/// This function initializes the voluntary exit process by setting up the network specification,
-/// loading the keystores, creating a validator service, and processing the voluntary exit.
+/// This function sets up the network specification and either broadcasts a signed exit from
+/// `--submit-exit-file`, or loads the keystores, signs the exits of the selected validators and
+/// broadcasts them, or writes them to `--output-dir` for broadcasting later.
 pub async fn run_voluntary_exit(config: VoluntaryExitConfig) {
     info!("Starting voluntary exit process...");
 
     set_beacon_network_spec(config.network.clone());
 
+    let beacon_api_client = BeaconApiClient::new(
+        vec![config.beacon_api_endpoint],
+        config.request_timeout,
+        RetryConfig::default(),
+    )
+    .expect("Failed to create beacon API client");
+
+    if let Some(exit_file) = &config.submit_exit_file {
+        let signed_voluntary_exit = serde_json::from_str::<SignedVoluntaryExit>(
+            &fs::read_to_string(exit_file).expect("Failed to read the signed exit file"),
+        )
+        .expect("Failed to parse the signed exit file");
+        submit_voluntary_exits(&beacon_api_client, vec![signed_voluntary_exit], config.wait).await;
+        return;
+    }
+
+    let password = process_password(
+        load_password_from_config(config.password_file.as_ref(), config.password)
+            .expect("Failed to load password"),
+    );
+
+    let keystores = load_keystore_directory(
+        config
+            .import_keystores
+            .as_ref()
+            .expect("--import-keystores is required to sign exits"),
+    )
+    .expect("Failed to load keystore directory")
+    .into_iter()
+    .map(|encrypted_keystore| {
+        encrypted_keystore
+            .decrypt(password.as_bytes())
+            .expect("Could not decrypt a keystore")
+    })
+    .collect::<Vec<_>>();
+
+    let validators = if config.all {
+        beacon_api_client
+            .get_state_validator_list(
+                ID::Head,
+                Some(
+                    keystores
+                        .iter()
+                        .map(|keystore| ValidatorID::Address(keystore.public_key.clone()))
+                        .collect(),
+                ),
+                Some(vec![ValidatorStatus::ActiveOngoing]),
+            )
+            .await
+            .expect("Failed to get validator info")
+            .data
+    } else {
+        let validators = beacon_api_client
+            .get_state_validator_list(
+                ID::Head,
+                Some(
+                    config
+                        .validator_index
+                        .iter()
+                        .copied()
+                        .map(ValidatorID::Index)
+                        .collect(),
+                ),
+                None,
+            )
+            .await
+            .expect("Failed to get validator info")
+            .data;
+        for validator_index in &config.validator_index {
+            assert!(
+                validators
+                    .iter()
+                    .any(|validator| validator.index == *validator_index),
+                "Validator {validator_index} is not in the beacon state"
+            );
+        }
+        validators
+    };
+
+    if validators.is_empty() {
+        info!("No active validators to exit");
+        return;
+    }
+
+    let epoch = match config.epoch {
+        Some(epoch) => epoch,
+        None => {
+            let genesis = beacon_api_client
+                .get_genesis()
+                .await
+                .expect("Failed to get genesis information");
+            get_current_epoch(genesis.data.genesis_time)
+        }
+    };
+
+    let signing_context = voluntary_exit_signing_context(None);
+    let mut signed_voluntary_exits = vec![];
+    for validator in &validators {
+        let keystore = keystores
+            .iter()
+            .find(|keystore| keystore.public_key == validator.validator.public_key)
+            .unwrap_or_else(|| panic!("No keystore found for validator index {}", validator.index));
+        signed_voluntary_exits.push(
+            sign_voluntary_exit(keystore, &signing_context, epoch, validator.index)
+                .await
+                .expect("Failed to sign voluntary exit"),
+        );
+    }
+
+    match &config.output_dir {
+        Some(output_dir) => {
+            fs::create_dir_all(output_dir).expect("Failed to create the output directory");
+            for signed_voluntary_exit in signed_voluntary_exits {
+                let exit_file_path = output_dir.join(format!(
+                    "exit_{}_{}.json",
+                    signed_voluntary_exit.message.validator_index,
+                    signed_voluntary_exit.message.epoch
+                ));
+                fs::write(
+                    &exit_file_path,
+                    serde_json::to_string(&signed_voluntary_exit)
+                        .expect("Failed to serialize the signed exit"),
+                )
+                .expect("Failed to write the signed exit file");
+                info!("Signed exit written to path: {}", exit_file_path.display());
+            }
+        }
+        None => {
+            submit_voluntary_exits(&beacon_api_client, signed_voluntary_exits, config.wait).await
+        }
+    }
+}
+
+/// Broadcasts `signed_voluntary_exits`, then waits for the accepted ones to exit if `wait` is set.
+async fn submit_voluntary_exits(
+    beacon_api_client: &BeaconApiClient,
+    signed_voluntary_exits: Vec<SignedVoluntaryExit>,
+    wait: bool,
+) {
+    let mut submitted = vec![];
+    for signed_voluntary_exit in signed_voluntary_exits {
+        let validator_index = signed_voluntary_exit.message.validator_index;
+        match submit_voluntary_exit(beacon_api_client, signed_voluntary_exit).await {
+            Ok(()) => {
+                info!("Voluntary exit of validator {validator_index} submitted successfully");
+                submitted.push(validator_index);
+            }
+            Err(err) => error!("Voluntary exit failed: {err}"),
+        }
+    }
+
+    if wait && !submitted.is_empty() {
+        match wait_for_exits(beacon_api_client, &submitted).await {
+            Ok(()) => info!("Voluntary exit completed successfully"),
+            Err(err) => error!("Failed to wait for the validators to exit: {err}"),
+        }
+    }
+}
+
+/// Runs the deposit process.
+///
+/// This function signs a deposit for each of the imported keystores and writes them to a
+/// launchpad compatible deposit data file. If an execution endpoint is given, the deposits are
+/// also submitted to the deposit contract, paid for by the given execution account.
+pub async fn run_deposit(config: DepositConfig) {
+    info!("Starting deposit process...");
+
+    let (withdrawal_prefix, max_amount) = if config.compounding {
+        (COMPOUNDING_WITHDRAWAL_PREFIX, MAX_EFFECTIVE_BALANCE_ELECTRA)
+    } else {
+        (ETH1_ADDRESS_WITHDRAWAL_PREFIX, MIN_ACTIVATION_BALANCE)
+    };
+    assert!(
+        config.amount <= max_amount,
+        "Deposit amount {} exceeds the maximum effective balance {max_amount} of the withdrawal credentials",
+        config.amount
+    );
+
     let password = process_password(
         load_password_from_config(config.password_file.as_ref(), config.password)
             .expect("Failed to load password"),
     );
+    let withdrawal_credentials =
+        address_withdrawal_credentials(withdrawal_prefix, config.withdrawal_address);
+    let fork_version = config.network.genesis_fork_version;
 
-    let keystores = load_keystore_directory(&config.import_keystores)
+    let deposits = load_keystore_directory(&config.import_keystores)
         .expect("Failed to load keystore directory")
         .into_iter()
         .map(|encrypted_keystore| {
-            encrypted_keystore
+            let keystore = encrypted_keystore
                 .decrypt(password.as_bytes())
-                .expect("Could not decrypt a keystore")
+                .expect("Could not decrypt a keystore");
+            sign_deposit_data(
+                &keystore.private_key,
+                withdrawal_credentials,
+                config.amount,
+                fork_version,
+            )
+            .expect("Failed to sign the deposit data")
         })
         .collect::<Vec<_>>();
 
-    let beacon_api_client =
-        BeaconApiClient::new(config.beacon_api_endpoint, config.request_timeout)
-            .expect("Failed to create beacon API client");
-
-    let validator_info = beacon_api_client
-        .get_state_validator(ID::Head, ValidatorID::Index(config.validator_index))
-        .await
-        .expect("Failed to get validator info");
-
-    let keystore = keystores
+    let network_name = config.network.network.to_string();
+    let deposit_data = deposits
         .iter()
-        .find(|keystore| keystore.public_key == validator_info.data.validator.public_key)
-        .expect("No keystore found for the specified validator index");
+        .map(|deposit_data| DepositDataJson::new(deposit_data, fork_version, &network_name))
+        .collect::<Vec<_>>();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the UNIX epoch")
+        .as_secs();
+    fs::create_dir_all(&config.output_dir).expect("Failed to create the output directory");
+    let deposit_data_file_path = config
+        .output_dir
+        .join(format!("deposit_data-{timestamp}.json"));
+    fs::write(
+        &deposit_data_file_path,
+        serde_json::to_string(&deposit_data).expect("Failed to serialize the deposit data"),
+    )
+    .expect("Failed to write the deposit data file");
+    info!(
+        "Deposit data of {} validator(s) written to path: {}",
+        deposits.len(),
+        deposit_data_file_path.display()
+    );
+
+    if let (Some(execution_endpoint), Some(execution_jwt_secret), Some(eth1_private_key_file)) = (
+        config.execution_endpoint,
+        config.execution_jwt_secret,
+        config.eth1_private_key_file,
+    ) {
+        let execution_engine = ExecutionEngine::new(execution_endpoint, execution_jwt_secret)
+            .expect("Failed to create the execution engine client");
+        let signer = PrivateKeySigner::from_bytes(
+            &fs::read_to_string(eth1_private_key_file)
+                .expect("Failed to read the execution private key file")
+                .trim()
+                .parse::<B256>()
+                .expect("Invalid execution private key"),
+        )
+        .expect("Invalid execution private key");
 
-    let genesis = beacon_api_client
-        .get_genesis()
+        match submit_deposits(
+            &execution_engine,
+            &signer,
+            config.network.deposit_contract_address,
+            &deposits,
+        )
         .await
-        .expect("Failed to get genesis information");
-
-    match process_voluntary_exit(
-        &beacon_api_client,
-        config.validator_index,
-        get_current_epoch(genesis.data.genesis_time),
-        &keystore.private_key,
-        config.wait,
-    )
-    .await
-    {
-        Ok(()) => info!("Voluntary exit completed successfully"),
-        Err(err) => error!("Voluntary exit failed: {err}"),
+        {
+            Ok(transaction_hashes) => info!(
+                "Submitted {} deposit transaction(s) from {}",
+                transaction_hashes.len(),
+                signer.address()
+            ),
+            Err(err) => error!("Failed to submit the deposits: {err}"),
+        }
     }
 }
 
 /// Calculates the current epoch from genesis time
 fn get_current_epoch(genesis_time: u64) -> u64 {
-    compute_epoch_at_slot(
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH + Duration::from_secs(genesis_time))
-            .expect("System Time is before the genesis time")
-            .as_secs()
-            / beacon_network_spec().seconds_per_slot,
+    SlotClock::new(
+        genesis_time,
+        beacon_network_spec().seconds_per_slot,
+        INTERVALS_PER_SLOT,
     )
+    .current_epoch()
+    .expect("System Time is before the genesis time")
 }
 
 /// Generates a new secp256k1 keypair and saves it to the specified path in hex encoding.
@@ -589,3 +1204,266 @@ pub async fn run_generate_private_key(config: GeneratePrivateKeyConfig) {
 
     process::exit(0);
 }
+
+/// Copies every table of the database into the database of another backend in the data
+/// directory. The node uses it after restarting with `--db.backend` set to the new backend.
+pub async fn run_migrate_db(config: MigrateDBConfig, ream_db: ReamDB, db_config: DBConfig) {
+    info!("Migrating the database to {}...", config.to);
+
+    let entries = ream_db
+        .migrate_to(config.to, &db_config)
+        .expect("Failed to migrate the database");
+    info!(
+        "Copied {entries} entries, restart ream with --db.backend {} to use the new database",
+        config.to
+    );
+
+    process::exit(0);
+}
+
+/// Runs the Electra state transition on a pre-state read from an SSZ file, and logs the root of
+/// the post-state so it can be compared with the one of other clients.
+pub async fn run_transition(config: TransitionConfig) {
+    let (network, pre_path, post_path) = match &config.command {
+        TransitionCommand::Blocks(config) => (&config.network, &config.pre, &config.post),
+        TransitionCommand::Slots(config) => (&config.network, &config.pre, &config.post),
+    };
+    set_beacon_network_spec(network.clone());
+    let mut state: BeaconState = read_ssz_file(pre_path).expect("Failed to read the pre-state");
+    set_genesis_validator_root(state.genesis_validators_root);
+    info!(
+        "Loaded pre-state of slot {} with root {}",
+        state.slot,
+        state.state_root()
+    );
+
+    let result = match &config.command {
+        TransitionCommand::Blocks(config) => {
+            // There is no execution client to ask, so every execution payload is considered valid
+            let execution_engine = Some(MockExecutionEngine::new());
+            let mut result = Ok(());
+            for block_path in &config.blocks {
+                let signed_block: SignedBeaconBlock =
+                    read_ssz_file(block_path).expect("Failed to read the block");
+                info!(
+                    "Applying block of slot {} with root {}",
+                    signed_block.message.slot,
+                    signed_block.message.block_root()
+                );
+                result = state
+                    .state_transition(&signed_block, !config.no_verify, &execution_engine)
+                    .await;
+                if result.is_err() {
+                    break;
+                }
+            }
+            result
+        }
+        TransitionCommand::Slots(config) => {
+            state.process_slots((state.slot + config.slots).as_u64())
+        }
+    };
+    if let Err(err) = result {
+        error!("State transition failed: {err:?}");
+        process::exit(1);
+    }
+
+    info!(
+        "Post-state of slot {} has root {}",
+        state.slot,
+        state.state_root()
+    );
+    if let Some(post_path) = post_path {
+        write_ssz_file(post_path, &state).expect("Failed to write the post-state");
+        info!("Post-state saved to: {}", post_path.display());
+    }
+
+    process::exit(0);
+}
+
+/// Decodes an SSZ file as a known container, and prints it with its hash tree root, or the
+/// Merkle proof of one of its fields.
+pub async fn run_ssz_inspect(config: InspectConfig) {
+    let bytes = read_ssz_bytes(&config.file).expect("Failed to read the SSZ file");
+    let output = match ssz_inspect::inspect(config.ssz_type, &bytes, config.field.as_deref()) {
+        Ok(output) => output,
+        Err(err) => {
+            error!("Failed to inspect {}: {err:?}", config.file.display());
+            process::exit(1);
+        }
+    };
+
+    let output = match config.format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(&output).expect("Failed to serialize to JSON")
+        }
+        OutputFormat::Yaml => serde_yaml::to_string(&output).expect("Failed to serialize to YAML"),
+    };
+    println!("{output}");
+
+    process::exit(0);
+}
+
+/// Reads an SSZ file, which is snappy compressed if its name ends with `.ssz_snappy` like the
+/// files of the consensus spec tests.
+fn read_ssz_file<T: Decode>(path: &Path) -> anyhow::Result<T> {
+    T::from_ssz_bytes(&read_ssz_bytes(path)?)
+        .map_err(|err| anyhow!("Failed to decode {}: {err:?}", path.display()))
+}
+
+fn read_ssz_bytes(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    if is_ssz_snappy(path) {
+        return Ok(snap::raw::Decoder::new().decompress_vec(&bytes)?);
+    }
+    Ok(bytes)
+}
+
+fn write_ssz_file(path: &Path, value: &impl Encode) -> anyhow::Result<()> {
+    let mut bytes = value.as_ssz_bytes();
+    if is_ssz_snappy(path) {
+        bytes = snap::raw::Encoder::new().compress_vec(&bytes)?;
+    }
+    Ok(fs::write(path, bytes)?)
+}
+
+fn is_ssz_snappy(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "ssz_snappy")
+}
+
+/// Writes the validator keys as EIP-2335 keystores to the `keystores` directory, and their
+/// password to `password.txt`, which the validator client loads with `--import-keystores` and
+/// `--password-file`.
+fn write_genesis_keystores(
+    config: &GenerateGenesisConfig,
+    public_keys: Vec<PublicKey>,
+    private_keys: Vec<PrivateKey>,
+) {
+    let password = process_password(
+        config
+            .keystore_password
+            .clone()
+            .unwrap_or_else(|| hex::encode(rand::random::<[u8; 32]>())),
+    );
+    let password_file_path = config.output_dir.join("password.txt");
+    fs::write(&password_file_path, &password).expect("Failed to write the password file");
+
+    let keystore_dir = config.output_dir.join("keystores");
+    fs::create_dir_all(&keystore_dir).expect("Failed to create the keystore directory");
+    for (index, (public_key, private_key)) in public_keys.into_iter().zip(private_keys).enumerate()
+    {
+        // PBKDF2 decrypts quicker than scrypt, which adds up over the validators of a devnet
+        let keystore = EncryptedKeystore::encrypt_with(
+            &Keystore {
+                public_key,
+                private_key,
+            },
+            password.as_bytes(),
+            "",
+            KdfParams::pbkdf2(),
+            CipherParams::aes_128_ctr(),
+        )
+        .expect("Failed to encrypt the keystore");
+        keystore
+            .save_to_file(keystore_dir.join(format!("keystore-{index}.json")))
+            .expect("Failed to write keystore file");
+    }
+    info!(
+        "Keystores written to {}, their password to {}",
+        keystore_dir.display(),
+        password_file_path.display()
+    );
+}
+
+/// Generates a genesis state from deterministic validator keys, and writes it to the output
+/// directory together with the keys, for spinning up a local devnet.
+pub async fn run_generate_genesis(config: GenerateGenesisConfig) {
+    let genesis_time = config.genesis_time.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time is before the UNIX epoch")
+            .as_secs()
+            + config.genesis_delay
+    });
+    info!(
+        "Generating {:?} genesis with {} validators at genesis time {genesis_time}...",
+        config.chain, config.validator_count
+    );
+
+    fs::create_dir_all(&config.output_dir).expect("Failed to create output directory");
+
+    match config.chain {
+        GenesisChain::Beacon => {
+            set_beacon_network_spec(config.network.clone());
+
+            let private_keys = (0..config.validator_count)
+                .map(|index| match &config.mnemonic {
+                    Some(mnemonic) => private_key_from_seed(derive_seed_with_user_input(
+                        mnemonic,
+                        index as u32,
+                        "",
+                    )),
+                    None => interop_private_key(index),
+                })
+                .collect::<Vec<_>>();
+            let public_keys = private_keys
+                .iter()
+                .map(|private_key| {
+                    private_key
+                        .public_key()
+                        .expect("Failed to derive the public key")
+                })
+                .collect::<Vec<_>>();
+
+            let genesis_state = BeaconState::genesis_from_public_keys(
+                genesis_time,
+                config.eth1_block_hash,
+                public_keys.clone(),
+            )
+            .expect("Failed to build the genesis state");
+            fs::write(
+                config.output_dir.join("genesis.ssz"),
+                genesis_state.as_ssz_bytes(),
+            )
+            .expect("Failed to write the genesis state");
+
+            write_genesis_keystores(&config, public_keys, private_keys);
+
+            info!(
+                "Genesis validators root: {}",
+                genesis_state.genesis_validators_root
+            );
+        }
+        GenesisChain::Lean => {
+            let (_, genesis_state) =
+                lean_genesis::generate_genesis(config.validator_count, genesis_time);
+            fs::write(
+                config.output_dir.join("genesis.ssz"),
+                genesis_state.as_ssz_bytes(),
+            )
+            .expect("Failed to write the genesis state");
+
+            // The hash-based signature keys are generated by the account manager, so only the
+            // assignment of the validators to this node is written.
+            let registry = NodeValidatorMapping {
+                nodes: HashMap::from([(
+                    APP_NAME.to_string(),
+                    (0..config.validator_count).collect(),
+                )]),
+            };
+            fs::write(
+                config.output_dir.join("validator_registry.yml"),
+                serde_yaml::to_string(&registry).expect("Failed to serialize the registry"),
+            )
+            .expect("Failed to write the validator registry");
+        }
+    }
+
+    info!(
+        "Genesis generated successfully and saved to: {}",
+        config.output_dir.display()
+    );
+
+    process::exit(0);
+}
@@ -0,0 +1,506 @@
+//! Decoding of SSZ containers for `ream ssz inspect`, with the Merkle proofs of their fields.
+//!
+//! https://ethereum.github.io/consensus-specs/ssz/merkle-proofs
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use ream_consensus_beacon::{
+    attestation::Attestation,
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        beacon_block_body::BeaconBlockBody,
+        beacon_state::BeaconState,
+    },
+    sync_committee::SyncCommittee,
+};
+use ream_consensus_misc::{
+    attestation_data::AttestationData,
+    beacon_block_header::{BeaconBlockHeader, SignedBeaconBlockHeader},
+    checkpoint::Checkpoint,
+    eth_1_data::Eth1Data,
+    fork::Fork,
+};
+use ream_merkle::{generate_proof, is_valid_merkle_branch, merkle_tree};
+use serde::Serialize;
+use serde_json::json;
+use ssz::Decode;
+use tree_hash::TreeHash;
+
+use crate::cli::ssz::SszType;
+
+/// An SSZ container whose fields can be walked for building Merkle proofs.
+pub trait Container {
+    /// The names of the fields, as they appear in the consensus specs.
+    fn field_names(&self) -> &'static [&'static str];
+
+    /// The hash tree roots of the fields, in the order of [Container::field_names].
+    fn field_roots(&self) -> Vec<B256>;
+
+    /// The field called `name` if it is a container itself.
+    fn field(&self, _name: &str) -> Option<&dyn Container> {
+        None
+    }
+}
+
+/// The Merkle proof of a field against the hash tree root of the container it is in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldProof {
+    pub generalized_index: u64,
+    pub leaf: B256,
+    /// The sibling nodes from the leaf up to the root
+    pub branch: Vec<B256>,
+}
+
+fn tree_depth(num_fields: usize) -> u64 {
+    num_fields.next_power_of_two().trailing_zeros() as u64
+}
+
+pub fn hash_tree_root(container: &dyn Container) -> anyhow::Result<B256> {
+    let field_roots = container.field_roots();
+    Ok(merkle_tree(&field_roots, tree_depth(field_roots.len()))?[1])
+}
+
+/// Builds the proof of the field at `path`, the names of the fields leading to it joined by
+/// dots, e.g. `finalized_checkpoint.root`.
+pub fn prove_field(container: &dyn Container, path: &str) -> anyhow::Result<FieldProof> {
+    let root = hash_tree_root(container)?;
+    let mut current = container;
+    let mut generalized_index = 1;
+    let mut level_proofs = vec![];
+    let mut leaf = root;
+
+    let names = path.split('.').collect::<Vec<_>>();
+    for (position, name) in names.iter().enumerate() {
+        let index = current
+            .field_names()
+            .iter()
+            .position(|field_name| field_name == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown field {name}, expected one of: {}",
+                    current.field_names().join(", ")
+                )
+            })?;
+        let field_roots = current.field_roots();
+        let depth = tree_depth(field_roots.len());
+        let tree = merkle_tree(&field_roots, depth)?;
+        level_proofs.push(generate_proof(&tree, index as u64, depth)?);
+        generalized_index = (generalized_index << depth) | index as u64;
+        leaf = field_roots[index];
+
+        if position + 1 < names.len() {
+            current = current
+                .field(name)
+                .ok_or_else(|| anyhow!("Field {name} has no fields to select from"))?;
+        }
+    }
+
+    let branch = level_proofs.into_iter().rev().flatten().collect::<Vec<_>>();
+    let depth = branch.len() as u64;
+    ensure!(
+        is_valid_merkle_branch(leaf, &branch, depth, generalized_index - (1 << depth), root),
+        "The proof of {path} doesn't verify against the root"
+    );
+
+    Ok(FieldProof {
+        generalized_index,
+        leaf,
+        branch,
+    })
+}
+
+/// Decodes `bytes` as `ssz_type`, and returns its hash tree root with either its value or the
+/// proof of `field`.
+pub fn inspect(
+    ssz_type: SszType,
+    bytes: &[u8],
+    field: Option<&str>,
+) -> anyhow::Result<serde_json::Value> {
+    match ssz_type {
+        SszType::BeaconState => inspect_container::<BeaconState>(bytes, field),
+        SszType::SignedBeaconBlock => inspect_container::<SignedBeaconBlock>(bytes, field),
+        SszType::BeaconBlock => inspect_container::<BeaconBlock>(bytes, field),
+        SszType::BeaconBlockBody => inspect_container::<BeaconBlockBody>(bytes, field),
+        SszType::SignedBeaconBlockHeader => {
+            inspect_container::<SignedBeaconBlockHeader>(bytes, field)
+        }
+        SszType::BeaconBlockHeader => inspect_container::<BeaconBlockHeader>(bytes, field),
+        SszType::Attestation => inspect_container::<Attestation>(bytes, field),
+        SszType::AttestationData => inspect_container::<AttestationData>(bytes, field),
+        SszType::Checkpoint => inspect_container::<Checkpoint>(bytes, field),
+        SszType::SyncCommittee => inspect_container::<SyncCommittee>(bytes, field),
+    }
+}
+
+fn inspect_container<T: Container + Decode + Serialize>(
+    bytes: &[u8],
+    field: Option<&str>,
+) -> anyhow::Result<serde_json::Value> {
+    let container =
+        T::from_ssz_bytes(bytes).map_err(|err| anyhow!("Failed to decode the SSZ: {err:?}"))?;
+    let tree_hash_root = hash_tree_root(&container)?;
+
+    Ok(match field {
+        Some(field) => {
+            let proof = prove_field(&container, field)?;
+            json!({
+                "tree_hash_root": tree_hash_root,
+                "field": field,
+                "generalized_index": proof.generalized_index,
+                "leaf": proof.leaf,
+                "branch": proof.branch,
+            })
+        }
+        None => json!({
+            "tree_hash_root": tree_hash_root,
+            "value": serde_json::to_value(&container)?,
+        }),
+    })
+}
+
+impl Container for BeaconState {
+    fn field_names(&self) -> &'static [&'static str] {
+        &[
+            "genesis_time",
+            "genesis_validators_root",
+            "slot",
+            "fork",
+            "latest_block_header",
+            "block_roots",
+            "state_roots",
+            "historical_roots",
+            "eth1_data",
+            "eth1_data_votes",
+            "eth1_deposit_index",
+            "validators",
+            "balances",
+            "randao_mixes",
+            "slashings",
+            "previous_epoch_participation",
+            "current_epoch_participation",
+            "justification_bits",
+            "previous_justified_checkpoint",
+            "current_justified_checkpoint",
+            "finalized_checkpoint",
+            "inactivity_scores",
+            "current_sync_committee",
+            "next_sync_committee",
+            "latest_execution_payload_header",
+            "next_withdrawal_index",
+            "next_withdrawal_validator_index",
+            "historical_summaries",
+            "deposit_requests_start_index",
+            "deposit_balance_to_consume",
+            "exit_balance_to_consume",
+            "earliest_exit_epoch",
+            "consolidation_balance_to_consume",
+            "earliest_consolidation_epoch",
+            "pending_deposits",
+            "pending_partial_withdrawals",
+            "pending_consolidations",
+        ]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        self.merkle_leaves()
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Container> {
+        match name {
+            "fork" => Some(&self.fork),
+            "latest_block_header" => Some(&self.latest_block_header),
+            "eth1_data" => Some(&self.eth1_data),
+            "previous_justified_checkpoint" => Some(&self.previous_justified_checkpoint),
+            "current_justified_checkpoint" => Some(&self.current_justified_checkpoint),
+            "finalized_checkpoint" => Some(&self.finalized_checkpoint),
+            "current_sync_committee" => Some(self.current_sync_committee.as_ref()),
+            "next_sync_committee" => Some(self.next_sync_committee.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Container for SignedBeaconBlock {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["message", "signature"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.message.tree_hash_root(),
+            self.signature.tree_hash_root(),
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Container> {
+        match name {
+            "message" => Some(&self.message),
+            _ => None,
+        }
+    }
+}
+
+impl Container for BeaconBlock {
+    fn field_names(&self) -> &'static [&'static str] {
+        &[
+            "slot",
+            "proposer_index",
+            "parent_root",
+            "state_root",
+            "body",
+        ]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.slot.tree_hash_root(),
+            self.proposer_index.tree_hash_root(),
+            self.parent_root.tree_hash_root(),
+            self.state_root.tree_hash_root(),
+            self.body.tree_hash_root(),
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Container> {
+        match name {
+            "body" => Some(&self.body),
+            _ => None,
+        }
+    }
+}
+
+impl Container for BeaconBlockBody {
+    fn field_names(&self) -> &'static [&'static str] {
+        &[
+            "randao_reveal",
+            "eth1_data",
+            "graffiti",
+            "proposer_slashings",
+            "attester_slashings",
+            "attestations",
+            "deposits",
+            "voluntary_exits",
+            "sync_aggregate",
+            "execution_payload",
+            "bls_to_execution_changes",
+            "blob_kzg_commitments",
+            "execution_requests",
+        ]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        self.merkle_leaves()
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Container> {
+        match name {
+            "eth1_data" => Some(&self.eth1_data),
+            _ => None,
+        }
+    }
+}
+
+impl Container for SignedBeaconBlockHeader {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["message", "signature"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.message.tree_hash_root(),
+            self.signature.tree_hash_root(),
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Container> {
+        match name {
+            "message" => Some(&self.message),
+            _ => None,
+        }
+    }
+}
+
+impl Container for BeaconBlockHeader {
+    fn field_names(&self) -> &'static [&'static str] {
+        &[
+            "slot",
+            "proposer_index",
+            "parent_root",
+            "state_root",
+            "body_root",
+        ]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.slot.tree_hash_root(),
+            self.proposer_index.tree_hash_root(),
+            self.parent_root.tree_hash_root(),
+            self.state_root.tree_hash_root(),
+            self.body_root.tree_hash_root(),
+        ]
+    }
+}
+
+impl Container for Attestation {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["aggregation_bits", "data", "signature", "committee_bits"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.aggregation_bits.tree_hash_root(),
+            self.data.tree_hash_root(),
+            self.signature.tree_hash_root(),
+            self.committee_bits.tree_hash_root(),
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Container> {
+        match name {
+            "data" => Some(&self.data),
+            _ => None,
+        }
+    }
+}
+
+impl Container for AttestationData {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["slot", "index", "beacon_block_root", "source", "target"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.slot.tree_hash_root(),
+            self.index.tree_hash_root(),
+            self.beacon_block_root.tree_hash_root(),
+            self.source.tree_hash_root(),
+            self.target.tree_hash_root(),
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Container> {
+        match name {
+            "source" => Some(&self.source),
+            "target" => Some(&self.target),
+            _ => None,
+        }
+    }
+}
+
+impl Container for Checkpoint {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["epoch", "root"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![self.epoch.tree_hash_root(), self.root.tree_hash_root()]
+    }
+}
+
+impl Container for Fork {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["previous_version", "current_version", "epoch"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.previous_version.tree_hash_root(),
+            self.current_version.tree_hash_root(),
+            self.epoch.tree_hash_root(),
+        ]
+    }
+}
+
+impl Container for Eth1Data {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["deposit_root", "deposit_count", "block_hash"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.deposit_root.tree_hash_root(),
+            self.deposit_count.tree_hash_root(),
+            self.block_hash.tree_hash_root(),
+        ]
+    }
+}
+
+impl Container for SyncCommittee {
+    fn field_names(&self) -> &'static [&'static str] {
+        &["pubkeys", "aggregate_pubkey"]
+    }
+
+    fn field_roots(&self) -> Vec<B256> {
+        vec![
+            self.public_keys.tree_hash_root(),
+            self.aggregate_public_key.tree_hash_root(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_misc::{
+        constants::beacon::EXECUTION_PAYLOAD_GINDEX,
+        slot_epoch::{Epoch, Slot},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_hash_tree_root_matches_tree_hash() {
+        let data = AttestationData {
+            slot: Slot::new(12),
+            index: 3,
+            beacon_block_root: B256::repeat_byte(1),
+            source: Checkpoint {
+                epoch: Epoch::new(1),
+                root: B256::repeat_byte(2),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(2),
+                root: B256::repeat_byte(3),
+            },
+        };
+        assert_eq!(hash_tree_root(&data).unwrap(), data.tree_hash_root());
+
+        let body = BeaconBlockBody::default();
+        assert_eq!(hash_tree_root(&body).unwrap(), body.tree_hash_root());
+    }
+
+    #[test]
+    fn test_prove_nested_field() {
+        let data = AttestationData {
+            slot: Slot::new(224),
+            index: 0,
+            beacon_block_root: B256::repeat_byte(1),
+            source: Checkpoint::default(),
+            target: Checkpoint {
+                epoch: Epoch::new(7),
+                root: B256::repeat_byte(4),
+            },
+        };
+
+        // The target is the 5th of 8 leaves and the root the 2nd of 2
+        let proof = prove_field(&data, "target.root").unwrap();
+        assert_eq!(proof.generalized_index, 0b1100 * 2 + 1);
+        assert_eq!(proof.leaf, B256::repeat_byte(4));
+        assert_eq!(proof.branch.len(), 4);
+
+        let body = BeaconBlockBody::default();
+        assert_eq!(
+            prove_field(&body, "execution_payload")
+                .unwrap()
+                .generalized_index,
+            EXECUTION_PAYLOAD_GINDEX
+        );
+    }
+
+    #[test]
+    fn test_prove_unknown_field() {
+        let checkpoint = Checkpoint::default();
+        assert!(prove_field(&checkpoint, "slot").is_err());
+        assert!(prove_field(&checkpoint, "root.epoch").is_err());
+    }
+}
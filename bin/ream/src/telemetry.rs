@@ -0,0 +1,62 @@
+use std::env;
+
+use anyhow::anyhow;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+use tracing::Level;
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, filter::Targets, fmt, layer::SubscriberExt, reload,
+    util::SubscriberInitExt,
+};
+use url::Url;
+
+/// The spans of the block processing pipeline are emitted at the debug level of the ream crates,
+/// so that they are exported without appearing in the logs.
+const TRACING_TARGET: &str = "ream";
+
+/// Initializes logging, and exports the spans of the ream crates over OTLP/HTTP to
+/// `tracing_endpoint` if it's set.
+///
+/// The returned provider has to be shut down before exiting, to flush the spans which are yet to
+/// be exported. The returned handle replaces the log filter at runtime.
+pub fn init_tracing(
+    service_name: &'static str,
+    tracing_endpoint: Option<&Url>,
+) -> anyhow::Result<(
+    Option<SdkTracerProvider>,
+    reload::Handle<EnvFilter, Registry>,
+)> {
+    // Set the default log level to `info` if not set
+    let rust_log = env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
+    let env_filter = match rust_log.is_empty() {
+        true => EnvFilter::builder().parse_lossy("info,actix_server=warn,discv5=error"),
+        false => EnvFilter::builder().parse_lossy(rust_log),
+    };
+    let (env_filter, log_filter_handle) = reload::Layer::new(env_filter);
+    let fmt_layer = fmt::layer().with_filter(env_filter);
+
+    let Some(tracing_endpoint) = tracing_endpoint else {
+        tracing_subscriber::registry().with(fmt_layer).init();
+        return Ok((None, log_filter_handle));
+    };
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(tracing_endpoint.as_str())
+        .build()
+        .map_err(|err| anyhow!("Failed to build the OTLP span exporter: {err:?}"))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build();
+    let tracing_layer = tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer(service_name))
+        .with_filter(Targets::new().with_target(TRACING_TARGET, Level::DEBUG));
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(tracing_layer)
+        .init();
+    Ok((Some(provider), log_filter_handle))
+}
@@ -1,15 +1,19 @@
 use ream_consensus_beacon::{
-    electra::{beacon_block::BeaconBlock, blinded_beacon_block::BlindedBeaconBlock},
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        blinded_beacon_block::BlindedBeaconBlock,
+    },
     execution_engine::rpc_types::get_blobs::Blob,
     polynomial_commitments::kzg_proof::KZGProof,
 };
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BroadcastValidation {
     /// Lightweight gossip checks only (default)
+    #[default]
     Gossip,
     /// Full consensus checks, including validation of all signatures and block fields
     /// except for the execution payload transactions
@@ -18,12 +22,6 @@ pub enum BroadcastValidation {
     ConsensusAndEquivocation,
 }
 
-impl Default for BroadcastValidation {
-    fn default() -> Self {
-        Self::Gossip
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProduceBlockResponse {
     pub version: String,
@@ -48,3 +46,12 @@ pub struct FullBlockData {
     pub kzg_proofs: Vec<KZGProof>,
     pub blobs: Vec<Blob>,
 }
+
+/// A signed block with the blobs which its commitments commit to and their KZG proofs, as
+/// published to `/eth/v2/beacon/blocks`.
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct SignedBlockContents {
+    pub signed_block: SignedBeaconBlock,
+    pub kzg_proofs: Vec<KZGProof>,
+    pub blobs: Vec<Blob>,
+}
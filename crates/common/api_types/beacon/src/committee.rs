@@ -1,3 +1,4 @@
+use ream_consensus_misc::slot_epoch::Slot;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -8,7 +9,16 @@ pub struct BeaconCommitteeSubscription {
     pub committee_index: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     pub committees_at_slot: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
     pub is_aggregator: bool,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncCommitteeSubscription {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub sync_committee_indices: Vec<u64>,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub until_epoch: u64,
+}
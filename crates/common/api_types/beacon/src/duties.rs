@@ -1,18 +1,18 @@
 use ream_bls::PublicKey;
+use ream_consensus_misc::slot_epoch::Slot;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 
-#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+#[derive(Debug, Clone, Deserialize, Serialize, Encode, Decode)]
 pub struct ProposerDuty {
     #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
     #[serde(with = "serde_utils::quoted_u64")]
     pub validator_index: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
 }
 
-#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+#[derive(Debug, Clone, Deserialize, Serialize, Encode, Decode)]
 pub struct AttesterDuty {
     #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
@@ -21,18 +21,20 @@ pub struct AttesterDuty {
     #[serde(with = "serde_utils::quoted_u64")]
     pub committee_index: u64,
     #[serde(with = "serde_utils::quoted_u64")]
+    pub committee_length: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
     pub committees_at_slot: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     pub validator_committee_index: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
 }
 
-#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+#[derive(Debug, Clone, Deserialize, Serialize, Encode, Decode)]
 pub struct SyncCommitteeDuty {
     #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
     #[serde(with = "serde_utils::quoted_u64")]
     pub validator_index: u64,
+    #[serde(with = "serde_utils::quoted_u64_vec")]
     pub validator_sync_committee_indices: Vec<u64>,
 }
@@ -2,11 +2,17 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ValidatorError {
-    #[error("Request failed with status code: {status_code}")]
-    RequestFailed { status_code: reqwest::StatusCode },
+    #[error("Request to {endpoint} failed with status code: {status_code}, body: {body}")]
+    RequestFailed {
+        endpoint: String,
+        status_code: reqwest::StatusCode,
+        /// The start of the response body
+        body: String,
+    },
 
-    #[error("Request failed with status code: {status_code}, message: {message}")]
+    #[error("Request to {endpoint} failed with status code: {status_code}, message: {message}")]
     RequestFailedWithMessage {
+        endpoint: String,
         status_code: reqwest::StatusCode,
         message: String,
     },
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use alloy_primitives::hex;
+use anyhow::anyhow;
 use ream_bls::PublicKey;
 use serde::{Deserialize, Serialize};
 
@@ -25,19 +26,26 @@ impl<'de> Deserialize<'de> for ValidatorID {
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for ValidatorID {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.starts_with("0x") {
-            PublicKey::from_str(&s)
+            PublicKey::from_str(s)
                 .map(ValidatorID::Address)
-                .map_err(|_| serde::de::Error::custom(format!("Invalid hex address: {s}")))
+                .map_err(|_| anyhow!("Invalid hex address: {s}"))
         } else if s.chars().all(|c| c.is_ascii_digit()) {
             s.parse::<u64>()
                 .map(ValidatorID::Index)
-                .map_err(|_| serde::de::Error::custom(format!("Invalid validator index: {s}")))
+                .map_err(|_| anyhow!("Invalid validator index: {s}"))
         } else {
-            Err(serde::de::Error::custom(format!(
-                "Invalid validator ID: {s}"
-            )))
+            Err(anyhow!("Invalid validator ID: {s}"))
         }
     }
 }
@@ -1,8 +1,9 @@
 use alloy_primitives::B256;
+use ream_bls::BLSSignature;
 use serde::{Deserialize, Serialize};
 
 use super::id::ValidatorID;
-use crate::validator::ValidatorStatus;
+use crate::{block::BroadcastValidation, validator::ValidatorStatus};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EpochQuery {
@@ -50,6 +51,12 @@ pub struct AttestationQuery {
     pub committee_index: u64,
 }
 
+#[derive(Default, Debug, Deserialize)]
+pub struct PoolAttestationQuery {
+    pub slot: Option<u64>,
+    pub committee_index: Option<u64>,
+}
+
 impl StatusQuery {
     pub fn has_status(&self) -> bool {
         match &self.status {
@@ -65,3 +72,20 @@ impl StatusQuery {
         }
     }
 }
+
+#[derive(Default, Debug, Deserialize)]
+pub struct BroadcastValidationQuery {
+    #[serde(default)]
+    pub broadcast_validation: BroadcastValidation,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProduceBlockQuery {
+    pub randao_reveal: BLSSignature,
+    pub graffiti: Option<B256>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct HealthQuery {
+    pub syncing_status: Option<u16>,
+}
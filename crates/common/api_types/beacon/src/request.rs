@@ -1,6 +1,8 @@
 use alloy_primitives::{Address, B256};
 use ream_bls::BLSSignature;
+use ream_consensus_misc::slot_epoch::Slot;
 use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
 
 use crate::{id::ValidatorID, validator::ValidatorStatus};
 
@@ -17,10 +19,9 @@ pub struct PrepareBeaconProposerItem {
     pub fee_recipient: Address,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
 pub struct SyncCommitteeRequestItem {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
     pub beacon_block_root: B256,
     #[serde(with = "serde_utils::quoted_u64")]
     pub validator_index: u64,
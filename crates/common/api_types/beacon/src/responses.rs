@@ -1,5 +1,8 @@
 use alloy_primitives::B256;
-use ream_consensus_misc::checkpoint::Checkpoint;
+use ream_consensus_misc::{
+    checkpoint::Checkpoint,
+    slot_epoch::{Epoch, Slot},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use ssz::{Decode, Encode};
@@ -178,12 +181,12 @@ impl<T: Serialize + Encode + Decode> SyncCommitteeDutiesResponse<T> {
 #[derive(Debug, Serialize)]
 pub struct BeaconHeadResponse {
     pub root: B256,
-    pub slot: u64,
+    pub slot: Slot,
     pub execution_optimistic: bool,
 }
 
 impl BeaconHeadResponse {
-    pub fn new(root: B256, slot: u64) -> Self {
+    pub fn new(root: B256, slot: Slot) -> Self {
         Self {
             root,
             slot,
@@ -247,14 +250,11 @@ impl ForkChoiceResponse {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ForkChoiceNode {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
     pub block_root: B256,
     pub parent_root: B256,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub justified_epoch: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub finalized_epoch: u64,
+    pub justified_epoch: Epoch,
+    pub finalized_epoch: Epoch,
     #[serde(with = "serde_utils::quoted_u64")]
     pub weight: u64,
     pub validity: ForkChoiceValidity,
@@ -41,7 +41,7 @@ impl ValidatorData {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ValidatorBalance {
     #[serde(with = "serde_utils::quoted_u64")]
     pub index: u64,
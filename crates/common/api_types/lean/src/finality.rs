@@ -0,0 +1,9 @@
+use ream_consensus_lean::checkpoint::Checkpoint;
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+
+#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+pub struct Finality {
+    pub justified: Checkpoint,
+    pub finalized: Checkpoint,
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use ream_consensus_lean::checkpoint::Checkpoint;
+use serde::{Deserialize, Serialize};
+
+/// A ForkChoiceResponse data struct that is used for /debug/fork_choice endpoint.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "head": "0xcf8e0d4e9587369b2301d0790347320302cc0943d5a1884560367e8208d920f2",
+///   "safe_target": "0xcf8e0d4e9587369b2301d0790347320302cc0943d5a1884560367e8208d920f2",
+///   "justified": {
+///     "root": "0x8a1d9e0a6f2a2b1b7f4b2b0c86b4e1f6b3f5d7c4f1f0d1c2b3a495867a8b9c0d",
+///     "slot": 4
+///   },
+///   "finalized": {
+///     "root": "0x8a1d9e0a6f2a2b1b7f4b2b0c86b4e1f6b3f5d7c4f1f0d1c2b3a495867a8b9c0d",
+///     "slot": 4
+///   },
+///   "fork_choice_nodes": [
+///     {
+///       "slot": 5,
+///       "block_root": "0xcf8e0d4e9587369b2301d0790347320302cc0943d5a1884560367e8208d920f2",
+///       "parent_root": "0x8a1d9e0a6f2a2b1b7f4b2b0c86b4e1f6b3f5d7c4f1f0d1c2b3a495867a8b9c0d",
+///       "proposer_index": 1,
+///       "weight": 3
+///     }
+///   ],
+///   "children": {
+///     "0x8a1d9e0a6f2a2b1b7f4b2b0c86b4e1f6b3f5d7c4f1f0d1c2b3a495867a8b9c0d": [
+///       "0xcf8e0d4e9587369b2301d0790347320302cc0943d5a1884560367e8208d920f2"
+///     ]
+///   }
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForkChoiceResponse {
+    pub head: B256,
+    pub safe_target: B256,
+    pub justified: Checkpoint,
+    pub finalized: Checkpoint,
+    pub fork_choice_nodes: Vec<ForkChoiceNode>,
+    pub children: HashMap<B256, Vec<B256>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForkChoiceNode {
+    pub slot: u64,
+    pub block_root: B256,
+    pub parent_root: B256,
+    pub proposer_index: u64,
+    pub weight: u64,
+}
@@ -1 +1,5 @@
+pub mod finality;
+pub mod fork_choice;
 pub mod head;
+pub mod proof;
+pub mod vote;
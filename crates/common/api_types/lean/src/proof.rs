@@ -0,0 +1,11 @@
+use alloy_primitives::{B256, Bytes};
+use serde::{Deserialize, Serialize};
+
+/// A proof of a lean block's state transition, with the roots its zkVM program commits to.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Proof {
+    pub block_root: B256,
+    pub pre_state_root: B256,
+    pub post_state_root: B256,
+    pub proof: Bytes,
+}
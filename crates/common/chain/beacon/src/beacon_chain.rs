@@ -1,16 +1,24 @@
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
 
+use alloy_primitives::B256;
 use anyhow::bail;
+use lru::LruCache;
 use ream_consensus_beacon::{
-    attestation::Attestation, attester_slashing::AttesterSlashing,
+    attestation::Attestation, attester_slashing::AttesterSlashing, blob_sidecar::BlobIdentifier,
     electra::beacon_block::SignedBeaconBlock,
 };
-use ream_consensus_misc::constants::beacon::genesis_validators_root;
+use ream_consensus_misc::{
+    constants::beacon::genesis_validators_root, misc::compute_epoch_at_slot,
+};
 use ream_execution_engine::ExecutionEngine;
 use ream_fork_choice::{
     handlers::{on_attestation, on_attester_slashing, on_block, on_tick},
     store::Store,
 };
+use ream_metrics::{
+    BEACON_FINALIZED_EPOCH, BEACON_HEAD_EPOCH, BEACON_JUSTIFIED_EPOCH, BLOCK_IMPORT_TIME,
+    set_int_gauge_vec, start_timer_vec, stop_timer,
+};
 use ream_network_spec::networks::beacon_network_spec;
 use ream_operation_pool::OperationPool;
 use ream_p2p::req_resp::beacon::messages::status::Status;
@@ -19,12 +27,22 @@ use ream_storage::{
     tables::{field::Field, table::Table},
 };
 use tokio::sync::Mutex;
-use tracing::warn;
+use tracing::{debug, info, instrument, warn};
+
+use crate::{orphan_blocks::OrphanBlocks, validator_monitor::ValidatorMonitor};
+
+/// Maximum number of blocks kept while waiting for their blobs to arrive.
+const BLOCKS_AWAITING_BLOBS_CACHE_SIZE: usize = 64;
 
 /// BeaconChain is the main struct which manages the nodes local beacon chain.
 pub struct BeaconChain {
     pub store: Mutex<Store>,
     pub execution_engine: Option<ExecutionEngine>,
+    /// Valid blocks whose blobs haven't all been received yet, keyed by block root.
+    blocks_awaiting_blobs: Mutex<LruCache<B256, SignedBeaconBlock>>,
+    /// Blocks which arrived before their parents, imported once their parents are.
+    orphan_blocks: Mutex<OrphanBlocks>,
+    pub validator_monitor: Mutex<ValidatorMonitor>,
 }
 
 impl BeaconChain {
@@ -37,21 +55,231 @@ impl BeaconChain {
         Self {
             store: Mutex::new(Store::new(db, operation_pool)),
             execution_engine,
+            blocks_awaiting_blobs: Mutex::new(LruCache::new(
+                NonZeroUsize::new(BLOCKS_AWAITING_BLOBS_CACHE_SIZE).expect("Invalid cache size"),
+            )),
+            orphan_blocks: Mutex::new(OrphanBlocks::default()),
+            validator_monitor: Mutex::new(ValidatorMonitor::default()),
         }
     }
 
+    /// Tracks the performance of the validators of `validator_monitor` in the imported blocks.
+    pub fn with_validator_monitor(mut self, validator_monitor: ValidatorMonitor) -> Self {
+        self.validator_monitor = Mutex::new(validator_monitor);
+        self
+    }
+
+    /// Imports the block, and then the orphan blocks which descend from it.
     pub async fn process_block(&self, signed_block: SignedBeaconBlock) -> anyhow::Result<()> {
+        let block_root = signed_block.message.block_root();
+        self.import_block(signed_block, true, false).await?;
+        self.process_orphan_blocks(block_root).await
+    }
+
+    /// Imports a block whose data columns have been sampled, and then the orphan blocks which
+    /// descend from it. Sampling shows the block's data is available, so it isn't checked against
+    /// the stored blobs.
+    pub async fn process_sampled_block(
+        &self,
+        signed_block: SignedBeaconBlock,
+    ) -> anyhow::Result<()> {
+        let block_root = signed_block.message.block_root();
+        self.import_block(signed_block, true, true).await?;
+        self.process_orphan_blocks(block_root).await
+    }
+
+    /// Imports a segment of blocks, each building on the one before it, in order. The proposer
+    /// signatures of the segment are verified together against the state of the parent of its
+    /// first block, and one by one on import if that batch can't be verified.
+    pub async fn process_block_segment(
+        &self,
+        signed_blocks: Vec<SignedBeaconBlock>,
+    ) -> anyhow::Result<()> {
+        let Some(first_block) = signed_blocks.first() else {
+            return Ok(());
+        };
+        let parent_state = self
+            .store
+            .lock()
+            .await
+            .db
+            .beacon_state_provider()
+            .get(first_block.message.parent_root)?;
+        let signatures_verified = match parent_state {
+            Some(parent_state) => parent_state
+                .verify_block_signatures_batch(&signed_blocks)
+                .unwrap_or_else(|err| {
+                    debug!("Failed to verify the signatures of a segment: {err:?}");
+                    false
+                }),
+            None => false,
+        };
+        if !signatures_verified {
+            debug!(
+                "Verifying the signatures of a segment of {} blocks one by one",
+                signed_blocks.len()
+            );
+        }
+
+        for signed_block in signed_blocks {
+            let block_root = signed_block.message.block_root();
+            self.import_block(signed_block, !signatures_verified, false)
+                .await?;
+            self.process_orphan_blocks(block_root).await?;
+        }
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, fields(slot = signed_block.message.slot.as_u64()))]
+    async fn import_block(
+        &self,
+        signed_block: SignedBeaconBlock,
+        verify_signature: bool,
+        data_sampled: bool,
+    ) -> anyhow::Result<()> {
         let mut store = self.store.lock().await;
+        let timer = start_timer_vec(&BLOCK_IMPORT_TIME, &[]);
         on_block(
             &mut store,
             &signed_block,
             &self.execution_engine,
-            signed_block.message.slot >= beacon_network_spec().slot_n_days_ago(17),
+            !data_sampled && signed_block.message.slot >= beacon_network_spec().slot_n_days_ago(17),
+            verify_signature,
         )
         .await?;
+        stop_timer(timer);
+
+        if let Err(err) = update_head(&store) {
+            warn!("Failed to update the head: {err:?}");
+        }
+
+        let mut validator_monitor = self.validator_monitor.lock().await;
+        if validator_monitor.is_enabled() {
+            let block_root = signed_block.message.block_root();
+            match store.db.beacon_state_provider().get(block_root) {
+                Ok(Some(state)) => {
+                    if let Err(err) = validator_monitor.process_block(&signed_block.message, &state)
+                    {
+                        warn!("Validator monitor failed to process block {block_root}: {err:?}");
+                    }
+                }
+                Ok(None) => warn!("Validator monitor found no post state for block {block_root}"),
+                Err(err) => {
+                    warn!("Validator monitor failed to read the state of {block_root}: {err:?}")
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Imports the block if all of its blobs have been received, otherwise queues it until the
+    /// missing blob sidecars arrive. Blocks outside of the blob retention window are imported
+    /// directly.
+    pub async fn process_block_when_available(
+        &self,
+        signed_block: SignedBeaconBlock,
+    ) -> anyhow::Result<()> {
+        if signed_block.message.slot < beacon_network_spec().slot_n_days_ago(17)
+            || self.has_all_blobs(&signed_block).await?
+        {
+            return self.process_block(signed_block).await;
+        }
+
+        let block_root = signed_block.message.block_root();
+        info!("Block {block_root} is waiting for its blobs before being imported");
+        self.blocks_awaiting_blobs
+            .lock()
+            .await
+            .put(block_root, signed_block);
+        Ok(())
+    }
+
+    /// Keeps a block whose parent hasn't been imported yet, until the parent is imported.
+    pub async fn add_orphan_block(&self, signed_block: SignedBeaconBlock) -> anyhow::Result<()> {
+        let parent_root = signed_block.message.parent_root;
+        {
+            let mut orphan_blocks = self.orphan_blocks.lock().await;
+            orphan_blocks.insert(signed_block, beacon_network_spec().current_slot());
+            debug!(
+                "{} orphan blocks are waiting for their parents",
+                orphan_blocks.len()
+            );
+        }
+
+        // The parent may have been imported since the block was found to be an orphan
+        let parent_imported = self
+            .store
+            .lock()
+            .await
+            .db
+            .beacon_block_provider()
+            .get(parent_root)?
+            .is_some();
+        if parent_imported {
+            self.process_orphan_blocks(parent_root).await?;
+        }
+        Ok(())
+    }
+
+    /// Imports the orphan blocks waiting on the block with `block_root`, and then the ones waiting
+    /// on those. Blocks missing blobs wait for them like any other block.
+    async fn process_orphan_blocks(&self, block_root: B256) -> anyhow::Result<()> {
+        let mut parent_roots = vec![block_root];
+        while let Some(parent_root) = parent_roots.pop() {
+            let children = self.orphan_blocks.lock().await.take_children(parent_root);
+            for signed_block in children {
+                let block_root = signed_block.message.block_root();
+                if signed_block.message.slot >= beacon_network_spec().slot_n_days_ago(17)
+                    && !self.has_all_blobs(&signed_block).await?
+                {
+                    info!(
+                        "Orphan block {block_root} is waiting for its blobs before being imported"
+                    );
+                    self.blocks_awaiting_blobs
+                        .lock()
+                        .await
+                        .put(block_root, signed_block);
+                    continue;
+                }
+
+                match self.import_block(signed_block, true, false).await {
+                    Ok(()) => {
+                        info!("Imported orphan block {block_root}");
+                        parent_roots.push(block_root);
+                    }
+                    Err(err) => warn!("Failed to import orphan block {block_root}: {err:?}"),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Called after a blob sidecar has been stored. Imports the block it belongs to if the block
+    /// was waiting on its blobs and all of them are now available.
+    pub async fn on_blob_sidecar_stored(&self, block_root: B256) -> anyhow::Result<()> {
+        let Some(signed_block) = self.blocks_awaiting_blobs.lock().await.pop(&block_root) else {
+            return Ok(());
+        };
+        self.process_block_when_available(signed_block).await
+    }
+
+    /// Whether every blob committed to by the block has been received and stored.
+    async fn has_all_blobs(&self, signed_block: &SignedBeaconBlock) -> anyhow::Result<bool> {
+        let block_root = signed_block.message.block_root();
+        let store = self.store.lock().await;
+        for index in 0..signed_block.message.body.blob_kzg_commitments.len() as u64 {
+            if store
+                .db
+                .blobs_and_proofs_provider()
+                .get(BlobIdentifier::new(block_root, index))?
+                .is_none()
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     pub async fn process_attester_slashing(
         &self,
         attester_slashing: AttesterSlashing,
@@ -105,18 +333,64 @@ impl BeaconChain {
             .beacon_block_provider()
             .get(head_root)
         {
-            Ok(Some(block)) => block.message.slot,
+            Ok(Some(block)) => block.message.slot.as_u64(),
             err => {
                 bail!("Failed to get block for head root {head_root}: {err:?}");
             }
         };
 
+        // We can only serve blocks from the oldest one stored, e.g. the checkpoint sync anchor
+        let earliest_available_slot = self
+            .store
+            .lock()
+            .await
+            .db
+            .slot_index_provider()
+            .get_oldest_slot()?
+            .unwrap_or(head_slot);
+
         Ok(Status {
             fork_digest: beacon_network_spec().fork_digest(genesis_validators_root()),
             finalized_root: finalized_checkpoint.root,
-            finalized_epoch: finalized_checkpoint.epoch,
+            finalized_epoch: finalized_checkpoint.epoch.as_u64(),
             head_root,
             head_slot,
+            earliest_available_slot,
         })
     }
 }
+
+/// Records the head chosen by fork choice, which the canonical chain is read back from, and sets
+/// the head, justified and finalized epoch gauges from the fork choice store.
+fn update_head(store: &Store) -> anyhow::Result<()> {
+    let head = store.get_head()?;
+    store.db.head_root_provider().insert(head)?;
+    if let Some(head_block) = store.db.beacon_block_provider().get(head)? {
+        set_int_gauge_vec(
+            &BEACON_HEAD_EPOCH,
+            compute_epoch_at_slot(head_block.message.slot.as_u64()) as i64,
+            &[],
+        );
+    }
+    set_int_gauge_vec(
+        &BEACON_JUSTIFIED_EPOCH,
+        store
+            .db
+            .justified_checkpoint_provider()
+            .get()?
+            .epoch
+            .as_u64() as i64,
+        &[],
+    );
+    set_int_gauge_vec(
+        &BEACON_FINALIZED_EPOCH,
+        store
+            .db
+            .finalized_checkpoint_provider()
+            .get()?
+            .epoch
+            .as_u64() as i64,
+        &[],
+    );
+    Ok(())
+}
@@ -1 +1,3 @@
 pub mod beacon_chain;
+pub mod orphan_blocks;
+pub mod validator_monitor;
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use ream_consensus_beacon::electra::beacon_block::SignedBeaconBlock;
+use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
+
+/// Maximum number of blocks kept while waiting for their parents to be imported.
+const MAX_ORPHAN_BLOCKS: usize = 64;
+/// Blocks whose parents weren't imported within this many slots are dropped.
+const ORPHAN_BLOCK_EXPIRY_SLOTS: u64 = SLOTS_PER_EPOCH;
+
+/// Blocks which arrived before their parents, keyed by the root of the parent they wait on, so
+/// they can be imported once the parent is.
+#[derive(Debug, Default)]
+pub struct OrphanBlocks {
+    blocks_by_parent: HashMap<B256, HashMap<B256, SignedBeaconBlock>>,
+    len: usize,
+}
+
+impl OrphanBlocks {
+    /// Adds the block and drops the expired ones. When the pool is full, the block of the lowest
+    /// slot is dropped, which may be the added block itself.
+    pub fn insert(&mut self, signed_block: SignedBeaconBlock, current_slot: u64) {
+        self.prune(current_slot);
+        if signed_block.message.slot + ORPHAN_BLOCK_EXPIRY_SLOTS < current_slot {
+            return;
+        }
+
+        let block_root = signed_block.message.block_root();
+        if self
+            .blocks_by_parent
+            .entry(signed_block.message.parent_root)
+            .or_default()
+            .insert(block_root, signed_block)
+            .is_none()
+        {
+            self.len += 1;
+        }
+
+        if self.len > MAX_ORPHAN_BLOCKS
+            && let Some((parent_root, block_root)) = self.lowest_slot_block()
+        {
+            self.remove(parent_root, block_root);
+        }
+    }
+
+    /// Removes and returns the blocks waiting on the block with `parent_root`.
+    pub fn take_children(&mut self, parent_root: B256) -> Vec<SignedBeaconBlock> {
+        let children = self
+            .blocks_by_parent
+            .remove(&parent_root)
+            .unwrap_or_default();
+        self.len -= children.len();
+        children.into_values().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn prune(&mut self, current_slot: u64) {
+        for children in self.blocks_by_parent.values_mut() {
+            children
+                .retain(|_, block| block.message.slot + ORPHAN_BLOCK_EXPIRY_SLOTS >= current_slot);
+        }
+        self.blocks_by_parent
+            .retain(|_, children| !children.is_empty());
+        self.len = self.blocks_by_parent.values().map(HashMap::len).sum();
+    }
+
+    fn lowest_slot_block(&self) -> Option<(B256, B256)> {
+        self.blocks_by_parent
+            .iter()
+            .flat_map(|(parent_root, children)| {
+                children
+                    .iter()
+                    .map(move |(block_root, block)| (block.message.slot, *parent_root, *block_root))
+            })
+            .min()
+            .map(|(_, parent_root, block_root)| (parent_root, block_root))
+    }
+
+    fn remove(&mut self, parent_root: B256, block_root: B256) {
+        if let Some(children) = self.blocks_by_parent.get_mut(&parent_root)
+            && children.remove(&block_root).is_some()
+        {
+            self.len -= 1;
+            if children.is_empty() {
+                self.blocks_by_parent.remove(&parent_root);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_beacon::electra::beacon_block::BeaconBlock;
+    use ream_consensus_misc::slot_epoch::Slot;
+
+    use super::*;
+
+    fn block(slot: u64, parent_root: B256) -> SignedBeaconBlock {
+        SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: Slot::new(slot),
+                parent_root,
+                ..Default::default()
+            },
+            signature: Default::default(),
+        }
+    }
+
+    fn slots(blocks: Vec<SignedBeaconBlock>) -> Vec<u64> {
+        let mut slots = blocks
+            .into_iter()
+            .map(|block| block.message.slot.as_u64())
+            .collect::<Vec<_>>();
+        slots.sort();
+        slots
+    }
+
+    #[test]
+    fn test_take_children_returns_only_direct_children() {
+        let mut orphan_blocks = OrphanBlocks::default();
+        let (parent_root, other_root) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        let child = block(10, parent_root);
+        let child_root = child.message.block_root();
+        orphan_blocks.insert(child, 10);
+        orphan_blocks.insert(block(11, parent_root), 11);
+        orphan_blocks.insert(block(12, child_root), 12);
+        orphan_blocks.insert(block(13, other_root), 13);
+        // Inserting the same block again doesn't count it twice
+        orphan_blocks.insert(block(13, other_root), 13);
+        assert_eq!(orphan_blocks.len(), 4);
+
+        assert_eq!(
+            slots(orphan_blocks.take_children(parent_root)),
+            vec![10, 11]
+        );
+        assert_eq!(orphan_blocks.len(), 2);
+        assert!(orphan_blocks.take_children(parent_root).is_empty());
+
+        assert_eq!(slots(orphan_blocks.take_children(child_root)), vec![12]);
+        assert_eq!(slots(orphan_blocks.take_children(other_root)), vec![13]);
+        assert!(orphan_blocks.is_empty());
+    }
+
+    #[test]
+    fn test_pool_is_bounded_by_evicting_the_lowest_slot() {
+        let mut orphan_blocks = OrphanBlocks::default();
+        let parent_root = B256::repeat_byte(1);
+        for slot in 100..100 + MAX_ORPHAN_BLOCKS as u64 {
+            orphan_blocks.insert(block(slot, parent_root), 100);
+        }
+        assert_eq!(orphan_blocks.len(), MAX_ORPHAN_BLOCKS);
+
+        // A block of a higher slot evicts the block of the lowest slot
+        orphan_blocks.insert(block(500, B256::repeat_byte(2)), 100);
+        assert_eq!(orphan_blocks.len(), MAX_ORPHAN_BLOCKS);
+        // A block of a lower slot than all of them is evicted itself
+        orphan_blocks.insert(block(99, parent_root), 100);
+        assert_eq!(orphan_blocks.len(), MAX_ORPHAN_BLOCKS);
+
+        let children_slots = slots(orphan_blocks.take_children(parent_root));
+        assert_eq!(children_slots.len(), MAX_ORPHAN_BLOCKS - 1);
+        assert_eq!(children_slots.first(), Some(&101));
+        assert_eq!(
+            slots(orphan_blocks.take_children(B256::repeat_byte(2))),
+            vec![500]
+        );
+    }
+
+    #[test]
+    fn test_blocks_expire_after_an_epoch() {
+        let mut orphan_blocks = OrphanBlocks::default();
+        let parent_root = B256::repeat_byte(1);
+        orphan_blocks.insert(block(10, parent_root), 10);
+        orphan_blocks.insert(block(20, parent_root), 20);
+
+        // Blocks are kept up to SLOTS_PER_EPOCH slots after their slot
+        orphan_blocks.insert(block(30, B256::repeat_byte(2)), 10 + SLOTS_PER_EPOCH);
+        assert_eq!(orphan_blocks.len(), 3);
+
+        orphan_blocks.insert(block(30, B256::repeat_byte(3)), 11 + SLOTS_PER_EPOCH);
+        assert_eq!(slots(orphan_blocks.take_children(parent_root)), vec![20]);
+
+        // Blocks which are already expired aren't added
+        orphan_blocks.insert(block(10, parent_root), 11 + SLOTS_PER_EPOCH);
+        assert!(orphan_blocks.take_children(parent_root).is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::anyhow;
+use ream_api_types_beacon::id::ValidatorID;
+use ream_bls::PublicKey;
+use ream_consensus_beacon::electra::{beacon_block::BeaconBlock, beacon_state::BeaconState};
+use ream_consensus_misc::misc::compute_epoch_at_slot;
+use ream_metrics::{
+    VALIDATOR_MONITOR_ATTESTATIONS, VALIDATOR_MONITOR_BLOCKS_PROPOSED,
+    VALIDATOR_MONITOR_INCLUSION_DISTANCE, VALIDATOR_MONITOR_SYNC_COMMITTEE, inc_int_counter_vec,
+    set_int_gauge_vec,
+};
+use serde::{Deserialize, Serialize};
+
+/// The performance of a monitored validator, as seen in the blocks imported since the node
+/// started.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorSummary {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attestations_included: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attestations_missed: u64,
+    /// The sum of the inclusion distances of the included attestations, in slots
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total_inclusion_distance: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub blocks_proposed: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub sync_committee_included: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub sync_committee_missed: u64,
+}
+
+#[derive(Debug)]
+struct MonitoredValidator {
+    public_key: Option<PublicKey>,
+    summary: ValidatorSummary,
+    /// The target epochs of the attestations included so far, which are dropped once the
+    /// inclusion window of the epoch has passed
+    included_epochs: BTreeSet<u64>,
+}
+
+impl MonitoredValidator {
+    fn new(index: u64, public_key: Option<PublicKey>) -> Self {
+        Self {
+            public_key,
+            summary: ValidatorSummary {
+                index,
+                ..Default::default()
+            },
+            included_epochs: BTreeSet::new(),
+        }
+    }
+
+    fn label(&self) -> String {
+        self.summary.index.to_string()
+    }
+}
+
+/// ValidatorMonitor tracks the performance of the validators given with `--monitor-validators`
+/// from the imported blocks: the inclusion of their attestations, their proposals and their sync
+/// committee participation.
+///
+/// An attestation is only counted as missed once the inclusion window of its target epoch has
+/// passed, i.e. when a block two epochs later is imported. Epochs before the first imported block
+/// are not checked, as their blocks haven't been seen.
+#[derive(Debug, Default)]
+pub struct ValidatorMonitor {
+    validators: BTreeMap<u64, MonitoredValidator>,
+    /// Validators given by public key which aren't in the state yet
+    pending_public_keys: Vec<PublicKey>,
+    /// The epoch of the last imported block
+    last_epoch: Option<u64>,
+    /// The next epoch to check for missed attestations
+    next_epoch_to_check: Option<u64>,
+}
+
+impl ValidatorMonitor {
+    pub fn new(validator_ids: Vec<ValidatorID>) -> Self {
+        let mut validator_monitor = Self::default();
+        for validator_id in validator_ids {
+            match validator_id {
+                ValidatorID::Index(index) => {
+                    validator_monitor
+                        .validators
+                        .insert(index, MonitoredValidator::new(index, None));
+                }
+                ValidatorID::Address(public_key) => {
+                    validator_monitor.pending_public_keys.push(public_key)
+                }
+            }
+        }
+        validator_monitor
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.validators.is_empty() || !self.pending_public_keys.is_empty()
+    }
+
+    pub fn summaries(&self) -> Vec<ValidatorSummary> {
+        self.validators
+            .values()
+            .map(|validator| validator.summary.clone())
+            .collect()
+    }
+
+    /// Records the duties of the monitored validators in `block`, given the state after it.
+    pub fn process_block(
+        &mut self,
+        block: &BeaconBlock,
+        state: &BeaconState,
+    ) -> anyhow::Result<()> {
+        let epoch = compute_epoch_at_slot(block.slot.as_u64());
+        if self.last_epoch != Some(epoch) {
+            self.last_epoch = Some(epoch);
+            self.resolve_validators(state);
+        }
+
+        if let Some(validator) = self.validators.get_mut(&block.proposer_index) {
+            validator.summary.blocks_proposed += 1;
+            inc_int_counter_vec(&VALIDATOR_MONITOR_BLOCKS_PROPOSED, &[&validator.label()]);
+        }
+
+        for attestation in block.body.attestations.iter() {
+            let inclusion_distance = block
+                .slot
+                .as_u64()
+                .saturating_sub(attestation.data.slot.as_u64());
+            for index in state.get_attesting_indices(attestation)? {
+                let Some(validator) = self.validators.get_mut(&index) else {
+                    continue;
+                };
+                // Only the first inclusion of an attestation counts
+                if !validator
+                    .included_epochs
+                    .insert(attestation.data.target.epoch.as_u64())
+                {
+                    continue;
+                }
+                validator.summary.attestations_included += 1;
+                validator.summary.total_inclusion_distance += inclusion_distance;
+                let label = validator.label();
+                inc_int_counter_vec(&VALIDATOR_MONITOR_ATTESTATIONS, &[&label, "included"]);
+                set_int_gauge_vec(
+                    &VALIDATOR_MONITOR_INCLUSION_DISTANCE,
+                    inclusion_distance as i64,
+                    &[&label],
+                );
+            }
+        }
+
+        let sync_committee_bits = &block.body.sync_aggregate.sync_committee_bits;
+        for (position, public_key) in state.current_sync_committee.public_keys.iter().enumerate() {
+            let Some(validator) = self
+                .validators
+                .values_mut()
+                .find(|validator| validator.public_key.as_ref() == Some(public_key))
+            else {
+                continue;
+            };
+            let included = sync_committee_bits
+                .get(position)
+                .map_err(|err| anyhow!("Invalid sync committee position {position}: {err:?}"))?;
+            let outcome = if included {
+                validator.summary.sync_committee_included += 1;
+                "included"
+            } else {
+                validator.summary.sync_committee_missed += 1;
+                "missed"
+            };
+            inc_int_counter_vec(
+                &VALIDATOR_MONITOR_SYNC_COMMITTEE,
+                &[&validator.label(), outcome],
+            );
+        }
+
+        self.check_missed_attestations(epoch, state);
+        Ok(())
+    }
+
+    /// Looks up the indices of the validators given by public key, and the public keys of the
+    /// validators given by index.
+    fn resolve_validators(&mut self, state: &BeaconState) {
+        for validator in self.validators.values_mut() {
+            if validator.public_key.is_none() {
+                validator.public_key = state
+                    .validators
+                    .get(validator.summary.index as usize)
+                    .map(|state_validator| state_validator.public_key.clone());
+            }
+        }
+
+        if self.pending_public_keys.is_empty() {
+            return;
+        }
+        for (index, state_validator) in state.validators.iter().enumerate() {
+            if let Some(position) = self
+                .pending_public_keys
+                .iter()
+                .position(|public_key| *public_key == state_validator.public_key)
+            {
+                let public_key = self.pending_public_keys.swap_remove(position);
+                self.validators.insert(
+                    index as u64,
+                    MonitoredValidator::new(index as u64, Some(public_key)),
+                );
+            }
+        }
+    }
+
+    /// Counts the attestations which weren't included within the inclusion window of their
+    /// epoch, which closes at the end of the next epoch.
+    fn check_missed_attestations(&mut self, epoch: u64, state: &BeaconState) {
+        let next_epoch_to_check = self.next_epoch_to_check.get_or_insert(epoch);
+        while *next_epoch_to_check + 2 <= epoch {
+            let checked_epoch = *next_epoch_to_check;
+            for validator in self.validators.values_mut() {
+                let is_active = state
+                    .validators
+                    .get(validator.summary.index as usize)
+                    .is_some_and(|state_validator| {
+                        state_validator.is_active_validator(checked_epoch)
+                    });
+                if is_active && !validator.included_epochs.contains(&checked_epoch) {
+                    validator.summary.attestations_missed += 1;
+                    inc_int_counter_vec(
+                        &VALIDATOR_MONITOR_ATTESTATIONS,
+                        &[&validator.label(), "missed"],
+                    );
+                }
+                validator
+                    .included_epochs
+                    .retain(|included_epoch| *included_epoch > checked_epoch);
+            }
+            *next_epoch_to_check += 1;
+        }
+    }
+}
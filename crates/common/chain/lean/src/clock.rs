@@ -1,32 +1,14 @@
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-
-use anyhow::anyhow;
-use ream_consensus_misc::constants::lean::INTERVALS_PER_SLOT;
+use ream_consensus_misc::constants::lean::{INTERVALS_PER_SLOT, SLOT_OFFSET};
 use ream_network_spec::networks::lean_network_spec;
-use tokio::time::{Instant, Interval, MissedTickBehavior, interval_at};
-
-pub fn create_lean_clock_interval() -> anyhow::Result<Interval> {
-    let genesis_instant = UNIX_EPOCH + Duration::from_secs(lean_network_spec().genesis_time);
-
-    let interval_start = Instant::now()
-        + genesis_instant
-            .duration_since(SystemTime::now())
-            .map_err(|err| {
-                anyhow!(format!(
-                    "Genesis time is {:?} but should be greater than {:?}: {err}",
-                    lean_network_spec().genesis_time,
-                    SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .expect("system time before UNIX EPOCH")
-                        .as_secs()
-                ))
-            })?;
-
-    let mut interval = interval_at(
-        interval_start,
-        Duration::from_secs(lean_network_spec().seconds_per_slot / INTERVALS_PER_SLOT),
-    );
-    interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
-
-    Ok(interval)
+use ream_slot_clock::SlotClock;
+
+/// The clock of the lean chain, whose slots are numbered from [SLOT_OFFSET].
+pub fn lean_slot_clock() -> SlotClock {
+    let network_spec = lean_network_spec();
+    SlotClock::new(
+        network_spec.genesis_time,
+        network_spec.seconds_per_slot,
+        INTERVALS_PER_SLOT,
+    )
+    .with_slot_offset(SLOT_OFFSET)
 }
@@ -21,11 +21,12 @@ fn genesis_state(num_validators: u64, genesis_time: u64) -> LeanState {
 ///
 /// Reference: https://github.com/ethereum/research/blob/d225a6775a9b184b5c1fd6c830cc58a375d9535f/3sf-mini/test_p2p.py#L119-L131
 pub fn setup_genesis() -> (Block, LeanState) {
-    let (num_validators, genesis_time) = {
-        let network_spec = lean_network_spec();
-        (network_spec.num_validators, network_spec.genesis_time)
-    };
+    let network_spec = lean_network_spec();
+    generate_genesis(network_spec.num_validators, network_spec.genesis_time)
+}
 
+/// Generate the genesis block and state of a Lean chain with `num_validators` validators.
+pub fn generate_genesis(num_validators: u64, genesis_time: u64) -> (Block, LeanState) {
     let mut genesis_state = genesis_state(num_validators, genesis_time);
     let genesis_block = genesis_block(genesis_state.tree_hash_root());
 
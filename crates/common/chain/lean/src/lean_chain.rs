@@ -9,15 +9,19 @@ use ream_consensus_lean::{
     state::LeanState,
     vote::{SignedVote, Vote},
 };
-use ream_fork_choice::lean::get_fork_choice_head;
-use ream_metrics::{PROPOSE_BLOCK_TIME, start_timer_vec, stop_timer};
-use ream_network_spec::networks::lean_network_spec;
+use ream_fork_choice::lean::{ForkChoice, compute_fork_choice, get_fork_choice_head};
+use ream_metrics::{
+    FINALIZED_SLOT, JUSTIFIED_SLOT, PROPOSE_BLOCK_TIME, set_int_gauge_vec, start_timer_vec,
+    stop_timer,
+};
 use ream_storage::{
     db::lean::LeanDB,
+    errors::StoreError,
     tables::{field::Field, lean::lean_block::LeanBlockTable, table::Table},
 };
 use ream_sync::rwlock::{Reader, Writer};
 use tokio::sync::Mutex;
+use tracing::{debug_span, info, instrument};
 use tree_hash::TreeHash;
 
 pub type LeanChainWriter = Writer<LeanChain>;
@@ -48,15 +52,34 @@ impl LeanChain {
     pub fn new(genesis_block: SignedBlock, genesis_state: LeanState, db: LeanDB) -> LeanChain {
         let genesis_block_hash = genesis_block.message.tree_hash_root();
         let no_of_validators = genesis_state.config.num_validators;
+        // The checkpoints start at the genesis block like in the Python implementation, rather than
+        // at the zero roots of the genesis state, which the blocks justify as the genesis block.
+        let anchor_checkpoint = Checkpoint {
+            root: genesis_block_hash,
+            slot: genesis_block.message.slot,
+        };
         db.lean_block_provider()
             .insert(genesis_block_hash, genesis_block)
             .expect("Failed to insert genesis block");
         db.latest_finalized_provider()
-            .insert(genesis_state.latest_finalized.clone())
+            .insert(anchor_checkpoint.clone())
             .expect("Failed to insert latest finalized checkpoint");
         db.latest_justified_provider()
-            .insert(genesis_state.latest_justified.clone())
+            .insert(anchor_checkpoint)
             .expect("Failed to insert latest justified checkpoint");
+        db.safe_target_provider()
+            .insert(genesis_block_hash)
+            .expect("Failed to insert safe target");
+        set_int_gauge_vec(
+            &JUSTIFIED_SLOT,
+            genesis_state.latest_justified.slot as i64,
+            &[],
+        );
+        set_int_gauge_vec(
+            &FINALIZED_SLOT,
+            genesis_state.latest_finalized.slot as i64,
+            &[],
+        );
         db.lean_state_provider()
             .insert(genesis_block_hash, genesis_state)
             .expect("Failed to insert genesis state");
@@ -71,6 +94,60 @@ impl LeanChain {
         }
     }
 
+    /// Restores the chain from `db` if it was initialized with the same genesis block before, or
+    /// initializes it with the genesis block and state otherwise.
+    ///
+    /// The head is recomputed from the persisted votes and justified checkpoint, while votes
+    /// which weren't accepted before the node stopped are lost.
+    pub async fn load(
+        genesis_block: SignedBlock,
+        genesis_state: LeanState,
+        db: LeanDB,
+    ) -> anyhow::Result<LeanChain> {
+        let genesis_block_hash = genesis_block.message.tree_hash_root();
+        if !db.lean_block_provider().contains_key(genesis_block_hash) {
+            return Ok(LeanChain::new(genesis_block, genesis_state, db));
+        }
+
+        let safe_target = match db.safe_target_provider().get() {
+            Ok(safe_target) => safe_target,
+            Err(StoreError::FieldNotInitilized) => genesis_block_hash,
+            Err(err) => return Err(err.into()),
+        };
+        let mut lean_chain = LeanChain {
+            store: Arc::new(Mutex::new(db)),
+            new_votes: Vec::new(),
+            genesis_hash: genesis_block_hash,
+            num_validators: genesis_state.config.num_validators,
+            safe_target,
+            head: genesis_block_hash,
+        };
+
+        let (known_votes, latest_justified, latest_finalized) = {
+            let db = lean_chain.store.lock().await;
+            (
+                db.known_votes_provider().get_all_votes()?,
+                db.latest_justified_provider().get()?,
+                db.latest_finalized_provider().get()?,
+            )
+        };
+        set_int_gauge_vec(&JUSTIFIED_SLOT, latest_justified.slot as i64, &[]);
+        set_int_gauge_vec(&FINALIZED_SLOT, latest_finalized.slot as i64, &[]);
+        lean_chain.head = get_fork_choice_head(
+            lean_chain.store.clone(),
+            &known_votes,
+            &latest_justified.root,
+            0,
+        )
+        .await?;
+        info!(
+            "Restored LeanChain from the database with head: {}, safe target: {}",
+            lean_chain.head, lean_chain.safe_target
+        );
+
+        Ok(lean_chain)
+    }
+
     pub async fn get_block_id_by_slot(&self, slot: u64) -> anyhow::Result<B256> {
         self.store
             .lock()
@@ -119,6 +196,11 @@ impl LeanChain {
             min_target_score,
         )
         .await?;
+        self.store
+            .lock()
+            .await
+            .safe_target_provider()
+            .insert(self.safe_target)?;
 
         Ok(())
     }
@@ -145,17 +227,26 @@ impl LeanChain {
     }
 
     /// Done upon processing new votes or a new block
+    /// Runs the fork choice for the head over the known votes, like [LeanChain::update_head]
+    /// does, without updating the head.
+    pub async fn get_fork_choice(&self) -> anyhow::Result<ForkChoice> {
+        let (known_votes, latest_justified_root) = {
+            let db = self.store.lock().await;
+            (
+                db.known_votes_provider().get_all_votes()?,
+                db.latest_justified_provider().get()?.root,
+            )
+        };
+
+        compute_fork_choice(self.store.clone(), &known_votes, &latest_justified_root, 0).await
+    }
+
     pub async fn update_head(&mut self) -> anyhow::Result<()> {
-        let (known_votes, latest_justified_root, latest_finalized_checkpoint) = {
+        let (known_votes, latest_justified_root) = {
             let db = self.store.lock().await;
             (
                 db.known_votes_provider().get_all_votes()?,
                 db.latest_justified_provider().get()?.root,
-                db.lean_state_provider()
-                    .get(self.head)?
-                    .ok_or_else(|| anyhow!("State not found in chain for head: {}", self.head))?
-                    .latest_finalized
-                    .clone(),
             )
         };
 
@@ -164,12 +255,41 @@ impl LeanChain {
             get_fork_choice_head(self.store.clone(), &known_votes, &latest_justified_root, 0)
                 .await?;
 
-        // Update latest finalized checkpoint in DB.
-        self.store
+        // Update latest finalized checkpoint from the state of the new head.
+        let head_state = self
+            .store
             .lock()
             .await
-            .latest_finalized_provider()
-            .insert(latest_finalized_checkpoint.clone())?;
+            .lean_state_provider()
+            .get(self.head)?
+            .ok_or_else(|| anyhow!("State not found in chain for head: {}", self.head))?;
+        self.update_checkpoints(&head_state.latest_justified, &head_state.latest_finalized)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Advances the justified and finalized checkpoints of the chain to the ones of a post state,
+    /// if they are later. Post states of different forks justify and finalize independently, so
+    /// the chain keeps the latest checkpoints any of them reached, as in 3SF-mini.
+    pub async fn update_checkpoints(
+        &self,
+        latest_justified: &Checkpoint,
+        latest_finalized: &Checkpoint,
+    ) -> anyhow::Result<()> {
+        let db = self.store.lock().await;
+
+        if latest_justified.slot > db.latest_justified_provider().get()?.slot {
+            db.latest_justified_provider()
+                .insert(latest_justified.clone())?;
+            set_int_gauge_vec(&JUSTIFIED_SLOT, latest_justified.slot as i64, &[]);
+        }
+
+        if latest_finalized.slot > db.latest_finalized_provider().get()?.slot {
+            db.latest_finalized_provider()
+                .insert(latest_finalized.clone())?;
+            set_int_gauge_vec(&FINALIZED_SLOT, latest_finalized.slot as i64, &[]);
+        }
 
         Ok(())
     }
@@ -226,6 +346,7 @@ impl LeanChain {
         })
     }
 
+    #[instrument(level = "debug", skip(self))]
     pub async fn propose_block(&self, slot: u64) -> anyhow::Result<Block> {
         let initialize_block_timer = start_timer_vec(&PROPOSE_BLOCK_TIME, &["initialize_block"]);
 
@@ -241,7 +362,7 @@ impl LeanChain {
         let mut new_block = SignedBlock {
             message: Block {
                 slot,
-                proposer_index: slot % lean_network_spec().num_validators,
+                proposer_index: slot % self.num_validators,
                 parent_root: self.head,
                 // Diverged from Python implementation: Using `B256::ZERO` instead of `None`)
                 state_root: B256::ZERO,
@@ -255,28 +376,31 @@ impl LeanChain {
         let mut state = head_state.clone();
 
         // Apply state transition so the state is brought up to the expected slot
-        state.state_transition(&new_block, true, false)?;
+        debug_span!("state_transition")
+            .in_scope(|| state.state_transition(&new_block, true, false))?;
 
         // Keep attempt to add valid votes from the list of available votes
         let add_votes_timer = start_timer_vec(&PROPOSE_BLOCK_TIME, &["add_valid_votes_to_block"]);
-        loop {
-            state.process_attestations(&new_block.message.body.attestations)?;
-            let new_votes_to_add = known_votes_provider
-                .filter_new_votes_to_add(state.latest_justified.root, &new_block)?;
-
-            if new_votes_to_add.is_empty() {
-                break;
-            }
-
-            for vote in new_votes_to_add {
-                new_block
-                    .message
-                    .body
-                    .attestations
-                    .push(vote)
-                    .map_err(|err| anyhow!("Failed to add vote to new_block: {err:?}"))?;
+        debug_span!("add_valid_votes_to_block").in_scope(|| -> anyhow::Result<()> {
+            loop {
+                state.process_attestations(&new_block.message.body.attestations)?;
+                let new_votes_to_add = known_votes_provider
+                    .filter_new_votes_to_add(state.latest_justified.root, &new_block)?;
+
+                if new_votes_to_add.is_empty() {
+                    return Ok(());
+                }
+
+                for vote in new_votes_to_add {
+                    new_block
+                        .message
+                        .body
+                        .attestations
+                        .push(vote)
+                        .map_err(|err| anyhow!("Failed to add vote to new_block: {err:?}"))?;
+                }
             }
-        }
+        })?;
         stop_timer(add_votes_timer);
 
         // Update `state.latest_block_header.body_root` so that it accounts for
@@ -286,7 +410,8 @@ impl LeanChain {
         // Compute the state root
         let compute_state_root_timer =
             start_timer_vec(&PROPOSE_BLOCK_TIME, &["compute_state_root"]);
-        new_block.message.state_root = state.tree_hash_root();
+        new_block.message.state_root =
+            debug_span!("compute_state_root").in_scope(|| state.tree_hash_root());
         stop_timer(compute_state_root_timer);
 
         Ok(new_block.message)
@@ -6,3 +6,4 @@ pub mod p2p_request;
 pub mod queue_item;
 pub mod service;
 pub mod slot;
+pub mod vote_verifier;
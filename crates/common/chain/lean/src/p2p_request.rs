@@ -1,7 +1,10 @@
+use alloy_primitives::B256;
 use ream_consensus_lean::{block::SignedBlock, vote::SignedVote};
 
 #[derive(Debug, Clone)]
 pub enum LeanP2PRequest {
     GossipBlock(SignedBlock),
     GossipVote(SignedVote),
+    /// Requests blocks which queued blocks or votes depend on from peers
+    RequestBlocksByRoot(Vec<B256>),
 }
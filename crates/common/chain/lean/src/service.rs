@@ -7,15 +7,16 @@ use ream_consensus_lean::{
     vote::SignedVote,
 };
 use ream_network_spec::networks::lean_network_spec;
-use ream_storage::tables::{field::Field, table::Table};
+use ream_prover_program::LeanProgramInput;
+use ream_slot_clock::SlotTime;
+use ream_storage::tables::table::Table;
 use tokio::sync::{mpsc, oneshot};
 use tracing::{error, info, warn};
 use tree_hash::TreeHash;
 
 use crate::{
-    clock::create_lean_clock_interval, lean_chain::LeanChainWriter,
-    messages::LeanChainServiceMessage, p2p_request::LeanP2PRequest, queue_item::QueueItem,
-    slot::get_current_slot,
+    clock::lean_slot_clock, lean_chain::LeanChainWriter, messages::LeanChainServiceMessage,
+    p2p_request::LeanP2PRequest, queue_item::QueueItem, vote_verifier::VoteVerifier,
 };
 
 /// LeanChainService is responsible for updating the [LeanChain] state. `LeanChain` is updated when:
@@ -30,6 +31,10 @@ pub struct LeanChainService {
     outbound_gossip: mpsc::UnboundedSender<LeanP2PRequest>,
     // Objects that we will process once we have processed their parents
     dependencies: HashMap<B256, Vec<QueueItem>>,
+    // Verifies the signatures of untrusted votes, if the validator public keys are registered
+    vote_verifier: Option<VoteVerifier>,
+    // Proves the state transition of every block this service imports, if a prover is running
+    prover: Option<mpsc::UnboundedSender<LeanProgramInput>>,
 }
 
 impl LeanChainService {
@@ -38,6 +43,8 @@ impl LeanChainService {
         receiver: mpsc::UnboundedReceiver<LeanChainServiceMessage>,
         sender: mpsc::UnboundedSender<LeanChainServiceMessage>,
         outbound_gossip: mpsc::UnboundedSender<LeanP2PRequest>,
+        vote_verifier: Option<VoteVerifier>,
+        prover: Option<mpsc::UnboundedSender<LeanProgramInput>>,
     ) -> Self {
         LeanChainService {
             lean_chain,
@@ -45,6 +52,8 @@ impl LeanChainService {
             sender,
             outbound_gossip,
             dependencies: HashMap::new(),
+            vote_verifier,
+            prover,
         }
     }
 
@@ -54,93 +63,148 @@ impl LeanChainService {
             lean_network_spec().genesis_time
         );
 
-        let mut tick_count = 0u64;
-
-        let mut interval =
-            create_lean_clock_interval().context("Failed to create clock interval")?;
+        let mut ticks = lean_slot_clock().ticks();
 
         loop {
             tokio::select! {
-                _ = interval.tick() => {
-                    match tick_count % 4 {
-                        0 => {
-                            // First tick (t=0/4): Log current head state, including its justification/finalization status.
-                            let current_slot = get_current_slot();
-                            let (head, store) = {
-                                let lean_chain = self.lean_chain.read().await;
-                                (lean_chain.head, lean_chain.store.clone())
-                            };
-                            let head_state = store.lock().await
-                                .lean_state_provider()
-                                .get(head)?.ok_or_else(|| anyhow!("Post state not found for head: {head}"))?;
-
-                            info!(
-                                "Current head state of slot {current_slot}: latest_justified.slot: {}, latest_finalized.slot: {}",
-                                head_state.latest_justified.slot,
-                                head_state.latest_finalized.slot
-                            );
-                        }
-                        2 => {
-                            // Third tick (t=2/4): Compute the safe target.
-                            let current_slot = get_current_slot();
-                            info!("Computing safe target at slot {current_slot} (tick {tick_count})");
-                            self.lean_chain.write().await.update_safe_target().await.expect("Failed to update safe target");
-                        }
-                        3 => {
-                            // Fourth tick (t=3/4): Accept new votes.
-                            let current_slot = get_current_slot();
-                            info!("Accepting new votes at slot {current_slot} (tick {tick_count})");
-                            self.lean_chain.write().await.accept_new_votes().await.expect("Failed to accept new votes");
-                        }
-                        _ => {
-                            // Other ticks (t=0, t=1/4): Do nothing.
-                        }
-                    }
-                    tick_count += 1;
+                slot_time = ticks.next() => {
+                    self.handle_tick(slot_time).await?;
                 }
                 Some(message) = self.receiver.recv() => {
-                    match message {
-                        LeanChainServiceMessage::ProduceBlock { slot, sender } => {
-                            if let Err(err) = self.handle_produce_block(slot, sender).await {
-                                error!("Failed to handle produce block message: {err:?}");
-                            }
-                        }
-                        LeanChainServiceMessage::ProcessBlock { signed_block, is_trusted, need_gossip } => {
-                            info!(
-                                "Processing block: slot={}, validator_id={}, root={}, parent={}, votes={}",
-                                signed_block.message.slot,
-                                signed_block.message.proposer_index,
-                                signed_block.message.tree_hash_root(),
-                                signed_block.message.parent_root,
-                                signed_block.message.body.attestations.len(),
-                            );
-
-                            if let Err(err) = self.handle_process_block(signed_block.clone(), is_trusted).await {
-                                warn!("Failed to handle process block message: {err:?}");
-                            }
+                    self.handle_message(message).await;
+                }
+            }
+        }
+    }
 
-                            if need_gossip && let Err(err) = self.outbound_gossip.send(LeanP2PRequest::GossipBlock(signed_block)) {
-                                warn!("Failed to send item to outbound gossip channel: {err:?}");
-                            }
-                        }
-                        LeanChainServiceMessage::ProcessVote { signed_vote, is_trusted, need_gossip } => {
-                            info!(
-                                "Processing vote: slot={}, validator_id={}, source={:?}, target={:?}",
-                                signed_vote.message.slot,
-                                signed_vote.validator_id,
-                                signed_vote.message.source,
-                                signed_vote.message.target
-                            );
-
-                            if let Err(err) = self.handle_process_vote(signed_vote.clone(), is_trusted).await {
-                                warn!("Failed to handle process block message: {err:?}");
-                            }
+    /// Performs the work of an interval of the slot. [LeanChainService::start] calls this on every
+    /// tick of the wall clock, while a simulation can drive it from a virtual clock instead.
+    pub async fn handle_tick(&mut self, slot_time: SlotTime) -> anyhow::Result<()> {
+        let current_slot = slot_time.slot;
+        match slot_time.interval {
+            0 => {
+                // First tick (t=0/4): Log current head state, including its
+                // justification/finalization status.
+                let (head, store) = {
+                    let lean_chain = self.lean_chain.read().await;
+                    (lean_chain.head, lean_chain.store.clone())
+                };
+                let head_state = store
+                    .lock()
+                    .await
+                    .lean_state_provider()
+                    .get(head)?
+                    .ok_or_else(|| anyhow!("Post state not found for head: {head}"))?;
+
+                info!(
+                    "Current head state of slot {current_slot}: latest_justified.slot: {}, latest_finalized.slot: {}",
+                    head_state.latest_justified.slot, head_state.latest_finalized.slot
+                );
+
+                // Votes before the finalized slot can't change the chain anymore.
+                if let Some(vote_verifier) = &mut self.vote_verifier {
+                    vote_verifier.prune(head_state.latest_finalized.slot);
+                }
+            }
+            2 => {
+                // Third tick (t=2/4): Compute the safe target.
+                info!("Computing safe target at slot {current_slot}");
+                self.lean_chain
+                    .write()
+                    .await
+                    .update_safe_target()
+                    .await
+                    .expect("Failed to update safe target");
+            }
+            3 => {
+                // Fourth tick (t=3/4): Accept new votes.
+                info!("Accepting new votes at slot {current_slot}");
+                self.lean_chain
+                    .write()
+                    .await
+                    .accept_new_votes()
+                    .await
+                    .expect("Failed to accept new votes");
+            }
+            _ => {
+                // Other ticks (t=1/4): Do nothing.
+            }
+        }
 
-                            if need_gossip && let Err(err) = self.outbound_gossip.send(LeanP2PRequest::GossipVote(signed_vote)) {
-                                warn!("Failed to send item to outbound gossip channel: {err:?}");
-                            }
-                        }
-                    }
+        Ok(())
+    }
+
+    /// Handles the messages which are already queued, without waiting for new ones. This includes
+    /// the dependencies which the handled blocks release.
+    pub async fn handle_queued_messages(&mut self) {
+        while let Ok(message) = self.receiver.try_recv() {
+            self.handle_message(message).await;
+        }
+    }
+
+    async fn handle_message(&mut self, message: LeanChainServiceMessage) {
+        match message {
+            LeanChainServiceMessage::ProduceBlock { slot, sender } => {
+                if let Err(err) = self.handle_produce_block(slot, sender).await {
+                    error!("Failed to handle produce block message: {err:?}");
+                }
+            }
+            LeanChainServiceMessage::ProcessBlock {
+                signed_block,
+                is_trusted,
+                need_gossip,
+            } => {
+                info!(
+                    "Processing block: slot={}, validator_id={}, root={}, parent={}, votes={}",
+                    signed_block.message.slot,
+                    signed_block.message.proposer_index,
+                    signed_block.message.tree_hash_root(),
+                    signed_block.message.parent_root,
+                    signed_block.message.body.attestations.len(),
+                );
+
+                if let Err(err) = self
+                    .handle_process_block(signed_block.clone(), is_trusted)
+                    .await
+                {
+                    warn!("Failed to handle process block message: {err:?}");
+                }
+
+                if need_gossip
+                    && let Err(err) = self
+                        .outbound_gossip
+                        .send(LeanP2PRequest::GossipBlock(signed_block))
+                {
+                    warn!("Failed to send item to outbound gossip channel: {err:?}");
+                }
+            }
+            LeanChainServiceMessage::ProcessVote {
+                signed_vote,
+                is_trusted,
+                need_gossip,
+            } => {
+                info!(
+                    "Processing vote: slot={}, validator_id={}, source={:?}, target={:?}",
+                    signed_vote.message.slot,
+                    signed_vote.validator_id,
+                    signed_vote.message.source,
+                    signed_vote.message.target
+                );
+
+                if let Err(err) = self
+                    .handle_process_vote(signed_vote.clone(), is_trusted)
+                    .await
+                {
+                    warn!("Failed to handle process block message: {err:?}");
+                    return;
+                }
+
+                if need_gossip
+                    && let Err(err) = self
+                        .outbound_gossip
+                        .send(LeanP2PRequest::GossipVote(signed_vote))
+                {
+                    warn!("Failed to send item to outbound gossip channel: {err:?}");
                 }
             }
         }
@@ -212,22 +276,34 @@ impl LeanChainService {
                         votes_to_add.push(vote.clone());
                     }
                 }
+                let latest_justified = state.latest_justified.clone();
+                let latest_finalized = state.latest_finalized.clone();
                 {
                     let db = lean_chain.store.lock().await;
                     db.lean_block_provider()
                         .insert(block_hash, signed_block.clone())?;
 
-                    db.latest_justified_provider()
-                        .insert(state.latest_justified.clone())?;
                     db.lean_state_provider().insert(block_hash, state)?;
 
                     db.known_votes_provider().batch_append(votes_to_add)?;
                 }
 
+                lean_chain
+                    .update_checkpoints(&latest_justified, &latest_finalized)
+                    .await?;
                 lean_chain.update_head().await?;
 
                 drop(lean_chain);
 
+                if let Some(prover) = &self.prover
+                    && let Err(err) = prover.send(LeanProgramInput {
+                        pre_state: parent_state,
+                        signed_block,
+                    })
+                {
+                    warn!("Failed to send block {block_hash} to the prover: {err:?}");
+                }
+
                 // Once we have received a block, also process all of its dependencies
                 // by sending them to this service itself.
                 // NOTE 1: As we already verified all QueueItems before appending them, we can trust
@@ -261,10 +337,12 @@ impl LeanChainService {
             None => {
                 // If we have not yet seen the block's parent, ignore for now,
                 // process later once we actually see the parent
+                let parent_root = signed_block.message.parent_root;
                 self.dependencies
-                    .entry(signed_block.message.parent_root)
+                    .entry(parent_root)
                     .or_default()
                     .push(QueueItem::Block(signed_block.message));
+                self.request_missing_block(parent_root);
             }
         }
 
@@ -276,8 +354,10 @@ impl LeanChainService {
         signed_vote: SignedVote,
         is_trusted: bool,
     ) -> anyhow::Result<()> {
-        if !is_trusted {
-            // TODO: Validate the signature.
+        if !is_trusted && let Some(vote_verifier) = &mut self.vote_verifier {
+            vote_verifier
+                .verify(&signed_vote)
+                .context("Rejected vote with an invalid or reused key")?;
         }
 
         let (lean_block_provider, known_votes_provider) = {
@@ -302,12 +382,26 @@ impl LeanChainService {
             let mut lean_chain = self.lean_chain.write().await;
             lean_chain.new_votes.push(signed_vote);
         } else {
+            let head_root = signed_vote.message.head.root;
             self.dependencies
-                .entry(signed_vote.message.head.root)
+                .entry(head_root)
                 .or_default()
                 .push(QueueItem::SignedVote(Box::new(signed_vote)));
+            self.request_missing_block(head_root);
         }
 
         Ok(())
     }
+
+    /// Requests a block which queued items depend on from peers. Once it arrives, it is processed
+    /// like any other block, so an unknown parent of it is requested in turn until the chain of
+    /// ancestors connects to a known block.
+    fn request_missing_block(&self, block_root: B256) {
+        if let Err(err) = self
+            .outbound_gossip
+            .send(LeanP2PRequest::RequestBlocksByRoot(vec![block_root]))
+        {
+            warn!("Failed to request missing block {block_root}: {err:?}");
+        }
+    }
 }
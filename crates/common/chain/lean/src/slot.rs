@@ -1,19 +1,9 @@
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-
-use ream_consensus_misc::constants::lean::SLOT_OFFSET;
-use ream_network_spec::networks::lean_network_spec;
+use crate::clock::lean_slot_clock;
 
 /// NOTE: Vitalik's implementation of 3SF-mini adds 2 slots more due to the test setup.
 /// This is due to the fact that his test code starts at slot 1.
 pub fn get_current_slot() -> u64 {
-    let network_spec = lean_network_spec();
-    let seconds_per_slot = network_spec.seconds_per_slot;
-    let genesis_time = network_spec.genesis_time;
-
-    let genesis_instant = UNIX_EPOCH + Duration::from_secs(genesis_time);
-    let elapsed = SystemTime::now()
-        .duration_since(genesis_instant)
-        .expect("Called before genesis time");
-
-    (elapsed.as_secs() / seconds_per_slot) + SLOT_OFFSET
+    lean_slot_clock()
+        .current_slot()
+        .expect("Called before genesis time")
 }
@@ -0,0 +1,194 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use ream_consensus_lean::vote::SignedVote;
+use ream_post_quantum_crypto::hashsig::{public_key::PublicKey, signature::Signature};
+use tree_hash::TreeHash;
+
+/// VoteVerifier checks the hash-based signatures of the votes received from the network against
+/// the registered public keys of the validators.
+///
+/// A validator signs the vote of a slot with the one-time key of the epoch equal to that slot.
+/// Signing two messages with a one-time key leaks enough of it to forge signatures, so a second,
+/// different vote signed with the key of an epoch is rejected.
+pub struct VoteVerifier {
+    public_keys: HashMap<u64, PublicKey>,
+    /// The root of the vote each validator signed with the key of an epoch
+    used_keys: HashMap<(u64, u64), B256>,
+    /// The keys of the epochs before this one are no longer tracked. Votes for these epochs are
+    /// still verified, but they can't change the chain anymore, so their keys aren't tracked.
+    min_epoch: u64,
+}
+
+impl VoteVerifier {
+    pub fn new(public_keys: HashMap<u64, PublicKey>) -> Self {
+        Self {
+            public_keys,
+            used_keys: HashMap::new(),
+            min_epoch: 0,
+        }
+    }
+
+    pub fn verify(&mut self, signed_vote: &SignedVote) -> anyhow::Result<()> {
+        let validator_id = signed_vote.validator_id;
+        let epoch = signed_vote.message.slot;
+        let public_key = self
+            .public_keys
+            .get(&validator_id)
+            .ok_or_else(|| anyhow!("No public key registered for validator {validator_id}"))?;
+        let vote_root = signed_vote.message.tree_hash_root();
+        let signature = Signature::from_bytes(signed_vote.signature.as_slice())?;
+        ensure!(
+            signature.verify(&vote_root.0, public_key, u32::try_from(epoch)?)?,
+            "Invalid signature of validator {validator_id} for the vote at slot {epoch}"
+        );
+
+        if epoch < self.min_epoch {
+            return Ok(());
+        }
+        match self.used_keys.entry((validator_id, epoch)) {
+            Entry::Occupied(entry) => ensure!(
+                *entry.get() == vote_root,
+                "Validator {validator_id} reused its one-time key of epoch {epoch}"
+            ),
+            Entry::Vacant(entry) => {
+                entry.insert(vote_root);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops tracking the keys of the epochs before `slot`.
+    pub fn prune(&mut self, slot: u64) {
+        if slot <= self.min_epoch {
+            return;
+        }
+        self.min_epoch = slot;
+        self.used_keys.retain(|(_, epoch), _| *epoch >= slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::FixedBytes;
+    use ream_consensus_lean::{checkpoint::Checkpoint, vote::Vote};
+    use ream_post_quantum_crypto::hashsig::private_key::PrivateKey;
+
+    use super::*;
+
+    const NUM_ACTIVE_EPOCHS: usize = 10;
+
+    fn signed_vote(private_key: &PrivateKey, slot: u64, head_root: B256) -> SignedVote {
+        let message = Vote {
+            slot,
+            head: Checkpoint {
+                root: head_root,
+                slot,
+            },
+            target: Checkpoint::default(),
+            source: Checkpoint::default(),
+        };
+        let signature = private_key
+            .sign(&mut rand::rng(), &message.tree_hash_root().0, slot as u32)
+            .unwrap();
+        SignedVote {
+            validator_id: 0,
+            message,
+            signature: FixedBytes::from_slice(
+                &signature
+                    .to_padded_bytes(FixedBytes::<4000>::len_bytes())
+                    .unwrap(),
+            ),
+        }
+    }
+
+    fn vote_verifier() -> (VoteVerifier, PrivateKey) {
+        let (public_key, private_key) =
+            PrivateKey::generate_key_pair(&mut rand::rng(), 0, NUM_ACTIVE_EPOCHS);
+        (
+            VoteVerifier::new(HashMap::from([(0, public_key)])),
+            private_key,
+        )
+    }
+
+    #[test]
+    fn test_valid_votes_are_accepted() {
+        let (mut vote_verifier, private_key) = vote_verifier();
+        let vote = signed_vote(&private_key, 3, B256::repeat_byte(1));
+        vote_verifier.verify(&vote).unwrap();
+        // The same vote may be received again
+        vote_verifier.verify(&vote).unwrap();
+
+        // Votes of unknown validators are rejected
+        let mut unknown_validator_vote = vote.clone();
+        unknown_validator_vote.validator_id = 1;
+        assert!(vote_verifier.verify(&unknown_validator_vote).is_err());
+    }
+
+    #[test]
+    fn test_bad_signatures_are_rejected() {
+        let (mut vote_verifier, private_key) = vote_verifier();
+
+        // A signature of another vote
+        let mut vote = signed_vote(&private_key, 3, B256::repeat_byte(1));
+        vote.message.head.root = B256::repeat_byte(2);
+        assert!(vote_verifier.verify(&vote).is_err());
+
+        // A signature with the key of another epoch
+        let mut vote = signed_vote(&private_key, 3, B256::repeat_byte(1));
+        vote.signature = signed_vote(&private_key, 4, B256::repeat_byte(1)).signature;
+        assert!(vote_verifier.verify(&vote).is_err());
+
+        // No signature
+        vote.signature = FixedBytes::default();
+        assert!(vote_verifier.verify(&vote).is_err());
+    }
+
+    #[test]
+    fn test_reused_one_time_keys_are_rejected() {
+        let (mut vote_verifier, private_key) = vote_verifier();
+        vote_verifier
+            .verify(&signed_vote(&private_key, 3, B256::repeat_byte(1)))
+            .unwrap();
+        assert!(
+            vote_verifier
+                .verify(&signed_vote(&private_key, 3, B256::repeat_byte(2)))
+                .is_err()
+        );
+        // The keys of other epochs are still unused
+        vote_verifier
+            .verify(&signed_vote(&private_key, 4, B256::repeat_byte(2)))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_pruned_epochs_are_verified_but_not_tracked() {
+        let (mut vote_verifier, private_key) = vote_verifier();
+        vote_verifier
+            .verify(&signed_vote(&private_key, 3, B256::repeat_byte(1)))
+            .unwrap();
+        vote_verifier
+            .verify(&signed_vote(&private_key, 5, B256::repeat_byte(1)))
+            .unwrap();
+        vote_verifier.prune(5);
+        assert_eq!(vote_verifier.used_keys.len(), 1);
+
+        // Votes older than the pruned slot are still accepted if their signature is valid
+        vote_verifier
+            .verify(&signed_vote(&private_key, 3, B256::repeat_byte(2)))
+            .unwrap();
+        let mut vote = signed_vote(&private_key, 2, B256::repeat_byte(1));
+        vote.message.head.root = B256::repeat_byte(2);
+        assert!(vote_verifier.verify(&vote).is_err());
+        assert_eq!(vote_verifier.used_keys.len(), 1);
+
+        // The keys of the epochs from the pruned slot on are still tracked
+        assert!(
+            vote_verifier
+                .verify(&signed_vote(&private_key, 5, B256::repeat_byte(2)))
+                .is_err()
+        );
+    }
+}
@@ -8,6 +8,7 @@ use ream_consensus_beacon::{
     blob_sidecar::{BlobIdentifier, BlobSidecar},
     electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
     execution_engine::rpc_types::get_blobs::BlobAndProofV1,
+    versioned::{VersionedBeaconState, VersionedSignedBeaconBlock},
 };
 use ream_consensus_misc::checkpoint::Checkpoint;
 use ream_fork_choice::{handlers::on_tick, store::get_forkchoice_store};
@@ -18,7 +19,6 @@ use reqwest::{
     header::{ACCEPT, HeaderValue},
 };
 use serde::{Deserialize, Serialize};
-use ssz::Decode;
 use tracing::{info, warn};
 use weak_subjectivity::{WeakSubjectivityState, verify_state_from_weak_subjectivity_checkpoint};
 
@@ -64,7 +64,7 @@ pub async fn initialize_db_from_checkpoint(
         block.message.block_root(),
         block.message.slot
     );
-    let slot = block.message.slot;
+    let slot = block.message.slot.as_u64();
 
     info!("Fetching blobs...");
     initialize_blobs_in_db(&checkpoint_sync_url, db.clone(), block.message.block_root()).await?;
@@ -112,7 +112,8 @@ async fn get_state(rpc: &Url, slot: u64) -> anyhow::Result<BeaconState> {
         .bytes()
         .await?;
 
-    BeaconState::from_ssz_bytes(&state)
+    VersionedBeaconState::from_ssz_bytes(&state)
+        .map(VersionedBeaconState::into_inner)
         .map_err(|err| anyhow!("Unable to decode state from ssz bytes: {err:?}"))
 }
 
@@ -127,7 +128,8 @@ async fn fetch_finalized_block(rpc: &Url) -> anyhow::Result<SignedBeaconBlock> {
         .bytes()
         .await?;
 
-    SignedBeaconBlock::from_ssz_bytes(&raw_bytes)
+    VersionedSignedBeaconBlock::from_ssz_bytes(&raw_bytes)
+        .map(VersionedSignedBeaconBlock::into_inner)
         .map_err(|err| anyhow!("Unable to decode block from ssz bytes: {err:?}"))
 }
 
@@ -25,13 +25,15 @@ pub fn is_within_weak_subjectivity_period(
         "State root must be equal to checkpoint root"
     );
     ensure!(
-        compute_epoch_at_slot(weak_subjectivity_state.slot) == weak_subjectivity_checkpoint.epoch,
+        compute_epoch_at_slot(weak_subjectivity_state.slot.as_u64())
+            == weak_subjectivity_checkpoint.epoch,
         "State epoch must be equal to checkpoint epoch"
     );
 
     let weak_subjectivity_period = weak_subjectivity_state.compute_weak_subjectivity_period();
-    let weak_subjectivity_state_epoch = compute_epoch_at_slot(weak_subjectivity_state.slot);
-    let current_epoch = compute_epoch_at_slot(store.get_current_slot()?);
+    let weak_subjectivity_state_epoch =
+        compute_epoch_at_slot(weak_subjectivity_state.slot.as_u64());
+    let current_epoch = store.get_current_slot()?.epoch();
     Ok(current_epoch <= weak_subjectivity_state_epoch + weak_subjectivity_period)
 }
 
@@ -42,7 +44,7 @@ pub fn verify_state_from_weak_subjectivity_checkpoint(
 ) -> anyhow::Result<bool> {
     if weak_subjectivity_checkpoint.epoch < state.get_current_epoch() {
         ensure!(
-            state.get_block_root(weak_subjectivity_checkpoint.epoch)?
+            state.get_block_root(weak_subjectivity_checkpoint.epoch.as_u64())?
                 == weak_subjectivity_checkpoint.root,
             "Weak subjectivity checkpoint not found"
         );
@@ -1,5 +1,6 @@
 use ream_bls::BLSSignature;
-use ream_consensus_misc::attestation_data::AttestationData;
+use ream_consensus_misc::{attestation_data::AttestationData, constants::beacon::SLOTS_PER_EPOCH};
+use ream_network_spec::networks::beacon_network_spec;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{
@@ -15,3 +16,16 @@ pub struct Attestation {
     pub signature: BLSSignature,
     pub committee_bits: BitVector<U64>,
 }
+
+/// Compute the correct subnet for an attestation for Phase 0.
+/// Note, this mimics expected future behavior where attestations will be mapped to their shard
+/// subnet.
+pub fn compute_subnet_for_attestation(
+    committees_per_slot: u64,
+    slot: u64,
+    committee_index: u64,
+) -> u64 {
+    let slots_since_epoch_start = slot % SLOTS_PER_EPOCH;
+    let committee_since_epoch_start = committees_per_slot * slots_since_epoch_start;
+    (committee_since_epoch_start + committee_index) % beacon_network_spec().attestation_subnet_count
+}
@@ -6,6 +6,7 @@ use ream_consensus_misc::{
     },
 };
 use ream_merkle::{get_root_from_merkle_branch, is_valid_merkle_branch};
+use ream_network_spec::networks::beacon_network_spec;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{FixedVector, typenum::U17};
@@ -68,6 +69,10 @@ impl BlobSidecar {
     }
 }
 
+pub fn compute_subnet_for_blob_sidecar(blob_index: u64) -> u64 {
+    blob_index % beacon_network_spec().blob_sidecar_subnet_count_electra
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
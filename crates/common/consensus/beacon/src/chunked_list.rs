@@ -0,0 +1,355 @@
+//! A list limited to `N` items which is stored in chunks of [CHUNK_SIZE] items.
+//!
+//! The chunks are shared between copies of the list, and a chunk is only copied when one of the
+//! copies changes it, so copying a list of millions of validators copies a few thousand pointers
+//! and changing one validator afterwards copies [CHUNK_SIZE] validators.
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    slice,
+    str::FromStr,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ssz::{BYTES_PER_LENGTH_OFFSET, Decode, DecodeError, Encode, SszEncoder};
+use ssz_types::{Error, VariableList, typenum::Unsigned};
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+
+/// The number of items of a chunk. A multiple of 32, so the chunks of lists of basic types pack
+/// into whole leaves.
+pub const CHUNK_SIZE: usize = 256;
+
+/// Encoded, decoded, hashed and serialized like a [VariableList] of the same limit.
+pub struct ChunkedList<T, N> {
+    /// Every chunk but the last one is full
+    chunks: Vec<Arc<Vec<T>>>,
+    _phantom: PhantomData<N>,
+}
+
+impl<T, N: Unsigned> ChunkedList<T, N> {
+    pub fn new(items: Vec<T>) -> Result<Self, Error> {
+        if items.len() > N::to_usize() {
+            return Err(Error::OutOfBounds {
+                i: items.len(),
+                len: N::to_usize(),
+            });
+        }
+        Ok(Self::from_vec(items))
+    }
+
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+}
+
+impl<T, N> ChunkedList<T, N> {
+    fn from_vec(items: Vec<T>) -> Self {
+        let mut chunks = Vec::with_capacity(items.len().div_ceil(CHUNK_SIZE));
+        let mut items = items.into_iter();
+        loop {
+            let chunk = items.by_ref().take(CHUNK_SIZE).collect::<Vec<_>>();
+            if chunk.is_empty() {
+                break;
+            }
+            chunks.push(Arc::new(chunk));
+        }
+        Self {
+            chunks,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks
+            .last()
+            .map_or(0, |last| (self.chunks.len() - 1) * CHUNK_SIZE + last.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.chunks.get(index / CHUNK_SIZE)?.get(index % CHUNK_SIZE)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            chunks: self.chunks.iter(),
+            chunk: [].iter(),
+            remaining: self.len(),
+        }
+    }
+
+    /// The chunks of the list, which are the same allocations until the list changes them.
+    pub fn chunks(&self) -> &[Arc<Vec<T>>] {
+        &self.chunks
+    }
+}
+
+impl<T: Clone, N: Unsigned> ChunkedList<T, N> {
+    /// Copies the chunk of the item first if it is shared with another list.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let chunk = self.chunks.get_mut(index / CHUNK_SIZE)?;
+        Arc::make_mut(chunk).get_mut(index % CHUNK_SIZE)
+    }
+
+    pub fn push(&mut self, item: T) -> Result<(), Error> {
+        let len = self.len();
+        if len >= N::to_usize() {
+            return Err(Error::OutOfBounds {
+                i: len + 1,
+                len: N::to_usize(),
+            });
+        }
+        match self.chunks.last_mut() {
+            Some(chunk) if chunk.len() < CHUNK_SIZE => Arc::make_mut(chunk).push(item),
+            _ => self.chunks.push(Arc::new(vec![item])),
+        }
+        Ok(())
+    }
+}
+
+/// Iterates over the items of a [ChunkedList] in order.
+pub struct Iter<'a, T> {
+    chunks: slice::Iter<'a, Arc<Vec<T>>>,
+    chunk: slice::Iter<'a, T>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.chunk.next() {
+                self.remaining -= 1;
+                return Some(item);
+            }
+            self.chunk = self.chunks.next()?.iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T, N> IntoIterator for &'a ChunkedList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, N> Index<usize> for ChunkedList<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.chunks[index / CHUNK_SIZE][index % CHUNK_SIZE]
+    }
+}
+
+impl<T: Clone, N: Unsigned> IndexMut<usize> for ChunkedList<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut Arc::make_mut(&mut self.chunks[index / CHUNK_SIZE])[index % CHUNK_SIZE]
+    }
+}
+
+/// Drops the items beyond the limit, like [VariableList].
+impl<T, N: Unsigned> From<Vec<T>> for ChunkedList<T, N> {
+    fn from(mut items: Vec<T>) -> Self {
+        items.truncate(N::to_usize());
+        Self::from_vec(items)
+    }
+}
+
+impl<T, N> Clone for ChunkedList<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, N> Default for ChunkedList<T, N> {
+    fn default() -> Self {
+        Self {
+            chunks: vec![],
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq, N> PartialEq for ChunkedList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .chunks
+                .iter()
+                .zip(&other.chunks)
+                .all(|(chunk, other_chunk)| Arc::ptr_eq(chunk, other_chunk) || chunk == other_chunk)
+    }
+}
+
+impl<T: fmt::Debug, N> fmt::Debug for ChunkedList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Encode, N> Encode for ChunkedList<T, N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        if T::is_ssz_fixed_len() {
+            T::ssz_fixed_len() * self.len()
+        } else {
+            self.iter()
+                .map(|item| item.ssz_bytes_len() + BYTES_PER_LENGTH_OFFSET)
+                .sum()
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if T::is_ssz_fixed_len() {
+            buf.reserve(T::ssz_fixed_len() * self.len());
+            for item in self {
+                item.ssz_append(buf);
+            }
+        } else {
+            let mut encoder = SszEncoder::container(buf, self.len() * BYTES_PER_LENGTH_OFFSET);
+            for item in self {
+                encoder.append(item);
+            }
+            encoder.finalize();
+        }
+    }
+}
+
+impl<T: Decode, N: Unsigned> Decode for ChunkedList<T, N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let items = VariableList::<T, N>::from_ssz_bytes(bytes)?;
+        Ok(Self::from_vec(Vec::from(items)))
+    }
+}
+
+/// Hashed through a [VariableList], so the root follows the limit of the `zkvm` feature. The root
+/// of the lists of a state is computed incrementally by its tree hash cache instead.
+impl<T: Clone + TreeHash, N: Unsigned> TreeHash for ChunkedList<T, N> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        VariableList::<T, N>::from(self.iter().cloned().collect::<Vec<_>>()).tree_hash_root()
+    }
+}
+
+impl<T: Serialize, N> Serialize for ChunkedList<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>, N: Unsigned> Deserialize<'de> for ChunkedList<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(Vec::deserialize(deserializer)?).map_err(|err| {
+            serde::de::Error::custom(format!("Cannot create ChunkedList from items: {err:?}"))
+        })
+    }
+}
+
+/// Serializes the integers of a [ChunkedList] as strings.
+pub mod quoted_chunked_list {
+    use super::{ChunkedList, Deserialize, Deserializer, FromStr, Serializer, Unsigned, fmt};
+
+    pub fn serialize<T, N, S>(list: &ChunkedList<T, N>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ToString,
+        S: Serializer,
+    {
+        serializer.collect_seq(list.iter().map(ToString::to_string))
+    }
+
+    pub fn deserialize<'de, T, N, D>(deserializer: D) -> Result<ChunkedList<T, N>, D::Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+        N: Unsigned,
+        D: Deserializer<'de>,
+    {
+        let items = Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|item| item.parse::<T>().map_err(serde::de::Error::custom))
+            .collect::<Result<Vec<_>, _>>()?;
+        ChunkedList::new(items).map_err(|err| {
+            serde::de::Error::custom(format!("Cannot create ChunkedList from items: {err:?}"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ssz_types::typenum::U1024;
+
+    use super::*;
+
+    type List = ChunkedList<u64, U1024>;
+
+    #[test]
+    fn test_matches_variable_list() {
+        let items = (0..CHUNK_SIZE as u64 * 2 + 3).collect::<Vec<_>>();
+        let list = List::new(items.clone()).unwrap();
+        let variable_list = VariableList::<u64, U1024>::new(items.clone()).unwrap();
+
+        assert_eq!(list.len(), items.len());
+        assert!(list.iter().eq(items.iter()));
+        assert_eq!(list.as_ssz_bytes(), variable_list.as_ssz_bytes());
+        assert_eq!(List::from_ssz_bytes(&list.as_ssz_bytes()).unwrap(), list);
+        assert_eq!(list.tree_hash_root(), variable_list.tree_hash_root());
+        assert!(List::new((0..1025).collect()).is_err());
+    }
+
+    #[test]
+    fn test_copies_only_the_changed_chunk() {
+        let mut list = List::new((0..CHUNK_SIZE as u64 * 3).collect()).unwrap();
+        let copy = list.clone();
+
+        list[CHUNK_SIZE + 1] = 0;
+        *list.get_mut(5).unwrap() += 1;
+        assert!(Arc::ptr_eq(&list.chunks()[2], &copy.chunks()[2]));
+        assert!(!Arc::ptr_eq(&list.chunks()[0], &copy.chunks()[0]));
+        assert!(!Arc::ptr_eq(&list.chunks()[1], &copy.chunks()[1]));
+        assert_eq!(copy[CHUNK_SIZE + 1], CHUNK_SIZE as u64 + 1);
+        assert_eq!(list[5], 6);
+        assert_ne!(list, copy);
+
+        list.push(7).unwrap();
+        assert_eq!(list.len(), CHUNK_SIZE * 3 + 1);
+        assert_eq!(list.get(CHUNK_SIZE * 3), Some(&7));
+        assert_eq!(copy.get(CHUNK_SIZE * 3), None);
+    }
+}
@@ -0,0 +1,81 @@
+use alloy_primitives::U256;
+use anyhow::ensure;
+use ethereum_hashing::hash;
+use ream_consensus_misc::constants::beacon::{NUMBER_OF_COLUMNS, NUMBER_OF_CUSTODY_GROUPS};
+
+/// Return the custody groups a node with `node_id` is responsible for, sorted ascending.
+pub fn get_custody_groups(node_id: U256, custody_group_count: u64) -> anyhow::Result<Vec<u64>> {
+    ensure!(
+        custody_group_count <= NUMBER_OF_CUSTODY_GROUPS,
+        "custody group count {custody_group_count} exceeds {NUMBER_OF_CUSTODY_GROUPS}"
+    );
+
+    // Skip the hashing when the node custodies every group
+    if custody_group_count == NUMBER_OF_CUSTODY_GROUPS {
+        return Ok((0..NUMBER_OF_CUSTODY_GROUPS).collect());
+    }
+
+    let mut current_id = node_id;
+    let mut custody_groups = vec![];
+    while (custody_groups.len() as u64) < custody_group_count {
+        let digest = hash(&current_id.to_le_bytes::<32>());
+        let mut first_bytes = [0u8; 8];
+        first_bytes.copy_from_slice(&digest[0..8]);
+        let custody_group = u64::from_le_bytes(first_bytes) % NUMBER_OF_CUSTODY_GROUPS;
+        if !custody_groups.contains(&custody_group) {
+            custody_groups.push(custody_group);
+        }
+        current_id = current_id.wrapping_add(U256::from(1));
+    }
+
+    custody_groups.sort_unstable();
+    Ok(custody_groups)
+}
+
+/// Return the columns which belong to `custody_group`.
+pub fn compute_columns_for_custody_group(custody_group: u64) -> anyhow::Result<Vec<u64>> {
+    ensure!(
+        custody_group < NUMBER_OF_CUSTODY_GROUPS,
+        "custody group {custody_group} must be less than {NUMBER_OF_CUSTODY_GROUPS}"
+    );
+    let columns_per_group = NUMBER_OF_COLUMNS / NUMBER_OF_CUSTODY_GROUPS;
+    Ok((0..columns_per_group)
+        .map(|index| NUMBER_OF_CUSTODY_GROUPS * index + custody_group)
+        .collect())
+}
+
+/// Return the columns a node with `node_id` custodies, sorted ascending.
+pub fn get_custody_columns(node_id: U256, custody_group_count: u64) -> anyhow::Result<Vec<u64>> {
+    let mut columns = get_custody_groups(node_id, custody_group_count)?
+        .into_iter()
+        .map(compute_columns_for_custody_group)
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .concat();
+    columns.sort_unstable();
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_misc::constants::beacon::CUSTODY_REQUIREMENT;
+
+    use super::*;
+
+    #[test]
+    fn test_get_custody_groups() {
+        let custody_groups = get_custody_groups(U256::MAX, CUSTODY_REQUIREMENT).unwrap();
+        assert_eq!(custody_groups.len() as u64, CUSTODY_REQUIREMENT);
+        assert!(custody_groups.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(
+            custody_groups
+                .iter()
+                .all(|custody_group| *custody_group < NUMBER_OF_CUSTODY_GROUPS)
+        );
+
+        assert_eq!(
+            get_custody_groups(U256::ZERO, NUMBER_OF_CUSTODY_GROUPS).unwrap(),
+            (0..NUMBER_OF_CUSTODY_GROUPS).collect::<Vec<_>>()
+        );
+        assert!(get_custody_groups(U256::ZERO, NUMBER_OF_CUSTODY_GROUPS + 1).is_err());
+    }
+}
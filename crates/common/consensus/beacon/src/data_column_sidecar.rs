@@ -0,0 +1,87 @@
+use alloy_primitives::B256;
+use ream_consensus_misc::{
+    beacon_block_header::SignedBeaconBlockHeader,
+    constants::beacon::{
+        BLOB_KZG_COMMITMENTS_INDEX, DATA_COLUMN_SIDECAR_SUBNET_COUNT,
+        KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH, NUMBER_OF_COLUMNS,
+    },
+};
+use ream_merkle::is_valid_merkle_branch;
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{
+    FixedVector, VariableList,
+    serde_utils::hex_fixed_vec,
+    typenum::{U4, U128, U2048, U4096},
+};
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+
+use crate::polynomial_commitments::{kzg_commitment::KZGCommitment, kzg_proof::KZGProof};
+
+/// A cell of an extended blob, `FIELD_ELEMENTS_PER_CELL` field elements long.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Decode, Encode, TreeHash, Default,
+)]
+#[serde(transparent)]
+pub struct Cell {
+    #[serde(with = "hex_fixed_vec")]
+    pub inner: FixedVector<u8, U2048>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Encode, Decode, TreeHash)]
+pub struct DataColumnSidecar {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    pub column: VariableList<Cell, U4096>,
+    pub kzg_commitments: VariableList<KZGCommitment, U4096>,
+    pub kzg_proofs: VariableList<KZGProof, U4096>,
+    pub signed_block_header: SignedBeaconBlockHeader,
+    pub kzg_commitments_inclusion_proof: FixedVector<B256, U4>,
+}
+
+impl DataColumnSidecar {
+    pub fn block_root(&self) -> B256 {
+        self.signed_block_header.message.tree_hash_root()
+    }
+
+    /// Verify the data column sidecar is well formed: the column index is in range and there is
+    /// a cell and proof for each commitment.
+    pub fn verify_data_column_sidecar(&self) -> bool {
+        self.index < NUMBER_OF_COLUMNS
+            && !self.kzg_commitments.is_empty()
+            && self.column.len() == self.kzg_commitments.len()
+            && self.column.len() == self.kzg_proofs.len()
+    }
+
+    /// Verify the KZG commitments are included in the body of the block the sidecar is for.
+    pub fn verify_data_column_sidecar_inclusion_proof(&self) -> bool {
+        is_valid_merkle_branch(
+            self.kzg_commitments.tree_hash_root(),
+            &self.kzg_commitments_inclusion_proof,
+            KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH,
+            BLOB_KZG_COMMITMENTS_INDEX,
+            self.signed_block_header.message.body_root,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, Encode, Decode)]
+pub struct DataColumnsByRootIdentifier {
+    pub block_root: B256,
+    pub columns: VariableList<u64, U128>,
+}
+
+impl DataColumnsByRootIdentifier {
+    pub fn new(block_root: B256, columns: Vec<u64>) -> anyhow::Result<Self> {
+        Ok(Self {
+            block_root,
+            columns: VariableList::new(columns)
+                .map_err(|err| anyhow::anyhow!("Too many columns were requested: {err:?}"))?,
+        })
+    }
+}
+
+pub fn compute_subnet_for_data_column_sidecar(column_index: u64) -> u64 {
+    column_index % DATA_COLUMN_SIDECAR_SUBNET_COUNT
+}
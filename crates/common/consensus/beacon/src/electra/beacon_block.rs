@@ -1,8 +1,12 @@
 use alloy_primitives::B256;
 use anyhow::ensure;
 use ream_bls::BLSSignature;
-use ream_consensus_misc::beacon_block_header::{BeaconBlockHeader, SignedBeaconBlockHeader};
+use ream_consensus_misc::{
+    beacon_block_header::{BeaconBlockHeader, SignedBeaconBlockHeader},
+    slot_epoch::Slot,
+};
 use serde::{Deserialize, Serialize};
+use ssz::{BYTES_PER_LENGTH_OFFSET, Decode, DecodeError, read_offset};
 use ssz_derive::{Decode, Encode};
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
@@ -26,6 +30,36 @@ pub struct SignedBeaconBlock {
 }
 
 impl SignedBeaconBlock {
+    /// Decode only the ``slot`` and the ``parent_root`` of an SSZ encoded block, without decoding
+    /// the body.
+    pub fn decode_slot_and_parent_root(bytes: &[u8]) -> Result<(u64, B256), DecodeError> {
+        // The fixed part of the signed block starts with the offset of the message, whose fixed
+        // part starts with the slot, the proposer index and the parent root.
+        let message_offset = bytes
+            .get(..BYTES_PER_LENGTH_OFFSET)
+            .ok_or(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BYTES_PER_LENGTH_OFFSET,
+            })
+            .and_then(read_offset)?;
+        let parent_root_position = message_offset + 2 * <u64 as Decode>::ssz_fixed_len();
+        let parent_root_end = parent_root_position + <B256 as Decode>::ssz_fixed_len();
+
+        let (Some(slot_bytes), Some(parent_root_bytes)) = (
+            bytes.get(message_offset..message_offset + <u64 as Decode>::ssz_fixed_len()),
+            bytes.get(parent_root_position..parent_root_end),
+        ) else {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: parent_root_end,
+            });
+        };
+        Ok((
+            u64::from_ssz_bytes(slot_bytes)?,
+            B256::from_ssz_bytes(parent_root_bytes)?,
+        ))
+    }
+
     pub fn signed_header(&self) -> SignedBeaconBlockHeader {
         SignedBeaconBlockHeader {
             message: BeaconBlockHeader {
@@ -113,8 +147,7 @@ impl SignedBeaconBlock {
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, Default,
 )]
 pub struct BeaconBlock {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
     #[serde(with = "serde_utils::quoted_u64")]
     pub proposer_index: u64,
     pub parent_root: B256,
@@ -12,7 +12,8 @@ use ethereum_hashing::{hash, hash_fixed};
 use itertools::Itertools;
 use ream_bls::{
     BLSSignature, PublicKey,
-    traits::{Aggregatable, Verifiable},
+    signature::SignatureSet,
+    traits::{Aggregatable, BatchVerifiable, Verifiable},
 };
 use ream_consensus_misc::{
     attestation_data::AttestationData,
@@ -55,31 +56,35 @@ use ream_consensus_misc::{
         compute_epoch_at_slot, compute_shuffled_index, compute_signing_root,
         compute_start_slot_at_epoch, get_committee_indices, is_sorted_and_unique,
     },
+    slot_epoch::{Epoch, Slot},
     validator::Validator,
 };
 use ream_merkle::{generate_proof, is_valid_merkle_branch, merkle_tree};
 use ream_network_spec::networks::beacon_network_spec;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
+use ssz::{BYTES_PER_LENGTH_OFFSET, Decode, DecodeError, read_offset};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{
-    BitVector, FixedVector, VariableList,
-    serde_utils::{quoted_u64_fixed_vec, quoted_u64_var_list},
+    BitList, BitVector, FixedVector, VariableList,
+    serde_utils::quoted_u64_fixed_vec,
     typenum::{U4, U2048, U8192, U65536, U262144, U16777216, U134217728},
 };
-use tree_hash::TreeHash;
-use tree_hash_derive::TreeHash;
+use tracing::instrument;
+use tree_hash::{PackedEncoding, TreeHash, TreeHashType};
 
 use super::{
     beacon_block::{BeaconBlock, SignedBeaconBlock},
     beacon_block_body::BeaconBlockBody,
     execution_payload::ExecutionPayload,
     execution_payload_header::ExecutionPayloadHeader,
+    tree_hash_cache::BeaconStateTreeHashCache,
     zkvm_types::ValidatorRegistryLimit,
 };
 use crate::{
     attestation::Attestation,
     attester_slashing::AttesterSlashing,
     bls_to_execution_change::SignedBLSToExecutionChange,
+    chunked_list::{ChunkedList, quoted_chunked_list},
     consolidation_request::ConsolidationRequest,
     deposit::Deposit,
     deposit_request::DepositRequest,
@@ -92,6 +97,8 @@ use crate::{
     pending_partial_withdrawal::PendingPartialWithdrawal,
     predicates::is_slashable_attestation_data,
     proposer_slashing::ProposerSlashing,
+    shuffling::EpochShuffling,
+    single_attestation::SingleAttestation,
     sync_aggregate::SyncAggregate,
     sync_committee::SyncCommittee,
     voluntary_exit::SignedVoluntaryExit,
@@ -99,39 +106,6 @@ use crate::{
     withdrawal_request::WithdrawalRequest,
 };
 
-pub mod quoted_u8_var_list {
-    use super::{
-        Deserialize, Deserializer, Serialize, Serializer, ValidatorRegistryLimit, VariableList,
-    };
-
-    pub fn serialize<S>(
-        value: &VariableList<u8, ValidatorRegistryLimit>,
-        serializer: S,
-    ) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let string_vec: Vec<String> = value.iter().map(|v| v.to_string()).collect();
-        string_vec.serialize(serializer)
-    }
-
-    pub fn deserialize<'de, D>(
-        deserializer: D,
-    ) -> Result<VariableList<u8, ValidatorRegistryLimit>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let string_vec: Vec<String> = Vec::deserialize(deserializer)?;
-        let bytes = string_vec
-            .into_iter()
-            .map(|s| s.parse::<u8>().map_err(serde::de::Error::custom))
-            .collect::<Result<Vec<_>, _>>()?;
-        VariableList::new(bytes).map_err(|err| {
-            serde::de::Error::custom(format!("Cannot create VariableList from bytes: {err:?}"))
-        })
-    }
-}
-
 /// The BeaconState contains some "zkvm" features that addresses where 32-bit zkVMs would fail
 /// on constructing a VariableList larger than 2^32 size (i.e. 2^40). When "zkvm" feature
 /// is enabled, it would construct the BeaconState with 2^29 list instead.
@@ -140,14 +114,13 @@ pub mod quoted_u8_var_list {
 /// https://github.com/ReamLabs/ssz_types/tree/magic-extended-list
 /// where the crate would detect 2^29 as a magic number when computing the root hash,
 /// and it will compute as a 2^40 list root instead.
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct BeaconState {
     // Versioning
     #[serde(with = "serde_utils::quoted_u64")]
     pub genesis_time: u64,
     pub genesis_validators_root: B256,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
     pub fork: Fork,
 
     // History
@@ -164,9 +137,9 @@ pub struct BeaconState {
     pub eth1_deposit_index: u64,
 
     // Registry
-    pub validators: VariableList<Validator, ValidatorRegistryLimit>,
-    #[serde(with = "quoted_u64_var_list")]
-    pub balances: VariableList<u64, ValidatorRegistryLimit>,
+    pub validators: ChunkedList<Validator, ValidatorRegistryLimit>,
+    #[serde(with = "quoted_chunked_list")]
+    pub balances: ChunkedList<u64, ValidatorRegistryLimit>,
 
     // Randomness
     pub randao_mixes: FixedVector<B256, U65536>,
@@ -176,10 +149,10 @@ pub struct BeaconState {
     pub slashings: FixedVector<u64, U8192>,
 
     // Participation
-    #[serde(with = "quoted_u8_var_list")]
-    pub previous_epoch_participation: VariableList<u8, ValidatorRegistryLimit>,
-    #[serde(with = "quoted_u8_var_list")]
-    pub current_epoch_participation: VariableList<u8, ValidatorRegistryLimit>,
+    #[serde(with = "quoted_chunked_list")]
+    pub previous_epoch_participation: ChunkedList<u8, ValidatorRegistryLimit>,
+    #[serde(with = "quoted_chunked_list")]
+    pub current_epoch_participation: ChunkedList<u8, ValidatorRegistryLimit>,
 
     // Finality
     pub justification_bits: BitVector<U4>,
@@ -188,8 +161,8 @@ pub struct BeaconState {
     pub finalized_checkpoint: Checkpoint,
 
     // Inactivity
-    #[serde(with = "quoted_u64_var_list")]
-    pub inactivity_scores: VariableList<u64, ValidatorRegistryLimit>,
+    #[serde(with = "quoted_chunked_list")]
+    pub inactivity_scores: ChunkedList<u64, ValidatorRegistryLimit>,
 
     // Sync
     pub current_sync_committee: Arc<SyncCommittee>,
@@ -223,12 +196,75 @@ pub struct BeaconState {
     pub pending_deposits: VariableList<PendingDeposit, U134217728>,
     pub pending_partial_withdrawals: VariableList<PendingPartialWithdrawal, U134217728>,
     pub pending_consolidations: VariableList<PendingConsolidation, U262144>,
+
+    /// Not part of the state, only speeds up computing its root
+    #[serde(skip)]
+    #[ssz(skip_serializing, skip_deserializing)]
+    pub tree_hash_cache: BeaconStateTreeHashCache,
 }
 
+/// The validator registry and the balances of a [BeaconState].
+pub type ValidatorsAndBalances = (
+    VariableList<Validator, ValidatorRegistryLimit>,
+    VariableList<u64, ValidatorRegistryLimit>,
+);
+
 impl BeaconState {
+    /// Decode only the ``validators`` and ``balances`` of an SSZ encoded state, without decoding
+    /// the rest of the state.
+    pub fn decode_validators_and_balances(
+        bytes: &[u8],
+    ) -> Result<ValidatorsAndBalances, DecodeError> {
+        // The fixed part of the state holds the fixed size fields and, in field order, the offsets
+        // of the variable size fields.
+        let validators_offset_position = <u64 as Decode>::ssz_fixed_len()
+            + <B256 as Decode>::ssz_fixed_len()
+            + <u64 as Decode>::ssz_fixed_len()
+            + <Fork as Decode>::ssz_fixed_len()
+            + <BeaconBlockHeader as Decode>::ssz_fixed_len()
+            + 2 * <FixedVector<B256, U8192> as Decode>::ssz_fixed_len()
+            + BYTES_PER_LENGTH_OFFSET
+            + <Eth1Data as Decode>::ssz_fixed_len()
+            + BYTES_PER_LENGTH_OFFSET
+            + <u64 as Decode>::ssz_fixed_len();
+        let balances_offset_position = validators_offset_position + BYTES_PER_LENGTH_OFFSET;
+        let previous_epoch_participation_offset_position = balances_offset_position
+            + BYTES_PER_LENGTH_OFFSET
+            + <FixedVector<B256, U65536> as Decode>::ssz_fixed_len()
+            + <FixedVector<u64, U8192> as Decode>::ssz_fixed_len();
+
+        let read_offset_at = |position: usize| {
+            bytes
+                .get(position..position + BYTES_PER_LENGTH_OFFSET)
+                .ok_or(DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: position + BYTES_PER_LENGTH_OFFSET,
+                })
+                .and_then(read_offset)
+        };
+        let validators_offset = read_offset_at(validators_offset_position)?;
+        let balances_offset = read_offset_at(balances_offset_position)?;
+        let previous_epoch_participation_offset =
+            read_offset_at(previous_epoch_participation_offset_position)?;
+
+        let (Some(validators_bytes), Some(balances_bytes)) = (
+            bytes.get(validators_offset..balances_offset),
+            bytes.get(balances_offset..previous_epoch_participation_offset),
+        ) else {
+            return Err(DecodeError::BytesInvalid(format!(
+                "Invalid validators and balances offsets: {validators_offset}, {balances_offset}, {previous_epoch_participation_offset}"
+            )));
+        };
+
+        Ok((
+            VariableList::from_ssz_bytes(validators_bytes)?,
+            VariableList::from_ssz_bytes(balances_bytes)?,
+        ))
+    }
+
     /// Return the current epoch.
     pub fn get_current_epoch(&self) -> u64 {
-        compute_epoch_at_slot(self.slot)
+        compute_epoch_at_slot(self.slot.as_u64())
     }
 
     pub fn get_eth1_vote(&self, eth1_chain: &[&Eth1Block]) -> Eth1Data {
@@ -268,7 +304,7 @@ impl BeaconState {
 
     pub fn voting_period_start_time(&self) -> u64 {
         let eth1_voting_period_start_slot =
-            self.slot - self.slot % (EPOCHS_PER_ETH1_VOTING_PERIOD * SLOTS_PER_EPOCH);
+            self.slot.as_u64() - self.slot % (EPOCHS_PER_ETH1_VOTING_PERIOD * SLOTS_PER_EPOCH);
         self.compute_timestamp_at_slot(eth1_voting_period_start_slot)
     }
 
@@ -390,7 +426,7 @@ impl BeaconState {
     pub fn get_beacon_proposer_index(&self, slot: Option<u64>) -> anyhow::Result<u64> {
         let (epoch, slot) = match slot {
             Some(slot) => (compute_epoch_at_slot(slot), slot),
-            None => (self.get_current_epoch(), self.slot),
+            None => (self.get_current_epoch(), self.slot.as_u64()),
         };
         let seed = B256::from(hash_fixed(
             &[
@@ -457,6 +493,25 @@ impl BeaconState {
         )
     }
 
+    /// Compute every beacon committee of ``epoch`` at once.
+    pub fn compute_epoch_shuffling(&self, epoch: u64) -> anyhow::Result<EpochShuffling> {
+        let committees_per_slot = self.get_committee_count_per_slot(epoch);
+        let committee_count = committees_per_slot * SLOTS_PER_EPOCH;
+        let active_validator_indices = self.get_active_validator_indices(epoch);
+        let seed = self.get_seed(epoch, DOMAIN_BEACON_ATTESTER);
+
+        let committees = (0..committee_count)
+            .map(|index| compute_committee(&active_validator_indices, seed, index, committee_count))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(EpochShuffling {
+            epoch,
+            seed,
+            committees_per_slot,
+            committees,
+        })
+    }
+
     /// Return the committee assignment in the ``epoch`` for ``validator_index``.
     /// ``assignment`` returned is a tuple of the following form:
     ///     * ``assignment[0]`` is the list of validators in the committee
@@ -504,7 +559,7 @@ impl BeaconState {
 
         let domain = self.get_domain(
             DOMAIN_BEACON_ATTESTER,
-            Some(indexed_attestation.data.target.epoch),
+            Some(indexed_attestation.data.target.epoch.as_u64()),
         );
         let signing_root = compute_signing_root(&indexed_attestation.data, domain);
 
@@ -531,7 +586,8 @@ impl BeaconState {
         let mut output = HashSet::new();
         let mut committee_offset = 0;
         for committee_index in get_committee_indices(&attestation.committee_bits) {
-            let committee = self.get_beacon_committee(attestation.data.slot, committee_index)?;
+            let committee =
+                self.get_beacon_committee(attestation.data.slot.as_u64(), committee_index)?;
 
             let mut committee_attesters = HashSet::new();
             for (i, attester_index) in committee.iter().enumerate() {
@@ -567,6 +623,71 @@ impl BeaconState {
         })
     }
 
+    /// Validate ``single_attestation`` and convert it into the committee based ``Attestation``
+    /// it represents, with a single aggregation bit set for the attester.
+    pub fn single_attestation_to_attestation(
+        &self,
+        single_attestation: &SingleAttestation,
+    ) -> anyhow::Result<Attestation> {
+        let data = &single_attestation.data;
+        ensure!(
+            data.target.epoch == self.get_previous_epoch()
+                || data.target.epoch == self.get_current_epoch(),
+            "Target epoch must be the previous or current epoch"
+        );
+        ensure!(
+            data.target.epoch == compute_epoch_at_slot(data.slot.as_u64()),
+            "Target epoch must match the computed epoch at slot"
+        );
+        ensure!(data.index == 0, "Attestation data index must be 0");
+
+        let committee_index = single_attestation.committee_index;
+        ensure!(
+            committee_index < self.get_committee_count_per_slot(data.target.epoch.as_u64()),
+            "Committee index must be within bounds"
+        );
+        let committee = self.get_beacon_committee(data.slot.as_u64(), committee_index)?;
+        let position = committee
+            .iter()
+            .position(|&index| index == single_attestation.attester_index)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Validator {} is not a member of committee {committee_index}",
+                    single_attestation.attester_index
+                )
+            })?;
+
+        let validator = self
+            .validators
+            .get(single_attestation.attester_index as usize)
+            .ok_or_else(|| anyhow!("Invalid attester index"))?;
+        let domain = self.get_domain(DOMAIN_BEACON_ATTESTER, Some(data.target.epoch.as_u64()));
+        ensure!(
+            single_attestation.signature.verify(
+                &validator.public_key,
+                compute_signing_root(data, domain).as_ref()
+            )?,
+            "Invalid attestation signature"
+        );
+
+        let mut aggregation_bits = BitList::with_capacity(committee.len())
+            .map_err(|err| anyhow!("Failed to create aggregation_bits: {err:?}"))?;
+        aggregation_bits
+            .set(position, true)
+            .map_err(|err| anyhow!("Failed to set aggregation_bit: {err:?}"))?;
+        let mut committee_bits = BitVector::new();
+        committee_bits
+            .set(committee_index as usize, true)
+            .map_err(|err| anyhow!("Failed to set committee_bit: {err:?}"))?;
+
+        Ok(Attestation {
+            aggregation_bits,
+            data: data.clone(),
+            signature: single_attestation.signature.clone(),
+            committee_bits,
+        })
+    }
+
     /// Increase the validator balance at index ``index`` by ``delta``.
     pub fn increase_balance(&mut self, index: u64, delta: u64) -> anyhow::Result<()> {
         if let Some(balance) = self.balances.get_mut(index as usize) {
@@ -659,6 +780,106 @@ impl BeaconState {
         Ok(())
     }
 
+    /// Builds a genesis state in which every validator of `public_keys` is active from genesis
+    /// with [MIN_ACTIVATION_BALANCE] and BLS withdrawal credentials, skipping the deposit
+    /// processing of ``initialize_beacon_state_from_eth1``. Used to quick-start local devnets.
+    pub fn genesis_from_public_keys(
+        genesis_time: u64,
+        eth1_block_hash: B256,
+        public_keys: Vec<PublicKey>,
+    ) -> anyhow::Result<Self> {
+        let fork_version = beacon_network_spec().electra_fork_version;
+        let validator_count = public_keys.len();
+        let validators = public_keys
+            .into_iter()
+            .map(|public_key| {
+                let mut withdrawal_credentials = hash_fixed(&public_key.inner);
+                withdrawal_credentials[..1].copy_from_slice(BLS_WITHDRAWAL_PREFIX);
+                Validator {
+                    public_key,
+                    withdrawal_credentials: withdrawal_credentials.into(),
+                    effective_balance: MIN_ACTIVATION_BALANCE,
+                    slashed: false,
+                    activation_eligibility_epoch: GENESIS_EPOCH,
+                    activation_epoch: GENESIS_EPOCH,
+                    exit_epoch: FAR_FUTURE_EPOCH,
+                    withdrawable_epoch: FAR_FUTURE_EPOCH,
+                }
+            })
+            .collect::<Vec<_>>();
+        let empty_sync_committee = Arc::new(SyncCommittee {
+            public_keys: FixedVector::from(vec![]),
+            aggregate_public_key: PublicKey::default(),
+        });
+
+        let mut state = Self {
+            genesis_time,
+            genesis_validators_root: B256::ZERO,
+            slot: Slot::new(GENESIS_SLOT),
+            fork: Fork {
+                previous_version: fork_version,
+                current_version: fork_version,
+                epoch: Epoch::new(GENESIS_EPOCH),
+            },
+            latest_block_header: BeaconBlockHeader {
+                body_root: BeaconBlockBody::default().tree_hash_root(),
+                ..Default::default()
+            },
+            block_roots: FixedVector::default(),
+            state_roots: FixedVector::default(),
+            historical_roots: VariableList::default(),
+            eth1_data: Eth1Data {
+                deposit_root: B256::ZERO,
+                deposit_count: validator_count as u64,
+                block_hash: eth1_block_hash,
+            },
+            eth1_data_votes: VariableList::default(),
+            eth1_deposit_index: validator_count as u64,
+            validators: ChunkedList::new(validators)
+                .map_err(|err| anyhow!("Too many validators: {err:?}"))?,
+            balances: vec![MIN_ACTIVATION_BALANCE; validator_count].into(),
+            randao_mixes: FixedVector::from(vec![
+                eth1_block_hash;
+                EPOCHS_PER_HISTORICAL_VECTOR as usize
+            ]),
+            slashings: FixedVector::default(),
+            previous_epoch_participation: vec![0; validator_count].into(),
+            current_epoch_participation: vec![0; validator_count].into(),
+            justification_bits: BitVector::new(),
+            previous_justified_checkpoint: Checkpoint::default(),
+            current_justified_checkpoint: Checkpoint::default(),
+            finalized_checkpoint: Checkpoint::default(),
+            inactivity_scores: vec![0; validator_count].into(),
+            current_sync_committee: empty_sync_committee.clone(),
+            next_sync_committee: empty_sync_committee,
+            latest_execution_payload_header: ExecutionPayloadHeader {
+                block_hash: eth1_block_hash,
+                ..Default::default()
+            },
+            next_withdrawal_index: 0,
+            next_withdrawal_validator_index: 0,
+            historical_summaries: VariableList::default(),
+            deposit_requests_start_index: UNSET_DEPOSIT_REQUESTS_START_INDEX,
+            deposit_balance_to_consume: 0,
+            exit_balance_to_consume: 0,
+            earliest_exit_epoch: GENESIS_EPOCH,
+            consolidation_balance_to_consume: 0,
+            earliest_consolidation_epoch: GENESIS_EPOCH,
+            pending_deposits: VariableList::default(),
+            pending_partial_withdrawals: VariableList::default(),
+            pending_consolidations: VariableList::default(),
+            tree_hash_cache: BeaconStateTreeHashCache::default(),
+        };
+
+        state.genesis_validators_root = state.validators.tree_hash_root();
+        // The current and next sync committees are the same at genesis
+        let sync_committee = Arc::new(state.get_next_sync_committee()?);
+        state.current_sync_committee = sync_committee.clone();
+        state.next_sync_committee = sync_committee;
+
+        Ok(state)
+    }
+
     pub fn is_valid_genesis_state(&self) -> bool {
         if self.genesis_time < MIN_GENESIS_TIME {
             return false;
@@ -762,7 +983,7 @@ impl BeaconState {
     }
 
     pub fn get_finality_delay(&self) -> u64 {
-        self.get_previous_epoch() - self.finalized_checkpoint.epoch
+        self.get_previous_epoch() - self.finalized_checkpoint.epoch.as_u64()
     }
 
     pub fn is_in_inactivity_leak(&self) -> bool {
@@ -798,10 +1019,10 @@ impl BeaconState {
             self.previous_justified_checkpoint
         };
         let is_matching_source = data.source == justified_checkpoint;
-        let is_matching_target =
-            is_matching_source && data.target.root == self.get_block_root(data.target.epoch)?;
+        let is_matching_target = is_matching_source
+            && data.target.root == self.get_block_root(data.target.epoch.as_u64())?;
         let is_matching_head = is_matching_target
-            && data.beacon_block_root == self.get_block_root_at_slot(data.slot)?;
+            && data.beacon_block_root == self.get_block_root_at_slot(data.slot.as_u64())?;
         ensure!(is_matching_source);
 
         let mut participation_flag_indices = vec![];
@@ -1355,7 +1576,7 @@ impl BeaconState {
                 withdrawal_credentials: deposit_request.withdrawal_credentials,
                 amount: deposit_request.amount,
                 signature: deposit_request.signature.clone(),
-                slot: self.slot,
+                slot: self.slot.as_u64(),
             })
             .map_err(|err| anyhow!("Failed to push PendingDeposit to pending_deposits {err:?}"))?;
 
@@ -1612,7 +1833,7 @@ impl BeaconState {
         ] {
             let domain = self.get_domain(
                 DOMAIN_BEACON_PROPOSER,
-                Some(compute_epoch_at_slot(signed_header.message.slot)),
+                Some(compute_epoch_at_slot(signed_header.message.slot.as_u64())),
             );
 
             let signing_root = compute_signing_root(&signed_header.message, domain);
@@ -1745,7 +1966,7 @@ impl BeaconState {
             }
         }
 
-        let previous_slot = max(self.slot, 1) - 1;
+        let previous_slot = max(self.slot.as_u64(), 1) - 1;
         let domain = self.get_domain(
             DOMAIN_SYNC_COMMITTEE,
             Some(compute_epoch_at_slot(previous_slot)),
@@ -1920,7 +2141,7 @@ impl BeaconState {
 
         if previous_epoch_target_balance * 3 >= total_active_balance * 2 {
             self.current_justified_checkpoint = Checkpoint {
-                epoch: previous_epoch,
+                epoch: Epoch::new(previous_epoch),
                 root: self.get_block_root(previous_epoch)?,
             };
             self.justification_bits
@@ -1930,7 +2151,7 @@ impl BeaconState {
 
         if current_epoch_target_balance * 3 >= total_active_balance * 2 {
             self.current_justified_checkpoint = Checkpoint {
-                epoch: current_epoch,
+                epoch: Epoch::new(current_epoch),
                 root: self.get_block_root(current_epoch)?,
             };
             self.justification_bits
@@ -1984,8 +2205,10 @@ impl BeaconState {
     }
 
     pub fn process_effective_balance_updates(&mut self) -> anyhow::Result<()> {
-        // Update effective balances with hysteresis
-        for (index, validator) in self.validators.iter_mut().enumerate() {
+        // Update effective balances with hysteresis. The chunk of a validator is only copied if it
+        // is shared and its effective balance changes.
+        for index in 0..self.validators.len() {
+            let validator = &self.validators[index];
             let balance = self.balances[index];
             let hysteresis_increment = EFFECTIVE_BALANCE_INCREMENT / HYSTERESIS_QUOTIENT;
             let downward_threshold = hysteresis_increment * HYSTERESIS_DOWNWARD_MULTIPLIER;
@@ -1994,8 +2217,9 @@ impl BeaconState {
             if balance + downward_threshold < validator.effective_balance
                 || validator.effective_balance + upward_threshold < balance
             {
-                validator.effective_balance = (balance - balance % EFFECTIVE_BALANCE_INCREMENT)
+                let effective_balance = (balance - balance % EFFECTIVE_BALANCE_INCREMENT)
                     .min(validator.get_max_effective_balance());
+                self.validators[index].effective_balance = effective_balance;
             }
         }
 
@@ -2056,7 +2280,7 @@ impl BeaconState {
         );
 
         ensure!(
-            data.target.epoch == compute_epoch_at_slot(data.slot),
+            data.target.epoch == compute_epoch_at_slot(data.slot.as_u64()),
             "Target epoch must match the computed epoch at slot"
         );
 
@@ -2066,7 +2290,7 @@ impl BeaconState {
         );
 
         ensure!(
-            data.index < self.get_committee_count_per_slot(data.target.epoch),
+            data.index < self.get_committee_count_per_slot(data.target.epoch.as_u64()),
             "Committee index must be within bounds"
         );
 
@@ -2074,8 +2298,10 @@ impl BeaconState {
         let committee_indices = get_committee_indices(&attestation.committee_bits);
         let mut committee_offset = 0;
         for committee_index in committee_indices {
-            ensure!(committee_index < self.get_committee_count_per_slot(data.target.epoch));
-            let committee = self.get_beacon_committee(data.slot, committee_index)?;
+            ensure!(
+                committee_index < self.get_committee_count_per_slot(data.target.epoch.as_u64())
+            );
+            let committee = self.get_beacon_committee(data.slot.as_u64(), committee_index)?;
             let mut committee_attesters = HashSet::new();
             for (i, &attester_index) in committee.iter().enumerate() {
                 if attestation
@@ -2229,7 +2455,7 @@ impl BeaconState {
         let mut next_deposit_index = 0;
         let mut deposits_to_postpone = vec![];
         let mut is_churn_limit_reached = false;
-        let finalized_slot = compute_start_slot_at_epoch(self.finalized_checkpoint.epoch);
+        let finalized_slot = self.finalized_checkpoint.epoch.start_slot().as_u64();
 
         for index in 0..self.pending_deposits.len() {
             let Some(deposit) = self.pending_deposits.get(index).cloned() else {
@@ -2429,9 +2655,45 @@ impl BeaconState {
             .map_err(|err| anyhow!("Invalid block signature: {err}"))
     }
 
+    /// Verify the proposer signatures of ``signed_blocks`` together, against the proposers and
+    /// the fork of this state. Returns false when any of them isn't valid for this state, e.g.
+    /// because a proposer joined the registry or the fork changed after it.
+    pub fn verify_block_signatures_batch(
+        &self,
+        signed_blocks: &[SignedBeaconBlock],
+    ) -> anyhow::Result<bool> {
+        let mut signing_roots = Vec::with_capacity(signed_blocks.len());
+        let mut public_keys = Vec::with_capacity(signed_blocks.len());
+        for signed_block in signed_blocks {
+            let header = signed_block.signed_header().message;
+            let Some(proposer) = self.validators.get(header.proposer_index as usize) else {
+                return Ok(false);
+            };
+            let domain = self.get_domain(
+                DOMAIN_BEACON_PROPOSER,
+                Some(compute_epoch_at_slot(header.slot.as_u64())),
+            );
+            signing_roots.push(compute_signing_root(header, domain));
+            public_keys.push(&proposer.public_key);
+        }
+
+        let signature_sets = signed_blocks
+            .iter()
+            .zip(&public_keys)
+            .zip(&signing_roots)
+            .map(|((signed_block, public_key), signing_root)| SignatureSet {
+                signature: &signed_block.signature,
+                public_key,
+                message: signing_root.as_ref(),
+            })
+            .collect::<Vec<_>>();
+        BLSSignature::verify_batch(&signature_sets)
+            .map_err(|err| anyhow!("Failed to verify block signatures: {err}"))
+    }
+
     /// Check if ``validator`` is eligible for activation.
     pub fn is_eligible_for_activation(
-        finalized_checkpoint_epoch: u64,
+        finalized_checkpoint_epoch: Epoch,
         validator: &Validator,
     ) -> bool {
         // Placement in queue is finalized
@@ -2454,9 +2716,11 @@ impl BeaconState {
         // Process activation eligibility, ejections, and activations
         let mut initiate_validator = vec![];
         let finalized_checkpoint_epoch = self.finalized_checkpoint.epoch;
-        for (index, validator) in self.validators.iter_mut().enumerate() {
+        // The chunk of a validator is only copied if it is shared and the validator changes
+        for index in 0..self.validators.len() {
+            let validator = &self.validators[index];
             if validator.is_eligible_for_activation_queue() {
-                validator.activation_eligibility_epoch =
+                self.validators[index].activation_eligibility_epoch =
                     current_epoch.checked_add(1).ok_or_else(|| {
                         anyhow::anyhow!("Epoch overflow when setting activation eligibility epoch")
                     })?;
@@ -2465,7 +2729,7 @@ impl BeaconState {
             {
                 initiate_validator.push(index as u64);
             } else if Self::is_eligible_for_activation(finalized_checkpoint_epoch, validator) {
-                validator.activation_epoch = activation_epoch;
+                self.validators[index].activation_epoch = activation_epoch;
             }
         }
 
@@ -2593,13 +2857,14 @@ impl BeaconState {
         Ok(())
     }
 
+    #[instrument(level = "debug", skip(self))]
     pub fn process_slots(&mut self, slot: u64) -> anyhow::Result<()> {
         ensure!(self.slot < slot);
 
         while self.slot < slot {
             self.process_slot()?;
             // Process epoch on the start slot of the next epoch
-            if (self.slot + 1).is_multiple_of(SLOTS_PER_EPOCH) {
+            if (self.slot + 1).is_epoch_start() {
                 self.process_epoch()?;
             }
 
@@ -2639,7 +2904,7 @@ impl BeaconState {
         // Verify prev_randao
         ensure!(payload.prev_randao == self.get_randao_mix(self.get_current_epoch()));
         // Verify timestamp
-        ensure!(payload.timestamp == self.compute_timestamp_at_slot(self.slot));
+        ensure!(payload.timestamp == self.compute_timestamp_at_slot(self.slot.as_u64()));
         // Verify commitments are under limit
         ensure!(body.blob_kzg_commitments.len() <= MAX_BLOBS_PER_BLOCK_ELECTRA as usize);
 
@@ -2668,6 +2933,7 @@ impl BeaconState {
         Ok(())
     }
 
+    #[instrument(level = "debug", skip_all)]
     pub async fn process_block(
         &mut self,
         block: &BeaconBlock,
@@ -2685,18 +2951,37 @@ impl BeaconState {
         Ok(())
     }
 
+    #[instrument(level = "debug", skip_all, fields(slot = signed_block.message.slot.as_u64()))]
     pub async fn state_transition(
         &mut self,
         signed_block: &SignedBeaconBlock,
         validate_result: bool,
         execution_engine: &Option<impl ExecutionApi>,
+    ) -> anyhow::Result<()> {
+        self.state_transition_with_signature_check(
+            signed_block,
+            validate_result,
+            validate_result,
+            execution_engine,
+        )
+        .await
+    }
+
+    /// Like [Self::state_transition], but only verifies the proposer signature when
+    /// ``verify_signature`` is set, for blocks whose signatures were verified in a batch already.
+    pub async fn state_transition_with_signature_check(
+        &mut self,
+        signed_block: &SignedBeaconBlock,
+        validate_result: bool,
+        verify_signature: bool,
+        execution_engine: &Option<impl ExecutionApi>,
     ) -> anyhow::Result<()> {
         let block = &signed_block.message;
         // Process slots (including those with no blocks) since block
-        self.process_slots(block.slot)?;
+        self.process_slots(block.slot.as_u64())?;
 
         // Verify signature
-        if validate_result {
+        if verify_signature {
             ensure!(self.verify_block_header_signature(&signed_block.signed_header())?)
         }
         // Process block
@@ -2861,30 +3146,33 @@ impl BeaconState {
         MIN_VALIDATOR_WITHDRAWABILITY_DELAY + epochs_for_validator_set_churn
     }
 
+    /// The roots of the fields of the state. The large lists and vectors are hashed through the
+    /// [BeaconStateTreeHashCache] of the state.
     pub fn merkle_leaves(&self) -> Vec<B256> {
+        let cache = &self.tree_hash_cache;
         vec![
             self.genesis_time.to_le_bytes().tree_hash_root(),
             self.genesis_validators_root.tree_hash_root(),
-            self.slot.to_le_bytes().tree_hash_root(),
+            self.slot.tree_hash_root(),
             self.fork.tree_hash_root(),
             self.latest_block_header.tree_hash_root(),
-            self.block_roots.tree_hash_root(),
-            self.state_roots.tree_hash_root(),
+            cache.block_roots_root(&self.block_roots),
+            cache.state_roots_root(&self.state_roots),
             self.historical_roots.tree_hash_root(),
             self.eth1_data.tree_hash_root(),
             self.eth1_data_votes.tree_hash_root(),
             self.eth1_deposit_index.to_le_bytes().tree_hash_root(),
-            self.validators.tree_hash_root(),
-            self.balances.tree_hash_root(),
-            self.randao_mixes.tree_hash_root(),
+            cache.validators_root(&self.validators),
+            cache.balances_root(&self.balances),
+            cache.randao_mixes_root(&self.randao_mixes),
             self.slashings.tree_hash_root(),
-            self.previous_epoch_participation.tree_hash_root(),
-            self.current_epoch_participation.tree_hash_root(),
+            cache.previous_epoch_participation_root(&self.previous_epoch_participation),
+            cache.current_epoch_participation_root(&self.current_epoch_participation),
             self.justification_bits.tree_hash_root(),
             self.previous_justified_checkpoint.tree_hash_root(),
             self.current_justified_checkpoint.tree_hash_root(),
             self.finalized_checkpoint.tree_hash_root(),
-            self.inactivity_scores.tree_hash_root(),
+            cache.inactivity_scores_root(&self.inactivity_scores),
             self.current_sync_committee.tree_hash_root(),
             self.next_sync_committee.tree_hash_root(),
             self.latest_execution_payload_header.tree_hash_root(),
@@ -2930,6 +3218,7 @@ impl BeaconState {
         let root_to_finalized_checkpoint_proof = vec![
             self.finalized_checkpoint
                 .epoch
+                .as_u64()
                 .to_le_bytes()
                 .tree_hash_root(),
         ];
@@ -2947,6 +3236,25 @@ impl BeaconState {
     }
 }
 
+impl TreeHash for BeaconState {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::Container
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("Container should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Container should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> B256 {
+        merkle_tree(&self.merkle_leaves(), BEACON_STATE_MERKLE_DEPTH)
+            .expect("The state has fewer fields than the leaves of its tree")[1]
+    }
+}
+
 pub fn get_validator_from_deposit(
     public_key: PublicKey,
     withdrawal_credentials: B256,
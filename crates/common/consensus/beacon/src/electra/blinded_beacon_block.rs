@@ -1,16 +1,22 @@
 use alloy_primitives::B256;
+use anyhow::ensure;
 use ream_bls::BLSSignature;
+use ream_consensus_misc::slot_epoch::Slot;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
-use crate::electra::blinded_beacon_block_body::BlindedBeaconBlockBody;
+use crate::electra::{
+    beacon_block::{BeaconBlock, SignedBeaconBlock},
+    beacon_block_body::BeaconBlockBody,
+    blinded_beacon_block_body::BlindedBeaconBlockBody,
+    execution_payload::ExecutionPayload,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct BlindedBeaconBlock {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
     #[serde(with = "serde_utils::quoted_u64")]
     pub proposer_index: u64,
     pub parent_root: B256,
@@ -29,3 +35,44 @@ pub struct SignedBlindedBeaconBlock {
     pub message: BlindedBeaconBlock,
     pub signature: BLSSignature,
 }
+
+impl SignedBlindedBeaconBlock {
+    /// Rebuild the full block from the execution payload revealed by the builder. The payload must
+    /// match the header the proposer signed over.
+    pub fn unblind(self, execution_payload: ExecutionPayload) -> anyhow::Result<SignedBeaconBlock> {
+        let SignedBlindedBeaconBlock { message, signature } = self;
+        let body = message.body;
+        ensure!(
+            execution_payload
+                .to_execution_payload_header()
+                .tree_hash_root()
+                == body.execution_payload_header.tree_hash_root(),
+            "Execution payload does not match the execution payload header of the blinded block"
+        );
+
+        Ok(SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: message.slot,
+                proposer_index: message.proposer_index,
+                parent_root: message.parent_root,
+                state_root: message.state_root,
+                body: BeaconBlockBody {
+                    randao_reveal: body.randao_reveal,
+                    eth1_data: body.eth1_data,
+                    graffiti: body.graffiti,
+                    proposer_slashings: body.proposer_slashings,
+                    attester_slashings: body.attester_slashings,
+                    attestations: body.attestations,
+                    deposits: body.deposits,
+                    voluntary_exits: body.voluntary_exits,
+                    sync_aggregate: body.sync_aggregate,
+                    execution_payload,
+                    bls_to_execution_changes: body.bls_to_execution_changes,
+                    blob_kzg_commitments: body.blob_kzg_commitments,
+                    execution_requests: body.execution_requests,
+                },
+            },
+            signature,
+        })
+    }
+}
@@ -5,4 +5,6 @@ pub mod blinded_beacon_block;
 pub mod blinded_beacon_block_body;
 pub mod execution_payload;
 pub mod execution_payload_header;
+pub mod state_witness;
+pub mod tree_hash_cache;
 pub mod zkvm_types;
@@ -0,0 +1,618 @@
+//! Minimal slices of a [BeaconState] for proving a state transition in a zkVM.
+//!
+//! Instead of the whole state, the guest is given the roots of the state fields, the validators
+//! touched by the block with their balances and participation flags, and a multiproof of them
+//! against the state root. The registry lists are never hashed in full: their nodes are derived
+//! from a tree over the existing entries and the zero hashes above it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail, ensure};
+use ethereum_hashing::hash32_concat;
+use ream_consensus_misc::{
+    constants::beacon::{
+        BALANCES_INDEX, BEACON_STATE_MERKLE_DEPTH, CURRENT_EPOCH_PARTICIPATION_INDEX,
+        PREVIOUS_EPOCH_PARTICIPATION_INDEX, VALIDATORS_INDEX, VALIDATORS_MERKLE_DEPTH,
+    },
+    validator::Validator,
+};
+use ream_merkle::{merkle_tree, multiproof::Multiproof};
+use serde::{Deserialize, Serialize};
+use tree_hash::TreeHash;
+
+use super::{beacon_block::BeaconBlock, beacon_state::BeaconState};
+
+/// The size in bytes of a chunk of a list of basic values.
+const BYTES_PER_CHUNK: u64 = 32;
+
+/// The size in bytes of a balance.
+const BYTES_PER_BALANCE: u64 = 8;
+
+/// The size in bytes of the participation flags of a validator.
+const BYTES_PER_PARTICIPATION: u64 = 1;
+
+/// The registry lists which are witnessed, with the size of their entries. Validators aren't
+/// packed, so their size is a whole chunk.
+const WITNESSED_LISTS: [(u64, u64); 4] = [
+    (VALIDATORS_INDEX, BYTES_PER_CHUNK),
+    (BALANCES_INDEX, BYTES_PER_BALANCE),
+    (PREVIOUS_EPOCH_PARTICIPATION_INDEX, BYTES_PER_PARTICIPATION),
+    (CURRENT_EPOCH_PARTICIPATION_INDEX, BYTES_PER_PARTICIPATION),
+];
+
+/// Generalized index of the length mix-in, relative to the root of a list.
+const LENGTH_GENERALIZED_INDEX: u64 = 3;
+
+fn state_field_generalized_index(field_index: u64) -> u64 {
+    (1 << BEACON_STATE_MERKLE_DEPTH) + field_index
+}
+
+/// The depth of the chunks of a registry list whose entries are `entry_size` bytes.
+fn list_merkle_depth(entry_size: u64) -> u64 {
+    VALIDATORS_MERKLE_DEPTH - depth_of(BYTES_PER_CHUNK / entry_size)
+}
+
+/// Generalized index of the chunk at `chunk_index` of the registry list at `field_index`.
+fn chunk_generalized_index(field_index: u64, entry_size: u64, chunk_index: u64) -> u64 {
+    ((state_field_generalized_index(field_index) * 2) << list_merkle_depth(entry_size))
+        + chunk_index
+}
+
+/// Generalized index of the length mix-in of the registry list at `field_index`.
+fn list_length_generalized_index(field_index: u64) -> u64 {
+    state_field_generalized_index(field_index) * 2 + 1
+}
+
+fn length_node(length: u64) -> B256 {
+    length.to_le_bytes().tree_hash_root()
+}
+
+fn depth_of(generalized_index: u64) -> u64 {
+    (u64::BITS - 1 - generalized_index.leading_zeros()) as u64
+}
+
+/// Packs basic values of `entry_size` bytes into chunks, as SSZ does for lists of basic values.
+fn pack(values: impl Iterator<Item = u64>, entry_size: u64) -> Vec<B256> {
+    let entry_size = entry_size as usize;
+    let mut chunks = vec![];
+    for (position, value) in values.enumerate() {
+        let offset = position * entry_size % BYTES_PER_CHUNK as usize;
+        if offset == 0 {
+            chunks.push(B256::ZERO);
+        }
+        if let Some(chunk) = chunks.last_mut() {
+            chunk.0[offset..offset + entry_size]
+                .copy_from_slice(&value.to_le_bytes()[..entry_size]);
+        }
+    }
+    chunks
+}
+
+/// The chunk of a packed list holding the entry at `index`.
+fn chunk_index(index: u64, entry_size: u64) -> u64 {
+    index * entry_size / BYTES_PER_CHUNK
+}
+
+/// Reads the entry at `index` from the witnessed chunks of a packed list.
+fn unpack(chunks: &BTreeMap<u64, B256>, index: u64, entry_size: u64) -> Option<u64> {
+    let chunk = chunks.get(&chunk_index(index, entry_size))?;
+    let offset = (index * entry_size % BYTES_PER_CHUNK) as usize;
+    let mut bytes = [0; 8];
+    bytes[..entry_size as usize].copy_from_slice(&chunk[offset..offset + entry_size as usize]);
+    Some(u64::from_le_bytes(bytes))
+}
+
+/// The validators touched by processing `block` on `state`: the proposer, the attesters, the sync
+/// committee, and the validators named by the operations and requests of the block. `state` must
+/// be advanced to the slot of the block, as the attesters depend on the committee shuffling.
+pub fn touched_validator_indices(
+    state: &BeaconState,
+    block: &BeaconBlock,
+) -> anyhow::Result<BTreeSet<u64>> {
+    let body = &block.body;
+    let mut indices = BTreeSet::from([block.proposer_index]);
+    indices.extend(
+        body.proposer_slashings
+            .iter()
+            .map(|slashing| slashing.signed_header_1.message.proposer_index),
+    );
+    for slashing in body.attester_slashings.iter() {
+        let attesting_indices_1 = slashing
+            .attestation_1
+            .attesting_indices
+            .iter()
+            .collect::<BTreeSet<_>>();
+        indices.extend(
+            slashing
+                .attestation_2
+                .attesting_indices
+                .iter()
+                .filter(|index| attesting_indices_1.contains(index)),
+        );
+    }
+    for attestation in body.attestations.iter() {
+        indices.extend(state.get_attesting_indices(attestation)?);
+    }
+    indices.extend(
+        body.voluntary_exits
+            .iter()
+            .map(|exit| exit.message.validator_index),
+    );
+    indices.extend(
+        body.bls_to_execution_changes
+            .iter()
+            .map(|change| change.message.validator_index),
+    );
+    indices.extend(
+        body.execution_payload
+            .withdrawals
+            .iter()
+            .map(|withdrawal| withdrawal.validator_index),
+    );
+
+    // Every member of the sync committee is rewarded or penalized, not only the participants
+    let public_key_to_index = state
+        .validators
+        .iter()
+        .enumerate()
+        .map(|(index, validator)| (&validator.public_key, index as u64))
+        .collect::<HashMap<_, _>>();
+    for public_key in state.current_sync_committee.public_keys.iter() {
+        let index = public_key_to_index
+            .get(public_key)
+            .ok_or_else(|| anyhow!("Sync committee member is not in the state"))?;
+        indices.insert(*index);
+    }
+    // Requests for unknown validators are ignored by the state transition
+    let requests = &body.execution_requests;
+    indices.extend(
+        requests
+            .withdrawals
+            .iter()
+            .map(|request| &request.validator_public_key)
+            .chain(
+                requests
+                    .consolidations
+                    .iter()
+                    .flat_map(|request| [&request.source_public_key, &request.target_public_key]),
+            )
+            .filter_map(|public_key| public_key_to_index.get(public_key)),
+    );
+
+    Ok(indices)
+}
+
+/// The nodes of a registry list, relative to its root, without hashing the empty part of the
+/// registry.
+struct ListTree {
+    /// The tree over the existing chunks, padded to the next power of two
+    tree: Vec<B256>,
+    depth: u64,
+    /// The depth of the chunks of the full registry
+    limit_depth: u64,
+    length: u64,
+    /// The zero hash of every height of the registry
+    zero_hashes: Vec<B256>,
+    /// The nodes above the root of `tree`, from the height of `tree` up to the registry root
+    ancestors: Vec<B256>,
+}
+
+impl ListTree {
+    fn new(chunks: &[B256], length: u64, limit_depth: u64) -> anyhow::Result<Self> {
+        let depth = match chunks.len() {
+            0 | 1 => 0,
+            num_chunks => depth_of(num_chunks as u64 - 1) + 1,
+        };
+        ensure!(depth <= limit_depth, "Too many entries: {length}");
+        let tree = merkle_tree(chunks, depth)?;
+
+        let mut zero_hashes = vec![B256::ZERO];
+        for height in 0..limit_depth as usize {
+            let zero_hash = zero_hashes[height];
+            zero_hashes.push(hash32_concat(zero_hash.as_slice(), zero_hash.as_slice()).into());
+        }
+
+        let mut ancestors = vec![tree[1]];
+        for height in depth..limit_depth {
+            let node = ancestors[ancestors.len() - 1];
+            ancestors.push(
+                hash32_concat(node.as_slice(), zero_hashes[height as usize].as_slice()).into(),
+            );
+        }
+
+        Ok(Self {
+            tree,
+            depth,
+            limit_depth,
+            length,
+            zero_hashes,
+            ancestors,
+        })
+    }
+
+    fn data_root(&self) -> B256 {
+        self.ancestors[self.ancestors.len() - 1]
+    }
+
+    fn chunk(&self, chunk_index: u64) -> anyhow::Result<B256> {
+        self.node((2 << self.limit_depth) + chunk_index)
+    }
+
+    fn node(&self, generalized_index: u64) -> anyhow::Result<B256> {
+        ensure!(generalized_index > 0, "Generalized indices start from 1");
+        let depth = depth_of(generalized_index);
+        if depth == 0 {
+            return Ok(hash32_concat(
+                self.data_root().as_slice(),
+                length_node(self.length).as_slice(),
+            )
+            .into());
+        }
+        if generalized_index >> (depth - 1) == LENGTH_GENERALIZED_INDEX {
+            ensure!(
+                generalized_index == LENGTH_GENERALIZED_INDEX,
+                "Generalized index {generalized_index} is below the length mix-in"
+            );
+            return Ok(length_node(self.length));
+        }
+
+        let data_depth = depth - 1;
+        ensure!(
+            data_depth <= self.limit_depth,
+            "Generalized index {generalized_index} is below the chunks"
+        );
+        let position = generalized_index - (2 << data_depth);
+        let height = self.limit_depth - data_depth;
+        if height >= self.depth {
+            return Ok(match position {
+                0 => self.ancestors[(height - self.depth) as usize],
+                _ => self.zero_hashes[height as usize],
+            });
+        }
+        let width = 1 << (self.depth - height);
+        if position < width {
+            Ok(self.tree[(width + position) as usize])
+        } else {
+            Ok(self.zero_hashes[height as usize])
+        }
+    }
+}
+
+/// A slice of the state consisting of the root of every field besides the registry lists, the
+/// validators touched by a block with the chunks of their balances and participation flags, and a
+/// multiproof of them against the state root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateWitness {
+    pub state_root: B256,
+    /// The roots of the state fields, keyed by field index
+    pub field_roots: BTreeMap<u64, B256>,
+    pub num_validators: u64,
+    /// The validators touched by the block, keyed by validator index
+    pub validators: BTreeMap<u64, Validator>,
+    /// The chunks of the balances of the touched validators, keyed by chunk index
+    pub balance_chunks: BTreeMap<u64, B256>,
+    /// The chunks of the previous epoch participation of the touched validators, keyed by chunk
+    /// index
+    pub previous_epoch_participation_chunks: BTreeMap<u64, B256>,
+    /// The chunks of the current epoch participation of the touched validators, keyed by chunk
+    /// index
+    pub current_epoch_participation_chunks: BTreeMap<u64, B256>,
+    pub multiproof: Multiproof,
+}
+
+impl StateWitness {
+    /// Extracts the slice of `state` which `block` touches.
+    pub fn from_block(state: &BeaconState, block: &BeaconBlock) -> anyhow::Result<Self> {
+        Self::generate(state, &touched_validator_indices(state, block)?)
+    }
+
+    /// Extracts the field roots of `state` and the validators at `validator_indices` with their
+    /// balances and participation flags.
+    pub fn generate(
+        state: &BeaconState,
+        validator_indices: &BTreeSet<u64>,
+    ) -> anyhow::Result<Self> {
+        let field_leaves = state.merkle_leaves();
+        let fields_tree = merkle_tree(&field_leaves, BEACON_STATE_MERKLE_DEPTH)?;
+        let num_validators = state.validators.len() as u64;
+        let list_trees = HashMap::from([
+            (
+                VALIDATORS_INDEX,
+                ListTree::new(
+                    &state
+                        .validators
+                        .iter()
+                        .map(|validator| validator.tree_hash_root())
+                        .collect::<Vec<_>>(),
+                    num_validators,
+                    list_merkle_depth(BYTES_PER_CHUNK),
+                )?,
+            ),
+            (
+                BALANCES_INDEX,
+                ListTree::new(
+                    &pack(state.balances.iter().copied(), BYTES_PER_BALANCE),
+                    state.balances.len() as u64,
+                    list_merkle_depth(BYTES_PER_BALANCE),
+                )?,
+            ),
+            (
+                PREVIOUS_EPOCH_PARTICIPATION_INDEX,
+                ListTree::new(
+                    &pack(
+                        state
+                            .previous_epoch_participation
+                            .iter()
+                            .map(|&flags| flags as u64),
+                        BYTES_PER_PARTICIPATION,
+                    ),
+                    state.previous_epoch_participation.len() as u64,
+                    list_merkle_depth(BYTES_PER_PARTICIPATION),
+                )?,
+            ),
+            (
+                CURRENT_EPOCH_PARTICIPATION_INDEX,
+                ListTree::new(
+                    &pack(
+                        state
+                            .current_epoch_participation
+                            .iter()
+                            .map(|&flags| flags as u64),
+                        BYTES_PER_PARTICIPATION,
+                    ),
+                    state.current_epoch_participation.len() as u64,
+                    list_merkle_depth(BYTES_PER_PARTICIPATION),
+                )?,
+            ),
+        ]);
+
+        let field_roots = field_leaves
+            .into_iter()
+            .enumerate()
+            .map(|(field_index, root)| (field_index as u64, root))
+            .filter(|(field_index, _)| !list_trees.contains_key(field_index))
+            .collect::<BTreeMap<_, _>>();
+        let validators = validator_indices
+            .iter()
+            .map(|&index| {
+                let validator = state
+                    .validators
+                    .get(index as usize)
+                    .ok_or_else(|| anyhow!("Validator {index} is not in the state"))?;
+                Ok((index, validator.clone()))
+            })
+            .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
+        let chunks = |field_index: u64, entry_size: u64| {
+            validator_indices
+                .iter()
+                .map(|&index| chunk_index(index, entry_size))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .map(|chunk_index| Ok((chunk_index, list_trees[&field_index].chunk(chunk_index)?)))
+                .collect::<anyhow::Result<BTreeMap<_, _>>>()
+        };
+        let balance_chunks = chunks(BALANCES_INDEX, BYTES_PER_BALANCE)?;
+        let previous_epoch_participation_chunks =
+            chunks(PREVIOUS_EPOCH_PARTICIPATION_INDEX, BYTES_PER_PARTICIPATION)?;
+        let current_epoch_participation_chunks =
+            chunks(CURRENT_EPOCH_PARTICIPATION_INDEX, BYTES_PER_PARTICIPATION)?;
+
+        let mut generalized_indices = field_roots
+            .keys()
+            .map(|&field_index| state_field_generalized_index(field_index))
+            .collect::<Vec<_>>();
+        for (field_index, entry_size) in WITNESSED_LISTS {
+            generalized_indices.push(list_length_generalized_index(field_index));
+            generalized_indices.extend(
+                validator_indices
+                    .iter()
+                    .map(|&index| chunk_index(index, entry_size))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .map(|chunk_index| {
+                        chunk_generalized_index(field_index, entry_size, chunk_index)
+                    }),
+            );
+        }
+
+        let multiproof = Multiproof::generate_from_nodes(
+            &generalized_indices,
+            |generalized_index| {
+                let depth = depth_of(generalized_index);
+                if depth <= BEACON_STATE_MERKLE_DEPTH {
+                    return Ok(fields_tree[generalized_index as usize]);
+                }
+                let relative_depth = depth - BEACON_STATE_MERKLE_DEPTH;
+                let field_generalized_index = generalized_index >> relative_depth;
+                let field_index = field_generalized_index - (1 << BEACON_STATE_MERKLE_DEPTH);
+                let Some(list_tree) = list_trees.get(&field_index) else {
+                    bail!(
+                        "Generalized index {generalized_index} is below a field other than the registry lists"
+                    );
+                };
+                list_tree.node(
+                    generalized_index - (field_generalized_index << relative_depth)
+                        + (1 << relative_depth),
+                )
+            },
+        )?;
+
+        Ok(Self {
+            state_root: fields_tree[1],
+            field_roots,
+            num_validators,
+            validators,
+            balance_chunks,
+            previous_epoch_participation_chunks,
+            current_epoch_participation_chunks,
+            multiproof,
+        })
+    }
+
+    /// The balance of a witnessed validator.
+    pub fn balance(&self, index: u64) -> Option<u64> {
+        unpack(&self.balance_chunks, index, BYTES_PER_BALANCE)
+    }
+
+    /// The previous epoch participation flags of a witnessed validator.
+    pub fn previous_epoch_participation(&self, index: u64) -> Option<u8> {
+        unpack(
+            &self.previous_epoch_participation_chunks,
+            index,
+            BYTES_PER_PARTICIPATION,
+        )
+        .map(|flags| flags as u8)
+    }
+
+    /// The current epoch participation flags of a witnessed validator.
+    pub fn current_epoch_participation(&self, index: u64) -> Option<u8> {
+        unpack(
+            &self.current_epoch_participation_chunks,
+            index,
+            BYTES_PER_PARTICIPATION,
+        )
+        .map(|flags| flags as u8)
+    }
+
+    /// Verifies that the witnessed slice belongs to the state of `state_root`. This is run in the
+    /// guest, after which the field roots, validators, balances and participation flags of the
+    /// witness can be trusted.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let mut expected_leaves = self
+            .field_roots
+            .iter()
+            .map(|(&field_index, &root)| {
+                ensure!(
+                    WITNESSED_LISTS
+                        .iter()
+                        .all(|(list_index, _)| *list_index != field_index)
+                        && field_index < (1 << BEACON_STATE_MERKLE_DEPTH),
+                    "Invalid field index {field_index}"
+                );
+                Ok((state_field_generalized_index(field_index), root))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        for (field_index, _) in WITNESSED_LISTS {
+            // The registry lists all have an entry per validator
+            expected_leaves.push((
+                list_length_generalized_index(field_index),
+                length_node(self.num_validators),
+            ));
+        }
+        for (&index, validator) in &self.validators {
+            ensure!(
+                index < self.num_validators,
+                "Validator {index} is out of the {} validators",
+                self.num_validators
+            );
+            expected_leaves.push((
+                chunk_generalized_index(VALIDATORS_INDEX, BYTES_PER_CHUNK, index),
+                validator.tree_hash_root(),
+            ));
+        }
+        for (field_index, entry_size, chunks) in [
+            (BALANCES_INDEX, BYTES_PER_BALANCE, &self.balance_chunks),
+            (
+                PREVIOUS_EPOCH_PARTICIPATION_INDEX,
+                BYTES_PER_PARTICIPATION,
+                &self.previous_epoch_participation_chunks,
+            ),
+            (
+                CURRENT_EPOCH_PARTICIPATION_INDEX,
+                BYTES_PER_PARTICIPATION,
+                &self.current_epoch_participation_chunks,
+            ),
+        ] {
+            for (&chunk_index, &chunk) in chunks {
+                ensure!(
+                    chunk_index * BYTES_PER_CHUNK / entry_size < self.num_validators,
+                    "Chunk {chunk_index} of field {field_index} is out of the {} validators",
+                    self.num_validators
+                );
+                expected_leaves.push((
+                    chunk_generalized_index(field_index, entry_size, chunk_index),
+                    chunk,
+                ));
+            }
+        }
+
+        ensure!(
+            self.multiproof.leaves.len() == expected_leaves.len()
+                && expected_leaves.iter().all(|(generalized_index, leaf)| {
+                    self.multiproof.leaves.get(generalized_index) == Some(leaf)
+                }),
+            "The multiproof doesn't prove the witnessed state slice"
+        );
+        self.multiproof.verify(self.state_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_bls::PublicKey;
+    use ssz_types::VariableList;
+
+    use super::*;
+    use crate::electra::zkvm_types::ValidatorRegistryLimit;
+
+    #[test]
+    fn test_validators_tree_matches_list_root() {
+        let validators = (0..5)
+            .map(|index| Validator {
+                public_key: PublicKey::default(),
+                withdrawal_credentials: B256::repeat_byte(index as u8),
+                effective_balance: index * 1_000,
+                slashed: false,
+                activation_eligibility_epoch: 0,
+                activation_epoch: 0,
+                exit_epoch: index,
+                withdrawable_epoch: index,
+            })
+            .collect::<Vec<_>>();
+        let list = VariableList::<Validator, ValidatorRegistryLimit>::new(validators.clone())
+            .expect("Validators fit in the registry");
+        let leaves = validators
+            .iter()
+            .map(|validator| validator.tree_hash_root())
+            .collect::<Vec<_>>();
+        let validators_tree = ListTree::new(&leaves, 5, VALIDATORS_MERKLE_DEPTH).unwrap();
+        assert_eq!(validators_tree.node(1).unwrap(), list.tree_hash_root());
+
+        let validator_index = (2 << VALIDATORS_MERKLE_DEPTH) + 3;
+        let multiproof = Multiproof::generate_from_nodes(
+            &[LENGTH_GENERALIZED_INDEX, validator_index],
+            |generalized_index| validators_tree.node(generalized_index),
+        )
+        .unwrap();
+        assert_eq!(
+            multiproof.leaves[&validator_index],
+            validators[3].tree_hash_root()
+        );
+        multiproof.verify(list.tree_hash_root()).unwrap();
+    }
+
+    #[test]
+    fn test_balances_tree_matches_list_root() {
+        let balances = (0..7)
+            .map(|index| index * 32_000_000_000)
+            .collect::<Vec<u64>>();
+        let list = VariableList::<u64, ValidatorRegistryLimit>::new(balances.clone())
+            .expect("Balances fit in the registry");
+        let chunks = pack(balances.iter().copied(), BYTES_PER_BALANCE);
+        let balances_tree = ListTree::new(
+            &chunks,
+            balances.len() as u64,
+            list_merkle_depth(BYTES_PER_BALANCE),
+        )
+        .unwrap();
+        assert_eq!(balances_tree.node(1).unwrap(), list.tree_hash_root());
+
+        let balance_chunks = BTreeMap::from([(1, balances_tree.chunk(1).unwrap())]);
+        assert_eq!(
+            unpack(&balance_chunks, 6, BYTES_PER_BALANCE),
+            Some(balances[6])
+        );
+        assert_eq!(unpack(&balance_chunks, 2, BYTES_PER_BALANCE), None);
+    }
+}
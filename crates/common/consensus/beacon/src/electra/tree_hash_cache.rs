@@ -0,0 +1,400 @@
+//! Incremental hashing of the large fields of a [BeaconState].
+//!
+//! The cache keeps the Merkle tree of every large list and vector of the state. When the root is
+//! needed again, the leaves are compared with the cached ones and only the nodes above the leaves
+//! which changed are rehashed, so the root after a block costs little more than a pass over the
+//! leaves.
+//!
+//! [BeaconState]: super::beacon_state::BeaconState
+
+use std::{
+    fmt, ptr,
+    sync::{Arc, LazyLock, Mutex, MutexGuard, Weak},
+};
+
+use alloy_primitives::B256;
+use ethereum_hashing::hash32_concat;
+use ream_consensus_misc::{constants::beacon::VALIDATORS_MERKLE_DEPTH, validator::Validator};
+use tree_hash::TreeHash;
+
+use super::zkvm_types::ValidatorRegistryLimit;
+use crate::chunked_list::{CHUNK_SIZE, ChunkedList};
+
+/// The depth of the lists which are limited by the validator registry, in chunks of one item.
+const REGISTRY_DEPTH: usize = VALIDATORS_MERKLE_DEPTH as usize;
+
+/// The depth of the registry lists of `u64`, which pack 4 items per chunk.
+const REGISTRY_U64_DEPTH: usize = REGISTRY_DEPTH - 2;
+
+/// The depth of the registry lists of `u8`, which pack 32 items per chunk.
+const REGISTRY_U8_DEPTH: usize = REGISTRY_DEPTH - 5;
+
+static ZERO_HASHES: LazyLock<Vec<B256>> = LazyLock::new(|| {
+    let mut zero_hashes = vec![B256::ZERO];
+    for height in 0..REGISTRY_DEPTH {
+        let zero_hash = zero_hashes[height];
+        zero_hashes.push(hash32_concat(zero_hash.as_slice(), zero_hash.as_slice()).into());
+    }
+    zero_hashes
+});
+
+fn mix_in_length(root: B256, length: usize) -> B256 {
+    hash32_concat(
+        root.as_slice(),
+        (length as u64).to_le_bytes().tree_hash_root().as_slice(),
+    )
+    .into()
+}
+
+fn pack_u64s(values: &[u64]) -> Vec<B256> {
+    values
+        .chunks(4)
+        .map(|values| {
+            let mut chunk = B256::ZERO;
+            for (index, value) in values.iter().enumerate() {
+                chunk[index * 8..(index + 1) * 8].copy_from_slice(&value.to_le_bytes());
+            }
+            chunk
+        })
+        .collect()
+}
+
+fn pack_u8s(values: &[u8]) -> Vec<B256> {
+    values
+        .chunks(32)
+        .map(|values| {
+            let mut chunk = B256::ZERO;
+            chunk[..values.len()].copy_from_slice(values);
+            chunk
+        })
+        .collect()
+}
+
+/// The Merkle tree over the leaves which exist, from the leaves up to a single node.
+#[derive(Debug, Clone, Default)]
+struct MerkleCache {
+    layers: Vec<Vec<B256>>,
+}
+
+impl MerkleCache {
+    /// Replaces the leaves and returns the root of a tree of `depth`, padded with zero hashes.
+    fn update(&mut self, leaves: Vec<B256>, depth: usize) -> B256 {
+        let cached_leaves = self.layers.first().map(Vec::as_slice).unwrap_or_default();
+        // Nodes of shrinking lists would need clearing, so they are hashed from scratch
+        let mut dirty = if leaves.len() < cached_leaves.len() {
+            self.layers.clear();
+            (0..leaves.len()).collect::<Vec<_>>()
+        } else {
+            leaves
+                .iter()
+                .enumerate()
+                .filter(|(index, leaf)| cached_leaves.get(*index) != Some(*leaf))
+                .map(|(index, _)| index)
+                .collect()
+        };
+
+        match self.layers.first_mut() {
+            Some(cached_leaves) => *cached_leaves = leaves,
+            None => self.layers.push(leaves),
+        }
+
+        let mut height = 0;
+        while self.layers[height].len() > 1 {
+            if self.layers.len() == height + 1 {
+                self.layers.push(vec![]);
+            }
+            let (children, parents) = self.layers.split_at_mut(height + 1);
+            let (children, parents) = (&children[height], &mut parents[0]);
+            parents.resize(children.len().div_ceil(2), B256::ZERO);
+
+            dirty.dedup_by_key(|index| *index / 2);
+            for index in dirty.iter_mut() {
+                *index /= 2;
+                let right = children.get(*index * 2 + 1).unwrap_or(&ZERO_HASHES[height]);
+                parents[*index] =
+                    hash32_concat(children[*index * 2].as_slice(), right.as_slice()).into();
+            }
+            height += 1;
+        }
+
+        let Some(mut root) = self.layers[height].first().copied() else {
+            return ZERO_HASHES[depth];
+        };
+        for zero_hash in &ZERO_HASHES[height..depth] {
+            root = hash32_concat(root.as_slice(), zero_hash.as_slice()).into();
+        }
+        root
+    }
+}
+
+/// The tree of the validator registry, with weak references to the chunks of the registry it was
+/// built from. While a weak reference to a chunk exists, [Arc::make_mut] moves the chunk to a new
+/// allocation when it changes, so the chunks which are still the same allocations keep the cached
+/// roots of their validators and only the other chunks are hashed again. The weak references
+/// don't keep the validators alive.
+#[derive(Debug, Clone, Default)]
+struct ValidatorsCache {
+    chunks: Vec<Weak<Vec<Validator>>>,
+    tree: Arc<MerkleCache>,
+    root: Option<B256>,
+}
+
+impl ValidatorsCache {
+    fn is_cached(&self, index: usize, chunk: &Arc<Vec<Validator>>) -> bool {
+        self.chunks
+            .get(index)
+            .is_some_and(|cached| ptr::eq(cached.as_ptr(), Arc::as_ptr(chunk)))
+    }
+
+    fn root(&mut self, validators: &ChunkedList<Validator, ValidatorRegistryLimit>) -> B256 {
+        let chunks = validators.chunks();
+        if let Some(root) = self.root
+            && self.chunks.len() == chunks.len()
+            && chunks
+                .iter()
+                .enumerate()
+                .all(|(index, chunk)| self.is_cached(index, chunk))
+        {
+            return root;
+        }
+
+        let cached_leaves = self
+            .tree
+            .layers
+            .first()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut leaves = Vec::with_capacity(validators.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            let start = index * CHUNK_SIZE;
+            match cached_leaves.get(start..start + chunk.len()) {
+                Some(cached) if self.is_cached(index, chunk) => leaves.extend_from_slice(cached),
+                _ => leaves.extend(chunk.iter().map(TreeHash::tree_hash_root)),
+            }
+        }
+        let root = mix_in_length(
+            Arc::make_mut(&mut self.tree).update(leaves, REGISTRY_DEPTH),
+            validators.len(),
+        );
+
+        self.chunks = chunks.iter().map(Arc::downgrade).collect();
+        self.root = Some(root);
+        root
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct FieldCaches {
+    block_roots: Arc<MerkleCache>,
+    state_roots: Arc<MerkleCache>,
+    validators: ValidatorsCache,
+    balances: Arc<MerkleCache>,
+    randao_mixes: Arc<MerkleCache>,
+    previous_epoch_participation: Arc<MerkleCache>,
+    current_epoch_participation: Arc<MerkleCache>,
+    inactivity_scores: Arc<MerkleCache>,
+}
+
+/// Caches the trees of the large fields of a [BeaconState], so computing its root again after
+/// changes only hashes what changed.
+///
+/// The cache is derived from the fields it hashes, so it is never serialized and never makes two
+/// states differ. Copies of the cache share the trees until one of them is updated.
+///
+/// [BeaconState]: super::beacon_state::BeaconState
+#[derive(Default)]
+pub struct BeaconStateTreeHashCache {
+    fields: Mutex<FieldCaches>,
+}
+
+impl BeaconStateTreeHashCache {
+    fn fields(&self) -> MutexGuard<'_, FieldCaches> {
+        self.fields.lock().unwrap_or_else(|err| {
+            // A panic while hashing may have left the trees half updated
+            self.fields.clear_poison();
+            let mut fields = err.into_inner();
+            *fields = FieldCaches::default();
+            fields
+        })
+    }
+
+    pub fn block_roots_root(&self, block_roots: &[B256]) -> B256 {
+        vector_root(&mut self.fields().block_roots, block_roots)
+    }
+
+    pub fn state_roots_root(&self, state_roots: &[B256]) -> B256 {
+        vector_root(&mut self.fields().state_roots, state_roots)
+    }
+
+    pub fn randao_mixes_root(&self, randao_mixes: &[B256]) -> B256 {
+        vector_root(&mut self.fields().randao_mixes, randao_mixes)
+    }
+
+    pub fn validators_root(
+        &self,
+        validators: &ChunkedList<Validator, ValidatorRegistryLimit>,
+    ) -> B256 {
+        self.fields().validators.root(validators)
+    }
+
+    pub fn balances_root(&self, balances: &ChunkedList<u64, ValidatorRegistryLimit>) -> B256 {
+        let root = Arc::make_mut(&mut self.fields().balances)
+            .update(packed_leaves(balances, pack_u64s), REGISTRY_U64_DEPTH);
+        mix_in_length(root, balances.len())
+    }
+
+    pub fn previous_epoch_participation_root(
+        &self,
+        participation: &ChunkedList<u8, ValidatorRegistryLimit>,
+    ) -> B256 {
+        let root = Arc::make_mut(&mut self.fields().previous_epoch_participation)
+            .update(packed_leaves(participation, pack_u8s), REGISTRY_U8_DEPTH);
+        mix_in_length(root, participation.len())
+    }
+
+    pub fn current_epoch_participation_root(
+        &self,
+        participation: &ChunkedList<u8, ValidatorRegistryLimit>,
+    ) -> B256 {
+        let root = Arc::make_mut(&mut self.fields().current_epoch_participation)
+            .update(packed_leaves(participation, pack_u8s), REGISTRY_U8_DEPTH);
+        mix_in_length(root, participation.len())
+    }
+
+    pub fn inactivity_scores_root(
+        &self,
+        inactivity_scores: &ChunkedList<u64, ValidatorRegistryLimit>,
+    ) -> B256 {
+        let root = Arc::make_mut(&mut self.fields().inactivity_scores).update(
+            packed_leaves(inactivity_scores, pack_u64s),
+            REGISTRY_U64_DEPTH,
+        );
+        mix_in_length(root, inactivity_scores.len())
+    }
+}
+
+/// Packs every chunk of the list on its own, which matches packing the whole list as the chunks
+/// hold a multiple of the items of a leaf.
+fn packed_leaves<T>(
+    list: &ChunkedList<T, ValidatorRegistryLimit>,
+    pack: fn(&[T]) -> Vec<B256>,
+) -> Vec<B256> {
+    list.chunks().iter().flat_map(|chunk| pack(chunk)).collect()
+}
+
+/// Roots of vectors of roots, whose length is a power of two.
+fn vector_root(cache: &mut Arc<MerkleCache>, roots: &[B256]) -> B256 {
+    Arc::make_mut(cache).update(
+        roots.to_vec(),
+        roots.len().next_power_of_two().trailing_zeros() as usize,
+    )
+}
+
+impl Clone for BeaconStateTreeHashCache {
+    fn clone(&self) -> Self {
+        Self {
+            fields: Mutex::new(self.fields().clone()),
+        }
+    }
+}
+
+impl PartialEq for BeaconStateTreeHashCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for BeaconStateTreeHashCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BeaconStateTreeHashCache")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_bls::PublicKey;
+    use ssz_types::{FixedVector, VariableList, typenum::U64};
+
+    use super::*;
+
+    fn validator(index: u64) -> Validator {
+        Validator {
+            public_key: PublicKey::default(),
+            withdrawal_credentials: B256::repeat_byte(index as u8),
+            effective_balance: index * 1_000,
+            slashed: false,
+            activation_eligibility_epoch: 0,
+            activation_epoch: 0,
+            exit_epoch: index,
+            withdrawable_epoch: index,
+        }
+    }
+
+    #[test]
+    fn test_lists_match_tree_hash_after_changes() {
+        let cache = BeaconStateTreeHashCache::default();
+        let mut validators = ChunkedList::<Validator, ValidatorRegistryLimit>::from(
+            (0..CHUNK_SIZE as u64 + 5)
+                .map(validator)
+                .collect::<Vec<_>>(),
+        );
+        let mut balances = ChunkedList::from((0..7).collect::<Vec<u64>>());
+        let mut participation = ChunkedList::from(vec![0u8; 45]);
+
+        for round in 0..4 {
+            let previous_validators = validators.clone();
+            assert_eq!(
+                cache.validators_root(&validators),
+                validators.tree_hash_root()
+            );
+            assert_eq!(cache.balances_root(&balances), balances.tree_hash_root());
+            assert_eq!(
+                cache.current_epoch_participation_root(&participation),
+                participation.tree_hash_root()
+            );
+
+            validators[round * 86].exit_epoch += 10;
+            validators.push(validator(10 + round as u64)).unwrap();
+            balances[round * 2] += 32;
+            for _ in 0..3 {
+                balances.push(round as u64).unwrap();
+            }
+            participation[round * 11] |= 0b111;
+            participation.push(1).unwrap();
+
+            // A copy from before the changes still has the chunks which changed since
+            assert_eq!(
+                cache.validators_root(&previous_validators),
+                previous_validators.tree_hash_root()
+            );
+        }
+    }
+
+    #[test]
+    fn test_vector_matches_tree_hash_after_changes() {
+        let cache = BeaconStateTreeHashCache::default();
+        let mut roots = vec![B256::ZERO; 64];
+        for index in [0, 17, 63] {
+            assert_eq!(
+                cache.block_roots_root(&roots),
+                FixedVector::<B256, U64>::new(roots.clone())
+                    .unwrap()
+                    .tree_hash_root()
+            );
+            roots[index] = B256::repeat_byte(index as u8 + 1);
+        }
+    }
+
+    #[test]
+    fn test_shrinking_list_matches_tree_hash() {
+        let cache = BeaconStateTreeHashCache::default();
+        cache.balances_root(&ChunkedList::from((0..9).collect::<Vec<u64>>()));
+        assert_eq!(
+            cache.balances_root(&ChunkedList::from(vec![0, 1])),
+            VariableList::<u64, ValidatorRegistryLimit>::new(vec![0, 1])
+                .unwrap()
+                .tree_hash_root()
+        );
+    }
+}
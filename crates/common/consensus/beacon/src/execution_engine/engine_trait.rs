@@ -16,4 +16,7 @@ pub trait ExecutionApi {
         &self,
         blob_version_hashes: Vec<B256>,
     ) -> anyhow::Result<Vec<Option<BlobAndProofV1>>>;
+
+    /// Return the engine API methods supported by the execution client.
+    async fn engine_exchange_capabilities(&self) -> anyhow::Result<Vec<String>>;
 }
@@ -1,7 +1,7 @@
-use std::path::Path;
+use std::{collections::HashMap, path::Path, time::Duration};
 
 use alloy_primitives::B256;
-use anyhow::Ok;
+use anyhow::{Ok, bail};
 use async_trait::async_trait;
 use serde::Deserialize;
 
@@ -10,15 +10,40 @@ use super::{
     rpc_types::get_blobs::BlobAndProofV1,
 };
 
+/// The response scripted for a payload, mirroring the statuses of the engine API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockPayloadStatus {
+    Valid,
+    Invalid,
+    /// The execution client hasn't validated the payload yet, as it is still syncing.
+    Syncing,
+    /// The execution client accepted the payload without validating it, as its parent is unknown.
+    Accepted,
+    /// The request fails, as if the execution client were unreachable.
+    Unavailable,
+}
+
+/// MockExecutionEngine answers the engine API from a script, so that the consensus code which
+/// depends on the execution client can be exercised deterministically.
+///
+/// Payloads are answered with the status set for their block hash, falling back to the status of
+/// `execution_valid`, which is what the spec tests' `execution.yaml` configures.
 #[derive(Deserialize, Debug, Default)]
 pub struct MockExecutionEngine {
     execution_valid: bool,
+    #[serde(skip)]
+    payload_statuses: HashMap<B256, MockPayloadStatus>,
+    #[serde(skip)]
+    latency: Duration,
+    #[serde(skip)]
+    capabilities: Vec<String>,
 }
 
 impl MockExecutionEngine {
     pub fn new() -> Self {
         Self {
             execution_valid: true,
+            ..Default::default()
         }
     }
 
@@ -30,21 +55,122 @@ impl MockExecutionEngine {
     pub fn set_payload_status(&mut self, payload_status: bool) {
         self.execution_valid = payload_status;
     }
+
+    /// Answers the payload of `block_hash` with `status` instead of the default status.
+    pub fn set_block_hash_status(&mut self, block_hash: B256, status: MockPayloadStatus) {
+        self.payload_statuses.insert(block_hash, status);
+    }
+
+    /// Delays every response by `latency`.
+    pub fn set_latency(&mut self, latency: Duration) {
+        self.latency = latency;
+    }
+
+    /// Sets the methods returned by `engine_exchangeCapabilities`.
+    pub fn set_capabilities(&mut self, capabilities: Vec<String>) {
+        self.capabilities = capabilities;
+    }
+
+    async fn respond(&self) {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+    }
+
+    /// Returns the status scripted for the payload of `new_payload_request`, like
+    /// `ExecutionEngine::notify_new_payload` returns the status of the execution client.
+    pub async fn notify_new_payload(
+        &self,
+        new_payload_request: NewPayloadRequest,
+    ) -> anyhow::Result<MockPayloadStatus> {
+        self.respond().await;
+        let block_hash = new_payload_request.execution_payload.block_hash;
+        match self.payload_statuses.get(&block_hash) {
+            Some(MockPayloadStatus::Unavailable) => {
+                bail!("Execution engine is unavailable for payload {block_hash}")
+            }
+            Some(status) => Ok(*status),
+            None if self.execution_valid => Ok(MockPayloadStatus::Valid),
+            None => Ok(MockPayloadStatus::Invalid),
+        }
+    }
 }
 
 #[async_trait]
 impl ExecutionApi for MockExecutionEngine {
     async fn verify_and_notify_new_payload(
         &self,
-        _new_payload_request: NewPayloadRequest,
+        new_payload_request: NewPayloadRequest,
     ) -> anyhow::Result<bool> {
-        Ok(self.execution_valid)
+        // Like `ExecutionEngine`, only VALID payloads are valid, while SYNCING and ACCEPTED
+        // payloads are yet to be validated
+        Ok(self.notify_new_payload(new_payload_request).await? == MockPayloadStatus::Valid)
     }
 
     async fn engine_get_blobs_v1(
         &self,
         blob_version_hashes: Vec<B256>,
     ) -> anyhow::Result<Vec<Option<BlobAndProofV1>>> {
+        self.respond().await;
         Ok(blob_version_hashes.into_iter().map(|_| None).collect())
     }
+
+    async fn engine_exchange_capabilities(&self) -> anyhow::Result<Vec<String>> {
+        self.respond().await;
+        Ok(self.capabilities.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        electra::execution_payload::ExecutionPayload, execution_requests::ExecutionRequests,
+    };
+
+    fn new_payload_request(block_hash: B256) -> NewPayloadRequest {
+        NewPayloadRequest {
+            execution_payload: ExecutionPayload {
+                block_hash,
+                ..Default::default()
+            },
+            versioned_hashes: vec![],
+            parent_beacon_block_root: B256::ZERO,
+            execution_requests: ExecutionRequests::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scripted_payload_statuses() {
+        let mut engine = MockExecutionEngine::new();
+        engine.set_block_hash_status(B256::repeat_byte(1), MockPayloadStatus::Invalid);
+        engine.set_block_hash_status(B256::repeat_byte(2), MockPayloadStatus::Syncing);
+        engine.set_block_hash_status(B256::repeat_byte(3), MockPayloadStatus::Accepted);
+        engine.set_block_hash_status(B256::repeat_byte(4), MockPayloadStatus::Unavailable);
+
+        let notify = |block_hash: B256| engine.notify_new_payload(new_payload_request(block_hash));
+        assert_eq!(notify(B256::ZERO).await.unwrap(), MockPayloadStatus::Valid);
+        assert_eq!(
+            notify(B256::repeat_byte(1)).await.unwrap(),
+            MockPayloadStatus::Invalid
+        );
+        assert_eq!(
+            notify(B256::repeat_byte(2)).await.unwrap(),
+            MockPayloadStatus::Syncing
+        );
+        assert_eq!(
+            notify(B256::repeat_byte(3)).await.unwrap(),
+            MockPayloadStatus::Accepted
+        );
+        assert!(notify(B256::repeat_byte(4)).await.is_err());
+
+        let verify = |block_hash: B256| {
+            engine.verify_and_notify_new_payload(new_payload_request(block_hash))
+        };
+        assert!(verify(B256::ZERO).await.unwrap());
+        for byte in 1..=3 {
+            assert!(!verify(B256::repeat_byte(byte)).await.unwrap());
+        }
+        assert!(verify(B256::repeat_byte(4)).await.is_err());
+    }
 }
@@ -1,4 +1,5 @@
 pub mod engine_trait;
+#[cfg(feature = "mock_engine")]
 pub mod mock_engine;
 pub mod new_payload_request;
 pub mod rpc_types;
@@ -1,7 +1,12 @@
-use ream_consensus_misc::constants::beacon::BYTES_PER_BLOB;
+use anyhow::ensure;
+use ream_consensus_misc::constants::beacon::{BYTES_PER_BLOB, CELLS_PER_EXT_BLOB};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use ssz_types::{FixedVector, serde_utils::hex_fixed_vec, typenum::U131072};
+use ssz_types::{
+    FixedVector,
+    serde_utils::hex_fixed_vec,
+    typenum::{U128, U131072},
+};
 use tree_hash_derive::TreeHash;
 
 use crate::{blob_sidecar::BlobSidecar, polynomial_commitments::kzg_proof::KZGProof};
@@ -38,3 +43,23 @@ impl From<BlobSidecar> for BlobAndProofV1 {
         }
     }
 }
+
+/// A blob with the KZG proofs of each cell of its extension, as returned by `engine_getBlobsV2`.
+/// The proofs are deserialized into a list of exactly `CELLS_PER_EXT_BLOB` entries.
+#[derive(Deserialize, Debug, Clone, PartialEq, Decode, Encode)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobAndProofV2 {
+    pub blob: Blob,
+    pub proofs: FixedVector<KZGProof, U128>,
+}
+
+impl BlobAndProofV2 {
+    /// The KZG proof of the cell at `cell_index` of the extended blob.
+    pub fn cell_proof(&self, cell_index: u64) -> anyhow::Result<&KZGProof> {
+        ensure!(
+            cell_index < CELLS_PER_EXT_BLOB,
+            "cell index {cell_index} must be less than {CELLS_PER_EXT_BLOB}"
+        );
+        Ok(&self.proofs[cell_index as usize])
+    }
+}
@@ -1,10 +1,11 @@
 use alloy_primitives::B256;
+use ream_consensus_misc::slot_epoch::Epoch;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash_derive::TreeHash;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct LatestMessage {
-    pub epoch: u64,
+    pub epoch: Epoch,
     pub root: B256,
 }
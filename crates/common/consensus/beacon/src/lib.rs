@@ -4,7 +4,10 @@ pub mod attestation;
 pub mod attester_slashing;
 pub mod blob_sidecar;
 pub mod bls_to_execution_change;
+pub mod chunked_list;
 pub mod consolidation_request;
+pub mod custody;
+pub mod data_column_sidecar;
 pub mod deposit;
 pub mod deposit_request;
 pub mod electra;
@@ -21,10 +24,12 @@ pub mod pending_partial_withdrawal;
 pub mod polynomial_commitments;
 pub mod predicates;
 pub mod proposer_slashing;
+pub mod shuffling;
 pub mod single_attestation;
 pub mod sync_aggregate;
 pub mod sync_committe_selection;
 pub mod sync_committee;
+pub mod versioned;
 pub mod voluntary_exit;
 pub mod withdrawal;
 pub mod withdrawal_request;
@@ -0,0 +1,62 @@
+use alloy_primitives::B256;
+use ream_consensus_misc::{constants::beacon::SLOTS_PER_EPOCH, misc::compute_epoch_at_slot};
+
+/// Every beacon committee of an epoch, computed at once from the epoch's seed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochShuffling {
+    pub epoch: u64,
+    pub seed: B256,
+    pub committees_per_slot: u64,
+    /// The committees ordered by slot, then by committee index.
+    pub committees: Vec<Vec<u64>>,
+}
+
+impl EpochShuffling {
+    /// The committee at `slot` for `index`, if `slot` is part of the epoch and `index` is in range.
+    pub fn committee(&self, slot: u64, index: u64) -> Option<&[u64]> {
+        if compute_epoch_at_slot(slot) != self.epoch || index >= self.committees_per_slot {
+            return None;
+        }
+
+        self.committees
+            .get(((slot % SLOTS_PER_EPOCH) * self.committees_per_slot + index) as usize)
+            .map(Vec::as_slice)
+    }
+
+    /// The committee assignment of `validator_index` as `(committee, committee index, slot)`.
+    pub fn committee_assignment(&self, validator_index: u64) -> Option<(&[u64], u64, u64)> {
+        let position =
+            self.committees
+                .iter()
+                .position(|committee| committee.contains(&validator_index))? as u64;
+        let slot = self.epoch * SLOTS_PER_EPOCH + position / self.committees_per_slot;
+        let index = position % self.committees_per_slot;
+
+        Some((self.committees[position as usize].as_slice(), index, slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_committee_lookup() {
+        let shuffling = EpochShuffling {
+            epoch: 2,
+            seed: B256::ZERO,
+            committees_per_slot: 2,
+            committees: (0..2 * SLOTS_PER_EPOCH).map(|index| vec![index]).collect(),
+        };
+        let start_slot = 2 * SLOTS_PER_EPOCH;
+
+        assert_eq!(shuffling.committee(start_slot + 1, 1), Some([3].as_slice()));
+        assert_eq!(shuffling.committee(start_slot, 2), None);
+        assert_eq!(shuffling.committee(start_slot - 1, 0), None);
+        assert_eq!(
+            shuffling.committee_assignment(5),
+            Some(([5].as_slice(), 1, start_slot + 2))
+        );
+        assert_eq!(shuffling.committee_assignment(2 * SLOTS_PER_EPOCH), None);
+    }
+}
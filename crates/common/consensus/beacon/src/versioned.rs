@@ -0,0 +1,202 @@
+use alloy_primitives::B256;
+use ream_consensus_misc::{beacon_block_header::SignedBeaconBlockHeader, fork_name::ForkName};
+use ream_network_spec::networks::beacon_network_spec;
+use serde::Serialize;
+use ssz::{Decode, DecodeError, Encode};
+
+use crate::electra::{
+    beacon_block::{BeaconBlock, SignedBeaconBlock},
+    beacon_state::BeaconState,
+};
+
+/// Length of the offset pointing to the `message` of a `SignedBeaconBlock`.
+const SSZ_OFFSET_LENGTH: usize = 4;
+/// Position of `slot` in a `BeaconState`, after `genesis_time` and `genesis_validators_root`.
+const BEACON_STATE_SLOT_OFFSET: usize = 40;
+
+fn read_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
+    let offset_bytes = bytes
+        .get(..SSZ_OFFSET_LENGTH)
+        .ok_or(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: SSZ_OFFSET_LENGTH,
+        })?;
+    Ok(u32::from_ssz_bytes(offset_bytes)? as usize)
+}
+
+fn read_slot(bytes: &[u8], offset: usize) -> Result<u64, DecodeError> {
+    let slot_bytes = bytes
+        .get(offset..offset + 8)
+        .ok_or(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: offset + 8,
+        })?;
+    u64::from_ssz_bytes(slot_bytes)
+}
+
+fn unsupported_fork(fork_name: ForkName) -> DecodeError {
+    DecodeError::BytesInvalid(format!("Unsupported fork: {fork_name}"))
+}
+
+/// A [SignedBeaconBlock] tagged with the fork it belongs to. Fulu didn't change the block
+/// containers, so it reuses the Electra ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum VersionedSignedBeaconBlock {
+    Electra(SignedBeaconBlock),
+    Fulu(SignedBeaconBlock),
+}
+
+impl VersionedSignedBeaconBlock {
+    /// Tag the block with the fork active at its slot.
+    pub fn new(signed_block: SignedBeaconBlock) -> anyhow::Result<Self> {
+        match beacon_network_spec().fork_name_at_slot(signed_block.message.slot.as_u64()) {
+            ForkName::Electra => Ok(Self::Electra(signed_block)),
+            ForkName::Fulu => Ok(Self::Fulu(signed_block)),
+            fork_name => Err(anyhow::anyhow!("Unsupported fork: {fork_name}")),
+        }
+    }
+
+    pub fn fork_name(&self) -> ForkName {
+        match self {
+            Self::Electra(_) => ForkName::Electra,
+            Self::Fulu(_) => ForkName::Fulu,
+        }
+    }
+
+    pub fn message(&self) -> &BeaconBlock {
+        match self {
+            Self::Electra(signed_block) | Self::Fulu(signed_block) => &signed_block.message,
+        }
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.message().slot.as_u64()
+    }
+
+    pub fn parent_root(&self) -> B256 {
+        self.message().parent_root
+    }
+
+    pub fn block_root(&self) -> B256 {
+        self.message().block_root()
+    }
+
+    pub fn signed_header(&self) -> SignedBeaconBlockHeader {
+        match self {
+            Self::Electra(signed_block) | Self::Fulu(signed_block) => signed_block.signed_header(),
+        }
+    }
+
+    pub fn into_inner(self) -> SignedBeaconBlock {
+        match self {
+            Self::Electra(signed_block) | Self::Fulu(signed_block) => signed_block,
+        }
+    }
+
+    pub fn from_ssz_bytes_by_fork(bytes: &[u8], fork_name: ForkName) -> Result<Self, DecodeError> {
+        match fork_name {
+            ForkName::Electra => Ok(Self::Electra(SignedBeaconBlock::from_ssz_bytes(bytes)?)),
+            ForkName::Fulu => Ok(Self::Fulu(SignedBeaconBlock::from_ssz_bytes(bytes)?)),
+            fork_name => Err(unsupported_fork(fork_name)),
+        }
+    }
+
+    /// Decode the block with the container of the fork active at the slot found in `bytes`.
+    pub fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let message_offset = read_offset(bytes)?;
+        let slot = read_slot(bytes, message_offset)?;
+        Self::from_ssz_bytes_by_fork(bytes, beacon_network_spec().fork_name_at_slot(slot))
+    }
+
+    pub fn as_ssz_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Electra(signed_block) | Self::Fulu(signed_block) => signed_block.as_ssz_bytes(),
+        }
+    }
+}
+
+/// A [BeaconState] tagged with the fork it belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum VersionedBeaconState {
+    Electra(Box<BeaconState>),
+}
+
+impl VersionedBeaconState {
+    /// Tag the state with the fork active at its slot.
+    pub fn new(state: BeaconState) -> anyhow::Result<Self> {
+        match beacon_network_spec().fork_name_at_slot(state.slot.as_u64()) {
+            ForkName::Electra => Ok(Self::Electra(Box::new(state))),
+            fork_name => Err(anyhow::anyhow!("Unsupported fork: {fork_name}")),
+        }
+    }
+
+    pub fn fork_name(&self) -> ForkName {
+        match self {
+            Self::Electra(_) => ForkName::Electra,
+        }
+    }
+
+    pub fn slot(&self) -> u64 {
+        match self {
+            Self::Electra(state) => state.slot.as_u64(),
+        }
+    }
+
+    pub fn into_inner(self) -> BeaconState {
+        match self {
+            Self::Electra(state) => *state,
+        }
+    }
+
+    pub fn from_ssz_bytes_by_fork(bytes: &[u8], fork_name: ForkName) -> Result<Self, DecodeError> {
+        match fork_name {
+            ForkName::Electra => Ok(Self::Electra(Box::new(BeaconState::from_ssz_bytes(bytes)?))),
+            fork_name => Err(unsupported_fork(fork_name)),
+        }
+    }
+
+    /// Decode the state with the container of the fork active at the slot found in `bytes`.
+    pub fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let slot = read_slot(bytes, BEACON_STATE_SLOT_OFFSET)?;
+        Self::from_ssz_bytes_by_fork(bytes, beacon_network_spec().fork_name_at_slot(slot))
+    }
+
+    pub fn as_ssz_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Electra(state) => state.as_ssz_bytes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_misc::{misc::compute_start_slot_at_epoch, slot_epoch::Slot};
+    use ream_network_spec::networks::initialize_test_network_spec;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_signed_beacon_block_by_slot() {
+        initialize_test_network_spec();
+
+        let mut signed_block = SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: Slot::new(compute_start_slot_at_epoch(
+                    beacon_network_spec().electra_fork_epoch,
+                )),
+                ..Default::default()
+            },
+            signature: Default::default(),
+        };
+        let versioned_block =
+            VersionedSignedBeaconBlock::from_ssz_bytes(&signed_block.as_ssz_bytes()).unwrap();
+        assert_eq!(versioned_block.fork_name(), ForkName::Electra);
+        assert_eq!(versioned_block.into_inner(), signed_block);
+
+        // Blocks from before Electra aren't supported
+        signed_block.message.slot = Slot::new(0);
+        assert!(VersionedSignedBeaconBlock::from_ssz_bytes(&signed_block.as_ssz_bytes()).is_err());
+    }
+}
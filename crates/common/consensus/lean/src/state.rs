@@ -3,8 +3,8 @@ use std::collections::HashMap;
 use alloy_primitives::B256;
 use anyhow::{Context, anyhow, ensure};
 use itertools::Itertools;
-use ream_consensus_misc::constants::lean::{MAX_HISTORICAL_BLOCK_HASHES, VALIDATOR_REGISTRY_LIMIT};
-use ream_metrics::{FINALIZED_SLOT, HEAD_SLOT, JUSTIFIED_SLOT, set_int_gauge_vec};
+use ream_consensus_misc::constants::lean::MAX_HISTORICAL_BLOCK_HASHES;
+use ream_metrics::{HEAD_SLOT, set_int_gauge_vec};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{
@@ -178,7 +178,7 @@ impl LeanState {
         Ok(())
     }
 
-    fn process_slots(&mut self, slot: u64) -> anyhow::Result<()> {
+    pub fn process_slots(&mut self, slot: u64) -> anyhow::Result<()> {
         ensure!(self.slot < slot, "State slot must be less than block slot");
 
         while self.slot < slot {
@@ -189,7 +189,7 @@ impl LeanState {
         Ok(())
     }
 
-    fn process_slot(&mut self) -> anyhow::Result<()> {
+    pub fn process_slot(&mut self) -> anyhow::Result<()> {
         // Cache latest block header state root
         if self.latest_block_header.state_root == B256::ZERO {
             self.latest_block_header.state_root = self.tree_hash_root();
@@ -198,7 +198,7 @@ impl LeanState {
         Ok(())
     }
 
-    fn process_block(&mut self, block: &Block) -> anyhow::Result<()> {
+    pub fn process_block(&mut self, block: &Block) -> anyhow::Result<()> {
         // Send latest head slot to metrics
         set_int_gauge_vec(&HEAD_SLOT, block.slot as i64, &[]);
 
@@ -371,7 +371,7 @@ impl LeanState {
 
             // Track attempts to justify new hashes
             let justifications = justifications_map.entry(vote.target.root).or_insert(
-                BitList::with_capacity(VALIDATOR_REGISTRY_LIMIT as usize).map_err(|err| {
+                BitList::with_capacity(self.config.num_validators as usize).map_err(|err| {
                     anyhow!(
                         "Failed to initialize justification for root {:?}: {err:?}",
                         &vote.target.root
@@ -406,7 +406,6 @@ impl LeanState {
                     "Block justified: checkpoint={:?}, num_votes={count}",
                     vote.target
                 );
-                set_int_gauge_vec(&JUSTIFIED_SLOT, self.latest_justified.slot as i64, &[]);
 
                 // Finalization: if the target is the next valid justifiable
                 // hash after the source
@@ -418,7 +417,6 @@ impl LeanState {
                     self.latest_finalized = vote.source.clone();
 
                     info!("Block finalized: checkpoint={:?}", vote.source);
-                    set_int_gauge_vec(&FINALIZED_SLOT, self.latest_finalized.slot as i64, &[]);
                 }
             }
         }
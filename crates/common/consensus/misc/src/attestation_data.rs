@@ -3,12 +3,11 @@ use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash_derive::TreeHash;
 
-use crate::checkpoint::Checkpoint;
+use crate::{checkpoint::Checkpoint, slot_epoch::Slot};
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, Hash)]
 pub struct AttestationData {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
+    pub slot: Slot,
     #[serde(with = "serde_utils::quoted_u64")]
     pub index: u64,
 
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash_derive::TreeHash;
 
+use crate::slot_epoch::Epoch;
+
 #[derive(
     Debug,
     Eq,
@@ -22,8 +24,7 @@ use tree_hash_derive::TreeHash;
     Default,
 )]
 pub struct Checkpoint {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub epoch: u64,
+    pub epoch: Epoch,
     pub root: B256,
 }
 
@@ -41,7 +42,7 @@ impl FromStr for Checkpoint {
             .and_then(|hex| B256::from_str(hex).map_err(|_| CheckpointParseError::InvalidHex))?;
 
         let epoch = epoch_str
-            .parse::<u64>()
+            .parse::<Epoch>()
             .map_err(|_| CheckpointParseError::InvalidEpoch)?;
 
         Ok(Self { epoch, root })
@@ -3,6 +3,7 @@ use std::sync::OnceLock;
 use alloy_primitives::{B256, aliases::B32, fixed_bytes};
 
 pub const ATTESTATION_PROPAGATION_SLOT_RANGE: u64 = 32;
+pub const BALANCES_INDEX: u64 = 12;
 pub const BASE_REWARDS_PER_EPOCH: u64 = 4;
 pub const BASE_REWARD_FACTOR: u64 = 64;
 pub const BEACON_STATE_MERKLE_DEPTH: u64 = 6;
@@ -13,16 +14,23 @@ pub const BYTES_PER_COMMITMENT: usize = 48;
 pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
 pub const BYTES_PER_PROOF: usize = 48;
 pub const CAPELLA_FORK_VERSION: B32 = fixed_bytes!("0x03000000");
+pub const CELLS_PER_EXT_BLOB: u64 = 128;
 pub const CHURN_LIMIT_QUOTIENT: u64 = 65536;
+pub const CURRENT_EPOCH_PARTICIPATION_INDEX: u64 = 16;
+pub const CURRENT_SYNC_COMMITTEE_GINDEX_ELECTRA: u64 = 86;
 pub const CURRENT_SYNC_COMMITTEE_INDEX: u64 = 22;
 pub const DEPOSIT_CONTRACT_TREE_DEPTH: u64 = 32;
 pub const DOMAIN_AGGREGATE_AND_PROOF: B32 = fixed_bytes!("0x06000000");
+pub const DOMAIN_APPLICATION_BUILDER: B32 = fixed_bytes!("0x00000001");
 pub const DOMAIN_BEACON_ATTESTER: B32 = fixed_bytes!("0x01000000");
 pub const DOMAIN_BEACON_PROPOSER: B32 = fixed_bytes!("0x00000000");
 pub const DOMAIN_BLS_TO_EXECUTION_CHANGE: B32 = fixed_bytes!("0x0A000000");
+pub const DOMAIN_CONTRIBUTION_AND_PROOF: B32 = fixed_bytes!("0x09000000");
 pub const DOMAIN_DEPOSIT: B32 = fixed_bytes!("0x03000000");
 pub const DOMAIN_RANDAO: B32 = fixed_bytes!("0x02000000");
+pub const DOMAIN_SELECTION_PROOF: B32 = fixed_bytes!("0x05000000");
 pub const DOMAIN_SYNC_COMMITTEE: B32 = fixed_bytes!("0x07000000");
+pub const DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF: B32 = fixed_bytes!("0x08000000");
 pub const DOMAIN_VOLUNTARY_EXIT: B32 = fixed_bytes!("0x04000000");
 pub const EFFECTIVE_BALANCE_INCREMENT: u64 = 1_000_000_000;
 pub const EJECTION_BALANCE: u64 = 16000000000;
@@ -31,10 +39,12 @@ pub const EPOCHS_PER_HISTORICAL_VECTOR: u64 = 65536;
 pub const EPOCHS_PER_SLASHINGS_VECTOR: u64 = 8192;
 pub const EPOCHS_PER_SYNC_COMMITTEE_PERIOD: u64 = 256;
 pub const ETH1_FOLLOW_DISTANCE: u64 = 2048;
+pub const EXECUTION_PAYLOAD_GINDEX: u64 = 25;
 pub const EXECUTION_PAYLOAD_INDEX: u64 = 9;
 pub const FAR_FUTURE_EPOCH: u64 = 18446744073709551615;
 pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
 pub const FINALIZED_CHECKPOINT_INDEX: u64 = 20;
+pub const FINALIZED_ROOT_GINDEX_ELECTRA: u64 = 169;
 pub const GENESIS_SLOT: u64 = 0;
 pub const GENESIS_EPOCH: u64 = 0;
 pub const GENESIS_FORK_VERSION: B32 = fixed_bytes!("0x00000000");
@@ -53,6 +63,7 @@ pub const MAX_COMMITTEES_PER_SLOT: u64 = 64;
 pub const MAX_DEPOSITS: u64 = 16;
 pub const MAX_SEED_LOOKAHEAD: u64 = 4;
 pub const MAX_PER_EPOCH_ACTIVATION_CHURN_LIMIT: u64 = 8;
+pub const MAX_PROPOSER_SLASHINGS: usize = 16;
 pub const MAX_RANDOM_VALUE: u64 = 65535;
 pub const MAX_VALIDATORS_PER_COMMITTEE: u64 = 2048;
 pub const MAX_VALIDATORS_PER_WITHDRAWALS_SWEEP: usize = 16384;
@@ -64,8 +75,10 @@ pub const MIN_GENESIS_TIME: u64 = 1606824000;
 pub const MIN_PER_EPOCH_CHURN_LIMIT: u64 = 4;
 pub const MIN_SEED_LOOKAHEAD: u64 = 1;
 pub const MIN_VALIDATOR_WITHDRAWABILITY_DELAY: u64 = 256;
+pub const NEXT_SYNC_COMMITTEE_GINDEX_ELECTRA: u64 = 87;
 pub const NEXT_SYNC_COMMITTEE_INDEX: u64 = 23;
 pub const NUM_FLAG_INDICES: usize = 3;
+pub const PREVIOUS_EPOCH_PARTICIPATION_INDEX: u64 = 15;
 pub const PROPORTIONAL_SLASHING_MULTIPLIER_BELLATRIX: u64 = 3;
 pub const PROPOSER_REWARD_QUOTIENT: u64 = 8;
 pub const PROPOSER_WEIGHT: u64 = 8;
@@ -87,6 +100,11 @@ pub const TIMELY_TARGET_WEIGHT: u64 = 26;
 pub const TIMELY_HEAD_WEIGHT: u64 = 14;
 pub const UINT64_MAX: u64 = u64::MAX;
 pub const UINT64_MAX_SQRT: u64 = 4294967295;
+pub const UPDATE_TIMEOUT: u64 = SLOTS_PER_EPOCH * EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+pub const VALIDATORS_INDEX: u64 = 11;
+/// The depth of the validator registry below its length mix-in, which is 2^40 long even when the
+/// "zkvm" feature shrinks the list type.
+pub const VALIDATORS_MERKLE_DEPTH: u64 = 40;
 pub const WEIGHT_DENOMINATOR: u64 = 64;
 pub const WHISTLEBLOWER_REWARD_QUOTIENT: u64 = 512;
 
@@ -119,6 +137,12 @@ pub const PENDING_PARTIAL_WITHDRAWALS_LIMIT: u64 = 134_217_728;
 pub const MAX_EFFECTIVE_BALANCE_ELECTRA: u64 = 2_048_000_000_000;
 pub const MIN_ACTIVATION_BALANCE: u64 = 32_000_000_000;
 
+// Max operations per block
+pub const MAX_ATTESTATIONS_ELECTRA: usize = 8;
+pub const MAX_ATTESTER_SLASHINGS_ELECTRA: usize = 1;
+pub const MAX_VOLUNTARY_EXITS: usize = 16;
+pub const MAX_BLS_TO_EXECUTION_CHANGES: usize = 16;
+
 // Pending deposits processing
 pub const MAX_PENDING_DEPOSITS_PER_EPOCH: u64 = 16;
 
@@ -129,6 +153,16 @@ pub const MAX_BLOBS_PER_BLOCK_ELECTRA: u64 = 9;
 pub const MAX_PER_EPOCH_ACTIVATION_EXIT_CHURN_LIMIT: u64 = 256_000_000_000;
 pub const MIN_PER_EPOCH_CHURN_LIMIT_ELECTRA: u64 = 128_000_000_000;
 
+// PeerDAS
+pub const BYTES_PER_CELL: usize = BYTES_PER_FIELD_ELEMENT * FIELD_ELEMENTS_PER_CELL;
+pub const CUSTODY_REQUIREMENT: u64 = 4;
+pub const DATA_COLUMN_SIDECAR_SUBNET_COUNT: u64 = 128;
+pub const FIELD_ELEMENTS_PER_CELL: usize = 64;
+pub const KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH: u64 = 4;
+pub const NUMBER_OF_COLUMNS: u64 = 128;
+pub const NUMBER_OF_CUSTODY_GROUPS: u64 = 128;
+pub const SAMPLES_PER_SLOT: u64 = 8;
+
 pub const PARTICIPATION_FLAG_WEIGHTS: [u64; NUM_FLAG_INDICES] = [
     TIMELY_SOURCE_WEIGHT,
     TIMELY_TARGET_WEIGHT,
@@ -3,12 +3,13 @@ use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash_derive::TreeHash;
 
+use crate::slot_epoch::Epoch;
+
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Encode, Decode, TreeHash, Eq)]
 pub struct Fork {
     pub previous_version: B32,
     pub current_version: B32,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub epoch: u64,
+    pub epoch: Epoch,
 }
 
 impl Fork {
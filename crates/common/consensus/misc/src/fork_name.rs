@@ -0,0 +1,116 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The consensus forks in activation order.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ForkName {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    #[default]
+    Electra,
+    Fulu,
+}
+
+impl ForkName {
+    pub const ALL: [ForkName; 7] = [
+        ForkName::Phase0,
+        ForkName::Altair,
+        ForkName::Bellatrix,
+        ForkName::Capella,
+        ForkName::Deneb,
+        ForkName::Electra,
+        ForkName::Fulu,
+    ];
+
+    pub fn previous(&self) -> Option<ForkName> {
+        match self {
+            ForkName::Phase0 => None,
+            ForkName::Altair => Some(ForkName::Phase0),
+            ForkName::Bellatrix => Some(ForkName::Altair),
+            ForkName::Capella => Some(ForkName::Bellatrix),
+            ForkName::Deneb => Some(ForkName::Capella),
+            ForkName::Electra => Some(ForkName::Deneb),
+            ForkName::Fulu => Some(ForkName::Electra),
+        }
+    }
+
+    pub fn next(&self) -> Option<ForkName> {
+        match self {
+            ForkName::Phase0 => Some(ForkName::Altair),
+            ForkName::Altair => Some(ForkName::Bellatrix),
+            ForkName::Bellatrix => Some(ForkName::Capella),
+            ForkName::Capella => Some(ForkName::Deneb),
+            ForkName::Deneb => Some(ForkName::Electra),
+            ForkName::Electra => Some(ForkName::Fulu),
+            ForkName::Fulu => None,
+        }
+    }
+}
+
+impl Display for ForkName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ForkName::Phase0 => "phase0",
+            ForkName::Altair => "altair",
+            ForkName::Bellatrix => "bellatrix",
+            ForkName::Capella => "capella",
+            ForkName::Deneb => "deneb",
+            ForkName::Electra => "electra",
+            ForkName::Fulu => "fulu",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for ForkName {
+    type Err = ForkNameParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ForkName::ALL
+            .into_iter()
+            .find(|fork_name| fork_name.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ForkNameParseError(s.to_string()))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown fork name: {0}")]
+pub struct ForkNameParseError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_name_round_trip() {
+        for fork_name in ForkName::ALL {
+            assert_eq!(
+                fork_name.to_string().parse::<ForkName>().unwrap(),
+                fork_name
+            );
+            assert_eq!(
+                serde_json::to_string(&fork_name).unwrap(),
+                format!("\"{fork_name}\"")
+            );
+        }
+        assert!("Electra".parse::<ForkName>().is_ok());
+        assert!("gloas".parse::<ForkName>().is_err());
+    }
+
+    #[test]
+    fn test_fork_name_order() {
+        assert!(ForkName::ALL.windows(2).all(|pair| pair[0] < pair[1]
+            && pair[0].next() == Some(pair[1])
+            && pair[1].previous() == Some(pair[0])));
+    }
+}
@@ -9,9 +9,11 @@ pub mod deposit_message;
 pub mod eth_1_data;
 pub mod fork;
 pub mod fork_data;
+pub mod fork_name;
 pub mod historical_batch;
 pub mod indexed_attestation;
 pub mod misc;
 pub mod pending_attestation;
 pub mod signing_data;
+pub mod slot_epoch;
 pub mod validator;
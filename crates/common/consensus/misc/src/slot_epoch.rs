@@ -0,0 +1,286 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    num::ParseIntError,
+    ops::{Add, AddAssign, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use tree_hash::{PackedEncoding, TreeHash, TreeHashType};
+
+use crate::{
+    constants::beacon::{EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SLOTS_PER_EPOCH},
+    misc::{compute_epoch_at_slot, compute_start_slot_at_epoch},
+};
+
+/// A slot number on the beacon chain.
+///
+/// Serializes as a quoted integer in JSON and as a plain `uint64` in SSZ, so it can replace a raw
+/// `u64` field without changing the wire format.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+#[serde(transparent)]
+#[ssz(struct_behaviour = "transparent")]
+pub struct Slot(#[serde(with = "serde_utils::quoted_u64")] u64);
+
+/// An epoch number on the beacon chain.
+///
+/// Serializes as a quoted integer in JSON and as a plain `uint64` in SSZ, so it can replace a raw
+/// `u64` field without changing the wire format.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+#[serde(transparent)]
+#[ssz(struct_behaviour = "transparent")]
+pub struct Epoch(#[serde(with = "serde_utils::quoted_u64")] u64);
+
+impl Slot {
+    pub const fn new(slot: u64) -> Self {
+        Self(slot)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Return the epoch this slot belongs to.
+    pub fn epoch(self) -> Epoch {
+        Epoch(compute_epoch_at_slot(self.0))
+    }
+
+    /// Return the position of this slot within its epoch.
+    pub fn slot_in_epoch(self) -> u64 {
+        self.0 % SLOTS_PER_EPOCH
+    }
+
+    pub fn is_epoch_start(self) -> bool {
+        self.0.is_multiple_of(SLOTS_PER_EPOCH)
+    }
+
+    pub fn sync_committee_period(self) -> u64 {
+        self.epoch().sync_committee_period()
+    }
+}
+
+impl Epoch {
+    pub const fn new(epoch: u64) -> Self {
+        Self(epoch)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Return the first slot of this epoch.
+    pub fn start_slot(self) -> Slot {
+        Slot(compute_start_slot_at_epoch(self.0))
+    }
+
+    /// Return the last slot of this epoch.
+    pub fn end_slot(self) -> Slot {
+        Slot(compute_start_slot_at_epoch(self.0) + SLOTS_PER_EPOCH - 1)
+    }
+
+    /// Return every slot of this epoch in ascending order.
+    pub fn slots(self) -> impl Iterator<Item = Slot> {
+        (self.start_slot().0..=self.end_slot().0).map(Slot)
+    }
+
+    pub fn sync_committee_period(self) -> u64 {
+        self.0 / EPOCHS_PER_SYNC_COMMITTEE_PERIOD
+    }
+}
+
+macro_rules! impl_u64_newtype {
+    ($type:ident) => {
+        impl $type {
+            pub const fn saturating_sub(self, rhs: u64) -> Self {
+                Self(self.0.saturating_sub(rhs))
+            }
+
+            pub const fn checked_sub(self, rhs: u64) -> Option<Self> {
+                match self.0.checked_sub(rhs) {
+                    Some(value) => Some(Self(value)),
+                    None => None,
+                }
+            }
+        }
+
+        impl From<u64> for $type {
+            fn from(value: u64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$type> for u64 {
+            fn from(value: $type) -> Self {
+                value.0
+            }
+        }
+
+        impl Display for $type {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $type {
+            type Err = ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse::<u64>().map(Self)
+            }
+        }
+
+        impl Add<u64> for $type {
+            type Output = Self;
+
+            fn add(self, rhs: u64) -> Self::Output {
+                Self(self.0 + rhs)
+            }
+        }
+
+        impl AddAssign<u64> for $type {
+            fn add_assign(&mut self, rhs: u64) {
+                self.0 += rhs;
+            }
+        }
+
+        impl Sub<u64> for $type {
+            type Output = Self;
+
+            fn sub(self, rhs: u64) -> Self::Output {
+                Self(self.0 - rhs)
+            }
+        }
+
+        impl SubAssign<u64> for $type {
+            fn sub_assign(&mut self, rhs: u64) {
+                self.0 -= rhs;
+            }
+        }
+
+        /// The distance between two values of the same unit.
+        impl Sub for $type {
+            type Output = u64;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                self.0 - rhs.0
+            }
+        }
+
+        impl Rem<u64> for $type {
+            type Output = u64;
+
+            fn rem(self, rhs: u64) -> Self::Output {
+                self.0 % rhs
+            }
+        }
+
+        impl PartialEq<u64> for $type {
+            fn eq(&self, other: &u64) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$type> for u64 {
+            fn eq(&self, other: &$type) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialOrd<u64> for $type {
+            fn partial_cmp(&self, other: &u64) -> Option<Ordering> {
+                self.0.partial_cmp(other)
+            }
+        }
+
+        impl PartialOrd<$type> for u64 {
+            fn partial_cmp(&self, other: &$type) -> Option<Ordering> {
+                self.partial_cmp(&other.0)
+            }
+        }
+
+        impl TreeHash for $type {
+            fn tree_hash_type() -> TreeHashType {
+                u64::tree_hash_type()
+            }
+
+            fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+                self.0.tree_hash_packed_encoding()
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                u64::tree_hash_packing_factor()
+            }
+
+            fn tree_hash_root(&self) -> B256 {
+                self.0.tree_hash_root()
+            }
+        }
+    };
+}
+
+impl_u64_newtype!(Slot);
+impl_u64_newtype!(Epoch);
+
+#[cfg(test)]
+mod tests {
+    use ssz::{Decode, Encode};
+
+    use super::*;
+
+    #[test]
+    fn test_slot_epoch_conversions() {
+        assert_eq!(Slot::new(0).epoch(), Epoch::new(0));
+        assert_eq!(Slot::new(SLOTS_PER_EPOCH - 1).epoch(), Epoch::new(0));
+        assert_eq!(Slot::new(SLOTS_PER_EPOCH).epoch(), Epoch::new(1));
+
+        let epoch = Epoch::new(3);
+        assert_eq!(epoch.start_slot(), Slot::new(3 * SLOTS_PER_EPOCH));
+        assert_eq!(epoch.end_slot(), Slot::new(4 * SLOTS_PER_EPOCH - 1));
+        assert_eq!(epoch.slots().count() as u64, SLOTS_PER_EPOCH);
+        assert!(epoch.start_slot().is_epoch_start());
+        assert_eq!(epoch.end_slot().epoch(), epoch);
+        assert_eq!(epoch.end_slot().slot_in_epoch(), SLOTS_PER_EPOCH - 1);
+    }
+
+    #[test]
+    fn test_slot_wire_format_matches_u64() {
+        let slot = Slot::new(12345);
+
+        assert_eq!(serde_json::to_string(&slot).unwrap(), "\"12345\"");
+        assert_eq!(serde_json::from_str::<Slot>("\"12345\"").unwrap(), slot);
+        assert_eq!(slot.as_ssz_bytes(), 12345_u64.as_ssz_bytes());
+        assert_eq!(Slot::from_ssz_bytes(&slot.as_ssz_bytes()).unwrap(), slot);
+        assert_eq!(slot.tree_hash_root(), 12345_u64.tree_hash_root());
+    }
+}
@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+/// The deadlines of the engine API methods.
+///
+/// https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md
+pub const NEW_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(8);
+pub const FORKCHOICE_UPDATED_TIMEOUT: Duration = Duration::from_secs(8);
+pub const GET_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(1);
+pub const GET_BLOBS_TIMEOUT: Duration = Duration::from_secs(1);
+pub const EXCHANGE_CAPABILITIES_TIMEOUT: Duration = Duration::from_secs(1);
+/// The deadline of the methods of the `eth` namespace.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(12);
+
+/// The number of times a request is sent again after a transient transport failure.
+pub const MAX_RETRIES: u32 = 3;
+/// The delay before the first retry, doubled on every following retry. Up to the same duration of
+/// jitter is added so that retries of concurrent requests don't line up.
+pub const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// How often the sync status of the execution client is polled with `eth_syncing`.
+pub const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(12);
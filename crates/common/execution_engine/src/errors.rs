@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EngineError {
+    /// The execution client didn't answer within the deadline of the method.
+    #[error("{method} timed out after {timeout:?}")]
+    Timeout { method: String, timeout: Duration },
+    /// The request couldn't be delivered or its response couldn't be read.
+    #[error("transport error: {0}")]
+    Transport(reqwest::Error),
+    /// The execution client answered with a response which couldn't be decoded.
+    #[error("invalid response: {0}")]
+    InvalidResponse(reqwest::Error),
+    /// The execution client answered with a JSON-RPC error.
+    #[error("execution client error {code}: {message}")]
+    ExecutionClient { code: i64, message: String },
+    #[error("failed to authenticate: {0}")]
+    Authentication(String),
+}
+
+impl EngineError {
+    pub(crate) fn from_reqwest(method: &str, timeout: Duration, err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            EngineError::Timeout {
+                method: method.to_string(),
+                timeout,
+            }
+        } else if err.is_decode() {
+            EngineError::InvalidResponse(err)
+        } else {
+            EngineError::Transport(err)
+        }
+    }
+
+    /// The label of the error in the metrics, which is the error code for JSON-RPC errors.
+    pub fn metric_label(&self) -> String {
+        match self {
+            EngineError::Timeout { .. } => "timeout".to_string(),
+            EngineError::Transport(_) => "transport".to_string(),
+            EngineError::InvalidResponse(_) => "invalid_response".to_string(),
+            EngineError::ExecutionClient { code, .. } => code.to_string(),
+            EngineError::Authentication(_) => "authentication".to_string(),
+        }
+    }
+
+    /// Returns true if the execution client couldn't be reached.
+    pub fn is_offline(&self) -> bool {
+        matches!(self, EngineError::Transport(_))
+    }
+
+    /// Returns true if the request failed before the execution client could act on it, so it is
+    /// safe to send it again.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, EngineError::Transport(err) if err.is_connect())
+    }
+}
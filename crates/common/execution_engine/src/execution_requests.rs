@@ -0,0 +1,158 @@
+use alloy_primitives::Bytes;
+use anyhow::{anyhow, bail, ensure};
+use ream_consensus_beacon::execution_requests::ExecutionRequests;
+use ream_consensus_misc::constants::beacon::{
+    CONSOLIDATION_REQUEST_TYPE, DEPOSIT_REQUEST_TYPE, WITHDRAWAL_REQUEST_TYPE,
+};
+use ssz::{Decode, Encode};
+
+/// Encode `execution_requests` as the list of typed requests of the Engine API, which leaves out
+/// the request types without requests.
+pub fn get_execution_requests_list(execution_requests: &ExecutionRequests) -> Vec<Bytes> {
+    let mut requests_list = vec![];
+    if !execution_requests.deposits.is_empty() {
+        requests_list.push(Bytes::from(
+            [
+                vec![DEPOSIT_REQUEST_TYPE],
+                execution_requests.deposits.as_ssz_bytes(),
+            ]
+            .concat(),
+        ));
+    }
+    if !execution_requests.withdrawals.is_empty() {
+        requests_list.push(Bytes::from(
+            [
+                vec![WITHDRAWAL_REQUEST_TYPE],
+                execution_requests.withdrawals.as_ssz_bytes(),
+            ]
+            .concat(),
+        ));
+    }
+    if !execution_requests.consolidations.is_empty() {
+        requests_list.push(Bytes::from(
+            [
+                vec![CONSOLIDATION_REQUEST_TYPE],
+                execution_requests.consolidations.as_ssz_bytes(),
+            ]
+            .concat(),
+        ));
+    }
+    requests_list
+}
+
+/// Decode the list of typed requests of the Engine API, the inverse of
+/// [get_execution_requests_list].
+///
+/// The list must be in strictly ascending order of request type, and every entry must hold at
+/// least one request of a known type, as any other encoding would give the payload a different
+/// `requests_hash`.
+pub fn get_execution_requests(
+    execution_requests_list: &[Bytes],
+) -> anyhow::Result<ExecutionRequests> {
+    let mut execution_requests = ExecutionRequests::default();
+    let mut previous_request_type = None;
+    for request in execution_requests_list {
+        let Some((&request_type, request_data)) = request.split_first() else {
+            bail!("Empty request in execution requests");
+        };
+        ensure!(
+            !request_data.is_empty(),
+            "Request of type {request_type} without any request data"
+        );
+        ensure!(
+            previous_request_type < Some(request_type),
+            "Execution requests aren't in strictly ascending order of type, found type {request_type} after {previous_request_type:?}"
+        );
+        previous_request_type = Some(request_type);
+
+        match request_type {
+            DEPOSIT_REQUEST_TYPE => {
+                execution_requests.deposits = decode_requests("deposit", request_data)?
+            }
+            WITHDRAWAL_REQUEST_TYPE => {
+                execution_requests.withdrawals = decode_requests("withdrawal", request_data)?
+            }
+            CONSOLIDATION_REQUEST_TYPE => {
+                execution_requests.consolidations = decode_requests("consolidation", request_data)?
+            }
+            _ => bail!("Invalid request type: {request_type}"),
+        }
+    }
+    Ok(execution_requests)
+}
+
+fn decode_requests<T: Decode>(request_kind: &str, request_data: &[u8]) -> anyhow::Result<T> {
+    T::from_ssz_bytes(request_data)
+        .map_err(|err| anyhow!("Failed to decode the {request_kind} requests: {err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+    use ream_consensus_beacon::{
+        consolidation_request::ConsolidationRequest, withdrawal_request::WithdrawalRequest,
+    };
+
+    use super::*;
+
+    fn execution_requests() -> ExecutionRequests {
+        ExecutionRequests {
+            deposits: Default::default(),
+            withdrawals: vec![WithdrawalRequest {
+                source_address: Address::repeat_byte(1),
+                validator_public_key: Default::default(),
+                amount: 32,
+            }]
+            .into(),
+            consolidations: vec![ConsolidationRequest {
+                source_address: Address::repeat_byte(2),
+                source_public_key: Default::default(),
+                target_public_key: Default::default(),
+            }]
+            .into(),
+        }
+    }
+
+    #[test]
+    fn test_execution_requests_round_trip() {
+        let execution_requests = execution_requests();
+        let execution_requests_list = get_execution_requests_list(&execution_requests);
+        assert_eq!(execution_requests_list.len(), 2);
+        assert_eq!(
+            get_execution_requests(&execution_requests_list).unwrap(),
+            execution_requests
+        );
+        assert_eq!(
+            get_execution_requests(&[]).unwrap(),
+            ExecutionRequests::default()
+        );
+    }
+
+    #[test]
+    fn test_malformed_execution_requests_are_rejected() {
+        let execution_requests_list = get_execution_requests_list(&execution_requests());
+        let (withdrawals, consolidations) = (
+            execution_requests_list[0].clone(),
+            execution_requests_list[1].clone(),
+        );
+
+        for malformed_list in [
+            // Out of order
+            vec![consolidations.clone(), withdrawals.clone()],
+            // Duplicated type
+            vec![withdrawals.clone(), withdrawals.clone()],
+            // Type without requests
+            vec![Bytes::from(vec![DEPOSIT_REQUEST_TYPE]), withdrawals.clone()],
+            vec![Bytes::new()],
+            // Unknown type
+            vec![withdrawals.clone(), Bytes::from(vec![3, 0])],
+            // Truncated request
+            vec![withdrawals.slice(..withdrawals.len() - 1)],
+        ] {
+            assert!(
+                get_execution_requests(&malformed_list).is_err(),
+                "{malformed_list:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::rpc_types::eth_syncing::EthSyncing;
+
+/// The status of the execution client, as of the last `eth_syncing` poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ExecutionEngineStatus {
+    Offline = 0,
+    Syncing = 1,
+    Synced = 2,
+}
+
+impl From<u8> for ExecutionEngineStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => ExecutionEngineStatus::Syncing,
+            2 => ExecutionEngineStatus::Synced,
+            _ => ExecutionEngineStatus::Offline,
+        }
+    }
+}
+
+impl From<&EthSyncing> for ExecutionEngineStatus {
+    fn from(eth_syncing: &EthSyncing) -> Self {
+        match eth_syncing {
+            EthSyncing::NotSyncing(false) => ExecutionEngineStatus::Synced,
+            EthSyncing::NotSyncing(true) | EthSyncing::SyncingInfo(_) => {
+                ExecutionEngineStatus::Syncing
+            }
+        }
+    }
+}
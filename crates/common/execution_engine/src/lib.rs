@@ -1,42 +1,76 @@
+pub mod constants;
+pub mod errors;
+pub mod execution_requests;
+pub mod health;
 pub mod rpc_types;
 pub mod utils;
 
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicU8, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
 use alloy_primitives::{Address, B64, B256, Bytes, U64, hex};
 use alloy_rpc_types_eth::{Block, BlockId, BlockNumberOrTag, Filter, Log, TransactionRequest};
-use anyhow::anyhow;
+use anyhow::{anyhow, ensure};
 use async_trait::async_trait;
+use constants::{
+    DEFAULT_TIMEOUT, EXCHANGE_CAPABILITIES_TIMEOUT, FORKCHOICE_UPDATED_TIMEOUT, GET_BLOBS_TIMEOUT,
+    GET_PAYLOAD_TIMEOUT, MAX_RETRIES, NEW_PAYLOAD_TIMEOUT, RETRY_BASE_DELAY, STATUS_POLL_INTERVAL,
+};
+use errors::EngineError;
+use execution_requests::{get_execution_requests, get_execution_requests_list};
+use health::ExecutionEngineStatus;
 use jsonwebtoken::{EncodingKey, Header, encode, get_current_timestamp};
+use rand::Rng;
+#[cfg(feature = "peerdas")]
+use ream_consensus_beacon::execution_engine::rpc_types::get_blobs::BlobAndProofV2;
 use ream_consensus_beacon::{
     electra::execution_payload::ExecutionPayload,
     execution_engine::{
         engine_trait::ExecutionApi, new_payload_request::NewPayloadRequest,
         rpc_types::get_blobs::BlobAndProofV1,
     },
-    execution_requests::ExecutionRequests,
 };
-use ream_consensus_misc::constants::beacon::{
-    CONSOLIDATION_REQUEST_TYPE, DEPOSIT_REQUEST_TYPE, WITHDRAWAL_REQUEST_TYPE,
+use ream_metrics::{
+    ENGINE_REQUEST_ERRORS, ENGINE_REQUEST_TIME, EXECUTION_ENGINE_STATUS, inc_int_counter_vec,
+    set_int_gauge_vec, start_timer_vec, stop_timer,
 };
 use reqwest::{Client, Request, Url};
 use rpc_types::{
     eth_syncing::EthSyncing,
     execution_payload::ExecutionPayloadV3,
     forkchoice_update::{ForkchoiceStateV1, ForkchoiceUpdateResult, PayloadAttributesV3},
-    get_payload::PayloadV4,
+    get_payload::{BuiltPayload, PayloadV4},
     payload_status::{PayloadStatus, PayloadStatusV1},
 };
-use serde_json::json;
-use ssz::Encode;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
 use ssz_types::VariableList;
+use tracing::warn;
 use utils::{Claims, JsonRpcRequest, JsonRpcResponse, blob_versioned_hashes, strip_prefix};
 
+/// ExecutionEngine is the client of the engine API and the `eth` JSON-RPC namespace of the
+/// execution client.
+///
+/// Every request gets a unique id and the deadline of its method. Requests which fail to reach the
+/// execution client are retried up to [MAX_RETRIES] times with a jittered exponential backoff.
+///
+/// The status of the execution client is shared between the clones of the engine, and kept up to
+/// date by [ExecutionEngine::poll_status].
 #[derive(Clone)]
 pub struct ExecutionEngine {
     http_client: Client,
     jwt_encoding_key: EncodingKey,
     engine_api_url: Url,
+    request_id: Arc<AtomicU64>,
+    timeouts: HashMap<String, Duration>,
+    status: Arc<AtomicU8>,
 }
 
 impl ExecutionEngine {
@@ -47,9 +81,63 @@ impl ExecutionEngine {
             http_client: Client::new(),
             jwt_encoding_key: EncodingKey::from_secret(jwt_private_key.as_slice()),
             engine_api_url,
+            request_id: Arc::new(AtomicU64::new(1)),
+            timeouts: HashMap::new(),
+            status: Arc::new(AtomicU8::new(ExecutionEngineStatus::Offline as u8)),
         })
     }
 
+    pub fn status(&self) -> ExecutionEngineStatus {
+        self.status.load(Ordering::Relaxed).into()
+    }
+
+    /// Returns true if the execution client is online and synced, so that payloads can be
+    /// validated and built.
+    pub fn is_execution_engine_healthy(&self) -> bool {
+        self.status() == ExecutionEngineStatus::Synced
+    }
+
+    fn set_status(&self, status: ExecutionEngineStatus) {
+        self.status.store(status as u8, Ordering::Relaxed);
+        set_int_gauge_vec(&EXECUTION_ENGINE_STATUS, status as i64, &[]);
+    }
+
+    /// Polls `eth_syncing` every [STATUS_POLL_INTERVAL] to keep the status of the execution
+    /// client up to date.
+    pub async fn poll_status(self) {
+        let mut interval = tokio::time::interval(STATUS_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            match self.eth_syncing().await {
+                Ok(eth_syncing) => self.set_status((&eth_syncing).into()),
+                Err(err) => {
+                    warn!("Failed to poll the sync status of the execution client: {err}");
+                    self.set_status(ExecutionEngineStatus::Offline);
+                }
+            }
+        }
+    }
+
+    /// Overrides the deadline of `method`.
+    pub fn with_timeout(mut self, method: &str, timeout: Duration) -> Self {
+        self.timeouts.insert(method.to_string(), timeout);
+        self
+    }
+
+    pub fn timeout(&self, method: &str) -> Duration {
+        if let Some(timeout) = self.timeouts.get(method) {
+            return *timeout;
+        }
+        match method {
+            "engine_newPayloadV4" => NEW_PAYLOAD_TIMEOUT,
+            "engine_forkchoiceUpdatedV3" => FORKCHOICE_UPDATED_TIMEOUT,
+            "engine_getPayloadV4" => GET_PAYLOAD_TIMEOUT,
+            "engine_getBlobsV1" | "engine_getBlobsV2" => GET_BLOBS_TIMEOUT,
+            "engine_exchangeCapabilities" => EXCHANGE_CAPABILITIES_TIMEOUT,
+            _ => DEFAULT_TIMEOUT,
+        }
+    }
+
     pub fn create_jwt_token(&self) -> anyhow::Result<String> {
         let header = Header::default();
         let claims = Claims {
@@ -96,67 +184,82 @@ impl ExecutionEngine {
         Ok(payload_status.status)
     }
 
-    pub fn build_request(&self, rpc_request: JsonRpcRequest) -> anyhow::Result<Request> {
-        Ok(self
-            .http_client
+    pub fn build_request(&self, rpc_request: &JsonRpcRequest) -> Result<Request, EngineError> {
+        let jwt_token = self
+            .create_jwt_token()
+            .map_err(|err| EngineError::Authentication(err.to_string()))?;
+        self.http_client
             .post(self.engine_api_url.clone())
-            .json(&rpc_request)
-            .bearer_auth(self.create_jwt_token()?)
-            .build()?)
+            .json(rpc_request)
+            .bearer_auth(jwt_token)
+            .timeout(self.timeout(&rpc_request.method))
+            .build()
+            .map_err(EngineError::Transport)
     }
 
-    pub async fn eth_syncing(&self) -> anyhow::Result<EthSyncing> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_syncing".to_string(),
-            params: vec![],
+    /// Sends `method` to the execution client, retrying it if it fails before reaching the
+    /// execution client.
+    async fn rpc_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Vec<Value>,
+    ) -> Result<T, EngineError> {
+        let timer = start_timer_vec(&ENGINE_REQUEST_TIME, &[method]);
+        let mut retries = 0;
+        let result = loop {
+            match self.send_request(method, params.clone()).await {
+                Err(err) if err.is_transient() && retries < MAX_RETRIES => {
+                    retries += 1;
+                    tokio::time::sleep(retry_delay(retries)).await;
+                }
+                result => break result,
+            }
         };
+        stop_timer(timer);
 
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<EthSyncing>>()
-            .await?
-            .to_result()
+        if let Err(err) = &result {
+            inc_int_counter_vec(&ENGINE_REQUEST_ERRORS, &[method, &err.metric_label()]);
+            if err.is_offline() {
+                self.set_status(ExecutionEngineStatus::Offline);
+            }
+        }
+        result
     }
 
-    pub async fn eth_block_number(&self) -> anyhow::Result<B64> {
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Vec<Value>,
+    ) -> Result<T, EngineError> {
         let request_body = JsonRpcRequest {
-            id: 1,
+            id: self.request_id.fetch_add(1, Ordering::Relaxed),
             jsonrpc: "2.0".to_string(),
-            method: "eth_blockNumber".to_string(),
-            params: vec![],
+            method: method.to_string(),
+            params,
         };
-
-        let http_post_request = self.build_request(request_body)?;
+        let timeout = self.timeout(method);
+        let http_post_request = self.build_request(&request_body)?;
 
         self.http_client
             .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<B64>>()
-            .await?
+            .await
+            .map_err(|err| EngineError::from_reqwest(method, timeout, err))?
+            .json::<JsonRpcResponse<T>>()
+            .await
+            .map_err(|err| EngineError::from_reqwest(method, timeout, err))?
             .to_result()
     }
 
-    pub async fn eth_chain_id(&self) -> anyhow::Result<U64> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_chainId".to_string(),
-            params: vec![],
-        };
+    pub async fn eth_syncing(&self) -> anyhow::Result<EthSyncing> {
+        Ok(self.rpc_request("eth_syncing", vec![]).await?)
+    }
 
-        let http_post_request = self.build_request(request_body)?;
+    pub async fn eth_block_number(&self) -> anyhow::Result<B64> {
+        Ok(self.rpc_request("eth_blockNumber", vec![]).await?)
+    }
 
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<U64>>()
-            .await?
-            .to_result()
+    pub async fn eth_chain_id(&self) -> anyhow::Result<U64> {
+        Ok(self.rpc_request("eth_chainId", vec![]).await?)
     }
 
     pub async fn eth_get_block_by_number(
@@ -164,21 +267,12 @@ impl ExecutionEngine {
         block_number_or_tag: BlockNumberOrTag,
         hydrated: bool,
     ) -> anyhow::Result<Block> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_getBlockByNumber".to_string(),
-            params: vec![json!(block_number_or_tag), json!(hydrated)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<Block>>()
-            .await?
-            .to_result()
+        Ok(self
+            .rpc_request(
+                "eth_getBlockByNumber",
+                vec![json!(block_number_or_tag), json!(hydrated)],
+            )
+            .await?)
     }
 
     pub async fn eth_get_block_by_hash(
@@ -186,39 +280,16 @@ impl ExecutionEngine {
         block_hash: B256,
         hydrated: bool,
     ) -> anyhow::Result<Block> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_getBlockByHash".to_string(),
-            params: vec![json!(block_hash), json!(hydrated)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<Block>>()
-            .await?
-            .to_result()
+        Ok(self
+            .rpc_request(
+                "eth_getBlockByHash",
+                vec![json!(block_hash), json!(hydrated)],
+            )
+            .await?)
     }
 
     pub async fn eth_get_logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_getLogs".to_string(),
-            params: vec![json!(filter)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<Vec<Log>>>()
-            .await?
-            .to_result()
+        Ok(self.rpc_request("eth_getLogs", vec![json!(filter)]).await?)
     }
 
     pub async fn eth_call(
@@ -231,99 +302,92 @@ impl ExecutionEngine {
             params.push(json!(block));
         }
 
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_call".to_string(),
-            params,
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<Bytes>>()
-            .await?
-            .to_result()
+        Ok(self.rpc_request("eth_call", params).await?)
     }
 
     pub async fn eth_send_raw_transaction(&self, transaction: Bytes) -> anyhow::Result<B256> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_sendRawTransaction".to_string(),
-            params: vec![json!(transaction)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
+        Ok(self
+            .rpc_request("eth_sendRawTransaction", vec![json!(transaction)])
+            .await?)
+    }
 
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<B256>>()
-            .await?
-            .to_result()
+    pub async fn eth_get_transaction_count(
+        &self,
+        address: Address,
+        block_id: BlockId,
+    ) -> anyhow::Result<U64> {
+        Ok(self
+            .rpc_request(
+                "eth_getTransactionCount",
+                vec![json!(address), json!(block_id)],
+            )
+            .await?)
     }
 
     pub async fn eth_get_code(&self, address: Address, block_id: BlockId) -> anyhow::Result<Bytes> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "eth_getCode".to_string(),
-            params: vec![json!(address), json!(block_id)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<Bytes>>()
-            .await?
-            .to_result()
+        Ok(self
+            .rpc_request("eth_getCode", vec![json!(address), json!(block_id)])
+            .await?)
     }
 
-    pub async fn engine_exchange_capabilities(&self) -> anyhow::Result<Vec<String>> {
-        let capabilities: Vec<String> = vec![
-            "engine_forkchoiceUpdatedV3".to_string(),
-            "engine_getBlobsV1".to_string(),
-            "engine_getPayloadV4".to_string(),
-            "engine_newPayloadV4".to_string(),
-        ];
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "engine_exchangeCapabilities".to_string(),
-            params: vec![json!(capabilities)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<Vec<String>>>()
-            .await?
-            .to_result()
+    /// Fetch blobs with the proofs of all their cells. Unlike `engine_getBlobsV1` the execution
+    /// client answers with `null` unless it has every requested blob.
+    #[cfg(feature = "peerdas")]
+    pub async fn engine_get_blobs_v2(
+        &self,
+        blob_version_hashes: Vec<B256>,
+    ) -> anyhow::Result<Option<Vec<BlobAndProofV2>>> {
+        Ok(self
+            .rpc_request("engine_getBlobsV2", vec![json!(blob_version_hashes)])
+            .await?)
     }
 
     pub async fn engine_get_payload_v4(&self, payload_id: B64) -> anyhow::Result<PayloadV4> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "engine_getPayloadV4".to_string(),
-            params: vec![json!(payload_id)],
-        };
+        Ok(self
+            .rpc_request("engine_getPayloadV4", vec![json!(payload_id)])
+            .await?)
+    }
 
-        let http_post_request = self.build_request(request_body)?;
+    /// Fetch the payload built for `payload_id` on top of `parent_beacon_block_root`, and decode
+    /// its execution requests.
+    ///
+    /// The block hash of the payload commits to the requests through the `requests_hash` of its
+    /// header, so a payload whose hash doesn't match the requests which came with it is rejected
+    /// instead of being put into a block.
+    pub async fn get_payload(
+        &self,
+        payload_id: B64,
+        parent_beacon_block_root: B256,
+    ) -> anyhow::Result<BuiltPayload> {
+        let PayloadV4 {
+            execution_payload,
+            block_value,
+            blobs_bundle,
+            should_override_builder,
+            execution_requests,
+        } = self.engine_get_payload_v4(payload_id).await?;
+        let execution_payload = ExecutionPayload::from(execution_payload);
+        let execution_requests = get_execution_requests(&execution_requests)
+            .map_err(|err| anyhow!("Invalid execution requests in payload {payload_id}: {err}"))?;
+
+        // The requests are hashed as encoded again, which is the encoding of the block
+        ensure!(
+            self.is_valid_block_hash(
+                &execution_payload,
+                parent_beacon_block_root,
+                &get_execution_requests_list(&execution_requests),
+            ),
+            "Block hash {} of payload {payload_id} doesn't commit to its execution requests",
+            execution_payload.block_hash
+        );
 
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<PayloadV4>>()
-            .await?
-            .to_result()
+        Ok(BuiltPayload {
+            execution_payload,
+            block_value,
+            blobs_bundle,
+            should_override_builder,
+            execution_requests,
+        })
     }
 
     pub async fn engine_new_payload_v4(
@@ -333,26 +397,17 @@ impl ExecutionEngine {
         parent_beacon_block_root: B256,
         execution_requests: Vec<Bytes>,
     ) -> anyhow::Result<PayloadStatusV1> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "engine_newPayloadV4".to_string(),
-            params: vec![
-                json!(execution_payload),
-                json!(expected_blob_versioned_hashes),
-                json!(parent_beacon_block_root),
-                json!(execution_requests),
-            ],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<PayloadStatusV1>>()
-            .await?
-            .to_result()
+        Ok(self
+            .rpc_request(
+                "engine_newPayloadV4",
+                vec![
+                    json!(execution_payload),
+                    json!(expected_blob_versioned_hashes),
+                    json!(parent_beacon_block_root),
+                    json!(execution_requests),
+                ],
+            )
+            .await?)
     }
 
     pub async fn engine_forkchoice_updated_v3(
@@ -360,24 +415,22 @@ impl ExecutionEngine {
         forkchoice_state: ForkchoiceStateV1,
         payload_attributes: Option<PayloadAttributesV3>,
     ) -> anyhow::Result<ForkchoiceUpdateResult> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "engine_forkchoiceUpdatedV3".to_string(),
-            params: vec![json!(forkchoice_state), json!(payload_attributes)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
-
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<ForkchoiceUpdateResult>>()
-            .await?
-            .to_result()
+        Ok(self
+            .rpc_request(
+                "engine_forkchoiceUpdatedV3",
+                vec![json!(forkchoice_state), json!(payload_attributes)],
+            )
+            .await?)
     }
 }
 
+/// The delay before the `retry`th retry, doubling from [RETRY_BASE_DELAY] with up to as much
+/// jitter.
+fn retry_delay(retry: u32) -> Duration {
+    let jitter = rand::rng().random_range(0..=RETRY_BASE_DELAY.as_millis() as u64);
+    RETRY_BASE_DELAY * 2u32.pow(retry - 1) + Duration::from_millis(jitter)
+}
+
 /// Return ``True`` if and only if the version hashes computed by the blob transactions of
 /// ``new_payload_request.execution_payload`` matches ``new_payload_request.versioned_hashes``.
 pub fn is_valid_versioned_hashes(new_payload_request: &NewPayloadRequest) -> anyhow::Result<bool> {
@@ -387,38 +440,6 @@ pub fn is_valid_versioned_hashes(new_payload_request: &NewPayloadRequest) -> any
     )
 }
 
-fn get_execution_requests_list(execution_requests: &ExecutionRequests) -> Vec<Bytes> {
-    let mut requests_list = vec![];
-    if !execution_requests.deposits.is_empty() {
-        requests_list.push(Bytes::from(
-            [
-                vec![DEPOSIT_REQUEST_TYPE],
-                execution_requests.deposits.as_ssz_bytes(),
-            ]
-            .concat(),
-        ));
-    }
-    if !execution_requests.withdrawals.is_empty() {
-        requests_list.push(Bytes::from(
-            [
-                vec![WITHDRAWAL_REQUEST_TYPE],
-                execution_requests.withdrawals.as_ssz_bytes(),
-            ]
-            .concat(),
-        ));
-    }
-    if !execution_requests.consolidations.is_empty() {
-        requests_list.push(Bytes::from(
-            [
-                vec![CONSOLIDATION_REQUEST_TYPE],
-                execution_requests.consolidations.as_ssz_bytes(),
-            ]
-            .concat(),
-        ));
-    }
-    requests_list
-}
-
 #[async_trait]
 impl ExecutionApi for ExecutionEngine {
     async fn verify_and_notify_new_payload(
@@ -454,20 +475,23 @@ impl ExecutionApi for ExecutionEngine {
         &self,
         blob_version_hashes: Vec<B256>,
     ) -> anyhow::Result<Vec<Option<BlobAndProofV1>>> {
-        let request_body = JsonRpcRequest {
-            id: 1,
-            jsonrpc: "2.0".to_string(),
-            method: "engine_getBlobsV1".to_string(),
-            params: vec![json!(blob_version_hashes)],
-        };
-
-        let http_post_request = self.build_request(request_body)?;
+        Ok(self
+            .rpc_request("engine_getBlobsV1", vec![json!(blob_version_hashes)])
+            .await?)
+    }
 
-        self.http_client
-            .execute(http_post_request)
-            .await?
-            .json::<JsonRpcResponse<Vec<Option<BlobAndProofV1>>>>()
-            .await?
-            .to_result()
+    async fn engine_exchange_capabilities(&self) -> anyhow::Result<Vec<String>> {
+        #[cfg_attr(not(feature = "peerdas"), expect(unused_mut))]
+        let mut capabilities: Vec<String> = vec![
+            "engine_forkchoiceUpdatedV3".to_string(),
+            "engine_getBlobsV1".to_string(),
+            "engine_getPayloadV4".to_string(),
+            "engine_newPayloadV4".to_string(),
+        ];
+        #[cfg(feature = "peerdas")]
+        capabilities.push("engine_getBlobsV2".to_string());
+        Ok(self
+            .rpc_request("engine_exchangeCapabilities", vec![json!(capabilities)])
+            .await?)
     }
 }
@@ -1,24 +1,23 @@
 use alloy_primitives::{B256, Bytes};
-use ream_consensus_beacon::polynomial_commitments::kzg_commitment::KZGCommitment;
+use ream_consensus_beacon::{
+    electra::execution_payload::ExecutionPayload,
+    execution_engine::rpc_types::get_blobs::Blob,
+    execution_requests::ExecutionRequests,
+    polynomial_commitments::{kzg_commitment::KZGCommitment, kzg_proof::KZGProof},
+};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use ssz_types::{
-    VariableList,
-    serde_utils::list_of_hex_var_list,
-    typenum::{U96, U1024, U1048576},
-};
-use tree_hash_derive::TreeHash;
+use ssz_types::{VariableList, typenum::U4096};
 
 use super::execution_payload::ExecutionPayloadV3;
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
+/// The blobs of a built payload, with the commitment and proof of each blob at the same index.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 #[serde(rename_all = "camelCase")]
 pub struct BlobsBundleV1 {
-    pub blobs: VariableList<KZGCommitment, U1048576>,
-    #[serde(with = "list_of_hex_var_list")]
-    pub commitments: VariableList<VariableList<u8, U96>, U1024>,
-    #[serde(with = "list_of_hex_var_list")]
-    pub proofs: VariableList<VariableList<u8, U96>, U1024>,
+    pub commitments: VariableList<KZGCommitment, U4096>,
+    pub proofs: VariableList<KZGProof, U4096>,
+    pub blobs: VariableList<Blob, U4096>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -27,6 +26,16 @@ pub struct PayloadV4 {
     pub execution_payload: ExecutionPayloadV3,
     pub block_value: B256,
     pub blobs_bundle: BlobsBundleV1,
-    pub should_overide_builder: bool,
+    pub should_override_builder: bool,
     pub execution_requests: Vec<Bytes>,
 }
+
+/// A [PayloadV4] whose execution requests were decoded and checked against its block hash.
+#[derive(Debug)]
+pub struct BuiltPayload {
+    pub execution_payload: ExecutionPayload,
+    pub block_value: B256,
+    pub blobs_bundle: BlobsBundleV1,
+    pub should_override_builder: bool,
+    pub execution_requests: ExecutionRequests,
+}
@@ -1,12 +1,13 @@
 use alloy_primitives::B256;
 use alloy_rlp::Decodable;
-use anyhow::{anyhow, bail};
+use anyhow::anyhow;
 use ream_consensus_beacon::{
     electra::execution_payload::Transactions,
     execution_engine::rpc_types::transaction::{BlobTransaction, TransactionType},
 };
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+
+use crate::errors::EngineError;
 
 pub fn strip_prefix(string: &str) -> &str {
     if let Some(stripped) = string.strip_prefix("0x") {
@@ -18,25 +19,35 @@ pub fn strip_prefix(string: &str) -> &str {
 
 #[derive(Serialize, Deserialize)]
 pub struct JsonRpcRequest {
-    pub id: i32,
+    pub id: u64,
     pub jsonrpc: String,
     pub method: String,
     pub params: Vec<serde_json::Value>,
 }
 
-// Define a wrapper struct to extract "result" without cloning
+#[derive(Deserialize, Debug)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+// Define a wrapper struct to extract "result" without cloning. The error is matched first, as a
+// missing "result" would be read as `None` for optional results.
 #[derive(Deserialize)]
 #[serde(untagged)]
 pub enum JsonRpcResponse<T> {
+    Error { error: JsonRpcError },
     Result { result: T },
-    Error(Value),
 }
 
 impl<T> JsonRpcResponse<T> {
-    pub fn to_result(self) -> anyhow::Result<T> {
+    pub fn to_result(self) -> Result<T, EngineError> {
         match self {
             JsonRpcResponse::Result { result } => Ok(result),
-            JsonRpcResponse::Error(err) => bail!("Failed to desirilze json {err:?}"),
+            JsonRpcResponse::Error { error } => Err(EngineError::ExecutionClient {
+                code: error.code,
+                message: error.message,
+            }),
         }
     }
 }
@@ -58,6 +58,31 @@ impl ReamExecutor {
         })
     }
 
+    /// Spawns a task which is handed the shutdown signal instead of being cancelled by it, so it
+    /// can wind down within the grace period of [Self::shutdown_runtime].
+    pub fn spawn_with_shutdown<F, Fut>(&self, future_fn: F) -> JoinHandle<Fut::Output>
+    where
+        F: FnOnce(broadcast::Receiver<()>) -> Fut,
+        Fut: Future + Send + 'static,
+        Fut::Output: Send + 'static,
+    {
+        self.runtime.spawn(future_fn(self.shutdown.subscribe()))
+    }
+
+    /// Spawns a task which only runs once the shutdown signal is sent, e.g. to persist in-memory
+    /// data before exiting. It has to finish within the grace period of [Self::shutdown_runtime].
+    pub fn spawn_on_shutdown<F>(&self, future: F) -> JoinHandle<Option<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let mut shutdown = self.shutdown.subscribe();
+        self.runtime.spawn(async move {
+            shutdown.recv().await.ok()?;
+            Some(future.await)
+        })
+    }
+
     /// Spawns a blocking task in a dedicated thread pool
     pub fn spawn_blocking<F, R>(&self, task: F) -> JoinHandle<R>
     where
@@ -155,6 +180,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_spawn_on_shutdown() {
+        let executor = ReamExecutor::new().unwrap();
+
+        let handle = executor.spawn_on_shutdown(async { 42 });
+        executor
+            .runtime
+            .block_on(async { sleep(Duration::from_millis(50)).await });
+        assert!(!handle.is_finished());
+
+        executor.shutdown_signal();
+        assert_eq!(executor.runtime.block_on(handle).unwrap(), Some(42));
+    }
+
     #[test]
     fn test_spawn_many() {
         let executor = ReamExecutor::new().unwrap();
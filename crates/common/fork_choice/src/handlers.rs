@@ -5,24 +5,33 @@ use ream_consensus_beacon::{
     electra::beacon_block::SignedBeaconBlock, execution_engine::engine_trait::ExecutionApi,
     predicates::is_slashable_attestation_data,
 };
-use ream_consensus_misc::{
-    constants::beacon::INTERVALS_PER_SLOT, misc::compute_start_slot_at_epoch,
-};
+use ream_consensus_misc::constants::beacon::INTERVALS_PER_SLOT;
 use ream_network_spec::networks::beacon_network_spec;
 use ream_storage::{
     errors::StoreError,
     tables::{field::Field, table::Table},
 };
+use tracing::{debug_span, instrument};
 use tree_hash::TreeHash;
 
 use crate::store::Store;
 
 /// Run ``on_block`` upon receiving a new block.
+///
+/// The proposer signature is only verified when ``verify_signature`` is set, so blocks whose
+/// signatures were verified in a batch already aren't verified again.
+#[instrument(
+    level = "debug",
+    name = "fork_choice_on_block",
+    skip_all,
+    fields(slot = signed_block.message.slot.as_u64())
+)]
 pub async fn on_block(
     store: &mut Store,
     signed_block: &SignedBeaconBlock,
     execution_engine: &Option<impl ExecutionApi>,
     verify_blob_availability: bool,
+    verify_signature: bool,
 ) -> anyhow::Result<()> {
     let block = &signed_block.message;
 
@@ -48,8 +57,12 @@ pub async fn on_block(
 
     // Check that block is later than the finalized epoch slot (optimization to reduce calls to
     // get_ancestor)
-    let finalized_slot =
-        compute_start_slot_at_epoch(store.db.finalized_checkpoint_provider().get()?.epoch);
+    let finalized_slot = store
+        .db
+        .finalized_checkpoint_provider()
+        .get()?
+        .epoch
+        .start_slot();
     ensure!(block.slot > finalized_slot);
 
     // Check block is a descendant of the finalized block at the checkpoint finalized slot
@@ -85,39 +98,49 @@ pub async fn on_block(
         .clone();
     let block_root = block.tree_hash_root();
     state
-        .state_transition(signed_block, true, execution_engine)
+        .state_transition_with_signature_check(
+            signed_block,
+            true,
+            verify_signature,
+            execution_engine,
+        )
         .await?;
 
-    // Add new block to the store
-    store
-        .db
-        .beacon_block_provider()
-        .insert(block_root, signed_block.clone())?;
+    debug_span!("db_commit").in_scope(|| -> anyhow::Result<()> {
+        // Add new block to the store
+        store
+            .db
+            .beacon_block_provider()
+            .insert(block_root, signed_block.clone())?;
 
-    // Add new state for this block to the store
-    store
-        .db
-        .beacon_state_provider()
-        .insert(block_root, state.clone())?;
-
-    // Add block timeliness to the store
-    let time_into_slot = (store.db.time_provider().get()?
-        - store.db.genesis_time_provider().get()?)
-        % beacon_network_spec().seconds_per_slot;
-    let is_before_attesting_interval =
-        time_into_slot < beacon_network_spec().seconds_per_slot / INTERVALS_PER_SLOT;
-    let is_timely = store.get_current_slot()? == block.slot && is_before_attesting_interval;
-    store
-        .db
-        .block_timeliness_provider()
-        .insert(block_root, is_timely)?;
+        // Add new state for this block to the store
+        store
+            .db
+            .beacon_state_provider()
+            .insert(block_root, state.clone())?;
+
+        // Add block timeliness to the store
+        let time_into_slot = (store.db.time_provider().get()?
+            - store.db.genesis_time_provider().get()?)
+            % beacon_network_spec().seconds_per_slot;
+        let is_before_attesting_interval =
+            time_into_slot < beacon_network_spec().seconds_per_slot / INTERVALS_PER_SLOT;
+        let is_timely = store.get_current_slot()? == block.slot && is_before_attesting_interval;
+        store
+            .db
+            .block_timeliness_provider()
+            .insert(block_root, is_timely)?;
 
-    // Add proposer score boost if the block is timely and not conflicting with an existing block
-    let is_first_block = store.db.proposer_boost_root_provider().get()? == B256::ZERO;
+        // Add proposer score boost if the block is timely and not conflicting with an existing
+        // block
+        let is_first_block = store.db.proposer_boost_root_provider().get()? == B256::ZERO;
 
-    if is_timely && is_first_block {
-        store.db.proposer_boost_root_provider().insert(block_root)?;
-    }
+        if is_timely && is_first_block {
+            store.db.proposer_boost_root_provider().insert(block_root)?;
+        }
+
+        Ok(())
+    })?;
 
     // Update checkpoints in store if necessary
     store.update_checkpoints(
@@ -189,7 +212,7 @@ pub fn on_tick(store: &mut Store, time: u64) -> anyhow::Result<()> {
         (time - store.db.genesis_time_provider().get()?) / beacon_network_spec().seconds_per_slot;
     while store.get_current_slot()? < tick_slot {
         let previous_time = store.db.genesis_time_provider().get()?
-            + (store.get_current_slot()? + 1) * beacon_network_spec().seconds_per_slot;
+            + (store.get_current_slot()? + 1).as_u64() * beacon_network_spec().seconds_per_slot;
         store.on_tick_per_slot(previous_time)?;
     }
     store.on_tick_per_slot(time)?;
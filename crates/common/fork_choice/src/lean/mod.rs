@@ -6,6 +6,18 @@ use ream_consensus_lean::vote::{SignedVote, Vote};
 use ream_storage::{db::lean::LeanDB, tables::table::Table};
 use tokio::sync::Mutex;
 
+/// The outcome of LMD GHOST, along with the intermediate results it was chosen from.
+#[derive(Debug, Clone)]
+pub struct ForkChoice {
+    /// The root the walk started from
+    pub root: B256,
+    pub head: B256,
+    /// The number of latest votes for each block or any of its descendants
+    pub vote_weights: HashMap<B256, u64>,
+    /// The children of each block which have at least the minimum score
+    pub children_map: HashMap<B256, Vec<B256>>,
+}
+
 /// Use LMD GHOST to get the head, given a particular root (usually the
 /// latest known justified block)
 pub async fn get_fork_choice_head(
@@ -14,6 +26,19 @@ pub async fn get_fork_choice_head(
     provided_root: &B256,
     min_score: u64,
 ) -> anyhow::Result<B256> {
+    Ok(compute_fork_choice(store, votes, provided_root, min_score)
+        .await?
+        .head)
+}
+
+/// Runs LMD GHOST like [get_fork_choice_head], returning the vote weights and children map
+/// the head was chosen from as well.
+pub async fn compute_fork_choice(
+    store: Arc<Mutex<LeanDB>>,
+    votes: &[SignedVote],
+    provided_root: &B256,
+    min_score: u64,
+) -> anyhow::Result<ForkChoice> {
     let mut root = *provided_root;
 
     let (slot_index_table, lean_block_provider) = {
@@ -90,5 +115,10 @@ pub async fn get_fork_choice_head(
             .ok_or_else(|| anyhow!("No children found for current root: {current_root}"))?;
     }
 
-    Ok(current_root)
+    Ok(ForkChoice {
+        root,
+        head: current_root,
+        vote_weights,
+        children_map,
+    })
 }
@@ -20,6 +20,7 @@ use ream_consensus_misc::{
     checkpoint::Checkpoint,
     constants::beacon::{GENESIS_EPOCH, GENESIS_SLOT, INTERVALS_PER_SLOT, SLOTS_PER_EPOCH},
     misc::{compute_epoch_at_slot, compute_start_slot_at_epoch, is_shuffling_stable},
+    slot_epoch::{Epoch, Slot},
 };
 use ream_network_spec::networks::beacon_network_spec;
 use ream_operation_pool::OperationPool;
@@ -38,8 +39,8 @@ use crate::constants::{
 #[derive(Debug)]
 pub struct BlockWithEpochInfo {
     pub block: BeaconBlock,
-    pub justified_epoch: u64,
-    pub finalized_epoch: u64,
+    pub justified_epoch: Epoch,
+    pub finalized_epoch: Epoch,
 }
 
 #[derive(Debug)]
@@ -58,12 +59,12 @@ impl Store {
         Ok(self.db.justified_checkpoint_provider().get()?.epoch + 1 == current_epoch)
     }
 
-    pub fn get_current_store_epoch(&self) -> anyhow::Result<u64> {
-        Ok(compute_epoch_at_slot(self.get_current_slot()?))
+    pub fn get_current_store_epoch(&self) -> anyhow::Result<Epoch> {
+        Ok(self.get_current_slot()?.epoch())
     }
 
-    pub fn get_current_slot(&self) -> anyhow::Result<u64> {
-        Ok(GENESIS_SLOT + self.get_slots_since_genesis()?)
+    pub fn get_current_slot(&self) -> anyhow::Result<Slot> {
+        Ok(Slot::new(GENESIS_SLOT + self.get_slots_since_genesis()?))
     }
 
     pub fn get_slots_since_genesis(&self) -> anyhow::Result<u64> {
@@ -88,8 +89,8 @@ impl Store {
     }
 
     /// Compute the checkpoint block for epoch ``epoch`` in the chain of block ``root``
-    pub fn get_checkpoint_block(&self, root: B256, epoch: u64) -> anyhow::Result<B256> {
-        let epoch_first_slot = compute_start_slot_at_epoch(epoch);
+    pub fn get_checkpoint_block(&self, root: B256, epoch: Epoch) -> anyhow::Result<B256> {
+        let epoch_first_slot = epoch.start_slot().as_u64();
         self.get_ancestor(root, epoch_first_slot)
     }
 
@@ -240,36 +241,20 @@ impl Store {
 
         // Update finalized checkpoint
         if finalized_checkpoint.epoch > self.db.finalized_checkpoint_provider().get()?.epoch {
-            self.db
-                .finalized_checkpoint_provider()
-                .insert(finalized_checkpoint)?;
-            // Clean operation pool
+            self.db.update_finalized_checkpoint(finalized_checkpoint)?;
+            // Prune the operation pool
             if let Some(beacon_state) = self
                 .db
                 .beacon_state_provider()
                 .get(finalized_checkpoint.root)?
             {
-                self.operation_pool
-                    .clean_signed_voluntary_exits(&beacon_state);
+                self.operation_pool.prune(&beacon_state);
 
                 // Clean expired proposer preparations
                 let current_epoch = self.get_current_store_epoch()?;
                 self.operation_pool
                     .clean_proposer_preparations(current_epoch);
-
-                if let Some(beacon_block) = self
-                    .db
-                    .beacon_block_provider()
-                    .get(finalized_checkpoint.root)?
-                {
-                    for signed_bls_to_execution_change in
-                        beacon_block.message.body.bls_to_execution_changes
-                    {
-                        self.operation_pool.remove_signed_bls_to_execution_change(
-                            signed_bls_to_execution_change.tree_hash_root(),
-                        );
-                    }
-                }
+                self.operation_pool.clean_attestations(current_epoch);
             }
         }
 
@@ -341,8 +326,13 @@ impl Store {
     }
 
     pub fn is_finalization_ok(&self, slot: u64) -> anyhow::Result<bool> {
-        let epochs_since_finalization =
-            compute_epoch_at_slot(slot) - self.db.finalized_checkpoint_provider().get()?.epoch;
+        let epochs_since_finalization = compute_epoch_at_slot(slot)
+            - self
+                .db
+                .finalized_checkpoint_provider()
+                .get()?
+                .epoch
+                .as_u64();
         Ok(epochs_since_finalization <= REORG_MAX_EPOCHS_SINCE_FINALIZATION)
     }
 
@@ -390,7 +380,8 @@ impl Store {
                         .get(root)?
                         .ok_or_else(|| anyhow!("beacon_block not found"))?
                         .message
-                        .slot,
+                        .slot
+                        .as_u64(),
                 )? == root
             {
                 attestation_score += state.validators[index as usize].effective_balance;
@@ -411,7 +402,8 @@ impl Store {
                 .get(root)?
                 .ok_or_else(|| anyhow!("beacon_block not found"))?
                 .message
-                .slot,
+                .slot
+                .as_u64(),
         )? == root
         {
             self.get_proposer_score()?
@@ -432,7 +424,7 @@ impl Store {
             .ok_or_else(|| anyhow!("beacon_block not found"))?;
 
         let current_epoch = self.get_current_store_epoch()?;
-        let block_epoch = compute_epoch_at_slot(block.message.slot);
+        let block_epoch = block.message.slot.epoch();
 
         if current_epoch > block_epoch {
             // The block is from a prior epoch, the voting source will be pulled-up
@@ -597,7 +589,7 @@ impl Store {
         }
 
         // If a new epoch, pull-up justification and finalization from previous epoch
-        if current_slot > previous_slot && compute_slots_since_epoch_start(current_slot) == 0 {
+        if current_slot > previous_slot && current_slot.is_epoch_start() {
             self.update_checkpoints(
                 self.db.unrealized_justified_checkpoint_provider().get()?,
                 self.db.unrealized_finalized_checkpoint_provider().get()?,
@@ -620,7 +612,7 @@ impl Store {
         let previous_epoch = if current_epoch > GENESIS_EPOCH {
             current_epoch - 1
         } else {
-            GENESIS_EPOCH
+            Epoch::new(GENESIS_EPOCH)
         };
 
         // If attestation target is from a future epoch, delay consideration until the epoch arrives
@@ -643,7 +635,7 @@ impl Store {
         }
 
         // Check that the epoch number and slot number are matching
-        ensure!(target.epoch == compute_epoch_at_slot(attestation.data.slot));
+        ensure!(target.epoch == attestation.data.slot.epoch());
 
         // Attestation target must be for a known block. If target block is unknown, delay
         // consideration until block is found
@@ -691,7 +683,7 @@ impl Store {
             return Ok(());
         };
 
-        let target_slot = compute_start_slot_at_epoch(target.epoch);
+        let target_slot = target.epoch.start_slot().as_u64();
         if base_state.slot < target_slot {
             base_state.process_slots(target_slot)?;
         }
@@ -746,10 +738,7 @@ impl Store {
             let execution_blobs_and_proofs = execution_engine
                 .engine_get_blobs_v1(blob_versioned_hashes)
                 .await?;
-            for (index, blob_and_proof) in indices
-                .into_iter()
-                .zip(execution_blobs_and_proofs.into_iter())
-            {
+            for (index, blob_and_proof) in indices.into_iter().zip(execution_blobs_and_proofs) {
                 blobs_and_proofs[index] = blob_and_proof;
             }
         }
@@ -790,14 +779,14 @@ impl Store {
         )?;
 
         // If the block is from a prior epoch, apply the realized values
-        let block_epoch = compute_epoch_at_slot(
-            self.db
-                .beacon_block_provider()
-                .get(block_root)?
-                .ok_or_else(|| anyhow!("beacon_block not found"))?
-                .message
-                .slot,
-        );
+        let block_epoch = self
+            .db
+            .beacon_block_provider()
+            .get(block_root)?
+            .ok_or_else(|| anyhow!("beacon_block not found"))?
+            .message
+            .slot
+            .epoch();
         let current_epoch = self.get_current_store_epoch()?;
         if block_epoch < current_epoch {
             self.update_checkpoints(
@@ -820,7 +809,10 @@ impl Store {
         };
 
         // calculate sync_distance
-        let sync_distance = self.get_current_slot()?.saturating_sub(head_slot);
+        let sync_distance = self
+            .get_current_slot()?
+            .saturating_sub(head_slot.as_u64())
+            .as_u64();
 
         Ok(sync_distance > 1)
     }
@@ -833,7 +825,7 @@ pub fn get_forkchoice_store(
 ) -> anyhow::Result<Store> {
     ensure!(anchor_block.state_root == anchor_state.tree_hash_root());
     let anchor_root = anchor_block.tree_hash_root();
-    let anchor_epoch = anchor_state.get_current_epoch();
+    let anchor_epoch = Epoch::new(anchor_state.get_current_epoch());
     let justified_checkpoint = Checkpoint {
         epoch: anchor_epoch,
         root: anchor_root,
@@ -851,7 +843,8 @@ pub fn get_forkchoice_store(
     };
 
     db.time_provider().insert(
-        anchor_state.genesis_time + beacon_network_spec().seconds_per_slot * anchor_state.slot,
+        anchor_state.genesis_time
+            + beacon_network_spec().seconds_per_slot * anchor_state.slot.as_u64(),
     )?;
     db.genesis_time_provider()
         .insert(anchor_state.genesis_time)?;
@@ -865,6 +858,7 @@ pub fn get_forkchoice_store(
         .insert(finalized_checkpoint)?;
     db.proposer_boost_root_provider()
         .insert(proposer_boost_root)?;
+    db.head_root_provider().insert(anchor_root)?;
     db.beacon_block_provider()
         .insert(anchor_root, signed_anchor_block)?;
     db.beacon_state_provider()
@@ -872,7 +866,7 @@ pub fn get_forkchoice_store(
     db.state_root_index_provider()
         .insert(anchor_state.tree_hash_root(), anchor_root)?;
     db.slot_index_provider()
-        .insert(anchor_state.slot, anchor_root)?;
+        .insert(anchor_state.slot.as_u64(), anchor_root)?;
     db.checkpoint_states_provider()
         .insert(justified_checkpoint, anchor_state)?;
     db.unrealized_justifications_provider()
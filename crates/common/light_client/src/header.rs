@@ -2,10 +2,13 @@ use alloy_primitives::B256;
 use ream_consensus_beacon::electra::{
     beacon_block::SignedBeaconBlock, execution_payload_header::ExecutionPayloadHeader,
 };
-use ream_consensus_misc::beacon_block_header::BeaconBlockHeader;
+use ream_consensus_misc::{
+    beacon_block_header::BeaconBlockHeader, constants::beacon::EXECUTION_PAYLOAD_GINDEX,
+};
+use ream_merkle::is_valid_normalized_merkle_branch;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use ssz_types::{FixedVector, typenum::U3};
+use ssz_types::{FixedVector, typenum::U4};
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
@@ -15,7 +18,7 @@ use tree_hash_derive::TreeHash;
 pub struct LightClientHeader {
     pub beacon: BeaconBlockHeader,
     pub execution: ExecutionPayloadHeader,
-    pub execution_branch: FixedVector<B256, U3>,
+    pub execution_branch: FixedVector<B256, U4>,
 }
 
 impl LightClientHeader {
@@ -40,4 +43,14 @@ impl LightClientHeader {
                 .into(),
         })
     }
+
+    /// Whether the execution payload header is included in the block body of the beacon header.
+    pub fn is_valid(&self) -> bool {
+        is_valid_normalized_merkle_branch(
+            self.execution.tree_hash_root(),
+            &self.execution_branch,
+            EXECUTION_PAYLOAD_GINDEX,
+            self.beacon.body_root,
+        )
+    }
 }
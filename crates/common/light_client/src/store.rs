@@ -0,0 +1,345 @@
+use std::cmp::max;
+
+use alloy_primitives::{B256, aliases::B32};
+use anyhow::{anyhow, ensure};
+use ream_consensus_beacon::{
+    electra::beacon_state::eth_fast_aggregate_verify, sync_committee::SyncCommittee,
+};
+use ream_consensus_misc::{
+    constants::beacon::{
+        CURRENT_SYNC_COMMITTEE_GINDEX_ELECTRA, DOMAIN_SYNC_COMMITTEE,
+        FINALIZED_ROOT_GINDEX_ELECTRA, GENESIS_SLOT, NEXT_SYNC_COMMITTEE_GINDEX_ELECTRA,
+        UPDATE_TIMEOUT,
+    },
+    misc::{compute_domain, compute_signing_root, compute_sync_committee_period_at_slot},
+};
+use ream_merkle::is_valid_normalized_merkle_branch;
+use tree_hash::TreeHash;
+
+use crate::{
+    bootstrap::LightClientBootstrap,
+    header::LightClientHeader,
+    update::{LightClientUpdate, MIN_SYNC_COMMITTEE_PARTICIPANTS},
+};
+
+/// The state of a light client following the chain through sync committee signatures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightClientStore {
+    /// Header that is finalized
+    pub finalized_header: LightClientHeader,
+    /// Sync committees corresponding to the finalized header
+    pub current_sync_committee: SyncCommittee,
+    pub next_sync_committee: Option<SyncCommittee>,
+    /// Best available header to switch finalized head to if we see nothing else
+    pub best_valid_update: Option<LightClientUpdate>,
+    /// Most recent available reasonably-safe header
+    pub optimistic_header: LightClientHeader,
+    /// Max number of active participants in a sync committee (used to calculate safety threshold)
+    pub previous_max_active_participants: u64,
+    pub current_max_active_participants: u64,
+}
+
+impl LightClientStore {
+    pub fn new(trusted_block_root: B256, bootstrap: LightClientBootstrap) -> anyhow::Result<Self> {
+        ensure!(
+            bootstrap.header.is_valid(),
+            "Invalid light client header in bootstrap"
+        );
+        ensure!(
+            bootstrap.header.beacon.tree_hash_root() == trusted_block_root,
+            "Bootstrap header root must be equal to the trusted block root"
+        );
+        ensure!(
+            is_valid_normalized_merkle_branch(
+                bootstrap.current_sync_committee.tree_hash_root(),
+                &bootstrap.current_sync_committee_branch,
+                CURRENT_SYNC_COMMITTEE_GINDEX_ELECTRA,
+                bootstrap.header.beacon.state_root,
+            ),
+            "Invalid current sync committee branch"
+        );
+
+        Ok(Self {
+            finalized_header: bootstrap.header.clone(),
+            current_sync_committee: bootstrap.current_sync_committee,
+            next_sync_committee: None,
+            best_valid_update: None,
+            optimistic_header: bootstrap.header,
+            previous_max_active_participants: 0,
+            current_max_active_participants: 0,
+        })
+    }
+
+    pub fn is_next_sync_committee_known(&self) -> bool {
+        self.next_sync_committee.is_some()
+    }
+
+    pub fn get_safety_threshold(&self) -> u64 {
+        max(
+            self.previous_max_active_participants,
+            self.current_max_active_participants,
+        ) / 2
+    }
+
+    /// `fork_version` is the fork version at the slot before `update.signature_slot`.
+    pub fn validate_light_client_update(
+        &self,
+        update: &LightClientUpdate,
+        current_slot: u64,
+        fork_version: B32,
+        genesis_validators_root: B256,
+    ) -> anyhow::Result<()> {
+        // Verify sync committee has sufficient participants
+        ensure!(
+            update.num_active_participants() >= MIN_SYNC_COMMITTEE_PARTICIPANTS,
+            "Not enough sync committee participants"
+        );
+
+        // Verify update does not skip a sync committee period
+        ensure!(
+            update.attested_header.is_valid(),
+            "Invalid attested light client header"
+        );
+        let update_attested_slot = update.attested_header.beacon.slot;
+        let update_finalized_slot = update.finalized_header.beacon.slot;
+        ensure!(
+            current_slot >= update.signature_slot
+                && update.signature_slot > update_attested_slot
+                && update_attested_slot >= update_finalized_slot,
+            "Update slots must satisfy current >= signature > attested >= finalized"
+        );
+        let store_period = self.finalized_header.beacon.slot.sync_committee_period();
+        let update_signature_period = compute_sync_committee_period_at_slot(update.signature_slot);
+        if self.is_next_sync_committee_known() {
+            ensure!(
+                update_signature_period == store_period
+                    || update_signature_period == store_period + 1,
+                "Update signature period must be the current or the next period"
+            );
+        } else {
+            ensure!(
+                update_signature_period == store_period,
+                "Update signature period must be the current period"
+            );
+        }
+
+        // Verify update is relevant
+        let update_attested_period = update_attested_slot.sync_committee_period();
+        let update_has_next_sync_committee = !self.is_next_sync_committee_known()
+            && update.is_sync_committee_update()
+            && update_attested_period == store_period;
+        ensure!(
+            update_attested_slot > self.finalized_header.beacon.slot
+                || update_has_next_sync_committee,
+            "Update is not relevant"
+        );
+
+        // Verify that the `finality_branch`, if present, confirms `finalized_header` to match the
+        // finalized checkpoint root saved in the state of `attested_header`. Note that the genesis
+        // finalized checkpoint root is represented as a zero hash.
+        if !update.is_finality_update() {
+            ensure!(
+                update.finalized_header == LightClientHeader::default(),
+                "Finalized header must be empty without a finality branch"
+            );
+        } else {
+            let finalized_root = if update_finalized_slot == GENESIS_SLOT {
+                ensure!(
+                    update.finalized_header == LightClientHeader::default(),
+                    "Finalized header must be empty at genesis"
+                );
+                B256::ZERO
+            } else {
+                ensure!(
+                    update.finalized_header.is_valid(),
+                    "Invalid finalized light client header"
+                );
+                update.finalized_header.beacon.tree_hash_root()
+            };
+            ensure!(
+                is_valid_normalized_merkle_branch(
+                    finalized_root,
+                    &update.finality_branch,
+                    FINALIZED_ROOT_GINDEX_ELECTRA,
+                    update.attested_header.beacon.state_root,
+                ),
+                "Invalid finality branch"
+            );
+        }
+
+        // Verify that the `next_sync_committee`, if present, actually is the next sync committee
+        // saved in the state of the `attested_header`
+        if !update.is_sync_committee_update() {
+            ensure!(
+                update.next_sync_committee == empty_sync_committee(),
+                "Next sync committee must be empty without a next sync committee branch"
+            );
+        } else {
+            if update_attested_period == store_period
+                && let Some(next_sync_committee) = &self.next_sync_committee
+            {
+                ensure!(
+                    update.next_sync_committee == *next_sync_committee,
+                    "Next sync committee must match the known next sync committee"
+                );
+            }
+            ensure!(
+                is_valid_normalized_merkle_branch(
+                    update.next_sync_committee.tree_hash_root(),
+                    &update.next_sync_committee_branch,
+                    NEXT_SYNC_COMMITTEE_GINDEX_ELECTRA,
+                    update.attested_header.beacon.state_root,
+                ),
+                "Invalid next sync committee branch"
+            );
+        }
+
+        // Verify sync committee aggregate signature
+        let sync_committee = if update_signature_period == store_period {
+            &self.current_sync_committee
+        } else {
+            self.next_sync_committee
+                .as_ref()
+                .ok_or_else(|| anyhow!("Next sync committee is unknown"))?
+        };
+        let participant_public_keys = update
+            .sync_aggregate
+            .sync_committee_bits
+            .iter()
+            .zip(sync_committee.public_keys.iter())
+            .filter_map(|(bit, public_key)| bit.then_some(public_key))
+            .collect::<Vec<_>>();
+        let domain = compute_domain(
+            DOMAIN_SYNC_COMMITTEE,
+            Some(fork_version),
+            Some(genesis_validators_root),
+        );
+        let signing_root = compute_signing_root(update.attested_header.beacon.clone(), domain);
+        ensure!(
+            eth_fast_aggregate_verify(
+                &participant_public_keys,
+                signing_root,
+                &update.sync_aggregate.sync_committee_signature,
+            )?,
+            "Sync aggregate signature verification failed"
+        );
+
+        Ok(())
+    }
+
+    pub fn apply_light_client_update(&mut self, update: &LightClientUpdate) -> anyhow::Result<()> {
+        let store_period = self.finalized_header.beacon.slot.sync_committee_period();
+        let update_finalized_period = update.finalized_header.beacon.slot.sync_committee_period();
+        let update_next_sync_committee = update
+            .is_sync_committee_update()
+            .then(|| update.next_sync_committee.clone());
+        if let Some(next_sync_committee) = &self.next_sync_committee {
+            if update_finalized_period == store_period + 1 {
+                self.current_sync_committee = next_sync_committee.clone();
+                self.next_sync_committee = update_next_sync_committee;
+                self.previous_max_active_participants = self.current_max_active_participants;
+                self.current_max_active_participants = 0;
+            }
+        } else {
+            ensure!(
+                update_finalized_period == store_period,
+                "Update finalized period must be the current period"
+            );
+            self.next_sync_committee = update_next_sync_committee;
+        }
+
+        if update.finalized_header.beacon.slot > self.finalized_header.beacon.slot {
+            self.finalized_header = update.finalized_header.clone();
+            if self.finalized_header.beacon.slot > self.optimistic_header.beacon.slot {
+                self.optimistic_header = self.finalized_header.clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the best valid update once no update has finalized a header for `UPDATE_TIMEOUT`
+    /// slots.
+    pub fn process_light_client_store_force_update(
+        &mut self,
+        current_slot: u64,
+    ) -> anyhow::Result<()> {
+        if current_slot > self.finalized_header.beacon.slot + UPDATE_TIMEOUT
+            && let Some(mut best_valid_update) = self.best_valid_update.take()
+        {
+            // The attested header may be treated as the finalized header in extended periods of
+            // non-finality to guarantee progression into later sync committee periods
+            if best_valid_update.finalized_header.beacon.slot <= self.finalized_header.beacon.slot {
+                best_valid_update.finalized_header = best_valid_update.attested_header.clone();
+            }
+            self.apply_light_client_update(&best_valid_update)?;
+        }
+
+        Ok(())
+    }
+
+    /// `fork_version` is the fork version at the slot before `update.signature_slot`.
+    pub fn process_light_client_update(
+        &mut self,
+        update: LightClientUpdate,
+        current_slot: u64,
+        fork_version: B32,
+        genesis_validators_root: B256,
+    ) -> anyhow::Result<()> {
+        self.validate_light_client_update(
+            &update,
+            current_slot,
+            fork_version,
+            genesis_validators_root,
+        )?;
+
+        let num_active_participants = update.num_active_participants();
+        let max_active_participants = update.sync_aggregate.sync_committee_bits.len() as u64;
+
+        // Update the best update in case we have to force-update to it if the timeout elapses
+        if self
+            .best_valid_update
+            .as_ref()
+            .is_none_or(|best_valid_update| update.is_better_update(best_valid_update))
+        {
+            self.best_valid_update = Some(update.clone());
+        }
+
+        // Track the maximum number of active participants in the committee signatures
+        self.current_max_active_participants = max(
+            self.current_max_active_participants,
+            num_active_participants,
+        );
+
+        // Update the optimistic header
+        if num_active_participants > self.get_safety_threshold()
+            && update.attested_header.beacon.slot > self.optimistic_header.beacon.slot
+        {
+            self.optimistic_header = update.attested_header.clone();
+        }
+
+        // Update finalized header
+        let update_has_finalized_next_sync_committee = !self.is_next_sync_committee_known()
+            && update.is_sync_committee_update()
+            && update.is_finality_update()
+            && update.finalized_header.beacon.slot.sync_committee_period()
+                == update.attested_header.beacon.slot.sync_committee_period();
+        if num_active_participants * 3 >= max_active_participants * 2
+            && (update.finalized_header.beacon.slot > self.finalized_header.beacon.slot
+                || update_has_finalized_next_sync_committee)
+        {
+            // Normal update through 2/3 threshold
+            self.apply_light_client_update(&update)?;
+            self.best_valid_update = None;
+        }
+
+        Ok(())
+    }
+}
+
+fn empty_sync_committee() -> SyncCommittee {
+    SyncCommittee {
+        public_keys: Default::default(),
+        aggregate_public_key: Default::default(),
+    }
+}
@@ -10,7 +10,10 @@ use ream_consensus_misc::{
 };
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use ssz_types::{FixedVector, typenum::U6};
+use ssz_types::{
+    FixedVector,
+    typenum::{U6, U7},
+};
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
@@ -27,7 +30,7 @@ pub struct LightClientUpdate {
     pub next_sync_committee_branch: FixedVector<B256, U6>,
     /// Finalized header corresponding to `attested_header.beacon.state_root`
     pub finalized_header: LightClientHeader,
-    pub finality_branch: FixedVector<B256, U6>,
+    pub finality_branch: FixedVector<B256, U7>,
     /// Sync committee aggregate signature
     pub sync_aggregate: SyncAggregate,
     /// Slot at which the aggregate signature was created (untrusted)
@@ -67,7 +70,7 @@ impl LightClientUpdate {
             header.tree_hash_root() == block.message.tree_hash_root(),
             "Header root must be equal to block root"
         );
-        let update_signature_period = compute_sync_committee_period_at_slot(block.message.slot);
+        let update_signature_period = block.message.slot.sync_committee_period();
         ensure!(attested_state.slot == attested_state.latest_block_header.slot);
         let mut attested_header = attested_state.latest_block_header.clone();
         attested_header.state_root = attested_state.tree_hash_root();
@@ -76,8 +79,7 @@ impl LightClientUpdate {
                 && attested_block.message.tree_hash_root() == block.message.parent_root,
             "Mismatch: attested_header, attested_block.message, or block.message.parent_root"
         );
-        let update_attested_period =
-            compute_sync_committee_period_at_slot(attested_block.message.slot);
+        let update_attested_period = attested_block.message.slot.sync_committee_period();
 
         let attested_header = LightClientHeader::new(&attested_block)?;
 
@@ -119,7 +121,82 @@ impl LightClientUpdate {
             finalized_header,
             finality_branch,
             sync_aggregate: block.message.body.sync_aggregate,
-            signature_slot: block.message.slot,
+            signature_slot: block.message.slot.as_u64(),
         })
     }
+
+    pub fn is_sync_committee_update(&self) -> bool {
+        self.next_sync_committee_branch != FixedVector::default()
+    }
+
+    pub fn is_finality_update(&self) -> bool {
+        self.finality_branch != FixedVector::default()
+    }
+
+    pub fn num_active_participants(&self) -> u64 {
+        self.sync_aggregate.sync_committee_bits.num_set_bits() as u64
+    }
+
+    /// Whether `self` should replace `old_update` as the best update of a sync committee period.
+    pub fn is_better_update(&self, old_update: &LightClientUpdate) -> bool {
+        // Compare supermajority (> 2/3) sync committee participation
+        let max_active_participants = self.sync_aggregate.sync_committee_bits.len() as u64;
+        let new_num_active_participants = self.num_active_participants();
+        let old_num_active_participants = old_update.num_active_participants();
+        let new_has_supermajority = new_num_active_participants * 3 >= max_active_participants * 2;
+        let old_has_supermajority = old_num_active_participants * 3 >= max_active_participants * 2;
+        if new_has_supermajority != old_has_supermajority {
+            return new_has_supermajority;
+        }
+        if !new_has_supermajority && new_num_active_participants != old_num_active_participants {
+            return new_num_active_participants > old_num_active_participants;
+        }
+
+        // Compare presence of relevant sync committee
+        let new_has_relevant_sync_committee = self.has_relevant_sync_committee();
+        let old_has_relevant_sync_committee = old_update.has_relevant_sync_committee();
+        if new_has_relevant_sync_committee != old_has_relevant_sync_committee {
+            return new_has_relevant_sync_committee;
+        }
+
+        // Compare indication of any finality
+        let new_has_finality = self.is_finality_update();
+        let old_has_finality = old_update.is_finality_update();
+        if new_has_finality != old_has_finality {
+            return new_has_finality;
+        }
+
+        // Compare sync committee finality
+        if new_has_finality {
+            let new_has_sync_committee_finality = self.has_sync_committee_finality();
+            let old_has_sync_committee_finality = old_update.has_sync_committee_finality();
+            if new_has_sync_committee_finality != old_has_sync_committee_finality {
+                return new_has_sync_committee_finality;
+            }
+        }
+
+        // Tiebreaker 1: Sync committee participation beyond supermajority
+        if new_num_active_participants != old_num_active_participants {
+            return new_num_active_participants > old_num_active_participants;
+        }
+
+        // Tiebreaker 2: Prefer older data (fewer changes to best)
+        if self.attested_header.beacon.slot != old_update.attested_header.beacon.slot {
+            return self.attested_header.beacon.slot < old_update.attested_header.beacon.slot;
+        }
+
+        // Tiebreaker 3: Prefer updates with earlier signature slots
+        self.signature_slot < old_update.signature_slot
+    }
+
+    fn has_relevant_sync_committee(&self) -> bool {
+        self.is_sync_committee_update()
+            && self.attested_header.beacon.slot.sync_committee_period()
+                == compute_sync_committee_period_at_slot(self.signature_slot)
+    }
+
+    fn has_sync_committee_finality(&self) -> bool {
+        self.finalized_header.beacon.slot.sync_committee_period()
+            == self.attested_header.beacon.slot.sync_committee_period()
+    }
 }
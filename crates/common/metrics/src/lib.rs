@@ -1,6 +1,8 @@
+pub use prometheus_exporter::prometheus::HistogramTimer;
 use prometheus_exporter::prometheus::{
-    HistogramTimer, HistogramVec, IntGaugeVec, default_registry,
-    register_histogram_vec_with_registry, register_int_gauge_vec_with_registry,
+    HistogramVec, IntCounterVec, IntGaugeVec, default_registry,
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry,
 };
 
 // Provisioning each metrics
@@ -28,6 +30,150 @@ lazy_static::lazy_static! {
         "The current finalized slot",
         &[]
     );
+
+    pub static ref LEAN_PEERS: IntGaugeVec = create_int_gauge_vec(
+        "lean_peers",
+        "The number of known peers of the lean node by connection direction and state",
+        &["direction", "state"]
+    );
+
+    pub static ref GOSSIPSUB_PEERS_BY_SCORE: IntGaugeVec = create_int_gauge_vec(
+        "beacon_gossipsub_peers_by_score",
+        "The number of connected peers in each gossipsub score range",
+        &["score"]
+    );
+
+    pub static ref GOSSIP_MESSAGES: IntCounterVec = create_int_counter_vec(
+        "beacon_gossip_messages",
+        "The number of gossip messages received by topic and validation result",
+        &["topic", "result"]
+    );
+
+    pub static ref PEERS: IntGaugeVec = create_int_gauge_vec(
+        "beacon_peers",
+        "The number of known peers by connection direction and state",
+        &["direction", "state"]
+    );
+
+    pub static ref REQ_RESP_REQUEST_TIME: HistogramVec = create_histogram_vec(
+        "beacon_req_resp_request_time",
+        "Duration until a peer starts answering a request, by protocol",
+        &["protocol"]
+    );
+
+    pub static ref BEACON_HEAD_EPOCH: IntGaugeVec = create_int_gauge_vec(
+        "beacon_head_epoch",
+        "The epoch of the current head",
+        &[]
+    );
+
+    pub static ref BEACON_JUSTIFIED_EPOCH: IntGaugeVec = create_int_gauge_vec(
+        "beacon_justified_epoch",
+        "The current justified epoch",
+        &[]
+    );
+
+    pub static ref BEACON_FINALIZED_EPOCH: IntGaugeVec = create_int_gauge_vec(
+        "beacon_finalized_epoch",
+        "The current finalized epoch",
+        &[]
+    );
+
+    pub static ref BLOCK_IMPORT_TIME: HistogramVec = create_histogram_vec(
+        "beacon_block_import_time",
+        "Duration of importing a block into fork choice",
+        &[]
+    );
+
+    pub static ref DB_OPERATION_TIME: HistogramVec = create_histogram_vec(
+        "beacon_db_operation_time",
+        "Duration of database reads and writes by table",
+        &["table", "operation"]
+    );
+
+    pub static ref ATTESTATION_POOL_SIZE: IntGaugeVec = create_int_gauge_vec(
+        "beacon_attestation_pool_size",
+        "The number of aggregated attestations in the operation pool",
+        &[]
+    );
+
+    pub static ref ENGINE_REQUEST_TIME: HistogramVec = create_histogram_vec(
+        "beacon_engine_request_time",
+        "Duration of the requests to the execution client by method",
+        &["method"]
+    );
+
+    pub static ref ENGINE_REQUEST_ERRORS: IntCounterVec = create_int_counter_vec(
+        "beacon_engine_request_errors",
+        "The number of failed requests to the execution client by method and error",
+        &["method", "error"]
+    );
+
+    pub static ref EXECUTION_ENGINE_STATUS: IntGaugeVec = create_int_gauge_vec(
+        "beacon_execution_engine_status",
+        "The status of the execution client: 0 if offline, 1 if syncing and 2 if synced",
+        &[]
+    );
+
+    pub static ref VALIDATOR_DUTIES: IntCounterVec = create_int_counter_vec(
+        "validator_duties",
+        "The number of duties of the local validators by type and outcome: scheduled, performed or missed",
+        &["duty", "outcome"]
+    );
+
+    pub static ref VALIDATOR_SIGNING_TIME: HistogramVec = create_histogram_vec(
+        "validator_signing_time",
+        "Duration of signing a message by duty",
+        &["duty"]
+    );
+
+    pub static ref VALIDATOR_BEACON_API_REQUEST_TIME: HistogramVec = create_histogram_vec(
+        "validator_beacon_api_request_time",
+        "Duration until a beacon node responds to a request, by endpoint",
+        &["endpoint"]
+    );
+
+    pub static ref VALIDATOR_BEACON_API_REQUEST_ERRORS: IntCounterVec = create_int_counter_vec(
+        "validator_beacon_api_request_errors",
+        "Requests to a beacon node which failed, by endpoint and kind of failure",
+        &["endpoint", "kind"]
+    );
+
+    pub static ref VALIDATOR_BEACON_API_REQUEST_RETRIES: IntCounterVec = create_int_counter_vec(
+        "validator_beacon_api_request_retries",
+        "Requests sent again after no beacon node could serve them, by endpoint",
+        &["endpoint"]
+    );
+
+    pub static ref VALIDATOR_BALANCE: IntGaugeVec = create_int_gauge_vec(
+        "validator_balance",
+        "The balance of a local validator in Gwei as of the last epoch start",
+        &["validator_index"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_ATTESTATIONS: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_attestations",
+        "The number of attestations of a monitored validator by outcome: included or missed",
+        &["validator_index", "outcome"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_INCLUSION_DISTANCE: IntGaugeVec = create_int_gauge_vec(
+        "beacon_validator_monitor_inclusion_distance",
+        "The inclusion distance in slots of the last included attestation of a monitored validator",
+        &["validator_index"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_BLOCKS_PROPOSED: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_blocks_proposed",
+        "The number of imported blocks proposed by a monitored validator",
+        &["validator_index"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_SYNC_COMMITTEE: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_sync_committee",
+        "The number of sync committee messages of a monitored validator by outcome: included or missed",
+        &["validator_index", "outcome"]
+    );
 }
 
 /// Create a new gauge metric
@@ -42,6 +188,23 @@ pub fn set_int_gauge_vec(gauge_vec: &IntGaugeVec, value: i64, label_values: &[&s
     gauge_vec.with_label_values(label_values).set(value);
 }
 
+/// Create a new counter metric
+pub fn create_int_counter_vec(name: &str, help: &str, label_names: &[&str]) -> IntCounterVec {
+    let registry = default_registry();
+    register_int_counter_vec_with_registry!(name, help, label_names, registry)
+        .expect("failed to create int counter vec")
+}
+
+/// Increment a counter metric
+pub fn inc_int_counter_vec(counter_vec: &IntCounterVec, label_values: &[&str]) {
+    counter_vec.with_label_values(label_values).inc();
+}
+
+/// Increase a counter metric by `value`
+pub fn inc_int_counter_vec_by(counter_vec: &IntCounterVec, value: u64, label_values: &[&str]) {
+    counter_vec.with_label_values(label_values).inc_by(value);
+}
+
 /// Create a new histogram metric
 pub fn create_histogram_vec(name: &str, help: &str, label_names: &[&str]) -> HistogramVec {
     let registry = default_registry();
@@ -1,4 +1,5 @@
 use std::{
+    fmt::{self, Display},
     str::FromStr,
     sync::{Arc, LazyLock, Once, OnceLock},
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -6,8 +7,12 @@ use std::{
 
 use alloy_primitives::{Address, B256, U256, address, aliases::B32, b256, fixed_bytes};
 use ream_consensus_misc::{
-    constants::beacon::GENESIS_VALIDATORS_ROOT, fork::Fork, fork_data::ForkData,
-    misc::checksummed_address,
+    constants::beacon::GENESIS_VALIDATORS_ROOT,
+    fork::Fork,
+    fork_data::ForkData,
+    fork_name::ForkName,
+    misc::{checksummed_address, compute_epoch_at_slot},
+    slot_epoch::Epoch,
 };
 use serde::Deserialize;
 
@@ -48,6 +53,19 @@ impl<'de> Deserialize<'de> for Network {
     }
 }
 
+impl Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Holesky => write!(f, "holesky"),
+            Network::Sepolia => write!(f, "sepolia"),
+            Network::Hoodi => write!(f, "hoodi"),
+            Network::Dev => write!(f, "dev"),
+            Network::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
 static BEACON_NETWORK_SPEC: OnceLock<Arc<BeaconNetworkSpec>> = OnceLock::new();
 
 /// MUST be called only once at the start of the application to initialize static
@@ -64,6 +82,10 @@ pub fn set_beacon_network_spec(network_spec: Arc<BeaconNetworkSpec>) {
         .expect("BeaconNetworkSpec should be set only once at the start of the application");
 }
 
+fn unscheduled_fork_epoch() -> u64 {
+    Fork::UNSCHEDULED_EPOCH
+}
+
 /// Returns the static [BeaconNetworkSpec] initialized by [set_beacon_network_spec].
 ///
 /// # Panics
@@ -112,6 +134,10 @@ pub struct BeaconNetworkSpec {
     #[serde(with = "crate::b32_hex")]
     pub electra_fork_version: B32,
     pub electra_fork_epoch: u64,
+    #[serde(with = "crate::b32_hex", default)]
+    pub fulu_fork_version: B32,
+    #[serde(default = "unscheduled_fork_epoch")]
+    pub fulu_fork_epoch: u64,
 
     // Time parameters
     pub seconds_per_slot: u64,
@@ -173,59 +199,113 @@ pub struct BeaconNetworkSpec {
 }
 
 impl BeaconNetworkSpec {
+    /// Returns the fork digest of the fork which is active at the current epoch.
     pub fn fork_digest(&self, genesis_validators_root: B256) -> B32 {
+        self.fork_digest_at_epoch(self.current_epoch(), genesis_validators_root)
+    }
+
+    pub fn fork_digest_at_epoch(&self, epoch: u64, genesis_validators_root: B256) -> B32 {
+        self.fork_name_digest(self.fork_name_at_epoch(epoch), genesis_validators_root)
+    }
+
+    pub fn fork_name_digest(&self, fork_name: ForkName, genesis_validators_root: B256) -> B32 {
         ForkData {
-            current_version: self.electra_fork_version,
+            current_version: self.fork_version(fork_name),
             genesis_validators_root,
         }
         .compute_fork_digest()
     }
 
+    /// Returns the scheduled fork whose digest is `fork_digest`, if any.
+    pub fn fork_name_from_digest(
+        &self,
+        fork_digest: B32,
+        genesis_validators_root: B256,
+    ) -> Option<ForkName> {
+        ForkName::ALL.into_iter().find(|fork_name| {
+            self.fork_epoch(*fork_name) != Fork::UNSCHEDULED_EPOCH
+                && self.fork_name_digest(*fork_name, genesis_validators_root) == fork_digest
+        })
+    }
+
     pub fn fork_schedule(&self) -> ForkSchedule {
-        ForkSchedule([
-            Fork {
-                previous_version: self.genesis_fork_version,
-                current_version: self.genesis_fork_version,
-                epoch: 0,
-            },
-            Fork {
-                previous_version: self.genesis_fork_version,
-                current_version: self.altair_fork_version,
-                epoch: self.altair_fork_epoch,
-            },
-            Fork {
-                previous_version: self.altair_fork_version,
-                current_version: self.bellatrix_fork_version,
-                epoch: self.bellatrix_fork_epoch,
-            },
-            Fork {
-                previous_version: self.bellatrix_fork_version,
-                current_version: self.capella_fork_version,
-                epoch: self.capella_fork_epoch,
-            },
-            Fork {
-                previous_version: self.capella_fork_version,
-                current_version: self.deneb_fork_version,
-                epoch: self.deneb_fork_epoch,
-            },
-            Fork {
-                previous_version: self.deneb_fork_version,
-                current_version: self.electra_fork_version,
-                epoch: self.electra_fork_epoch,
-            },
-        ])
+        ForkSchedule::new(ForkName::ALL.map(|fork_name| Fork {
+            previous_version: self.fork_version(fork_name.previous().unwrap_or(fork_name)),
+            current_version: self.fork_version(fork_name),
+            epoch: Epoch::new(self.fork_epoch(fork_name)),
+        }))
+    }
+
+    pub fn fork_version(&self, fork_name: ForkName) -> B32 {
+        match fork_name {
+            ForkName::Phase0 => self.genesis_fork_version,
+            ForkName::Altair => self.altair_fork_version,
+            ForkName::Bellatrix => self.bellatrix_fork_version,
+            ForkName::Capella => self.capella_fork_version,
+            ForkName::Deneb => self.deneb_fork_version,
+            ForkName::Electra => self.electra_fork_version,
+            ForkName::Fulu => self.fulu_fork_version,
+        }
+    }
+
+    pub fn fork_epoch(&self, fork_name: ForkName) -> u64 {
+        match fork_name {
+            ForkName::Phase0 => 0,
+            ForkName::Altair => self.altair_fork_epoch,
+            ForkName::Bellatrix => self.bellatrix_fork_epoch,
+            ForkName::Capella => self.capella_fork_epoch,
+            ForkName::Deneb => self.deneb_fork_epoch,
+            ForkName::Electra => self.electra_fork_epoch,
+            ForkName::Fulu => self.fulu_fork_epoch,
+        }
+    }
+
+    /// Returns the latest fork which is active at `epoch`.
+    pub fn fork_name_at_epoch(&self, epoch: u64) -> ForkName {
+        ForkName::ALL
+            .into_iter()
+            .rev()
+            .find(|fork_name| self.fork_epoch(*fork_name) <= epoch)
+            .unwrap_or(ForkName::Phase0)
+    }
+
+    pub fn fork_name_at_slot(&self, slot: u64) -> ForkName {
+        self.fork_name_at_epoch(compute_epoch_at_slot(slot))
+    }
+
+    /// Returns the fork which follows the one active at `epoch` together with its activation
+    /// epoch, if it is scheduled.
+    pub fn next_fork(&self, epoch: u64) -> Option<(ForkName, u64)> {
+        let next_fork = self.fork_name_at_epoch(epoch).next()?;
+        let next_fork_epoch = self.fork_epoch(next_fork);
+        (next_fork_epoch != Fork::UNSCHEDULED_EPOCH).then_some((next_fork, next_fork_epoch))
+    }
+
+    /// Returns the current slot, or 0 before genesis.
+    pub fn current_slot(&self) -> u64 {
+        self.current_slot_with_disparity(Duration::ZERO)
+    }
+
+    /// Returns the slot the clock will be at in `disparity`, which is the latest slot a message
+    /// may be from when the clock of its sender runs up to `disparity` ahead of ours.
+    pub fn current_slot_with_disparity(&self, disparity: Duration) -> u64 {
+        let genesis_instant = UNIX_EPOCH + Duration::from_secs(self.min_genesis_time);
+        (SystemTime::now() + disparity)
+            .duration_since(genesis_instant)
+            .map(|elapsed| elapsed.as_secs() / self.seconds_per_slot)
+            .unwrap_or_default()
+    }
+
+    pub fn current_epoch(&self) -> u64 {
+        compute_epoch_at_slot(self.current_slot())
     }
 
     /// Returns the slot number for `n_days_ago` days ago.
     ///
     /// if n_days_ago is larger then the current slot, it returns 0.
     pub fn slot_n_days_ago(&self, n_days_ago: u64) -> u64 {
-        let genesis_instant = UNIX_EPOCH + Duration::from_secs(self.min_genesis_time);
-        let elapsed = SystemTime::now()
-            .duration_since(genesis_instant)
-            .expect("System Time is before the genesis time");
-        let current_slot = elapsed.as_secs() / self.seconds_per_slot;
-        current_slot.saturating_sub(n_days_ago * 24 * 60 * 60 / self.seconds_per_slot)
+        self.current_slot()
+            .saturating_sub(n_days_ago * 24 * 60 * 60 / self.seconds_per_slot)
     }
 }
 
@@ -253,6 +333,8 @@ pub static MAINNET: LazyLock<Arc<BeaconNetworkSpec>> = LazyLock::new(|| {
         deneb_fork_epoch: 269568,
         electra_fork_version: fixed_bytes!("0x05000000"),
         electra_fork_epoch: 364032,
+        fulu_fork_version: fixed_bytes!("0x06000000"),
+        fulu_fork_epoch: 18446744073709551615,
         seconds_per_slot: 12,
         seconds_per_eth1_block: 14,
         min_validator_withdrawability_delay: 256,
@@ -322,6 +404,8 @@ pub static HOLESKY: LazyLock<Arc<BeaconNetworkSpec>> = LazyLock::new(|| {
         deneb_fork_epoch: 29696,
         electra_fork_version: fixed_bytes!("0x06017000"),
         electra_fork_epoch: 115968,
+        fulu_fork_version: fixed_bytes!("0x07017000"),
+        fulu_fork_epoch: 18446744073709551615,
         seconds_per_slot: 12,
         seconds_per_eth1_block: 14,
         min_validator_withdrawability_delay: 256,
@@ -391,6 +475,8 @@ pub static SEPOLIA: LazyLock<Arc<BeaconNetworkSpec>> = LazyLock::new(|| {
         deneb_fork_epoch: 132608,
         electra_fork_version: fixed_bytes!("0x90000074"),
         electra_fork_epoch: 222464,
+        fulu_fork_version: fixed_bytes!("0x90000075"),
+        fulu_fork_epoch: 18446744073709551615,
         seconds_per_slot: 12,
         seconds_per_eth1_block: 14,
         min_validator_withdrawability_delay: 256,
@@ -460,6 +546,8 @@ pub static HOODI: LazyLock<Arc<BeaconNetworkSpec>> = LazyLock::new(|| {
         deneb_fork_epoch: 0,
         electra_fork_version: fixed_bytes!("0x60000910"),
         electra_fork_epoch: 2048,
+        fulu_fork_version: fixed_bytes!("0x70000910"),
+        fulu_fork_epoch: 18446744073709551615,
         seconds_per_slot: 12,
         seconds_per_eth1_block: 14,
         min_validator_withdrawability_delay: 256,
@@ -529,6 +617,8 @@ pub static DEV: LazyLock<Arc<BeaconNetworkSpec>> = LazyLock::new(|| {
         deneb_fork_epoch: 269568,
         electra_fork_version: fixed_bytes!("0x05000000"),
         electra_fork_epoch: 364032,
+        fulu_fork_version: fixed_bytes!("0x06000000"),
+        fulu_fork_epoch: 18446744073709551615,
         seconds_per_slot: 12,
         seconds_per_eth1_block: 14,
         min_validator_withdrawability_delay: 256,
@@ -1,18 +1,30 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, hash_map::Entry};
 
 use alloy_primitives::{Address, B256, map::HashSet};
 use parking_lot::RwLock;
+use ream_bls::{BLSSignature, traits::Aggregatable};
 use ream_consensus_beacon::{
-    attester_slashing::AttesterSlashing, bls_to_execution_change::SignedBLSToExecutionChange,
-    electra::beacon_state::BeaconState, proposer_slashing::ProposerSlashing,
+    attestation::Attestation,
+    attester_slashing::AttesterSlashing,
+    bls_to_execution_change::SignedBLSToExecutionChange,
+    chunked_list::ChunkedList,
+    electra::{beacon_state::BeaconState, zkvm_types::ValidatorRegistryLimit},
+    proposer_slashing::ProposerSlashing,
     voluntary_exit::SignedVoluntaryExit,
 };
+use ream_consensus_misc::{
+    constants::beacon::{BLS_WITHDRAWAL_PREFIX, FAR_FUTURE_EPOCH},
+    misc::get_committee_indices,
+    slot_epoch::Epoch,
+    validator::Validator,
+};
+use ream_metrics::{ATTESTATION_POOL_SIZE, set_int_gauge_vec};
 use tree_hash::TreeHash;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProposerPreparation {
     pub fee_recipient: Address,
-    pub submission_epoch: u64,
+    pub submission_epoch: Epoch,
 }
 
 #[derive(Debug, Default)]
@@ -22,14 +34,26 @@ pub struct OperationPool {
     proposer_preparations: RwLock<HashMap<u64, ProposerPreparation>>,
     attester_slashings: RwLock<HashSet<AttesterSlashing>>,
     proposer_slashings: RwLock<HashSet<ProposerSlashing>>,
+    /// Attestations aggregated per committee, keyed by the root of their data and the committee
+    /// index.
+    attestations: RwLock<HashMap<(B256, u64), Attestation>>,
 }
 
 impl OperationPool {
-    pub fn insert_signed_voluntary_exit(&self, signed_voluntary_exit: SignedVoluntaryExit) {
-        self.signed_voluntary_exits.write().insert(
-            signed_voluntary_exit.message.validator_index,
-            signed_voluntary_exit,
-        );
+    /// Returns `false` if an exit for the same validator is already in the pool, in which case
+    /// the pool is left untouched.
+    pub fn insert_signed_voluntary_exit(&self, signed_voluntary_exit: SignedVoluntaryExit) -> bool {
+        match self
+            .signed_voluntary_exits
+            .write()
+            .entry(signed_voluntary_exit.message.validator_index)
+        {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(signed_voluntary_exit);
+                true
+            }
+        }
     }
 
     pub fn get_signed_voluntary_exits(&self) -> Vec<SignedVoluntaryExit> {
@@ -40,23 +64,17 @@ impl OperationPool {
             .collect()
     }
 
-    pub fn clean_signed_voluntary_exits(&self, beacon_state: &BeaconState) {
-        self.signed_voluntary_exits
-            .write()
-            .retain(|&validator_index, _| {
-                beacon_state.validators[validator_index as usize].exit_epoch
-                    >= beacon_state.finalized_checkpoint.epoch
-            });
-    }
-
     pub fn insert_signed_bls_to_execution_change(
         &self,
         signed_bls_to_execution_change: SignedBLSToExecutionChange,
-    ) {
-        self.signed_bls_to_execution_changes.write().insert(
-            signed_bls_to_execution_change.tree_hash_root(),
-            signed_bls_to_execution_change,
-        );
+    ) -> bool {
+        self.signed_bls_to_execution_changes
+            .write()
+            .insert(
+                signed_bls_to_execution_change.tree_hash_root(),
+                signed_bls_to_execution_change,
+            )
+            .is_none()
     }
 
     pub fn get_signed_bls_to_execution_changes(&self) -> Vec<SignedBLSToExecutionChange> {
@@ -75,7 +93,7 @@ impl OperationPool {
         &self,
         validator_index: u64,
         fee_recipient: Address,
-        submission_epoch: u64,
+        submission_epoch: Epoch,
     ) {
         self.proposer_preparations.write().insert(
             validator_index,
@@ -101,7 +119,7 @@ impl OperationPool {
             .collect()
     }
 
-    pub fn clean_proposer_preparations(&self, current_epoch: u64) {
+    pub fn clean_proposer_preparations(&self, current_epoch: Epoch) {
         self.proposer_preparations.write().retain(|_, preparation| {
             // Keep preparations that are still valid
             // They persist through the epoch of submission and for 2 more epochs after that
@@ -109,27 +127,279 @@ impl OperationPool {
         });
     }
 
-    pub fn insert_attester_slashing(&self, slashing: AttesterSlashing) {
-        self.attester_slashings.write().insert(slashing);
+    pub fn insert_attester_slashing(&self, slashing: AttesterSlashing) -> bool {
+        self.attester_slashings.write().insert(slashing)
     }
 
     pub fn get_all_attester_slashings(&self) -> Vec<AttesterSlashing> {
         self.attester_slashings.read().iter().cloned().collect()
     }
 
-    pub fn get_all_proposer_slahsings(&self) -> Vec<ProposerSlashing> {
+    pub fn get_all_proposer_slashings(&self) -> Vec<ProposerSlashing> {
         self.proposer_slashings.read().iter().cloned().collect()
     }
 
-    pub fn insert_proposer_slashing(&self, slashing: ProposerSlashing) {
-        self.proposer_slashings.write().insert(slashing);
+    pub fn insert_proposer_slashing(&self, slashing: ProposerSlashing) -> bool {
+        self.proposer_slashings.write().insert(slashing)
+    }
+
+    /// Insert an attestation of a single committee, aggregating it into the pooled attestation with
+    /// the same data if their attesters don't overlap.
+    ///
+    /// Returns `false` if the pool already holds every attester of `attestation`, or if it could
+    /// not be aggregated.
+    pub fn insert_attestation(&self, attestation: Attestation) -> bool {
+        let [committee_index] = get_committee_indices(&attestation.committee_bits)[..] else {
+            return false;
+        };
+
+        let mut attestations = self.attestations.write();
+        let pooled = match attestations.entry((attestation.data.tree_hash_root(), committee_index))
+        {
+            Entry::Vacant(entry) => {
+                entry.insert(attestation);
+                set_int_gauge_vec(&ATTESTATION_POOL_SIZE, attestations.len() as i64, &[]);
+                return true;
+            }
+            Entry::Occupied(entry) => entry.into_mut(),
+        };
+
+        if !pooled
+            .aggregation_bits
+            .intersection(&attestation.aggregation_bits)
+            .is_zero()
+        {
+            return false;
+        }
+        let Ok(signature) = BLSSignature::aggregate(&[&pooled.signature, &attestation.signature])
+        else {
+            return false;
+        };
+
+        pooled.aggregation_bits = pooled.aggregation_bits.union(&attestation.aggregation_bits);
+        pooled.signature = signature;
+        true
+    }
+
+    /// The pooled attestations, optionally filtered by slot and committee index.
+    pub fn get_attestations(
+        &self,
+        slot: Option<u64>,
+        committee_index: Option<u64>,
+    ) -> Vec<Attestation> {
+        self.attestations
+            .read()
+            .iter()
+            .filter(|((_, index), attestation)| {
+                slot.is_none_or(|slot| attestation.data.slot == slot)
+                    && committee_index.is_none_or(|committee_index| *index == committee_index)
+            })
+            .map(|(_, attestation)| attestation.clone())
+            .collect()
+    }
+
+    /// Drop the operations which the finalized chain made useless, so the pool doesn't grow
+    /// without bounds:
+    /// - Attestations which can't be included in a block built on `finalized_state` anymore
+    /// - Exits of validators which already initiated their exit
+    /// - Slashings which don't slash any validator which is still slashable
+    /// - BLS to execution changes of validators which already have execution credentials
+    pub fn prune(&self, finalized_state: &BeaconState) {
+        let epoch = finalized_state.get_current_epoch();
+        self.clean_attestations(Epoch::new(epoch));
+        self.prune_for_validators(&finalized_state.validators, epoch);
+    }
+
+    fn prune_for_validators(
+        &self,
+        validators: &ChunkedList<Validator, ValidatorRegistryLimit>,
+        epoch: u64,
+    ) {
+        let is_slashable = |validator_index: u64| {
+            validators
+                .get(validator_index as usize)
+                .is_some_and(|validator| validator.is_slashable_validator(epoch))
+        };
+
+        self.signed_voluntary_exits
+            .write()
+            .retain(|&validator_index, _| {
+                validators
+                    .get(validator_index as usize)
+                    .is_some_and(|validator| validator.exit_epoch == FAR_FUTURE_EPOCH)
+            });
+        self.proposer_slashings
+            .write()
+            .retain(|slashing| is_slashable(slashing.signed_header_1.message.proposer_index));
+        self.attester_slashings.write().retain(|slashing| {
+            let attesting_indices = slashing
+                .attestation_2
+                .attesting_indices
+                .iter()
+                .collect::<HashSet<_>>();
+            slashing
+                .attestation_1
+                .attesting_indices
+                .iter()
+                .any(|index| attesting_indices.contains(index) && is_slashable(*index))
+        });
+        self.signed_bls_to_execution_changes
+            .write()
+            .retain(|_, signed_bls_to_execution_change| {
+                validators
+                    .get(signed_bls_to_execution_change.message.validator_index as usize)
+                    .is_some_and(|validator| {
+                        &validator.withdrawal_credentials[..1] == BLS_WITHDRAWAL_PREFIX
+                    })
+            });
+    }
+
+    /// Drop attestations which target an epoch before the previous epoch, as they can no longer
+    /// be included in a block.
+    pub fn clean_attestations(&self, current_epoch: Epoch) {
+        let mut attestations = self.attestations.write();
+        attestations.retain(|_, attestation| attestation.data.target.epoch + 1 >= current_epoch);
+        set_int_gauge_vec(&ATTESTATION_POOL_SIZE, attestations.len() as i64, &[]);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use ream_bls::PublicKey;
+    use ream_consensus_beacon::{
+        bls_to_execution_change::BLSToExecutionChange, voluntary_exit::VoluntaryExit,
+    };
+    use ream_consensus_misc::{
+        attestation_data::AttestationData,
+        beacon_block_header::{BeaconBlockHeader, SignedBeaconBlockHeader},
+        checkpoint::Checkpoint,
+        slot_epoch::Slot,
+    };
+    use ssz_types::{BitList, BitVector};
+
     use super::*;
 
+    fn attestation(committee_size: usize, positions: &[usize]) -> Attestation {
+        let mut aggregation_bits = BitList::with_capacity(committee_size).unwrap();
+        for position in positions {
+            aggregation_bits.set(*position, true).unwrap();
+        }
+        let mut committee_bits = BitVector::new();
+        committee_bits.set(1, true).unwrap();
+
+        Attestation {
+            aggregation_bits,
+            data: AttestationData {
+                slot: Slot::new(8),
+                index: 0,
+                beacon_block_root: B256::ZERO,
+                source: Checkpoint::default(),
+                target: Checkpoint {
+                    epoch: Epoch::new(1),
+                    root: B256::ZERO,
+                },
+            },
+            signature: BLSSignature::infinity(),
+            committee_bits,
+        }
+    }
+
+    #[test]
+    fn test_attestation_aggregation() {
+        let operation_pool = OperationPool::default();
+
+        assert!(operation_pool.insert_attestation(attestation(4, &[0])));
+        assert!(operation_pool.insert_attestation(attestation(4, &[2])));
+        assert!(!operation_pool.insert_attestation(attestation(4, &[2])));
+
+        let attestations = operation_pool.get_attestations(Some(8), Some(1));
+        assert_eq!(attestations.len(), 1);
+        assert_eq!(
+            attestations[0].aggregation_bits.iter().collect::<Vec<_>>(),
+            vec![true, false, true, false]
+        );
+        assert!(operation_pool.get_attestations(None, Some(0)).is_empty());
+
+        operation_pool.clean_attestations(Epoch::new(3));
+        assert!(operation_pool.get_attestations(None, None).is_empty());
+    }
+
+    fn validator(withdrawal_prefix: u8, slashed: bool, exit_epoch: u64) -> Validator {
+        let mut withdrawal_credentials = B256::ZERO;
+        withdrawal_credentials[0] = withdrawal_prefix;
+        Validator {
+            public_key: PublicKey::default(),
+            withdrawal_credentials,
+            effective_balance: 32_000_000_000,
+            slashed,
+            activation_eligibility_epoch: 0,
+            activation_epoch: 0,
+            exit_epoch,
+            withdrawable_epoch: exit_epoch,
+        }
+    }
+
+    fn signed_header(proposer_index: u64) -> SignedBeaconBlockHeader {
+        SignedBeaconBlockHeader {
+            message: BeaconBlockHeader {
+                proposer_index,
+                ..Default::default()
+            },
+            signature: BLSSignature::infinity(),
+        }
+    }
+
+    #[test]
+    fn test_prune_applied_operations() {
+        let operation_pool = OperationPool::default();
+        let validators = ChunkedList::from(vec![
+            validator(0, false, FAR_FUTURE_EPOCH),
+            validator(1, true, FAR_FUTURE_EPOCH),
+            validator(1, false, 5),
+        ]);
+
+        for validator_index in 0..3 {
+            operation_pool.insert_signed_voluntary_exit(SignedVoluntaryExit {
+                message: VoluntaryExit {
+                    epoch: 0,
+                    validator_index,
+                },
+                signature: BLSSignature::infinity(),
+            });
+            operation_pool.insert_proposer_slashing(ProposerSlashing {
+                signed_header_1: signed_header(validator_index),
+                signed_header_2: signed_header(validator_index),
+            });
+            operation_pool.insert_signed_bls_to_execution_change(SignedBLSToExecutionChange {
+                message: BLSToExecutionChange {
+                    validator_index,
+                    from_bls_public_key: PublicKey::default(),
+                    to_execution_address: Address::ZERO,
+                },
+                signature: BLSSignature::infinity(),
+            });
+        }
+
+        operation_pool.prune_for_validators(&validators, 2);
+
+        // The second validator is slashed, the third exited and both have execution credentials
+        let exits = operation_pool.get_signed_voluntary_exits();
+        assert_eq!(exits.len(), 2);
+        assert!(exits.iter().all(|exit| exit.message.validator_index != 2));
+        let proposer_slashings = operation_pool.get_all_proposer_slashings();
+        assert_eq!(proposer_slashings.len(), 2);
+        assert!(
+            proposer_slashings.iter().all(|slashing| slashing
+                .signed_header_1
+                .message
+                .proposer_index
+                != 1)
+        );
+        let bls_to_execution_changes = operation_pool.get_signed_bls_to_execution_changes();
+        assert_eq!(bls_to_execution_changes.len(), 1);
+        assert_eq!(bls_to_execution_changes[0].message.validator_index, 0);
+    }
+
     #[test]
     fn test_proposer_preparation_operations() {
         let operation_pool = OperationPool::default();
@@ -138,19 +408,19 @@ mod tests {
 
         assert_eq!(operation_pool.get_proposer_preparation(1), None);
 
-        operation_pool.insert_proposer_preparation(1, fee_recipient1, 100);
+        operation_pool.insert_proposer_preparation(1, fee_recipient1, Epoch::new(100));
         assert_eq!(
             operation_pool.get_proposer_preparation(1),
             Some(fee_recipient1)
         );
 
-        operation_pool.insert_proposer_preparation(2, fee_recipient2, 100);
+        operation_pool.insert_proposer_preparation(2, fee_recipient2, Epoch::new(100));
         let all_preparations = operation_pool.get_all_proposer_preparations();
         assert_eq!(all_preparations.len(), 2);
         assert_eq!(all_preparations.get(&1), Some(&fee_recipient1));
         assert_eq!(all_preparations.get(&2), Some(&fee_recipient2));
 
-        operation_pool.insert_proposer_preparation(1, fee_recipient2, 101);
+        operation_pool.insert_proposer_preparation(1, fee_recipient2, Epoch::new(101));
         assert_eq!(
             operation_pool.get_proposer_preparation(1),
             Some(fee_recipient2)
@@ -165,19 +435,19 @@ mod tests {
         let fee_recipient3 = Address::from([0x33; 20]);
 
         // Insert preparations at different epochs
-        operation_pool.insert_proposer_preparation(1, fee_recipient1, 100);
-        operation_pool.insert_proposer_preparation(2, fee_recipient2, 101);
-        operation_pool.insert_proposer_preparation(3, fee_recipient3, 102);
+        operation_pool.insert_proposer_preparation(1, fee_recipient1, Epoch::new(100));
+        operation_pool.insert_proposer_preparation(2, fee_recipient2, Epoch::new(101));
+        operation_pool.insert_proposer_preparation(3, fee_recipient3, Epoch::new(102));
 
         // All should be present initially
         assert_eq!(operation_pool.get_all_proposer_preparations().len(), 3);
 
         // Clean at epoch 102 - all should still be valid
-        operation_pool.clean_proposer_preparations(102);
+        operation_pool.clean_proposer_preparations(Epoch::new(102));
         assert_eq!(operation_pool.get_all_proposer_preparations().len(), 3);
 
         // Clean at epoch 103 - validator 1 (epoch 100) should be expired
-        operation_pool.clean_proposer_preparations(103);
+        operation_pool.clean_proposer_preparations(Epoch::new(103));
         let remaining = operation_pool.get_all_proposer_preparations();
         assert_eq!(remaining.len(), 2);
         assert_eq!(remaining.get(&1), None);
@@ -185,13 +455,13 @@ mod tests {
         assert_eq!(remaining.get(&3), Some(&fee_recipient3));
 
         // Clean at epoch 104 - validators 1 and 2 should be expired
-        operation_pool.clean_proposer_preparations(104);
+        operation_pool.clean_proposer_preparations(Epoch::new(104));
         let remaining = operation_pool.get_all_proposer_preparations();
         assert_eq!(remaining.len(), 1);
         assert_eq!(remaining.get(&3), Some(&fee_recipient3));
 
         // Clean at epoch 105 - all should be expired
-        operation_pool.clean_proposer_preparations(105);
+        operation_pool.clean_proposer_preparations(Epoch::new(105));
         assert_eq!(operation_pool.get_all_proposer_preparations().len(), 0);
     }
 
@@ -201,17 +471,17 @@ mod tests {
         let fee_recipient = Address::from([0x11; 20]);
 
         // Test exact boundary - submission at epoch 100 is valid through epoch 102
-        operation_pool.insert_proposer_preparation(1, fee_recipient, 100);
+        operation_pool.insert_proposer_preparation(1, fee_recipient, Epoch::new(100));
 
         // Should be valid at epoch 102
-        operation_pool.clean_proposer_preparations(102);
+        operation_pool.clean_proposer_preparations(Epoch::new(102));
         assert_eq!(
             operation_pool.get_proposer_preparation(1),
             Some(fee_recipient)
         );
 
         // Should be expired at epoch 103
-        operation_pool.clean_proposer_preparations(103);
+        operation_pool.clean_proposer_preparations(Epoch::new(103));
         assert_eq!(operation_pool.get_proposer_preparation(1), None);
     }
 }
@@ -1,11 +1,22 @@
-use kzg::eip_4844::verify_blob_kzg_proof_batch_raw;
+use anyhow::ensure;
+use kzg::{
+    Fr, G1,
+    das::DAS,
+    eip_4844::{blob_to_kzg_commitment_raw, verify_blob_kzg_proof_batch_raw},
+};
 use ream_consensus_beacon::{
+    blob_sidecar::BlobSidecar,
+    data_column_sidecar::DataColumnSidecar,
     execution_engine::rpc_types::get_blobs::Blob,
     polynomial_commitments::{kzg_commitment::KZGCommitment, kzg_proof::KZGProof},
 };
+use rust_kzg_blst::types::{fr::FsFr, g1::FsG1};
 
 use super::{error::KzgError, trusted_setup};
 
+/// The size in bytes of a field element in a cell.
+const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
 /// Given a list of blobs and blob KZG proofs, verify that they correspond to the provided
 /// commitments. Will return True if there are zero blobs/commitments/proofs.
 /// Public method.
@@ -35,3 +46,80 @@ pub fn verify_blob_kzg_proof_batch(
 
     result.map_err(KzgError::KzgError).map_err(Into::into)
 }
+
+/// Compute the KZG commitment of a blob.
+pub fn blob_to_kzg_commitment(blob: &Blob) -> anyhow::Result<KZGCommitment> {
+    let commitment =
+        blob_to_kzg_commitment_raw(blob.to_fixed_bytes(), trusted_setup::blst_settings())
+            .map_err(KzgError::KzgError)?;
+
+    Ok(KZGCommitment(commitment.to_bytes()))
+}
+
+/// Verify the sidecar's KZG commitment is included in the body of the block the sidecar is for.
+pub fn verify_kzg_commitment_inclusion_proof(blob_sidecar: &BlobSidecar) -> bool {
+    blob_sidecar.verify_blob_sidecar_inclusion_proof()
+}
+
+/// Verify the inclusion proofs and the KZG proofs of blob sidecars received from a peer before
+/// they are trusted. The KZG proofs are verified as a single batch.
+pub fn verify_blob_sidecars(blob_sidecars: &[BlobSidecar]) -> anyhow::Result<()> {
+    for blob_sidecar in blob_sidecars {
+        ensure!(
+            verify_kzg_commitment_inclusion_proof(blob_sidecar),
+            "Invalid inclusion proof for blob sidecar with index {}",
+            blob_sidecar.index
+        );
+    }
+
+    let blobs = blob_sidecars
+        .iter()
+        .map(|blob_sidecar| blob_sidecar.blob.clone())
+        .collect::<Vec<_>>();
+    let commitments = blob_sidecars
+        .iter()
+        .map(|blob_sidecar| blob_sidecar.kzg_commitment)
+        .collect::<Vec<_>>();
+    let proofs = blob_sidecars
+        .iter()
+        .map(|blob_sidecar| blob_sidecar.kzg_proof)
+        .collect::<Vec<_>>();
+    ensure!(
+        verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs)?,
+        "Invalid KZG proofs for blob sidecars"
+    );
+
+    Ok(())
+}
+
+/// Verify that the cells of a data column sidecar correspond to the sidecar's KZG commitments,
+/// using the sidecar's cell proofs. The proofs of the column are verified as a single batch.
+pub fn verify_data_column_sidecar_kzg_proofs(
+    data_column_sidecar: &DataColumnSidecar,
+) -> anyhow::Result<bool> {
+    let commitments = data_column_sidecar
+        .kzg_commitments
+        .iter()
+        .map(|commitment| FsG1::from_bytes(&commitment.0))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(KzgError::KzgError)?;
+    let proofs = data_column_sidecar
+        .kzg_proofs
+        .iter()
+        .map(|proof| FsG1::from_bytes(&proof.0))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(KzgError::KzgError)?;
+    let cells = data_column_sidecar
+        .column
+        .iter()
+        .flat_map(|cell| cell.inner.chunks(BYTES_PER_FIELD_ELEMENT))
+        .map(FsFr::from_bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(KzgError::KzgError)?;
+    let cell_indices = vec![data_column_sidecar.index as usize; data_column_sidecar.column.len()];
+
+    trusted_setup::blst_settings()
+        .verify_cell_kzg_proof_batch(&commitments, &cell_indices, &cells, &proofs)
+        .map_err(KzgError::KzgError)
+        .map_err(Into::into)
+}
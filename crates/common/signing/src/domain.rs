@@ -0,0 +1,179 @@
+use alloy_primitives::{B256, aliases::B32};
+use ream_consensus_misc::{
+    constants::beacon::{
+        DOMAIN_AGGREGATE_AND_PROOF, DOMAIN_APPLICATION_BUILDER, DOMAIN_BEACON_ATTESTER,
+        DOMAIN_BEACON_PROPOSER, DOMAIN_CONTRIBUTION_AND_PROOF, DOMAIN_RANDAO,
+        DOMAIN_SELECTION_PROOF, DOMAIN_SYNC_COMMITTEE, DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF,
+        DOMAIN_VOLUNTARY_EXIT,
+    },
+    misc::{compute_domain, compute_signing_root},
+};
+use tree_hash::TreeHash;
+
+/// The kinds of messages validators sign, each under a domain of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureKind {
+    /// The epoch of a proposed block
+    RandaoReveal,
+    BeaconBlock,
+    Attestation,
+    /// The slot of an attestation, which decides whether the validator aggregates it
+    SelectionProof,
+    AggregateAndProof,
+    /// The head block root signed by a member of the sync committee
+    SyncCommitteeMessage,
+    /// The slot and subcommittee of a sync committee message, which decide whether the validator
+    /// aggregates it
+    SyncCommitteeSelectionProof,
+    ContributionAndProof,
+    VoluntaryExit,
+    /// The registration of a validator with the builders
+    ValidatorRegistration,
+}
+
+impl SignatureKind {
+    pub const ALL: [Self; 10] = [
+        Self::RandaoReveal,
+        Self::BeaconBlock,
+        Self::Attestation,
+        Self::SelectionProof,
+        Self::AggregateAndProof,
+        Self::SyncCommitteeMessage,
+        Self::SyncCommitteeSelectionProof,
+        Self::ContributionAndProof,
+        Self::VoluntaryExit,
+        Self::ValidatorRegistration,
+    ];
+
+    pub fn domain_type(self) -> B32 {
+        match self {
+            Self::RandaoReveal => DOMAIN_RANDAO,
+            Self::BeaconBlock => DOMAIN_BEACON_PROPOSER,
+            Self::Attestation => DOMAIN_BEACON_ATTESTER,
+            Self::SelectionProof => DOMAIN_SELECTION_PROOF,
+            Self::AggregateAndProof => DOMAIN_AGGREGATE_AND_PROOF,
+            Self::SyncCommitteeMessage => DOMAIN_SYNC_COMMITTEE,
+            Self::SyncCommitteeSelectionProof => DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF,
+            Self::ContributionAndProof => DOMAIN_CONTRIBUTION_AND_PROOF,
+            Self::VoluntaryExit => DOMAIN_VOLUNTARY_EXIT,
+            Self::ValidatorRegistration => DOMAIN_APPLICATION_BUILDER,
+        }
+    }
+}
+
+/// The fork and chain which the signatures of validators are bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigningContext {
+    pub fork_version: B32,
+    /// The domains are computed with a zero root when the genesis validators root isn't known
+    pub genesis_validators_root: Option<B256>,
+}
+
+impl SigningContext {
+    pub fn new(fork_version: B32, genesis_validators_root: Option<B256>) -> Self {
+        Self {
+            fork_version,
+            genesis_validators_root,
+        }
+    }
+
+    /// The domain of the messages of `kind`.
+    ///
+    /// Builder registrations are valid on every fork and are signed before the builders know the
+    /// chain, so their domain always uses the genesis fork version and a zero root.
+    pub fn domain(&self, kind: SignatureKind) -> B256 {
+        match kind {
+            SignatureKind::ValidatorRegistration => builder_domain(),
+            _ => compute_domain(
+                kind.domain_type(),
+                Some(self.fork_version),
+                self.genesis_validators_root,
+            ),
+        }
+    }
+
+    pub fn signing_root(&self, kind: SignatureKind, object: impl TreeHash) -> B256 {
+        compute_signing_root(object, self.domain(kind))
+    }
+}
+
+/// The domain of the messages exchanged with the builders.
+pub fn builder_domain() -> B256 {
+    compute_domain(DOMAIN_APPLICATION_BUILDER, None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{b256, fixed_bytes};
+
+    use super::*;
+
+    /// The root of the genesis fork data with a zero genesis validators root.
+    const GENESIS_FORK_DATA_ROOT_PREFIX: &str =
+        "f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9";
+
+    #[test]
+    fn test_domain_types() {
+        assert_eq!(
+            SignatureKind::ALL.map(SignatureKind::domain_type),
+            [
+                fixed_bytes!("0x02000000"),
+                fixed_bytes!("0x00000000"),
+                fixed_bytes!("0x01000000"),
+                fixed_bytes!("0x05000000"),
+                fixed_bytes!("0x06000000"),
+                fixed_bytes!("0x07000000"),
+                fixed_bytes!("0x08000000"),
+                fixed_bytes!("0x09000000"),
+                fixed_bytes!("0x04000000"),
+                fixed_bytes!("0x00000001"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_genesis_domains() {
+        let signing_context = SigningContext::new(B32::ZERO, None);
+        for kind in SignatureKind::ALL {
+            let domain_type = kind.domain_type().to_string();
+            assert_eq!(
+                signing_context.domain(kind).to_string(),
+                format!("{domain_type}{GENESIS_FORK_DATA_ROOT_PREFIX}"),
+                "{kind:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_domain() {
+        assert_eq!(
+            builder_domain(),
+            b256!("0x00000001f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9")
+        );
+
+        // Registrations are signed under the same domain on every fork and chain
+        let signing_context = SigningContext::new(B32::repeat_byte(1), Some(B256::repeat_byte(2)));
+        assert_eq!(
+            signing_context.domain(SignatureKind::ValidatorRegistration),
+            builder_domain()
+        );
+    }
+
+    #[test]
+    fn test_domains_depend_on_fork_and_chain() {
+        let genesis = SigningContext::new(B32::ZERO, None);
+        let fork = SigningContext::new(B32::repeat_byte(1), None);
+        let chain = SigningContext::new(B32::ZERO, Some(B256::repeat_byte(2)));
+
+        for kind in SignatureKind::ALL {
+            if kind == SignatureKind::ValidatorRegistration {
+                continue;
+            }
+            let domain = genesis.domain(kind);
+            assert_ne!(domain, fork.domain(kind), "{kind:?}");
+            assert_ne!(domain, chain.domain(kind), "{kind:?}");
+            // The domain type is kept in the first bytes whatever the fork data
+            assert_eq!(fork.domain(kind)[..4], kind.domain_type()[..], "{kind:?}");
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Signing of the messages of validators.
+//!
+//! Every message is signed over its signing root, which mixes the root of the message with the
+//! domain of its kind, so a signature can't be passed off as one of another kind of message or of
+//! another chain. The [signer::Signer] holding the keys only ever sees signing roots.
+
+pub mod domain;
+pub mod signer;
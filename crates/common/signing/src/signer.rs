@@ -0,0 +1,93 @@
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use ream_bls::{BLSSignature, PublicKey, traits::Signable};
+use ream_keystore::keystore::Keystore;
+use tree_hash::TreeHash;
+
+use crate::domain::{SignatureKind, SigningContext};
+
+/// Holds the key of a validator, such as a local keystore or a remote signer.
+#[async_trait]
+pub trait Signer: Send + Sync {
+    fn public_key(&self) -> &PublicKey;
+
+    /// Signs `signing_root`, the signing root of a message of `kind`. The kind lets signers which
+    /// protect their keys refuse the messages they don't sign.
+    async fn sign_root(
+        &self,
+        kind: SignatureKind,
+        signing_root: B256,
+    ) -> anyhow::Result<BLSSignature>;
+}
+
+#[async_trait]
+impl Signer for Keystore {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    async fn sign_root(
+        &self,
+        _kind: SignatureKind,
+        signing_root: B256,
+    ) -> anyhow::Result<BLSSignature> {
+        Ok(self.private_key.sign(signing_root.as_ref())?)
+    }
+}
+
+/// Signs `object`, a message of `kind`, under its domain in `signing_context`.
+pub async fn sign<S: Signer + ?Sized>(
+    signer: &S,
+    signing_context: &SigningContext,
+    kind: SignatureKind,
+    object: impl TreeHash,
+) -> anyhow::Result<BLSSignature> {
+    let signing_root = signing_context.signing_root(kind, object);
+    signer.sign_root(kind, signing_root).await
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::aliases::B32;
+    use ream_bls::{
+        PrivateKey,
+        traits::{PublicKeyDerivable, Verifiable},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_keystore_signs_under_domain() {
+        let private_key = PrivateKey {
+            inner: B256::with_last_byte(1),
+        };
+        let keystore = Keystore {
+            public_key: private_key.public_key().unwrap(),
+            private_key,
+        };
+        let signing_context = SigningContext::new(B32::ZERO, Some(B256::repeat_byte(1)));
+
+        let signature = sign(
+            &keystore,
+            &signing_context,
+            SignatureKind::RandaoReveal,
+            5u64,
+        )
+        .await
+        .unwrap();
+        let signing_root = signing_context.signing_root(SignatureKind::RandaoReveal, 5u64);
+        assert!(
+            signature
+                .verify(keystore.public_key(), signing_root.as_ref())
+                .unwrap()
+        );
+
+        // The same epoch signed as another kind of message doesn't verify
+        let signing_root = signing_context.signing_root(SignatureKind::VoluntaryExit, 5u64);
+        assert!(
+            !signature
+                .verify(keystore.public_key(), signing_root.as_ref())
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::RwLock;
+use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
+use tokio::time::sleep;
+
+/// A point of the chain's clock, as a slot and an interval within it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotTime {
+    pub slot: u64,
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone)]
+enum TimeSource {
+    System,
+    Manual(Arc<RwLock<Duration>>),
+}
+
+/// SlotClock derives the slot and the interval within it from the genesis time of a chain.
+///
+/// The time is read from the system clock, or from a manually set time for tests. All times are
+/// durations since the UNIX epoch.
+#[derive(Debug, Clone)]
+pub struct SlotClock {
+    genesis_time: Duration,
+    slot_duration: Duration,
+    intervals_per_slot: u64,
+    slot_offset: u64,
+    time_source: TimeSource,
+}
+
+impl SlotClock {
+    pub fn new(genesis_time: u64, seconds_per_slot: u64, intervals_per_slot: u64) -> Self {
+        Self {
+            genesis_time: Duration::from_secs(genesis_time),
+            slot_duration: Duration::from_secs(seconds_per_slot),
+            intervals_per_slot,
+            slot_offset: 0,
+            time_source: TimeSource::System,
+        }
+    }
+
+    /// A clock which stands still at `now` until it is moved with [SlotClock::set_now] or
+    /// [SlotClock::advance]. Clones of the clock share its time.
+    pub fn manual(
+        genesis_time: u64,
+        seconds_per_slot: u64,
+        intervals_per_slot: u64,
+        now: Duration,
+    ) -> Self {
+        Self {
+            time_source: TimeSource::Manual(Arc::new(RwLock::new(now))),
+            ..Self::new(genesis_time, seconds_per_slot, intervals_per_slot)
+        }
+    }
+
+    /// Numbers the slots from `slot_offset` instead of 0, as the lean chain does.
+    pub fn with_slot_offset(mut self, slot_offset: u64) -> Self {
+        self.slot_offset = slot_offset;
+        self
+    }
+
+    /// Sets the time of a manual clock. Has no effect on a clock which follows the system time.
+    pub fn set_now(&self, now: Duration) {
+        if let TimeSource::Manual(time) = &self.time_source {
+            *time.write() = now;
+        }
+    }
+
+    /// Moves a manual clock forward. Has no effect on a clock which follows the system time.
+    pub fn advance(&self, duration: Duration) {
+        if let TimeSource::Manual(time) = &self.time_source {
+            *time.write() += duration;
+        }
+    }
+
+    pub fn now(&self) -> Duration {
+        match &self.time_source {
+            TimeSource::System => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("System time is before the UNIX epoch"),
+            TimeSource::Manual(time) => *time.read(),
+        }
+    }
+
+    pub fn genesis_time(&self) -> Duration {
+        self.genesis_time
+    }
+
+    pub fn slot_duration(&self) -> Duration {
+        self.slot_duration
+    }
+
+    pub fn interval_duration(&self) -> Duration {
+        self.slot_duration / self.intervals_per_slot as u32
+    }
+
+    /// The number of intervals since genesis at `now`, or None before genesis.
+    fn interval_index_at(&self, now: Duration) -> Option<u64> {
+        let elapsed = now.checked_sub(self.genesis_time)?;
+        Some((elapsed.as_nanos() / self.interval_duration().as_nanos()) as u64)
+    }
+
+    fn interval_start(&self, interval_index: u64) -> Duration {
+        self.genesis_time
+            + Duration::from_nanos(
+                (self.interval_duration().as_nanos() * interval_index as u128) as u64,
+            )
+    }
+
+    fn slot_time_of(&self, interval_index: u64) -> SlotTime {
+        SlotTime {
+            slot: interval_index / self.intervals_per_slot + self.slot_offset,
+            interval: interval_index % self.intervals_per_slot,
+        }
+    }
+
+    /// Returns the slot and interval at `now`, or None before genesis.
+    pub fn slot_time_at(&self, now: Duration) -> Option<SlotTime> {
+        Some(self.slot_time_of(self.interval_index_at(now)?))
+    }
+
+    pub fn current_slot_time(&self) -> Option<SlotTime> {
+        self.slot_time_at(self.now())
+    }
+
+    /// Returns the current slot, or None before genesis.
+    pub fn current_slot(&self) -> Option<u64> {
+        self.current_slot_time().map(|slot_time| slot_time.slot)
+    }
+
+    /// Returns the current epoch of the beacon chain, or None before genesis.
+    pub fn current_epoch(&self) -> Option<u64> {
+        self.current_slot()
+            .map(|slot| (slot - self.slot_offset) / SLOTS_PER_EPOCH)
+    }
+
+    /// Returns the time at which `slot` starts.
+    pub fn start_of(&self, slot: u64) -> Duration {
+        self.interval_start(slot.saturating_sub(self.slot_offset) * self.intervals_per_slot)
+    }
+
+    /// Returns the duration until the next slot starts, or until genesis before genesis.
+    pub fn duration_to_next_slot(&self) -> Duration {
+        let now = self.now();
+        let next_slot = match self.interval_index_at(now) {
+            Some(interval_index) => interval_index / self.intervals_per_slot + 1,
+            None => 0,
+        };
+        self.interval_start(next_slot * self.intervals_per_slot)
+            .saturating_sub(now)
+    }
+
+    /// Returns the duration until the next interval starts, or until genesis before genesis.
+    pub fn duration_to_next_interval(&self) -> Duration {
+        let now = self.now();
+        let next_interval = self
+            .interval_index_at(now)
+            .map_or(0, |interval_index| interval_index + 1);
+        self.interval_start(next_interval).saturating_sub(now)
+    }
+
+    /// Returns a stream of the starts of the intervals, beginning with the next one.
+    pub fn ticks(&self) -> IntervalTicks {
+        IntervalTicks {
+            clock: self.clone(),
+            next_interval: None,
+        }
+    }
+}
+
+/// The starts of the intervals of a [SlotClock].
+///
+/// Every tick is derived from the clock rather than counted, so a tick which fires late is still
+/// attributed to the interval it was due in. Intervals which have passed entirely, e.g. while the
+/// system was suspended, are skipped instead of being ticked in a burst.
+#[derive(Debug)]
+pub struct IntervalTicks {
+    clock: SlotClock,
+    next_interval: Option<u64>,
+}
+
+impl IntervalTicks {
+    /// Waits for the start of the next interval and returns it. Cancelling the returned future
+    /// doesn't skip an interval.
+    pub async fn next(&mut self) -> SlotTime {
+        let now = self.clock.now();
+        let target = self.next_interval.unwrap_or_else(|| {
+            self.clock
+                .interval_index_at(now)
+                .map_or(0, |interval_index| interval_index + 1)
+        });
+        sleep(self.clock.interval_start(target).saturating_sub(now)).await;
+
+        let interval_index = self
+            .clock
+            .interval_index_at(self.clock.now())
+            .map_or(target, |interval_index| interval_index.max(target));
+        self.next_interval = Some(interval_index + 1);
+        self.clock.slot_time_of(interval_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS_TIME: u64 = 1_000;
+
+    fn at(seconds: u64, millis: u64) -> Duration {
+        Duration::from_secs(GENESIS_TIME + seconds) + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_slot_time() {
+        let clock = SlotClock::manual(GENESIS_TIME, 12, 3, at(0, 0) - Duration::from_secs(5));
+        assert_eq!(clock.current_slot(), None);
+        assert_eq!(clock.duration_to_next_slot(), Duration::from_secs(5));
+        assert_eq!(clock.duration_to_next_interval(), Duration::from_secs(5));
+
+        clock.set_now(at(0, 0));
+        assert_eq!(
+            clock.current_slot_time(),
+            Some(SlotTime {
+                slot: 0,
+                interval: 0
+            })
+        );
+
+        clock.advance(Duration::from_millis(12 * 32 * 1_000 + 8_500));
+        assert_eq!(
+            clock.current_slot_time(),
+            Some(SlotTime {
+                slot: 32,
+                interval: 2
+            })
+        );
+        assert_eq!(clock.current_epoch(), Some(1));
+        assert_eq!(clock.duration_to_next_slot(), Duration::from_millis(3_500));
+        assert_eq!(
+            clock.duration_to_next_interval(),
+            Duration::from_millis(3_500)
+        );
+        assert_eq!(clock.start_of(33), at(12 * 33, 0));
+    }
+
+    #[test]
+    fn test_slot_offset() {
+        let clock = SlotClock::manual(GENESIS_TIME, 4, 4, at(5, 0)).with_slot_offset(1);
+        assert_eq!(
+            clock.current_slot_time(),
+            Some(SlotTime {
+                slot: 2,
+                interval: 1
+            })
+        );
+        assert_eq!(clock.current_epoch(), Some(0));
+        assert_eq!(clock.start_of(2), at(4, 0));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ticks() {
+        let clock = SlotClock::manual(GENESIS_TIME, 4, 4, at(0, 500));
+        let mut ticks = clock.ticks();
+
+        assert_eq!(
+            ticks.next().await,
+            SlotTime {
+                slot: 0,
+                interval: 1
+            }
+        );
+        assert_eq!(
+            ticks.next().await,
+            SlotTime {
+                slot: 0,
+                interval: 2
+            }
+        );
+
+        // The intervals which passed while the ticks weren't polled are skipped
+        clock.set_now(at(5, 200));
+        assert_eq!(
+            ticks.next().await,
+            SlotTime {
+                slot: 1,
+                interval: 1
+            }
+        );
+    }
+}
@@ -2,9 +2,8 @@ use ream_bls::{BLSSignature, PrivateKey, traits::Signable};
 use ream_consensus_beacon::{attestation::Attestation, electra::beacon_state::BeaconState};
 use ream_consensus_misc::{
     constants::beacon::DOMAIN_AGGREGATE_AND_PROOF,
-    misc::{compute_domain, compute_epoch_at_slot, compute_signing_root},
+    misc::{compute_epoch_at_slot, compute_signing_root},
 };
-use ream_network_spec::networks::beacon_network_spec;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash_derive::TreeHash;
@@ -18,7 +17,7 @@ pub struct AggregateAndProof {
     pub selection_proof: BLSSignature,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Encode, Decode)]
 pub struct SignedAggregateAndProof {
     pub message: AggregateAndProof,
     pub signature: BLSSignature,
@@ -31,7 +30,7 @@ pub fn get_aggregate_and_proof(
     private_key: PrivateKey,
 ) -> anyhow::Result<AggregateAndProof> {
     Ok(AggregateAndProof {
-        selection_proof: get_slot_signature(state, aggregate.data.slot, private_key)?,
+        selection_proof: get_slot_signature(state, aggregate.data.slot.as_u64(), private_key)?,
         aggregator_index,
         aggregate,
     })
@@ -45,22 +44,9 @@ pub fn get_aggregate_and_proof_signature(
     let domain = state.get_domain(
         DOMAIN_AGGREGATE_AND_PROOF,
         Some(compute_epoch_at_slot(
-            aggregate_and_proof.aggregate.data.slot,
+            aggregate_and_proof.aggregate.data.slot.as_u64(),
         )),
     );
     let signing_root = compute_signing_root(aggregate_and_proof, domain);
     Ok(private_key.sign(signing_root.as_ref())?)
 }
-
-pub fn sign_aggregate_and_proof(
-    aggregate_and_proof: &AggregateAndProof,
-    private_key: &PrivateKey,
-) -> anyhow::Result<BLSSignature> {
-    let domain = compute_domain(
-        DOMAIN_AGGREGATE_AND_PROOF,
-        Some(beacon_network_spec().electra_fork_version),
-        None,
-    );
-    let signing_root = compute_signing_root(aggregate_and_proof, domain);
-    Ok(private_key.sign(signing_root.as_ref())?)
-}
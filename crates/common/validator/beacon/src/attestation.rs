@@ -10,21 +10,17 @@ use ream_consensus_beacon::{attestation::Attestation, electra::beacon_state::Bea
 use ream_consensus_misc::{
     attestation_data::AttestationData,
     constants::beacon::{
-        DOMAIN_BEACON_ATTESTER, MAX_COMMITTEES_PER_SLOT, MAX_VALIDATORS_PER_COMMITTEE,
-        SLOTS_PER_EPOCH,
+        DOMAIN_BEACON_ATTESTER, DOMAIN_SELECTION_PROOF, MAX_COMMITTEES_PER_SLOT,
+        MAX_VALIDATORS_PER_COMMITTEE,
     },
-    misc::{compute_domain, compute_epoch_at_slot, compute_signing_root, get_committee_indices},
+    misc::{compute_epoch_at_slot, compute_signing_root, get_committee_indices},
 };
-use ream_network_spec::networks::beacon_network_spec;
 use ssz_types::{
     BitList, BitVector,
     typenum::{U64, U131072},
 };
 
-use crate::{
-    constants::{DOMAIN_SELECTION_PROOF, TARGET_AGGREGATORS_PER_COMMITTEE},
-    hash_signature_prefix_to_u64,
-};
+use crate::{constants::TARGET_AGGREGATORS_PER_COMMITTEE, hash_signature_prefix_to_u64};
 
 pub fn is_aggregator(
     state: &BeaconState,
@@ -32,26 +28,24 @@ pub fn is_aggregator(
     committee_index: u64,
     slot_signature: BLSSignature,
 ) -> anyhow::Result<bool> {
-    Ok(
-        (hash_signature_prefix_to_u64(&slot_signature) as usize).is_multiple_of(max(
-            1,
-            state.get_beacon_committee(slot, committee_index)?.len()
-                / TARGET_AGGREGATORS_PER_COMMITTEE as usize,
-        )),
-    )
+    Ok(is_aggregator_for_committee_length(
+        state.get_beacon_committee(slot, committee_index)?.len() as u64,
+        &slot_signature,
+    ))
 }
 
-/// Compute the correct subnet for an attestation for Phase 0.
-/// Note, this mimics expected future behavior where attestations will be mapped to their shard
-/// subnet.
-pub fn compute_subnet_for_attestation(
-    committees_per_slot: u64,
-    slot: u64,
-    committee_index: u64,
-) -> u64 {
-    let slots_since_epoch_start = slot % SLOTS_PER_EPOCH;
-    let committee_since_epoch_start = committees_per_slot * slots_since_epoch_start;
-    (committee_since_epoch_start + committee_index) % beacon_network_spec().attestation_subnet_count
+/// Selects on average [TARGET_AGGREGATORS_PER_COMMITTEE] aggregators out of a committee of
+/// `committee_length` validators. Every member of a committee smaller than the target is an
+/// aggregator.
+pub fn is_aggregator_for_committee_length(
+    committee_length: u64,
+    slot_signature: &BLSSignature,
+) -> bool {
+    hash_signature_prefix_to_u64(slot_signature).is_multiple_of(aggregator_modulo(committee_length))
+}
+
+pub fn aggregator_modulo(committee_length: u64) -> u64 {
+    max(1, committee_length / TARGET_AGGREGATORS_PER_COMMITTEE)
 }
 
 pub fn compute_on_chain_aggregate(mut aggregates: Vec<Attestation>) -> anyhow::Result<Attestation> {
@@ -102,7 +96,10 @@ pub fn get_attestation_signature(
     attestation_data: AttestationData,
     private_key: PrivateKey,
 ) -> anyhow::Result<BLSSignature> {
-    let domain = state.get_domain(DOMAIN_BEACON_ATTESTER, Some(attestation_data.target.epoch));
+    let domain = state.get_domain(
+        DOMAIN_BEACON_ATTESTER,
+        Some(attestation_data.target.epoch.as_u64()),
+    );
     let signing_root = compute_signing_root(attestation_data, domain);
     Ok(private_key.sign(signing_root.as_ref())?)
 }
@@ -125,25 +122,64 @@ pub fn get_aggregate_signature(attestations: Vec<Attestation>) -> anyhow::Result
     Ok(BLSSignature::aggregate(&signatures)?)
 }
 
-pub fn sign_attestation_data(
-    attestation_data: &AttestationData,
-    private_key: &PrivateKey,
-) -> anyhow::Result<BLSSignature> {
-    let domain = compute_domain(
-        DOMAIN_BEACON_ATTESTER,
-        Some(beacon_network_spec().electra_fork_version),
-        None,
-    );
-    let signing_root = compute_signing_root(attestation_data, domain);
-    Ok(private_key.sign(signing_root.as_ref())?)
-}
+#[cfg(test)]
+mod tests {
+    use ssz_types::FixedVector;
 
-pub fn get_selection_proof(slot: u64, private_key: &PrivateKey) -> anyhow::Result<BLSSignature> {
-    let domain = compute_domain(
-        DOMAIN_SELECTION_PROOF,
-        Some(beacon_network_spec().electra_fork_version),
-        None,
-    );
-    let signing_root = compute_signing_root(slot, domain);
-    Ok(private_key.sign(signing_root.as_ref())?)
+    use super::*;
+
+    fn signature_with_byte(byte: u8) -> BLSSignature {
+        BLSSignature {
+            inner: FixedVector::from(vec![byte; 96]),
+        }
+    }
+
+    #[test]
+    fn test_aggregator_modulo() {
+        assert_eq!(aggregator_modulo(0), 1);
+        assert_eq!(aggregator_modulo(1), 1);
+        assert_eq!(aggregator_modulo(TARGET_AGGREGATORS_PER_COMMITTEE - 1), 1);
+        assert_eq!(aggregator_modulo(TARGET_AGGREGATORS_PER_COMMITTEE), 1);
+        assert_eq!(
+            aggregator_modulo(2 * TARGET_AGGREGATORS_PER_COMMITTEE - 1),
+            1
+        );
+        assert_eq!(aggregator_modulo(2 * TARGET_AGGREGATORS_PER_COMMITTEE), 2);
+        assert_eq!(aggregator_modulo(MAX_VALIDATORS_PER_COMMITTEE), 128);
+    }
+
+    #[test]
+    fn test_small_committee_members_are_all_aggregators() {
+        for byte in 0..=u8::MAX {
+            let signature = signature_with_byte(byte);
+            for committee_length in 0..2 * TARGET_AGGREGATORS_PER_COMMITTEE {
+                assert!(is_aggregator_for_committee_length(
+                    committee_length,
+                    &signature
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_aggregator_selection_follows_hash_prefix() {
+        let committee_length = MAX_VALIDATORS_PER_COMMITTEE;
+        let modulo = aggregator_modulo(committee_length);
+
+        let mut selected = 0;
+        for byte in 0..=u8::MAX {
+            let signature = signature_with_byte(byte);
+            let is_selected = is_aggregator_for_committee_length(committee_length, &signature);
+            assert_eq!(
+                is_selected,
+                hash_signature_prefix_to_u64(&signature).is_multiple_of(modulo)
+            );
+            if is_selected {
+                selected += 1;
+            }
+        }
+
+        // Roughly 1 in 128 signatures is selected, so far from every one of the 256 samples
+        assert!(selected < 32);
+    }
 }
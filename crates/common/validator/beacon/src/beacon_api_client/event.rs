@@ -84,6 +84,15 @@ pub enum BeaconEvent {
     Attestation(AttestationEvent),
 }
 
+/// The items of an events stream, which tell about its connection besides the events, so the
+/// consumers know when events may have been missed.
+pub enum EventStreamItem {
+    Connected,
+    /// The stream failed and is reconnecting, or ends if it can't
+    Disconnected,
+    Event(Box<BeaconEvent>),
+}
+
 impl BeaconEvent {
     fn from_json<T: DeserializeOwned>(
         json: &str,
@@ -0,0 +1,431 @@
+use std::{collections::HashSet, time::Duration};
+
+use anyhow::ensure;
+use futures::future::join_all;
+use parking_lot::RwLock;
+use ream_api_types_beacon::{responses::DataResponse, sync::SyncStatus};
+use ream_metrics::{VALIDATOR_BEACON_API_REQUEST_RETRIES, inc_int_counter_vec};
+use reqwest::{IntoUrl, Request, RequestBuilder, Response, StatusCode, Url};
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use super::{
+    http_client::{ClientWithBaseUrl, ContentType, endpoint_label, join_base_url},
+    retry::{RetryConfig, is_retryable},
+};
+
+/// Health of a beacon node endpoint, ordered from most to least preferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EndpointHealth {
+    Synced,
+    Syncing,
+    Offline,
+}
+
+/// Sends Beacon API requests to a list of beacon nodes.
+///
+/// Requests are built against the preferred endpoint, and retried against the other endpoints
+/// when it can't be reached or answers with a server error. Endpoints are preferred by their
+/// [EndpointHealth] first and by the order they were configured in second. Requests publishing
+/// blocks and signatures are broadcast to every endpoint instead. When no endpoint could serve a
+/// request, it is sent again after a backoff as configured by [RetryConfig].
+///
+/// Requests with an SSZ body can carry the same request with a JSON body, which is sent instead to
+/// the endpoints rejecting SSZ for that path. Which endpoints rejected SSZ is remembered, so they
+/// are only sent JSON from then on.
+#[derive(Debug)]
+pub struct BeaconNodeFallback {
+    clients: Vec<ClientWithBaseUrl>,
+    health: RwLock<Vec<EndpointHealth>>,
+    retry_config: RetryConfig,
+    /// The endpoints which don't accept SSZ bodies, by index and path label
+    json_only: RwLock<HashSet<(usize, String)>>,
+}
+
+impl BeaconNodeFallback {
+    pub fn new(
+        urls: Vec<Url>,
+        request_timeout: Duration,
+        retry_config: RetryConfig,
+        content_type: ContentType,
+    ) -> anyhow::Result<Self> {
+        ensure!(
+            !urls.is_empty(),
+            "At least one beacon api endpoint is required"
+        );
+
+        let clients = urls
+            .into_iter()
+            .map(|url| ClientWithBaseUrl::new(url, request_timeout, content_type.clone()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            health: RwLock::new(vec![EndpointHealth::Synced; clients.len()]),
+            clients,
+            retry_config,
+            json_only: RwLock::new(HashSet::new()),
+        })
+    }
+
+    pub fn base_url(&self) -> &Url {
+        self.preferred_client().base_url()
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> anyhow::Result<RequestBuilder> {
+        self.preferred_client().get(url)
+    }
+
+    pub fn post<U: IntoUrl>(
+        &self,
+        url: U,
+        content_type: ContentType,
+    ) -> anyhow::Result<RequestBuilder> {
+        self.preferred_client().post(url, content_type)
+    }
+
+    /// Execute the request against the healthiest endpoint, falling back to the next endpoints on
+    /// connection errors, timeouts and server errors, and retrying while all of them fail.
+    pub async fn execute(&self, request: Request) -> Result<Response, reqwest::Error> {
+        self.execute_with_json_fallback(request, None).await
+    }
+
+    async fn execute_with_json_fallback(
+        &self,
+        request: Request,
+        json_request: Option<&Request>,
+    ) -> Result<Response, reqwest::Error> {
+        let mut retry = 0;
+        loop {
+            let Some(result) = self.execute_once(&request, json_request).await else {
+                // Requests which can't be cloned can only be sent once
+                return self.preferred_client().execute(request).await;
+            };
+            if !self.should_retry(&request, &result, retry) {
+                return result;
+            }
+            sleep(self.retry_config.backoff(retry)).await;
+            retry += 1;
+        }
+    }
+
+    /// Execute the request against the endpoints in order of preference until one serves it. The
+    /// result is [None] if the request couldn't be sent to any endpoint.
+    async fn execute_once(
+        &self,
+        request: &Request,
+        json_request: Option<&Request>,
+    ) -> Option<Result<Response, reqwest::Error>> {
+        let mut last_result = None;
+        for index in self.endpoint_order() {
+            let Some(result) = self.send_to_endpoint(index, request, json_request).await else {
+                continue;
+            };
+
+            let base_url = self.clients[index].base_url();
+            match result {
+                Ok(response) if response.status().is_server_error() => {
+                    warn!(
+                        "Beacon node {base_url} failed to serve {}: {}",
+                        request.url().path(),
+                        response.status()
+                    );
+                    last_result = Some(Ok(response));
+                }
+                Ok(response) => return Some(Ok(response)),
+                Err(err) => {
+                    warn!("Beacon node {base_url} is unreachable: {err}");
+                    self.set_health(index, EndpointHealth::Offline);
+                    last_result = Some(Err(err));
+                }
+            }
+        }
+
+        last_result
+    }
+
+    /// Execute the request against every endpoint at once, returning the first successful
+    /// response if there is one, and retrying while none of them succeeds.
+    pub async fn broadcast(&self, request: Request) -> Result<Response, reqwest::Error> {
+        self.broadcast_with_json_fallback(request, None).await
+    }
+
+    /// Broadcast a request with an SSZ body, sending `json_request` instead to the endpoints which
+    /// reject SSZ.
+    pub async fn broadcast_ssz(
+        &self,
+        ssz_request: Request,
+        json_request: Request,
+    ) -> Result<Response, reqwest::Error> {
+        self.broadcast_with_json_fallback(ssz_request, Some(&json_request))
+            .await
+    }
+
+    async fn broadcast_with_json_fallback(
+        &self,
+        request: Request,
+        json_request: Option<&Request>,
+    ) -> Result<Response, reqwest::Error> {
+        let mut retry = 0;
+        loop {
+            if self.clients.len() < 2 || request.try_clone().is_none() {
+                return self.execute_with_json_fallback(request, json_request).await;
+            }
+
+            let mut results = join_all(
+                (0..self.clients.len())
+                    .map(|index| self.send_to_endpoint(index, &request, json_request)),
+            )
+            .await
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+            if results.is_empty() {
+                return self.preferred_client().execute(request).await;
+            }
+
+            let index = results
+                .iter()
+                .position(|result| {
+                    result
+                        .as_ref()
+                        .is_ok_and(|response| response.status().is_success())
+                })
+                .unwrap_or_default();
+            let result = results.swap_remove(index);
+            if !self.should_retry(&request, &result, retry) {
+                return result;
+            }
+            sleep(self.retry_config.backoff(retry)).await;
+            retry += 1;
+        }
+    }
+
+    fn should_retry(
+        &self,
+        request: &Request,
+        result: &Result<Response, reqwest::Error>,
+        retry: u32,
+    ) -> bool {
+        if retry >= self.retry_config.max_retries || !is_retryable(result) {
+            return false;
+        }
+
+        let endpoint = endpoint_label(request.url().path());
+        warn!(
+            "No beacon node could serve {endpoint}, retrying in {:?}",
+            self.retry_config.backoff(retry)
+        );
+        inc_int_counter_vec(&VALIDATOR_BEACON_API_REQUEST_RETRIES, &[&endpoint]);
+        true
+    }
+
+    /// Refresh the health of every endpoint from its `/eth/v1/node/syncing` status.
+    pub async fn update_health(&self) {
+        if self.clients.len() < 2 {
+            return;
+        }
+
+        let health = join_all(self.clients.iter().map(|client| async move {
+            fetch_endpoint_health(client).await.unwrap_or_else(|err| {
+                warn!(
+                    "Failed to fetch the sync status of beacon node {}: {err:?}",
+                    client.base_url()
+                );
+                EndpointHealth::Offline
+            })
+        }))
+        .await;
+
+        let mut current_health = self.health.write();
+        for (client, (current, new)) in self
+            .clients
+            .iter()
+            .zip(current_health.iter().zip(health.iter()))
+        {
+            if current != new {
+                info!(
+                    "Beacon node {} changed from {current:?} to {new:?}",
+                    client.base_url()
+                );
+            }
+        }
+        *current_health = health;
+    }
+
+    fn set_health(&self, index: usize, health: EndpointHealth) {
+        if let Some(endpoint_health) = self.health.write().get_mut(index) {
+            *endpoint_health = health;
+        }
+    }
+
+    fn endpoint_order(&self) -> Vec<usize> {
+        order_endpoints(&self.health.read())
+    }
+
+    fn preferred_client(&self) -> &ClientWithBaseUrl {
+        let index = self.endpoint_order().first().copied().unwrap_or_default();
+        &self.clients[index]
+    }
+
+    /// Send the request to one endpoint, as JSON if the endpoint doesn't accept the SSZ body. The
+    /// result is [None] if the request couldn't be built for the endpoint.
+    async fn send_to_endpoint(
+        &self,
+        index: usize,
+        request: &Request,
+        json_request: Option<&Request>,
+    ) -> Option<Result<Response, reqwest::Error>> {
+        let Some(json_request) = json_request else {
+            let endpoint_request = self.request_for_endpoint(request, index)?;
+            return Some(self.clients[index].execute(endpoint_request).await);
+        };
+
+        let endpoint = (index, endpoint_label(request.url().path()));
+        if !self.json_only.read().contains(&endpoint) {
+            let endpoint_request = self.request_for_endpoint(request, index)?;
+            let result = self.clients[index].execute(endpoint_request).await;
+            if !result
+                .as_ref()
+                .is_ok_and(|response| rejects_ssz(response.status()))
+            {
+                return Some(result);
+            }
+
+            info!(
+                "Beacon node {} doesn't accept SSZ for {}, sending JSON instead",
+                self.clients[index].base_url(),
+                endpoint.1
+            );
+            self.json_only.write().insert(endpoint);
+        }
+
+        let endpoint_request = self.request_for_endpoint(json_request, index)?;
+        Some(self.clients[index].execute(endpoint_request).await)
+    }
+
+    fn request_for_endpoint(&self, request: &Request, index: usize) -> Option<Request> {
+        let mut endpoint_request = request.try_clone()?;
+        // The request was built on the base url of one of the endpoints, which is the longest one
+        // it starts with
+        let request_base_url = self
+            .clients
+            .iter()
+            .map(|client| client.base_url())
+            .filter(|base_url| request.url().as_str().starts_with(base_url.as_str()))
+            .max_by_key(|base_url| base_url.as_str().len());
+        *endpoint_request.url_mut() = rebase_url(
+            request.url(),
+            request_base_url,
+            self.clients[index].base_url(),
+        )
+        .inspect_err(|err| warn!("Failed to build the request url: {err}"))
+        .ok()?;
+        Some(endpoint_request)
+    }
+}
+
+async fn fetch_endpoint_health(client: &ClientWithBaseUrl) -> anyhow::Result<EndpointHealth> {
+    let response = client
+        .execute(client.get("/eth/v1/node/syncing".to_string())?.build()?)
+        .await?;
+    ensure!(
+        response.status().is_success(),
+        "Request failed with status code: {}",
+        response.status()
+    );
+
+    let sync_status = response.json::<DataResponse<SyncStatus>>().await?.data;
+    Ok(if sync_status.is_syncing || sync_status.el_offline {
+        EndpointHealth::Syncing
+    } else {
+        EndpointHealth::Synced
+    })
+}
+
+/// Whether a beacon node answered that it can't read the SSZ body of a request.
+fn rejects_ssz(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::UNSUPPORTED_MEDIA_TYPE | StatusCode::NOT_ACCEPTABLE
+    )
+}
+
+/// Indices of the endpoints from the most to the least preferred.
+fn order_endpoints(health: &[EndpointHealth]) -> Vec<usize> {
+    let mut order = (0..health.len()).collect::<Vec<_>>();
+    order.sort_by_key(|index| health[*index]);
+    order
+}
+
+/// Point `url`, which was built on `from_base_url`, at the beacon node of `base_url`, keeping its
+/// path below the base path and its query.
+fn rebase_url(
+    url: &Url,
+    from_base_url: Option<&Url>,
+    base_url: &Url,
+) -> Result<Url, url::ParseError> {
+    let path = from_base_url
+        .and_then(|from_base_url| url.path().strip_prefix(from_base_url.path()))
+        .unwrap_or(url.path());
+    let mut rebased_url = join_base_url(base_url, path)?;
+    rebased_url.set_query(url.query());
+    Ok(rebased_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_endpoints() {
+        assert_eq!(
+            order_endpoints(&[
+                EndpointHealth::Offline,
+                EndpointHealth::Synced,
+                EndpointHealth::Syncing,
+                EndpointHealth::Synced,
+            ]),
+            vec![1, 3, 2, 0]
+        );
+    }
+
+    #[test]
+    fn test_rejects_ssz() {
+        assert!(rejects_ssz(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+        assert!(rejects_ssz(StatusCode::NOT_ACCEPTABLE));
+        assert!(!rejects_ssz(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn test_rebase_url() {
+        let from_base_url = Url::parse("http://localhost:5052").unwrap();
+        let url =
+            Url::parse("http://localhost:5052/eth/v1/validator/attestation_data?slot=1").unwrap();
+        let base_url = Url::parse("https://beacon.example.com:443/").unwrap();
+
+        assert_eq!(
+            rebase_url(&url, Some(&from_base_url), &base_url)
+                .unwrap()
+                .as_str(),
+            "https://beacon.example.com/eth/v1/validator/attestation_data?slot=1"
+        );
+    }
+
+    #[test]
+    fn test_rebase_url_keeps_base_paths() {
+        let from_base_url = Url::parse("http://localhost:5052/beacon").unwrap();
+        let url = join_base_url(&from_base_url, "/eth/v1/node/syncing").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:5052/beacon/eth/v1/node/syncing"
+        );
+
+        let base_url = Url::parse("https://example.com/nodes/1/").unwrap();
+        assert_eq!(
+            rebase_url(&url, Some(&from_base_url), &base_url)
+                .unwrap()
+                .as_str(),
+            "https://example.com/nodes/1/eth/v1/node/syncing"
+        );
+    }
+}
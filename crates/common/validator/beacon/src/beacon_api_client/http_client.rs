@@ -2,11 +2,17 @@ use std::time::Duration;
 
 use anyhow::anyhow;
 use ream_api_types_beacon::responses::{ACCEPT_PRIORITY, JSON_CONTENT_TYPE, SSZ_CONTENT_TYPE};
+use ream_metrics::{
+    VALIDATOR_BEACON_API_REQUEST_ERRORS, VALIDATOR_BEACON_API_REQUEST_TIME, inc_int_counter_vec,
+    start_timer_vec, stop_timer,
+};
 use reqwest::{
     Client, IntoUrl, Request, RequestBuilder, Response, Url,
     header::{ACCEPT, CONTENT_TYPE, HeaderMap, HeaderValue},
 };
 
+use super::retry::error_kind;
+
 #[derive(Debug, Clone)]
 pub enum ContentType {
     Json,
@@ -56,7 +62,7 @@ impl ClientWithBaseUrl {
     }
 
     pub fn get<U: IntoUrl>(&self, url: U) -> anyhow::Result<RequestBuilder> {
-        let url = self.base_url.join(url.as_str())?;
+        let url = join_base_url(&self.base_url, url.as_str())?;
 
         let mut headers = HeaderMap::new();
         match self.content_type {
@@ -77,7 +83,7 @@ impl ClientWithBaseUrl {
         url: U,
         content_type: ContentType,
     ) -> anyhow::Result<RequestBuilder> {
-        let url = self.base_url.join(url.as_str())?;
+        let url = join_base_url(&self.base_url, url.as_str())?;
 
         Ok(self
             .client
@@ -86,6 +92,59 @@ impl ClientWithBaseUrl {
     }
 
     pub async fn execute(&self, request: Request) -> Result<Response, reqwest::Error> {
-        self.client.execute(request).await
+        let endpoint = endpoint_label(request.url().path());
+        let timer = start_timer_vec(&VALIDATOR_BEACON_API_REQUEST_TIME, &[&endpoint]);
+        let result = self.client.execute(request).await;
+        stop_timer(timer);
+        if let Some(kind) = error_kind(&result) {
+            inc_int_counter_vec(&VALIDATOR_BEACON_API_REQUEST_ERRORS, &[&endpoint, kind]);
+        }
+        result
+    }
+}
+
+/// Join `path` onto the path of `base_url`. Unlike [Url::join], an absolute `path` keeps the path
+/// of `base_url`, e.g. of a beacon node served behind a proxy at `https://example.com/beacon/`.
+pub fn join_base_url(base_url: &Url, path: &str) -> Result<Url, url::ParseError> {
+    let mut url = base_url.clone();
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    url.join(path.trim_start_matches('/'))
+}
+
+/// The path of a Beacon API request with its slots, epochs, roots and public keys replaced, so
+/// that the requests to an endpoint share a metrics label.
+pub fn endpoint_label(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.starts_with("0x") || segment.parse::<u64>().is_ok() {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_label() {
+        assert_eq!(
+            endpoint_label("/eth/v3/validator/blocks/123"),
+            "/eth/v3/validator/blocks/{id}"
+        );
+        assert_eq!(
+            endpoint_label("/eth/v1/beacon/states/head/validators/0xabcd"),
+            "/eth/v1/beacon/states/head/validators/{id}"
+        );
+        assert_eq!(
+            endpoint_label("/eth/v1/node/syncing"),
+            "/eth/v1/node/syncing"
+        );
     }
 }
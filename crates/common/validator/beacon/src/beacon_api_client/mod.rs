@@ -1,16 +1,22 @@
 pub mod event;
+pub mod fallback;
 pub mod http_client;
+pub mod retry;
 
 use std::{pin::Pin, str::FromStr, time::Duration};
 
 use alloy_primitives::{B256, hex};
 use anyhow::anyhow;
-use event::{BeaconEvent, EventTopic};
+use event::{BeaconEvent, EventStreamItem, EventTopic};
 use eventsource_client::{Client, ClientBuilder, SSE};
+use fallback::BeaconNodeFallback;
 use futures::{Stream, StreamExt};
-use http_client::{ClientWithBaseUrl, ContentType};
+use http_client::{ContentType, endpoint_label};
 use ream_api_types_beacon::{
-    block::{BroadcastValidation, FullBlockData, ProduceBlockData, ProduceBlockResponse},
+    block::{
+        BroadcastValidation, FullBlockData, ProduceBlockData, ProduceBlockResponse,
+        SignedBlockContents,
+    },
     committee::BeaconCommitteeSubscription,
     duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
     error::ValidatorError,
@@ -27,47 +33,80 @@ use ream_api_types_common::id::ID;
 use ream_bls::BLSSignature;
 use ream_consensus_beacon::{
     attestation::Attestation,
-    electra::{
-        beacon_block::SignedBeaconBlock,
-        blinded_beacon_block::{BlindedBeaconBlock, SignedBlindedBeaconBlock},
-    },
+    electra::blinded_beacon_block::{BlindedBeaconBlock, SignedBlindedBeaconBlock},
     genesis::Genesis,
     single_attestation::SingleAttestation,
     voluntary_exit::SignedVoluntaryExit,
 };
-use ream_consensus_misc::{attestation_data::AttestationData, fork::Fork};
+use ream_consensus_misc::{
+    attestation_data::AttestationData,
+    fork::Fork,
+    slot_epoch::{Epoch, Slot},
+};
 use ream_network_spec::networks::BeaconNetworkSpec;
-use reqwest::{Url, header::HeaderMap};
+use reqwest::{Request, RequestBuilder, Url, header::HeaderMap};
+use retry::RetryConfig;
+use serde::Serialize;
 use serde_json::json;
 use ssz::{Decode, Encode};
 use tracing::{error, info};
 
 use crate::{
     aggregate_and_proof::SignedAggregateAndProof,
+    builder::validator_registration::SignedValidatorRegistrationV1,
     contribution_and_proof::{SignedContributionAndProof, SyncCommitteeContribution},
 };
 
-#[derive(Clone)]
+/// How much of the body of a failed response is kept in its error.
+const ERROR_BODY_SNIPPET_LENGTH: usize = 256;
+
 pub struct BeaconApiClient {
-    http_client: ClientWithBaseUrl,
+    http_client: BeaconNodeFallback,
 }
 
 impl BeaconApiClient {
-    pub fn new(beacon_api_endpoint: Url, request_timeout: Duration) -> anyhow::Result<Self> {
+    pub fn new(
+        beacon_api_endpoints: Vec<Url>,
+        request_timeout: Duration,
+        retry_config: RetryConfig,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
-            http_client: ClientWithBaseUrl::new(
-                beacon_api_endpoint,
+            http_client: BeaconNodeFallback::new(
+                beacon_api_endpoints,
                 request_timeout,
+                retry_config,
                 ContentType::Ssz,
             )?,
         })
     }
 
+    /// Refresh which beacon node is preferred when several endpoints are configured.
+    pub async fn update_endpoint_health(&self) {
+        self.http_client.update_health().await;
+    }
+
     pub fn get_events_stream(
         &self,
         topics: &[EventTopic],
         stream_tag: &'static str,
     ) -> anyhow::Result<Pin<Box<dyn Stream<Item = BeaconEvent> + Send>>> {
+        Ok(self
+            .get_event_stream_items(topics, stream_tag)?
+            .filter_map(|item| async move {
+                match item {
+                    EventStreamItem::Event(event) => Some(*event),
+                    _ => None,
+                }
+            })
+            .boxed())
+    }
+
+    /// Like [Self::get_events_stream], with the changes of the connection of the stream.
+    pub fn get_event_stream_items(
+        &self,
+        topics: &[EventTopic],
+        stream_tag: &'static str,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = EventStreamItem> + Send>>> {
         let endpoint = self.http_client.base_url().join(&format!(
             "/eth/v1/events?topics={}",
             topics
@@ -85,7 +124,7 @@ impl BeaconApiClient {
                     Ok(SSE::Event(event)) => event,
                     Ok(SSE::Connected(connection_details)) => {
                         info!("{stream_tag}: Connected to SSE stream: {connection_details:?}");
-                        return None;
+                        return Some(EventStreamItem::Connected);
                     }
                     Ok(SSE::Comment(comment)) => {
                         info!("{stream_tag}: Received comment: {comment:?}");
@@ -93,11 +132,11 @@ impl BeaconApiClient {
                     }
                     Err(err) => {
                         error!("{stream_tag}: Error receiving event: {err:?}");
-                        return None;
+                        return Some(EventStreamItem::Disconnected);
                     }
                 };
                 match BeaconEvent::try_from(event) {
-                    Ok(event) => Some(event),
+                    Ok(event) => Some(EventStreamItem::Event(Box::new(event))),
                     Err(err) => {
                         error!("{stream_tag}: Failed to decode event: {err:?}");
                         None
@@ -118,9 +157,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -128,21 +165,19 @@ impl BeaconApiClient {
 
     pub async fn get_block_root(
         &self,
-        state_id: ID,
+        block_id: ID,
     ) -> anyhow::Result<BeaconResponse<RootResponse>, ValidatorError> {
         let response = self
             .http_client
             .execute(
                 self.http_client
-                    .get(format!("/eth/v1/beacon/states/{state_id}/root"))?
+                    .get(format!("/eth/v1/beacon/blocks/{block_id}/root"))?
                     .build()?,
             )
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -161,9 +196,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -183,9 +216,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -195,23 +226,18 @@ impl BeaconApiClient {
         &self,
         sync_committee_request: Vec<SyncCommitteeRequestItem>,
     ) -> anyhow::Result<(), ValidatorError> {
+        let (ssz_request, json_request) = self.publish_requests(
+            "/eth/v1/beacon/pool/sync_committees",
+            None,
+            &sync_committee_request,
+        )?;
         let response = self
             .http_client
-            .execute(
-                self.http_client
-                    .post(
-                        "/eth/v1/beacon/pool/sync_committees".to_string(),
-                        ContentType::Json,
-                    )?
-                    .json(&sync_committee_request)
-                    .build()?,
-            )
+            .broadcast_ssz(ssz_request, json_request)
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(())
@@ -240,9 +266,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -265,9 +289,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -286,9 +308,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -296,7 +316,7 @@ impl BeaconApiClient {
 
     pub async fn get_proposer_duties(
         &self,
-        epoch: u64,
+        epoch: Epoch,
     ) -> anyhow::Result<DutiesResponse<ProposerDuty>, ValidatorError> {
         let response = self
             .http_client
@@ -308,9 +328,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -318,7 +336,7 @@ impl BeaconApiClient {
 
     pub async fn get_attester_duties(
         &self,
-        epoch: u64,
+        epoch: Epoch,
         validator_indices: &[u64],
     ) -> Result<DutiesResponse<AttesterDuty>, ValidatorError> {
         let response = self
@@ -340,9 +358,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -350,7 +366,7 @@ impl BeaconApiClient {
 
     pub async fn get_sync_committee_duties(
         &self,
-        epoch: u64,
+        epoch: Epoch,
         validator_indices: &[u64],
     ) -> Result<SyncCommitteeDutiesResponse<SyncCommitteeDuty>, ValidatorError> {
         let response = self
@@ -372,9 +388,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -398,9 +412,33 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    /// Register the fee recipients and gas limits of the validators with the builders, through
+    /// the beacon node.
+    pub async fn register_validator(
+        &self,
+        registrations: Vec<SignedValidatorRegistrationV1>,
+    ) -> anyhow::Result<(), ValidatorError> {
+        let response = self
+            .http_client
+            .broadcast(
+                self.http_client
+                    .post(
+                        "/eth/v1/validator/register_validator".to_string(),
+                        ContentType::Json,
+                    )?
+                    .json(&registrations)
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(handle_error_response(response).await);
         }
 
         Ok(())
@@ -408,7 +446,7 @@ impl BeaconApiClient {
 
     pub async fn get_sync_committee_contribution(
         &self,
-        slot: u64,
+        slot: Slot,
         subcommittee_index: u64,
         beacon_block_root: B256,
     ) -> Result<DataResponse<SyncCommitteeContribution>, ValidatorError> {
@@ -427,9 +465,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -439,23 +475,18 @@ impl BeaconApiClient {
         &self,
         signed_contribution_and_proofs: Vec<SignedContributionAndProof>,
     ) -> anyhow::Result<(), ValidatorError> {
+        let (ssz_request, json_request) = self.publish_requests(
+            "/eth/v1/validator/contribution_and_proofs",
+            None,
+            &signed_contribution_and_proofs,
+        )?;
         let response = self
             .http_client
-            .execute(
-                self.http_client
-                    .post(
-                        "/eth/v1/validator/contribution_and_proofs".to_string(),
-                        ContentType::Json,
-                    )?
-                    .json(&signed_contribution_and_proofs)
-                    .build()?,
-            )
+            .broadcast_ssz(ssz_request, json_request)
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(())
@@ -480,9 +511,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -492,24 +521,18 @@ impl BeaconApiClient {
         &self,
         single_attestation: Vec<SingleAttestation>,
     ) -> anyhow::Result<(), ValidatorError> {
+        let (ssz_request, json_request) = self.publish_requests(
+            "/eth/v2/beacon/pool/attestations",
+            None,
+            &single_attestation,
+        )?;
         let response = self
             .http_client
-            .execute(
-                self.http_client
-                    .post(
-                        "/eth/v2/beacon/pool/attestations".to_string(),
-                        ContentType::Json,
-                    )?
-                    .header(ETH_CONSENSUS_VERSION_HEADER, VERSION)
-                    .json(&single_attestation)
-                    .build()?,
-            )
+            .broadcast_ssz(ssz_request, json_request)
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(())
@@ -519,24 +542,18 @@ impl BeaconApiClient {
         &self,
         signed_aggregate_and_proofs: Vec<SignedAggregateAndProof>,
     ) -> anyhow::Result<(), ValidatorError> {
+        let (ssz_request, json_request) = self.publish_requests(
+            "/eth/v2/validator/aggregate_and_proofs",
+            None,
+            &signed_aggregate_and_proofs,
+        )?;
         let response = self
             .http_client
-            .execute(
-                self.http_client
-                    .post(
-                        "/eth/v2/validator/aggregate_and_proofs".to_string(),
-                        ContentType::Json,
-                    )?
-                    .header(ETH_CONSENSUS_VERSION_HEADER, VERSION)
-                    .json(&signed_aggregate_and_proofs)
-                    .build()?,
-            )
+            .broadcast_ssz(ssz_request, json_request)
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(())
@@ -563,9 +580,7 @@ impl BeaconApiClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(response.json().await?)
@@ -648,27 +663,20 @@ impl BeaconApiClient {
     pub async fn publish_block(
         &self,
         broadcast_validation: BroadcastValidation,
-        signed_beacon_block: SignedBeaconBlock,
+        signed_block_contents: SignedBlockContents,
     ) -> anyhow::Result<(), ValidatorError> {
+        let (ssz_request, json_request) = self.publish_requests(
+            "/eth/v2/beacon/blocks",
+            Some(broadcast_validation),
+            &signed_block_contents,
+        )?;
         let response = self
             .http_client
-            .execute(
-                self.http_client
-                    .post("/eth/v2/beacon/blocks".to_string(), ContentType::Ssz)?
-                    .query(&[(
-                        "broadcast_validation",
-                        serde_json::to_string(&broadcast_validation)?,
-                    )])
-                    .header(ETH_CONSENSUS_VERSION_HEADER, VERSION)
-                    .body(signed_beacon_block.as_ssz_bytes())
-                    .build()?,
-            )
+            .broadcast_ssz(ssz_request, json_request)
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(())
@@ -679,28 +687,18 @@ impl BeaconApiClient {
         broadcast_validation: BroadcastValidation,
         signed_blinded_beacon_block: SignedBlindedBeaconBlock,
     ) -> anyhow::Result<(), ValidatorError> {
+        let (ssz_request, json_request) = self.publish_requests(
+            "/eth/v2/beacon/blinded_blocks",
+            Some(broadcast_validation),
+            &signed_blinded_beacon_block,
+        )?;
         let response = self
             .http_client
-            .execute(
-                self.http_client
-                    .post(
-                        "/eth/v2/beacon/blinded_blocks".to_string(),
-                        ContentType::Ssz,
-                    )?
-                    .query(&[(
-                        "broadcast_validation",
-                        serde_json::to_string(&broadcast_validation)?,
-                    )])
-                    .header(ETH_CONSENSUS_VERSION_HEADER, VERSION)
-                    .body(signed_blinded_beacon_block.as_ssz_bytes())
-                    .build()?,
-            )
+            .broadcast_ssz(ssz_request, json_request)
             .await?;
 
         if !response.status().is_success() {
-            return Err(ValidatorError::RequestFailed {
-                status_code: response.status(),
-            });
+            return Err(handle_error_response(response).await);
         }
 
         Ok(())
@@ -729,6 +727,34 @@ impl BeaconApiClient {
 
         Ok(())
     }
+
+    /// Builds a publish request with an SSZ body, and the same request with a JSON body for the
+    /// beacon nodes which don't accept SSZ.
+    fn publish_requests<T: Encode + Serialize>(
+        &self,
+        path: &str,
+        broadcast_validation: Option<BroadcastValidation>,
+        body: &T,
+    ) -> Result<(Request, Request), ValidatorError> {
+        let build_request = |content_type: ContentType| -> Result<RequestBuilder, ValidatorError> {
+            let mut request_builder = self
+                .http_client
+                .post(path, content_type)?
+                .header(ETH_CONSENSUS_VERSION_HEADER, VERSION);
+            if let Some(broadcast_validation) = broadcast_validation {
+                request_builder =
+                    request_builder.query(&[("broadcast_validation", broadcast_validation)]);
+            }
+            Ok(request_builder)
+        };
+
+        Ok((
+            build_request(ContentType::Ssz)?
+                .body(body.as_ssz_bytes())
+                .build()?,
+            build_request(ContentType::Json)?.json(body).build()?,
+        ))
+    }
 }
 
 pub fn get_header_str<'a>(headers: &'a HeaderMap, key: &'a str) -> anyhow::Result<&'a str> {
@@ -748,20 +774,50 @@ where
         .map_err(|err| anyhow!("Failed to parse header '{key}': {err}"))
 }
 
+/// Builds the error of a failed response, with the message of the beacon node if the body is a
+/// Beacon API error and the start of the body otherwise.
 pub async fn handle_error_response(response: reqwest::Response) -> ValidatorError {
+    let endpoint = endpoint_label(response.url().path());
     let status_code = response.status();
-    match response.text().await {
-        Ok(response_body) => {
-            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&response_body)
-                && let Some(message) = json_value["message"].as_str()
-            {
-                return ValidatorError::RequestFailedWithMessage {
-                    status_code,
-                    message: message.to_string(),
-                };
-            }
-            ValidatorError::RequestFailed { status_code }
-        }
-        Err(_) => ValidatorError::RequestFailed { status_code },
+    let body = response.text().await.unwrap_or_default();
+    if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&body)
+        && let Some(message) = json_value["message"].as_str()
+    {
+        return ValidatorError::RequestFailedWithMessage {
+            endpoint,
+            status_code,
+            message: message.to_string(),
+        };
+    }
+
+    ValidatorError::RequestFailed {
+        endpoint,
+        status_code,
+        body: body_snippet(&body),
+    }
+}
+
+/// The first [ERROR_BODY_SNIPPET_LENGTH] characters of a response body.
+fn body_snippet(body: &str) -> String {
+    match body.char_indices().nth(ERROR_BODY_SNIPPET_LENGTH) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_snippet() {
+        assert_eq!(
+            body_snippet("Internal Server Error"),
+            "Internal Server Error"
+        );
+        assert_eq!(
+            body_snippet(&"é".repeat(ERROR_BODY_SNIPPET_LENGTH + 1)),
+            format!("{}...", "é".repeat(ERROR_BODY_SNIPPET_LENGTH))
+        );
     }
 }
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use reqwest::Response;
+
+/// How requests are retried when none of the beacon nodes could serve them.
+///
+/// Only server errors, timeouts and failed connections are retried, as a beacon node answering
+/// with a client error would answer the same way again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// How many times a request is sent again after the first attempt failed
+    pub max_retries: u32,
+    /// The wait before the first retry, which doubles with every retry after it
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// The wait before the retry numbered `retry`, counting from zero.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// Whether a request which ended with `result` may succeed when sent again.
+pub fn is_retryable(result: &Result<Response, reqwest::Error>) -> bool {
+    match result {
+        Ok(response) => response.status().is_server_error(),
+        Err(err) => err.is_timeout() || err.is_connect(),
+    }
+}
+
+/// The kind of failure of a request for the error metrics, or [None] if it succeeded.
+pub fn error_kind(result: &Result<Response, reqwest::Error>) -> Option<&'static str> {
+    match result {
+        Ok(response) if response.status().is_server_error() => Some("server_error"),
+        Ok(response) if response.status().is_client_error() => Some("client_error"),
+        Ok(_) => None,
+        Err(err) if err.is_timeout() => Some("timeout"),
+        Err(err) if err.is_connect() => Some("connect"),
+        Err(_) => Some("other"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let retry_config = RetryConfig {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1_000),
+        };
+
+        assert_eq!(
+            (0..6)
+                .map(|retry| retry_config.backoff(retry))
+                .collect::<Vec<_>>(),
+            [100, 200, 400, 800, 1_000, 1_000].map(Duration::from_millis)
+        );
+        assert_eq!(retry_config.backoff(u32::MAX), retry_config.max_backoff);
+    }
+}
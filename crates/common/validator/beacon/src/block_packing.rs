@@ -0,0 +1,235 @@
+//! Selection of the pooled operations which are worth the most to a block.
+//!
+//! Choosing attestations and slashings is a maximum coverage problem, as operations may reward
+//! the same participation flags or slash the same validators. The greedy algorithm used here
+//! picks the operation covering the most value which isn't covered yet, which is known to reach
+//! at least `1 - 1/e` of the optimum.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use alloy_primitives::B256;
+use ream_consensus_beacon::{
+    attestation::Attestation, attester_slashing::AttesterSlashing,
+    electra::beacon_state::BeaconState, proposer_slashing::ProposerSlashing,
+};
+use ream_consensus_misc::constants::beacon::{
+    MAX_ATTESTATIONS_ELECTRA, MAX_ATTESTER_SLASHINGS_ELECTRA, MAX_PROPOSER_SLASHINGS,
+    MIN_ATTESTATION_INCLUSION_DELAY, PARTICIPATION_FLAG_WEIGHTS, WEIGHT_DENOMINATOR,
+};
+use tree_hash::TreeHash;
+
+use crate::attestation::compute_on_chain_aggregate;
+
+/// Picks up to `limit` of the `candidates`, each covering elements of some weight, so that the
+/// covered elements weigh as much as possible. The elements in `covered` are worth nothing, and
+/// are extended with the elements of the picked candidates.
+///
+/// A candidate which covers nothing new is never picked, so fewer than `limit` may be returned.
+pub fn maximum_cover<T, E: Hash + Eq + Copy>(
+    mut candidates: Vec<(T, HashMap<E, u64>)>,
+    covered: &mut HashSet<E>,
+    limit: usize,
+) -> Vec<T> {
+    let mut selected = vec![];
+    while selected.len() < limit {
+        let best = candidates
+            .iter()
+            .enumerate()
+            .map(|(position, (_, elements))| {
+                let value = elements
+                    .iter()
+                    .filter(|(element, _)| !covered.contains(element))
+                    .map(|(_, weight)| weight)
+                    .sum::<u64>();
+                (position, value)
+            })
+            .max_by_key(|(_, value)| *value);
+        let Some((position, _)) = best.filter(|(_, value)| *value > 0) else {
+            break;
+        };
+
+        let (candidate, elements) = candidates.swap_remove(position);
+        covered.extend(elements.into_keys());
+        selected.push(candidate);
+    }
+    selected
+}
+
+/// Chooses the attestations of a block built on `state`, which must be processed up to the slot
+/// of the block, among `attestations` of single committees such as the ones of the operation
+/// pool.
+///
+/// The attestations sharing their data are aggregated on chain into one attestation of the block.
+/// Each of them is worth the rewards of the participation flags it sets for the first time.
+pub fn pack_attestations(
+    state: &BeaconState,
+    attestations: Vec<Attestation>,
+) -> anyhow::Result<Vec<Attestation>> {
+    let current_epoch = state.get_current_epoch();
+    let previous_epoch = state.get_previous_epoch();
+    let base_reward_per_increment = state.get_base_reward_per_increment();
+
+    let mut attestations_by_data: HashMap<B256, Vec<Attestation>> = HashMap::new();
+    for attestation in attestations {
+        let data = &attestation.data;
+        if (data.target.epoch != current_epoch && data.target.epoch != previous_epoch)
+            || data.slot + MIN_ATTESTATION_INCLUSION_DELAY > state.slot
+        {
+            continue;
+        }
+        attestations_by_data
+            .entry(data.tree_hash_root())
+            .or_default()
+            .push(attestation);
+    }
+
+    let mut candidates = vec![];
+    for attestations in attestations_by_data.into_values() {
+        let data = &attestations[0].data;
+        // The source of the attestations doesn't match the state, so they are invalid
+        let Ok(flag_indices) =
+            state.get_attestation_participation_flag_indices(data, state.slot - data.slot)
+        else {
+            continue;
+        };
+        let participation = if data.target.epoch == current_epoch {
+            &state.current_epoch_participation
+        } else {
+            &state.previous_epoch_participation
+        };
+
+        let mut rewards = HashMap::new();
+        for attestation in &attestations {
+            let Ok(attesting_indices) = state.get_attesting_indices(attestation) else {
+                continue;
+            };
+            for validator_index in attesting_indices {
+                let flags = participation[validator_index as usize];
+                let base_reward = state.get_base_reward(validator_index, base_reward_per_increment);
+                for &flag_index in &flag_indices {
+                    if !BeaconState::has_flag(flags, flag_index) {
+                        rewards.insert(
+                            (validator_index, flag_index),
+                            base_reward * PARTICIPATION_FLAG_WEIGHTS[flag_index as usize]
+                                / WEIGHT_DENOMINATOR,
+                        );
+                    }
+                }
+            }
+        }
+        candidates.push((attestations, rewards));
+    }
+
+    maximum_cover(candidates, &mut HashSet::new(), MAX_ATTESTATIONS_ELECTRA)
+        .into_iter()
+        .map(compute_on_chain_aggregate)
+        .collect()
+}
+
+/// Chooses the proposer and attester slashings of a block built on `state`, which are worth the
+/// effective balance of the validators they slash. A validator slashed by a proposer slashing
+/// adds nothing to the attester slashings.
+pub fn pack_slashings(
+    state: &BeaconState,
+    proposer_slashings: Vec<ProposerSlashing>,
+    attester_slashings: Vec<AttesterSlashing>,
+) -> (Vec<ProposerSlashing>, Vec<AttesterSlashing>) {
+    let current_epoch = state.get_current_epoch();
+    let slashable_balance = |validator_index: u64| {
+        state
+            .validators
+            .get(validator_index as usize)
+            .filter(|validator| validator.is_slashable_validator(current_epoch))
+            .map(|validator| validator.effective_balance)
+    };
+
+    let proposer_candidates = proposer_slashings
+        .into_iter()
+        .filter_map(|slashing| {
+            let proposer_index = slashing.signed_header_1.message.proposer_index;
+            let balance = slashable_balance(proposer_index)?;
+            Some((slashing, HashMap::from([(proposer_index, balance)])))
+        })
+        .collect();
+    let attester_candidates = attester_slashings
+        .into_iter()
+        .map(|slashing| {
+            let attesting_indices = slashing
+                .attestation_2
+                .attesting_indices
+                .iter()
+                .collect::<HashSet<_>>();
+            let balances = slashing
+                .attestation_1
+                .attesting_indices
+                .iter()
+                .filter(|validator_index| attesting_indices.contains(validator_index))
+                .filter_map(|&validator_index| {
+                    Some((validator_index, slashable_balance(validator_index)?))
+                })
+                .collect();
+            (slashing, balances)
+        })
+        .collect();
+
+    let mut slashed = HashSet::new();
+    let proposer_slashings =
+        maximum_cover(proposer_candidates, &mut slashed, MAX_PROPOSER_SLASHINGS);
+    let attester_slashings = maximum_cover(
+        attester_candidates,
+        &mut slashed,
+        MAX_ATTESTER_SLASHINGS_ELECTRA,
+    );
+    (proposer_slashings, attester_slashings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(name: &'static str, elements: &[(u64, u64)]) -> (&'static str, HashMap<u64, u64>) {
+        (name, elements.iter().copied().collect())
+    }
+
+    #[test]
+    fn test_maximum_cover_prefers_new_value() {
+        // Once b is picked, a and c add nothing, so d is picked although it's worth less alone
+        let candidates = vec![
+            candidate("a", &[(1, 10), (2, 10), (3, 10)]),
+            candidate("b", &[(1, 10), (2, 10), (3, 10), (4, 1)]),
+            candidate("c", &[(1, 10), (2, 10)]),
+            candidate("d", &[(5, 8), (6, 8)]),
+        ];
+
+        let selected = maximum_cover(candidates, &mut HashSet::new(), 2);
+        assert_eq!(selected, vec!["b", "d"]);
+    }
+
+    #[test]
+    fn test_maximum_cover_skips_covered_elements() {
+        let candidates = vec![
+            candidate("a", &[(1, 10)]),
+            candidate("b", &[(1, 10), (2, 1)]),
+            candidate("c", &[(3, 0)]),
+        ];
+
+        // Element 1 is already covered, e.g. by a proposer slashing of the same validator
+        let mut covered = HashSet::from([1]);
+        let selected = maximum_cover(candidates, &mut covered, 3);
+        assert_eq!(selected, vec!["b"]);
+        assert_eq!(covered, HashSet::from([1, 2]));
+    }
+
+    #[test]
+    fn test_maximum_cover_respects_limit() {
+        let candidates = (0..20)
+            .map(|index| (index, HashMap::from([(index, index + 1)])))
+            .collect::<Vec<_>>();
+
+        let selected = maximum_cover(candidates, &mut HashSet::new(), MAX_ATTESTATIONS_ELECTRA);
+        assert_eq!(selected, (12..20).rev().collect::<Vec<_>>());
+    }
+}
@@ -1,5 +1,6 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256, U256};
 use anyhow::ensure;
+use ream_bls::PublicKey;
 use ream_consensus_beacon::electra::beacon_state::BeaconState;
 
 use super::builder_bid::SignedBuilderBid;
@@ -21,3 +22,134 @@ pub fn process_bid(
     ensure!(verify_bid_signature(bid)?, "bid signature must be valid");
     Ok(())
 }
+
+/// What a bid of a relay must satisfy before its payload is built upon.
+#[derive(Debug, Clone)]
+pub struct BidRequirements {
+    pub parent_hash: B256,
+    /// The fee recipient the validator registered with the builders
+    pub fee_recipient: Address,
+    pub min_bid: U256,
+    /// The key of the relay which the bid came from, if the relay is pinned to one
+    pub relay_public_key: Option<PublicKey>,
+}
+
+/// Checks that `bid` builds on the expected parent, pays the registered fee recipient at least the
+/// minimum bid, and is signed by its builder under the builder domain.
+pub fn validate_bid(bid: &SignedBuilderBid, requirements: &BidRequirements) -> anyhow::Result<()> {
+    let message = &bid.message;
+    ensure!(
+        message.header.parent_hash == requirements.parent_hash,
+        "bid builds on {} instead of {}",
+        message.header.parent_hash,
+        requirements.parent_hash
+    );
+    ensure!(
+        message.header.fee_recipient == requirements.fee_recipient,
+        "bid pays {} instead of the registered fee recipient {}",
+        message.header.fee_recipient,
+        requirements.fee_recipient
+    );
+    ensure!(!message.value.is_zero(), "bid has no value");
+    ensure!(
+        message.value >= requirements.min_bid,
+        "bid value {} is below the minimum bid {}",
+        message.value,
+        requirements.min_bid
+    );
+    if let Some(relay_public_key) = &requirements.relay_public_key {
+        ensure!(
+            message.public_key == *relay_public_key,
+            "bid is signed by {:?} instead of the relay key {relay_public_key:?}",
+            message.public_key
+        );
+    }
+    ensure!(verify_bid_signature(bid)?, "bid signature is invalid");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_bls::{
+        PrivateKey,
+        traits::{PublicKeyDerivable, Signable},
+    };
+    use ream_consensus_misc::misc::compute_signing_root;
+    use ream_signing::domain::builder_domain;
+
+    use super::*;
+    use crate::builder::builder_bid::BuilderBid;
+
+    fn signed_bid(private_key: &PrivateKey, value: u64) -> SignedBuilderBid {
+        let mut message = BuilderBid {
+            header: Default::default(),
+            blob_kzg_commitments: Default::default(),
+            execution_requests: Default::default(),
+            value: U256::from(value),
+            public_key: private_key.public_key().unwrap(),
+        };
+        message.header.parent_hash = B256::repeat_byte(1);
+        message.header.fee_recipient = Address::repeat_byte(2);
+        let signing_root = compute_signing_root(message.clone(), builder_domain());
+        SignedBuilderBid {
+            signature: private_key.sign(signing_root.as_ref()).unwrap(),
+            message,
+        }
+    }
+
+    #[test]
+    fn test_validate_bid() {
+        let private_key = PrivateKey {
+            inner: B256::with_last_byte(1),
+        };
+        let requirements = BidRequirements {
+            parent_hash: B256::repeat_byte(1),
+            fee_recipient: Address::repeat_byte(2),
+            min_bid: U256::from(100),
+            relay_public_key: Some(private_key.public_key().unwrap()),
+        };
+        let bid = signed_bid(&private_key, 100);
+        validate_bid(&bid, &requirements).unwrap();
+
+        let mut tampered_bid = bid.clone();
+        tampered_bid.message.value += U256::from(1);
+        let other_private_key = PrivateKey {
+            inner: B256::with_last_byte(2),
+        };
+        for (invalid_bid, invalid_requirements) in [
+            // Builds on another parent
+            (
+                bid.clone(),
+                BidRequirements {
+                    parent_hash: B256::repeat_byte(3),
+                    ..requirements.clone()
+                },
+            ),
+            // Pays another fee recipient
+            (
+                bid.clone(),
+                BidRequirements {
+                    fee_recipient: Address::repeat_byte(3),
+                    ..requirements.clone()
+                },
+            ),
+            // Below the minimum bid
+            (signed_bid(&private_key, 99), requirements.clone()),
+            // Signed by a builder other than the relay
+            (signed_bid(&other_private_key, 100), requirements.clone()),
+            // Changed after it was signed
+            (tampered_bid, requirements.clone()),
+        ] {
+            assert!(validate_bid(&invalid_bid, &invalid_requirements).is_err());
+        }
+
+        // Without a pinned key any builder may sign, but never for nothing
+        let requirements = BidRequirements {
+            min_bid: U256::ZERO,
+            relay_public_key: None,
+            ..requirements
+        };
+        validate_bid(&signed_bid(&other_private_key, 100), &requirements).unwrap();
+        assert!(validate_bid(&signed_bid(&other_private_key, 0), &requirements).is_err());
+    }
+}
@@ -1,23 +1,32 @@
 use ream_consensus_beacon::{
     electra::execution_payload::ExecutionPayload,
-    polynomial_commitments::kzg_commitment::KZGCommitment,
+    execution_engine::rpc_types::get_blobs::Blob,
+    polynomial_commitments::{kzg_commitment::KZGCommitment, kzg_proof::KZGProof},
 };
+use ream_execution_engine::rpc_types::get_payload::BlobsBundleV1;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use ssz_types::{
-    VariableList,
-    typenum::{U96, U1024, U1048576},
-};
-use tree_hash_derive::TreeHash;
+use ssz_types::{VariableList, typenum::U4096};
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
+/// The blobs of a payload, with the commitment and proof of each blob at the same index.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct BlobsBundle {
-    pub blobs: VariableList<KZGCommitment, U1048576>,
-    pub commitments: VariableList<VariableList<u8, U96>, U1024>,
-    pub proofs: VariableList<VariableList<u8, U96>, U1024>,
+    pub commitments: VariableList<KZGCommitment, U4096>,
+    pub proofs: VariableList<KZGProof, U4096>,
+    pub blobs: VariableList<Blob, U4096>,
+}
+
+impl From<BlobsBundleV1> for BlobsBundle {
+    fn from(blobs_bundle: BlobsBundleV1) -> Self {
+        Self {
+            commitments: blobs_bundle.commitments,
+            proofs: blobs_bundle.proofs,
+            blobs: blobs_bundle.blobs,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct ExecutionPayloadAndBlobsBundle {
     pub execution_payload: ExecutionPayload,
     pub blobs_bundle: BlobsBundle,
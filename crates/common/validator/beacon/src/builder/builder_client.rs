@@ -1,15 +1,23 @@
-use std::time::Duration;
+use std::{collections::HashMap, str::FromStr, time::Duration};
 
-use alloy_primitives::B256;
-use anyhow::{Ok, anyhow};
+use alloy_primitives::{B256, U256};
+use anyhow::{anyhow, bail, ensure};
+use futures::future::{join_all, select_ok};
+use parking_lot::RwLock;
 use ream_api_types_beacon::responses::{ETH_CONSENSUS_VERSION_HEADER, VERSION};
 use ream_bls::PublicKey;
 use ream_consensus_beacon::electra::blinded_beacon_block::SignedBlindedBeaconBlock;
+use ream_execution_engine::ExecutionEngine;
 use reqwest::StatusCode;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
 use url::Url;
 
 use super::{
-    blobs::ExecutionPayloadAndBlobsBundle, builder_bid::SignedBuilderBid,
+    bid::{BidRequirements, validate_bid},
+    blobs::ExecutionPayloadAndBlobsBundle,
+    builder_bid::SignedBuilderBid,
+    payload_cache::PayloadCache,
     validator_registration::SignedValidatorRegistrationV1,
 };
 use crate::beacon_api_client::http_client::{ClientWithBaseUrl, ContentType};
@@ -17,46 +25,79 @@ use crate::beacon_api_client::http_client::{ClientWithBaseUrl, ContentType};
 #[derive(Debug, Clone)]
 pub struct BuilderConfig {
     pub builder_enabled: bool,
-    pub mev_relay_url: Url,
+    /// The relays which bids are requested from. A relay whose URL names a public key as its
+    /// user, as in `https://0x...@relay.example`, is only trusted with bids signed by that key.
+    pub mev_relay_urls: Vec<Url>,
+    /// Bids worth less than this many wei are ignored
+    pub min_bid: U256,
 }
 
-pub struct BuilderClient {
+#[derive(Deserialize)]
+struct VersionedBid {
+    data: SignedBuilderBid,
+}
+
+#[derive(Deserialize)]
+struct VersionedPayloadAndBlobsBundle {
+    data: ExecutionPayloadAndBlobsBundle,
+}
+
+/// A relay of the builder client.
+struct Relay {
     client: ClientWithBaseUrl,
+    /// The key the bids of the relay must be signed with, if its URL names one
+    public_key: Option<PublicKey>,
 }
 
-impl BuilderClient {
-    pub fn new(
-        config: BuilderConfig,
+impl Relay {
+    fn new(
+        mut url: Url,
         request_timeout: Duration,
         content_type: ContentType,
     ) -> anyhow::Result<Self> {
+        let public_key = match url.username() {
+            "" => None,
+            public_key => Some(
+                PublicKey::from_str(public_key)
+                    .map_err(|err| anyhow!("Invalid public key of relay {url}: {err:?}"))?,
+            ),
+        };
+        // The key only pins the relay, it isn't sent as credentials
+        url.set_username("")
+            .map_err(|_| anyhow!("Invalid relay URL {url}"))?;
         Ok(Self {
-            client: ClientWithBaseUrl::new(config.mev_relay_url, request_timeout, content_type)?,
+            client: ClientWithBaseUrl::new(url, request_timeout, content_type)?,
+            public_key,
         })
     }
 
-    /// Get an execution payload header.
-    pub async fn get_builder_header(
+    fn name(&self) -> &str {
+        self.client.base_url().host_str().unwrap_or_default()
+    }
+
+    async fn get_header(
         &self,
+        slot: u64,
         parent_hash: B256,
         public_key: &PublicKey,
-        slot: u64,
-    ) -> anyhow::Result<SignedBuilderBid> {
-        Ok(self
+    ) -> anyhow::Result<Option<SignedBuilderBid>> {
+        let response = self
             .client
             .get(format!(
                 "/eth/v1/builder/header/{slot}/{parent_hash:?}/{public_key:?}"
             ))?
             .send()
-            .await?
-            .json::<SignedBuilderBid>()
-            .await?)
+            .await?;
+        match response.status() {
+            StatusCode::OK => Ok(Some(response.json::<VersionedBid>().await?.data)),
+            StatusCode::NO_CONTENT => Ok(None),
+            status => bail!("failed to get builder header: {status:?}"),
+        }
     }
 
-    /// Submit a signed blinded block and get unblinded execution payload.
-    pub async fn get_blinded_blocks(
+    async fn submit_blinded_block(
         &self,
-        signed_blinded_block: SignedBlindedBeaconBlock,
+        signed_blinded_block: &SignedBlindedBeaconBlock,
     ) -> anyhow::Result<ExecutionPayloadAndBlobsBundle> {
         let response = self
             .client
@@ -65,38 +106,30 @@ impl BuilderClient {
                 ContentType::Json,
             )?
             .header(ETH_CONSENSUS_VERSION_HEADER, VERSION)
-            .json(&signed_blinded_block)
+            .json(signed_blinded_block)
             .send()
             .await?;
 
-        Ok(response.json::<ExecutionPayloadAndBlobsBundle>().await?)
-    }
-
-    /// Check if builder is healthy.
-    pub async fn get_builder_status(&self) -> anyhow::Result<()> {
-        let response = self.client.get("/eth/v1/builder/status")?.send().await?;
         match response.status() {
-            StatusCode::OK => Ok(()),
-            StatusCode::INTERNAL_SERVER_ERROR => {
-                Err(anyhow!("internal error: builder internal error"))
-            }
-            status => Err(anyhow!("failed to get builder status: {status:?}")),
+            StatusCode::OK => Ok(response
+                .json::<VersionedPayloadAndBlobsBundle>()
+                .await?
+                .data),
+            status => Err(anyhow!(
+                "failed to get unblinded execution payload from relay {}: {status:?}",
+                self.name()
+            )),
         }
     }
 
-    /// Registers a validator's preferred fee recipient and gas limit.
-    pub async fn resgister_validator(
+    async fn register_validators(
         &self,
-        signed_registration: SignedValidatorRegistrationV1,
+        signed_registrations: &[SignedValidatorRegistrationV1],
     ) -> anyhow::Result<()> {
         let response = self
             .client
-            .post(
-                "/eth/v1/builder/register_validator".to_string(),
-                ContentType::Json,
-            )?
-            .header(ETH_CONSENSUS_VERSION_HEADER, VERSION)
-            .json(&signed_registration)
+            .post("/eth/v1/builder/validators".to_string(), ContentType::Json)?
+            .json(signed_registrations)
             .send()
             .await?;
 
@@ -108,4 +141,337 @@ impl BuilderClient {
             status => Err(anyhow!("internal error: {status:?}")),
         }
     }
+
+    async fn get_status(&self) -> anyhow::Result<()> {
+        let response = self.client.get("/eth/v1/builder/status")?.send().await?;
+        match response.status() {
+            StatusCode::OK => Ok(()),
+            StatusCode::INTERNAL_SERVER_ERROR => {
+                Err(anyhow!("internal error: builder internal error"))
+            }
+            status => Err(anyhow!("failed to get builder status: {status:?}")),
+        }
+    }
+}
+
+pub struct BuilderClient {
+    relays: Vec<Relay>,
+    /// Whether every relay answered its last status check, all relays are assumed healthy until
+    /// checked
+    health: RwLock<Vec<bool>>,
+    min_bid: U256,
+    /// The latest registration of every validator which a relay accepted
+    registrations: RwLock<HashMap<PublicKey, SignedValidatorRegistrationV1>>,
+    payload_cache: PayloadCache,
+}
+
+impl BuilderClient {
+    pub fn new(
+        config: BuilderConfig,
+        request_timeout: Duration,
+        content_type: ContentType,
+    ) -> anyhow::Result<Self> {
+        ensure!(
+            !config.mev_relay_urls.is_empty(),
+            "No MEV relay to connect to"
+        );
+        let relays = config
+            .mev_relay_urls
+            .into_iter()
+            .map(|url| Relay::new(url, request_timeout, content_type.clone()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            health: RwLock::new(vec![true; relays.len()]),
+            relays,
+            min_bid: config.min_bid,
+            registrations: RwLock::new(HashMap::new()),
+            payload_cache: PayloadCache::default(),
+        })
+    }
+
+    /// The bids and local payloads which the blinded blocks of upcoming proposals are matched to.
+    pub fn payload_cache(&self) -> &PayloadCache {
+        &self.payload_cache
+    }
+
+    /// Get the most valuable bid of the healthy relays for the block of `slot` built on
+    /// `parent_hash`. The bids which don't build on the parent, don't pay the fee recipient which
+    /// the validator registered, are worth less than the minimum bid or aren't signed by their
+    /// builder are dropped, and [None] is returned if no bid is left. The chosen bid is tracked in
+    /// the payload cache, so the block built from it is submitted to its relay.
+    pub async fn get_builder_header(
+        &self,
+        parent_hash: B256,
+        public_key: &PublicKey,
+        slot: u64,
+    ) -> Option<SignedBuilderBid> {
+        let Some(registration) = self.validator_registration(public_key) else {
+            debug!(
+                "Validator {public_key:?} isn't registered with the relays, not asking for bids"
+            );
+            return None;
+        };
+
+        let health = self.health.read().clone();
+        let relays = self
+            .relays
+            .iter()
+            .zip(health)
+            .filter_map(|(relay, healthy)| healthy.then_some(relay));
+        let bids = join_all(relays.map(|relay| async move {
+            (relay, relay.get_header(slot, parent_hash, public_key).await)
+        }))
+        .await;
+
+        let (relay, bid) = bids
+            .into_iter()
+            .filter_map(|(relay, bid)| match bid {
+                Ok(Some(bid)) => {
+                    let requirements = BidRequirements {
+                        parent_hash,
+                        fee_recipient: registration.message.fee_recipient,
+                        min_bid: self.min_bid,
+                        relay_public_key: relay.public_key.clone(),
+                    };
+                    match validate_bid(&bid, &requirements) {
+                        Ok(()) => Some((relay, bid)),
+                        Err(err) => {
+                            warn!(
+                                "Ignoring bid of relay {} for slot {slot}: {err}",
+                                relay.name()
+                            );
+                            None
+                        }
+                    }
+                }
+                Ok(None) => None,
+                Err(err) => {
+                    warn!("Failed to get a bid of relay {}: {err:?}", relay.name());
+                    None
+                }
+            })
+            .max_by_key(|(_, bid)| bid.message.value)?;
+        self.payload_cache
+            .insert_bid(slot, relay.client.base_url().clone(), &bid);
+        Some(bid)
+    }
+
+    /// Submit a signed blinded block and get unblinded execution payload. The block is sent to
+    /// every relay, as any relay which knows the payload can reveal it.
+    pub async fn get_blinded_blocks(
+        &self,
+        signed_blinded_block: SignedBlindedBeaconBlock,
+    ) -> anyhow::Result<ExecutionPayloadAndBlobsBundle> {
+        let requests = self
+            .relays
+            .iter()
+            .map(|relay| Box::pin(relay.submit_blinded_block(&signed_blinded_block)));
+        Ok(select_ok(requests).await?.0)
+    }
+
+    /// Reveal the execution payload which `signed_blinded_block` commits to, together with the
+    /// blobs of the payload.
+    ///
+    /// The block is submitted to the relay whose bid it was built from, and then to every relay.
+    /// If no relay reveals the payload, the local payload of the slot is used instead, which is
+    /// only accepted if it is the payload the proposer signed the header of.
+    pub async fn reveal_payload(
+        &self,
+        signed_blinded_block: &SignedBlindedBeaconBlock,
+        execution_engine: Option<&ExecutionEngine>,
+    ) -> anyhow::Result<ExecutionPayloadAndBlobsBundle> {
+        let slot = signed_blinded_block.message.slot.as_u64();
+        let block_hash = signed_blinded_block
+            .message
+            .body
+            .execution_payload_header
+            .block_hash;
+        let relay_error = match self
+            .reveal_payload_from_relays(signed_blinded_block, slot, block_hash)
+            .await
+        {
+            Ok(payload_and_blobs) => return Ok(payload_and_blobs),
+            Err(err) => err,
+        };
+
+        let (Some(local_payload), Some(execution_engine)) =
+            (self.payload_cache.local_payload(slot), execution_engine)
+        else {
+            return Err(relay_error);
+        };
+        warn!(
+            "No relay revealed payload {block_hash} of slot {slot}, trying the local payload: {relay_error:?}"
+        );
+        let built_payload = execution_engine
+            .get_payload(
+                local_payload.payload_id,
+                local_payload.parent_beacon_block_root,
+            )
+            .await?;
+        ensure!(
+            built_payload.execution_payload.block_hash == block_hash,
+            "The block of slot {slot} commits to payload {block_hash} instead of the local payload {}, and no relay revealed it: {relay_error:?}",
+            built_payload.execution_payload.block_hash
+        );
+        Ok(ExecutionPayloadAndBlobsBundle {
+            execution_payload: built_payload.execution_payload,
+            blobs_bundle: built_payload.blobs_bundle.into(),
+        })
+    }
+
+    async fn reveal_payload_from_relays(
+        &self,
+        signed_blinded_block: &SignedBlindedBeaconBlock,
+        slot: u64,
+        block_hash: B256,
+    ) -> anyhow::Result<ExecutionPayloadAndBlobsBundle> {
+        if let Some(bid) = self.payload_cache.bid(slot, block_hash)
+            && let Some(relay) = self
+                .relays
+                .iter()
+                .find(|relay| *relay.client.base_url() == bid.relay)
+        {
+            match relay.submit_blinded_block(signed_blinded_block).await {
+                Ok(payload_and_blobs) => return Ok(payload_and_blobs),
+                Err(err) => warn!(
+                    "Relay {} failed to reveal the payload of its bid for slot {slot}: {err:?}",
+                    relay.name()
+                ),
+            }
+        }
+        self.get_blinded_blocks(signed_blinded_block.clone()).await
+    }
+
+    /// Check the status of every relay, so bids are only requested from the healthy ones.
+    /// Returns the number of healthy relays.
+    pub async fn update_relay_status(&self) -> usize {
+        let health = join_all(self.relays.iter().map(|relay| async move {
+            relay
+                .get_status()
+                .await
+                .inspect_err(|err| warn!("Relay {} is unhealthy: {err:?}", relay.name()))
+                .is_ok()
+        }))
+        .await;
+
+        let mut current_health = self.health.write();
+        for (relay, (current, new)) in self
+            .relays
+            .iter()
+            .zip(current_health.iter().zip(health.iter()))
+        {
+            if current != new {
+                info!(
+                    "Relay {} is {}",
+                    relay.name(),
+                    if *new { "healthy again" } else { "unhealthy" }
+                );
+            }
+        }
+        *current_health = health;
+        current_health.iter().filter(|healthy| **healthy).count()
+    }
+
+    /// Registers the preferred fee recipients and gas limits of validators with every relay.
+    /// Registrations which the relays were sent before are skipped, the others are remembered
+    /// once a relay accepts them.
+    pub async fn register_validators(
+        &self,
+        signed_registrations: Vec<SignedValidatorRegistrationV1>,
+    ) -> anyhow::Result<()> {
+        let signed_registrations = {
+            let registrations = self.registrations.read();
+            signed_registrations
+                .into_iter()
+                .filter(|registration| {
+                    registrations
+                        .get(&registration.message.public_key)
+                        .is_none_or(|known| {
+                            known.message != registration.message
+                                && known.message.timestamp < registration.message.timestamp
+                        })
+                })
+                .collect::<Vec<_>>()
+        };
+        if signed_registrations.is_empty() {
+            return Ok(());
+        }
+
+        let results = join_all(
+            self.relays
+                .iter()
+                .map(|relay| relay.register_validators(&signed_registrations)),
+        )
+        .await;
+
+        let mut errors = vec![];
+        for (relay, result) in self.relays.iter().zip(results) {
+            if let Err(err) = result {
+                errors.push(format!("{}: {err}", relay.name()));
+            }
+        }
+        ensure!(
+            errors.len() < self.relays.len(),
+            "No relay accepted the registrations: {}",
+            errors.join(", ")
+        );
+        if !errors.is_empty() {
+            warn!(
+                "Some relays rejected the registrations: {}",
+                errors.join(", ")
+            );
+        }
+
+        self.registrations.write().extend(
+            signed_registrations
+                .into_iter()
+                .map(|registration| (registration.message.public_key.clone(), registration)),
+        );
+        Ok(())
+    }
+
+    /// The latest registration of a validator which a relay accepted.
+    pub fn validator_registration(
+        &self,
+        public_key: &PublicKey,
+    ) -> Option<SignedValidatorRegistrationV1> {
+        self.registrations.read().get(public_key).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_public_key_is_taken_from_url() {
+        let public_key = format!("0x{}", "ab".repeat(48));
+        let relay = Relay::new(
+            format!("https://{public_key}@relay.example/")
+                .parse()
+                .unwrap(),
+            Duration::from_secs(1),
+            ContentType::Json,
+        )
+        .unwrap();
+        assert_eq!(relay.public_key, Some(public_key.parse().unwrap()));
+        assert_eq!(relay.client.base_url().as_str(), "https://relay.example/");
+
+        let relay = Relay::new(
+            "https://relay.example".parse().unwrap(),
+            Duration::from_secs(1),
+            ContentType::Json,
+        )
+        .unwrap();
+        assert_eq!(relay.public_key, None);
+
+        assert!(
+            Relay::new(
+                "https://0x1234@relay.example".parse().unwrap(),
+                Duration::from_secs(1),
+                ContentType::Json,
+            )
+            .is_err()
+        );
+    }
 }
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use alloy_primitives::{B64, B256, U256};
+use parking_lot::RwLock;
+use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
+use url::Url;
+
+use super::builder_bid::SignedBuilderBid;
+
+/// A bid accepted from a relay, whose payload only the relay can reveal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackedBid {
+    pub relay: Url,
+    pub block_hash: B256,
+    pub value: U256,
+}
+
+/// A payload which the local execution engine was asked to build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalPayload {
+    pub payload_id: B64,
+    pub parent_beacon_block_root: B256,
+}
+
+#[derive(Debug, Default)]
+struct SlotPayloads {
+    bids: Vec<TrackedBid>,
+    local_payload: Option<LocalPayload>,
+}
+
+/// The payloads which the blocks of upcoming proposals may commit to, so a signed blinded block
+/// can be matched to the relay which knows its payload, or be unblinded with the local payload.
+///
+/// Only the payloads of the latest epoch worth of slots are kept.
+#[derive(Debug, Default)]
+pub struct PayloadCache {
+    slots: RwLock<BTreeMap<u64, SlotPayloads>>,
+}
+
+impl PayloadCache {
+    pub fn insert_bid(&self, slot: u64, relay: Url, bid: &SignedBuilderBid) {
+        let mut slots = self.slots.write();
+        let bids = &mut slots.entry(slot).or_default().bids;
+        let block_hash = bid.message.header.block_hash;
+        // A relay sending the same payload again replaces the bid
+        bids.retain(|tracked_bid| tracked_bid.block_hash != block_hash);
+        bids.push(TrackedBid {
+            relay,
+            block_hash,
+            value: bid.message.value,
+        });
+        prune(&mut slots);
+    }
+
+    pub fn insert_local_payload(&self, slot: u64, payload_id: B64, parent_beacon_block_root: B256) {
+        let mut slots = self.slots.write();
+        slots.entry(slot).or_default().local_payload = Some(LocalPayload {
+            payload_id,
+            parent_beacon_block_root,
+        });
+        prune(&mut slots);
+    }
+
+    /// The bid of `slot` whose payload has `block_hash`.
+    pub fn bid(&self, slot: u64, block_hash: B256) -> Option<TrackedBid> {
+        self.slots
+            .read()
+            .get(&slot)?
+            .bids
+            .iter()
+            .find(|tracked_bid| tracked_bid.block_hash == block_hash)
+            .cloned()
+    }
+
+    pub fn local_payload(&self, slot: u64) -> Option<LocalPayload> {
+        self.slots.read().get(&slot)?.local_payload
+    }
+}
+
+fn prune(slots: &mut BTreeMap<u64, SlotPayloads>) {
+    if let Some(&latest_slot) = slots.keys().next_back() {
+        *slots = slots.split_off(&latest_slot.saturating_sub(SLOTS_PER_EPOCH - 1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::builder_bid::BuilderBid;
+
+    fn bid(block_hash: B256, value: u64) -> SignedBuilderBid {
+        let mut message = BuilderBid {
+            header: Default::default(),
+            blob_kzg_commitments: Default::default(),
+            execution_requests: Default::default(),
+            value: U256::from(value),
+            public_key: Default::default(),
+        };
+        message.header.block_hash = block_hash;
+        SignedBuilderBid {
+            message,
+            signature: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_payloads_are_matched_by_slot_and_block_hash() {
+        let cache = PayloadCache::default();
+        let relay: Url = "https://relay.example".parse().unwrap();
+        let other_relay: Url = "https://other-relay.example".parse().unwrap();
+        cache.insert_bid(10, relay.clone(), &bid(B256::repeat_byte(1), 1));
+        cache.insert_bid(10, other_relay.clone(), &bid(B256::repeat_byte(2), 2));
+        cache.insert_local_payload(10, B64::repeat_byte(3), B256::repeat_byte(4));
+
+        assert_eq!(
+            cache.bid(10, B256::repeat_byte(2)),
+            Some(TrackedBid {
+                relay: other_relay.clone(),
+                block_hash: B256::repeat_byte(2),
+                value: U256::from(2),
+            })
+        );
+        assert_eq!(cache.bid(10, B256::repeat_byte(5)), None);
+        assert_eq!(cache.bid(11, B256::repeat_byte(1)), None);
+        assert_eq!(
+            cache.local_payload(10),
+            Some(LocalPayload {
+                payload_id: B64::repeat_byte(3),
+                parent_beacon_block_root: B256::repeat_byte(4),
+            })
+        );
+        assert_eq!(cache.local_payload(11), None);
+
+        // The latest bid for a payload decides which relay reveals it
+        cache.insert_bid(10, relay.clone(), &bid(B256::repeat_byte(2), 3));
+        assert_eq!(
+            cache.bid(10, B256::repeat_byte(2)).map(|bid| bid.relay),
+            Some(relay)
+        );
+    }
+
+    #[test]
+    fn test_payloads_of_old_slots_are_pruned() {
+        let cache = PayloadCache::default();
+        cache.insert_local_payload(10, B64::repeat_byte(1), B256::ZERO);
+        cache.insert_local_payload(10 + SLOTS_PER_EPOCH - 1, B64::repeat_byte(2), B256::ZERO);
+        assert!(cache.local_payload(10).is_some());
+
+        cache.insert_local_payload(10 + SLOTS_PER_EPOCH, B64::repeat_byte(3), B256::ZERO);
+        assert_eq!(cache.local_payload(10), None);
+        assert!(cache.local_payload(10 + SLOTS_PER_EPOCH - 1).is_some());
+    }
+}
@@ -1,38 +1,204 @@
+use std::{collections::HashMap, sync::Arc};
+
 use alloy_primitives::Address;
-use ream_bls::{BLSSignature, PrivateKey, PublicKey, traits::Signable};
-use ream_consensus_misc::misc::{compute_domain, compute_signing_root};
+use ream_bls::{BLSSignature, PublicKey};
+use ream_consensus_misc::misc::compute_signing_root;
+use ream_signing::{
+    domain::{SignatureKind, builder_domain},
+    signer::Signer,
+};
 use serde::{Deserialize, Serialize};
-use tree_hash::TreeHash;
+use ssz_derive::{Decode, Encode};
 use tree_hash_derive::TreeHash;
 
-use super::DOMAIN_APPLICATION_BUILDER;
-
-#[derive(Debug, PartialEq, Eq, Clone, TreeHash, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, TreeHash, Serialize, Deserialize, Encode, Decode)]
 pub struct ValidatorRegistrationV1 {
     pub fee_recipient: Address,
     #[serde(with = "serde_utils::quoted_u64")]
     pub gas_limit: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     pub timestamp: u64,
+    #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
 }
 
 impl ValidatorRegistrationV1 {
-    pub fn create_signed_registration(
+    pub async fn create_signed_registration(
         &self,
-        private_key: &PrivateKey,
+        signer: &dyn Signer,
     ) -> anyhow::Result<SignedValidatorRegistrationV1> {
-        let domain = compute_domain(DOMAIN_APPLICATION_BUILDER, None, None);
-        let signature = compute_signing_root(self.tree_hash_root(), domain);
+        let signing_root = compute_signing_root(self, builder_domain());
         Ok(SignedValidatorRegistrationV1 {
             message: self.clone(),
-            signature: private_key.sign(signature.as_ref())?,
+            signature: signer
+                .sign_root(SignatureKind::ValidatorRegistration, signing_root)
+                .await?,
         })
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct SignedValidatorRegistrationV1 {
     pub message: ValidatorRegistrationV1,
     pub signature: BLSSignature,
 }
+
+/// How the local validators propose blocks, which they register with the builders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposerConfig {
+    pub fee_recipient: Address,
+    pub gas_limit: u64,
+    /// Whether the validators register with the builders to propose their blocks
+    pub enable_builder: bool,
+}
+
+/// The signed builder registrations of the local validators.
+///
+/// A registration is only signed again when the fee recipient or gas limit of its validator
+/// changes, as the relays ignore registrations which aren't newer than the one they know and
+/// resigning the same preferences every epoch would only cost signatures.
+#[derive(Debug, Default)]
+pub struct ValidatorRegistrations {
+    registrations: HashMap<PublicKey, SignedValidatorRegistrationV1>,
+    /// Whether registrations were signed since the registrations were last submitted
+    has_unsubmitted: bool,
+}
+
+impl ValidatorRegistrations {
+    /// Signs the registrations of the validators of `signers` which are new or whose proposer
+    /// config changed, and drops the registrations of every other validator.
+    pub async fn update(
+        &mut self,
+        signers: &[Arc<dyn Signer>],
+        proposer_config: &ProposerConfig,
+        timestamp: u64,
+    ) -> anyhow::Result<()> {
+        self.registrations.retain(|public_key, _| {
+            signers
+                .iter()
+                .any(|signer| signer.public_key() == public_key)
+        });
+
+        for signer in signers {
+            if let Some(registration) = self.registrations.get(signer.public_key())
+                && registration.message.fee_recipient == proposer_config.fee_recipient
+                && registration.message.gas_limit == proposer_config.gas_limit
+            {
+                continue;
+            }
+
+            let registration = ValidatorRegistrationV1 {
+                fee_recipient: proposer_config.fee_recipient,
+                gas_limit: proposer_config.gas_limit,
+                timestamp,
+                public_key: signer.public_key().clone(),
+            }
+            .create_signed_registration(signer.as_ref())
+            .await?;
+            self.registrations
+                .insert(signer.public_key().clone(), registration);
+            self.has_unsubmitted = true;
+        }
+
+        Ok(())
+    }
+
+    /// Whether registrations were signed which the builders don't know yet.
+    pub fn has_unsubmitted(&self) -> bool {
+        self.has_unsubmitted
+    }
+
+    pub fn registrations(&self) -> Vec<SignedValidatorRegistrationV1> {
+        self.registrations.values().cloned().collect()
+    }
+
+    pub fn mark_submitted(&mut self) {
+        self.has_unsubmitted = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::{PrivateKey, traits::PublicKeyDerivable};
+    use ream_keystore::keystore::Keystore;
+
+    use super::*;
+    use crate::builder::verify::verify_registration_signature;
+
+    fn keystore(index: u8) -> Arc<dyn Signer> {
+        Arc::new(Keystore {
+            public_key: PublicKey {
+                inner: vec![index; 48].into(),
+            },
+            private_key: PrivateKey {
+                inner: B256::with_last_byte(index),
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_signed_registration_verifies() {
+        let private_key = PrivateKey {
+            inner: B256::with_last_byte(1),
+        };
+        let keystore = Keystore {
+            public_key: private_key.public_key().unwrap(),
+            private_key,
+        };
+        let mut signed_registration = ValidatorRegistrationV1 {
+            fee_recipient: Address::repeat_byte(1),
+            gas_limit: 36_000_000,
+            timestamp: 100,
+            public_key: keystore.public_key.clone(),
+        }
+        .create_signed_registration(&keystore)
+        .await
+        .unwrap();
+        assert!(verify_registration_signature(&signed_registration).unwrap());
+
+        signed_registration.message.gas_limit += 1;
+        assert!(!verify_registration_signature(&signed_registration).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_registrations_are_only_signed_on_changes() {
+        let mut proposer_config = ProposerConfig {
+            fee_recipient: Address::repeat_byte(1),
+            gas_limit: 36_000_000,
+            enable_builder: true,
+        };
+        let keystores = vec![keystore(1), keystore(2)];
+        let mut registrations = ValidatorRegistrations::default();
+
+        registrations
+            .update(&keystores, &proposer_config, 100)
+            .await
+            .unwrap();
+        assert!(registrations.has_unsubmitted());
+        registrations.mark_submitted();
+
+        registrations
+            .update(&keystores, &proposer_config, 200)
+            .await
+            .unwrap();
+        assert!(!registrations.has_unsubmitted());
+        assert!(
+            registrations
+                .registrations()
+                .iter()
+                .all(|registration| registration.message.timestamp == 100)
+        );
+
+        proposer_config.gas_limit = 45_000_000;
+        registrations
+            .update(&keystores[..1], &proposer_config, 300)
+            .await
+            .unwrap();
+        assert!(registrations.has_unsubmitted());
+        let registrations = registrations.registrations();
+        assert_eq!(registrations.len(), 1);
+        assert_eq!(registrations[0].message.timestamp, 300);
+        assert_eq!(registrations[0].message.gas_limit, 45_000_000);
+    }
+}
@@ -1,7 +1,10 @@
 use ream_bls::traits::Verifiable;
-use ream_consensus_misc::misc::{compute_domain, compute_signing_root};
+use ream_consensus_misc::{
+    constants::beacon::DOMAIN_APPLICATION_BUILDER,
+    misc::{compute_domain, compute_signing_root},
+};
 
-use super::{DOMAIN_APPLICATION_BUILDER, builder_bid::SignedBuilderBid};
+use super::{builder_bid::SignedBuilderBid, validator_registration::SignedValidatorRegistrationV1};
 
 pub fn verify_bid_signature(signed_bid: &SignedBuilderBid) -> anyhow::Result<bool> {
     let domain = compute_domain(DOMAIN_APPLICATION_BUILDER, None, None);
@@ -11,3 +14,15 @@ pub fn verify_bid_signature(signed_bid: &SignedBuilderBid) -> anyhow::Result<boo
         .signature
         .verify(&signed_bid.message.public_key, signing_root.as_ref())?)
 }
+
+pub fn verify_registration_signature(
+    signed_registration: &SignedValidatorRegistrationV1,
+) -> anyhow::Result<bool> {
+    let domain = compute_domain(DOMAIN_APPLICATION_BUILDER, None, None);
+    let signing_root = compute_signing_root(signed_registration.message.clone(), domain);
+
+    Ok(signed_registration.signature.verify(
+        &signed_registration.message.public_key,
+        signing_root.as_ref(),
+    )?)
+}
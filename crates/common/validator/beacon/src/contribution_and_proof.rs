@@ -1,15 +1,12 @@
 use alloy_primitives::B256;
-use ream_bls::{BLSSignature, PrivateKey, traits::Signable};
-use ream_consensus_misc::misc::{compute_domain, compute_signing_root};
-use ream_network_spec::networks::beacon_network_spec;
+use ream_bls::BLSSignature;
+use ream_signing::{domain::SigningContext, signer::Signer};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{BitVector, typenum::U128};
 use tree_hash_derive::TreeHash;
 
-use crate::{
-    constants::DOMAIN_CONTRIBUTION_AND_PROOF, sync_committee::get_sync_committee_selection_proof,
-};
+use crate::sync_committee::get_sync_committee_selection_proof;
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct SyncCommitteeContribution {
@@ -36,31 +33,23 @@ pub struct SignedContributionAndProof {
     pub signature: BLSSignature,
 }
 
-pub fn get_contribution_and_proof(
+/// Creates the contribution and proof of `aggregator_index`, proving with a selection proof that
+/// it aggregates the subcommittee of `contribution`.
+pub async fn get_contribution_and_proof(
+    signer: &dyn Signer,
+    signing_context: &SigningContext,
     contribution: SyncCommitteeContribution,
     aggregator_index: u64,
-    private_key: &PrivateKey,
 ) -> anyhow::Result<ContributionAndProof> {
     Ok(ContributionAndProof {
         selection_proof: get_sync_committee_selection_proof(
+            signer,
+            signing_context,
             contribution.slot,
             contribution.subcommittee_index,
-            private_key,
-        )?,
+        )
+        .await?,
         aggregator_index,
         contribution,
     })
 }
-
-pub fn get_contribution_and_proof_signature(
-    contribution_and_proof: &ContributionAndProof,
-    private_key: &PrivateKey,
-) -> anyhow::Result<BLSSignature> {
-    let domain = compute_domain(
-        DOMAIN_CONTRIBUTION_AND_PROOF,
-        Some(beacon_network_spec().electra_fork_version),
-        None,
-    );
-    let signing_root = compute_signing_root(contribution_and_proof, domain);
-    Ok(private_key.sign(signing_root.as_ref())?)
-}
@@ -0,0 +1,252 @@
+use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Address, B256, Bytes, TxKind, U256, aliases::B32, hex};
+use alloy_rpc_types_eth::{BlockId, BlockNumberOrTag};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::anyhow;
+use ream_bls::{
+    PrivateKey, PublicKey,
+    traits::{PublicKeyDerivable, Signable},
+};
+use ream_consensus_misc::{
+    constants::beacon::{BLS_WITHDRAWAL_PREFIX, DOMAIN_DEPOSIT},
+    deposit_data::DepositData,
+    deposit_message::DepositMessage,
+    misc::{compute_domain, compute_signing_root},
+};
+use ream_execution_engine::ExecutionEngine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info;
+use tree_hash::TreeHash;
+
+/// The staking-deposit-cli version written to the deposit data, which the launchpad checks.
+pub const DEPOSIT_CLI_VERSION: &str = "2.7.0";
+
+/// The gas limit of a deposit transaction. A deposit costs about 60k gas.
+pub const DEPOSIT_GAS_LIMIT: u64 = 150_000;
+
+/// The priority fee paid on top of the base fee by deposit transactions, in wei.
+pub const DEPOSIT_MAX_PRIORITY_FEE_PER_GAS: u128 = 1_000_000_000;
+
+/// The selector of `deposit(bytes,bytes,bytes,bytes32)` of the deposit contract.
+const DEPOSIT_FUNCTION_SELECTOR: [u8; 4] = hex!("22895118");
+
+const GWEI_TO_WEI: u64 = 1_000_000_000;
+
+/// Withdrawal credentials with `prefix` which withdraw to `address`.
+pub fn address_withdrawal_credentials(prefix: &[u8], address: Address) -> B256 {
+    let mut withdrawal_credentials = B256::ZERO;
+    withdrawal_credentials[..1].copy_from_slice(prefix);
+    withdrawal_credentials[12..].copy_from_slice(address.as_slice());
+    withdrawal_credentials
+}
+
+/// BLS withdrawal credentials committing to `withdrawal_public_key`.
+pub fn bls_withdrawal_credentials(withdrawal_public_key: &PublicKey) -> B256 {
+    let mut withdrawal_credentials =
+        B256::from_slice(&Sha256::digest(withdrawal_public_key.to_bytes()));
+    withdrawal_credentials[..1].copy_from_slice(BLS_WITHDRAWAL_PREFIX);
+    withdrawal_credentials
+}
+
+/// Signs a deposit of `amount` gwei for the validator of `private_key` over [DOMAIN_DEPOSIT]. The
+/// deposit domain is computed from the genesis fork version, as deposits are valid across forks.
+pub fn sign_deposit_data(
+    private_key: &PrivateKey,
+    withdrawal_credentials: B256,
+    amount: u64,
+    genesis_fork_version: B32,
+) -> anyhow::Result<DepositData> {
+    let public_key = private_key
+        .public_key()
+        .map_err(|err| anyhow!("Failed to derive the public key: {err}"))?;
+    let deposit_message = DepositMessage {
+        public_key: public_key.clone(),
+        withdrawal_credentials,
+        amount,
+    };
+    let signature = private_key
+        .sign(
+            compute_signing_root(
+                deposit_message,
+                compute_domain(DOMAIN_DEPOSIT, Some(genesis_fork_version), None),
+            )
+            .as_slice(),
+        )
+        .map_err(|err| anyhow!("Failed to sign the deposit message: {err}"))?;
+
+    Ok(DepositData {
+        public_key,
+        withdrawal_credentials,
+        amount,
+        signature,
+    })
+}
+
+/// An entry of the deposit_data.json file written by the staking-deposit-cli, which the launchpad
+/// accepts. The byte fields are hex encoded without a 0x prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositDataJson {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: u64,
+    pub signature: String,
+    pub deposit_message_root: String,
+    pub deposit_data_root: String,
+    pub fork_version: String,
+    pub network_name: String,
+    pub deposit_cli_version: String,
+}
+
+impl DepositDataJson {
+    pub fn new(deposit_data: &DepositData, genesis_fork_version: B32, network_name: &str) -> Self {
+        let deposit_message = DepositMessage {
+            public_key: deposit_data.public_key.clone(),
+            withdrawal_credentials: deposit_data.withdrawal_credentials,
+            amount: deposit_data.amount,
+        };
+        Self {
+            pubkey: hex::encode(deposit_data.public_key.to_bytes()),
+            withdrawal_credentials: hex::encode(deposit_data.withdrawal_credentials),
+            amount: deposit_data.amount,
+            signature: hex::encode(deposit_data.signature.to_slice()),
+            deposit_message_root: hex::encode(deposit_message.tree_hash_root()),
+            deposit_data_root: hex::encode(deposit_data.tree_hash_root()),
+            fork_version: hex::encode(genesis_fork_version),
+            network_name: network_name.to_string(),
+            deposit_cli_version: DEPOSIT_CLI_VERSION.to_string(),
+        }
+    }
+}
+
+/// The ABI encoded call of `deposit(pubkey, withdrawal_credentials, signature, deposit_data_root)`.
+pub fn deposit_calldata(deposit_data: &DepositData) -> Bytes {
+    let dynamic_arguments = [
+        deposit_data.public_key.to_bytes(),
+        deposit_data.withdrawal_credentials.as_slice(),
+        deposit_data.signature.to_slice(),
+    ];
+    // The head holds an offset for each dynamic argument and the deposit data root.
+    let head_size = 32 * (dynamic_arguments.len() + 1);
+
+    let mut head = DEPOSIT_FUNCTION_SELECTOR.to_vec();
+    let mut tail = vec![];
+    for argument in dynamic_arguments {
+        head.extend(U256::from(head_size + tail.len()).to_be_bytes::<32>());
+        tail.extend(U256::from(argument.len()).to_be_bytes::<32>());
+        tail.extend(argument);
+        tail.resize(tail.len().next_multiple_of(32), 0);
+    }
+    head.extend(deposit_data.tree_hash_root());
+    head.extend(tail);
+    head.into()
+}
+
+/// Submits a transaction per deposit to the deposit contract through the execution client, paying
+/// from the account of `signer`. Returns the hashes of the transactions.
+pub async fn submit_deposits(
+    execution_engine: &ExecutionEngine,
+    signer: &PrivateKeySigner,
+    deposit_contract_address: Address,
+    deposits: &[DepositData],
+) -> anyhow::Result<Vec<B256>> {
+    let chain_id = execution_engine.eth_chain_id().await?.to::<u64>();
+    let nonce = execution_engine
+        .eth_get_transaction_count(signer.address(), BlockId::pending())
+        .await?
+        .to::<u64>();
+    let base_fee_per_gas = execution_engine
+        .eth_get_block_by_number(BlockNumberOrTag::Latest, false)
+        .await?
+        .header
+        .base_fee_per_gas
+        .ok_or_else(|| anyhow!("The latest block has no base fee"))?;
+
+    let mut transaction_hashes = vec![];
+    for (index, deposit_data) in deposits.iter().enumerate() {
+        let transaction = TxEip1559 {
+            chain_id,
+            nonce: nonce + index as u64,
+            gas_limit: DEPOSIT_GAS_LIMIT,
+            max_fee_per_gas: 2 * base_fee_per_gas as u128 + DEPOSIT_MAX_PRIORITY_FEE_PER_GAS,
+            max_priority_fee_per_gas: DEPOSIT_MAX_PRIORITY_FEE_PER_GAS,
+            to: TxKind::Call(deposit_contract_address),
+            value: U256::from(deposit_data.amount) * U256::from(GWEI_TO_WEI),
+            input: deposit_calldata(deposit_data),
+            ..Default::default()
+        };
+        let signature = signer.sign_hash_sync(&transaction.signature_hash())?;
+        let transaction_hash = execution_engine
+            .eth_send_raw_transaction(
+                TxEnvelope::from(transaction.into_signed(signature))
+                    .encoded_2718()
+                    .into(),
+            )
+            .await?;
+        info!(
+            "Submitted the deposit of validator 0x{} in transaction {transaction_hash}",
+            hex::encode(deposit_data.public_key.to_bytes())
+        );
+        transaction_hashes.push(transaction_hash);
+    }
+
+    Ok(transaction_hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_bls::{BLSSignature, PrivateKey};
+
+    use super::*;
+
+    #[test]
+    fn test_deposit_calldata() {
+        let deposit_data = DepositData {
+            public_key: PublicKey::default(),
+            withdrawal_credentials: B256::repeat_byte(1),
+            amount: 32_000_000_000,
+            signature: BLSSignature::infinity(),
+        };
+        let calldata = deposit_calldata(&deposit_data);
+
+        // The selector, 4 head words, and the padded public key, credentials and signature with
+        // their lengths.
+        assert_eq!(
+            calldata.len(),
+            4 + 32 * 4 + (32 + 64) + (32 + 32) + (32 + 96)
+        );
+        assert_eq!(calldata[..4], DEPOSIT_FUNCTION_SELECTOR);
+        assert_eq!(U256::from_be_slice(&calldata[4..36]), U256::from(0x80));
+        assert_eq!(U256::from_be_slice(&calldata[36..68]), U256::from(0xe0));
+        assert_eq!(U256::from_be_slice(&calldata[68..100]), U256::from(0x120));
+        assert_eq!(calldata[100..132], deposit_data.tree_hash_root()[..]);
+        assert_eq!(U256::from_be_slice(&calldata[132..164]), U256::from(48));
+    }
+
+    #[test]
+    fn test_sign_deposit_data() {
+        let private_key = PrivateKey {
+            inner: B256::with_last_byte(1),
+        };
+        let withdrawal_credentials =
+            address_withdrawal_credentials(&[1], Address::repeat_byte(0x42));
+        assert_eq!(withdrawal_credentials[0], 1);
+        assert_eq!(withdrawal_credentials[1..12], [0; 11]);
+
+        let deposit_data = sign_deposit_data(
+            &private_key,
+            withdrawal_credentials,
+            32_000_000_000,
+            B32::ZERO,
+        )
+        .unwrap();
+        let deposit_data_json = DepositDataJson::new(&deposit_data, B32::ZERO, "mainnet");
+        assert_eq!(deposit_data_json.fork_version, "00000000");
+        assert_eq!(
+            deposit_data_json.deposit_data_root,
+            hex::encode(deposit_data.tree_hash_root())
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use ream_api_types_beacon::{
+    duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
+    responses::DutiesResponse,
+};
+use ream_consensus_misc::slot_epoch::{Epoch, Slot};
+use ream_metrics::{VALIDATOR_DUTIES, inc_int_counter_vec_by};
+
+/// Duties of a single epoch along with the `dependent_root` the beacon node computed them against.
+///
+/// The duties are only valid while the block at the dependent slot stays canonical, so a different
+/// `dependent_root` in a later response means a re-org happened and the duties must be replaced.
+#[derive(Debug, Clone)]
+pub struct EpochDuties<T> {
+    pub dependent_root: B256,
+    pub duties: Vec<T>,
+}
+
+/// Outcome of inserting freshly fetched duties into the [DutiesCache].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DutiesUpdate {
+    /// No duties were known for the epoch before.
+    New,
+    /// The duties were fetched against the same dependent root and were left untouched.
+    Unchanged,
+    /// The dependent root changed, the previous duties were replaced.
+    Reorged { previous_dependent_root: B256 },
+}
+
+/// The kinds of duties of the local validators, which are counted in the metrics as they become
+/// due and once they are performed or missed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DutyKind {
+    Proposer,
+    Attester,
+    SyncCommittee,
+    Aggregator,
+    SyncAggregator,
+}
+
+impl DutyKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DutyKind::Proposer => "proposer",
+            DutyKind::Attester => "attester",
+            DutyKind::SyncCommittee => "sync_committee",
+            DutyKind::Aggregator => "aggregator",
+            DutyKind::SyncAggregator => "sync_aggregator",
+        }
+    }
+
+    /// Counts `count` duties of this kind which are due.
+    pub fn record_scheduled(&self, count: usize) {
+        inc_int_counter_vec_by(&VALIDATOR_DUTIES, count as u64, &[self.name(), "scheduled"]);
+    }
+
+    /// Counts `count` due duties of this kind as performed or missed, by the `result` of
+    /// performing them.
+    pub fn record_result<T>(&self, count: usize, result: &anyhow::Result<T>) {
+        let outcome = match result {
+            Ok(_) => "performed",
+            Err(_) => "missed",
+        };
+        inc_int_counter_vec_by(&VALIDATOR_DUTIES, count as u64, &[self.name(), outcome]);
+    }
+}
+
+/// Proposer and attester duties of the local validators, keyed by epoch, and their sync committee
+/// duties, keyed by sync committee period.
+#[derive(Debug, Default)]
+pub struct DutiesCache {
+    proposers: HashMap<Epoch, EpochDuties<ProposerDuty>>,
+    attesters: HashMap<Epoch, EpochDuties<AttesterDuty>>,
+    sync_committees: HashMap<u64, Vec<SyncCommitteeDuty>>,
+}
+
+impl DutiesCache {
+    pub fn insert_proposer_duties(
+        &mut self,
+        epoch: Epoch,
+        response: DutiesResponse<ProposerDuty>,
+    ) -> DutiesUpdate {
+        insert_epoch_duties(&mut self.proposers, epoch, response)
+    }
+
+    pub fn insert_attester_duties(
+        &mut self,
+        epoch: Epoch,
+        response: DutiesResponse<AttesterDuty>,
+    ) -> DutiesUpdate {
+        insert_epoch_duties(&mut self.attesters, epoch, response)
+    }
+
+    pub fn proposer_duties_at_slot(&self, slot: Slot) -> Vec<ProposerDuty> {
+        duties_at_slot(&self.proposers, slot, |duty| duty.slot)
+    }
+
+    pub fn attester_duties_at_slot(&self, slot: Slot) -> Vec<AttesterDuty> {
+        duties_at_slot(&self.attesters, slot, |duty| duty.slot)
+    }
+
+    pub fn insert_sync_committee_duties(
+        &mut self,
+        sync_committee_period: u64,
+        duties: Vec<SyncCommitteeDuty>,
+    ) {
+        self.sync_committees.insert(sync_committee_period, duties);
+    }
+
+    /// The sync committee duties for signing at `slot`.
+    ///
+    /// Messages signed at `slot` are included in the block of `slot + 1`, so the members of the
+    /// next sync committee already sign during the last slot of the current period.
+    pub fn sync_committee_duties_at_slot(&self, slot: Slot) -> &[SyncCommitteeDuty] {
+        self.sync_committees
+            .get(&(slot + 1).sync_committee_period())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Drop the duties of every epoch before the previous epoch, and of every sync committee
+    /// period before the current one.
+    pub fn prune(&mut self, current_epoch: Epoch) {
+        let oldest_epoch = current_epoch.saturating_sub(1);
+        self.proposers.retain(|epoch, _| *epoch >= oldest_epoch);
+        self.attesters.retain(|epoch, _| *epoch >= oldest_epoch);
+
+        let current_period = current_epoch.sync_committee_period();
+        self.sync_committees
+            .retain(|period, _| *period >= current_period);
+    }
+}
+
+fn insert_epoch_duties<T: ssz::Encode + ssz::Decode>(
+    cache: &mut HashMap<Epoch, EpochDuties<T>>,
+    epoch: Epoch,
+    response: DutiesResponse<T>,
+) -> DutiesUpdate {
+    let update = match cache.get(&epoch) {
+        None => DutiesUpdate::New,
+        Some(cached) if cached.dependent_root == response.dependent_root => {
+            return DutiesUpdate::Unchanged;
+        }
+        Some(cached) => DutiesUpdate::Reorged {
+            previous_dependent_root: cached.dependent_root,
+        },
+    };
+
+    cache.insert(
+        epoch,
+        EpochDuties {
+            dependent_root: response.dependent_root,
+            duties: response.data,
+        },
+    );
+    update
+}
+
+fn duties_at_slot<T: Clone>(
+    cache: &HashMap<Epoch, EpochDuties<T>>,
+    slot: Slot,
+    duty_slot: impl Fn(&T) -> Slot,
+) -> Vec<T> {
+    cache
+        .get(&slot.epoch())
+        .map(|epoch_duties| {
+            epoch_duties
+                .duties
+                .iter()
+                .filter(|duty| duty_slot(duty) == slot)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_bls::PublicKey;
+    use ream_consensus_misc::constants::beacon::EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+
+    use super::*;
+
+    fn proposer_duty(slot: u64, validator_index: u64) -> ProposerDuty {
+        ProposerDuty {
+            public_key: PublicKey::default(),
+            validator_index,
+            slot: Slot::new(slot),
+        }
+    }
+
+    #[test]
+    fn test_reorg_replaces_duties() {
+        let mut cache = DutiesCache::default();
+        let epoch = Epoch::new(1);
+        let slot = epoch.start_slot();
+
+        assert_eq!(
+            cache.insert_proposer_duties(
+                epoch,
+                DutiesResponse::new(B256::repeat_byte(1), vec![proposer_duty(slot.as_u64(), 7)])
+            ),
+            DutiesUpdate::New
+        );
+        assert_eq!(
+            cache.insert_proposer_duties(
+                epoch,
+                DutiesResponse::new(B256::repeat_byte(1), vec![proposer_duty(slot.as_u64(), 9)])
+            ),
+            DutiesUpdate::Unchanged
+        );
+        assert_eq!(cache.proposer_duties_at_slot(slot)[0].validator_index, 7);
+
+        assert_eq!(
+            cache.insert_proposer_duties(
+                epoch,
+                DutiesResponse::new(B256::repeat_byte(2), vec![proposer_duty(slot.as_u64(), 9)])
+            ),
+            DutiesUpdate::Reorged {
+                previous_dependent_root: B256::repeat_byte(1)
+            }
+        );
+        assert_eq!(cache.proposer_duties_at_slot(slot)[0].validator_index, 9);
+        assert!(cache.proposer_duties_at_slot(slot + 1).is_empty());
+    }
+
+    #[test]
+    fn test_prune_keeps_previous_epoch() {
+        let mut cache = DutiesCache::default();
+        for epoch in 0..4 {
+            cache.insert_proposer_duties(
+                Epoch::new(epoch),
+                DutiesResponse::new(B256::ZERO, vec![proposer_duty(0, 0)]),
+            );
+        }
+
+        cache.prune(Epoch::new(3));
+        assert_eq!(cache.proposers.len(), 2);
+        assert!(cache.proposers.contains_key(&Epoch::new(2)));
+        assert!(cache.proposers.contains_key(&Epoch::new(3)));
+    }
+
+    #[test]
+    fn test_next_sync_committee_signs_last_slot_of_period() {
+        let mut cache = DutiesCache::default();
+        let duty = SyncCommitteeDuty {
+            public_key: PublicKey::default(),
+            validator_index: 3,
+            validator_sync_committee_indices: vec![42],
+        };
+        cache.insert_sync_committee_duties(1, vec![duty]);
+
+        let first_slot_of_next_period = Epoch::new(EPOCHS_PER_SYNC_COMMITTEE_PERIOD).start_slot();
+        assert!(
+            cache
+                .sync_committee_duties_at_slot(first_slot_of_next_period - 2)
+                .is_empty()
+        );
+        assert_eq!(
+            cache.sync_committee_duties_at_slot(first_slot_of_next_period - 1)[0].validator_index,
+            3
+        );
+        assert_eq!(
+            cache
+                .sync_committee_duties_at_slot(first_slot_of_next_period)
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::{fs, path::PathBuf, str::FromStr};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use ream_bls::PublicKey;
+use ream_node::version::{APP_NAME, REAM_SHORT_COMMIT, REAM_VERSION};
+use tracing::warn;
+
+pub const GRAFFITI_BYTES_LENGTH: usize = 32;
+
+/// Parse a UTF-8 graffiti message into the 32 byte, zero-padded form used in blocks.
+pub fn parse_graffiti(graffiti: &str) -> anyhow::Result<B256> {
+    let bytes = graffiti.as_bytes();
+    ensure!(
+        bytes.len() <= GRAFFITI_BYTES_LENGTH,
+        "Graffiti must be at most {GRAFFITI_BYTES_LENGTH} bytes, got {}",
+        bytes.len()
+    );
+
+    let mut graffiti = [0u8; GRAFFITI_BYTES_LENGTH];
+    graffiti[..bytes.len()].copy_from_slice(bytes);
+    Ok(B256::from(graffiti))
+}
+
+/// The graffiti used when neither `--graffiti` nor `--graffiti-file` provide one,
+/// e.g. `ream/v0.1.0-892ad575`.
+pub fn default_graffiti() -> B256 {
+    let mut graffiti = format!("{APP_NAME}/{REAM_VERSION}-{REAM_SHORT_COMMIT}");
+    graffiti.truncate(GRAFFITI_BYTES_LENGTH);
+    parse_graffiti(&graffiti).unwrap_or_default()
+}
+
+/// Graffiti entries read from a graffiti file.
+///
+/// Each non-empty line is either `default: <graffiti>`, `<0x-prefixed public key>: <graffiti>`,
+/// or a bare `<graffiti>` which is treated as the default. Lines starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GraffitiFile {
+    pub default: Option<B256>,
+    pub validators: Vec<(PublicKey, B256)>,
+}
+
+impl GraffitiFile {
+    pub fn graffiti_for(&self, public_key: &PublicKey) -> Option<B256> {
+        self.validators
+            .iter()
+            .find(|(key, _)| key == public_key)
+            .map(|(_, graffiti)| *graffiti)
+            .or(self.default)
+    }
+}
+
+impl FromStr for GraffitiFile {
+    type Err = anyhow::Error;
+
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let mut graffiti_file = GraffitiFile::default();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once(':') {
+                Some((key, graffiti)) if key.trim().eq_ignore_ascii_case("default") => {
+                    graffiti_file.default = Some(parse_graffiti(graffiti.trim())?);
+                }
+                Some((key, graffiti)) if key.trim().starts_with("0x") => {
+                    let public_key = PublicKey::from_str(key.trim())
+                        .map_err(|err| anyhow!("Invalid public key {key}: {err:?}"))?;
+                    graffiti_file
+                        .validators
+                        .push((public_key, parse_graffiti(graffiti.trim())?));
+                }
+                _ => graffiti_file.default = Some(parse_graffiti(line)?),
+            }
+        }
+        Ok(graffiti_file)
+    }
+}
+
+/// Resolves the graffiti for each block proposal.
+///
+/// The graffiti file is re-read on every proposal so operators can rotate messages without
+/// restarting the validator client. Falls back to `--graffiti`, then to [default_graffiti].
+#[derive(Debug, Clone)]
+pub struct GraffitiProvider {
+    graffiti: Option<B256>,
+    graffiti_file: Option<PathBuf>,
+}
+
+impl GraffitiProvider {
+    pub fn new(graffiti: Option<B256>, graffiti_file: Option<PathBuf>) -> Self {
+        Self {
+            graffiti,
+            graffiti_file,
+        }
+    }
+
+    pub fn graffiti(&self, public_key: &PublicKey) -> B256 {
+        if let Some(path) = &self.graffiti_file {
+            match fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|contents| GraffitiFile::from_str(&contents))
+            {
+                Ok(graffiti_file) => {
+                    if let Some(graffiti) = graffiti_file.graffiti_for(public_key) {
+                        return graffiti;
+                    }
+                }
+                Err(err) => warn!("Failed to read graffiti file {}: {err:?}", path.display()),
+            }
+        }
+
+        self.graffiti.unwrap_or_else(default_graffiti)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_graffiti() {
+        let graffiti = parse_graffiti("ream").unwrap();
+        assert_eq!(&graffiti[..4], b"ream");
+        assert!(graffiti[4..].iter().all(|byte| *byte == 0));
+
+        assert!(parse_graffiti(&"a".repeat(GRAFFITI_BYTES_LENGTH)).is_ok());
+        assert!(parse_graffiti(&"a".repeat(GRAFFITI_BYTES_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_graffiti_file() {
+        let public_key_hex = format!("0x{}", "ab".repeat(48));
+        let public_key = PublicKey::from_str(&public_key_hex).unwrap();
+        let other_public_key = PublicKey::from_str(&format!("0x{}", "cd".repeat(48))).unwrap();
+
+        let graffiti_file = GraffitiFile::from_str(&format!(
+            "# comment\ndefault: hello ream\n{public_key_hex}: gm: validator\n"
+        ))
+        .unwrap();
+
+        assert_eq!(
+            graffiti_file.graffiti_for(&public_key),
+            Some(parse_graffiti("gm: validator").unwrap())
+        );
+        assert_eq!(
+            graffiti_file.graffiti_for(&other_public_key),
+            Some(parse_graffiti("hello ream").unwrap())
+        );
+
+        let bare_graffiti_file = GraffitiFile::from_str("just graffiti\n").unwrap();
+        assert_eq!(
+            bare_graffiti_file.graffiti_for(&public_key),
+            Some(parse_graffiti("just graffiti").unwrap())
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use alloy_primitives::B256;
+use alloy_rpc_types_beacon::events::{ChainReorgEvent, HeadEvent};
+use ream_consensus_misc::slot_epoch::Slot;
+use tracing::{info, warn};
+
+/// What the validator has to do after a change of the head.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HeadUpdate {
+    /// The duties may have been computed on a chain which is no longer canonical
+    pub refresh_duties: bool,
+    /// The block of this slot became the head, so its attestations can be made right away
+    pub block_slot: Option<Slot>,
+}
+
+/// Follows the head of the beacon node through its `head` and `chain_reorg` events.
+///
+/// The events tell about re-orgs as they happen, so the duties only need to be fetched again when
+/// the dependent roots change. While the events stream is down, changes of the head may be
+/// missed, so the validator polls the duties instead.
+#[derive(Debug, Default)]
+pub struct HeadTracker {
+    connected: bool,
+    /// The `previous_duty_dependent_root` and `current_duty_dependent_root` of the last head
+    dependent_roots: Option<(B256, B256)>,
+}
+
+impl HeadTracker {
+    /// Whether the events stream is connected, so the duties don't need to be polled.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    pub fn on_connected(&mut self) {
+        info!("Following the head through beacon node events");
+        self.connected = true;
+    }
+
+    pub fn on_disconnected(&mut self) {
+        if self.connected {
+            warn!("Lost the beacon node events stream, polling the duties until it reconnects");
+        }
+        self.connected = false;
+        // Re-orgs may happen while disconnected
+        self.dependent_roots = None;
+    }
+
+    pub fn on_head(&mut self, head: &HeadEvent) -> HeadUpdate {
+        let dependent_roots = (
+            head.previous_duty_dependent_root,
+            head.current_duty_dependent_root,
+        );
+        let refresh_duties = self
+            .dependent_roots
+            .replace(dependent_roots)
+            .is_some_and(|previous_roots| previous_roots != dependent_roots);
+
+        HeadUpdate {
+            refresh_duties,
+            block_slot: Some(Slot::new(head.slot)),
+        }
+    }
+
+    pub fn on_chain_reorg(&mut self, reorg: &ChainReorgEvent) -> HeadUpdate {
+        info!(
+            "Chain re-org at slot {} of depth {}, refreshing the duties",
+            reorg.slot, reorg.depth
+        );
+        HeadUpdate {
+            refresh_duties: true,
+            block_slot: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(slot: u64, previous_root: u8, current_root: u8) -> HeadEvent {
+        HeadEvent {
+            slot,
+            block: B256::repeat_byte(slot as u8),
+            state: B256::ZERO,
+            epoch_transition: false,
+            previous_duty_dependent_root: B256::repeat_byte(previous_root),
+            current_duty_dependent_root: B256::repeat_byte(current_root),
+            execution_optimistic: false,
+        }
+    }
+
+    #[test]
+    fn test_dependent_root_change_refreshes_duties() {
+        let mut head_tracker = HeadTracker::default();
+        head_tracker.on_connected();
+
+        assert_eq!(
+            head_tracker.on_head(&head(1, 1, 2)),
+            HeadUpdate {
+                refresh_duties: false,
+                block_slot: Some(Slot::new(1)),
+            }
+        );
+        assert!(!head_tracker.on_head(&head(2, 1, 2)).refresh_duties);
+        assert!(head_tracker.on_head(&head(3, 1, 3)).refresh_duties);
+
+        head_tracker.on_disconnected();
+        assert!(!head_tracker.is_connected());
+        assert!(!head_tracker.on_head(&head(4, 5, 6)).refresh_duties);
+    }
+}
@@ -4,13 +4,16 @@ use ream_bls::BLSSignature;
 pub mod aggregate_and_proof;
 pub mod attestation;
 pub mod beacon_api_client;
-pub mod blob_sidecars;
 pub mod block;
+pub mod block_packing;
 pub mod builder;
 pub mod constants;
 pub mod contribution_and_proof;
+pub mod deposit;
+pub mod duties;
 pub mod execution_requests;
-pub mod randao;
+pub mod graffiti;
+pub mod head_tracker;
 pub mod state;
 pub mod sync_committee;
 pub mod validator;
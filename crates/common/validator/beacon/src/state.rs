@@ -18,8 +18,11 @@ pub fn get_epoch_signature(
     block: &BeaconBlock,
     private_key: PrivateKey,
 ) -> anyhow::Result<BLSSignature> {
-    let domain = state.get_domain(DOMAIN_RANDAO, Some(compute_epoch_at_slot(block.slot)));
-    let signing_root = compute_signing_root(compute_epoch_at_slot(block.slot), domain);
+    let domain = state.get_domain(
+        DOMAIN_RANDAO,
+        Some(compute_epoch_at_slot(block.slot.as_u64())),
+    );
+    let signing_root = compute_signing_root(compute_epoch_at_slot(block.slot.as_u64()), domain);
     Ok(private_key.sign(signing_root.as_ref())?)
 }
 
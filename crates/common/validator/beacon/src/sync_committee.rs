@@ -14,9 +14,12 @@ use ream_consensus_misc::{
     constants::beacon::{
         DOMAIN_SYNC_COMMITTEE, EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SYNC_COMMITTEE_SIZE,
     },
-    misc::{compute_domain, compute_epoch_at_slot, compute_signing_root},
+    misc::{compute_epoch_at_slot, compute_signing_root},
+};
+use ream_signing::{
+    domain::{SignatureKind, SigningContext},
+    signer::{Signer, sign},
 };
-use ream_network_spec::networks::beacon_network_spec;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{BitVector, typenum::U512};
@@ -82,7 +85,7 @@ pub fn compute_subnets_for_sync_committee(
     state: &BeaconState,
     validator_index: u64,
 ) -> anyhow::Result<HashSet<u64>> {
-    let next_slot_epoch = compute_epoch_at_slot(state.slot + 1);
+    let next_slot_epoch = compute_epoch_at_slot(state.slot.as_u64() + 1);
     let sync_committee = if compute_sync_committee_period(state.get_current_epoch())
         == compute_sync_committee_period(next_slot_epoch)
     {
@@ -139,24 +142,22 @@ pub fn process_sync_committee_contributions(
     Ok(())
 }
 
-pub fn get_sync_committee_selection_proof(
+pub async fn get_sync_committee_selection_proof(
+    signer: &dyn Signer,
+    signing_context: &SigningContext,
     slot: u64,
     subcommittee_index: u64,
-    private_key: &PrivateKey,
 ) -> anyhow::Result<BLSSignature> {
-    let domain = compute_domain(
-        DOMAIN_SYNC_COMMITTEE,
-        Some(beacon_network_spec().electra_fork_version),
-        None,
-    );
-    let signing_root = compute_signing_root(
+    sign(
+        signer,
+        signing_context,
+        SignatureKind::SyncCommitteeSelectionProof,
         SyncAggregatorSelectionData {
             slot,
             subcommittee_index,
         },
-        domain,
-    );
-    Ok(private_key.sign(signing_root.as_ref())?)
+    )
+    .await
 }
 
 pub fn is_sync_committee_aggregator(signature: &BLSSignature) -> bool {
@@ -177,7 +178,7 @@ pub fn get_sync_committee_message(
     let signing_root = compute_signing_root(beacon_block_root, domain);
 
     Ok(SyncCommitteeMessage {
-        slot: state.slot,
+        slot: state.slot.as_u64(),
         beacon_block_root,
         validator_index,
         signature: private_key.sign(signing_root.as_ref())?,
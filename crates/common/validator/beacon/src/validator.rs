@@ -1,52 +1,70 @@
 use std::{
-    collections::{HashMap, hash_map::Entry},
+    collections::{HashMap, HashSet, hash_map::Entry},
+    future::pending,
     mem::take,
+    pin::Pin,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
     vec,
 };
 
-use alloy_primitives::Address;
-use anyhow::{anyhow, bail};
-use futures::future::try_join_all;
+use alloy_primitives::B256;
+use anyhow::anyhow;
+use futures::{Stream, StreamExt, future::try_join_all};
 use ream_api_types_beacon::{
-    block::{BroadcastValidation, ProduceBlockData},
+    block::{BroadcastValidation, ProduceBlockData, SignedBlockContents},
     duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
     id::ValidatorID,
     request::SyncCommitteeRequestItem,
+    responses::DutiesResponse,
 };
 use ream_api_types_common::id::ID;
-use ream_bls::{BLSSignature, PublicKey, traits::Signable};
+use ream_bls::{BLSSignature, PublicKey};
 use ream_consensus_beacon::{
-    electra::beacon_state::BeaconState, single_attestation::SingleAttestation,
+    electra::{
+        beacon_block::SignedBeaconBlock, beacon_state::BeaconState,
+        blinded_beacon_block::SignedBlindedBeaconBlock,
+    },
+    single_attestation::SingleAttestation,
 };
 use ream_consensus_misc::{
     attestation_data::AttestationData,
     constants::beacon::{
-        DOMAIN_SYNC_COMMITTEE, INTERVALS_PER_SLOT, SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
+        EPOCHS_PER_SYNC_COMMITTEE_PERIOD, INTERVALS_PER_SLOT, SYNC_COMMITTEE_SIZE,
     },
-    misc::{compute_domain, compute_epoch_at_slot, compute_signing_root},
+    misc::compute_epoch_at_slot,
+    slot_epoch::{Epoch, Slot},
 };
 use ream_executor::ReamExecutor;
-use ream_keystore::keystore::Keystore;
+use ream_metrics::{
+    VALIDATOR_BALANCE, VALIDATOR_SIGNING_TIME, set_int_gauge_vec, start_timer_vec, stop_timer,
+};
 use ream_network_spec::networks::beacon_network_spec;
+use ream_signing::{
+    domain::{SignatureKind, SigningContext},
+    signer::{Signer, sign},
+};
+use ream_slot_clock::{SlotClock, SlotTime};
 use reqwest::Url;
-use tokio::time::{Instant, MissedTickBehavior, interval_at, sleep};
 use tracing::{error, info, warn};
 use tree_hash::TreeHash;
 
 use crate::{
-    aggregate_and_proof::{AggregateAndProof, SignedAggregateAndProof, sign_aggregate_and_proof},
-    attestation::{get_selection_proof, sign_attestation_data},
-    beacon_api_client::BeaconApiClient,
-    block::{sign_beacon_block, sign_blinded_beacon_block},
-    constants::SYNC_COMMITTEE_SUBNET_COUNT,
-    contribution_and_proof::{
-        ContributionAndProof, SignedContributionAndProof, get_contribution_and_proof_signature,
+    aggregate_and_proof::{AggregateAndProof, SignedAggregateAndProof},
+    attestation::is_aggregator_for_committee_length,
+    beacon_api_client::{
+        BeaconApiClient,
+        event::{BeaconEvent, EventStreamItem, EventTopic},
+        retry::RetryConfig,
     },
-    randao::sign_randao_reveal,
+    builder::validator_registration::{ProposerConfig, ValidatorRegistrations},
+    constants::SYNC_COMMITTEE_SUBNET_COUNT,
+    contribution_and_proof::{ContributionAndProof, SignedContributionAndProof},
+    duties::{DutiesCache, DutiesUpdate, DutyKind},
+    graffiti::GraffitiProvider,
+    head_tracker::HeadTracker,
     sync_committee::{get_sync_committee_selection_proof, is_sync_committee_aggregator},
-    voluntary_exit::sign_voluntary_exit,
+    voluntary_exit::{sign_voluntary_exit, voluntary_exit_signing_context},
 };
 
 pub fn check_if_validator_active(
@@ -64,116 +82,194 @@ pub fn is_proposer(state: &BeaconState, validator_index: u64) -> anyhow::Result<
     Ok(state.get_beacon_proposer_index(None)? == validator_index)
 }
 pub struct SyncTaskInfo {
+    pub validator_index: u64,
+    pub subcommittee_index: u64,
+    pub beacon_block_root: B256,
+    pub selection_proof: BLSSignature,
+    pub signer: Arc<dyn Signer>,
+}
+
+pub struct AttestationAggregatorInfo {
     pub validator_index: u64,
     pub committee_index: u64,
     pub selection_proof: BLSSignature,
-    pub keystore: Arc<Keystore>,
+    pub signer: Arc<dyn Signer>,
 }
 
 pub struct ValidatorService {
     pub beacon_api_client: Arc<BeaconApiClient>,
-    pub validators: Vec<Arc<Keystore>>,
-    pub suggested_fee_recipient: Arc<Address>,
+    pub validators: Vec<Arc<dyn Signer>>,
+    pub proposer_config: ProposerConfig,
+    pub registrations: ValidatorRegistrations,
+    pub graffiti_provider: GraffitiProvider,
     pub executor: ReamExecutor,
     pub active_validator_count: usize,
     pub public_key_to_index: HashMap<PublicKey, u64>,
-    pub validator_index_to_keystore: HashMap<u64, Arc<Keystore>>,
-    pub proposer_duties: Vec<ProposerDuty>,
-    pub attester_duties: Vec<AttesterDuty>,
-    pub sync_committee_duties: Vec<SyncCommitteeDuty>,
+    pub validator_index_to_signer: HashMap<u64, Arc<dyn Signer>>,
+    pub duties: DutiesCache,
+    pub attestation_aggregator_infos: Vec<AttestationAggregatorInfo>,
     pub sync_aggregator_infos: Vec<SyncTaskInfo>,
-    pub sync_normal_infos: Vec<SyncTaskInfo>,
+    pub head_tracker: HeadTracker,
+    /// The last slot whose attestations were made
+    pub attested_slot: Option<Slot>,
+    /// The root of the attestation data signed at `attested_slot`, which its aggregates aggregate
+    pub attestation_data_root: Option<B256>,
+    /// The domains are computed with a zero root when the genesis validators root isn't known
+    pub genesis_validators_root: Option<B256>,
 }
 
 impl ValidatorService {
     pub fn new(
-        keystores: Vec<Keystore>,
-        suggested_fee_recipient: Address,
-        beacon_api_endpoint: Url,
+        validators: Vec<Arc<dyn Signer>>,
+        proposer_config: ProposerConfig,
+        beacon_api_endpoints: Vec<Url>,
         request_timeout: Duration,
+        retry_config: RetryConfig,
+        graffiti_provider: GraffitiProvider,
         executor: ReamExecutor,
     ) -> anyhow::Result<Self> {
-        let validators = keystores.into_iter().map(Arc::new).collect::<Vec<_>>();
-
         Ok(Self {
             beacon_api_client: Arc::new(BeaconApiClient::new(
-                beacon_api_endpoint,
+                beacon_api_endpoints,
                 request_timeout,
+                retry_config,
             )?),
             validators,
-            suggested_fee_recipient: Arc::new(suggested_fee_recipient),
+            proposer_config,
+            registrations: ValidatorRegistrations::default(),
+            graffiti_provider,
             executor,
             active_validator_count: 0,
             public_key_to_index: HashMap::new(),
-            validator_index_to_keystore: HashMap::new(),
-            proposer_duties: Vec::new(),
-            attester_duties: Vec::new(),
-            sync_committee_duties: Vec::new(),
+            validator_index_to_signer: HashMap::new(),
+            duties: DutiesCache::default(),
+            attestation_aggregator_infos: Vec::new(),
             sync_aggregator_infos: Vec::new(),
-            sync_normal_infos: Vec::new(),
+            head_tracker: HeadTracker::default(),
+            attested_slot: None,
+            attestation_data_root: None,
+            genesis_validators_root: None,
         })
     }
 
+    /// The context which the messages of `epoch` are signed under, bound to the fork active at
+    /// `epoch`, so the duties after a fork boundary are signed with the version of the new fork.
+    pub fn signing_context(&self, epoch: Epoch) -> SigningContext {
+        let network_spec = beacon_network_spec();
+        SigningContext::new(
+            network_spec.fork_version(network_spec.fork_name_at_epoch(epoch.as_u64())),
+            self.genesis_validators_root,
+        )
+    }
+
     pub async fn start(mut self) {
-        let seconds_per_slot = beacon_network_spec().seconds_per_slot;
-        let seconds_per_interval = seconds_per_slot / INTERVALS_PER_SLOT;
-
-        let genesis_instant =
-            UNIX_EPOCH + Duration::from_secs(beacon_network_spec().min_genesis_time);
-        let elapsed = SystemTime::now()
-            .duration_since(genesis_instant)
-            .expect("System Time is before the genesis time");
-
-        let mut intervals = elapsed.as_secs() / seconds_per_interval;
-        let mut slot = intervals / INTERVALS_PER_SLOT;
-        let mut epoch = compute_epoch_at_slot(slot);
-
-        let mut interval = {
-            let interval_start =
-                Instant::now() - (elapsed - Duration::from_secs(intervals * seconds_per_interval));
-            interval_at(interval_start, Duration::from_secs(seconds_per_interval))
-        };
-        interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+        let network_spec = beacon_network_spec();
+        let clock = SlotClock::new(
+            network_spec.min_genesis_time,
+            network_spec.seconds_per_slot,
+            INTERVALS_PER_SLOT,
+        );
+        let current_slot = Slot::new(clock.current_slot().unwrap_or_default());
 
+        // Load the duties of the current epoch so a client started mid-epoch does not have to wait
+        // for the next epoch boundary.
+        let mut epoch = current_slot.epoch();
+        self.on_epoch(epoch).await;
+
+        let mut ticks = clock.ticks();
+        let mut head_events = self.subscribe_head_events();
         loop {
             tokio::select! {
-                _ = interval.tick() => {
-                    intervals += 1;
-                    if intervals.is_multiple_of(INTERVALS_PER_SLOT * SLOTS_PER_EPOCH) {
-                        epoch += 1;
-                        self.on_epoch(epoch).await;
-                    }
-                    if intervals.is_multiple_of(INTERVALS_PER_SLOT) {
-                        slot += 1;
-                        self.on_slot(slot).await;
-                    }
-                    if intervals % INTERVALS_PER_SLOT == 2 {
-                        self.on_slot_aggregator(slot).await;
-                    }
-                    if (intervals + 1).is_multiple_of(INTERVALS_PER_SLOT * SLOTS_PER_EPOCH) {
-                        self.on_epoch_end(epoch).await;
+                SlotTime { slot, interval } = ticks.next() => {
+                    let slot = Slot::new(slot);
+                    match interval {
+                        0 => {
+                            // Compared to the last epoch rather than checking for the epoch start,
+                            // as the ticks skip the intervals which have passed while the client
+                            // was stalled
+                            if slot.epoch() > epoch {
+                                epoch = slot.epoch();
+                                self.on_epoch(epoch).await;
+                            }
+                            if head_events.is_none() {
+                                head_events = self.subscribe_head_events();
+                            }
+                            self.on_slot(slot).await;
+                        }
+                        1 => {
+                            self.on_slot_attester(slot).await;
+                            self.on_slot_sync_committee(slot).await;
+                        }
+                        _ => self.on_slot_aggregator(slot).await,
                     }
                 }
+                item = next_item(&mut head_events) => {
+                    let Some(item) = item else {
+                        // The stream gave up reconnecting, it is subscribed again next slot
+                        head_events = None;
+                        self.head_tracker.on_disconnected();
+                        continue;
+                    };
+                    let current_slot = Slot::new(clock.current_slot().unwrap_or_default());
+                    self.on_head_event(item, current_slot).await;
+                }
             }
         }
     }
 
-    pub async fn process_aggregator_sync_infos(&mut self, slot: u64) -> anyhow::Result<()> {
+    fn subscribe_head_events(&self) -> Option<Pin<Box<dyn Stream<Item = EventStreamItem> + Send>>> {
+        self.beacon_api_client
+            .get_event_stream_items(&[EventTopic::Head, EventTopic::ChainReorg], "head")
+            .inspect_err(|err| warn!("Failed to subscribe to the head events: {err:?}"))
+            .ok()
+    }
+
+    /// Refreshes the duties when the head events tell about a re-org, and makes the attestations
+    /// of the current slot as soon as its block becomes the head.
+    pub async fn on_head_event(&mut self, item: EventStreamItem, current_slot: Slot) {
+        let update = match item {
+            EventStreamItem::Connected => {
+                self.head_tracker.on_connected();
+                return;
+            }
+            EventStreamItem::Disconnected => {
+                self.head_tracker.on_disconnected();
+                return;
+            }
+            EventStreamItem::Event(event) => match *event {
+                BeaconEvent::Head(head) => self.head_tracker.on_head(&head),
+                BeaconEvent::ChainReorg(reorg) => self.head_tracker.on_chain_reorg(&reorg),
+                _ => return,
+            },
+        };
+
+        if update.refresh_duties {
+            self.update_duties(current_slot.epoch()).await;
+        }
+        if update.block_slot == Some(current_slot) {
+            self.on_slot_attester(current_slot).await;
+        }
+    }
+
+    pub async fn process_aggregator_sync_infos(&mut self, slot: Slot) -> anyhow::Result<()> {
         let client = self.beacon_api_client.clone();
+        let signing_context = self.signing_context(slot.epoch());
         let aggregator_infos = take(&mut self.sync_aggregator_infos);
-
-        let block_root = client.get_block_root(ID::Slot(slot)).await?.data.root;
+        if aggregator_infos.is_empty() {
+            return Ok(());
+        }
 
         let contribution_tasks = aggregator_infos
             .into_iter()
             .map(|aggregator_info| {
                 let client = client.clone();
                 tokio::spawn(async move {
-                    let subcommittee_index = aggregator_info.committee_index
-                        / (SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT);
-
                     let contribution = client
-                        .get_sync_committee_contribution(slot, subcommittee_index, block_root)
+                        .get_sync_committee_contribution(
+                            slot,
+                            aggregator_info.subcommittee_index,
+                            aggregator_info.beacon_block_root,
+                        )
                         .await?
                         .data;
 
@@ -183,10 +279,18 @@ impl ValidatorService {
                         selection_proof: aggregator_info.selection_proof,
                     };
 
-                    let contribution_and_proof_signature = get_contribution_and_proof_signature(
+                    let timer = start_timer_vec(
+                        &VALIDATOR_SIGNING_TIME,
+                        &[DutyKind::SyncAggregator.name()],
+                    );
+                    let contribution_and_proof_signature = sign(
+                        aggregator_info.signer.as_ref(),
+                        &signing_context,
+                        SignatureKind::ContributionAndProof,
                         &contribution_and_proof,
-                        &aggregator_info.keystore.private_key,
-                    )?;
+                    )
+                    .await?;
+                    stop_timer(timer);
 
                     Ok::<_, anyhow::Error>(SignedContributionAndProof {
                         message: contribution_and_proof,
@@ -208,85 +312,314 @@ impl ValidatorService {
         Ok(())
     }
 
-    pub async fn process_normal_sync_infos(&mut self, slot: u64) -> anyhow::Result<()> {
-        let normal_infos = take(&mut self.sync_normal_infos)
-            .into_iter()
-            .map(|sync_info| sync_info.validator_index)
-            .collect::<Vec<u64>>();
-        self.submit_sync_committee(slot, normal_infos.as_ref())
+    /// Sign the head block root with every local member of the sync committee of `slot` and
+    /// select the members which aggregate their subcommittee.
+    pub async fn process_sync_committee_messages(&mut self, slot: Slot) -> anyhow::Result<()> {
+        self.sync_aggregator_infos.clear();
+
+        let duties = self.duties.sync_committee_duties_at_slot(slot).to_vec();
+        if duties.is_empty() {
+            return Ok(());
+        }
+
+        let beacon_block_root = self
+            .beacon_api_client
+            .get_block_root(ID::Head)
+            .await?
+            .data
+            .root;
+
+        self.prepare_sync_infos(slot, beacon_block_root, &duties)
+            .await?;
+
+        let validator_indices = duties
+            .iter()
+            .map(|duty| duty.validator_index)
+            .collect::<Vec<_>>();
+        self.submit_sync_committee(slot, beacon_block_root, &validator_indices)
             .await
     }
 
     // Runs on the start of every epoch prior to the per-slot code.
     // - Fetches validator indicies
-    // - Fetches proposer and committee duties for the epoch
-    pub async fn on_epoch(&mut self, epoch: u64) {
+    // - Polls the balances of the validators for the metrics
+    // - Resubmits the builder registrations
+    // - Prunes duties of past epochs
+    // - Fetches the proposer and attester duties of the epoch
+    // - Fetches sync committee duties for the current and next period
+    pub async fn on_epoch(&mut self, epoch: Epoch) {
         info!("Current Epoch: {epoch}");
 
         self.fetch_validator_indicies().await;
-        let validator_indices: Vec<u64> = self.public_key_to_index.values().cloned().collect();
+        self.update_balances().await;
+        self.update_registrations(true).await;
+        self.duties.prune(epoch);
+        self.update_duties(epoch).await;
 
+        let validator_indices: Vec<u64> = self.public_key_to_index.values().cloned().collect();
         if validator_indices.is_empty() {
             warn!("No active validators found, skipping duty fetch");
             return;
         }
 
-        if let Some(proposer_duties) = self.fetch_proposer_duties(epoch, &validator_indices).await {
-            self.proposer_duties = proposer_duties;
+        // Sync committee duties are fetched for the current and the next period, as the members of
+        // the next sync committee already sign in the last slot of the current period.
+        let next_period_epoch =
+            Epoch::new((epoch.sync_committee_period() + 1) * EPOCHS_PER_SYNC_COMMITTEE_PERIOD);
+        let (current_sync_duties, next_sync_duties) = tokio::join!(
+            self.fetch_sync_committee_duties(epoch, &validator_indices),
+            self.fetch_sync_committee_duties(next_period_epoch, &validator_indices),
+        );
+        for (epoch, sync_duties) in [
+            (epoch, current_sync_duties),
+            (next_period_epoch, next_sync_duties),
+        ] {
+            if let Some(sync_duties) = sync_duties {
+                self.duties
+                    .insert_sync_committee_duties(epoch.sync_committee_period(), sync_duties);
+            }
         }
     }
 
-    // Runs on the end of every epoch after the per-slot code(exactly 4 seconds prior to the next
-    // epoch).
-    // - Fetches the attester duties for the next epoch
-    pub async fn on_epoch_end(&mut self, epoch: u64) {
-        info!("Current Epoch: {epoch}");
-        let validator_indices: Vec<u64> = self.public_key_to_index.values().cloned().collect();
-
-        if validator_indices.is_empty() {
-            warn!("No active validators found, skipping duty fetch");
-            return;
+    // Runs at the start of every slot
+    // - Refreshes the health of the beacon nodes
+    // - Refreshes the duties while the head events are down, replacing them if their dependent root
+    //   changed
+    // - Submits the builder registrations which changed
+    // - Spawns the block proposals for the slot
+    pub async fn on_slot(&mut self, slot: Slot) {
+        info!("Current Slot: {slot}");
+        self.beacon_api_client.update_endpoint_health().await;
+        if !self.head_tracker.is_connected() {
+            self.update_duties(slot.epoch()).await;
         }
+        self.update_registrations(false).await;
 
-        // In the future, we can likely increase the lookahead for sync duties
-        let (attester_duties, sync_duties) = tokio::join!(
-            self.fetch_attester_duties(epoch + 1, &validator_indices),
-            self.fetch_sync_committee_duties(epoch + 1, &validator_indices),
-        );
+        for duty in self.duties.proposer_duties_at_slot(slot) {
+            let Some(signer) = self.validator_index_to_signer.get(&duty.validator_index) else {
+                continue;
+            };
+            let beacon_api_client = self.beacon_api_client.clone();
+            let signer = signer.clone();
+            let signing_context = self.signing_context(slot.epoch());
+            let graffiti = self.graffiti_provider.graffiti(signer.public_key());
+            DutyKind::Proposer.record_scheduled(1);
+            self.executor.spawn(async move {
+                let result =
+                    propose_block(beacon_api_client, signer, signing_context, slot, graffiti).await;
+                if let Err(err) = &result {
+                    error!(
+                        "Failed to propose block at slot {slot} for validator {}: {err:?}",
+                        duty.validator_index
+                    );
+                }
+                DutyKind::Proposer.record_result(1, &result);
+            });
+        }
+    }
 
-        if let Some(attester_duties) = attester_duties {
-            self.attester_duties = attester_duties;
+    // Runs at 1 interval into every slot, or earlier once the block of the slot is the head
+    // - Spawns the attestations of the slot
+    // - Computes the selection proofs to find out which attesters aggregate the slot
+    pub async fn on_slot_attester(&mut self, slot: Slot) {
+        if self
+            .attested_slot
+            .is_some_and(|attested_slot| attested_slot >= slot)
+        {
+            return;
         }
+        self.attested_slot = Some(slot);
+        self.attestation_data_root = None;
+        self.attestation_aggregator_infos.clear();
 
-        if let Some(sync_duties) = sync_duties {
-            self.sync_committee_duties = sync_duties;
+        let duties = self.duties.attester_duties_at_slot(slot);
+        if duties.is_empty() {
+            return;
         }
 
-        // Fetch proposer duties separately (could also be joined if needed)
-        if let Some(proposer_duties) = self
-            .fetch_proposer_duties(epoch + 1, &validator_indices)
+        // Since Electra the attestation data no longer depends on the committee index, so every
+        // attester of the slot signs the same data, and it identifies the aggregates of every
+        // committee.
+        let attestation_data = match self
+            .beacon_api_client
+            .get_attestation_data(slot.as_u64(), 0)
             .await
         {
-            self.proposer_duties = proposer_duties;
+            Ok(response) => response.data,
+            Err(err) => {
+                error!("Failed to get the attestation data of slot {slot}: {err:?}");
+                let result: anyhow::Result<()> = Err(err.into());
+                DutyKind::Attester.record_scheduled(duties.len());
+                DutyKind::Attester.record_result(duties.len(), &result);
+                return;
+            }
+        };
+        self.attestation_data_root = Some(attestation_data.tree_hash_root());
+
+        for duty in duties {
+            let Some(signer) = self
+                .validator_index_to_signer
+                .get(&duty.validator_index)
+                .cloned()
+            else {
+                continue;
+            };
+
+            match sign(
+                signer.as_ref(),
+                &self.signing_context(slot.epoch()),
+                SignatureKind::SelectionProof,
+                slot.as_u64(),
+            )
+            .await
+            {
+                Ok(selection_proof) => {
+                    if is_aggregator_for_committee_length(duty.committee_length, &selection_proof) {
+                        self.attestation_aggregator_infos
+                            .push(AttestationAggregatorInfo {
+                                validator_index: duty.validator_index,
+                                committee_index: duty.committee_index,
+                                selection_proof,
+                                signer: signer.clone(),
+                            });
+                    }
+                }
+                Err(err) => warn!(
+                    "Could not get selection proof for validator {}: {err:?}",
+                    duty.validator_index
+                ),
+            }
+
+            let beacon_api_client = self.beacon_api_client.clone();
+            let signing_context = self.signing_context(slot.epoch());
+            let attestation_data = attestation_data.clone();
+            DutyKind::Attester.record_scheduled(1);
+            self.executor.spawn(async move {
+                let result = make_attestation(
+                    beacon_api_client,
+                    signer,
+                    signing_context,
+                    attestation_data,
+                    duty.validator_index,
+                    duty.committee_index,
+                )
+                .await;
+                if let Err(err) = &result {
+                    error!(
+                        "Failed to attest at slot {slot} for validator {}: {err:?}",
+                        duty.validator_index
+                    );
+                }
+                DutyKind::Attester.record_result(1, &result);
+            });
         }
     }
 
-    // Runs at the start of every slot
-    pub async fn on_slot(&mut self, slot: u64) {
-        info!("Current Slot: {slot}");
-        if let Err(sync_error) = self.prepare_sync_infos(slot - 1).await {
-            warn!("Could not prepare the sync infos: {sync_error:?}");
-        } else if let Err(sync_error) = self.process_normal_sync_infos(slot - 1).await {
-            warn!("Could not process the normal sync infos: {sync_error:?}");
+    // Runs at 1 interval into every slot: publishes the sync committee messages of the slot
+    pub async fn on_slot_sync_committee(&mut self, slot: Slot) {
+        let duty_count = self.duties.sync_committee_duties_at_slot(slot).len();
+        DutyKind::SyncCommittee.record_scheduled(duty_count);
+        let result = self.process_sync_committee_messages(slot).await;
+        if let Err(sync_error) = &result {
+            warn!("Could not process the sync committee messages: {sync_error:?}");
         }
+        DutyKind::SyncCommittee.record_result(duty_count, &result);
     }
 
     // Runs at 2 intervals into every slot: meant for aggregators
-    pub async fn on_slot_aggregator(&mut self, slot: u64) {
-        info!("Current Slot: {slot}");
-        if let Err(sync_error) = self.process_aggregator_sync_infos(slot - 1).await {
+    pub async fn on_slot_aggregator(&mut self, slot: Slot) {
+        // The aggregates record their own results once they are published
+        let aggregator_count = self.attestation_aggregator_infos.len();
+        DutyKind::Aggregator.record_scheduled(aggregator_count);
+        let result = self.process_attestation_aggregator_infos(slot).await;
+        if let Err(aggregation_error) = &result {
+            warn!("Could not process the attestation aggregator infos: {aggregation_error:?}");
+            DutyKind::Aggregator.record_result(aggregator_count, &result);
+        }
+
+        let sync_aggregator_count = self.sync_aggregator_infos.len();
+        DutyKind::SyncAggregator.record_scheduled(sync_aggregator_count);
+        let result = self.process_aggregator_sync_infos(slot).await;
+        if let Err(sync_error) = &result {
             warn!("Could not process the aggregator sync infos: {sync_error:?}");
         }
+        DutyKind::SyncAggregator.record_result(sync_aggregator_count, &result);
+    }
+
+    /// Sign the builder registrations of the validators with an index whose proposer config
+    /// changed, and submit the registrations if any were signed or `resubmit` is set. The relays
+    /// forget registrations after a while, so they are resubmitted every epoch.
+    pub async fn update_registrations(&mut self, resubmit: bool) {
+        if !self.proposer_config.enable_builder {
+            return;
+        }
+
+        let signers = self
+            .validator_index_to_signer
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if let Err(err) = self
+            .registrations
+            .update(&signers, &self.proposer_config, timestamp)
+            .await
+        {
+            warn!("Failed to sign the builder registrations: {err:?}");
+        }
+        if !resubmit && !self.registrations.has_unsubmitted() {
+            return;
+        }
+
+        let registrations = self.registrations.registrations();
+        if registrations.is_empty() {
+            return;
+        }
+        let registration_count = registrations.len();
+        match self
+            .beacon_api_client
+            .register_validator(registrations)
+            .await
+        {
+            Ok(()) => {
+                self.registrations.mark_submitted();
+                info!("Submitted the builder registrations of {registration_count} validators");
+            }
+            Err(err) => warn!("Failed to submit the builder registrations: {err:?}"),
+        }
+    }
+
+    /// Fetch the proposer duties of the current epoch and the attester duties of the current and
+    /// next epoch. Duties whose `dependent_root` changed since the last fetch were computed on a
+    /// chain which has since been re-orged out, so they are replaced.
+    pub async fn update_duties(&mut self, epoch: Epoch) {
+        let validator_indices: Vec<u64> = self.public_key_to_index.values().cloned().collect();
+        if validator_indices.is_empty() {
+            return;
+        }
+
+        let (proposer_duties, current_attester_duties, next_attester_duties) = tokio::join!(
+            self.fetch_proposer_duties(epoch, &validator_indices),
+            self.fetch_attester_duties(epoch, &validator_indices),
+            self.fetch_attester_duties(epoch + 1, &validator_indices),
+        );
+
+        if let Some(proposer_duties) = proposer_duties {
+            let update = self.duties.insert_proposer_duties(epoch, proposer_duties);
+            log_duties_update("proposer", epoch, update);
+        }
+        for (epoch, attester_duties) in [
+            (epoch, current_attester_duties),
+            (epoch + 1, next_attester_duties),
+        ] {
+            if let Some(attester_duties) = attester_duties {
+                let update = self.duties.insert_attester_duties(epoch, attester_duties);
+                log_duties_update("attester", epoch, update);
+            }
+        }
     }
 
     pub async fn fetch_validator_indicies(&mut self) {
@@ -299,7 +632,7 @@ impl ValidatorService {
                         self.validators
                             .iter()
                             .map(|validator_info| {
-                                ValidatorID::Address(validator_info.public_key.clone())
+                                ValidatorID::Address(validator_info.public_key().clone())
                             })
                             .collect::<Vec<_>>(),
                     ),
@@ -315,16 +648,16 @@ impl ValidatorService {
                     {
                         entry.insert(validator_data.index);
 
-                        if let Some(keystore) = self
+                        if let Some(signer) = self
                             .validators
                             .iter()
-                            .find(|keystore| {
-                                keystore.public_key == validator_data.validator.public_key
+                            .find(|signer| {
+                                *signer.public_key() == validator_data.validator.public_key
                             })
                             .cloned()
                         {
-                            self.validator_index_to_keystore
-                                .insert(validator_data.index, keystore);
+                            self.validator_index_to_signer
+                                .insert(validator_data.index, signer);
                         }
 
                         self.active_validator_count += 1;
@@ -334,19 +667,47 @@ impl ValidatorService {
         }
     }
 
+    /// Poll the balances of the validators as of the head state for the metrics.
+    pub async fn update_balances(&self) {
+        if self.public_key_to_index.is_empty() {
+            return;
+        }
+
+        let validator_ids = self
+            .public_key_to_index
+            .values()
+            .map(|validator_index| ValidatorID::Index(*validator_index))
+            .collect::<Vec<_>>();
+        match self
+            .beacon_api_client
+            .get_state_validator_list(ID::Head, Some(validator_ids), None)
+            .await
+        {
+            Ok(validator_infos) => {
+                for validator_data in validator_infos.data {
+                    set_int_gauge_vec(
+                        &VALIDATOR_BALANCE,
+                        validator_data.balance as i64,
+                        &[&validator_data.index.to_string()],
+                    );
+                }
+            }
+            Err(err) => warn!("Failed to fetch the balances of the validators: {err:?}"),
+        }
+    }
+
     pub async fn fetch_proposer_duties(
         &self,
-        epoch: u64,
+        epoch: Epoch,
         validator_indices: &[u64],
-    ) -> Option<Vec<ProposerDuty>> {
+    ) -> Option<DutiesResponse<ProposerDuty>> {
         match self.beacon_api_client.get_proposer_duties(epoch).await {
-            Ok(duties_response) => Some(
+            Ok(mut duties_response) => {
                 duties_response
                     .data
-                    .into_iter()
-                    .filter(|duty| validator_indices.contains(&duty.validator_index))
-                    .collect(),
-            ),
+                    .retain(|duty| validator_indices.contains(&duty.validator_index));
+                Some(duties_response)
+            }
             Err(err) => {
                 error!("Failed to fetch proposer duties for epoch {epoch}: {err:?}");
                 None
@@ -356,15 +717,15 @@ impl ValidatorService {
 
     pub async fn fetch_attester_duties(
         &self,
-        epoch: u64,
+        epoch: Epoch,
         validator_indices: &[u64],
-    ) -> Option<Vec<AttesterDuty>> {
+    ) -> Option<DutiesResponse<AttesterDuty>> {
         match self
             .beacon_api_client
             .get_attester_duties(epoch, validator_indices)
             .await
         {
-            Ok(duties_response) => Some(duties_response.data),
+            Ok(duties_response) => Some(duties_response),
             Err(err) => {
                 error!("Failed to fetch attester duties for epoch {epoch}: {err:?}");
                 None
@@ -374,7 +735,7 @@ impl ValidatorService {
 
     pub async fn fetch_sync_committee_duties(
         &self,
-        epoch: u64,
+        epoch: Epoch,
         validator_indices: &[u64],
     ) -> Option<Vec<SyncCommitteeDuty>> {
         match self
@@ -390,67 +751,48 @@ impl ValidatorService {
         }
     }
 
-    pub async fn propose_block(&self, slot: u64, validator_index: u64) -> anyhow::Result<()> {
-        let keystore = self
-            .validator_index_to_keystore
-            .get(&validator_index)
-            .cloned()
-            .ok_or_else(|| anyhow!("keystore not found for validator: {validator_index}"))?;
-        let randao_reveal = sign_randao_reveal(slot, &keystore.private_key)?;
-        let block_response = self
-            .beacon_api_client
-            .produce_block(slot, randao_reveal, None, None, None)
-            .await?;
-
-        match block_response.data {
-            ProduceBlockData::Full(full_block) => {
-                let signed_beacon_block =
-                    sign_beacon_block(slot, full_block.block, &keystore.private_key)?;
-
-                self.beacon_api_client
-                    .publish_block(BroadcastValidation::Gossip, signed_beacon_block)
-                    .await?;
-            }
-            ProduceBlockData::Blinded(blinded_block) => {
-                let signed_blinded_block =
-                    sign_blinded_beacon_block(slot, blinded_block, &keystore.private_key)?;
-
-                self.beacon_api_client
-                    .publish_blinded_block(BroadcastValidation::Gossip, signed_blinded_block)
-                    .await?;
-            }
-        };
-
-        Ok(())
-    }
-
-    pub async fn prepare_sync_infos(&mut self, slot: u64) -> anyhow::Result<()> {
-        self.sync_normal_infos.clear();
-        self.sync_aggregator_infos.clear();
-
-        for duty in &self.sync_committee_duties {
-            let Some(keystore) = self.validator_index_to_keystore.get(&duty.validator_index) else {
+    pub async fn prepare_sync_infos(
+        &mut self,
+        slot: Slot,
+        beacon_block_root: B256,
+        duties: &[SyncCommitteeDuty],
+    ) -> anyhow::Result<()> {
+        for duty in duties {
+            let Some(signer) = self
+                .validator_index_to_signer
+                .get(&duty.validator_index)
+                .cloned()
+            else {
                 continue;
             };
-            for &committee_index in &duty.validator_sync_committee_indices {
+
+            // A validator can hold several seats in the same subcommittee, but aggregates it once
+            let subcommittee_indices = duty
+                .validator_sync_committee_indices
+                .iter()
+                .map(|committee_index| {
+                    committee_index / (SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT)
+                })
+                .collect::<HashSet<_>>();
+
+            for subcommittee_index in subcommittee_indices {
                 let selection_proof = get_sync_committee_selection_proof(
-                    slot,
-                    committee_index,
-                    &keystore.private_key,
+                    signer.as_ref(),
+                    &self.signing_context(slot.epoch()),
+                    slot.as_u64(),
+                    subcommittee_index,
                 )
+                .await
                 .map_err(|err| anyhow!("Could not get selection proof: {err:?}"))?;
 
-                let task_info = SyncTaskInfo {
-                    validator_index: duty.validator_index,
-                    committee_index,
-                    selection_proof,
-                    keystore: Arc::clone(keystore),
-                };
-
-                if is_sync_committee_aggregator(&task_info.selection_proof) {
-                    self.sync_aggregator_infos.push(task_info);
-                } else {
-                    self.sync_normal_infos.push(task_info);
+                if is_sync_committee_aggregator(&selection_proof) {
+                    self.sync_aggregator_infos.push(SyncTaskInfo {
+                        validator_index: duty.validator_index,
+                        subcommittee_index,
+                        beacon_block_root,
+                        selection_proof,
+                        signer: Arc::clone(&signer),
+                    });
                 }
             }
         }
@@ -460,41 +802,33 @@ impl ValidatorService {
 
     pub async fn submit_sync_committee(
         &self,
-        slot: u64,
+        slot: Slot,
+        beacon_block_root: B256,
         validator_indices: &[u64],
     ) -> anyhow::Result<()> {
-        let domain = compute_domain(
-            DOMAIN_SYNC_COMMITTEE,
-            Some(beacon_network_spec().electra_fork_version),
-            None,
-        );
-        let beacon_block_root = self
-            .beacon_api_client
-            .get_block_root(ID::Slot(slot))
-            .await?
-            .data
-            .root;
-        let signing_root = compute_signing_root(beacon_block_root, domain);
-
-        let payload = validator_indices
-            .iter()
-            .filter_map(|&validator_index| {
-                if let Some(keystore) = self.validator_index_to_keystore.get(&validator_index) {
-                    return match keystore.private_key.sign(signing_root.as_ref()) {
-                        Ok(signature) => Some(Ok(SyncCommitteeRequestItem {
-                            slot,
-                            beacon_block_root,
-                            validator_index,
-                            signature,
-                        })),
-                        Err(signing_error) => Some(Err(anyhow!(
-                            "Signing failed for validator {validator_index:?}: {signing_error:?}"
-                        ))),
-                    };
-                }
-                None
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut payload = vec![];
+        for &validator_index in validator_indices {
+            let Some(signer) = self.validator_index_to_signer.get(&validator_index) else {
+                continue;
+            };
+            let timer = start_timer_vec(&VALIDATOR_SIGNING_TIME, &[DutyKind::SyncCommittee.name()]);
+            let signature = sign(
+                signer.as_ref(),
+                &self.signing_context(slot.epoch()),
+                SignatureKind::SyncCommitteeMessage,
+                beacon_block_root,
+            )
+            .await;
+            stop_timer(timer);
+            payload.push(SyncCommitteeRequestItem {
+                slot,
+                beacon_block_root,
+                validator_index,
+                signature: signature.map_err(|signing_error| {
+                    anyhow!("Signing failed for validator {validator_index:?}: {signing_error:?}")
+                })?,
+            });
+        }
 
         Ok(self
             .beacon_api_client
@@ -502,71 +836,42 @@ impl ValidatorService {
             .await?)
     }
 
-    pub async fn make_attestation(
-        &self,
-        slot: u64,
-        validator_index: u64,
-        committee_index: u64,
-    ) -> anyhow::Result<()> {
-        sleep(Duration::from_secs(
-            beacon_network_spec().seconds_per_slot / 3,
-        ))
-        .await;
-
-        let Some(keystore) = self.validator_index_to_keystore.get(&validator_index) else {
-            bail!("Keystore not found for validator: {validator_index}");
-        };
-
-        let attestation_data = self
-            .beacon_api_client
-            .get_attestation_data(slot, committee_index)
-            .await?
-            .data;
-        Ok(self
-            .beacon_api_client
-            .submit_attestation(vec![SingleAttestation {
-                attester_index: validator_index,
-                committee_index,
-                signature: sign_attestation_data(&attestation_data, &keystore.private_key)?,
-                data: attestation_data,
-            }])
-            .await?)
-    }
+    /// Publishes the aggregates of the attestation data signed at 1 interval into the slot. Each
+    /// aggregate is published on its own, so one failing doesn't hold back the others.
+    pub async fn process_attestation_aggregator_infos(&mut self, slot: Slot) -> anyhow::Result<()> {
+        let aggregator_infos = take(&mut self.attestation_aggregator_infos);
+        if aggregator_infos.is_empty() {
+            return Ok(());
+        }
 
-    pub async fn submit_aggregate_and_proof(
-        &self,
-        attestation_data: AttestationData,
-        slot: u64,
-        committee_index: u64,
-        aggregator_index: u64,
-    ) -> anyhow::Result<()> {
-        let keystore = self
-            .validator_index_to_keystore
-            .get(&aggregator_index)
-            .cloned()
-            .ok_or_else(|| anyhow!("Keystore not found for validator: {aggregator_index}"))?;
+        let attestation_data_root = self
+            .attestation_data_root
+            .filter(|_| self.attested_slot == Some(slot))
+            .ok_or_else(|| anyhow!("No attestation data was signed at slot {slot}"))?;
 
-        let aggregate_and_proof = AggregateAndProof {
-            aggregator_index,
-            aggregate: self
-                .beacon_api_client
-                .get_aggregated_attestation(
-                    attestation_data.tree_hash_root(),
+        for aggregator_info in aggregator_infos {
+            let beacon_api_client = self.beacon_api_client.clone();
+            let signing_context = self.signing_context(slot.epoch());
+            self.executor.spawn(async move {
+                let validator_index = aggregator_info.validator_index;
+                let result = publish_aggregate(
+                    beacon_api_client,
+                    signing_context,
                     slot,
-                    committee_index,
+                    attestation_data_root,
+                    aggregator_info,
                 )
-                .await?
-                .data,
-            selection_proof: get_selection_proof(slot, &keystore.private_key)?,
-        };
+                .await;
+                if let Err(err) = &result {
+                    error!(
+                        "Failed to publish the aggregate of validator {validator_index} at slot {slot}: {err:?}"
+                    );
+                }
+                DutyKind::Aggregator.record_result(1, &result);
+            });
+        }
 
-        Ok(self
-            .beacon_api_client
-            .publish_aggregate_and_proofs(vec![SignedAggregateAndProof {
-                signature: sign_aggregate_and_proof(&aggregate_and_proof, &keystore.private_key)?,
-                message: aggregate_and_proof,
-            }])
-            .await?)
+        Ok(())
     }
 
     pub async fn submit_voluntary_exit(
@@ -574,18 +879,173 @@ impl ValidatorService {
         validator_index: u64,
         epoch: u64,
     ) -> anyhow::Result<()> {
+        let signer = self
+            .validator_index_to_signer
+            .get(&validator_index)
+            .ok_or_else(|| anyhow!("Signer not found for validator: {validator_index}"))?;
         self.beacon_api_client
-            .submit_signed_voluntary_exit(sign_voluntary_exit(
-                epoch,
-                validator_index,
-                &self
-                    .validator_index_to_keystore
-                    .get(&validator_index)
-                    .ok_or_else(|| anyhow!("Keystore not found for validator: {validator_index}"))?
-                    .private_key,
-            )?)
+            .submit_signed_voluntary_exit(
+                sign_voluntary_exit(
+                    signer.as_ref(),
+                    &voluntary_exit_signing_context(self.genesis_validators_root),
+                    epoch,
+                    validator_index,
+                )
+                .await?,
+            )
             .await?;
 
         Ok(())
     }
 }
+
+fn log_duties_update(duty_kind: &str, epoch: Epoch, update: DutiesUpdate) {
+    if let DutiesUpdate::Reorged {
+        previous_dependent_root,
+    } = update
+    {
+        warn!(
+            "Dependent root of the {duty_kind} duties for epoch {epoch} changed from {previous_dependent_root}, replaced the duties"
+        );
+    }
+}
+
+pub async fn propose_block(
+    beacon_api_client: Arc<BeaconApiClient>,
+    signer: Arc<dyn Signer>,
+    signing_context: SigningContext,
+    slot: Slot,
+    graffiti: B256,
+) -> anyhow::Result<()> {
+    let slot = slot.as_u64();
+    let randao_reveal = sign(
+        signer.as_ref(),
+        &signing_context,
+        SignatureKind::RandaoReveal,
+        compute_epoch_at_slot(slot),
+    )
+    .await?;
+    let block_response = beacon_api_client
+        .produce_block(slot, randao_reveal, Some(graffiti), None, None)
+        .await?;
+
+    match block_response.data {
+        ProduceBlockData::Full(full_block) => {
+            let timer = start_timer_vec(&VALIDATOR_SIGNING_TIME, &[DutyKind::Proposer.name()]);
+            let signature = sign(
+                signer.as_ref(),
+                &signing_context,
+                SignatureKind::BeaconBlock,
+                &full_block.block,
+            )
+            .await?;
+            stop_timer(timer);
+            let signed_block_contents = SignedBlockContents {
+                signed_block: SignedBeaconBlock {
+                    message: full_block.block,
+                    signature,
+                },
+                kzg_proofs: full_block.kzg_proofs,
+                blobs: full_block.blobs,
+            };
+
+            beacon_api_client
+                .publish_block(BroadcastValidation::Gossip, signed_block_contents)
+                .await?;
+        }
+        ProduceBlockData::Blinded(blinded_block) => {
+            let timer = start_timer_vec(&VALIDATOR_SIGNING_TIME, &[DutyKind::Proposer.name()]);
+            let signature = sign(
+                signer.as_ref(),
+                &signing_context,
+                SignatureKind::BeaconBlock,
+                &blinded_block,
+            )
+            .await?;
+            stop_timer(timer);
+            let signed_blinded_block = SignedBlindedBeaconBlock {
+                message: blinded_block,
+                signature,
+            };
+
+            beacon_api_client
+                .publish_blinded_block(BroadcastValidation::Gossip, signed_blinded_block)
+                .await?;
+        }
+    };
+
+    Ok(())
+}
+
+async fn publish_aggregate(
+    beacon_api_client: Arc<BeaconApiClient>,
+    signing_context: SigningContext,
+    slot: Slot,
+    attestation_data_root: B256,
+    aggregator_info: AttestationAggregatorInfo,
+) -> anyhow::Result<()> {
+    let aggregate_and_proof = AggregateAndProof {
+        aggregator_index: aggregator_info.validator_index,
+        aggregate: beacon_api_client
+            .get_aggregated_attestation(
+                attestation_data_root,
+                slot.as_u64(),
+                aggregator_info.committee_index,
+            )
+            .await?
+            .data,
+        selection_proof: aggregator_info.selection_proof,
+    };
+
+    let timer = start_timer_vec(&VALIDATOR_SIGNING_TIME, &[DutyKind::Aggregator.name()]);
+    let signature = sign(
+        aggregator_info.signer.as_ref(),
+        &signing_context,
+        SignatureKind::AggregateAndProof,
+        &aggregate_and_proof,
+    )
+    .await?;
+    stop_timer(timer);
+
+    Ok(beacon_api_client
+        .publish_aggregate_and_proofs(vec![SignedAggregateAndProof {
+            signature,
+            message: aggregate_and_proof,
+        }])
+        .await?)
+}
+
+/// The next item of `stream`, which never comes without a stream.
+async fn next_item<S: Stream + Unpin>(stream: &mut Option<S>) -> Option<S::Item> {
+    match stream {
+        Some(stream) => stream.next().await,
+        None => pending().await,
+    }
+}
+
+pub async fn make_attestation(
+    beacon_api_client: Arc<BeaconApiClient>,
+    signer: Arc<dyn Signer>,
+    signing_context: SigningContext,
+    attestation_data: AttestationData,
+    validator_index: u64,
+    committee_index: u64,
+) -> anyhow::Result<()> {
+    let timer = start_timer_vec(&VALIDATOR_SIGNING_TIME, &[DutyKind::Attester.name()]);
+    let signature = sign(
+        signer.as_ref(),
+        &signing_context,
+        SignatureKind::Attestation,
+        &attestation_data,
+    )
+    .await?;
+    stop_timer(timer);
+    Ok(beacon_api_client
+        .submit_attestation(vec![SingleAttestation {
+            attester_index: validator_index,
+            committee_index,
+            signature,
+            data: attestation_data,
+        }])
+        .await?)
+}
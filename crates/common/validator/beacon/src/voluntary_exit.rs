@@ -1,24 +1,34 @@
 use std::time::Duration;
 
-use anyhow::{anyhow, bail};
+use alloy_primitives::B256;
+use anyhow::{Context, bail};
 use ream_api_types_beacon::{error::ValidatorError, id::ValidatorID, validator::ValidatorStatus};
 use ream_api_types_common::id::ID;
-use ream_bls::{PrivateKey, traits::Signable};
 use ream_consensus_beacon::voluntary_exit::{SignedVoluntaryExit, VoluntaryExit};
-use ream_consensus_misc::{
-    constants::beacon::DOMAIN_VOLUNTARY_EXIT,
-    misc::{compute_domain, compute_signing_root},
-};
 use ream_network_spec::networks::beacon_network_spec;
+use ream_signing::{
+    domain::{SignatureKind, SigningContext},
+    signer::{Signer, sign},
+};
 use tokio::time::sleep;
 use tracing::info;
 
 use crate::beacon_api_client::BeaconApiClient;
 
-pub fn sign_voluntary_exit(
+/// The context which voluntary exits are signed under. Since Deneb the exits are signed with the
+/// Capella fork version whatever the current fork, so an exit signed once stays valid (EIP-7044).
+pub fn voluntary_exit_signing_context(genesis_validators_root: Option<B256>) -> SigningContext {
+    SigningContext::new(
+        beacon_network_spec().capella_fork_version,
+        genesis_validators_root,
+    )
+}
+
+pub async fn sign_voluntary_exit(
+    signer: &dyn Signer,
+    signing_context: &SigningContext,
     epoch: u64,
     validator_index: u64,
-    private_key: &PrivateKey,
 ) -> anyhow::Result<SignedVoluntaryExit> {
     let voluntary_exit = VoluntaryExit {
         epoch,
@@ -26,29 +36,22 @@ pub fn sign_voluntary_exit(
     };
 
     Ok(SignedVoluntaryExit {
-        signature: private_key
-            .sign(
-                compute_signing_root(
-                    &voluntary_exit,
-                    compute_domain(
-                        DOMAIN_VOLUNTARY_EXIT,
-                        Some(beacon_network_spec().electra_fork_version),
-                        None,
-                    ),
-                )
-                .as_ref(),
-            )
-            .map_err(|err| anyhow!("Failed to sign voluntary exit: {err}"))?,
+        signature: sign(
+            signer,
+            signing_context,
+            SignatureKind::VoluntaryExit,
+            &voluntary_exit,
+        )
+        .await
+        .context("Failed to sign voluntary exit")?,
         message: voluntary_exit,
     })
 }
 
-pub async fn process_voluntary_exit(
+/// Broadcasts `signed_voluntary_exit` through the beacon node.
+pub async fn submit_voluntary_exit(
     beacon_api_client: &BeaconApiClient,
-    validator_index: u64,
-    epoch: u64,
-    private_key: &PrivateKey,
-    wait_till_exit: bool,
+    signed_voluntary_exit: SignedVoluntaryExit,
 ) -> anyhow::Result<()> {
     if beacon_api_client
         .get_node_syncing_status()
@@ -59,38 +62,56 @@ pub async fn process_voluntary_exit(
         bail!("Cannot process voluntary exit while node is syncing");
     }
 
+    let validator_index = signed_voluntary_exit.message.validator_index;
     if let Err(err) = beacon_api_client
-        .submit_signed_voluntary_exit(sign_voluntary_exit(epoch, validator_index, private_key)?)
+        .submit_signed_voluntary_exit(signed_voluntary_exit)
         .await
     {
         match err {
             ValidatorError::RequestFailedWithMessage { message, .. } => {
-                bail!("Failed to submit voluntary exit: {message}");
+                bail!("Failed to submit voluntary exit of validator {validator_index}: {message}");
             }
-            _ => bail!("Failed to submit voluntary exit: {err}"),
+            _ => bail!("Failed to submit voluntary exit of validator {validator_index}: {err}"),
         }
     }
 
-    if wait_till_exit {
-        loop {
-            sleep(Duration::from_secs(beacon_network_spec().seconds_per_slot)).await;
-            match beacon_api_client
-                .get_state_validator(ID::Head, ValidatorID::Index(validator_index))
-                .await?
-                .data
-                .status
-            {
+    Ok(())
+}
+
+/// Polls the beacon node once per slot until all of `validator_indices` have exited.
+pub async fn wait_for_exits(
+    beacon_api_client: &BeaconApiClient,
+    validator_indices: &[u64],
+) -> anyhow::Result<()> {
+    let mut pending = validator_indices.to_vec();
+    while !pending.is_empty() {
+        sleep(Duration::from_secs(beacon_network_spec().seconds_per_slot)).await;
+        let validators = beacon_api_client
+            .get_state_validator_list(
+                ID::Head,
+                Some(pending.iter().copied().map(ValidatorID::Index).collect()),
+                None,
+            )
+            .await?
+            .data;
+
+        for validator in validators {
+            match validator.status {
                 ValidatorStatus::ActiveExiting => {
                     info!(
-                        "Voluntary exit has been published to beacon chain but validator has not yet exited."
+                        "Voluntary exit of validator {} has been published to beacon chain but validator has not yet exited.",
+                        validator.index
                     );
                 }
                 ValidatorStatus::ExitedSlashed | ValidatorStatus::ExitedUnslashed => {
-                    info!("Validator has successfully exited");
-                    break;
+                    info!("Validator {} has successfully exited", validator.index);
+                    pending.retain(|index| *index != validator.index);
                 }
                 _ => {
-                    info!("Voluntary exit has not yet been published to beacon chain.");
+                    info!(
+                        "Voluntary exit of validator {} has not yet been published to beacon chain.",
+                        validator.index
+                    );
                 }
             }
         }
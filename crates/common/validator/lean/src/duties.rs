@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use ream_consensus_misc::constants::lean::{INTERVALS_PER_SLOT, SLOT_OFFSET};
+
+/// The interval of a slot in which its proposer builds and publishes the block.
+pub const PROPOSAL_INTERVAL: u64 = 0;
+/// The interval of a slot in which the validators vote for the head.
+pub const VOTE_INTERVAL: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Duty {
+    Propose,
+    Vote,
+}
+
+/// A point of the lean chain's clock, as a slot and an interval within it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotTime {
+    pub slot: u64,
+    pub interval: u64,
+}
+
+/// DutyScheduler maps the wall clock onto the duties of the local validators.
+///
+/// The slot and interval are derived from the genesis time on every tick rather than counted, so a
+/// delayed or skipped tick can't shift the duties into the wrong slot. A duty is only performed in
+/// its own interval and at most once per slot; a duty whose interval has passed is skipped.
+#[derive(Debug, Clone)]
+pub struct DutyScheduler {
+    genesis_time: Duration,
+    interval_duration: Duration,
+    num_validators: u64,
+    last_duty: Option<(u64, Duty)>,
+}
+
+impl DutyScheduler {
+    pub fn new(genesis_time: u64, seconds_per_slot: u64, num_validators: u64) -> Self {
+        Self {
+            genesis_time: Duration::from_secs(genesis_time),
+            interval_duration: Duration::from_secs(seconds_per_slot) / INTERVALS_PER_SLOT as u32,
+            num_validators,
+            last_duty: None,
+        }
+    }
+
+    /// Returns the slot and interval whose start is the closest to `now`, the time since the
+    /// UNIX epoch, or None before genesis.
+    ///
+    /// Rounding to the closest interval start keeps a tick which fires marginally before the
+    /// boundary from being attributed to the previous interval.
+    pub fn slot_time(&self, now: Duration) -> Option<SlotTime> {
+        let elapsed = (now + self.interval_duration / 2).checked_sub(self.genesis_time)?;
+        let intervals = (elapsed.as_millis() / self.interval_duration.as_millis()) as u64;
+
+        Some(SlotTime {
+            slot: intervals / INTERVALS_PER_SLOT + SLOT_OFFSET,
+            interval: intervals % INTERVALS_PER_SLOT,
+        })
+    }
+
+    /// The validator which proposes the block of `slot`, in round robin order.
+    pub fn proposer_index(&self, slot: u64) -> u64 {
+        slot % self.num_validators
+    }
+
+    /// Returns the duty due at `now` together with its slot, if it hasn't been performed yet.
+    pub fn next_duty(&mut self, now: Duration) -> Option<(u64, Duty)> {
+        let SlotTime { slot, interval } = self.slot_time(now)?;
+        let duty = match interval {
+            PROPOSAL_INTERVAL => Duty::Propose,
+            VOTE_INTERVAL => Duty::Vote,
+            _ => return None,
+        };
+
+        if self
+            .last_duty
+            .is_some_and(|last_duty| last_duty >= (slot, duty))
+        {
+            return None;
+        }
+        self.last_duty = Some((slot, duty));
+        Some((slot, duty))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS_TIME: u64 = 1_000;
+
+    fn at(seconds: u64, millis: u64) -> Duration {
+        Duration::from_secs(GENESIS_TIME + seconds) + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_slot_time() {
+        let scheduler = DutyScheduler::new(GENESIS_TIME, 4, 4);
+
+        assert_eq!(
+            scheduler.slot_time(Duration::from_secs(GENESIS_TIME - 1)),
+            None
+        );
+        assert_eq!(
+            scheduler.slot_time(at(0, 0)),
+            Some(SlotTime {
+                slot: SLOT_OFFSET,
+                interval: 0
+            })
+        );
+        // A tick firing just before the boundary belongs to the next interval
+        assert_eq!(
+            scheduler.slot_time(at(4, 0) - Duration::from_millis(1)),
+            Some(SlotTime {
+                slot: SLOT_OFFSET + 1,
+                interval: 0
+            })
+        );
+        assert_eq!(
+            scheduler.slot_time(at(7, 100)),
+            Some(SlotTime {
+                slot: SLOT_OFFSET + 1,
+                interval: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_next_duty() {
+        let mut scheduler = DutyScheduler::new(GENESIS_TIME, 4, 4);
+        assert_eq!(scheduler.proposer_index(SLOT_OFFSET + 4), SLOT_OFFSET);
+
+        assert_eq!(
+            scheduler.next_duty(at(0, 0)),
+            Some((SLOT_OFFSET, Duty::Propose))
+        );
+        // A duty is performed once per slot
+        assert_eq!(scheduler.next_duty(at(0, 100)), None);
+        assert_eq!(
+            scheduler.next_duty(at(1, 0)),
+            Some((SLOT_OFFSET, Duty::Vote))
+        );
+        assert_eq!(scheduler.next_duty(at(2, 0)), None);
+
+        // The vote of a slot is skipped when its interval has passed
+        assert_eq!(
+            scheduler.next_duty(at(4, 0)),
+            Some((SLOT_OFFSET + 1, Duty::Propose))
+        );
+        assert_eq!(scheduler.next_duty(at(6, 0)), None);
+        assert_eq!(
+            scheduler.next_duty(at(8, 0)),
+            Some((SLOT_OFFSET + 2, Duty::Propose))
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod duties;
 pub mod registry;
 pub mod service;
 pub mod validator;
@@ -1,6 +1,15 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
+use anyhow::anyhow;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use ream_post_quantum_crypto::hashsig::{private_key::PrivateKey, public_key::PublicKey};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::warn;
 
 /// YAML structure for node-based validator mapping
 /// Example:
@@ -59,3 +68,114 @@ pub fn load_validator_registry<P: AsRef<Path>>(
         .map(|&id| LeanKeystore { validator_id: id })
         .collect())
 }
+
+/// Load the hash-based signature public keys of all validators from a YAML file, which maps each
+/// validator ID to its public key as printed by the account manager.
+///
+/// # Arguments
+/// * `path` - Path to the validator public keys YAML file
+pub fn load_validator_public_keys<P: AsRef<Path>>(
+    path: P,
+) -> anyhow::Result<HashMap<u64, PublicKey>> {
+    let content = fs::read_to_string(&path).map_err(|err| {
+        anyhow::anyhow!(
+            "Failed to read validator public keys file {:?}: {err}",
+            path.as_ref(),
+        )
+    })?;
+
+    serde_yaml::from_str::<HashMap<u64, PublicKey>>(&content)
+        .map_err(|err| anyhow::anyhow!("Failed to parse validator public keys YAML: {err}"))
+}
+
+/// The hash-based signature private keys of a validator. Blocks and votes are signed with separate
+/// keys, since signing both with the one-time key of the same slot would leak that key.
+#[derive(Deserialize)]
+pub struct ValidatorPrivateKeys {
+    pub attestation: PrivateKey,
+    pub block: PrivateKey,
+}
+
+/// Load the hash-based signature private keys of the validators of this node from a YAML file,
+/// which maps each validator ID to its attestation and block private keys.
+///
+/// # Arguments
+/// * `path` - Path to the validator private keys YAML file
+pub fn load_validator_private_keys<P: AsRef<Path>>(
+    path: P,
+) -> anyhow::Result<HashMap<u64, ValidatorPrivateKeys>> {
+    let content = fs::read_to_string(&path).map_err(|err| {
+        anyhow::anyhow!(
+            "Failed to read validator private keys file {:?}: {err}",
+            path.as_ref(),
+        )
+    })?;
+
+    serde_yaml::from_str::<HashMap<u64, ValidatorPrivateKeys>>(&content)
+        .map_err(|err| anyhow::anyhow!("Failed to parse validator private keys YAML: {err}"))
+}
+
+/// Watches the validator registry file and reloads the keystores of a node whenever it changes.
+///
+/// The directory of the registry is watched rather than the file itself, so that editors which
+/// replace the file on save are picked up too. A registry which fails to load, e.g. while it is
+/// being written, is skipped and the previous keystores are kept.
+pub struct RegistryWatcher {
+    // Dropping the watcher stops the notifications
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::UnboundedReceiver<Vec<LeanKeystore>>,
+}
+
+impl RegistryWatcher {
+    pub fn new(path: PathBuf, node_id: String) -> anyhow::Result<Self> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("Validator registry path {path:?} is not a file"))?
+            .to_owned();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let registry_path = path.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let event = match event {
+                Ok(event) => event,
+                Err(err) => {
+                    warn!("Failed to watch validator registry: {err}");
+                    return;
+                }
+            };
+            if !(event.kind.is_create() || event.kind.is_modify())
+                || !event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == Some(file_name.as_os_str()))
+            {
+                return;
+            }
+
+            match load_validator_registry(&registry_path, &node_id) {
+                Ok(keystores) => {
+                    // The receiver is only dropped when the validator service stops
+                    let _ = sender.send(keystores);
+                }
+                Err(err) => {
+                    warn!("Keeping the current validators, failed to reload registry: {err}")
+                }
+            }
+        })?;
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            receiver,
+        })
+    }
+
+    /// Waits for the keystores of the next change of the registry.
+    pub async fn next(&mut self) -> Option<Vec<LeanKeystore>> {
+        self.receiver.recv().await
+    }
+}
@@ -1,148 +1,248 @@
-use alloy_primitives::FixedBytes;
-use anyhow::Context;
+use std::collections::HashMap;
+
+use alloy_primitives::{B256, FixedBytes};
 use ream_chain_lean::{
-    clock::create_lean_clock_interval, lean_chain::LeanChainReader,
-    messages::LeanChainServiceMessage,
+    clock::lean_slot_clock, lean_chain::LeanChainReader, messages::LeanChainServiceMessage,
 };
 use ream_consensus_lean::{block::SignedBlock, vote::SignedVote};
 use ream_network_spec::networks::lean_network_spec;
+use ream_post_quantum_crypto::hashsig::private_key::PrivateKey;
 use tokio::sync::{mpsc, oneshot};
-use tracing::info;
+use tracing::{info, warn};
 use tree_hash::TreeHash;
 
-use crate::registry::LeanKeystore;
+use crate::{
+    duties::{Duty, DutyScheduler},
+    registry::{LeanKeystore, RegistryWatcher, ValidatorPrivateKeys},
+};
 
 /// ValidatorService is responsible for managing validator operations
 /// such as proposing blocks and voting on them. This service also holds the keystores
 /// for its validators, which are used to sign.
 ///
-/// Every first tick (t=0) it proposes a block if it's the validator's turn.
-/// Every second tick (t=1/4) it votes on the proposed block.
+/// The duties are scheduled from the wall clock by [DutyScheduler]: in the proposal interval
+/// (t=0) it proposes a block if it's the validator's turn, and in the vote interval (t=1/4) it
+/// votes on the proposed block.
+///
+/// If a [RegistryWatcher] is given, the keystores are swapped for the reloaded set between two
+/// ticks whenever the validator registry changes, so no duty sees a partially applied registry.
+///
+/// Blocks and votes are signed with the private keys of the validators, each with the one-time key
+/// of its slot. Validators without private keys send them unsigned, which only nodes that don't
+/// verify signatures accept.
 ///
 /// NOTE: Other ticks should be handled by the other services, such as [LeanChainService].
 pub struct ValidatorService {
     lean_chain: LeanChainReader,
     keystores: Vec<LeanKeystore>,
+    private_keys: HashMap<u64, ValidatorPrivateKeys>,
     chain_sender: mpsc::UnboundedSender<LeanChainServiceMessage>,
+    registry_watcher: Option<RegistryWatcher>,
 }
 
 impl ValidatorService {
     pub async fn new(
         lean_chain: LeanChainReader,
         keystores: Vec<LeanKeystore>,
+        private_keys: HashMap<u64, ValidatorPrivateKeys>,
         chain_sender: mpsc::UnboundedSender<LeanChainServiceMessage>,
+        registry_watcher: Option<RegistryWatcher>,
     ) -> Self {
         ValidatorService {
             lean_chain,
             keystores,
+            private_keys,
             chain_sender,
+            registry_watcher,
         }
     }
 
-    pub async fn start(self) -> anyhow::Result<()> {
+    pub async fn start(mut self) -> anyhow::Result<()> {
+        let network_spec = lean_network_spec();
         info!(
             "ValidatorService started with {} validator(s), genesis_time: {}",
             self.keystores.len(),
-            lean_network_spec().genesis_time
+            network_spec.genesis_time
         );
 
-        let mut tick_count = 0u64;
-
-        // Start from slot 0, will be incremented for every slot boundary.
-        let mut slot = 0;
+        let mut scheduler = DutyScheduler::new(
+            network_spec.genesis_time,
+            network_spec.seconds_per_slot,
+            network_spec.num_validators,
+        );
 
-        let mut interval =
-            create_lean_clock_interval().context("Failed to create clock interval")?;
+        let clock = lean_slot_clock();
+        let mut ticks = clock.ticks();
+        let mut registry_watcher = self.registry_watcher.take();
 
         loop {
             tokio::select! {
-                _ = interval.tick() => {
-                    match tick_count % 4 {
-                        0 => {
-                            slot += 1;
-
-                            // First tick (t=0): Propose a block.
-                            if let Some(keystore) = self.is_proposer(slot) {
-                                info!("Validator {} proposing block for slot {slot} (tick {tick_count})", keystore.validator_id);
-
-                                let (tx, rx) = oneshot::channel();
-                                self.chain_sender
-                                    .send(LeanChainServiceMessage::ProduceBlock { slot, sender: tx })
-                                    .expect("Failed to send vote to LeanChainService");
-
-                                // Wait for the block to be produced.
-                                let new_block = rx.await.expect("Failed to receive block from LeanChainService");
-
-                                info!(
-                                    "Validator {} built block: slot={}, root={:?}, parent={:?}, votes={}, state_root={:?}",
-                                    keystore.validator_id,
-                                    new_block.slot,
-                                    new_block.tree_hash_root(),
-                                    new_block.parent_root,
-                                    new_block.body.attestations.len(),
-                                    new_block.state_root
-                                );
-
-                                // TODO: Sign the block with the keystore.
-                                let signed_block = SignedBlock {
-                                    message: new_block,
-                                    signature: FixedBytes::default(),
-                                };
-
-                                // Send block to the LeanChainService.
-                                self.chain_sender
-                                    .send(LeanChainServiceMessage::ProcessBlock { signed_block, is_trusted: true, need_gossip: true })
-                                    .expect("Failed to send block to LeanChainService");
-                            } else {
-                                let proposer_index = slot % lean_network_spec().num_validators;
-                                info!("Not proposer for slot {slot} (proposer is validator {proposer_index}), skipping");
-                            }
-                        }
-                        1 => {
-                            // Second tick (t=1/4): Vote.
-                            info!("Starting vote phase at slot {slot} (tick {tick_count}): {} validator(s) voting", self.keystores.len());
-
-                            // Build the vote from LeanChain, and modify its validator ID
-                            let vote_template = self.lean_chain.read().await.build_vote(slot).await.expect("Failed to build vote");
-                            info!(
-                                "Built vote template for head={:?}, slot={}, source={:?}, target={:?}",
-                                vote_template.head,
-                                vote_template.slot,
-                                vote_template.source,
-                                vote_template.target
-                            );
-
-                            // TODO: Sign the vote with the keystore.
-                            let signed_votes = self.keystores.iter().map(|keystore| {
-                                SignedVote {
-                                    validator_id: keystore.validator_id,
-                                    message: vote_template.clone(),
-                                    signature: FixedBytes::default(),
-                                }
-                            }).collect::<Vec<_>>();
-
-                            for signed_vote in signed_votes {
-                                self.chain_sender
-                                    .send(LeanChainServiceMessage::ProcessVote { signed_vote, is_trusted: true, need_gossip: true })
-                                    .expect("Failed to send vote to LeanChainService");
+                Some(keystores) = next_registry_update(&mut registry_watcher) => {
+                    self.reload_keystores(keystores);
+                }
+                _ = ticks.next() => {
+                    match scheduler.next_duty(clock.now()) {
+                        Some((slot, Duty::Propose)) => {
+                            let proposer_index = scheduler.proposer_index(slot);
+                            match self.keystores.iter().find(|keystore| keystore.validator_id == proposer_index) {
+                                Some(keystore) => self.propose_block(slot, keystore).await,
+                                None => info!("Not proposer for slot {slot} (proposer is validator {proposer_index}), skipping"),
                             }
                         }
-                        _ => {
-                            // Other ticks (t=2/4, t=3/4): Do nothing.
+                        Some((slot, Duty::Vote)) => self.vote(slot).await,
+                        None => {
+                            // Other intervals are handled by the other services, or the duty of
+                            // this interval has already been performed.
                         }
                     }
-                    tick_count += 1;
                 }
             }
         }
     }
 
-    /// Determine if one of the keystores is the proposer for the current slot.
-    fn is_proposer(&self, slot: u64) -> Option<&LeanKeystore> {
-        let proposer_index = slot % lean_network_spec().num_validators;
+    fn reload_keystores(&mut self, keystores: Vec<LeanKeystore>) {
+        let validator_ids = |keystores: &[LeanKeystore]| {
+            keystores
+                .iter()
+                .map(|keystore| keystore.validator_id)
+                .collect::<Vec<_>>()
+        };
+        let (previous_ids, new_ids) = (validator_ids(&self.keystores), validator_ids(&keystores));
+        if previous_ids == new_ids {
+            return;
+        }
+
+        info!("Validator registry reloaded: validators {previous_ids:?} -> {new_ids:?}");
+        self.keystores = keystores;
+    }
+
+    async fn propose_block(&self, slot: u64, keystore: &LeanKeystore) {
+        info!(
+            "Validator {} proposing block for slot {slot}",
+            keystore.validator_id
+        );
+
+        let (tx, rx) = oneshot::channel();
+        self.chain_sender
+            .send(LeanChainServiceMessage::ProduceBlock { slot, sender: tx })
+            .expect("Failed to send vote to LeanChainService");
+
+        // Wait for the block to be produced.
+        let new_block = rx
+            .await
+            .expect("Failed to receive block from LeanChainService");
+
+        info!(
+            "Validator {} built block: slot={}, root={:?}, parent={:?}, votes={}, state_root={:?}",
+            keystore.validator_id,
+            new_block.slot,
+            new_block.tree_hash_root(),
+            new_block.parent_root,
+            new_block.body.attestations.len(),
+            new_block.state_root
+        );
+
+        let signature = self.sign(
+            keystore.validator_id,
+            |private_keys| &private_keys.block,
+            new_block.tree_hash_root(),
+            slot,
+        );
+        let signed_block = SignedBlock {
+            message: new_block,
+            signature,
+        };
+
+        // Send block to the LeanChainService.
+        self.chain_sender
+            .send(LeanChainServiceMessage::ProcessBlock {
+                signed_block,
+                is_trusted: true,
+                need_gossip: true,
+            })
+            .expect("Failed to send block to LeanChainService");
+    }
+
+    async fn vote(&self, slot: u64) {
+        info!(
+            "Starting vote phase at slot {slot}: {} validator(s) voting",
+            self.keystores.len()
+        );
+
+        // Build the vote from LeanChain, and modify its validator ID
+        let vote_template = self
+            .lean_chain
+            .read()
+            .await
+            .build_vote(slot)
+            .await
+            .expect("Failed to build vote");
+        info!(
+            "Built vote template for head={:?}, slot={}, source={:?}, target={:?}",
+            vote_template.head, vote_template.slot, vote_template.source, vote_template.target
+        );
+
+        let vote_root = vote_template.tree_hash_root();
+        for keystore in &self.keystores {
+            let signed_vote = SignedVote {
+                validator_id: keystore.validator_id,
+                message: vote_template.clone(),
+                signature: self.sign(
+                    keystore.validator_id,
+                    |private_keys| &private_keys.attestation,
+                    vote_root,
+                    slot,
+                ),
+            };
+            self.chain_sender
+                .send(LeanChainServiceMessage::ProcessVote {
+                    signed_vote,
+                    is_trusted: true,
+                    need_gossip: true,
+                })
+                .expect("Failed to send vote to LeanChainService");
+        }
+    }
+
+    /// Signs `root` with the one-time key of `slot` of the private key selected by `private_key`,
+    /// or returns an empty signature if the validator has no private keys or signing fails.
+    fn sign(
+        &self,
+        validator_id: u64,
+        private_key: impl Fn(&ValidatorPrivateKeys) -> &PrivateKey,
+        root: B256,
+        slot: u64,
+    ) -> FixedBytes<4000> {
+        let Some(private_keys) = self.private_keys.get(&validator_id) else {
+            if !self.private_keys.is_empty() {
+                warn!("No private keys for validator {validator_id}, sending it unsigned");
+            }
+            return FixedBytes::default();
+        };
+
+        match sign_root(private_key(private_keys), root, slot) {
+            Ok(signature) => signature,
+            Err(err) => {
+                warn!("Failed to sign for validator {validator_id} at slot {slot}: {err:?}");
+                FixedBytes::default()
+            }
+        }
+    }
+}
+
+fn sign_root(private_key: &PrivateKey, root: B256, slot: u64) -> anyhow::Result<FixedBytes<4000>> {
+    let signature = private_key.sign(&mut rand::rng(), &root.0, u32::try_from(slot)?)?;
+    Ok(FixedBytes::from_slice(
+        &signature.to_padded_bytes(FixedBytes::<4000>::len_bytes())?,
+    ))
+}
 
-        self.keystores
-            .iter()
-            .find(|keystore| keystore.validator_id == proposer_index as u64)
+/// Waits for the next reload of the registry, or forever if it isn't watched.
+async fn next_registry_update(
+    registry_watcher: &mut Option<RegistryWatcher>,
+) -> Option<Vec<LeanKeystore>> {
+    match registry_watcher {
+        Some(registry_watcher) => registry_watcher.next().await,
+        None => std::future::pending().await,
     }
 }
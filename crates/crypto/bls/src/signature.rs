@@ -5,11 +5,21 @@ use ssz_derive::{Decode, Encode};
 use ssz_types::{FixedVector, typenum::U96};
 use tree_hash_derive::TreeHash;
 
+use crate::PublicKey;
+
 #[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, Hash, TreeHash, Default)]
 pub struct BLSSignature {
     pub inner: FixedVector<u8, U96>,
 }
 
+/// A signature together with the public key and message it should be valid for.
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureSet<'a> {
+    pub signature: &'a BLSSignature,
+    pub public_key: &'a PublicKey,
+    pub message: &'a [u8],
+}
+
 impl Serialize for BLSSignature {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use blst::{
-    BLST_ERROR,
+    BLST_ERROR, blst_scalar,
     min_pk::{AggregateSignature as BlstAggregateSignature, Signature as BlstSignature},
 };
 use ssz_types::FixedVector;
@@ -9,10 +9,16 @@ use crate::{
     constants::DST,
     errors::BLSError,
     public_key::PublicKey,
-    signature::BLSSignature,
-    traits::{Aggregatable, SupranationalAggregatable, SupranationalVerifiable, Verifiable},
+    signature::{BLSSignature, SignatureSet},
+    traits::{
+        Aggregatable, BatchVerifiable, SupranationalAggregatable, SupranationalBatchVerifiable,
+        SupranationalVerifiable, Verifiable,
+    },
 };
 
+/// The number of bits of the random scalars each signature of a batch is weighted with
+const BATCH_RANDOM_BITS: usize = 64;
+
 impl BLSSignature {
     pub fn to_blst_signature(&self) -> Result<BlstSignature, BLSError> {
         BlstSignature::from_bytes(&self.inner).map_err(|err| BLSError::BlstError(err.into()))
@@ -63,6 +69,55 @@ impl Verifiable for BLSSignature {
     }
 }
 
+impl BatchVerifiable for BLSSignature {
+    type Error = BLSError;
+
+    fn verify_batch(signature_sets: &[SignatureSet]) -> Result<bool, BLSError> {
+        if signature_sets.is_empty() {
+            return Ok(true);
+        }
+
+        let signatures = signature_sets
+            .iter()
+            .map(|set| set.signature.to_blst_signature())
+            .collect::<Result<Vec<_>, _>>()?;
+        let public_keys = signature_sets
+            .iter()
+            .map(|set| set.public_key.to_blst_public_key())
+            .collect::<Result<Vec<_>, _>>()?;
+        let messages = signature_sets
+            .iter()
+            .map(|set| set.message)
+            .collect::<Vec<_>>();
+
+        // Weighting each signature with a random non-zero scalar keeps invalid signatures from
+        // cancelling each other out
+        let random_scalars = signature_sets
+            .iter()
+            .map(|_| {
+                let mut random = 0;
+                while random == 0 {
+                    random = rand::random::<u64>();
+                }
+                let mut scalar = blst_scalar::default();
+                scalar.b[..8].copy_from_slice(&random.to_le_bytes());
+                scalar
+            })
+            .collect::<Vec<_>>();
+
+        Ok(BlstSignature::verify_multiple_aggregate_signatures(
+            &messages,
+            DST,
+            &public_keys.iter().collect::<Vec<_>>(),
+            false,
+            &signatures.iter().collect::<Vec<_>>(),
+            true,
+            &random_scalars,
+            BATCH_RANDOM_BITS,
+        ) == BLST_ERROR::BLST_SUCCESS)
+    }
+}
+
 impl Aggregatable<BLSSignature> for BLSSignature {
     type Error = anyhow::Error;
 
@@ -83,3 +138,5 @@ impl Aggregatable<BLSSignature> for BLSSignature {
 impl SupranationalAggregatable<BLSSignature> for BLSSignature {}
 
 impl SupranationalVerifiable for BLSSignature {}
+
+impl SupranationalBatchVerifiable for BLSSignature {}
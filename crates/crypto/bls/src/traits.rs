@@ -1,4 +1,4 @@
-use crate::{BLSSignature, PublicKey, errors::BLSError};
+use crate::{BLSSignature, PublicKey, errors::BLSError, signature::SignatureSet};
 
 /// Trait for aggregating BLS public keys.
 ///
@@ -46,6 +46,23 @@ pub trait ZkcryptoSignable: Signable<Error = BLSError> {}
 /// Marker trait for supranational/blst BLS signing implementation
 pub trait SupranationalSignable: Signable<Error = anyhow::Error> {}
 
+/// Trait for deriving the BLS public key of a private key.
+pub trait PublicKeyDerivable {
+    type Error;
+
+    /// Derives the public key of the private key.
+    ///
+    /// # Returns
+    /// * `Result<PublicKey, Self::Error>` - The BLS public key or an error
+    fn public_key(&self) -> Result<PublicKey, Self::Error>;
+}
+
+/// Marker trait for zkcrypto/bls12_381 BLS public key derivation implementation
+pub trait ZkcryptoPublicKeyDerivable: PublicKeyDerivable<Error = BLSError> {}
+
+/// Marker trait for supranational/blst BLS public key derivation implementation
+pub trait SupranationalPublicKeyDerivable: PublicKeyDerivable<Error = anyhow::Error> {}
+
 /// Trait for verifying BLS signatures.
 ///
 /// This trait provides functionality to verify both individual and aggregate BLS signatures
@@ -89,3 +106,27 @@ pub trait ZkcryptoVerifiable: Verifiable<Error = BLSError> {}
 
 /// Marker trait for supranational/blst BLS signature verification implementation
 pub trait SupranationalVerifiable: Verifiable<Error = BLSError> {}
+
+/// Trait for verifying many BLS signatures at once.
+///
+/// Verifying a batch costs about as much as verifying a single signature plus one pairing per
+/// message, but only tells whether every signature of the batch is valid.
+pub trait BatchVerifiable {
+    type Error;
+
+    /// Verifies that each signature is valid for its public key and message.
+    ///
+    /// # Arguments
+    /// * `signature_sets` - The signatures with the public key and message each one signs
+    ///
+    /// # Returns
+    /// * `Result<bool, BLSError>` - Ok(true) if every signature is valid, Ok(false) if any of them
+    ///   isn't, or Err if there are issues with signature or public key bytes
+    fn verify_batch(signature_sets: &[SignatureSet]) -> Result<bool, Self::Error>;
+}
+
+/// Marker trait for zkcrypto/bls12_381 BLS batch verification implementation
+pub trait ZkcryptoBatchVerifiable: BatchVerifiable<Error = BLSError> {}
+
+/// Marker trait for supranational/blst BLS batch verification implementation
+pub trait SupranationalBatchVerifiable: BatchVerifiable<Error = BLSError> {}
@@ -1,18 +1,31 @@
 use bls12_381::{
-    G2Projective, Scalar,
+    G1Projective, G2Projective, Scalar,
     hash_to_curve::{ExpandMsgXmd, HashToCurve},
 };
 use group::Curve;
 use ssz_types::FixedVector;
 
 use crate::{
-    PrivateKey,
+    PrivateKey, PublicKey,
     constants::DST,
     errors::BLSError,
     signature::BLSSignature,
-    traits::{Signable, ZkcryptoSignable},
+    traits::{PublicKeyDerivable, Signable, ZkcryptoPublicKeyDerivable, ZkcryptoSignable},
 };
 
+impl TryFrom<&PrivateKey> for Scalar {
+    type Error = BLSError;
+
+    /// The private key is stored big-endian, as in the keystores, while [Scalar] is little-endian.
+    fn try_from(value: &PrivateKey) -> Result<Self, Self::Error> {
+        let mut bytes = value.inner.0;
+        bytes.reverse();
+        Scalar::from_bytes(&bytes)
+            .into_option()
+            .ok_or(BLSError::InvalidPrivateKey)
+    }
+}
+
 impl Signable for PrivateKey {
     type Error = BLSError;
 
@@ -22,9 +35,7 @@ impl Signable for PrivateKey {
             DST,
         );
 
-        let scalar = Scalar::from_bytes(self.inner.as_ref())
-            .into_option()
-            .ok_or(BLSError::InvalidPrivateKey)?;
+        let scalar = Scalar::try_from(self)?;
         let signature_point = hash_point * scalar;
         let signature_bytes = signature_point.to_affine().to_compressed();
 
@@ -36,3 +47,15 @@ impl Signable for PrivateKey {
 }
 
 impl ZkcryptoSignable for PrivateKey {}
+
+impl PublicKeyDerivable for PrivateKey {
+    type Error = BLSError;
+
+    fn public_key(&self) -> Result<PublicKey, Self::Error> {
+        Ok(PublicKey::from(
+            G1Projective::generator() * Scalar::try_from(self)?,
+        ))
+    }
+}
+
+impl ZkcryptoPublicKeyDerivable for PrivateKey {}
@@ -8,7 +8,11 @@ use crate::{
     BLSSignature, PublicKey,
     constants::DST,
     errors::BLSError,
-    traits::{Aggregatable, Verifiable, ZkcryptoAggregatable, ZkcryptoVerifiable},
+    signature::SignatureSet,
+    traits::{
+        Aggregatable, BatchVerifiable, Verifiable, ZkcryptoAggregatable, ZkcryptoBatchVerifiable,
+        ZkcryptoVerifiable,
+    },
 };
 
 impl TryFrom<&BLSSignature> for G2Affine {
@@ -72,6 +76,19 @@ impl Verifiable for BLSSignature {
     }
 }
 
+impl BatchVerifiable for BLSSignature {
+    type Error = BLSError;
+
+    fn verify_batch(signature_sets: &[SignatureSet]) -> Result<bool, BLSError> {
+        for set in signature_sets {
+            if !set.signature.verify(set.public_key, set.message)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
 impl Aggregatable<BLSSignature> for BLSSignature {
     type Error = BLSError;
 
@@ -90,3 +107,5 @@ impl Aggregatable<BLSSignature> for BLSSignature {
 impl ZkcryptoAggregatable<BLSSignature> for BLSSignature {}
 
 impl ZkcryptoVerifiable for BLSSignature {}
+
+impl ZkcryptoBatchVerifiable for BLSSignature {}
@@ -0,0 +1,153 @@
+//! BLS12-381 key derivation from a seed and an EIP-2334 path.
+//!
+//! https://eips.ethereum.org/EIPS/eip-2333
+//! https://eips.ethereum.org/EIPS/eip-2334
+
+use alloy_primitives::{B256, U256, aliases::U384};
+use anyhow::{anyhow, ensure};
+use ream_bls::PrivateKey;
+use sha2::{Digest, Sha256};
+
+use crate::{hmac::hmac_sha_256, interop::CURVE_ORDER};
+
+/// The number of 32 byte chunks of a Lamport private key.
+const LAMPORT_CHUNKS: usize = 255;
+
+/// The purpose of the EIP-2334 paths of BLS12-381 keys.
+pub const BLS_PURPOSE: u32 = 12381;
+/// The coin type of the EIP-2334 paths of Ethereum validators.
+pub const ETH_COIN_TYPE: u32 = 3600;
+
+/// The EIP-2334 path of the signing key of the validator at `index`.
+pub fn signing_key_path(index: u32) -> String {
+    format!("m/{BLS_PURPOSE}/{ETH_COIN_TYPE}/{index}/0/0")
+}
+
+/// The EIP-2334 path of the withdrawal key of the validator at `index`.
+pub fn withdrawal_key_path(index: u32) -> String {
+    format!("m/{BLS_PURPOSE}/{ETH_COIN_TYPE}/{index}/0")
+}
+
+fn hkdf_expand(prk: &B256, info: &[u8], length: usize) -> Vec<u8> {
+    let mut okm = Vec::with_capacity(length);
+    let mut block = vec![];
+    // The counter is a single byte, so at most 255 blocks are expanded, enough for a lamport key
+    for counter in 1..=length.div_ceil(32) as u8 {
+        block = hmac_sha_256(prk.as_slice(), &[&block, info, &[counter]].concat()).to_vec();
+        okm.extend_from_slice(&block);
+    }
+    okm.truncate(length);
+    okm
+}
+
+fn hkdf_mod_r(ikm: &[u8]) -> U256 {
+    let mut salt = B256::from_slice(&Sha256::digest(b"BLS-SIG-KEYGEN-SALT-"));
+    loop {
+        let prk = hmac_sha_256(salt.as_slice(), &[ikm, &[0]].concat());
+        let okm = hkdf_expand(&prk, &48u16.to_be_bytes(), 48);
+        let private_key = U384::from_be_slice(&okm) % U384::from(CURVE_ORDER);
+        if !private_key.is_zero() {
+            return U256::from(private_key);
+        }
+        salt = B256::from_slice(&Sha256::digest(salt));
+    }
+}
+
+fn ikm_to_lamport_private_key(ikm: &[u8], salt: &[u8]) -> Vec<u8> {
+    let prk = hmac_sha_256(salt, ikm);
+    hkdf_expand(&prk, &[], 32 * LAMPORT_CHUNKS)
+}
+
+fn parent_private_key_to_lamport_public_key(parent_private_key: U256, index: u32) -> B256 {
+    let salt = index.to_be_bytes();
+    let ikm = parent_private_key.to_be_bytes::<32>();
+    let not_ikm = ikm.map(|byte| !byte);
+
+    let mut lamport_public_key = Sha256::new();
+    for lamport_private_key in [
+        ikm_to_lamport_private_key(&ikm, &salt),
+        ikm_to_lamport_private_key(&not_ikm, &salt),
+    ] {
+        for chunk in lamport_private_key.chunks(32) {
+            lamport_public_key.update(Sha256::digest(chunk));
+        }
+    }
+    B256::from_slice(&lamport_public_key.finalize())
+}
+
+fn derive_child(parent_private_key: U256, index: u32) -> U256 {
+    hkdf_mod_r(parent_private_key_to_lamport_public_key(parent_private_key, index).as_slice())
+}
+
+fn to_private_key(private_key: U256) -> PrivateKey {
+    PrivateKey {
+        inner: B256::from(private_key.to_be_bytes::<32>()),
+    }
+}
+
+/// Derives the master private key from a seed of at least 32 bytes, such as a BIP-39 seed.
+pub fn derive_master_private_key(seed: &[u8]) -> anyhow::Result<PrivateKey> {
+    ensure!(seed.len() >= 32, "Seed must be at least 32 bytes");
+    Ok(to_private_key(hkdf_mod_r(seed)))
+}
+
+/// Derives the child private key at `index` of `parent_private_key`.
+pub fn derive_child_private_key(parent_private_key: &PrivateKey, index: u32) -> PrivateKey {
+    to_private_key(derive_child(
+        U256::from_be_bytes(parent_private_key.inner.0),
+        index,
+    ))
+}
+
+/// Derives the private key at an EIP-2334 `path`, e.g. `m/12381/3600/0/0/0`, from a seed.
+pub fn derive_private_key_from_path(seed: &[u8], path: &str) -> anyhow::Result<PrivateKey> {
+    let mut nodes = path.split('/');
+    ensure!(
+        nodes.next() == Some("m"),
+        "Path {path} must start with the master node m"
+    );
+    nodes.try_fold(derive_master_private_key(seed)?, |private_key, node| {
+        let index = node
+            .parse::<u32>()
+            .map_err(|err| anyhow!("Invalid index {node} in path {path}: {err}"))?;
+        Ok(derive_child_private_key(&private_key, index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    #[test]
+    fn test_eip2333_vector() {
+        let seed = hex!(
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        let master_private_key = derive_master_private_key(&seed).unwrap();
+        assert_eq!(
+            U256::from_be_bytes(master_private_key.inner.0),
+            U256::from_str_radix(
+                "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+                10
+            )
+            .unwrap()
+        );
+
+        let child_private_key = derive_child_private_key(&master_private_key, 0);
+        assert_eq!(
+            U256::from_be_bytes(child_private_key.inner.0),
+            U256::from_str_radix(
+                "20397789859736650942317412262472558107875392172444076792671091975210932703118",
+                10
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            derive_private_key_from_path(&seed, "m/0").unwrap(),
+            child_private_key
+        );
+        assert!(derive_private_key_from_path(&seed, "0/0").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+//! Deterministic validator keys for local devnets.
+//!
+//! https://github.com/ethereum/eth2.0-pm/tree/master/interop/mocked_start#pubkeyprivkey-generation
+
+use alloy_primitives::{B256, U256, uint};
+use ream_bls::PrivateKey;
+use sha2::{Digest, Sha256};
+
+/// The order of the BLS12-381 scalar field.
+pub const CURVE_ORDER: U256 =
+    uint!(0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001_U256);
+
+/// Reduces a little-endian 32 byte `seed` into a private key.
+pub fn private_key_from_seed(seed: [u8; 32]) -> PrivateKey {
+    let private_key = U256::from_le_bytes(seed) % CURVE_ORDER;
+    PrivateKey {
+        inner: B256::from(private_key.to_be_bytes::<32>()),
+    }
+}
+
+/// The interop private key of the validator at `index`, which every client derives the same way.
+pub fn interop_private_key(index: u64) -> PrivateKey {
+    let mut preimage = [0u8; 32];
+    preimage[..8].copy_from_slice(&index.to_le_bytes());
+    private_key_from_seed(Sha256::digest(preimage).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+    use ream_bls::traits::PublicKeyDerivable;
+
+    use super::*;
+
+    #[test]
+    fn test_interop_keys() {
+        let private_key = interop_private_key(0);
+        assert_eq!(
+            private_key.inner,
+            B256::from(hex!(
+                "25295f0d1d592a90b333e26e85149708208e9f8e8bc18f6c77bd62f8ad7a6866"
+            ))
+        );
+        assert_eq!(
+            private_key.public_key().unwrap().to_bytes(),
+            hex!(
+                "a99a76ed7796f7be22d5b7e85deeb7c5677e88e511e0b337618f8c4eb61349b4bf2d153f649f7b53359fe8b94a38e44c"
+            )
+        );
+    }
+}
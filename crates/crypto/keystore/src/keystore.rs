@@ -1,7 +1,7 @@
 use std::fs;
 
 use alloy_primitives::B256;
-use anyhow::{Result, anyhow, ensure};
+use anyhow::{Result, anyhow, bail, ensure};
 use rand;
 use ream_bls::{PrivateKey, PublicKey};
 use serde::{Deserialize, Serialize};
@@ -42,6 +42,75 @@ where
 }
 
 impl EncryptedKeystore {
+    /// Encrypts `keystore` with `password` into an EIP-2335 keystore, using scrypt as the KDF.
+    /// `path` is the EIP-2334 path the key was derived at, or empty if it wasn't.
+    pub fn encrypt(keystore: &Keystore, password: &[u8], path: &str) -> anyhow::Result<Self> {
+        Self::encrypt_with(
+            keystore,
+            password,
+            path,
+            KdfParams::scrypt(),
+            CipherParams::aes_128_ctr(),
+        )
+    }
+
+    /// Encrypts `keystore` with `password` into an EIP-2335 keystore, using the given KDF and
+    /// cipher parameters.
+    pub fn encrypt_with(
+        keystore: &Keystore,
+        password: &[u8],
+        path: &str,
+        kdf: KdfParams,
+        cipher: CipherParams,
+    ) -> anyhow::Result<Self> {
+        let derived_key = kdf.derive_key(password)?;
+        ensure!(
+            derived_key.len() >= 32,
+            "Derived key must be at least 32 bytes, got {}",
+            derived_key.len()
+        );
+
+        let mut cipher_message = keystore.private_key.inner.to_vec();
+        match &cipher {
+            CipherParams::Aes128Ctr { iv } => {
+                let key_param: [u8; 16] = derived_key[0..16].try_into().map_err(|err| {
+                    anyhow!("Failed to convert derived key into 16 byte array: {err:?}")
+                })?;
+                let iv_param: &[u8; 16] = iv
+                    .as_slice()
+                    .try_into()
+                    .map_err(|err| anyhow!("Failed to convert iv into 16 byte array: {err:?}"))?;
+                aes128_ctr(&mut cipher_message, key_param, iv_param);
+            }
+            CipherParams::Aes256Gcm { .. } => {
+                bail!("aes-256-gcm is not supported by EIP-2335 keystores")
+            }
+        };
+        let checksum = Sha256::digest([&derived_key[16..32], &cipher_message].concat());
+
+        Ok(EncryptedKeystore {
+            crypto: CryptoV4 {
+                kdf: FunctionBlock {
+                    params: kdf,
+                    message: vec![],
+                },
+                checksum: FunctionBlock {
+                    params: ChecksumParams::Sha256 {},
+                    message: checksum.to_vec(),
+                },
+                cipher: FunctionBlock {
+                    params: cipher,
+                    message: cipher_message,
+                },
+            },
+            description: String::new(),
+            public_key: keystore.public_key.clone(),
+            path: path.to_string(),
+            uuid: Uuid::new_v4().to_string(),
+            version: 4,
+        })
+    }
+
     pub fn validate_password(&self, password: &[u8]) -> anyhow::Result<bool> {
         let derived_key = self.crypto.kdf.params.derive_key(password)?;
         let derived_key_slice = &derived_key[16..32];
@@ -179,6 +248,27 @@ pub enum KdfParams {
 }
 
 impl KdfParams {
+    /// The scrypt parameters recommended by EIP-2335, with a random salt.
+    pub fn scrypt() -> Self {
+        KdfParams::Scrypt {
+            dklen: 32,
+            n: 262144,
+            p: 1,
+            r: 8,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        }
+    }
+
+    /// The PBKDF2 parameters recommended by EIP-2335, with a random salt.
+    pub fn pbkdf2() -> Self {
+        KdfParams::Pbkdf2 {
+            c: 262144,
+            dklen: 32,
+            prf: Prf::HmacSha256,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        }
+    }
+
     pub fn derive_key(&self, password: &[u8]) -> anyhow::Result<Vec<u8>> {
         match self {
             KdfParams::Pbkdf2 {
@@ -222,6 +312,15 @@ pub enum CipherParams {
     },
 }
 
+impl CipherParams {
+    /// AES-128-CTR with a random iv.
+    pub fn aes_128_ctr() -> Self {
+        CipherParams::Aes128Ctr {
+            iv: rand::random::<[u8; 16]>().to_vec(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(tag = "function", content = "params", rename_all = "lowercase")]
 pub enum ChecksumParams {
@@ -492,4 +591,88 @@ mod tests {
             private_key
         );
     }
+
+    fn encrypt_test_vector(path: &str) {
+        let vector: EncryptedKeystore = EncryptedKeystore::load_from_file(path).unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        let keystore = Keystore {
+            public_key: vector.public_key.clone(),
+            private_key: PrivateKey {
+                inner: B256::from(hex!(
+                    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+                )),
+            },
+        };
+        let salt = hex!("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3");
+        let kdf = match vector.crypto.kdf.params {
+            KdfParams::Scrypt { .. } => KdfParams::Scrypt {
+                dklen: 32,
+                n: 262144,
+                p: 1,
+                r: 8,
+                salt: salt.to_vec(),
+            },
+            KdfParams::Pbkdf2 { .. } => KdfParams::Pbkdf2 {
+                c: 262144,
+                dklen: 32,
+                prf: Prf::HmacSha256,
+                salt: salt.to_vec(),
+            },
+            KdfParams::Argon2Id { .. } => unreachable!(),
+        };
+        let cipher = CipherParams::Aes128Ctr {
+            iv: hex!("264daa3f303d7259501c93d997d84fe6").to_vec(),
+        };
+
+        let encrypted =
+            EncryptedKeystore::encrypt_with(&keystore, &password, &vector.path, kdf, cipher)
+                .unwrap();
+        assert_eq!(encrypted.crypto, vector.crypto);
+
+        let decrypted = encrypted.decrypt(&password).unwrap();
+        assert_eq!(decrypted.private_key.inner, keystore.private_key.inner);
+    }
+
+    #[test]
+    fn encrypt_pbkdf2() {
+        encrypt_test_vector("./assets/Pbkdf2TestKeystore.json");
+    }
+
+    #[test]
+    fn encrypt_scrypt() {
+        encrypt_test_vector("./assets/ScryptDecryptionTest.json");
+    }
+
+    #[test]
+    fn encrypt_round_trip() {
+        let keystore = Keystore {
+            public_key: PublicKey::default(),
+            private_key: PrivateKey {
+                inner: B256::repeat_byte(7),
+            },
+        };
+        let password = b"password123";
+        let encrypted = EncryptedKeystore::encrypt_with(
+            &keystore,
+            password,
+            "",
+            KdfParams::Pbkdf2 {
+                c: 2,
+                dklen: 32,
+                prf: Prf::HmacSha256,
+                salt: vec![1; 32],
+            },
+            CipherParams::aes_128_ctr(),
+        )
+        .unwrap();
+        let encrypted: EncryptedKeystore =
+            serde_json::from_str(&serde_json::to_string(&encrypted).unwrap()).unwrap();
+
+        assert!(encrypted.validate_password(password).unwrap());
+        assert!(!encrypted.validate_password(b"password").unwrap());
+        assert_eq!(
+            encrypted.decrypt(password).unwrap().private_key.inner,
+            keystore.private_key.inner
+        );
+    }
 }
@@ -1,6 +1,8 @@
 pub mod decrypt;
+pub mod eip2333;
 pub mod hex_serde;
 pub mod hmac;
+pub mod interop;
 pub mod keystore;
 pub mod pbkdf2;
 pub mod salsa;
@@ -42,16 +42,38 @@ impl Multiproof {
             .iter()
             .map(|&index| generalized_index_from_leaf_index(index, DEPTH))
             .collect::<Vec<_>>();
-        let helper_indices = get_helper_indices(&generalized_indices);
+
+        Self::generate_from_nodes(&generalized_indices, |generalized_index| {
+            tree.get(generalized_index as usize)
+                .copied()
+                .ok_or_else(|| anyhow!("Missing node at index {generalized_index}"))
+        })
+    }
+
+    /// Generate a multiproof for the given generalized indices, reading the nodes of the tree
+    /// from `get_node`.
+    ///
+    /// This allows proving leaves of trees which are too large to be materialized, as only the
+    /// leaves and their helper nodes are requested.
+    pub fn generate_from_nodes(
+        generalized_indices: &[u64],
+        get_node: impl Fn(u64) -> anyhow::Result<B256>,
+    ) -> anyhow::Result<Self> {
+        ensure!(!generalized_indices.is_empty(), "Indices cannot be empty");
+        ensure!(
+            !generalized_indices.contains(&0),
+            "Generalized indices start from 1"
+        );
+        let helper_indices = get_helper_indices(generalized_indices);
 
         let leaves = generalized_indices
             .iter()
-            .map(|&generalized_index| (generalized_index, tree[generalized_index as usize]))
-            .collect::<HashMap<u64, B256>>();
+            .map(|&generalized_index| Ok((generalized_index, get_node(generalized_index)?)))
+            .collect::<anyhow::Result<HashMap<u64, B256>>>()?;
         let proofs = helper_indices
             .iter()
-            .map(|&generalized_index| (generalized_index, tree[generalized_index as usize]))
-            .collect::<BTreeMap<u64, B256>>();
+            .map(|&generalized_index| Ok((generalized_index, get_node(generalized_index)?)))
+            .collect::<anyhow::Result<BTreeMap<u64, B256>>>()?;
 
         Ok(Self { leaves, proofs })
     }
@@ -158,4 +180,18 @@ mod tests {
         // Should succeed to verify the multiproof.
         multiproof.verify(root).unwrap();
     }
+
+    #[test]
+    fn test_generate_from_nodes_at_different_depths() {
+        let leaves = (0..8u8).map(B256::repeat_byte).collect::<Vec<_>>();
+        let tree = merkle_tree(&leaves, DEPTH).unwrap();
+
+        // An inner node and a leaf outside of its subtree
+        let multiproof =
+            Multiproof::generate_from_nodes(&[4, 14], |index| Ok(tree[index as usize])).unwrap();
+        assert_eq!(multiproof.proofs.len(), 3);
+        multiproof.verify(tree[1]).unwrap();
+
+        assert!(Multiproof::generate_from_nodes(&[], |index| Ok(tree[index as usize])).is_err());
+    }
 }
@@ -46,7 +46,7 @@ impl PrivateKey {
 mod tests {
     use rand::rng;
 
-    use crate::hashsig::private_key::PrivateKey;
+    use crate::hashsig::{private_key::PrivateKey, signature::Signature};
 
     #[test]
     fn test_sign_and_verify() {
@@ -74,4 +74,30 @@ mod tests {
         assert!(verify_result.is_ok(), "Verification should succeed");
         assert!(verify_result.unwrap(), "Signature should be valid");
     }
+
+    #[test]
+    fn test_signature_bytes_round_trip() {
+        let mut rng = rng();
+        let (public_key, private_key) = PrivateKey::generate_key_pair(&mut rng, 0, 10);
+
+        let epoch = 3;
+        let message = [1u8; 32];
+        let signature = private_key
+            .sign(&mut rng, &message, epoch)
+            .expect("Signing should succeed");
+
+        // Signatures are zero padded to the size of the signature fields
+        let bytes = signature.to_bytes().expect("Encoding should succeed");
+        let bytes = signature
+            .to_padded_bytes(bytes.len() + 64)
+            .expect("Padding should succeed");
+        assert!(signature.to_padded_bytes(bytes.len() - 65).is_err());
+        let decoded = Signature::from_bytes(&bytes).expect("Decoding should succeed");
+
+        assert!(
+            decoded
+                .verify(&message, &public_key, epoch)
+                .expect("Verification should succeed")
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use anyhow::{anyhow, ensure};
 use hashsig::{MESSAGE_LENGTH, signature::SignatureScheme};
 
 use crate::hashsig::{HashSigScheme, public_key::PublicKey};
@@ -13,6 +14,33 @@ impl Signature {
         Self { inner }
     }
 
+    /// Encodes the signature as it is carried in the fixed size signature fields of the lean
+    /// containers.
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serde::encode_to_vec(&self.inner, bincode::config::standard())
+            .map_err(|err| anyhow!("Failed to encode signature: {err}"))
+    }
+
+    /// Encodes the signature like [Signature::to_bytes], zero padded to `length` bytes to fill a
+    /// signature field.
+    pub fn to_padded_bytes(&self, length: usize) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = self.to_bytes()?;
+        ensure!(
+            bytes.len() <= length,
+            "Signature of {} bytes doesn't fit in {length} bytes",
+            bytes.len()
+        );
+        bytes.resize(length, 0);
+        Ok(bytes)
+    }
+
+    /// Decodes a signature encoded by [Signature::to_bytes], ignoring the zero padding after it.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let (inner, _) = bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(|err| anyhow!("Failed to decode signature: {err}"))?;
+        Ok(Self::new(inner))
+    }
+
     pub fn verify(
         &self,
         message: &[u8; MESSAGE_LENGTH],
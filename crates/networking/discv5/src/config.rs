@@ -1,14 +1,42 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
 
 use discv5::{ConfigBuilder, Enr, ListenConfig};
 
 use crate::subnet::{AttestationSubnets, SyncCommitteeSubnets};
 
+/// The ENR key of the QUIC port
+pub const QUIC_ENR_KEY: &str = "quic";
+/// The ENR key of the QUIC port on IPv6
+pub const QUIC6_ENR_KEY: &str = "quic6";
+/// The ENR key of the genesis time of the lean chain
+pub const LEAN_ENR_KEY: &str = "lean";
+/// The ENR key of the number of custody groups a beacon node custodies
+pub const CUSTODY_GROUP_COUNT_ENR_KEY: &str = "cgc";
+
+/// The chain whose peers are discovered, which determines the chain specific fields of the local
+/// ENR and the ENRs of the peers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiscoveryChain {
+    /// Beacon nodes advertise the fork id and their attestation and sync committee subnets
+    #[default]
+    Beacon,
+    /// Lean nodes advertise the genesis time of their network, and only listen on QUIC
+    Lean { genesis_time: u64 },
+}
+
 pub struct DiscoveryConfig {
     pub discv5_config: discv5::Config,
+    pub chain: DiscoveryChain,
     pub bootnodes: Vec<Enr>,
     pub socket_address: IpAddr,
+    /// An IPv6 address listened on next to an IPv4 socket address for dual-stack
+    pub socket_address_v6: Option<Ipv6Addr>,
+    /// The address advertised in the ENR instead of the socket address of the same IP version,
+    /// e.g. the public address of a node behind NAT
+    pub advertised_address: Option<IpAddr>,
     pub socket_port: u16,
+    /// The QUIC listening port (UDP), QUIC is disabled if it is not set
+    pub quic_port: Option<u16>,
     pub discovery_port: u16,
     pub disable_discovery: bool,
     pub attestation_subnets: AttestationSubnets,
@@ -37,9 +65,13 @@ impl Default for DiscoveryConfig {
 
         Self {
             discv5_config,
+            chain: DiscoveryChain::Beacon,
             bootnodes: Vec::new(),
             socket_address: socket_address.into(),
+            socket_address_v6: None,
+            advertised_address: None,
             socket_port,
+            quic_port: None,
             discovery_port,
             disable_discovery: false,
             attestation_subnets,
@@ -47,3 +79,95 @@ impl Default for DiscoveryConfig {
         }
     }
 }
+
+impl DiscoveryConfig {
+    /// The addresses to listen on, which are the socket address and for dual-stack the IPv6
+    /// socket address.
+    pub fn listen_addresses(&self) -> Vec<IpAddr> {
+        let mut listen_addresses = vec![self.socket_address];
+        if self.socket_address.is_ipv4()
+            && let Some(socket_address_v6) = self.socket_address_v6
+        {
+            listen_addresses.push(socket_address_v6.into());
+        }
+        listen_addresses
+    }
+
+    /// The addresses to advertise in the ENR, which are the listen addresses unless an address
+    /// of the same IP version is configured to be advertised.
+    pub fn enr_addresses(&self) -> Vec<IpAddr> {
+        self.listen_addresses()
+            .into_iter()
+            .map(|address| match self.advertised_address {
+                Some(advertised_address) if advertised_address.is_ipv4() == address.is_ipv4() => {
+                    advertised_address
+                }
+                _ => address,
+            })
+            .collect()
+    }
+}
+
+/// The discv5 listen config for `address`, listening on an IPv6 socket next to an IPv4 one if
+/// `address_v6` is set.
+pub fn discv5_listen_config(
+    address: IpAddr,
+    address_v6: Option<Ipv6Addr>,
+    port: u16,
+) -> ListenConfig {
+    match (address, address_v6) {
+        (IpAddr::V4(address), Some(address_v6)) => ListenConfig::from_two_sockets(
+            Some(SocketAddrV4::new(address, port)),
+            Some(SocketAddrV6::new(address_v6, port, 0, 0)),
+        ),
+        (address, _) => ListenConfig::from_ip(address, port),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listen_addresses() {
+        let mut config = DiscoveryConfig::default();
+        assert_eq!(
+            config.listen_addresses(),
+            vec![IpAddr::from(Ipv4Addr::UNSPECIFIED)]
+        );
+
+        config.socket_address_v6 = Some(Ipv6Addr::UNSPECIFIED);
+        config.advertised_address = Some(Ipv6Addr::LOCALHOST.into());
+        assert_eq!(
+            config.listen_addresses(),
+            vec![IpAddr::from(Ipv4Addr::UNSPECIFIED), Ipv6Addr::UNSPECIFIED.into()]
+        );
+        assert_eq!(
+            config.enr_addresses(),
+            vec![IpAddr::from(Ipv4Addr::UNSPECIFIED), Ipv6Addr::LOCALHOST.into()]
+        );
+
+        // An IPv6 socket address can't be combined with another IPv6 address
+        config.socket_address = Ipv6Addr::LOCALHOST.into();
+        assert_eq!(
+            config.listen_addresses(),
+            vec![IpAddr::from(Ipv6Addr::LOCALHOST)]
+        );
+    }
+
+    #[test]
+    fn test_discv5_listen_config() {
+        assert!(matches!(
+            discv5_listen_config(Ipv6Addr::LOCALHOST.into(), None, 9000),
+            ListenConfig::Ipv6 { port: 9000, .. }
+        ));
+        assert!(matches!(
+            discv5_listen_config(Ipv4Addr::LOCALHOST.into(), Some(Ipv6Addr::LOCALHOST), 9000),
+            ListenConfig::DualStack {
+                ipv4_port: 9000,
+                ipv6_port: 9000,
+                ..
+            }
+        ));
+    }
+}
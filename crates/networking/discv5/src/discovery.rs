@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     future::Future,
+    net::SocketAddr,
     pin::Pin,
     task::{Context, Poll},
     time::Instant,
@@ -26,8 +27,8 @@ use tokio::sync::mpsc;
 use tracing::{error, info, trace, warn};
 
 use crate::{
-    config::DiscoveryConfig,
-    eth2::{ENR_ETH2_KEY, EnrForkId},
+    config::{DiscoveryChain, DiscoveryConfig, LEAN_ENR_KEY, QUIC_ENR_KEY, QUIC6_ENR_KEY},
+    eth2::{ENR_ETH2_KEY, EnrForkId, enr_fork_id},
     subnet::{
         ATTESTATION_BITFIELD_ENR_KEY, SYNC_COMMITTEE_BITFIELD_ENR_KEY,
         attestation_subnet_predicate, sync_committee_subnet_predicate,
@@ -66,6 +67,7 @@ struct QueryResult {
 
 pub struct Discovery {
     discv5: Discv5,
+    chain: DiscoveryChain,
     event_stream: EventStream,
     discovery_queries: FuturesUnordered<Pin<Box<dyn Future<Output = QueryResult> + Send>>>,
     find_peer_active: bool,
@@ -77,18 +79,45 @@ impl Discovery {
         let enr_local =
             convert_to_enr(local_key).map_err(|err| anyhow!("Failed to convert key: {err:?}"))?;
 
+        let is_beacon = config.chain == DiscoveryChain::Beacon;
         let mut enr_builder = Enr::builder();
-        enr_builder.ip(config.socket_address);
-        enr_builder.tcp4(config.socket_port);
-        enr_builder.udp4(config.discovery_port);
+        for address in config.enr_addresses() {
+            enr_builder.ip(address);
+            if address.is_ipv4() {
+                if is_beacon {
+                    enr_builder.tcp4(config.socket_port);
+                }
+                enr_builder.udp4(config.discovery_port);
+                if let Some(quic_port) = config.quic_port {
+                    enr_builder.add_value(QUIC_ENR_KEY, &quic_port);
+                }
+            } else {
+                if is_beacon {
+                    enr_builder.tcp6(config.socket_port);
+                }
+                enr_builder.udp6(config.discovery_port);
+                if let Some(quic_port) = config.quic_port {
+                    enr_builder.add_value(QUIC6_ENR_KEY, &quic_port);
+                }
+            }
+        }
+
+        match config.chain {
+            DiscoveryChain::Beacon => {
+                enr_builder
+                    .add_value(ENR_ETH2_KEY, &EnrForkId::new(genesis_validators_root()))
+                    .add_value(ATTESTATION_BITFIELD_ENR_KEY, &config.attestation_subnets)
+                    .add_value(
+                        SYNC_COMMITTEE_BITFIELD_ENR_KEY,
+                        &config.sync_committee_subnets,
+                    );
+            }
+            DiscoveryChain::Lean { genesis_time } => {
+                enr_builder.add_value(LEAN_ENR_KEY, &genesis_time);
+            }
+        }
 
         let enr = enr_builder
-            .add_value(ENR_ETH2_KEY, &EnrForkId::electra(genesis_validators_root()))
-            .add_value(ATTESTATION_BITFIELD_ENR_KEY, &config.attestation_subnets)
-            .add_value(
-                SYNC_COMMITTEE_BITFIELD_ENR_KEY,
-                &config.sync_committee_subnets,
-            )
             .build(&enr_local)
             .map_err(|err| anyhow!("Failed to build ENR: {err}"))?;
 
@@ -121,6 +150,7 @@ impl Discovery {
 
         Ok(Self {
             discv5,
+            chain: config.chain,
             event_stream,
             discovery_queries: FuturesUnordered::new(),
             find_peer_active: false,
@@ -146,21 +176,17 @@ impl Discovery {
                 NodeId::random(),
                 match query.clone() {
                     QueryType::Peers => {
-                        let Some(Ok(fork_id)) = self
-                            .discv5
-                            .local_enr()
-                            .get_decodable::<EnrForkId>(ENR_ETH2_KEY)
-                        else {
+                        if let DiscoveryChain::Lean { genesis_time } = self.chain {
+                            return self.start_lean_query(genesis_time, target_peers);
+                        }
+                        let Some(local_fork_id) = enr_fork_id(&self.discv5.local_enr()) else {
                             warn!("ENR missing or invalid ENR_ETH2_KEY, skipping peer query");
                             return;
                         };
-                        let fork_digest = fork_id.fork_digest;
 
                         Box::new(move |enr: &Enr| {
-                            enr.get_decodable::<EnrForkId>(ENR_ETH2_KEY)
-                                .and_then(Result::ok)
-                                .map(|id| id.fork_digest == fork_digest)
-                                .unwrap_or(false)
+                            enr_fork_id(enr)
+                                .is_some_and(|fork_id| local_fork_id.is_compatible(&fork_id))
                                 && (enr.tcp4().is_some() || enr.tcp6().is_some())
                         })
                     }
@@ -181,8 +207,40 @@ impl Discovery {
         self.discovery_queries.push(Box::pin(query_future));
     }
 
+    /// Queries for lean nodes of the network started at `genesis_time` which listen on QUIC.
+    fn start_lean_query(&mut self, genesis_time: u64, target_peers: usize) {
+        let query_future = self
+            .discv5
+            .find_node_predicate(
+                NodeId::random(),
+                Box::new(move |enr: &Enr| {
+                    enr.get_decodable::<u64>(LEAN_ENR_KEY)
+                        .and_then(Result::ok)
+                        .is_some_and(|enr_genesis_time| enr_genesis_time == genesis_time)
+                        && (enr.get_raw_rlp(QUIC_ENR_KEY).is_some()
+                            || enr.get_raw_rlp(QUIC6_ENR_KEY).is_some())
+                }),
+                target_peers,
+            )
+            .map(move |result| QueryResult {
+                query_type: QueryType::Peers,
+                result,
+            });
+
+        self.discovery_queries.push(Box::pin(query_future));
+    }
+
     fn process_queries(&mut self, cx: &mut Context) -> Option<HashMap<Enr, Option<Instant>>> {
         while let Poll::Ready(Some(query)) = self.discovery_queries.poll_next_unpin(cx) {
+            let query = QueryResult {
+                result: query.result.map(|peers| {
+                    peers
+                        .into_iter()
+                        .filter(|enr| self.is_fork_compatible(enr))
+                        .collect()
+                }),
+                ..query
+            };
             let result = match query.query_type {
                 QueryType::Peers => {
                     self.find_peer_active = false;
@@ -259,6 +317,50 @@ impl Discovery {
     pub fn local_enr(&self) -> Enr {
         self.discv5.local_enr()
     }
+
+    /// Adds a node to the routing table, e.g. a bootnode which is only known after startup.
+    pub fn add_enr(&mut self, enr: Enr) {
+        if enr.node_id() == self.discv5.local_enr().node_id() {
+            return;
+        }
+        if let Err(err) = self.discv5.add_enr(enr) {
+            error!("Failed to add ENR to Discv5 {err:?}");
+        }
+    }
+
+    /// Whether `enr` announces a fork compatible with the fork of the local ENR, so the node is
+    /// worth dialing. Lean nodes don't announce forks, so they are always compatible.
+    pub fn is_fork_compatible(&self, enr: &Enr) -> bool {
+        match self.chain {
+            DiscoveryChain::Beacon => {
+                match (enr_fork_id(&self.discv5.local_enr()), enr_fork_id(enr)) {
+                    (Some(local_fork_id), Some(fork_id)) => local_fork_id.is_compatible(&fork_id),
+                    _ => false,
+                }
+            }
+            DiscoveryChain::Lean { .. } => true,
+        }
+    }
+
+    /// Announces `fork_id` in the `eth2` field of the local ENR, e.g. when a fork activates.
+    /// Returns whether the ENR changed.
+    pub fn update_enr_fork_id(&mut self, fork_id: EnrForkId) -> bool {
+        if enr_fork_id(&self.discv5.local_enr()) == Some(fork_id) {
+            return false;
+        }
+        match self.discv5.enr_insert(ENR_ETH2_KEY, &fork_id) {
+            Ok(_) => true,
+            Err(err) => {
+                error!("Failed to update the eth2 field of the local ENR: {err:?}");
+                false
+            }
+        }
+    }
+
+    /// Sets the IP address and TCP port of the local ENR, returning whether the ENR changed.
+    pub fn update_enr_tcp_socket(&mut self, socket_address: SocketAddr) -> bool {
+        self.discv5.update_local_enr_socket(socket_address, true)
+    }
 }
 
 impl NetworkBehaviour for Discovery {
@@ -372,7 +474,7 @@ fn convert_to_enr(key: Keypair) -> anyhow::Result<CombinedKey> {
 
 #[cfg(test)]
 mod tests {
-    use std::net::Ipv4Addr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
 
     use alloy_primitives::B256;
     use libp2p::identity::Keypair;
@@ -408,6 +510,100 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_advertised_address_in_enr() -> anyhow::Result<()> {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+        let key = Keypair::generate_secp256k1();
+        let advertised_address = Ipv4Addr::new(203, 0, 113, 1);
+        let config = DiscoveryConfig {
+            advertised_address: Some(advertised_address.into()),
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+
+        let mut discovery = Discovery::new(key, &config).await.unwrap();
+        assert_eq!(discovery.local_enr().ip4(), Some(advertised_address));
+
+        let observed_address = Ipv4Addr::new(203, 0, 113, 2);
+        assert!(discovery.update_enr_tcp_socket((observed_address, 9100).into()));
+        assert_eq!(discovery.local_enr().ip4(), Some(observed_address));
+        assert_eq!(discovery.local_enr().tcp4(), Some(9100));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ipv6_only_enr() -> anyhow::Result<()> {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+        let key = Keypair::generate_secp256k1();
+        let config = DiscoveryConfig {
+            socket_address: Ipv6Addr::LOCALHOST.into(),
+            quic_port: Some(9001),
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+
+        let enr = Discovery::new(key, &config).await.unwrap().local_enr();
+        assert_eq!(enr.ip6(), Some(Ipv6Addr::LOCALHOST));
+        assert_eq!(enr.tcp6(), Some(9000));
+        assert_eq!(enr.udp6(), Some(9000));
+        assert_eq!(
+            enr.get_decodable::<u16>(QUIC6_ENR_KEY).transpose()?,
+            Some(9001)
+        );
+        assert_eq!(enr.ip4(), None);
+        assert_eq!(enr.tcp4(), None);
+        assert_eq!(enr.get_decodable::<u16>(QUIC_ENR_KEY).transpose()?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dual_stack_enr() -> anyhow::Result<()> {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+        let key = Keypair::generate_secp256k1();
+        let config = DiscoveryConfig {
+            socket_address: Ipv4Addr::LOCALHOST.into(),
+            socket_address_v6: Some(Ipv6Addr::LOCALHOST),
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+
+        let enr = Discovery::new(key, &config).await.unwrap().local_enr();
+        assert_eq!(enr.ip4(), Some(Ipv4Addr::LOCALHOST));
+        assert_eq!(enr.tcp4(), Some(9000));
+        assert_eq!(enr.udp4(), Some(9000));
+        assert_eq!(enr.ip6(), Some(Ipv6Addr::LOCALHOST));
+        assert_eq!(enr.tcp6(), Some(9000));
+        assert_eq!(enr.udp6(), Some(9000));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lean_enr() -> anyhow::Result<()> {
+        let key = Keypair::generate_secp256k1();
+        let config = DiscoveryConfig {
+            chain: DiscoveryChain::Lean { genesis_time: 100 },
+            quic_port: Some(9001),
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+
+        let enr = Discovery::new(key, &config).await.unwrap().local_enr();
+        assert_eq!(
+            enr.get_decodable::<u64>(LEAN_ENR_KEY).transpose()?,
+            Some(100)
+        );
+        assert_eq!(
+            enr.get_decodable::<u16>(QUIC_ENR_KEY).transpose()?,
+            Some(9001)
+        );
+        assert_eq!(enr.tcp4(), None);
+        assert!(enr.get_raw_rlp(ENR_ETH2_KEY).is_none());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_attestation_subnet_predicate() -> anyhow::Result<()> {
         let key = Keypair::generate_secp256k1();
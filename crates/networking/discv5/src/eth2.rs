@@ -1,13 +1,14 @@
 use alloy_primitives::{B256, Bytes, aliases::B32, bytes};
 use alloy_rlp::{Decodable, Encodable};
-use ream_consensus_misc::{constants::beacon::FAR_FUTURE_EPOCH, fork_data::ForkData};
+use discv5::Enr;
+use ream_consensus_misc::constants::beacon::FAR_FUTURE_EPOCH;
 use ream_network_spec::networks::beacon_network_spec;
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 
 pub const ENR_ETH2_KEY: &str = "eth2";
 
-#[derive(Default, Debug, Encode, Decode)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
 pub struct EnrForkId {
     pub fork_digest: B32,
     pub next_fork_version: B32,
@@ -15,23 +16,44 @@ pub struct EnrForkId {
 }
 
 impl EnrForkId {
-    pub fn electra(genesis_validators_root: B256) -> Self {
-        let current_fork_version = beacon_network_spec().electra_fork_version;
-        let next_fork_version = current_fork_version;
-        let next_fork_epoch = FAR_FUTURE_EPOCH;
-
-        let fork_digest = ForkData {
-            current_version: current_fork_version,
+    /// The fork id of the fork active at the current epoch, announcing the next scheduled fork.
+    pub fn new(genesis_validators_root: B256) -> Self {
+        Self::at_epoch(
+            beacon_network_spec().current_epoch(),
             genesis_validators_root,
-        }
-        .compute_fork_digest();
+        )
+    }
+
+    /// The fork id of the fork active at `epoch`, announcing the next scheduled fork.
+    pub fn at_epoch(epoch: u64, genesis_validators_root: B256) -> Self {
+        let network_spec = beacon_network_spec();
+        let current_fork_version =
+            network_spec.fork_version(network_spec.fork_name_at_epoch(epoch));
+        let (next_fork_version, next_fork_epoch) = match network_spec.next_fork(epoch) {
+            Some((next_fork, next_fork_epoch)) => {
+                (network_spec.fork_version(next_fork), next_fork_epoch)
+            }
+            None => (current_fork_version, FAR_FUTURE_EPOCH),
+        };
 
         Self {
-            fork_digest,
+            fork_digest: network_spec.fork_digest_at_epoch(epoch, genesis_validators_root),
             next_fork_version,
             next_fork_epoch,
         }
     }
+
+    /// Whether a peer announcing `other` in its ENR is on the same fork, so it is worth
+    /// connecting to. A peer which scheduled another next fork is only compatible until the
+    /// earlier of the two forks, when the fork digests part.
+    pub fn is_compatible(&self, other: &EnrForkId) -> bool {
+        self.fork_digest == other.fork_digest
+    }
+}
+
+/// The fork id which `enr` announces, if it has a valid `eth2` field.
+pub fn enr_fork_id(enr: &Enr) -> Option<EnrForkId> {
+    enr.get_decodable::<EnrForkId>(ENR_ETH2_KEY)?.ok()
 }
 
 impl Encodable for EnrForkId {
@@ -53,6 +75,8 @@ impl Decodable for EnrForkId {
 
 #[cfg(test)]
 mod tests {
+    use ream_network_spec::networks::initialize_test_network_spec;
+
     use super::*;
 
     #[test]
@@ -73,4 +97,31 @@ mod tests {
         assert_eq!(fork_id.next_fork_epoch, deserialized.next_fork_epoch);
         Ok(())
     }
+    #[test]
+    fn test_fork_id_announces_the_next_fork() {
+        initialize_test_network_spec();
+        let network_spec = beacon_network_spec();
+        let electra_fork_epoch = network_spec.electra_fork_epoch;
+
+        let deneb_fork_id = EnrForkId::at_epoch(electra_fork_epoch - 1, B256::ZERO);
+        assert_eq!(
+            deneb_fork_id.next_fork_version,
+            network_spec.electra_fork_version
+        );
+        assert_eq!(deneb_fork_id.next_fork_epoch, electra_fork_epoch);
+
+        // Fulu isn't scheduled, so the current fork is announced as the next one
+        let electra_fork_id = EnrForkId::at_epoch(electra_fork_epoch, B256::ZERO);
+        assert_eq!(
+            electra_fork_id.next_fork_version,
+            network_spec.electra_fork_version
+        );
+        assert_eq!(electra_fork_id.next_fork_epoch, FAR_FUTURE_EPOCH);
+
+        assert!(!electra_fork_id.is_compatible(&deneb_fork_id));
+        assert!(electra_fork_id.is_compatible(&EnrForkId {
+            next_fork_epoch: electra_fork_epoch + 10,
+            ..electra_fork_id
+        }));
+    }
 }
@@ -0,0 +1,51 @@
+use anyhow::anyhow;
+use tokio::sync::{mpsc, oneshot};
+
+/// The commands of the admin API which are carried out by the [NetworkManagerService].
+///
+/// [NetworkManagerService]: crate::service::NetworkManagerService
+#[derive(Debug)]
+pub enum AdminCommand {
+    /// Restarts the block range syncer from the block at the slot.
+    Resync { start_slot: u64 },
+    /// Clears the caches of the gossip messages seen so far.
+    FlushCaches,
+    /// Reports the number of items waiting in the queues of the manager.
+    QueueDepths(oneshot::Sender<ManagerQueueDepths>),
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManagerQueueDepths {
+    /// The gossip and req/resp messages from the network worker waiting to be handled
+    pub network_events: usize,
+    /// The admin commands waiting to be handled
+    pub admin_commands: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdminSender(pub mpsc::UnboundedSender<AdminCommand>);
+
+impl AdminSender {
+    pub fn resync(&self, start_slot: u64) -> anyhow::Result<()> {
+        self.0
+            .send(AdminCommand::Resync { start_slot })
+            .map_err(|err| anyhow!("Failed to send resync command: {err}"))
+    }
+
+    pub fn flush_caches(&self) -> anyhow::Result<()> {
+        self.0
+            .send(AdminCommand::FlushCaches)
+            .map_err(|err| anyhow!("Failed to send flush caches command: {err}"))
+    }
+
+    /// Asks the manager for the depths of its queues.
+    pub async fn queue_depths(&self) -> anyhow::Result<ManagerQueueDepths> {
+        let (sender, receiver) = oneshot::channel();
+        self.0
+            .send(AdminCommand::QueueDepths(sender))
+            .map_err(|err| anyhow!("Failed to send queue depths command: {err}"))?;
+        receiver
+            .await
+            .map_err(|err| anyhow!("Failed to receive the queue depths: {err}"))
+    }
+}
@@ -1,5 +1,9 @@
-use std::{net::IpAddr, path::PathBuf};
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    path::PathBuf,
+};
 
+use ream_api_types_beacon::id::ValidatorID;
 use ream_p2p::bootnodes::Bootnodes;
 use url::Url;
 
@@ -8,11 +12,20 @@ pub struct ManagerConfig {
     pub http_port: u16,
     pub http_allow_origin: bool,
     pub socket_address: IpAddr,
+    pub socket_address_v6: Option<Ipv6Addr>,
+    pub discovery_address: Option<IpAddr>,
+    pub advertised_address: Option<IpAddr>,
     pub socket_port: u16,
+    pub quic_port: u16,
+    pub disable_quic: bool,
+    pub prefer_quic: bool,
     pub discovery_port: u16,
     pub disable_discovery: bool,
+    pub disable_upnp: bool,
+    pub target_peers: usize,
     pub bootnodes: Bootnodes,
     pub checkpoint_sync_url: Option<Url>,
     pub execution_endpoint: Option<Url>,
     pub execution_jwt_secret: Option<PathBuf>,
+    pub monitor_validators: Vec<ValidatorID>,
 }
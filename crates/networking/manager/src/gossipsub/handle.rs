@@ -1,31 +1,34 @@
-use libp2p::gossipsub::Message;
+use libp2p::{
+    PeerId,
+    gossipsub::{Message, MessageId},
+};
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
     blob_sidecar::BlobIdentifier, execution_engine::rpc_types::get_blobs::BlobAndProofV1,
 };
 use ream_consensus_misc::constants::beacon::genesis_validators_root;
+use ream_metrics::{GOSSIP_MESSAGES, inc_int_counter_vec};
 use ream_network_spec::networks::beacon_network_spec;
-use ream_p2p::{
-    gossipsub::beacon::{
-        configurations::GossipsubConfig,
-        message::GossipsubMessage,
-        topics::{GossipTopic, GossipTopicKind},
-    },
-    network::beacon::channel::GossipMessage,
+use ream_p2p::gossipsub::beacon::{
+    configurations::GossipsubConfig,
+    message::GossipsubMessage,
+    topics::{GossipTopic, GossipTopicKind},
 };
 use ream_storage::{cache::CachedDB, tables::table::Table};
-use ream_validator_beacon::blob_sidecars::compute_subnet_for_blob_sidecar;
-use ssz::Encode;
-use tracing::{error, info, trace, warn};
+use ream_syncer::{block_lookup::BlockLookups, data_column_sampling::DataColumnSampler};
+use tracing::{error, info, instrument, trace, warn};
 use tree_hash::TreeHash;
 
 use crate::{
     gossipsub::validate::{
         attester_slashing::validate_attester_slashing,
-        beacon_attestation::validate_beacon_attestation,
-        beacon_block::validate_gossip_beacon_block, blob_sidecar::validate_blob_sidecar,
+        beacon_attestation::{VOTED_BLOCK_NOT_SEEN, validate_beacon_attestation},
+        beacon_block::{PARENT_BLOCK_NOT_FOUND, validate_gossip_beacon_block},
+        blob_sidecar::validate_blob_sidecar,
         bls_to_execution_change::validate_bls_to_execution_change,
-        proposer_slashing::validate_proposer_slashing, result::ValidationResult,
+        data_column_sidecar::validate_data_column_sidecar,
+        proposer_slashing::validate_proposer_slashing,
+        result::ValidationResult,
         sync_committee::validate_sync_committee,
         sync_committee_contribution_and_proof::validate_sync_committee_contribution_and_proof,
         voluntary_exit::validate_voluntary_exit,
@@ -36,7 +39,7 @@ use crate::{
 pub fn init_gossipsub_config_with_topics() -> GossipsubConfig {
     let mut gossipsub_config = GossipsubConfig::default();
 
-    gossipsub_config.set_topics(vec![
+    let mut topics = vec![
         GossipTopic {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::BeaconBlock,
@@ -81,90 +84,146 @@ pub fn init_gossipsub_config_with_topics() -> GossipsubConfig {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::LightClientOptimisticUpdate,
         },
-        GossipTopic {
-            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
-            kind: GossipTopicKind::BlobSidecar(0),
-        },
         GossipTopic {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::VoluntaryExit,
         },
-    ]);
+    ];
+    topics.extend(
+        (0..beacon_network_spec().blob_sidecar_subnet_count_electra).map(|subnet_id| GossipTopic {
+            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
+            kind: GossipTopicKind::BlobSidecar(subnet_id),
+        }),
+    );
+    gossipsub_config.set_topics(topics);
 
     gossipsub_config
 }
 
-/// Dispatches a gossipsub message to its appropriate handler.
+/// Dispatches a gossipsub message to its appropriate handler and reports the validation result
+/// back to gossipsub, which forwards accepted messages and scores the propagating peer.
+#[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", name = "gossip_message", skip_all, fields(topic = %message.topic))]
 pub async fn handle_gossipsub_message(
+    message_id: MessageId,
+    propagation_source: PeerId,
     message: Message,
     beacon_chain: &BeaconChain,
     cached_db: &CachedDB,
     p2p_sender: &P2PSender,
+    block_lookups: &BlockLookups,
+    data_column_sampler: &DataColumnSampler,
 ) {
-    match GossipsubMessage::decode(&message.topic, &message.data) {
-        Ok(gossip_message) => match gossip_message {
-            GossipsubMessage::BeaconBlock(signed_block) => {
-                info!(
-                    "Beacon block received over gossipsub: slot: {}, root: {}",
-                    signed_block.message.slot,
-                    signed_block.message.block_root()
-                );
+    let topic = GossipTopic::from_topic_hash(&message.topic)
+        .map(|topic| topic.kind.name())
+        .unwrap_or("unknown");
+    let validation_result = validate_and_process_message(
+        message,
+        propagation_source,
+        beacon_chain,
+        cached_db,
+        block_lookups,
+        data_column_sampler,
+    )
+    .await;
+    inc_int_counter_vec(&GOSSIP_MESSAGES, &[topic, validation_result.name()]);
+    p2p_sender.report_validation_result(message_id, propagation_source, &validation_result);
+}
+
+async fn validate_and_process_message(
+    message: Message,
+    propagation_source: PeerId,
+    beacon_chain: &BeaconChain,
+    cached_db: &CachedDB,
+    block_lookups: &BlockLookups,
+    data_column_sampler: &DataColumnSampler,
+) -> ValidationResult {
+    let gossip_message = match GossipsubMessage::decode(&message.topic, &message.data) {
+        Ok(gossip_message) => gossip_message,
+        Err(err) => {
+            trace!("Failed to decode gossip message: {err:?}");
+            return ValidationResult::Reject(format!("Failed to decode gossip message: {err:?}"));
+        }
+    };
 
-                let validation_result = match validate_gossip_beacon_block(
-                    beacon_chain,
-                    cached_db,
-                    &signed_block,
-                )
-                .await
-                {
+    match gossip_message {
+        GossipsubMessage::BeaconBlock(signed_block) => {
+            info!(
+                "Beacon block received over gossipsub: slot: {}, root: {}",
+                signed_block.message.slot,
+                signed_block.message.block_root()
+            );
+
+            let (validation_result, seen_block) =
+                match validate_gossip_beacon_block(beacon_chain, cached_db, &signed_block).await {
                     Ok(result) => result,
                     Err(err) => {
                         warn!("Failed to validate gossipsub beacon block: {err}");
-                        return;
+                        return ValidationResult::Ignore(err.to_string());
                     }
                 };
 
-                match validation_result {
-                    ValidationResult::Accept => {
-                        let signed_block_bytes = signed_block.as_ssz_bytes();
-                        if let Err(err) = beacon_chain.process_block(*signed_block).await {
-                            error!("Failed to process gossipsub beacon block: {err}");
-                        }
-                        p2p_sender.send_gossip(GossipMessage {
-                            topic: GossipTopic::from_topic_hash(&message.topic)
-                                .expect("invalid topic hash"),
-                            data: signed_block_bytes,
-                        });
+            match &validation_result {
+                ValidationResult::Accept if DataColumnSampler::should_sample(&signed_block) => {
+                    // The block is valid, so it is seen even though importing it has to wait for
+                    // its columns to be sampled
+                    if let Some(seen_block) = seen_block {
+                        seen_block.record(cached_db).await;
                     }
-                    ValidationResult::Ignore(reason) => {
-                        warn!("Ignoring gossipsub beacon block: {reason}");
+                    data_column_sampler.sample_and_import(*signed_block);
+                }
+                ValidationResult::Accept => {
+                    match beacon_chain
+                        .process_block_when_available(*signed_block)
+                        .await
+                    {
+                        Ok(()) => {
+                            if let Some(seen_block) = seen_block {
+                                seen_block.record(cached_db).await;
+                            }
+                        }
+                        Err(err) => error!("Failed to process gossipsub beacon block: {err}"),
                     }
-                    ValidationResult::Reject(reason) => {
-                        warn!("Rejecting gossipsub beacon block: {reason}");
+                }
+                ValidationResult::Ignore(reason) if reason == PARENT_BLOCK_NOT_FOUND => {
+                    info!("Gossipsub beacon block has an unknown parent, looking it up");
+                    let parent_root = signed_block.message.parent_root;
+                    if let Err(err) = beacon_chain.add_orphan_block(*signed_block).await {
+                        error!("Failed to add orphan gossipsub beacon block: {err}");
                     }
+                    block_lookups.lookup_block(parent_root, propagation_source);
+                }
+                ValidationResult::Ignore(reason) => {
+                    warn!("Ignoring gossipsub beacon block: {reason}");
+                }
+                ValidationResult::Reject(reason) => {
+                    warn!("Rejecting gossipsub beacon block: {reason}");
                 }
             }
-            GossipsubMessage::BeaconAttestation((single_attestation, subnet_id)) => {
-                trace!(
-                    "Beacon Attestation received over gossipsub: root: {}",
-                    single_attestation.tree_hash_root()
-                );
+            validation_result
+        }
+        GossipsubMessage::BeaconAttestation((single_attestation, subnet_id)) => {
+            trace!(
+                "Beacon Attestation received over gossipsub: root: {}",
+                single_attestation.tree_hash_root()
+            );
 
-                match validate_beacon_attestation(
-                    &single_attestation,
-                    beacon_chain,
-                    subnet_id,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: single_attestation.as_ssz_bytes(),
-                            });
+            match validate_beacon_attestation(
+                &single_attestation,
+                beacon_chain,
+                subnet_id,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
+                        ValidationResult::Ignore(reason) if reason == VOTED_BLOCK_NOT_SEEN => {
+                            block_lookups.lookup_block(
+                                single_attestation.data.beacon_block_root,
+                                propagation_source,
+                            );
                         }
                         ValidationResult::Reject(reason) => {
                             info!("Attestation rejected: {reason}");
@@ -172,129 +231,120 @@ pub async fn handle_gossipsub_message(
                         ValidationResult::Ignore(reason) => {
                             info!("Attestation ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        trace!("Could not validate attestation: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    trace!("Could not validate attestation: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::BlsToExecutionChange(signed_bls_to_execution_change) => {
-                info!(
-                    "BLS to Execution Change received over gossipsub: root: {}",
-                    signed_bls_to_execution_change.tree_hash_root()
-                );
+        }
+        GossipsubMessage::BlsToExecutionChange(signed_bls_to_execution_change) => {
+            info!(
+                "BLS to Execution Change received over gossipsub: root: {}",
+                signed_bls_to_execution_change.tree_hash_root()
+            );
 
-                match validate_bls_to_execution_change(
-                    &signed_bls_to_execution_change,
-                    beacon_chain,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: signed_bls_to_execution_change.as_ssz_bytes(),
-                            });
-                        }
+            match validate_bls_to_execution_change(
+                &signed_bls_to_execution_change,
+                beacon_chain,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("BLS to Execution Change rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("BLS to Execution Change ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate BLS to Execution Change: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate BLS to Execution Change: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::AggregateAndProof(aggregate_and_proof) => {
-                info!(
-                    "Aggregate And Proof received over gossipsub: root: {}",
-                    aggregate_and_proof.tree_hash_root()
-                );
-            }
-            GossipsubMessage::SyncCommittee((sync_committee, subnet_id)) => {
-                info!(
-                    "Sync Committee received over gossipsub: root: {}",
-                    sync_committee.tree_hash_root()
-                );
+        }
+        GossipsubMessage::AggregateAndProof(aggregate_and_proof) => {
+            info!(
+                "Aggregate And Proof received over gossipsub: root: {}",
+                aggregate_and_proof.tree_hash_root()
+            );
+            ValidationResult::Ignore("Aggregate and proof validation is not supported".to_string())
+        }
+        GossipsubMessage::SyncCommittee((sync_committee, subnet_id)) => {
+            info!(
+                "Sync Committee received over gossipsub: root: {}",
+                sync_committee.tree_hash_root()
+            );
 
-                match validate_sync_committee(&sync_committee, beacon_chain, subnet_id, cached_db)
-                    .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: sync_committee.as_ssz_bytes(),
-                            });
-                        }
+            match validate_sync_committee(&sync_committee, beacon_chain, subnet_id, cached_db).await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("Sync committee message rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("Sync committee message ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate sync committee message: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate sync committee message: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::SyncCommitteeContributionAndProof(signed_contribution_and_proof) => {
-                info!(
-                    "Sync Committee Contribution And Proof received over gossipsub: root: {}",
-                    signed_contribution_and_proof.tree_hash_root()
-                );
-
-                match validate_sync_committee_contribution_and_proof(
-                    beacon_chain,
-                    cached_db,
-                    &signed_contribution_and_proof,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: signed_contribution_and_proof.as_ssz_bytes(),
-                            });
-                        }
+        }
+        GossipsubMessage::SyncCommitteeContributionAndProof(signed_contribution_and_proof) => {
+            info!(
+                "Sync Committee Contribution And Proof received over gossipsub: root: {}",
+                signed_contribution_and_proof.tree_hash_root()
+            );
 
+            match validate_sync_committee_contribution_and_proof(
+                beacon_chain,
+                cached_db,
+                &signed_contribution_and_proof,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("Sync committee contribution and proof rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("Sync committee contribution and proof ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate sync committee contribution and proof: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate sync committee contribution and proof: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::AttesterSlashing(attester_slashing) => {
-                info!(
-                    "Attester Slashing received over gossipsub: root: {}",
-                    attester_slashing.tree_hash_root()
-                );
+        }
+        GossipsubMessage::AttesterSlashing(attester_slashing) => {
+            info!(
+                "Attester Slashing received over gossipsub: root: {}",
+                attester_slashing.tree_hash_root()
+            );
 
-                match validate_attester_slashing(&attester_slashing, beacon_chain, cached_db).await
-                {
-                    Ok(validation_result) => match validation_result {
+            match validate_attester_slashing(&attester_slashing, beacon_chain, cached_db).await {
+                Ok(validation_result) => {
+                    match &validation_result {
                         ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: attester_slashing.as_ssz_bytes(),
-                            });
                             if let Err(err) = beacon_chain
                                 .process_attester_slashing(*attester_slashing)
                                 .await
@@ -308,81 +358,76 @@ pub async fn handle_gossipsub_message(
                         ValidationResult::Ignore(reason) => {
                             info!("Attester slashing ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate attester slashing: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate attester slashing: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::ProposerSlashing(proposer_slashing) => {
-                info!(
-                    "Proposer Slashing received over gossipsub: root: {}",
-                    proposer_slashing.tree_hash_root()
-                );
+        }
+        GossipsubMessage::ProposerSlashing(proposer_slashing) => {
+            info!(
+                "Proposer Slashing received over gossipsub: root: {}",
+                proposer_slashing.tree_hash_root()
+            );
 
-                match validate_proposer_slashing(&proposer_slashing, beacon_chain, cached_db).await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: proposer_slashing.as_ssz_bytes(),
-                            });
-                        }
+            match validate_proposer_slashing(&proposer_slashing, beacon_chain, cached_db).await {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("Proposer slashing rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("Proposer slashing ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate proposer slashing: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate proposer slashing: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::BlobSidecar(blob_sidecar) => {
-                info!(
-                    "Blob Sidecar received over gossipsub: root: {}",
-                    blob_sidecar.tree_hash_root()
-                );
-                match validate_blob_sidecar(
-                    beacon_chain,
-                    &blob_sidecar,
-                    compute_subnet_for_blob_sidecar(blob_sidecar.index),
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
+        }
+        GossipsubMessage::BlobSidecar((blob_sidecar, subnet_id)) => {
+            info!(
+                "Blob Sidecar received over gossipsub: root: {}",
+                blob_sidecar.tree_hash_root()
+            );
+            match validate_blob_sidecar(beacon_chain, &blob_sidecar, subnet_id, cached_db).await {
+                Ok(validation_result) => {
+                    match &validation_result {
                         ValidationResult::Accept => {
-                            let blob_sidecar_bytes = blob_sidecar.as_ssz_bytes();
-                            if let Err(err) = beacon_chain
+                            let block_root =
+                                blob_sidecar.signed_block_header.message.tree_hash_root();
+                            let insert_result = beacon_chain
                                 .store
                                 .lock()
                                 .await
                                 .db
                                 .blobs_and_proofs_provider()
                                 .insert(
-                                    BlobIdentifier::new(
-                                        blob_sidecar.signed_block_header.message.tree_hash_root(),
-                                        blob_sidecar.index,
-                                    ),
+                                    BlobIdentifier::new(block_root, blob_sidecar.index),
                                     BlobAndProofV1 {
                                         blob: blob_sidecar.blob,
                                         proof: blob_sidecar.kzg_proof,
                                     },
-                                )
-                            {
-                                error!("Failed to insert blob_sidecar: {err}");
+                                );
+                            match insert_result {
+                                Ok(()) => {
+                                    if let Err(err) =
+                                        beacon_chain.on_blob_sidecar_stored(block_root).await
+                                    {
+                                        error!(
+                                            "Failed to import block {block_root} waiting on blobs: {err}"
+                                        );
+                                    }
+                                }
+                                Err(err) => error!("Failed to insert blob_sidecar: {err}"),
                             }
-
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: blob_sidecar_bytes,
-                            });
                         }
                         ValidationResult::Reject(reason) => {
                             info!("Blob_sidecar rejected: {reason}");
@@ -390,54 +435,89 @@ pub async fn handle_gossipsub_message(
                         ValidationResult::Ignore(reason) => {
                             info!("Blob_sidecar ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate blob_sidecar: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate blob_sidecar: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
-                info!(
-                    "Light Client Finality Update received over gossipsub: root: {}",
-                    light_client_finality_update.tree_hash_root()
-                );
-            }
-            GossipsubMessage::LightClientOptimisticUpdate(light_client_optimistic_update) => {
-                info!(
-                    "Light Client Optimistic Update received over gossipsub: root: {}",
-                    light_client_optimistic_update.tree_hash_root()
-                );
+        }
+        GossipsubMessage::DataColumnSidecar((data_column_sidecar, subnet_id)) => {
+            info!(
+                "Data Column Sidecar received over gossipsub: block root: {}, index: {}",
+                data_column_sidecar.block_root(),
+                data_column_sidecar.index
+            );
+            match validate_data_column_sidecar(
+                beacon_chain,
+                &data_column_sidecar,
+                subnet_id,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
+                        ValidationResult::Reject(reason) => {
+                            info!("Data_column_sidecar rejected: {reason}");
+                        }
+                        ValidationResult::Ignore(reason) => {
+                            info!("Data_column_sidecar ignored: {reason}");
+                        }
+                    }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate data_column_sidecar: {err}");
+                    ValidationResult::Ignore(err.to_string())
+                }
             }
-            GossipsubMessage::VoluntaryExit(voluntary_exit) => {
-                info!(
-                    "Voluntary Exit received over gossipsub: root: {}",
-                    voluntary_exit.tree_hash_root()
-                );
+        }
+        GossipsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
+            info!(
+                "Light Client Finality Update received over gossipsub: root: {}",
+                light_client_finality_update.tree_hash_root()
+            );
+            ValidationResult::Ignore(
+                "Light client finality update validation is not supported".to_string(),
+            )
+        }
+        GossipsubMessage::LightClientOptimisticUpdate(light_client_optimistic_update) => {
+            info!(
+                "Light Client Optimistic Update received over gossipsub: root: {}",
+                light_client_optimistic_update.tree_hash_root()
+            );
+            ValidationResult::Ignore(
+                "Light client optimistic update validation is not supported".to_string(),
+            )
+        }
+        GossipsubMessage::VoluntaryExit(voluntary_exit) => {
+            info!(
+                "Voluntary Exit received over gossipsub: root: {}",
+                voluntary_exit.tree_hash_root()
+            );
 
-                match validate_voluntary_exit(&voluntary_exit, beacon_chain, cached_db).await {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: voluntary_exit.as_ssz_bytes(),
-                            });
-                        }
+            match validate_voluntary_exit(&voluntary_exit, beacon_chain, cached_db).await {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("voluntary_exit rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("voluntary_exit ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate voluntary_exit: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate voluntary_exit: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-        },
-        Err(err) => {
-            trace!("Failed to decode gossip message: {err:?}");
         }
-    };
+    }
 }
@@ -2,7 +2,8 @@ use anyhow::anyhow;
 use ream_bls::traits::Verifiable;
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
-    electra::beacon_state::BeaconState, single_attestation::SingleAttestation,
+    attestation::compute_subnet_for_attestation, electra::beacon_state::BeaconState,
+    single_attestation::SingleAttestation,
 };
 use ream_consensus_misc::{
     constants::beacon::DOMAIN_BEACON_ATTESTER,
@@ -12,10 +13,13 @@ use ream_storage::{
     cache::{AtestationKey, CachedDB},
     tables::{field::Field, table::Table},
 };
-use ream_validator_beacon::attestation::compute_subnet_for_attestation;
 
 use super::result::ValidationResult;
 
+/// The reason an attestation for a block we haven't seen is ignored, after its signature was
+/// verified.
+pub const VOTED_BLOCK_NOT_SEEN: &str = "The block being voted for has not been seen";
+
 pub async fn validate_beacon_attestation(
     attestation: &SingleAttestation,
     beacon_chain: &BeaconChain,
@@ -32,7 +36,8 @@ pub async fn validate_beacon_attestation(
         .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
 
     let index = attestation.committee_index;
-    let committees_per_slot = state.get_committee_count_per_slot(attestation.data.target.epoch);
+    let committees_per_slot =
+        state.get_committee_count_per_slot(attestation.data.target.epoch.as_u64());
 
     // [REJECT] The committee index is within the expected range
     if index >= committees_per_slot {
@@ -42,7 +47,7 @@ pub async fn validate_beacon_attestation(
     }
 
     // [REJECT] The attestation is for the correct subnet
-    if compute_subnet_for_attestation(committees_per_slot, attestation.data.slot, index)
+    if compute_subnet_for_attestation(committees_per_slot, attestation.data.slot.as_u64(), index)
         != attestation_subnet_id
     {
         return Ok(ValidationResult::Reject(
@@ -68,7 +73,7 @@ pub async fn validate_beacon_attestation(
 
     // [IGNORE] the epoch of attestation.data.slot is either the current or previous epoch (with a
     // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
-    let attestation_epoch = compute_epoch_at_slot(attestation.data.slot);
+    let attestation_epoch = compute_epoch_at_slot(attestation.data.slot.as_u64());
     let current_epoch = state.get_current_epoch();
     let previous_epoch = state.get_previous_epoch();
 
@@ -94,7 +99,7 @@ pub async fn validate_beacon_attestation(
 
     // [REJECT] The attester is a member of the committee
     if !state
-        .get_beacon_committee(attestation.data.slot, index)?
+        .get_beacon_committee(attestation.data.slot.as_u64(), index)?
         .contains(&(attestation.attester_index))
     {
         return Ok(ValidationResult::Reject(
@@ -126,7 +131,10 @@ pub async fn validate_beacon_attestation(
         .get(attestation.attester_index as usize)
         .ok_or_else(|| anyhow!("Could not get validator"))?;
 
-    let domain = state.get_domain(DOMAIN_BEACON_ATTESTER, Some(attestation.data.target.epoch));
+    let domain = state.get_domain(
+        DOMAIN_BEACON_ATTESTER,
+        Some(attestation.data.target.epoch.as_u64()),
+    );
     let signing_root = compute_signing_root(&attestation.data, domain);
 
     let signature_valid = attestation
@@ -148,9 +156,7 @@ pub async fn validate_beacon_attestation(
         .get(attestation.data.beacon_block_root)?
         .is_none()
     {
-        return Ok(ValidationResult::Ignore(
-            "The block being voted for has not been seen".to_string(),
-        ));
+        return Ok(ValidationResult::Ignore(VOTED_BLOCK_NOT_SEEN.to_string()));
     }
 
     // [REJECT] The block being voted for (aggregate.data.beacon_block_root) passes validation.
@@ -1,197 +1,211 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
 use anyhow::anyhow;
+use ream_bls::BLSSignature;
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
+    bls_to_execution_change::BLSToExecutionChange,
     electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
-    execution_engine::new_payload_request::NewPayloadRequest,
-};
-use ream_consensus_misc::{
-    constants::beacon::MAX_BLOBS_PER_BLOCK_ELECTRA, misc::compute_start_slot_at_epoch,
 };
-use ream_execution_engine::rpc_types::payload_status::PayloadStatus;
+use ream_consensus_misc::constants::beacon::MAX_BLOBS_PER_BLOCK_ELECTRA;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_p2p::constants::MAXIMUM_GOSSIP_CLOCK_DISPARITY;
 use ream_storage::{
     cache::{AddressSlotIdentifier, CachedDB},
     tables::{field::Field, table::Table},
 };
+use tracing::instrument;
+use tree_hash::TreeHash;
 
 use super::result::ValidationResult;
 
+/// The reason a block whose parent we haven't seen is ignored, after its signature was verified.
+pub const PARENT_BLOCK_NOT_FOUND: &str = "Parent block not found";
+
+/// The entries of the seen caches for an accepted gossip block. They are only recorded once the
+/// block was imported, so that a block whose import failed can still be delivered again.
+pub struct SeenBeaconBlock {
+    block_root: B256,
+    proposer_signature: (AddressSlotIdentifier, BLSSignature),
+    bls_to_execution_changes: Vec<(AddressSlotIdentifier, BLSToExecutionChange)>,
+}
+
+impl SeenBeaconBlock {
+    pub async fn record(self, cached_db: &CachedDB) {
+        cached_db.seen_blocks.write().await.put(self.block_root, ());
+        let (identifier, signature) = self.proposer_signature;
+        cached_db
+            .seen_proposer_signature
+            .write()
+            .await
+            .put(identifier, signature);
+        let mut seen_bls_to_execution_signature =
+            cached_db.seen_bls_to_execution_signature.write().await;
+        for (identifier, bls_to_execution_change) in self.bls_to_execution_changes {
+            seen_bls_to_execution_signature.put(identifier, bls_to_execution_change);
+        }
+    }
+}
+
+/// Validates a gossip block against the state of its parent, advanced to the epoch of the block,
+/// so that only blocks which pass the gossip conditions of the spec are handed to the beacon chain
+/// and forwarded to our peers. Accepted blocks come with the entries to record in the seen caches
+/// once they are imported.
+#[instrument(level = "debug", skip_all, fields(slot = block.message.slot.as_u64()))]
 pub async fn validate_gossip_beacon_block(
     beacon_chain: &BeaconChain,
     cached_db: &CachedDB,
     block: &SignedBeaconBlock,
-) -> anyhow::Result<ValidationResult> {
-    let latest_state = beacon_chain.store.lock().await.db.get_latest_state()?;
+) -> anyhow::Result<(ValidationResult, Option<SeenBeaconBlock>)> {
+    // [IGNORE] The block is not from a future slot (with a MAXIMUM_GOSSIP_CLOCK_DISPARITY
+    // allowance).
+    if block.message.slot
+        > beacon_network_spec().current_slot_with_disparity(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+    {
+        return Ok((
+            ValidationResult::Ignore("Block is from a future slot".to_string()),
+            None,
+        ));
+    }
 
-    // Validate incoming block
-    match validate_beacon_block(beacon_chain, cached_db, block, &latest_state, false).await? {
-        ValidationResult::Accept => {}
-        ValidationResult::Ignore(reason) => {
-            return Ok(ValidationResult::Ignore(reason));
-        }
-        ValidationResult::Reject(reason) => {
-            return Ok(ValidationResult::Reject(reason));
-        }
+    // [IGNORE] The block is from a slot greater than the latest finalized slot.
+    let finalized_checkpoint = beacon_chain
+        .store
+        .lock()
+        .await
+        .db
+        .finalized_checkpoint_provider()
+        .get()?;
+    if block.message.slot <= finalized_checkpoint.epoch.start_slot() {
+        return Ok((
+            ValidationResult::Ignore(
+                "Block is from a slot greater than the latest finalized slot".to_string(),
+            ),
+            None,
+        ));
     }
 
-    let (parent_block, parent_state) = {
-        let store = beacon_chain.store.lock().await;
-        let Some(parent_block) = store
+    // Blocks which were already imported are ignored before any signature is verified
+    let block_root = block.message.tree_hash_root();
+    if cached_db.seen_blocks.read().await.contains(&block_root)
+        || beacon_chain
+            .store
+            .lock()
+            .await
             .db
             .beacon_block_provider()
-            .get(block.message.parent_root)?
-        else {
-            return Err(anyhow!("failed to get parent block"));
-        };
-
-        let Some(parent_state) = store
-            .db
-            .beacon_state_provider()
-            .get(block.message.parent_root)?
-        else {
-            return Err(anyhow!("failed to get parent state"));
-        };
-
-        (parent_block, parent_state)
-    };
-
-    // Validate parent block [block.message.parent_root]
-    match validate_beacon_block(beacon_chain, cached_db, &parent_block, &parent_state, true).await?
+            .get(block_root)?
+            .is_some()
     {
-        ValidationResult::Accept => {}
-        ValidationResult::Ignore(reason) => {
-            return Ok(ValidationResult::Ignore(reason));
-        }
-        ValidationResult::Reject(reason) => {
-            return Ok(ValidationResult::Reject(reason));
-        }
-    };
+        return Ok((
+            ValidationResult::Ignore("Block already seen".to_string()),
+            None,
+        ));
+    }
 
-    let Some(validator) = latest_state
-        .validators
-        .get(block.message.proposer_index as usize)
-    else {
-        return Ok(ValidationResult::Reject("Validator not found".to_string()));
+    // [IGNORE] The block's parent (defined by block.parent_root) has been seen.
+    // Blocks are only stored once they were imported, so a stored parent also passed validation.
+    let parent_block = beacon_chain
+        .store
+        .lock()
+        .await
+        .db
+        .beacon_block_provider()
+        .get(block.message.parent_root)?;
+    let Some(parent_block) = parent_block else {
+        // The block is kept until its parent is imported, so its signature is verified against
+        // the latest state in the meantime, to only keep blocks of actual proposers.
+        let latest_state = beacon_chain.store.lock().await.db.get_latest_state()?;
+        let validation_result = validate_proposer_signature(cached_db, block, &latest_state).await;
+        if validation_result != ValidationResult::Accept {
+            return Ok((validation_result, None));
+        }
+        return Ok((
+            ValidationResult::Ignore(PARENT_BLOCK_NOT_FOUND.to_string()),
+            None,
+        ));
     };
 
-    cached_db.seen_proposer_signature.write().await.put(
-        AddressSlotIdentifier {
-            address: validator.public_key.clone(),
-            slot: block.message.slot,
-        },
-        block.signature.clone(),
-    );
+    // [REJECT] The current finalized_checkpoint is an ancestor of block.
+    #[cfg(not(feature = "disable_ancestor_validation"))]
+    if beacon_chain
+        .store
+        .lock()
+        .await
+        .get_checkpoint_block(block.message.parent_root, finalized_checkpoint.epoch)?
+        != finalized_checkpoint.root
+    {
+        return Ok((
+            ValidationResult::Reject("Finalized checkpoint is not an ancestor".to_string()),
+            None,
+        ));
+    }
 
-    for signed_bls_execution_change in block.message.body.bls_to_execution_changes.iter() {
-        let validator =
-            &latest_state.validators[signed_bls_execution_change.message.validator_index as usize];
+    let state = proposer_state(beacon_chain, cached_db, block).await?;
+    let validation_result = validate_beacon_block(cached_db, block, &parent_block, &state).await?;
+    if validation_result != ValidationResult::Accept {
+        return Ok((validation_result, None));
+    }
 
-        cached_db.seen_bls_to_execution_signature.write().await.put(
+    let seen_block = SeenBeaconBlock {
+        block_root,
+        proposer_signature: (
             AddressSlotIdentifier {
-                address: validator.public_key.clone(),
+                address: state.validators[block.message.proposer_index as usize]
+                    .public_key
+                    .clone(),
                 slot: block.message.slot,
             },
-            signed_bls_execution_change.message.clone(),
-        );
-    }
-
-    Ok(ValidationResult::Accept)
+            block.signature.clone(),
+        ),
+        bls_to_execution_changes: block
+            .message
+            .body
+            .bls_to_execution_changes
+            .iter()
+            .map(|signed_bls_execution_change| {
+                (
+                    AddressSlotIdentifier {
+                        address: state.validators
+                            [signed_bls_execution_change.message.validator_index as usize]
+                            .public_key
+                            .clone(),
+                        slot: block.message.slot,
+                    },
+                    signed_bls_execution_change.message.clone(),
+                )
+            })
+            .collect(),
+    };
+    Ok((ValidationResult::Accept, Some(seen_block)))
 }
 
+/// Validates the block against `state`, the state of `parent_block` advanced to the epoch of the
+/// block.
 pub async fn validate_beacon_block(
-    beacon_chain: &BeaconChain,
     cached_db: &CachedDB,
     block: &SignedBeaconBlock,
+    parent_block: &SignedBeaconBlock,
     state: &BeaconState,
-    is_parent: bool,
 ) -> anyhow::Result<ValidationResult> {
-    let store = beacon_chain.store.lock().await;
-
-    // [IGNORE] The block is not from a future slot.
-    if block.message.slot > store.get_current_slot()? {
-        return Ok(ValidationResult::Ignore(
-            "Block is from a future slot".to_string(),
-        ));
+    let validation_result = validate_proposer_signature(cached_db, block, state).await;
+    if validation_result != ValidationResult::Accept {
+        return Ok(validation_result);
     }
 
-    // [IGNORE] The block is from a slot greater than the latest finalized slot.
-    if block.message.slot
-        <= compute_start_slot_at_epoch(store.db.finalized_checkpoint_provider().get()?.epoch)
-    {
-        return Ok(ValidationResult::Ignore(
-            "Block is from a slot greater than the latest finalized slot".to_string(),
-        ));
-    }
-
-    let Some(validator) = state.validators.get(block.message.proposer_index as usize) else {
-        return Ok(ValidationResult::Reject("Validator not found".to_string()));
-    };
-
-    // [IGNORE] The block is the first block with valid signature received for the proposer for the
-    // slot.
-    if cached_db
-        .seen_proposer_signature
-        .read()
-        .await
-        .contains(&AddressSlotIdentifier {
-            address: validator.public_key.clone(),
-            slot: block.message.slot,
-        })
-    {
-        return Ok(ValidationResult::Ignore(
-            "Signature already received".to_string(),
+    // [REJECT] The block is from a higher slot than its parent.
+    if block.message.slot <= parent_block.message.slot {
+        return Ok(ValidationResult::Reject(
+            "Block is not from a higher slot".to_string(),
         ));
     }
 
-    // [REJECT] The proposer signature, signed_beacon_block.signature, is valid with respect to the
-    // proposer_index pubkey.
-    match state.verify_block_header_signature(&block.signed_header()) {
-        Ok(true) => {}
-        Ok(false) => {
-            return Ok(ValidationResult::Reject("Invalid signature".to_string()));
-        }
-        Err(err) => {
-            return Ok(ValidationResult::Reject(format!(
-                "Signature verification failed: {err}"
-            )));
-        }
-    }
-
-    match store
-        .db
-        .beacon_block_provider()
-        .get(block.message.parent_root)?
+    // [REJECT] The block is proposed by the expected proposer_index for the block's slot in the
+    // context of the current shuffling (defined by parent_root/slot).
+    if state.get_beacon_proposer_index(Some(block.message.slot.as_u64()))?
+        != block.message.proposer_index
     {
-        Some(parent_block) => {
-            // [REJECT] The block is from a higher slot than its parent.
-            if block.message.slot <= parent_block.message.slot {
-                return Ok(ValidationResult::Reject(
-                    "Block is not from a higher slot".to_string(),
-                ));
-            }
-        }
-        None => {
-            // [IGNORE] The block's parent (defined by block.parent_root) has been seen.
-            return Ok(ValidationResult::Ignore(
-                "Parent block not found".to_string(),
-            ));
-        }
-    }
-
-    #[cfg(not(feature = "disable_ancestor_validation"))]
-    {
-        let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
-        // [REJECT] The current finalized_checkpoint is an ancestor of block.
-        if store.get_checkpoint_block(block.message.parent_root, finalized_checkpoint.epoch)?
-            != finalized_checkpoint.root
-        {
-            return Ok(ValidationResult::Reject(
-                "Finalized checkpoint is not an ancestor".to_string(),
-            ));
-        }
-    }
-
-    // [REJECT] The block is proposed by the expected proposer_index for the block's slot.
-    if state.get_beacon_proposer_index(Some(block.message.slot))? != block.message.proposer_index {
         return Ok(ValidationResult::Reject(
             "Proposer index is incorrect".to_string(),
         ));
@@ -199,7 +213,7 @@ pub async fn validate_beacon_block(
 
     // [REJECT] The block's execution payload timestamp is correct with respect to the slot.
     if block.message.body.execution_payload.timestamp
-        != state.compute_timestamp_at_slot(block.message.slot)
+        != state.compute_timestamp_at_slot(block.message.slot.as_u64())
     {
         return Ok(ValidationResult::Reject(
             "Execution payload timestamp is incorrect".to_string(),
@@ -208,12 +222,13 @@ pub async fn validate_beacon_block(
 
     // [IGNORE] The signed_bls_to_execution_change is the first valid signed bls to execution change
     // received for the validator with index.
+    let proposer = &state.validators[block.message.proposer_index as usize];
     if cached_db
         .seen_bls_to_execution_signature
         .read()
         .await
         .contains(&AddressSlotIdentifier {
-            address: validator.public_key.clone(),
+            address: proposer.public_key.clone(),
             slot: block.message.slot,
         })
     {
@@ -241,40 +256,80 @@ pub async fn validate_beacon_block(
         ));
     }
 
-    if is_parent && let Some(execution_enigne) = &beacon_chain.execution_engine {
-        let mut versioned_hashes = vec![];
-        for commitment in block.message.body.blob_kzg_commitments.iter() {
-            versioned_hashes.push(commitment.calculate_versioned_hash());
-        }
+    Ok(ValidationResult::Accept)
+}
 
-        let payload_verification_status = execution_enigne
-            .notify_new_payload(NewPayloadRequest {
-                execution_payload: block.message.body.execution_payload.clone(),
-                versioned_hashes,
-                parent_beacon_block_root: block.message.parent_root,
-                execution_requests: block.message.body.execution_requests.clone(),
-            })
-            .await?;
+/// Checks that the block is the first one of its proposer for the slot and that the proposer
+/// signed it.
+async fn validate_proposer_signature(
+    cached_db: &CachedDB,
+    block: &SignedBeaconBlock,
+    state: &BeaconState,
+) -> ValidationResult {
+    let Some(validator) = state.validators.get(block.message.proposer_index as usize) else {
+        return ValidationResult::Reject("Validator not found".to_string());
+    };
 
-        match payload_verification_status {
-            // If execution_payload verification of block's parent by an execution node is not
-            // complete: [REJECT] The block's parent passes all validation (excluding
-            // execution node verification of the block.body.execution_payload)
-            PayloadStatus::Valid | PayloadStatus::Accepted | PayloadStatus::Syncing => {
-                return Ok(ValidationResult::Accept);
-            }
-            // Otherwise:
-            // [IGNORE] The block's parent passes all validation (including execution node
-            // verification of the block.body.execution_payload).
-            PayloadStatus::InvalidBlockHash | PayloadStatus::Invalid => {
-                return Ok(ValidationResult::Reject(
-                    "Execution payload is invalid".to_string(),
-                ));
-            }
-        }
+    // [IGNORE] The block is the first block with valid signature received for the proposer for the
+    // slot.
+    if cached_db
+        .seen_proposer_signature
+        .read()
+        .await
+        .contains(&AddressSlotIdentifier {
+            address: validator.public_key.clone(),
+            slot: block.message.slot,
+        })
+    {
+        return ValidationResult::Ignore("Signature already received".to_string());
+    }
+
+    // [REJECT] The proposer signature, signed_beacon_block.signature, is valid with respect to the
+    // proposer_index pubkey.
+    match state.verify_block_header_signature(&block.signed_header()) {
+        Ok(true) => ValidationResult::Accept,
+        Ok(false) => ValidationResult::Reject("Invalid signature".to_string()),
+        Err(err) => ValidationResult::Reject(format!("Signature verification failed: {err}")),
     }
+}
 
-    drop(store);
+/// The state of the parent of the block advanced to the start of the epoch of the block, whose
+/// shuffling decides the proposer of the block. The advanced states are cached, since every block
+/// of the epoch building on the same parent needs the same epoch transition.
+async fn proposer_state(
+    beacon_chain: &BeaconChain,
+    cached_db: &CachedDB,
+    block: &SignedBeaconBlock,
+) -> anyhow::Result<Arc<BeaconState>> {
+    let parent_root = block.message.parent_root;
+    let epoch_start_slot = block.message.slot.epoch().start_slot().as_u64();
+    if let Some(state) = cached_db
+        .proposer_states
+        .write()
+        .await
+        .get(&(parent_root, epoch_start_slot))
+    {
+        return Ok(state.clone());
+    }
 
-    Ok(ValidationResult::Accept)
+    let mut state = beacon_chain
+        .store
+        .lock()
+        .await
+        .db
+        .beacon_state_provider()
+        .get(parent_root)?
+        .ok_or_else(|| anyhow!("Failed to get parent state {parent_root}"))?;
+    if state.slot >= epoch_start_slot {
+        return Ok(Arc::new(state));
+    }
+
+    state.process_slots(epoch_start_slot)?;
+    let state = Arc::new(state);
+    cached_db
+        .proposer_states
+        .write()
+        .await
+        .put((parent_root, epoch_start_slot), state.clone());
+    Ok(state)
 }
@@ -1,15 +1,17 @@
 use anyhow::anyhow;
 use ream_chain_beacon::beacon_chain::BeaconChain;
-use ream_consensus_beacon::{blob_sidecar::BlobSidecar, electra::beacon_state::BeaconState};
-use ream_consensus_misc::{
-    constants::beacon::MAX_BLOBS_PER_BLOCK_ELECTRA, misc::compute_start_slot_at_epoch,
+use ream_consensus_beacon::{
+    blob_sidecar::{BlobSidecar, compute_subnet_for_blob_sidecar},
+    electra::beacon_state::BeaconState,
+};
+use ream_consensus_misc::constants::beacon::MAX_BLOBS_PER_BLOCK_ELECTRA;
+use ream_polynomial_commitments::handlers::{
+    verify_blob_kzg_proof_batch, verify_kzg_commitment_inclusion_proof,
 };
-use ream_polynomial_commitments::handlers::verify_blob_kzg_proof_batch;
 use ream_storage::{
     cache::CachedDB,
     tables::{field::Field, table::Table},
 };
-use ream_validator_beacon::blob_sidecars::compute_subnet_for_blob_sidecar;
 
 use super::result::ValidationResult;
 
@@ -46,7 +48,7 @@ pub async fn validate_blob_sidecar(
     let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
 
     // [IGNORE] The sidecar is from a slot greater than the latest finalized slot
-    if header.slot <= compute_start_slot_at_epoch(finalized_checkpoint.epoch) {
+    if header.slot <= finalized_checkpoint.epoch.start_slot() {
         return Ok(ValidationResult::Ignore(
             "The sidecar is from a slot less than the latest finalized slot".to_string(),
         ));
@@ -94,7 +96,7 @@ pub async fn validate_blob_sidecar(
     }
 
     // [REJECT] The sidecar's inclusion proof is valid as verified by
-    if !blob_sidecar.verify_blob_sidecar_inclusion_proof() {
+    if !verify_kzg_commitment_inclusion_proof(blob_sidecar) {
         return Ok(ValidationResult::Reject(
             "Invalid blob sidecar inclusion proof".to_string(),
         ));
@@ -111,21 +113,9 @@ pub async fn validate_blob_sidecar(
         ));
     }
 
-    // [IGNORE] The sidecar is the first sidecar for the tuple (block_header.slot,
-    // block_header.proposer_index, blob_sidecar.index) with valid header signature, sidecar
-    // inclusion proof, and kzg proof.
-    let tuple = (header.slot, header.proposer_index, blob_sidecar.index);
-    let mut seen = cached_db.seen_blob_sidecars.write().await;
-    if seen.contains(&tuple) {
-        return Ok(ValidationResult::Ignore(
-            "Duplicate blob sidecar for (slot, proposer_index, index)".to_string(),
-        ));
-    }
-    seen.put(tuple, ());
-
     // [REJECT or IGNORE] The sidecar is proposed by the expected proposer_index for the block's
     // slot in the context of the current shuffling
-    match state.get_beacon_proposer_index(Some(header.slot)) {
+    match state.get_beacon_proposer_index(Some(header.slot.as_u64())) {
         Ok(expected_index) => {
             if expected_index != header.proposer_index {
                 return Ok(ValidationResult::Reject(format!(
@@ -141,5 +131,21 @@ pub async fn validate_blob_sidecar(
         }
     }
 
+    // [IGNORE] The sidecar is the first sidecar for the tuple (block_header.slot,
+    // block_header.proposer_index, blob_sidecar.index) with valid header signature, sidecar
+    // inclusion proof, and kzg proof.
+    let tuple = (
+        header.slot.as_u64(),
+        header.proposer_index,
+        blob_sidecar.index,
+    );
+    let mut seen = cached_db.seen_blob_sidecars.write().await;
+    if seen.contains(&tuple) {
+        return Ok(ValidationResult::Ignore(
+            "Duplicate blob sidecar for (slot, proposer_index, index)".to_string(),
+        ));
+    }
+    seen.put(tuple, ());
+
     Ok(ValidationResult::Accept)
 }
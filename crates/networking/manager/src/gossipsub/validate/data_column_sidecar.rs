@@ -0,0 +1,162 @@
+use anyhow::anyhow;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_beacon::{
+    data_column_sidecar::{DataColumnSidecar, compute_subnet_for_data_column_sidecar},
+    electra::beacon_state::BeaconState,
+};
+use ream_polynomial_commitments::handlers::verify_data_column_sidecar_kzg_proofs;
+use ream_storage::{
+    cache::CachedDB,
+    tables::{field::Field, table::Table},
+};
+
+use super::result::ValidationResult;
+
+pub async fn validate_data_column_sidecar(
+    beacon_chain: &BeaconChain,
+    data_column_sidecar: &DataColumnSidecar,
+    subnet_id: u64,
+    cached_db: &CachedDB,
+) -> anyhow::Result<ValidationResult> {
+    // [REJECT] The sidecar is valid as verified by verify_data_column_sidecar
+    if !data_column_sidecar.verify_data_column_sidecar() {
+        return Ok(ValidationResult::Reject(
+            "Malformed data column sidecar".to_string(),
+        ));
+    }
+
+    // [REJECT] The sidecar is for the correct subnet
+    if compute_subnet_for_data_column_sidecar(data_column_sidecar.index) != subnet_id {
+        return Ok(ValidationResult::Reject(
+            "Data column sidecar not for correct subnet".to_string(),
+        ));
+    }
+
+    let header = &data_column_sidecar.signed_block_header.message;
+    let store = beacon_chain.store.lock().await;
+
+    // [IGNORE] The sidecar is not from a future slot
+    if header.slot > store.get_current_slot()? {
+        return Ok(ValidationResult::Ignore(
+            "The sidecar is from a future slot".to_string(),
+        ));
+    }
+
+    let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+
+    // [IGNORE] The sidecar is from a slot greater than the latest finalized slot
+    if header.slot <= finalized_checkpoint.epoch.start_slot() {
+        return Ok(ValidationResult::Ignore(
+            "The sidecar is from a slot less than the latest finalized slot".to_string(),
+        ));
+    }
+
+    let head_root = store.get_head()?;
+    let state: BeaconState = store
+        .db
+        .beacon_state_provider()
+        .get(head_root)?
+        .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
+
+    // [REJECT] The proposer signature of sidecar.signed_block_header, is valid with respect to the
+    // block_header.proposer_index pubkey.
+    if !state.verify_block_header_signature(&data_column_sidecar.signed_block_header)? {
+        return Ok(ValidationResult::Reject(
+            "Invalid proposer signature on data column sidecar's block header".to_string(),
+        ));
+    }
+
+    // [IGNORE] The sidecar's block's parent (defined by block_header.parent_root) has been seen
+    let Some(parent_block) = store.db.beacon_block_provider().get(header.parent_root)? else {
+        return Ok(ValidationResult::Ignore(
+            "Parent block not seen".to_string(),
+        ));
+    };
+
+    // [REJECT] The sidecar is from a higher slot than the sidecar's block's parent
+    if header.slot <= parent_block.message.slot {
+        return Ok(ValidationResult::Reject(
+            "Sidecar slot not higher than parent block's slot".to_string(),
+        ));
+    }
+
+    // [REJECT] The current finalized_checkpoint is an ancestor of the sidecar's block
+    if store.get_checkpoint_block(header.parent_root, finalized_checkpoint.epoch)?
+        != finalized_checkpoint.root
+    {
+        return Ok(ValidationResult::Reject(
+            "Finalized checkpoint is not an ancestor of the sidecar's block".to_string(),
+        ));
+    }
+
+    // [REJECT] The sidecar's kzg_commitments field inclusion proof is valid as verified by
+    // verify_data_column_sidecar_inclusion_proof
+    if !data_column_sidecar.verify_data_column_sidecar_inclusion_proof() {
+        return Ok(ValidationResult::Reject(
+            "Invalid data column sidecar inclusion proof".to_string(),
+        ));
+    }
+
+    // [REJECT] The sidecar's column data is valid as verified by
+    // verify_data_column_sidecar_kzg_proofs
+    match verify_data_column_sidecar_kzg_proofs(data_column_sidecar) {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(ValidationResult::Reject(
+                "Invalid data column sidecar KZG proofs".to_string(),
+            ));
+        }
+        Err(err) => {
+            return Ok(ValidationResult::Reject(format!(
+                "Could not verify data column sidecar KZG proofs: {err:?}"
+            )));
+        }
+    }
+
+    // [IGNORE] The sidecar is the first sidecar for the tuple (block_header.slot,
+    // block_header.proposer_index, sidecar.index) with valid header signature, sidecar inclusion
+    // proof, and kzg proof.
+    let tuple = (
+        header.slot.as_u64(),
+        header.proposer_index,
+        data_column_sidecar.index,
+    );
+    if cached_db
+        .seen_data_column_sidecars
+        .read()
+        .await
+        .contains(&tuple)
+    {
+        return Ok(ValidationResult::Ignore(
+            "Duplicate data column sidecar for (slot, proposer_index, index)".to_string(),
+        ));
+    }
+
+    // [REJECT] The sidecar is proposed by the expected proposer_index for the block's slot in the
+    // context of the current shuffling
+    match state.get_beacon_proposer_index(Some(header.slot.as_u64())) {
+        Ok(expected_index) => {
+            if expected_index != header.proposer_index {
+                return Ok(ValidationResult::Reject(format!(
+                    "Wrong proposer index: slot {}: expected {expected_index}, got {}",
+                    header.slot, header.proposer_index
+                )));
+            }
+        }
+        Err(err) => {
+            return Ok(ValidationResult::Reject(format!(
+                "Could not verify proposer index: {err:?}"
+            )));
+        }
+    }
+
+    // Only a sidecar which passed every check counts as seen, so an invalid sidecar can't
+    // shadow the valid one for the same tuple.
+    cached_db
+        .seen_data_column_sidecars
+        .write()
+        .await
+        .put(tuple, ());
+
+    Ok(ValidationResult::Accept)
+}
@@ -1,6 +1,28 @@
+use libp2p::gossipsub::MessageAcceptance;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ValidationResult {
     Accept,
     Ignore(String),
     Reject(String),
 }
+
+impl ValidationResult {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValidationResult::Accept => "accept",
+            ValidationResult::Ignore(_) => "ignore",
+            ValidationResult::Reject(_) => "reject",
+        }
+    }
+}
+
+impl From<&ValidationResult> for MessageAcceptance {
+    fn from(validation_result: &ValidationResult) -> Self {
+        match validation_result {
+            ValidationResult::Accept => MessageAcceptance::Accept,
+            ValidationResult::Ignore(_) => MessageAcceptance::Ignore,
+            ValidationResult::Reject(_) => MessageAcceptance::Reject,
+        }
+    }
+}
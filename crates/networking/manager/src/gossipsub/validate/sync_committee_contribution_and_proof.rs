@@ -3,7 +3,10 @@ use ream_bls::{PublicKey, traits::Verifiable};
 use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::electra::beacon_state::BeaconState;
 use ream_consensus_misc::{
-    constants::beacon::{DOMAIN_SYNC_COMMITTEE, SYNC_COMMITTEE_SIZE},
+    constants::beacon::{
+        DOMAIN_CONTRIBUTION_AND_PROOF, DOMAIN_SYNC_COMMITTEE,
+        DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF, SYNC_COMMITTEE_SIZE,
+    },
     misc::{compute_epoch_at_slot, compute_signing_root, compute_sync_committee_period},
 };
 use ream_storage::{
@@ -11,10 +14,7 @@ use ream_storage::{
     tables::table::Table,
 };
 use ream_validator_beacon::{
-    constants::{
-        DOMAIN_CONTRIBUTION_AND_PROOF, DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF,
-        SYNC_COMMITTEE_SUBNET_COUNT,
-    },
+    constants::SYNC_COMMITTEE_SUBNET_COUNT,
     contribution_and_proof::SignedContributionAndProof,
     sync_committee::{SyncAggregatorSelectionData, is_sync_committee_aggregator},
 };
@@ -44,7 +44,7 @@ pub async fn validate_sync_committee_contribution_and_proof(
         .get(head_root)?
         .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
 
-    let current_slot: u64 = block.message.slot;
+    let current_slot: u64 = block.message.slot.as_u64();
 
     // [IGNORE] if contribution.slot is equal to or earlier than the current_slot (with a
     // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
@@ -216,7 +216,7 @@ pub fn get_sync_subcommittee_pubkeys(
 ) -> Vec<PublicKey> {
     let current_epoch = state.get_current_epoch();
 
-    let next_slot_epoch = compute_epoch_at_slot(state.slot + 1);
+    let next_slot_epoch = compute_epoch_at_slot(state.slot.as_u64() + 1);
     let sync_committee = if compute_sync_committee_period(current_epoch)
         == compute_sync_committee_period(next_slot_epoch)
     {
@@ -1,15 +1,22 @@
 use anyhow::anyhow;
-use libp2p::{PeerId, swarm::ConnectionId};
+use libp2p::{Multiaddr, PeerId, gossipsub::MessageId, swarm::ConnectionId};
 use ream_p2p::{
-    network::beacon::channel::{GossipMessage, P2PMessage, P2PResponse},
+    network::beacon::channel::{
+        GossipMessage, NetworkQueueDepths, P2PMessage, P2PResponse, SubnetSubscription,
+    },
     req_resp::{
-        beacon::messages::BeaconResponseMessage, error::ReqRespError, handler::RespMessage,
+        beacon::messages::{BeaconResponseMessage, goodbye::Goodbye},
+        error::ReqRespError,
+        handler::RespMessage,
         messages::ResponseMessage,
     },
 };
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tracing::warn;
 
+use crate::gossipsub::validate::result::ValidationResult;
+
+#[derive(Debug, Clone)]
 pub struct P2PSender(pub mpsc::UnboundedSender<P2PMessage>);
 
 impl P2PSender {
@@ -19,6 +26,21 @@ impl P2PSender {
         }
     }
 
+    pub fn report_validation_result(
+        &self,
+        message_id: MessageId,
+        propagation_source: PeerId,
+        validation_result: &ValidationResult,
+    ) {
+        if let Err(err) = self.0.send(P2PMessage::GossipValidationResult {
+            message_id,
+            propagation_source,
+            acceptance: validation_result.into(),
+        }) {
+            warn!("Failed to send gossip validation result: {err}");
+        }
+    }
+
     pub fn send_response(
         &self,
         peer_id: PeerId,
@@ -60,16 +82,65 @@ impl P2PSender {
         connection_id: ConnectionId,
         stream_id: u64,
         error: &str,
+    ) {
+        self.send_req_resp_error(
+            peer_id,
+            connection_id,
+            stream_id,
+            ReqRespError::Anyhow(anyhow!(error.to_string())),
+        );
+    }
+
+    /// Sends `error` with the response code matching its kind.
+    pub fn send_req_resp_error(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        error: ReqRespError,
     ) {
         if let Err(err) = self.0.send(P2PMessage::Response(P2PResponse {
             peer_id,
             connection_id,
             stream_id,
-            message: Box::new(RespMessage::Error(ReqRespError::Anyhow(anyhow!(
-                error.to_string()
-            )))),
+            message: Box::new(RespMessage::Error(error)),
         })) {
             warn!("Failed to send error response: {err}");
         }
     }
+
+    pub fn dial_peer(&self, address: Multiaddr) {
+        if let Err(err) = self.0.send(P2PMessage::DialPeer(address)) {
+            warn!("Failed to send dial peer message: {err}");
+        }
+    }
+
+    pub fn disconnect_peer(&self, peer_id: PeerId, reason: Goodbye) {
+        if let Err(err) = self.0.send(P2PMessage::DisconnectPeer { peer_id, reason }) {
+            warn!("Failed to send disconnect peer message: {err}");
+        }
+    }
+
+    pub fn ban_peer(&self, peer_id: PeerId) {
+        if let Err(err) = self.0.send(P2PMessage::BanPeer(peer_id)) {
+            warn!("Failed to send ban peer message: {err}");
+        }
+    }
+
+    pub fn subscribe_subnets(&self, subscriptions: Vec<SubnetSubscription>) {
+        if let Err(err) = self.0.send(P2PMessage::SubscribeSubnets(subscriptions)) {
+            warn!("Failed to send subscribe subnets message: {err}");
+        }
+    }
+
+    /// Asks the network worker for the depths of its queues.
+    pub async fn queue_depths(&self) -> anyhow::Result<NetworkQueueDepths> {
+        let (sender, receiver) = oneshot::channel();
+        self.0
+            .send(P2PMessage::QueueDepths(sender))
+            .map_err(|err| anyhow!("Failed to send queue depths message: {err}"))?;
+        receiver
+            .await
+            .map_err(|err| anyhow!("Failed to receive the queue depths: {err}"))
+    }
 }
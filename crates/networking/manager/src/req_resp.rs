@@ -1,13 +1,22 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
+use anyhow::anyhow;
 use libp2p::{PeerId, swarm::ConnectionId};
 use ream_consensus_beacon::blob_sidecar::BlobIdentifier;
+use ream_network_spec::networks::beacon_network_spec;
 use ream_p2p::{
+    constants::MAX_REQUEST_BLOCKS_DENEB,
     network::beacon::network_state::NetworkState,
-    req_resp::beacon::messages::{
-        BeaconRequestMessage, BeaconResponseMessage,
-        blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request},
-        blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request},
+    req_resp::{
+        beacon::messages::{
+            BeaconRequestMessage, BeaconResponseMessage,
+            blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request},
+            blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request},
+            data_column_sidecars::{
+                DataColumnSidecarsByRangeV1Request, DataColumnSidecarsByRootV1Request,
+            },
+        },
+        error::ReqRespError,
     },
 };
 use ream_storage::{db::beacon::BeaconDB, tables::table::Table};
@@ -48,26 +57,35 @@ pub async fn handle_req_resp_message(
             count,
             ..
         }) => {
-            for slot in start_slot..start_slot + count {
-                let Ok(Some(block_root)) = ream_db.slot_index_provider().get(slot) else {
-                    trace!("No block root found for slot {slot}");
-                    p2p_sender.send_error_response(
-                        peer_id,
-                        connection_id,
-                        stream_id,
-                        &format!("No block root found for slot {slot}"),
-                    );
-                    return;
-                };
-                let Ok(Some(block)) = ream_db.beacon_block_provider().get(block_root) else {
-                    trace!("No block found for root {block_root}");
-                    p2p_sender.send_error_response(
-                        peer_id,
-                        connection_id,
-                        stream_id,
-                        &format!("No block found for root {block_root}"),
-                    );
+            trace!(
+                ?peer_id,
+                start_slot, count, "Received BeaconBlocksByRange request"
+            );
+
+            let blocks = match requested_slots(ream_db, start_slot, count, MAX_REQUEST_BLOCKS_DENEB)
+                .and_then(|slots| Ok(ream_db.iter_canonical_blocks(slots)?))
+            {
+                Ok(blocks) => blocks,
+                Err(err) => {
+                    trace!("Failed to serve blocks by range: {err}");
+                    p2p_sender.send_req_resp_error(peer_id, connection_id, stream_id, err);
                     return;
+                }
+            };
+
+            for block in blocks {
+                let block = match block {
+                    Ok(block) => block,
+                    Err(err) => {
+                        trace!("Failed to read block: {err}");
+                        p2p_sender.send_error_response(
+                            peer_id,
+                            connection_id,
+                            stream_id,
+                            &format!("Failed to read block: {err}"),
+                        );
+                        return;
+                    }
                 };
 
                 p2p_sender.send_response(
@@ -107,17 +125,26 @@ pub async fn handle_req_resp_message(
             start_slot,
             count,
         }) => {
-            for slot in start_slot..start_slot + count {
-                let Ok(Some(block_root)) = ream_db.slot_index_provider().get(slot) else {
-                    trace!("No block root found for slot {slot}");
-                    p2p_sender.send_error_response(
-                        peer_id,
-                        connection_id,
-                        stream_id,
-                        &format!("No block root found for slot {slot}"),
-                    );
+            trace!(
+                ?peer_id,
+                start_slot, count, "Received BlobSidecarsByRange request"
+            );
+
+            let network_spec = beacon_network_spec();
+            let max_blocks = network_spec.max_request_blob_sidecars_electra
+                / network_spec.max_blobs_per_block_electra;
+            let block_roots = match requested_slots(ream_db, start_slot, count, max_blocks)
+                .and_then(|slots| Ok(ream_db.get_canonical_block_roots(slots)?))
+            {
+                Ok(block_roots) => block_roots,
+                Err(err) => {
+                    trace!("Failed to serve blob sidecars by range: {err}");
+                    p2p_sender.send_req_resp_error(peer_id, connection_id, stream_id, err);
                     return;
-                };
+                }
+            };
+
+            for (_, block_root) in block_roots {
                 let Ok(Some(block)) = ream_db.beacon_block_provider().get(block_root) else {
                     trace!("No block found for root {block_root}");
                     p2p_sender.send_error_response(
@@ -227,6 +254,66 @@ pub async fn handle_req_resp_message(
             }
             p2p_sender.send_end_of_stream_response(peer_id, connection_id, stream_id);
         }
+        // Data columns aren't stored yet, so there is nothing to serve
+        BeaconRequestMessage::DataColumnSidecarsByRange(DataColumnSidecarsByRangeV1Request {
+            start_slot,
+            count,
+            columns,
+        }) => {
+            trace!(
+                ?peer_id,
+                start_slot,
+                count,
+                ?columns,
+                "Received DataColumnSidecarsByRange request"
+            );
+            p2p_sender.send_end_of_stream_response(peer_id, connection_id, stream_id);
+        }
+        BeaconRequestMessage::DataColumnSidecarsByRoot(DataColumnSidecarsByRootV1Request {
+            inner,
+        }) => {
+            trace!(
+                ?peer_id,
+                identifiers = inner.len(),
+                "Received DataColumnSidecarsByRoot request"
+            );
+            p2p_sender.send_end_of_stream_response(peer_id, connection_id, stream_id);
+        }
         _ => warn!("This message shouldn't be handled in the network manager: {message:?}"),
     };
 }
+
+/// Returns the `count` slots from `start_slot`, capped at `max_blocks` slots, after checking that
+/// their blocks are available.
+fn requested_slots(
+    ream_db: &BeaconDB,
+    start_slot: u64,
+    count: u64,
+    max_blocks: u64,
+) -> Result<Range<u64>, ReqRespError> {
+    if count == 0 {
+        return Err(ReqRespError::InvalidData(
+            "Requested range is empty".to_string(),
+        ));
+    }
+
+    let earliest_available_slot = ream_db
+        .slot_index_provider()
+        .get_oldest_slot()
+        .map_err(|err| anyhow!("Failed to get oldest slot: {err}"))?;
+    match earliest_available_slot {
+        Some(earliest_available_slot) if earliest_available_slot <= start_slot => {}
+        Some(earliest_available_slot) => {
+            return Err(ReqRespError::ResourceUnavailable(format!(
+                "Blocks before slot {earliest_available_slot} are not available"
+            )));
+        }
+        None => {
+            return Err(ReqRespError::ResourceUnavailable(
+                "No blocks are available".to_string(),
+            ));
+        }
+    }
+
+    Ok(start_slot..start_slot.saturating_add(count.min(max_blocks)))
+}
@@ -1,12 +1,9 @@
-use std::{
-    path::PathBuf,
-    sync::Arc,
-    time::{Duration, SystemTime, UNIX_EPOCH},
-};
+use std::{path::PathBuf, sync::Arc};
 
-use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_chain_beacon::{beacon_chain::BeaconChain, validator_monitor::ValidatorMonitor};
+use ream_consensus_misc::constants::beacon::INTERVALS_PER_SLOT;
 use ream_discv5::{
-    config::DiscoveryConfig,
+    config::{DiscoveryChain, DiscoveryConfig, discv5_listen_config},
     subnet::{AttestationSubnets, SyncCommitteeSubnets},
 };
 use ream_execution_engine::ExecutionEngine;
@@ -17,12 +14,17 @@ use ream_p2p::{
     config::NetworkConfig,
     network::beacon::{Network, ReamNetworkEvent, network_state::NetworkState},
 };
-use ream_storage::{cache::CachedDB, db::beacon::BeaconDB};
-use ream_syncer::block_range::BlockRangeSyncer;
-use tokio::{sync::mpsc, time::interval};
+use ream_slot_clock::SlotClock;
+use ream_storage::{cache::CachedDB, db::beacon::BeaconDB, tables::field::Field};
+use ream_syncer::{
+    block_lookup::BlockLookups, block_range::BlockRangeSyncer,
+    data_column_sampling::DataColumnSampler, sync_progress::SyncProgress,
+};
+use tokio::sync::mpsc;
 use tracing::{error, info};
 
 use crate::{
+    admin::{AdminCommand, AdminSender, ManagerQueueDepths},
     config::ManagerConfig,
     gossipsub::handle::{handle_gossipsub_message, init_gossipsub_config_with_topics},
     p2p_sender::P2PSender,
@@ -35,8 +37,13 @@ pub struct NetworkManagerService {
     pub p2p_sender: P2PSender,
     pub network_state: Arc<NetworkState>,
     pub block_range_syncer: BlockRangeSyncer,
+    pub data_column_sampler: DataColumnSampler,
+    pub sync_progress: Arc<SyncProgress>,
     pub ream_db: BeaconDB,
     pub cached_db: CachedDB,
+    pub admin_sender: AdminSender,
+    admin_receiver: mpsc::UnboundedReceiver<AdminCommand>,
+    executor: ReamExecutor,
 }
 
 /// The `NetworkManagerService` acts as the manager for all networking activities in Ream.
@@ -60,8 +67,9 @@ impl NetworkManagerService {
         ream_dir: PathBuf,
         operation_pool: Arc<OperationPool>,
     ) -> anyhow::Result<Self> {
-        let discv5_config = discv5::ConfigBuilder::new(discv5::ListenConfig::from_ip(
-            config.socket_address,
+        let discv5_config = discv5::ConfigBuilder::new(discv5_listen_config(
+            config.discovery_address.unwrap_or(config.socket_address),
+            config.socket_address_v6,
             config.discovery_port,
         ))
         .build();
@@ -71,9 +79,13 @@ impl NetworkManagerService {
             .to_enrs_beacon(beacon_network_spec().network.clone());
         let discv5_config = DiscoveryConfig {
             discv5_config,
+            chain: DiscoveryChain::Beacon,
             bootnodes,
             socket_address: config.socket_address,
+            socket_address_v6: config.socket_address_v6,
+            advertised_address: config.advertised_address,
             socket_port: config.socket_port,
+            quic_port: (!config.disable_quic).then_some(config.quic_port),
             discovery_port: config.discovery_port,
             disable_discovery: config.disable_discovery,
             attestation_subnets: AttestationSubnets::new(),
@@ -85,41 +97,55 @@ impl NetworkManagerService {
         let network_config = NetworkConfig {
             discv5_config,
             gossipsub_config,
+            disable_upnp: config.disable_upnp,
+            target_peers: config.target_peers,
+            prefer_quic: config.prefer_quic,
             data_dir: ream_dir,
+            known_peers: Some(ream_db.known_peers_provider()),
         };
 
         let (manager_sender, manager_receiver) = mpsc::unbounded_channel();
         let (p2p_sender, p2p_receiver) = mpsc::unbounded_channel();
+        let (admin_sender, admin_receiver) = mpsc::unbounded_channel();
 
         let execution_engine = if let (Some(execution_endpoint), Some(jwt_path)) =
             (config.execution_endpoint, config.execution_jwt_secret)
         {
-            Some(ExecutionEngine::new(execution_endpoint, jwt_path)?)
+            let execution_engine = ExecutionEngine::new(execution_endpoint, jwt_path)?;
+            executor.spawn(execution_engine.clone().poll_status());
+            Some(execution_engine)
         } else {
             None
         };
-        let beacon_chain = Arc::new(BeaconChain::new(
-            ream_db.clone(),
-            operation_pool,
-            execution_engine,
-        ));
+        let beacon_chain = Arc::new(
+            BeaconChain::new(ream_db.clone(), operation_pool, execution_engine)
+                .with_validator_monitor(ValidatorMonitor::new(config.monitor_validators)),
+        );
         let status = beacon_chain.build_status_request().await?;
 
         let network = Network::init(executor.clone(), &network_config, status).await?;
 
         let network_state = network.network_state();
 
-        executor.spawn(async move {
-            network.start(manager_sender, p2p_receiver).await;
-        });
+        executor
+            .spawn_with_shutdown(|shutdown| network.start(manager_sender, p2p_receiver, shutdown));
 
+        let sync_progress = Arc::new(SyncProgress::default());
         let block_range_syncer = BlockRangeSyncer::new(
             beacon_chain.clone(),
             p2p_sender.clone(),
             network_state.clone(),
             executor.clone(),
+            sync_progress.clone(),
         );
 
+        let data_column_sampler = DataColumnSampler::new(
+            beacon_chain.clone(),
+            network_state.clone(),
+            p2p_sender.clone(),
+            executor.clone(),
+        )?;
+
         let cached_db = CachedDB::new();
 
         Ok(Self {
@@ -128,8 +154,13 @@ impl NetworkManagerService {
             p2p_sender: P2PSender(p2p_sender),
             network_state,
             block_range_syncer,
+            data_column_sampler,
+            sync_progress,
             ream_db,
             cached_db,
+            admin_sender: AdminSender(admin_sender),
+            admin_receiver,
+            executor,
         })
     }
 
@@ -146,10 +177,36 @@ impl NetworkManagerService {
             cached_db,
             network_state,
             block_range_syncer,
+            data_column_sampler,
+            sync_progress,
+            mut admin_receiver,
+            executor,
             ..
         } = self;
 
-        let mut interval = interval(Duration::from_secs(beacon_network_spec().seconds_per_slot));
+        let genesis_time = match beacon_chain
+            .store
+            .lock()
+            .await
+            .db
+            .genesis_time_provider()
+            .get()
+        {
+            Ok(genesis_time) => genesis_time,
+            Err(err) => {
+                error!("Failed to read the genesis time, falling back to the network spec: {err}");
+                beacon_network_spec().min_genesis_time
+            }
+        };
+        // Fork choice is ticked at the start of every interval
+        let clock = SlotClock::new(
+            genesis_time,
+            beacon_network_spec().seconds_per_slot,
+            INTERVALS_PER_SLOT,
+        );
+        let mut ticks = clock.ticks();
+        let block_lookups =
+            BlockLookups::new(beacon_chain.clone(), p2p_sender.0.clone(), executor.clone());
         let mut syncer_handle = block_range_syncer.start();
         loop {
             tokio::select! {
@@ -182,21 +239,46 @@ impl NetworkManagerService {
                         syncer_handle = block_range_syncer.start();
                     }
                 }
-                _ = interval.tick() => {
-                    let time = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .expect("correct time")
-                        .as_secs();
-
-                    if let Err(err) =  beacon_chain.process_tick(time).await {
+                Some(command) = admin_receiver.recv() => {
+                    match command {
+                        AdminCommand::Resync { start_slot } => {
+                            info!("Restarting the block range syncer from slot {start_slot}");
+                            syncer_handle.abort();
+                            syncer_handle = BlockRangeSyncer::new(
+                                beacon_chain.clone(),
+                                p2p_sender.0.clone(),
+                                network_state.clone(),
+                                executor.clone(),
+                                sync_progress.clone(),
+                            )
+                            .with_start_slot(start_slot)
+                            .start();
+                        }
+                        AdminCommand::FlushCaches => {
+                            info!("Flushing the caches of seen gossip messages");
+                            cached_db.clear().await;
+                        }
+                        AdminCommand::QueueDepths(sender) => {
+                            let queue_depths = ManagerQueueDepths {
+                                network_events: manager_receiver.len(),
+                                admin_commands: admin_receiver.len(),
+                            };
+                            if sender.send(queue_depths).is_err() {
+                                error!("Failed to send the queue depths of the manager");
+                            }
+                        }
+                    }
+                }
+                _ = ticks.next() => {
+                    if let Err(err) = beacon_chain.process_tick(clock.now().as_secs()).await {
                         error!("Failed to process gossipsub tick: {err}");
                     }
                 }
                 Some(event) = manager_receiver.recv() => {
                     match event {
                         // Handles Gossipsub messages from other peers.
-                        ReamNetworkEvent::GossipsubMessage { message } =>
-                            handle_gossipsub_message(message, &beacon_chain, &cached_db, &p2p_sender).await,
+                        ReamNetworkEvent::GossipsubMessage { message_id, propagation_source, message } =>
+                            handle_gossipsub_message(message_id, propagation_source, message, &beacon_chain, &cached_db, &p2p_sender, &block_lookups, &data_column_sampler).await,
                         // Handles Req/Resp messages from other peers.
                         ReamNetworkEvent::RequestMessage { peer_id, stream_id, connection_id, message } =>
                             handle_req_resp_message(peer_id, stream_id, connection_id, message, &p2p_sender, &ream_db, network_state.clone()).await,
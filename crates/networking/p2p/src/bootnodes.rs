@@ -5,7 +5,10 @@ use discv5::{Enr, multiaddr::Protocol};
 use libp2p::Multiaddr;
 use ream_network_spec::networks::Network;
 
-use crate::{network::misc::peer_id_from_enr, utils::quic_from_enr};
+use crate::{
+    network::misc::peer_id_from_enr,
+    utils::{quic_from_enr, quic6_from_enr},
+};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub enum Bootnodes {
@@ -90,6 +93,14 @@ impl Bootnodes {
         }
     }
 
+    /// The ENRs of the lean bootnodes, which discovery starts from.
+    pub fn to_enrs_lean(&self) -> Vec<Enr> {
+        match self {
+            Bootnodes::Custom(enrs) => enrs.clone(),
+            Bootnodes::Default | Bootnodes::None | Bootnodes::Multiaddr(_) => vec![],
+        }
+    }
+
     pub fn to_multiaddrs_lean(&self) -> Vec<Multiaddr> {
         match self {
             Bootnodes::Default => {
@@ -123,13 +134,21 @@ pub fn to_multiaddrs(enrs: &[Enr]) -> Vec<Multiaddr> {
                     multiaddrs.push(multiaddr);
                 }
             }
-            if let Some(ip6) = enr.ip6()
-                && let Some(tcp6) = enr.tcp6()
-            {
-                let mut multiaddr: Multiaddr = ip6.into();
-                multiaddr.push(Protocol::Tcp(tcp6));
-                multiaddr.push(Protocol::P2p(peer_id));
-                multiaddrs.push(multiaddr);
+            if let Some(ip6) = enr.ip6() {
+                if let Some(quic6) = quic6_from_enr(enr) {
+                    let mut multiaddr: Multiaddr = ip6.into();
+                    multiaddr.push(Protocol::Udp(quic6));
+                    multiaddr.push(Protocol::QuicV1);
+                    multiaddr.push(Protocol::P2p(peer_id));
+                    multiaddrs.push(multiaddr);
+                }
+
+                if let Some(tcp6) = enr.tcp6() {
+                    let mut multiaddr: Multiaddr = ip6.into();
+                    multiaddr.push(Protocol::Tcp(tcp6));
+                    multiaddr.push(Protocol::P2p(peer_id));
+                    multiaddrs.push(multiaddr);
+                }
             }
         }
     }
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use ream_discv5::config::DiscoveryConfig;
+use ream_storage::tables::known_peers::KnownPeersTable;
 
 use crate::gossipsub::beacon::configurations::GossipsubConfig;
 
@@ -9,5 +10,16 @@ pub struct NetworkConfig {
 
     pub gossipsub_config: GossipsubConfig,
 
+    pub disable_upnp: bool,
+
+    /// The number of connected peers to maintain
+    pub target_peers: usize,
+
+    /// Whether peers are dialed over QUIC before TCP when they advertise both
+    pub prefer_quic: bool,
+
     pub data_dir: PathBuf,
+
+    /// Where the connected peers are saved, to dial them first after a restart
+    pub known_peers: Option<KnownPeersTable>,
 }
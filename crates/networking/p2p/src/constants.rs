@@ -4,10 +4,61 @@ use alloy_primitives::{aliases::B32, fixed_bytes};
 
 /// The maximum allowed size of uncompressed payload in gossipsub messages and RPC chunks
 pub const MAX_PAYLOAD_SIZE: u64 = 10485760;
+/// Gossipsub v1.2 sends IDONTWANT to the mesh for received messages at least this large. Gossipsub
+/// only supports a single threshold, which is set so that blocks, blob and data column sidecars are
+/// covered while small and frequent messages like attestations are not.
+pub const IDONTWANT_MESSAGE_SIZE_THRESHOLD: usize = 1000;
 pub const MESSAGE_DOMAIN_VALID_SNAPPY: B32 = fixed_bytes!("0x01000000");
 pub const MESSAGE_DOMAIN_INVALID_SNAPPY: B32 = fixed_bytes!("0x00000000");
 
-pub const PING_INTERVAL_DURATION: Duration = Duration::from_secs(300);
-pub const TARGET_PEER_COUNT: usize = 50;
+/// The clock difference to peers tolerated when checking whether a gossip message is from a
+/// future slot
+pub const MAXIMUM_GOSSIP_CLOCK_DISPARITY: Duration = Duration::from_millis(500);
 
-pub const QUIC_ENR_KEY: &[u8] = b"quic";
+/// The maximum number of blocks in a single `beacon_blocks_by_range` or `blob_sidecars_by_range`
+/// response
+pub const MAX_REQUEST_BLOCKS_DENEB: u64 = 128;
+
+pub const PING_INTERVAL_DURATION: Duration = Duration::from_secs(30);
+/// A connected peer which hasn't answered a Ping or sent any request or response for this long is
+/// disconnected
+pub const PEER_LIVENESS_TIMEOUT: Duration = Duration::from_secs(90);
+/// How long the network keeps running on shutdown for its Goodbyes to reach the peers
+pub const GOODBYE_GRACE_PERIOD: Duration = Duration::from_secs(1);
+pub const PEER_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(10);
+/// Fraction of the target peer count reserved for outbound connections, so that the peers we dial
+/// can't be crowded out by inbound connections, e.g. of an attacker trying to eclipse us
+pub const OUTBOUND_PEER_RATIO: f64 = 0.2;
+/// Fraction of the target peer count connected above the target, before excess peers are pruned
+pub const PEER_EXCESS_RATIO: f64 = 0.1;
+
+/// The number of best connected peers saved to be dialed first after a restart
+pub const MAX_KNOWN_PEERS: usize = 100;
+/// Saved peers which haven't been connected for this long aren't dialed after a restart anymore
+pub const KNOWN_PEER_EXPIRY: Duration = Duration::from_secs(24 * 60 * 60);
+pub const KNOWN_PEERS_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Connected peer count below which the lean node discovers more peers
+pub const LEAN_MIN_PEER_COUNT: usize = 8;
+/// The delay before redialing a dropped lean peer, which is doubled on every failed redial
+pub const LEAN_REDIAL_BASE_DELAY: Duration = Duration::from_secs(1);
+pub const LEAN_REDIAL_MAX_DELAY: Duration = Duration::from_secs(300);
+/// The number of failed redials in a row after which a dropped lean peer is forgotten
+pub const LEAN_MAX_REDIAL_ATTEMPTS: u32 = 10;
+/// The maximum number of blocks in a single `lean_blocks_by_range` response
+pub const LEAN_MAX_REQUEST_BLOCKS: u64 = 1024;
+/// The number of slots the lean node requests at once while catching up with its peers
+pub const LEAN_BLOCKS_PER_RANGE_REQUEST: u64 = 64;
+/// The number of synced ranges which may wait to be imported before no more ranges are requested
+pub const LEAN_MAX_PENDING_RANGES: usize = 4;
+/// A synced range whose blocks weren't imported this long after they were handed to the chain is
+/// considered invalid
+pub const LEAN_SYNC_IMPORT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Number of distinct peers which have to observe the same IP address through identify before it
+/// is advertised in the ENR
+pub const OBSERVED_ADDRESS_CONFIRMATIONS: usize = 3;
+
+/// Number of epochs before a fork at which its topics are subscribed to, and after it at which the
+/// topics of the previous fork are dropped
+pub const FORK_TOPIC_TRANSITION_EPOCHS: u64 = 2;
@@ -1,12 +1,13 @@
 use std::time::Duration;
 
-use libp2p::gossipsub::{Config, ConfigBuilder, MessageId, ValidationMode};
+use libp2p::gossipsub::{Config, ConfigBuilder, ValidationMode};
 use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
 use ream_network_spec::networks::beacon_network_spec;
-use sha2::{Digest, Sha256};
 
 use super::topics::GossipTopic;
-use crate::{constants::MESSAGE_DOMAIN_VALID_SNAPPY, utils::max_message_size};
+use crate::{
+    constants::IDONTWANT_MESSAGE_SIZE_THRESHOLD, gossipsub::message_id, utils::max_message_size,
+};
 
 #[derive(Debug, Clone)]
 pub struct GossipsubConfig {
@@ -25,30 +26,19 @@ impl Default for GossipsubConfig {
             .mesh_n_low(6)
             .mesh_n_high(12)
             .gossip_lazy(6)
-            .history_length(12)
+            .history_length(6)
             .history_gossip(3)
             .max_messages_per_rpc(Some(500))
+            // seen_ttl is SLOTS_PER_EPOCH * SECONDS_PER_SLOT / heartbeat_interval heartbeats
             .duplicate_cache_time(Duration::from_secs(
-                SLOTS_PER_EPOCH * beacon_network_spec().seconds_per_slot * 2,
+                SLOTS_PER_EPOCH * beacon_network_spec().seconds_per_slot,
             ))
             .validate_messages()
             .validation_mode(ValidationMode::Anonymous)
             .allow_self_origin(true)
             .flood_publish(false)
-            .idontwant_message_size_threshold(1000)
-            .message_id_fn(move |message| {
-                MessageId::from(
-                    &Sha256::digest({
-                        let topic_bytes = message.topic.as_str().as_bytes();
-                        let mut digest = vec![];
-                        digest.extend_from_slice(MESSAGE_DOMAIN_VALID_SNAPPY.as_slice());
-                        digest.extend_from_slice(&topic_bytes.len().to_le_bytes());
-                        digest.extend_from_slice(topic_bytes);
-                        digest.extend_from_slice(&message.data);
-                        digest
-                    })[..20],
-                )
-            })
+            .idontwant_message_size_threshold(IDONTWANT_MESSAGE_SIZE_THRESHOLD)
+            .message_id_fn(message_id)
             .build()
             .expect("Failed to build gossipsub config");
 
@@ -64,3 +54,17 @@ impl GossipsubConfig {
         self.topics = topics;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_beacon::{blob_sidecar::BlobSidecar, single_attestation::SingleAttestation};
+    use ssz::Encode;
+
+    use super::*;
+
+    #[test]
+    fn test_idontwant_covers_blob_sidecars_but_not_attestations() {
+        assert!(<SingleAttestation as Encode>::ssz_fixed_len() < IDONTWANT_MESSAGE_SIZE_THRESHOLD);
+        assert!(<BlobSidecar as Encode>::ssz_fixed_len() >= IDONTWANT_MESSAGE_SIZE_THRESHOLD);
+    }
+}
@@ -1,8 +1,9 @@
 use libp2p::gossipsub::TopicHash;
 use ream_consensus_beacon::{
     attester_slashing::AttesterSlashing, blob_sidecar::BlobSidecar,
-    bls_to_execution_change::SignedBLSToExecutionChange, electra::beacon_block::SignedBeaconBlock,
-    proposer_slashing::ProposerSlashing, single_attestation::SingleAttestation,
+    bls_to_execution_change::SignedBLSToExecutionChange, data_column_sidecar::DataColumnSidecar,
+    electra::beacon_block::SignedBeaconBlock, proposer_slashing::ProposerSlashing,
+    single_attestation::SingleAttestation, versioned::VersionedSignedBeaconBlock,
     voluntary_exit::SignedVoluntaryExit,
 };
 use ream_consensus_misc::constants::beacon::genesis_validators_root;
@@ -25,7 +26,8 @@ pub enum GossipsubMessage {
     AttesterSlashing(Box<AttesterSlashing>),
     ProposerSlashing(Box<ProposerSlashing>),
     AggregateAndProof(Box<AggregateAndProof>),
-    BlobSidecar(Box<BlobSidecar>),
+    BlobSidecar((Box<BlobSidecar>, u64)),
+    DataColumnSidecar((Box<DataColumnSidecar>, u64)),
     BeaconAttestation((Box<SingleAttestation>, u64)),
     SyncCommittee((Box<SyncCommitteeMessage>, u64)),
     BlsToExecutionChange(Box<SignedBLSToExecutionChange>),
@@ -39,15 +41,17 @@ impl GossipsubMessage {
     pub fn decode(topic: &TopicHash, data: &[u8]) -> Result<Self, GossipsubError> {
         let gossip_topic = GossipTopic::from_topic_hash(topic)?;
 
-        if gossip_topic.fork != beacon_network_spec().fork_digest(genesis_validators_root()) {
+        let Some(fork_name) = beacon_network_spec()
+            .fork_name_from_digest(gossip_topic.fork, genesis_validators_root())
+        else {
             return Err(GossipsubError::InvalidTopic(format!(
                 "Invalid topic fork: {topic:?}"
             )));
-        }
+        };
 
         match gossip_topic.kind {
             GossipTopicKind::BeaconBlock => Ok(Self::BeaconBlock(Box::new(
-                SignedBeaconBlock::from_ssz_bytes(data)?,
+                VersionedSignedBeaconBlock::from_ssz_bytes_by_fork(data, fork_name)?.into_inner(),
             ))),
             GossipTopicKind::SyncCommittee(subnet_id) => Ok(Self::SyncCommittee((
                 Box::new(SyncCommitteeMessage::from_ssz_bytes(data)?),
@@ -74,8 +78,13 @@ impl GossipsubMessage {
             GossipTopicKind::ProposerSlashing => Ok(Self::ProposerSlashing(Box::new(
                 ProposerSlashing::from_ssz_bytes(data)?,
             ))),
-            GossipTopicKind::BlobSidecar(_) => Ok(Self::BlobSidecar(Box::new(
-                BlobSidecar::from_ssz_bytes(data)?,
+            GossipTopicKind::BlobSidecar(subnet_id) => Ok(Self::BlobSidecar((
+                Box::new(BlobSidecar::from_ssz_bytes(data)?),
+                subnet_id,
+            ))),
+            GossipTopicKind::DataColumnSidecar(subnet_id) => Ok(Self::DataColumnSidecar((
+                Box::new(DataColumnSidecar::from_ssz_bytes(data)?),
+                subnet_id,
             ))),
             GossipTopicKind::LightClientFinalityUpdate => Ok(Self::LightClientFinalityUpdate(
                 Box::new(LightClientFinalityUpdate::from_ssz_bytes(data)?),
@@ -1,3 +1,4 @@
 pub mod configurations;
 pub mod message;
+pub mod scoring;
 pub mod topics;
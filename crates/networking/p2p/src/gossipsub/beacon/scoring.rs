@@ -0,0 +1,197 @@
+//! Gossipsub v1.1 peer scoring parameters for the beacon topics, derived from the expected message
+//! rate of each topic.
+//!
+//! https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.1.md#peer-scoring
+
+use std::time::Duration;
+
+use libp2p::gossipsub::{PeerScoreParams, PeerScoreThresholds, TopicScoreParams};
+use ream_consensus_misc::constants::beacon::{DATA_COLUMN_SIDECAR_SUBNET_COUNT, SLOTS_PER_EPOCH};
+use ream_network_spec::networks::beacon_network_spec;
+use ream_validator_beacon::constants::SYNC_COMMITTEE_SUBNET_COUNT;
+
+use super::topics::GossipTopicKind;
+
+pub const GOSSIP_THRESHOLD: f64 = -4000.0;
+pub const PUBLISH_THRESHOLD: f64 = -8000.0;
+pub const GRAYLIST_THRESHOLD: f64 = -16000.0;
+
+/// The labels of the score ranges peers are counted in for the metrics.
+pub const SCORE_RANGES: [&str; 4] = ["graylisted", "below_publish", "below_gossip", "healthy"];
+
+/// The counters of a peer are decayed once per slot and dropped once they fall below this value.
+const DECAY_TO_ZERO: f64 = 0.01;
+/// The highest score a peer can get from the topic parameters.
+const MAX_POSITIVE_SCORE: f64 = 100.0;
+/// The weight of the invalid message deliveries of a topic, scaled by the topic weight. A single
+/// invalid message on a topic takes a peer most of the way to the graylist.
+const INVALID_MESSAGE_DELIVERIES_WEIGHT: f64 = GRAYLIST_THRESHOLD / 2.0;
+
+const BEACON_BLOCK_WEIGHT: f64 = 0.5;
+const AGGREGATE_AND_PROOF_WEIGHT: f64 = 0.5;
+const BEACON_ATTESTATION_SUBNETS_WEIGHT: f64 = 1.0;
+const SYNC_COMMITTEE_SUBNETS_WEIGHT: f64 = 0.2;
+const BLOB_SIDECAR_SUBNETS_WEIGHT: f64 = 0.5;
+const DATA_COLUMN_SIDECAR_SUBNETS_WEIGHT: f64 = 0.5;
+const OPERATION_WEIGHT: f64 = 0.05;
+const LIGHT_CLIENT_WEIGHT: f64 = 0.02;
+
+fn slot_duration() -> Duration {
+    Duration::from_secs(beacon_network_spec().seconds_per_slot)
+}
+
+fn epoch_duration() -> Duration {
+    slot_duration() * SLOTS_PER_EPOCH as u32
+}
+
+/// The per-slot decay factor which takes a counter to [DECAY_TO_ZERO] after `decay_time`.
+fn score_parameter_decay(decay_time: Duration) -> f64 {
+    let ticks = decay_time.as_secs_f64() / slot_duration().as_secs_f64();
+    DECAY_TO_ZERO.powf(1.0 / ticks)
+}
+
+pub fn peer_score_thresholds() -> PeerScoreThresholds {
+    PeerScoreThresholds {
+        gossip_threshold: GOSSIP_THRESHOLD,
+        publish_threshold: PUBLISH_THRESHOLD,
+        graylist_threshold: GRAYLIST_THRESHOLD,
+        accept_px_threshold: MAX_POSITIVE_SCORE,
+        opportunistic_graft_threshold: MAX_POSITIVE_SCORE / 20.0,
+    }
+}
+
+/// The label of the range `score` falls in, from [SCORE_RANGES].
+pub fn score_range(score: f64) -> &'static str {
+    if score < GRAYLIST_THRESHOLD {
+        SCORE_RANGES[0]
+    } else if score < PUBLISH_THRESHOLD {
+        SCORE_RANGES[1]
+    } else if score < GOSSIP_THRESHOLD {
+        SCORE_RANGES[2]
+    } else {
+        SCORE_RANGES[3]
+    }
+}
+
+/// The peer score parameters without any topics; the parameters of each topic are added with
+/// [topic_score_params] when it is subscribed to.
+pub fn peer_score_params() -> PeerScoreParams {
+    PeerScoreParams {
+        topic_score_cap: MAX_POSITIVE_SCORE / 2.0,
+        app_specific_weight: 1.0,
+        ip_colocation_factor_weight: -MAX_POSITIVE_SCORE,
+        ip_colocation_factor_threshold: 8.0,
+        behaviour_penalty_weight: GOSSIP_THRESHOLD / 100.0,
+        behaviour_penalty_threshold: 6.0,
+        behaviour_penalty_decay: score_parameter_decay(epoch_duration() * 10),
+        decay_interval: slot_duration(),
+        decay_to_zero: DECAY_TO_ZERO,
+        retain_score: epoch_duration() * 100,
+        ..Default::default()
+    }
+}
+
+/// The score parameters of a topic. Topics which are subdivided into subnets share their weight
+/// between the subnets, and only the topics with a steady message rate penalize mesh peers for
+/// not delivering messages.
+pub fn topic_score_params(kind: &GossipTopicKind) -> TopicScoreParams {
+    let network_spec = beacon_network_spec();
+    let (topic_weight, expected_messages_per_slot) = match kind {
+        GossipTopicKind::BeaconBlock => (BEACON_BLOCK_WEIGHT, Some(1.0)),
+        GossipTopicKind::AggregateAndProof => (AGGREGATE_AND_PROOF_WEIGHT, Some(16.0)),
+        GossipTopicKind::BeaconAttestation(_) => (
+            BEACON_ATTESTATION_SUBNETS_WEIGHT / network_spec.attestation_subnet_count as f64,
+            None,
+        ),
+        GossipTopicKind::SyncCommittee(_) => (
+            SYNC_COMMITTEE_SUBNETS_WEIGHT / SYNC_COMMITTEE_SUBNET_COUNT as f64,
+            None,
+        ),
+        GossipTopicKind::BlobSidecar(_) => (
+            BLOB_SIDECAR_SUBNETS_WEIGHT / network_spec.blob_sidecar_subnet_count_electra as f64,
+            None,
+        ),
+        GossipTopicKind::DataColumnSidecar(_) => (
+            DATA_COLUMN_SIDECAR_SUBNETS_WEIGHT / DATA_COLUMN_SIDECAR_SUBNET_COUNT as f64,
+            None,
+        ),
+        GossipTopicKind::VoluntaryExit
+        | GossipTopicKind::ProposerSlashing
+        | GossipTopicKind::AttesterSlashing
+        | GossipTopicKind::BlsToExecutionChange
+        | GossipTopicKind::SyncCommitteeContributionAndProof => (OPERATION_WEIGHT, None),
+        GossipTopicKind::LightClientFinalityUpdate
+        | GossipTopicKind::LightClientOptimisticUpdate => (LIGHT_CLIENT_WEIGHT, None),
+    };
+
+    let mut params = TopicScoreParams {
+        topic_weight,
+        time_in_mesh_weight: MAX_POSITIVE_SCORE / 10.0 / 300.0,
+        time_in_mesh_quantum: slot_duration(),
+        time_in_mesh_cap: 300.0,
+        first_message_deliveries_weight: 1.0,
+        first_message_deliveries_decay: score_parameter_decay(epoch_duration() * 20),
+        first_message_deliveries_cap: 100.0,
+        mesh_message_deliveries_weight: 0.0,
+        mesh_message_deliveries_decay: 0.0,
+        mesh_message_deliveries_cap: 0.0,
+        mesh_message_deliveries_threshold: 0.0,
+        mesh_message_deliveries_window: Duration::from_secs(2),
+        mesh_message_deliveries_activation: epoch_duration() * 4,
+        mesh_failure_penalty_weight: 0.0,
+        mesh_failure_penalty_decay: 0.0,
+        invalid_message_deliveries_weight: INVALID_MESSAGE_DELIVERIES_WEIGHT / topic_weight,
+        invalid_message_deliveries_decay: score_parameter_decay(epoch_duration() * 50),
+    };
+
+    if let Some(expected_messages_per_slot) = expected_messages_per_slot {
+        // Expect a mesh peer to deliver a fifth of the messages of the last epoch
+        let mesh_message_deliveries_decay = score_parameter_decay(epoch_duration());
+        let threshold = expected_messages_per_slot * SLOTS_PER_EPOCH as f64 / 5.0;
+        let mesh_penalty_weight = GOSSIP_THRESHOLD / topic_weight / (threshold * threshold);
+        params.mesh_message_deliveries_weight = mesh_penalty_weight;
+        params.mesh_message_deliveries_decay = mesh_message_deliveries_decay;
+        params.mesh_message_deliveries_cap = threshold * 4.0;
+        params.mesh_message_deliveries_threshold = threshold;
+        params.mesh_failure_penalty_weight = mesh_penalty_weight;
+        params.mesh_failure_penalty_decay = mesh_message_deliveries_decay;
+    }
+
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use libp2p::gossipsub::TopicHash;
+    use ream_network_spec::networks::initialize_test_network_spec;
+
+    use super::*;
+
+    #[test]
+    fn test_score_parameters_are_valid() {
+        initialize_test_network_spec();
+
+        let mut params = peer_score_params();
+        for (index, kind) in [
+            GossipTopicKind::BeaconBlock,
+            GossipTopicKind::AggregateAndProof,
+            GossipTopicKind::BeaconAttestation(0),
+            GossipTopicKind::SyncCommittee(0),
+            GossipTopicKind::BlobSidecar(0),
+            GossipTopicKind::DataColumnSidecar(0),
+            GossipTopicKind::VoluntaryExit,
+            GossipTopicKind::LightClientFinalityUpdate,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let topic_params = topic_score_params(&kind);
+            assert!(topic_params.validate().is_ok(), "{kind}");
+            params
+                .topics
+                .insert(TopicHash::from_raw(index.to_string()), topic_params);
+        }
+        assert!(params.validate().is_ok());
+        assert!(peer_score_thresholds().validate().is_ok());
+    }
+}
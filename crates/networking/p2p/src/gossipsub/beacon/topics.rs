@@ -22,6 +22,7 @@ pub const BLS_TO_EXECUTION_CHANGE_TOPIC: &str = "bls_to_execution_change";
 pub const LIGHT_CLIENT_FINALITY_UPDATE_TOPIC: &str = "light_client_finality_update";
 pub const LIGHT_CLIENT_OPTIMISTIC_UPDATE_TOPIC: &str = "light_client_optimistic_update";
 pub const BLOB_SIDECAR_PREFIX_TOPIC: &str = "blob_sidecar_";
+pub const DATA_COLUMN_SIDECAR_PREFIX_TOPIC: &str = "data_column_sidecar_";
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct GossipTopic {
@@ -58,6 +59,11 @@ impl GossipTopic {
                     .strip_prefix(BLOB_SIDECAR_PREFIX_TOPIC)
                     .and_then(|s| s.parse().ok())
                     .map(GossipTopicKind::BlobSidecar)
+            } else if topic.starts_with(DATA_COLUMN_SIDECAR_PREFIX_TOPIC) {
+                topic
+                    .strip_prefix(DATA_COLUMN_SIDECAR_PREFIX_TOPIC)
+                    .and_then(|s| s.parse().ok())
+                    .map(GossipTopicKind::DataColumnSidecar)
             } else {
                 None
             }
@@ -140,6 +146,12 @@ impl From<GossipTopic> for TopicHash {
                     val.fork.encode_hex(),
                 ));
             }
+            DataColumnSidecar(index) => {
+                return TopicHash::from_raw(format!(
+                    "/{TOPIC_PREFIX}/{}/{DATA_COLUMN_SIDECAR_PREFIX_TOPIC}{index}{ENCODING_POSTFIX}",
+                    val.fork.encode_hex(),
+                ));
+            }
         };
 
         TopicHash::from_raw(format!(
@@ -163,6 +175,34 @@ pub enum GossipTopicKind {
     LightClientFinalityUpdate,
     LightClientOptimisticUpdate,
     BlobSidecar(u64),
+    DataColumnSidecar(u64),
+}
+
+impl GossipTopicKind {
+    /// The name of the topic, without the index of the subnet for the subnet topics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GossipTopicKind::BeaconBlock => BEACON_BLOCK_TOPIC,
+            GossipTopicKind::AggregateAndProof => BEACON_AGGREGATE_AND_PROOF_TOPIC,
+            GossipTopicKind::VoluntaryExit => VOLUNTARY_EXIT_TOPIC,
+            GossipTopicKind::ProposerSlashing => PROPOSER_SLASHING_TOPIC,
+            GossipTopicKind::AttesterSlashing => ATTESTER_SLASHING_TOPIC,
+            GossipTopicKind::BeaconAttestation(_) => {
+                BEACON_ATTESTATION_PREFIX.trim_end_matches('_')
+            }
+            GossipTopicKind::SyncCommittee(_) => SYNC_COMMITTEE_PREFIX_TOPIC.trim_end_matches('_'),
+            GossipTopicKind::SyncCommitteeContributionAndProof => {
+                SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF_TOPIC
+            }
+            GossipTopicKind::BlsToExecutionChange => BLS_TO_EXECUTION_CHANGE_TOPIC,
+            GossipTopicKind::LightClientFinalityUpdate => LIGHT_CLIENT_FINALITY_UPDATE_TOPIC,
+            GossipTopicKind::LightClientOptimisticUpdate => LIGHT_CLIENT_OPTIMISTIC_UPDATE_TOPIC,
+            GossipTopicKind::BlobSidecar(_) => BLOB_SIDECAR_PREFIX_TOPIC.trim_end_matches('_'),
+            GossipTopicKind::DataColumnSidecar(_) => {
+                DATA_COLUMN_SIDECAR_PREFIX_TOPIC.trim_end_matches('_')
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for GossipTopicKind {
@@ -196,6 +236,9 @@ impl std::fmt::Display for GossipTopicKind {
             GossipTopicKind::BlobSidecar(blob_index) => {
                 write!(f, "{BLOB_SIDECAR_PREFIX_TOPIC}{blob_index}")
             }
+            GossipTopicKind::DataColumnSidecar(subnet_id) => {
+                write!(f, "{DATA_COLUMN_SIDECAR_PREFIX_TOPIC}{subnet_id}")
+            }
         }
     }
 }
@@ -1,11 +1,11 @@
 use std::time::Duration;
 
-use libp2p::gossipsub::{Config, ConfigBuilder, MessageId, ValidationMode};
+use libp2p::gossipsub::{Config, ConfigBuilder, ValidationMode};
 use ream_network_spec::networks::lean_network_spec;
-use sha2::{Digest, Sha256};
 
 use crate::{
-    constants::MESSAGE_DOMAIN_VALID_SNAPPY, gossipsub::lean::topics::LeanGossipTopic,
+    constants::IDONTWANT_MESSAGE_SIZE_THRESHOLD,
+    gossipsub::{lean::topics::LeanGossipTopic, message_id},
     utils::max_message_size,
 };
 
@@ -38,20 +38,8 @@ impl Default for LeanGossipsubConfig {
             .validation_mode(ValidationMode::Anonymous)
             .allow_self_origin(true)
             .flood_publish(false)
-            .idontwant_message_size_threshold(1000)
-            .message_id_fn(move |message| {
-                MessageId::from(
-                    &Sha256::digest({
-                        let topic_bytes = message.topic.as_str().as_bytes();
-                        let mut digest = vec![];
-                        digest.extend_from_slice(MESSAGE_DOMAIN_VALID_SNAPPY.as_slice());
-                        digest.extend_from_slice(&topic_bytes.len().to_le_bytes());
-                        digest.extend_from_slice(topic_bytes);
-                        digest.extend_from_slice(&message.data);
-                        digest
-                    })[..20],
-                )
-            })
+            .idontwant_message_size_threshold(IDONTWANT_MESSAGE_SIZE_THRESHOLD)
+            .message_id_fn(message_id)
             .build()
             .expect("Failed to build gossipsub config");
 
@@ -5,8 +5,50 @@ pub mod error;
 pub mod lean;
 pub mod snappy;
 
-use libp2p::gossipsub::{AllowAllSubscriptionFilter, Behaviour};
+use libp2p::gossipsub::{AllowAllSubscriptionFilter, Behaviour, Message, MessageId};
+use sha2::{Digest, Sha256};
 
-use crate::gossipsub::snappy::SnappyTransform;
+use crate::{constants::MESSAGE_DOMAIN_VALID_SNAPPY, gossipsub::snappy::SnappyTransform};
 
 pub type GossipsubBehaviour = Behaviour<SnappyTransform, AllowAllSubscriptionFilter>;
+
+/// The length of a gossip message-id in bytes.
+const MESSAGE_ID_LENGTH: usize = 20;
+
+/// The message-id of a gossip message, shared by the beacon and lean chains:
+/// `SHA256(MESSAGE_DOMAIN_VALID_SNAPPY + uint_to_bytes(uint64(len(topic))) + topic + data)[:20]`
+///
+/// `message.data` has already been decompressed by the [SnappyTransform], and messages which fail
+/// to decompress are rejected before their id is computed, so only the valid snappy domain is
+/// used.
+pub fn message_id(message: &Message) -> MessageId {
+    let topic_bytes = message.topic.as_str().as_bytes();
+    let mut hasher = Sha256::new();
+    hasher.update(MESSAGE_DOMAIN_VALID_SNAPPY);
+    hasher.update((topic_bytes.len() as u64).to_le_bytes());
+    hasher.update(topic_bytes);
+    hasher.update(&message.data);
+    MessageId::from(&hasher.finalize()[..MESSAGE_ID_LENGTH])
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+    use libp2p::gossipsub::TopicHash;
+
+    use super::*;
+
+    #[test]
+    fn test_message_id() {
+        let message = Message {
+            source: None,
+            data: vec![1, 2, 3],
+            sequence_number: None,
+            topic: TopicHash::from_raw("/eth2/00000000/beacon_block/ssz_snappy"),
+        };
+        assert_eq!(
+            message_id(&message).0,
+            hex!("7a4ad94ecae20a7a44ba01e9e9de12c8e9169818")
+        );
+    }
+}
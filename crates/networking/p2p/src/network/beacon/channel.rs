@@ -1,14 +1,20 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use libp2p::{PeerId, swarm::ConnectionId};
-use ream_consensus_beacon::blob_sidecar::BlobIdentifier;
-use tokio::sync::mpsc;
+use libp2p::{
+    Multiaddr, PeerId,
+    gossipsub::{MessageAcceptance, MessageId},
+    swarm::ConnectionId,
+};
+use ream_consensus_beacon::{
+    blob_sidecar::BlobIdentifier, data_column_sidecar::DataColumnsByRootIdentifier,
+};
+use tokio::sync::{mpsc, oneshot};
 
 use crate::{
-    gossipsub::beacon::topics::GossipTopic,
+    gossipsub::beacon::topics::{GossipTopic, GossipTopicKind},
     req_resp::{
-        beacon::messages::{BeaconResponseMessage, status::Status},
+        beacon::messages::{BeaconResponseMessage, goodbye::Goodbye, status::Status},
         handler::RespMessage,
     },
 };
@@ -24,6 +30,43 @@ pub enum P2PMessage {
     Request(P2PRequest),
     Response(P2PResponse),
     Gossip(GossipMessage),
+    /// The result of validating a received gossip message, which decides whether gossipsub
+    /// forwards it and how it scores the peer that propagated it.
+    GossipValidationResult {
+        message_id: MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    },
+    /// Dials the peer at the address, regardless of the target peer count.
+    DialPeer(Multiaddr),
+    /// Says goodbye to the peer with the reason and disconnects from it.
+    DisconnectPeer {
+        peer_id: PeerId,
+        reason: Goodbye,
+    },
+    /// Disconnects from the peer and refuses its connections until the node restarts.
+    BanPeer(PeerId),
+    /// Reports the number of items waiting in the queues of the network worker.
+    QueueDepths(oneshot::Sender<NetworkQueueDepths>),
+    /// Subscribes to the subnets of attestation and sync committee duties until they expire.
+    SubscribeSubnets(Vec<SubnetSubscription>),
+}
+
+/// A short-lived subscription to a subnet, requested by a validator client for its duties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubnetSubscription {
+    /// A [GossipTopicKind::BeaconAttestation] or [GossipTopicKind::SyncCommittee] topic
+    pub kind: GossipTopicKind,
+    /// The subscription is dropped once this slot starts
+    pub until_slot: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetworkQueueDepths {
+    /// The P2P messages waiting to be handled by the network worker
+    pub p2p_messages: usize,
+    /// The requests sent to peers which haven't been answered yet
+    pub pending_requests: usize,
 }
 
 pub enum P2PRequest {
@@ -47,6 +90,11 @@ pub enum P2PRequest {
         blob_identifiers: Vec<BlobIdentifier>,
         callback: mpsc::Sender<anyhow::Result<P2PCallbackResponse>>,
     },
+    DataColumnIdentifiers {
+        peer_id: PeerId,
+        identifiers: Vec<DataColumnsByRootIdentifier>,
+        callback: mpsc::Sender<anyhow::Result<P2PCallbackResponse>>,
+    },
 }
 
 pub struct P2PResponse {
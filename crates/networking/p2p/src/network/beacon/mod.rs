@@ -6,13 +6,17 @@ pub mod utils;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Debug,
+    net::{IpAddr, SocketAddr},
     num::{NonZeroU8, NonZeroUsize},
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use anyhow::anyhow;
-use channel::{P2PCallbackResponse, P2PMessage, P2PRequest, P2PResponse};
+use channel::{
+    NetworkQueueDepths, P2PCallbackResponse, P2PMessage, P2PRequest, P2PResponse,
+    SubnetSubscription,
+};
 use delay_map::{HashMapDelay, HashSetDelay};
 use discv5::Enr;
 use libp2p::{
@@ -20,22 +24,39 @@ use libp2p::{
     connection_limits::{self, ConnectionLimits},
     core::ConnectedPoint,
     futures::StreamExt,
-    gossipsub::{Event as GossipsubEvent, IdentTopic as Topic, Message, MessageAuthenticity},
+    gossipsub::{
+        Event as GossipsubEvent, IdentTopic as Topic, Message, MessageAuthenticity, MessageId,
+    },
     identify,
     multiaddr::Protocol,
-    swarm::{self, ConnectionId, NetworkBehaviour, SwarmEvent},
+    swarm::{
+        self, ConnectionId, NetworkBehaviour, SwarmEvent, behaviour::toggle::Toggle,
+        dial_opts::DialOpts,
+    },
+    upnp,
 };
 use libp2p_identity::{Keypair, PublicKey, secp256k1};
 use network_state::NetworkState;
 use parking_lot::{Mutex, RwLock};
 use peer::CachedPeer;
 use ream_consensus_misc::constants::beacon::genesis_validators_root;
-use ream_discv5::discovery::{Discovery, DiscoveryOutEvent, QueryType};
+use ream_discv5::{
+    discovery::{Discovery, DiscoveryOutEvent, QueryType},
+    eth2::EnrForkId,
+};
 use ream_executor::ReamExecutor;
+use ream_metrics::{
+    GOSSIPSUB_PEERS_BY_SCORE, HistogramTimer, PEERS, REQ_RESP_REQUEST_TIME, set_int_gauge_vec,
+    start_timer_vec, stop_timer,
+};
 use ream_network_spec::networks::beacon_network_spec;
+use ream_storage::tables::known_peers::KnownPeersTable;
 use tokio::{
-    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
-    time::interval,
+    sync::{
+        broadcast,
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+    },
+    time::{interval, timeout},
 };
 use tracing::{error, info, trace, warn};
 use utils::read_meta_data_from_disk;
@@ -43,10 +64,25 @@ use utils::read_meta_data_from_disk;
 use super::peer::Direction;
 use crate::{
     config::NetworkConfig,
-    constants::{PING_INTERVAL_DURATION, TARGET_PEER_COUNT},
-    gossipsub::{GossipsubBehaviour, beacon::topics::GossipTopic, snappy::SnappyTransform},
+    constants::{
+        FORK_TOPIC_TRANSITION_EPOCHS, GOODBYE_GRACE_PERIOD, KNOWN_PEERS_SAVE_INTERVAL,
+        OBSERVED_ADDRESS_CONFIRMATIONS, OUTBOUND_PEER_RATIO, PEER_EXCESS_RATIO,
+        PEER_LIVENESS_TIMEOUT, PEER_MAINTENANCE_INTERVAL, PING_INTERVAL_DURATION,
+    },
+    gossipsub::{
+        GossipsubBehaviour,
+        beacon::{
+            scoring::{
+                SCORE_RANGES, peer_score_params, peer_score_thresholds, score_range,
+                topic_score_params,
+            },
+            topics::{GossipTopic, GossipTopicKind},
+        },
+        snappy::SnappyTransform,
+    },
     network::{
-        misc::{Executor, build_transport, peer_id_from_enr},
+        known_peers::{load_known_peers, save_known_peers},
+        misc::{Executor, build_transport, peer_id_from_enr, tcp_socket_address_from_multiaddr},
         peer::ConnectionState,
     },
     req_resp::{
@@ -55,7 +91,9 @@ use crate::{
             BeaconRequestMessage, BeaconResponseMessage,
             blob_sidecars::BlobSidecarsByRootV1Request,
             blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request},
-            meta_data::GetMetaDataV2,
+            data_column_sidecars::DataColumnSidecarsByRootV1Request,
+            goodbye::Goodbye,
+            meta_data::GetMetaDataV3,
             ping::Ping,
             status::Status,
         },
@@ -63,6 +101,7 @@ use crate::{
         handler::{ReqRespMessageError, ReqRespMessageReceived, RespMessage},
         messages::{RequestMessage, ResponseMessage},
     },
+    utils::{quic_from_enr, quic6_from_enr},
 };
 
 #[derive(NetworkBehaviour)]
@@ -79,6 +118,9 @@ pub(crate) struct ReamBehaviour {
     pub gossipsub: GossipsubBehaviour,
 
     pub connection_registry: connection_limits::Behaviour,
+
+    /// Port mapping on the local gateway: UPnP only, NAT-PMP is not supported
+    pub upnp: Toggle<upnp::tokio::Behaviour>,
 }
 
 // TODO: these are stub events which needs to be replaced
@@ -95,6 +137,8 @@ pub enum ReamNetworkEvent {
         message: BeaconRequestMessage,
     },
     GossipsubMessage {
+        message_id: MessageId,
+        propagation_source: PeerId,
         message: Message,
     },
 }
@@ -105,14 +149,29 @@ pub struct Network {
     subscribed_topics: Arc<Mutex<HashSet<GossipTopic>>>,
     callbacks: HashMapDelay<u64, mpsc::Sender<anyhow::Result<P2PCallbackResponse>>>,
     request_id: u64,
+    /// The timers of the requests which haven't been answered yet, for the metrics
+    request_timers: HashMap<u64, HistogramTimer>,
     network_state: Arc<NetworkState>,
     peers_to_ping: HashSetDelay<PeerId>,
+    /// The address configured to be advertised, which takes precedence over discovered addresses
+    advertised_address: Option<IpAddr>,
+    socket_port: u16,
+    target_peers: usize,
+    enable_quic: bool,
+    prefer_quic: bool,
+    /// The peers which observed each of our IP addresses through identify
+    observed_addresses: HashMap<IpAddr, HashSet<PeerId>>,
+    /// The peers banned through the admin API, whose connections are refused
+    banned_peers: HashSet<PeerId>,
+    /// The subnets subscribed to for the duties of validators, with the slot they expire at
+    subnet_subscriptions: HashMap<GossipTopicKind, u64>,
+    known_peers: Option<KnownPeersTable>,
 }
 
 impl Network {
     /// Initializes the network by:
     /// - Creating a local keypair
-    /// - Setting up the discovery, req_resp and gossipsub behaviours
+    /// - Setting up the discovery, req_resp, gossipsub and UPnP behaviours
     /// - Starting P2P listening and discovery
     /// - Connecting to the configured bootnodes
     /// - Subscribing to the configured gossipsub topics
@@ -135,19 +194,29 @@ impl Network {
         let gossipsub = {
             let snappy_transform =
                 SnappyTransform::new(config.gossipsub_config.config.max_transmit_size());
-            GossipsubBehaviour::new_with_transform(
+            let mut gossipsub = GossipsubBehaviour::new_with_transform(
                 MessageAuthenticity::Anonymous,
                 config.gossipsub_config.config.clone(),
                 None,
                 snappy_transform,
             )
-            .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?
+            .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?;
+            gossipsub
+                .with_peer_score(peer_score_params(), peer_score_thresholds())
+                .map_err(|err| anyhow!("Failed to enable gossipsub peer scoring: {err}"))?;
+            gossipsub
         };
 
         let connection_limits = {
+            // Inbound connections can't take the slots reserved for outbound peers
+            let max_peers = max_peers(config.target_peers);
             let limits = ConnectionLimits::default()
                 .with_max_pending_incoming(Some(5))
                 .with_max_pending_outgoing(Some(16))
+                .with_max_established_incoming(Some(
+                    (max_peers - outbound_peer_quota(config.target_peers)) as u32,
+                ))
+                .with_max_established(Some(max_peers as u32))
                 .with_max_established_per_peer(Some(1));
 
             connection_limits::Behaviour::new(limits)
@@ -165,6 +234,8 @@ impl Network {
             identify::Behaviour::new(identify_config)
         };
 
+        let upnp = Toggle::from((!config.disable_upnp).then(upnp::tokio::Behaviour::default));
+
         let local_enr = discovery.local_enr();
         let behaviour = {
             ReamBehaviour {
@@ -173,11 +244,15 @@ impl Network {
                 gossipsub,
                 identify,
                 connection_registry: connection_limits,
+                upnp,
             }
         };
 
-        let transport = build_transport(Keypair::from(local_key.clone()))
-            .map_err(|err| anyhow!("Failed to build transport: {err:?}"))?;
+        let transport = build_transport(
+            Keypair::from(local_key.clone()),
+            config.discv5_config.quic_port.is_some(),
+        )
+        .map_err(|err| anyhow!("Failed to build transport: {err:?}"))?;
 
         let swarm = {
             let config = swarm::Config::with_executor(Executor(executor))
@@ -203,7 +278,7 @@ impl Network {
             meta_data: RwLock::new(
                 read_meta_data_from_disk(config.data_dir.clone()).unwrap_or_else(|err| {
                     error!("Failed to read meta data from disk: {err:?}");
-                    GetMetaDataV2::default()
+                    GetMetaDataV3::default()
                 }),
             ),
             status: RwLock::new(status),
@@ -216,8 +291,18 @@ impl Network {
             subscribed_topics: Arc::new(Mutex::new(HashSet::new())),
             callbacks: HashMapDelay::new(REQUEST_TIMEOUT),
             request_id: 0,
+            request_timers: HashMap::new(),
             network_state,
             peers_to_ping: HashSetDelay::new(PING_INTERVAL_DURATION),
+            advertised_address: config.discv5_config.advertised_address,
+            socket_port: config.discv5_config.socket_port,
+            target_peers: config.target_peers,
+            enable_quic: config.discv5_config.quic_port.is_some(),
+            prefer_quic: config.prefer_quic,
+            observed_addresses: HashMap::new(),
+            banned_peers: HashSet::new(),
+            subnet_subscriptions: HashMap::new(),
+            known_peers: config.known_peers.clone(),
         };
 
         network.start_network_worker(config).await?;
@@ -228,25 +313,56 @@ impl Network {
     async fn start_network_worker(&mut self, config: &NetworkConfig) -> anyhow::Result<()> {
         info!("Libp2p starting .... ");
 
-        let mut multi_addr: Multiaddr = config.discv5_config.socket_address.into();
-        multi_addr.push(Protocol::Tcp(config.discv5_config.socket_port));
-
-        match self.swarm.listen_on(multi_addr.clone()) {
-            Ok(listener_id) => {
-                info!(
-                    "Listening on {:?} with peer_id {:?} {listener_id:?}",
-                    multi_addr, self.peer_id
-                );
+        let mut listen_addresses = vec![];
+        for address in config.discv5_config.listen_addresses() {
+            let mut tcp_address: Multiaddr = address.into();
+            tcp_address.push(Protocol::Tcp(config.discv5_config.socket_port));
+            listen_addresses.push(tcp_address);
+            if let Some(quic_port) = config.discv5_config.quic_port {
+                let mut quic_address: Multiaddr = address.into();
+                quic_address.push(Protocol::Udp(quic_port));
+                quic_address.push(Protocol::QuicV1);
+                listen_addresses.push(quic_address);
             }
-            Err(err) => {
-                error!("Failed to start libp2p peer listen on {multi_addr:?}, error: {err:?}",);
+        }
+
+        for multi_addr in listen_addresses {
+            match self.swarm.listen_on(multi_addr.clone()) {
+                Ok(listener_id) => {
+                    info!(
+                        "Listening on {:?} with peer_id {:?} {listener_id:?}",
+                        multi_addr, self.peer_id
+                    );
+                }
+                Err(err) => {
+                    error!("Failed to start libp2p peer listen on {multi_addr:?}, error: {err:?}",);
+                }
             }
         }
 
+        if let Some(advertised_address) = self.advertised_address {
+            let mut external_address: Multiaddr = advertised_address.into();
+            external_address.push(Protocol::Tcp(self.socket_port));
+            info!("Advertising external address {external_address}");
+            self.swarm.add_external_address(external_address);
+        }
+
         let mut bootnodes = HashMap::new();
         for bootnode in config.discv5_config.bootnodes.clone() {
             bootnodes.insert(bootnode, None);
         }
+        // The peers of the previous run are dialed before discovery finds any
+        if let Some(known_peers) = &self.known_peers {
+            let discovery = &mut self.swarm.behaviour_mut().discovery;
+            for enr in load_known_peers(known_peers) {
+                // Peers saved before a fork may not have followed it
+                if !discovery.is_fork_compatible(&enr) {
+                    continue;
+                }
+                discovery.add_enr(enr.clone());
+                bootnodes.insert(enr, None);
+            }
+        }
         self.handle_discovered_peers(bootnodes);
 
         for topic in &config.gossipsub_config.topics {
@@ -291,17 +407,24 @@ impl Network {
     /// - A swarm event
     /// - A p2p message
     /// - A peer pinging
-    /// - An interval tick to perform p2p maintenance e.g. peer pinging, peer clean up and peer
-    ///   discovery
+    /// - An interval tick to perform p2p maintenance e.g. peer pinging, peer clean up, peer
+    ///   discovery and peer pruning
     ///
     /// The network worker will then route each event to the appropriate handler. The handlers are
     /// defined in `NetworkManagerService`.
+    ///
+    /// On `shutdown` the worker says goodbye to its peers and saves them before it stops.
     pub async fn start(
         mut self,
         manager_sender: UnboundedSender<ReamNetworkEvent>,
         mut p2p_receiver: UnboundedReceiver<P2PMessage>,
+        mut shutdown: broadcast::Receiver<()>,
     ) {
         let mut status_interval = interval(Duration::from_secs(30));
+        let mut peer_maintenance_interval = interval(PEER_MAINTENANCE_INTERVAL);
+        let mut known_peers_interval = interval(KNOWN_PEERS_SAVE_INTERVAL);
+        let mut fork_interval =
+            interval(Duration::from_secs(beacon_network_spec().seconds_per_slot));
         loop {
             tokio::select! {
                 Some(event) = self.swarm.next() => {
@@ -333,6 +456,13 @@ impl Network {
                                     warn!("Failed to send error response: {err:?}");
                                 }
                             }
+                            P2PRequest::DataColumnIdentifiers { peer_id, identifiers, callback } => {
+                                if let Some(request_id) = self.send_request(peer_id, BeaconRequestMessage::DataColumnSidecarsByRoot(DataColumnSidecarsByRootV1Request::new(identifiers))) {
+                                    self.callbacks.insert(request_id, callback);
+                                } else if let Err(err) = callback.send(Ok(P2PCallbackResponse::Disconnected)).await {
+                                    warn!("Failed to send error response: {err:?}");
+                                }
+                            }
                             P2PRequest::Status { peer_id, status } => {
                                 self.send_request(peer_id, BeaconRequestMessage::Status(status));
                             }
@@ -345,6 +475,26 @@ impl Network {
                                 warn!("Failed to publish gossip message: {err}");
                             }
                         }
+                        P2PMessage::GossipValidationResult { message_id, propagation_source, acceptance } => {
+                            self.swarm.behaviour_mut().gossipsub.report_message_validation_result(&message_id, &propagation_source, acceptance);
+                        }
+                        P2PMessage::DialPeer(address) => self.dial_peer(address),
+                        P2PMessage::DisconnectPeer { peer_id, reason } => self.disconnect_peer(peer_id, reason),
+                        P2PMessage::BanPeer(peer_id) => {
+                            info!("Banning peer {peer_id}");
+                            self.banned_peers.insert(peer_id);
+                            self.disconnect_peer(peer_id, Goodbye::Banned);
+                        }
+                        P2PMessage::QueueDepths(sender) => {
+                            let queue_depths = NetworkQueueDepths {
+                                p2p_messages: p2p_receiver.len(),
+                                pending_requests: self.callbacks.len(),
+                            };
+                            if sender.send(queue_depths).is_err() {
+                                warn!("Failed to send the queue depths of the network worker");
+                            }
+                        }
+                        P2PMessage::SubscribeSubnets(subscriptions) => self.subscribe_subnets(subscriptions),
                     }
                 }
                 Some(Ok(peer_id)) = self.peers_to_ping.next() => {
@@ -364,7 +514,24 @@ impl Network {
                         warn!("Failed to send timeout response: {err:?}");
                     }
                 }
+                _ = fork_interval.tick() => {
+                    self.prune_subnet_subscriptions();
+                    self.update_fork_subscriptions();
+                    self.update_enr_fork_id();
+                }
+                _ = peer_maintenance_interval.tick() => {
+                    self.maintain_peers();
+                }
+                _ = known_peers_interval.tick() => {
+                    self.save_known_peers();
+                }
+                _ = shutdown.recv() => {
+                    self.shut_down().await;
+                    return;
+                }
                 _ = status_interval.tick() => {
+                    self.update_peer_scores();
+
                     let now = Instant::now();
                     let mut peer_table = self.network_state.peer_table.write();
 
@@ -386,19 +553,10 @@ impl Network {
                         }
                     }
 
-                    let peer_count = peer_table.len();
                     let peers_to_ping_count = self.peers_to_ping.len();
                     let seq_number = self.network_state.meta_data.read().seq_number;
 
                     info!("Peer statuses: {counts:?}, Peers with Status {status_is_some_count}, Peers with MetaData {meta_data_some_count}, Peers to ping: {peers_to_ping_count}, MetaData seq_number: {seq_number}");
-
-                    if peer_count < TARGET_PEER_COUNT {
-                        info!("Peer count is below target: {peer_count}, discovering more peers");
-                        self.swarm
-                            .behaviour_mut()
-                            .discovery
-                            .discover_peers(QueryType::Peers, 16);
-                    }
                 }
             }
         }
@@ -410,6 +568,12 @@ impl Network {
         }
 
         let request_id = self.request_id();
+        if !matches!(message, BeaconRequestMessage::Goodbye(_)) {
+            self.request_timers.insert(
+                request_id,
+                start_timer_vec(&REQ_RESP_REQUEST_TIME, &[message.name()]),
+            );
+        }
         self.swarm.behaviour_mut().req_resp.send_request(
             peer_id,
             request_id,
@@ -457,6 +621,12 @@ impl Network {
             SwarmEvent::ConnectionEstablished {
                 peer_id, endpoint, ..
             } => {
+                if self.banned_peers.contains(&peer_id) {
+                    trace!("Refusing the connection of banned peer {peer_id}");
+                    let _ = self.swarm.disconnect_peer_id(peer_id);
+                    return None;
+                }
+
                 if let ConnectedPoint::Listener { send_back_addr, .. } = &endpoint {
                     self.network_state.upsert_peer(
                         peer_id,
@@ -494,7 +664,14 @@ impl Network {
                 }
             }
             SwarmEvent::Behaviour(behaviour_event) => match behaviour_event {
-                ReamBehaviourEvent::Identify(_) => None,
+                ReamBehaviourEvent::Identify(event) => {
+                    self.handle_identify_event(event);
+                    None
+                }
+                ReamBehaviourEvent::Upnp(event) => {
+                    self.handle_upnp_event(event);
+                    None
+                }
                 ReamBehaviourEvent::Discovery(discovery_event) => match discovery_event {
                     DiscoveryOutEvent::DiscoveredPeers { peers } => {
                         self.handle_discovered_peers(peers);
@@ -521,48 +698,266 @@ impl Network {
         }
     }
 
+    /// Keeps the connected peers at the target peer count:
+    /// - Disconnects peers which stopped answering our Pings
+    /// - Discovers more peers while below the target or the outbound quota, or while no connected
+    ///   peer serves a subnet we are subscribed to for duties
+    /// - Prunes the lowest scoring peers above the target, keeping the outbound quota, by saying
+    ///   goodbye first and disconnecting on the next maintenance
+    fn maintain_peers(&mut self) {
+        let disconnecting_peers = self
+            .network_state
+            .peer_table
+            .read()
+            .values()
+            .filter(|peer| peer.state == ConnectionState::Disconnecting)
+            .map(|peer| peer.peer_id)
+            .collect::<Vec<_>>();
+        for peer_id in disconnecting_peers {
+            if self.swarm.disconnect_peer_id(peer_id).is_err() {
+                self.network_state
+                    .update_peer_state(peer_id, ConnectionState::Disconnected);
+            }
+        }
+
+        let unresponsive_peers = self
+            .network_state
+            .connected_peers()
+            .into_iter()
+            .filter(|peer| peer.last_seen.elapsed() > PEER_LIVENESS_TIMEOUT)
+            .map(|peer| peer.peer_id)
+            .collect::<Vec<_>>();
+        for peer_id in unresponsive_peers {
+            warn!("Peer {peer_id} stopped answering our Pings");
+            self.disconnect_peer(peer_id, Goodbye::FaultOrError);
+        }
+
+        let outbound_quota = outbound_peer_quota(self.target_peers);
+        let (peer_count, outbound_peer_count) = self.connected_peer_counts();
+        if peer_count < self.target_peers || outbound_peer_count < outbound_quota {
+            info!(
+                "Peer count: {peer_count}, outbound peer count: {outbound_peer_count}, target: {}, outbound quota: {outbound_quota}, discovering more peers",
+                self.target_peers
+            );
+            self.swarm
+                .behaviour_mut()
+                .discovery
+                .discover_peers(QueryType::Peers, 16);
+        }
+        self.discover_subnet_peers();
+
+        let peers_to_prune = self
+            .network_state
+            .peers_to_prune(self.target_peers, outbound_quota);
+        if !peers_to_prune.is_empty() {
+            info!(
+                "Peer count: {peer_count} is above target: {}, pruning {} peers",
+                self.target_peers,
+                peers_to_prune.len()
+            );
+        }
+        for peer_id in peers_to_prune {
+            self.send_request(
+                peer_id,
+                BeaconRequestMessage::Goodbye(Goodbye::TooManyPeers),
+            );
+            self.network_state
+                .update_peer_state(peer_id, ConnectionState::Disconnecting);
+            self.peers_to_ping.remove(&peer_id);
+        }
+    }
+
+    /// Discovers peers for the duty subnets which no connected peer advertises in its MetaData.
+    fn discover_subnet_peers(&mut self) {
+        let (mut attestation_subnets, mut sync_committee_subnets) = (vec![], vec![]);
+        for kind in self.subnet_subscriptions.keys() {
+            if self.network_state.subnet_peer_count(*kind) > 0 {
+                continue;
+            }
+            match kind {
+                GossipTopicKind::BeaconAttestation(subnet_id) => {
+                    attestation_subnets.push(*subnet_id as u8)
+                }
+                GossipTopicKind::SyncCommittee(subnet_id) => {
+                    sync_committee_subnets.push(*subnet_id as u8)
+                }
+                _ => {}
+            }
+        }
+
+        let discovery = &mut self.swarm.behaviour_mut().discovery;
+        if !attestation_subnets.is_empty() {
+            info!("No peers on attestation subnets {attestation_subnets:?}, discovering peers");
+            discovery.discover_peers(QueryType::AttestationSubnetPeers(attestation_subnets), 16);
+        }
+        if !sync_committee_subnets.is_empty() {
+            info!(
+                "No peers on sync committee subnets {sync_committee_subnets:?}, discovering peers"
+            );
+            discovery.discover_peers(
+                QueryType::SyncCommitteeSubnetPeers(sync_committee_subnets),
+                16,
+            );
+        }
+    }
+
+    /// Dials the peer at `address`, which is given through the admin API.
+    fn dial_peer(&mut self, address: Multiaddr) {
+        let peer_id = address.iter().find_map(|protocol| match protocol {
+            Protocol::P2p(peer_id) => Some(peer_id),
+            _ => None,
+        });
+        if let Some(peer_id) = peer_id
+            && self.banned_peers.contains(&peer_id)
+        {
+            warn!("Not dialing banned peer {peer_id}");
+            return;
+        }
+
+        info!("Dialing peer at {address}");
+        if let Err(err) = self.swarm.dial(address.clone()) {
+            warn!("Failed to dial peer at {address}: {err:?}");
+            return;
+        }
+        if let Some(peer_id) = peer_id {
+            self.network_state.upsert_peer(
+                peer_id,
+                Some(address),
+                ConnectionState::Connecting,
+                Direction::Outbound,
+                None,
+            );
+        }
+    }
+
+    /// Says goodbye to the peer, which is disconnected on the next peer maintenance.
+    fn disconnect_peer(&mut self, peer_id: PeerId, reason: Goodbye) {
+        if !self.swarm.is_connected(&peer_id) {
+            return;
+        }
+        info!("Disconnecting peer {peer_id}: {reason:?}");
+        self.send_request(peer_id, BeaconRequestMessage::Goodbye(reason));
+        self.network_state
+            .update_peer_state(peer_id, ConnectionState::Disconnecting);
+        self.peers_to_ping.remove(&peer_id);
+    }
+
+    /// Says goodbye to every connected peer and saves them, then keeps the swarm running for the
+    /// grace period so the Goodbyes are sent.
+    async fn shut_down(&mut self) {
+        info!("Saying goodbye to all peers before shutting down");
+        for peer in self.network_state.connected_peers() {
+            self.send_request(
+                peer.peer_id,
+                BeaconRequestMessage::Goodbye(Goodbye::ClientShutdown),
+            );
+        }
+        self.save_known_peers();
+        let _ = timeout(GOODBYE_GRACE_PERIOD, async {
+            while self.swarm.next().await.is_some() {}
+        })
+        .await;
+    }
+
+    /// Returns the number of connected peers and how many of them are outbound.
+    fn connected_peer_counts(&self) -> (usize, usize) {
+        let connected_peers = self.network_state.connected_peers();
+        let outbound_peer_count = connected_peers
+            .iter()
+            .filter(|peer| peer.direction == Direction::Outbound)
+            .count();
+        (connected_peers.len(), outbound_peer_count)
+    }
+
     fn handle_discovered_peers(&mut self, peers: HashMap<Enr, Option<Instant>>) {
         trace!("Discovered peers: {peers:?}");
+        let (peer_count, outbound_peer_count) = self.connected_peer_counts();
+        if peer_count >= self.target_peers
+            && outbound_peer_count >= outbound_peer_quota(self.target_peers)
+        {
+            trace!("Peer count: {peer_count} is at target, not dialing discovered peers");
+            return;
+        }
         for (enr, _) in peers {
-            let mut multiaddrs: Vec<Multiaddr> = Vec::new();
+            let Some(peer_id) = peer_id_from_enr(&enr) else {
+                trace!("Failed to derive peer id from ENR: {enr:?}");
+                continue;
+            };
+            if self.banned_peers.contains(&peer_id) {
+                continue;
+            }
+            // Peers which said goodbye aren't redialed until the peer table forgets them
+            if self
+                .cached_peer(&peer_id)
+                .and_then(|peer| peer.goodbye)
+                .is_some_and(|reason| reason.rejects_us())
+            {
+                trace!("Not dialing peer {peer_id}, which said goodbye");
+                continue;
+            }
+
+            let mut tcp_multiaddrs: Vec<Multiaddr> = Vec::new();
             if let Some(ip) = enr.ip4()
                 && let Some(tcp) = enr.tcp4()
             {
                 let mut multiaddr: Multiaddr = ip.into();
                 multiaddr.push(Protocol::Tcp(tcp));
-                multiaddrs.push(multiaddr);
+                tcp_multiaddrs.push(multiaddr);
             }
             if let Some(ip6) = enr.ip6()
                 && let Some(tcp6) = enr.tcp6()
             {
                 let mut multiaddr: Multiaddr = ip6.into();
                 multiaddr.push(Protocol::Tcp(tcp6));
-                multiaddrs.push(multiaddr);
+                tcp_multiaddrs.push(multiaddr);
             }
 
-            let mut successfully_dialed = false;
-            for multiaddr in multiaddrs {
-                if let Err(err) = self.swarm.dial(multiaddr) {
-                    warn!("Failed to dial peer: {err:?}");
-                } else {
-                    successfully_dialed = true;
+            let mut quic_multiaddrs: Vec<Multiaddr> = Vec::new();
+            if self.enable_quic {
+                if let Some(ip) = enr.ip4()
+                    && let Some(quic) = quic_from_enr(&enr)
+                {
+                    let mut multiaddr: Multiaddr = ip.into();
+                    multiaddr.push(Protocol::Udp(quic));
+                    multiaddr.push(Protocol::QuicV1);
+                    quic_multiaddrs.push(multiaddr);
+                }
+                if let Some(ip6) = enr.ip6()
+                    && let Some(quic6) = quic6_from_enr(&enr)
+                {
+                    let mut multiaddr: Multiaddr = ip6.into();
+                    multiaddr.push(Protocol::Udp(quic6));
+                    multiaddr.push(Protocol::QuicV1);
+                    quic_multiaddrs.push(multiaddr);
                 }
             }
 
-            if !successfully_dialed {
-                trace!("Failed to dial any multiaddr for peer: {:?}", enr);
+            let multiaddrs = if self.prefer_quic {
+                [quic_multiaddrs, tcp_multiaddrs].concat()
+            } else {
+                [tcp_multiaddrs, quic_multiaddrs].concat()
+            };
+            if multiaddrs.is_empty() {
+                trace!("No multiaddr to dial for peer: {enr:?}");
                 continue;
             }
 
-            if let Some(peer_id) = peer_id_from_enr(&enr) {
-                self.network_state.upsert_peer(
-                    peer_id,
-                    None,
-                    ConnectionState::Connecting,
-                    Direction::Outbound,
-                    Some(enr.clone()),
-                );
+            // With a dial concurrency factor of 1 the addresses are tried in order of preference
+            if let Err(err) = self
+                .swarm
+                .dial(DialOpts::peer_id(peer_id).addresses(multiaddrs).build())
+            {
+                warn!("Failed to dial peer {peer_id}: {err:?}");
+                continue;
             }
+
+            self.network_state.upsert_peer(
+                peer_id,
+                None,
+                ConnectionState::Connecting,
+                Direction::Outbound,
+                Some(enr.clone()),
+            );
         }
     }
 
@@ -588,6 +983,11 @@ impl Network {
         let message = match message {
             Ok(message) => message,
             Err(err) => {
+                if let ReqRespMessageError::Outbound { request_id, .. } = &err
+                    && let Some(timer) = self.request_timers.remove(request_id)
+                {
+                    timer.stop_and_discard();
+                }
                 if let ReqRespMessageError::Outbound { request_id, .. } = &err
                     && let Some(callback) = self.callbacks.get(request_id)
                     && let Err(err) = callback.send(Err(anyhow!("{err:?}"))).await
@@ -602,13 +1002,13 @@ impl Network {
             ReqRespMessageReceived::Request { stream_id, message } => {
                 if let RequestMessage::Beacon(message) = *message {
                     match message {
-                        BeaconRequestMessage::MetaData(get_meta_data_v2) => {
+                        BeaconRequestMessage::MetaData(get_meta_data) => {
                             trace!(
                                 ?peer_id,
                                 ?stream_id,
                                 ?connection_id,
-                                ?get_meta_data_v2,
-                                "Received GetMetaDataV2 request"
+                                ?get_meta_data,
+                                "Received GetMetaData request"
                             );
                             let response = BeaconResponseMessage::MetaData(
                                 self.network_state.meta_data.read().clone().into(),
@@ -628,6 +1028,7 @@ impl Network {
                                 self.network_state.meta_data.read().seq_number,
                             ));
                             self.send_response(peer_id, connection_id, stream_id, response);
+                            self.refresh_meta_data(peer_id, ping.sequence_number);
                             None
                         }
                         BeaconRequestMessage::Goodbye(goodbye) => {
@@ -638,6 +1039,9 @@ impl Network {
                                 ?goodbye,
                                 "Received Goodbye message"
                             );
+                            info!("Peer {peer_id} said goodbye: {goodbye:?}");
+                            self.network_state.record_goodbye(peer_id, goodbye);
+                            self.peers_to_ping.remove(&peer_id);
                             None
                         }
                         BeaconRequestMessage::Status(status) => {
@@ -677,6 +1081,9 @@ impl Network {
                 request_id,
                 message,
             } => {
+                if let Some(timer) = self.request_timers.remove(&request_id) {
+                    stop_timer(timer);
+                }
                 if let ResponseMessage::Beacon(beacon_response_message) = *message {
                     match beacon_response_message.as_ref() {
                         BeaconResponseMessage::MetaData(meta_data) => {
@@ -702,22 +1109,7 @@ impl Network {
                                 "Received Ping response: seq_number: {}",
                                 ping.sequence_number
                             );
-
-                            let cached_peer =
-                                self.network_state.peer_table.read().get(&peer_id).cloned();
-                            if let Some(cached_peer) = cached_peer
-                                && (cached_peer.meta_data.is_none()
-                                    || ping.sequence_number
-                                        != cached_peer
-                                            .meta_data
-                                            .as_ref()
-                                            .map_or(0, |meta_data| meta_data.seq_number))
-                            {
-                                let meta_data_message = BeaconRequestMessage::MetaData(
-                                    self.network_state.meta_data.read().clone().into(),
-                                );
-                                self.send_request(peer_id, meta_data_message);
-                            }
+                            self.refresh_meta_data(peer_id, ping.sequence_number);
                         }
                         BeaconResponseMessage::Status(status) => {
                             trace!(
@@ -753,6 +1145,9 @@ impl Network {
                 None
             }
             ReqRespMessageReceived::EndOfStream { request_id } => {
+                if let Some(timer) = self.request_timers.remove(&request_id) {
+                    stop_timer(timer);
+                }
                 let callback = self.callbacks.remove(&request_id);
                 if let Some(callback) = callback
                     && let Err(err) = callback.send(Ok(P2PCallbackResponse::EndOfStream)).await
@@ -764,6 +1159,21 @@ impl Network {
         }
     }
 
+    /// Fetches the MetaData of the peer when the `seq_number` it reported in a Ping is newer than
+    /// the MetaData we have, so we know which subnets it serves after it changes subscriptions.
+    fn refresh_meta_data(&mut self, peer_id: PeerId, seq_number: u64) {
+        if !self
+            .network_state
+            .is_meta_data_outdated(&peer_id, seq_number)
+        {
+            return;
+        }
+        trace!(?peer_id, seq_number, "Fetching the MetaData of the peer");
+        let meta_data_message =
+            BeaconRequestMessage::MetaData(self.network_state.meta_data.read().clone().into());
+        self.send_request(peer_id, meta_data_message);
+    }
+
     fn handle_status_req_resp_event(&mut self, peer_id: PeerId, status: Status) {
         if self.network_state.peer_table.read().get(&peer_id).is_some() {
             // We only want to have peers on the same network as us
@@ -788,13 +1198,83 @@ impl Network {
         }
     }
 
+    /// Advertises the IP address our peers observe us at once enough distinct peers agree on it,
+    /// unless an address is configured to be advertised.
+    fn handle_identify_event(&mut self, event: identify::Event) {
+        let identify::Event::Received { peer_id, info, .. } = event else {
+            return;
+        };
+        if self.advertised_address.is_some() {
+            return;
+        }
+        let Some(observed_address) = tcp_socket_address_from_multiaddr(&info.observed_addr) else {
+            return;
+        };
+        let ip = observed_address.ip();
+        if ip.is_loopback() || ip.is_unspecified() {
+            return;
+        }
+
+        let observers = self.observed_addresses.entry(ip).or_default();
+        observers.insert(peer_id);
+        if observers.len() >= OBSERVED_ADDRESS_CONFIRMATIONS {
+            // The observed port is the ephemeral port of our outbound connection, so advertise the
+            // port we listen on
+            self.observed_addresses.clear();
+            self.update_external_address(SocketAddr::new(ip, self.socket_port));
+        }
+    }
+
+    /// Advertises the external address of the TCP port mapped on the gateway. The discovery UDP
+    /// port is not mapped, discv5 discovers its external socket on its own.
+    fn handle_upnp_event(&mut self, event: upnp::Event) {
+        match event {
+            upnp::Event::NewExternalAddr(address) => {
+                info!("UPnP mapped external address {address}");
+                if self.advertised_address.is_none()
+                    && let Some(socket_address) = tcp_socket_address_from_multiaddr(&address)
+                {
+                    self.update_external_address(socket_address);
+                }
+            }
+            upnp::Event::ExpiredExternalAddr(address) => {
+                warn!("UPnP mapping of external address {address} expired");
+            }
+            upnp::Event::GatewayNotFound => {
+                info!("UPnP gateway not found, ports are not mapped");
+            }
+            upnp::Event::NonRoutableGateway => {
+                warn!("UPnP gateway is not exposed directly to the public network");
+            }
+        }
+    }
+
+    /// Updates the IP address and TCP port of the local ENR and adds them to the external
+    /// addresses of the swarm.
+    fn update_external_address(&mut self, socket_address: SocketAddr) {
+        let discovery = &mut self.swarm.behaviour_mut().discovery;
+        if discovery.update_enr_tcp_socket(socket_address) {
+            let enr = discovery.local_enr();
+            info!("Updated local ENR with external address {socket_address}: {enr}");
+            *self.network_state.local_enr.write() = enr;
+        }
+
+        let mut external_address: Multiaddr = socket_address.ip().into();
+        external_address.push(Protocol::Tcp(socket_address.port()));
+        self.swarm.add_external_address(external_address);
+    }
+
     fn handle_gossipsub_event(&mut self, event: GossipsubEvent) -> Option<ReamNetworkEvent> {
         match event {
             GossipsubEvent::Message {
-                propagation_source: _,
-                message_id: _,
+                propagation_source,
+                message_id,
+                message,
+            } => Some(ReamNetworkEvent::GossipsubMessage {
+                message_id,
+                propagation_source,
                 message,
-            } => Some(ReamNetworkEvent::GossipsubMessage { message }),
+            }),
             GossipsubEvent::Subscribed { peer_id, topic } => {
                 trace!("Peer {peer_id} subscribed to topic: {topic:?}");
                 None
@@ -810,16 +1290,16 @@ impl Network {
     fn subscribe_to_topic(&mut self, topic: GossipTopic) -> bool {
         self.subscribed_topics.lock().insert(topic);
 
+        let topic_params = topic_score_params(&topic.kind);
         let topic: Topic = topic.into();
 
-        self.swarm
-            .behaviour_mut()
-            .gossipsub
-            .subscribe(&topic)
-            .is_ok()
+        let gossipsub = &mut self.swarm.behaviour_mut().gossipsub;
+        if let Err(err) = gossipsub.set_topic_params(topic.clone(), topic_params) {
+            warn!("Failed to set score parameters of topic {topic}: {err}");
+        }
+        gossipsub.subscribe(&topic).is_ok()
     }
 
-    #[allow(dead_code)]
     fn unsubscribe_from_topic(&mut self, topic: GossipTopic) -> bool {
         self.subscribed_topics.lock().remove(&topic);
 
@@ -827,6 +1307,193 @@ impl Network {
 
         self.swarm.behaviour_mut().gossipsub.unsubscribe(&topic)
     }
+
+    /// Subscribes to the subnets of the duties of validators, extending the subscriptions which
+    /// exist already. The subnets subscribed to for other reasons, e.g. the long-lived subnets
+    /// configured at startup, are left alone so they never expire.
+    fn subscribe_subnets(&mut self, subscriptions: Vec<SubnetSubscription>) {
+        let network_spec = beacon_network_spec();
+        let current_slot = network_spec.current_slot();
+        let fork = network_spec.fork_name_digest(
+            network_spec.fork_name_at_epoch(network_spec.current_epoch()),
+            genesis_validators_root(),
+        );
+
+        for SubnetSubscription { kind, until_slot } in subscriptions {
+            if until_slot <= current_slot {
+                continue;
+            }
+            if let Some(expiry) = self.subnet_subscriptions.get_mut(&kind) {
+                *expiry = (*expiry).max(until_slot);
+                continue;
+            }
+
+            let topic = GossipTopic { fork, kind };
+            if self.subscribed_topics.lock().contains(&topic) {
+                continue;
+            }
+            if self.subscribe_to_topic(topic) {
+                info!("Subscribed to topic until slot {until_slot}: {topic}");
+                self.subnet_subscriptions.insert(kind, until_slot);
+            } else {
+                error!("Failed to subscribe to topic: {topic}");
+            }
+        }
+    }
+
+    /// Unsubscribes from the subnets of duties which are over, on every fork.
+    fn prune_subnet_subscriptions(&mut self) {
+        let current_slot = beacon_network_spec().current_slot();
+        let expired_kinds = self
+            .subnet_subscriptions
+            .iter()
+            .filter(|(_, until_slot)| **until_slot <= current_slot)
+            .map(|(kind, _)| *kind)
+            .collect::<HashSet<_>>();
+        if expired_kinds.is_empty() {
+            return;
+        }
+
+        self.subnet_subscriptions
+            .retain(|kind, _| !expired_kinds.contains(kind));
+        let expired_topics = self
+            .subscribed_topics
+            .lock()
+            .iter()
+            .filter(|topic| expired_kinds.contains(&topic.kind))
+            .copied()
+            .collect::<Vec<_>>();
+        for topic in expired_topics {
+            if self.unsubscribe_from_topic(topic) {
+                info!("Unsubscribed from topic of expired duties: {topic}");
+            }
+        }
+    }
+
+    /// Saves the connected peers whose ENR is known, with their gossipsub score.
+    fn save_known_peers(&self) {
+        let Some(known_peers) = &self.known_peers else {
+            return;
+        };
+        let connected_peers = self
+            .network_state
+            .peer_table
+            .read()
+            .values()
+            .filter(|peer| peer.state == ConnectionState::Connected)
+            .filter_map(|peer| Some((peer.enr.clone()?, peer.gossipsub_score.unwrap_or_default())))
+            .collect::<Vec<_>>();
+        save_known_peers(known_peers, connected_peers);
+    }
+
+    /// Records the gossipsub score of each peer in the peer table and counts the peers per score
+    /// range, direction and state for the metrics.
+    fn update_peer_scores(&self) {
+        let gossipsub = &self.swarm.behaviour().gossipsub;
+        let mut peers_by_score: HashMap<&str, i64> = HashMap::new();
+        let mut peers_by_state: HashMap<(Direction, ConnectionState), i64> = HashMap::new();
+        for peer in self.network_state.peer_table.write().values_mut() {
+            peer.gossipsub_score = gossipsub.peer_score(&peer.peer_id);
+            if let Some(score) = peer.gossipsub_score {
+                *peers_by_score.entry(score_range(score)).or_default() += 1;
+            }
+            *peers_by_state
+                .entry((peer.direction, peer.state))
+                .or_default() += 1;
+        }
+        for direction in Direction::ALL {
+            for state in ConnectionState::ALL {
+                set_int_gauge_vec(
+                    &PEERS,
+                    peers_by_state
+                        .get(&(direction, state))
+                        .copied()
+                        .unwrap_or_default(),
+                    &[direction.name(), state.name()],
+                );
+            }
+        }
+
+        for range in SCORE_RANGES {
+            set_int_gauge_vec(
+                &GOSSIPSUB_PEERS_BY_SCORE,
+                peers_by_score.get(range).copied().unwrap_or_default(),
+                &[range],
+            );
+        }
+    }
+
+    /// Subscribes to the topics of the next fork shortly before it activates and unsubscribes
+    /// from the topics of the previous fork shortly after, keeping the same topic kinds.
+    /// Announces the fork active at the current epoch and the next scheduled fork in the `eth2`
+    /// field of the local ENR, so discovery keeps finding peers across fork transitions.
+    fn update_enr_fork_id(&mut self) {
+        let fork_id = EnrForkId::new(genesis_validators_root());
+        let discovery = &mut self.swarm.behaviour_mut().discovery;
+        if discovery.update_enr_fork_id(fork_id) {
+            let enr = discovery.local_enr();
+            info!(
+                "Updated local ENR with fork digest {}: {enr}",
+                fork_id.fork_digest
+            );
+            *self.network_state.local_enr.write() = enr;
+        }
+    }
+
+    fn update_fork_subscriptions(&mut self) {
+        let network_spec = beacon_network_spec();
+        let current_epoch = network_spec.current_epoch();
+        let current_fork = network_spec.fork_name_at_epoch(current_epoch);
+
+        let mut fork_digests =
+            vec![network_spec.fork_name_digest(current_fork, genesis_validators_root())];
+        if let Some((next_fork, next_fork_epoch)) = network_spec.next_fork(current_epoch)
+            && current_epoch + FORK_TOPIC_TRANSITION_EPOCHS >= next_fork_epoch
+        {
+            fork_digests.push(network_spec.fork_name_digest(next_fork, genesis_validators_root()));
+        }
+        if let Some(previous_fork) = current_fork.previous()
+            && current_epoch < network_spec.fork_epoch(current_fork) + FORK_TOPIC_TRANSITION_EPOCHS
+        {
+            fork_digests
+                .push(network_spec.fork_name_digest(previous_fork, genesis_validators_root()));
+        }
+
+        let subscribed_topics = self.subscribed_topics.lock().clone();
+        for topic in &subscribed_topics {
+            if !fork_digests.contains(&topic.fork) && self.unsubscribe_from_topic(*topic) {
+                info!("Unsubscribed from topic of a previous fork: {topic}");
+            }
+        }
+
+        let topic_kinds = subscribed_topics
+            .iter()
+            .map(|topic| topic.kind)
+            .collect::<HashSet<_>>();
+        for kind in topic_kinds {
+            for fork in &fork_digests {
+                let topic = GossipTopic { fork: *fork, kind };
+                if subscribed_topics.contains(&topic) {
+                    continue;
+                }
+                if self.subscribe_to_topic(topic) {
+                    info!("Subscribed to topic: {topic}");
+                } else {
+                    error!("Failed to subscribe to topic: {topic}");
+                }
+            }
+        }
+    }
+}
+
+/// The number of connected peers reserved for outbound connections.
+fn outbound_peer_quota(target_peers: usize) -> usize {
+    (target_peers as f64 * OUTBOUND_PEER_RATIO).ceil() as usize
+}
+
+/// The maximum number of connected peers, the target and the excess allowed above it.
+fn max_peers(target_peers: usize) -> usize {
+    (target_peers as f64 * (1.0 + PEER_EXCESS_RATIO)).ceil() as usize
 }
 
 #[cfg(test)]
@@ -838,7 +1505,7 @@ mod tests {
     use k256::ecdsa::SigningKey;
     use libp2p_identity::{Keypair, PeerId};
     use ream_discv5::{
-        config::DiscoveryConfig,
+        config::{DiscoveryChain, DiscoveryConfig},
         subnet::{AttestationSubnets, SyncCommitteeSubnets},
     };
     use ream_executor::ReamExecutor;
@@ -870,9 +1537,13 @@ mod tests {
         let config = NetworkConfig {
             discv5_config: DiscoveryConfig {
                 discv5_config,
+                chain: DiscoveryChain::Beacon,
                 bootnodes,
                 socket_address,
+                socket_address_v6: None,
+                advertised_address: None,
                 socket_port,
+                quic_port: None,
                 discovery_port,
                 disable_discovery,
                 attestation_subnets: AttestationSubnets::new(),
@@ -882,7 +1553,11 @@ mod tests {
                 topics,
                 ..Default::default()
             },
+            disable_upnp: true,
+            target_peers: 50,
+            prefer_quic: false,
             data_dir: std::env::temp_dir().join("ream_network_test"),
+            known_peers: None,
         };
 
         Network::init(
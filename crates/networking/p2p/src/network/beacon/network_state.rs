@@ -8,14 +8,15 @@ use ssz::Encode;
 
 use super::{peer::CachedPeer, utils::META_DATA_FILE_NAME};
 use crate::{
+    gossipsub::beacon::topics::GossipTopicKind,
     network::peer::{ConnectionState, Direction},
-    req_resp::beacon::messages::{meta_data::GetMetaDataV2, status::Status},
+    req_resp::beacon::messages::{goodbye::Goodbye, meta_data::GetMetaDataV3, status::Status},
 };
 
 pub struct NetworkState {
     pub local_enr: RwLock<Enr>,
     pub peer_table: RwLock<HashMap<PeerId, CachedPeer>>,
-    pub meta_data: RwLock<GetMetaDataV2>,
+    pub meta_data: RwLock<GetMetaDataV3>,
     pub status: RwLock<Status>,
     pub data_dir: PathBuf,
 }
@@ -38,6 +39,9 @@ impl NetworkState {
                 }
                 cached_peer.state = state;
                 cached_peer.direction = direction;
+                if state == ConnectionState::Connected {
+                    cached_peer.goodbye = None;
+                }
                 if let Some(enr_ref) = &enr {
                     cached_peer.enr = Some(enr_ref.clone());
                 }
@@ -54,6 +58,48 @@ impl NetworkState {
             });
     }
 
+    /// Records the Goodbye of a peer, which is disconnected on the next peer maintenance.
+    pub fn record_goodbye(&self, peer_id: PeerId, reason: Goodbye) {
+        if let Some(cached_peer) = self.peer_table.write().get_mut(&peer_id) {
+            cached_peer.goodbye = Some(reason);
+            cached_peer.state = ConnectionState::Disconnecting;
+        }
+    }
+
+    /// Whether the MetaData cached for the peer is older than the `seq_number` the peer reported
+    /// in a Ping, or hasn't been fetched yet.
+    pub fn is_meta_data_outdated(&self, peer_id: &PeerId, seq_number: u64) -> bool {
+        self.peer_table
+            .read()
+            .get(peer_id)
+            .is_some_and(|cached_peer| match &cached_peer.meta_data {
+                Some(meta_data) => meta_data.seq_number < seq_number,
+                None => true,
+            })
+    }
+
+    /// The number of connected peers whose MetaData advertises the attestation or sync committee
+    /// subnet of `kind`.
+    pub fn subnet_peer_count(&self, kind: GossipTopicKind) -> usize {
+        self.peer_table
+            .read()
+            .values()
+            .filter(|peer| peer.state == ConnectionState::Connected)
+            .filter_map(|peer| peer.meta_data.as_ref())
+            .filter(|meta_data| match kind {
+                GossipTopicKind::BeaconAttestation(subnet_id) => meta_data
+                    .attnets
+                    .get(subnet_id as usize)
+                    .unwrap_or_default(),
+                GossipTopicKind::SyncCommittee(subnet_id) => meta_data
+                    .syncnets
+                    .get(subnet_id as usize)
+                    .unwrap_or_default(),
+                _ => false,
+            })
+            .count()
+    }
+
     pub fn write_meta_data_to_disk(&self) -> anyhow::Result<()> {
         let meta_data_path = self.data_dir.join(META_DATA_FILE_NAME);
         fs::write(meta_data_path, self.meta_data.read().as_ssz_bytes())
@@ -61,6 +107,40 @@ impl NetworkState {
         Ok(())
     }
 
+    /// The connected peers to disconnect to get back down to `target_peers`, the lowest scoring
+    /// first, while keeping at least `outbound_quota` outbound peers.
+    pub fn peers_to_prune(&self, target_peers: usize, outbound_quota: usize) -> Vec<PeerId> {
+        let mut connected_peers = self.connected_peers();
+        let excess = connected_peers.len().saturating_sub(target_peers);
+        if excess == 0 {
+            return vec![];
+        }
+
+        connected_peers.sort_by(|a, b| {
+            a.gossipsub_score
+                .unwrap_or_default()
+                .total_cmp(&b.gossipsub_score.unwrap_or_default())
+        });
+        let mut outbound_peers = connected_peers
+            .iter()
+            .filter(|peer| peer.direction == Direction::Outbound)
+            .count();
+        let mut peers_to_prune = vec![];
+        for peer in connected_peers {
+            if peers_to_prune.len() == excess {
+                break;
+            }
+            if peer.direction == Direction::Outbound {
+                if outbound_peers <= outbound_quota {
+                    continue;
+                }
+                outbound_peers -= 1;
+            }
+            peers_to_prune.push(peer.peer_id);
+        }
+        peers_to_prune
+    }
+
     /// Gets a vector of all connected peers.
     pub fn connected_peers(&self) -> Vec<CachedPeer> {
         self.peer_table
@@ -71,3 +151,114 @@ impl NetworkState {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use discv5::enr::CombinedKey;
+
+    use super::*;
+
+    fn network_state(peers: &[(Direction, f64)]) -> (NetworkState, Vec<PeerId>) {
+        let network_state = NetworkState {
+            local_enr: RwLock::new(
+                Enr::builder()
+                    .build(&CombinedKey::generate_secp256k1())
+                    .expect("Failed to build ENR"),
+            ),
+            peer_table: RwLock::new(HashMap::new()),
+            meta_data: RwLock::new(GetMetaDataV3::default()),
+            status: RwLock::new(Status::default()),
+            data_dir: temp_dir(),
+        };
+        let mut peer_ids = vec![];
+        for (direction, score) in peers {
+            let peer_id = PeerId::random();
+            network_state.upsert_peer(peer_id, None, ConnectionState::Connected, *direction, None);
+            network_state
+                .peer_table
+                .write()
+                .entry(peer_id)
+                .and_modify(|peer| peer.gossipsub_score = Some(*score));
+            peer_ids.push(peer_id);
+        }
+        (network_state, peer_ids)
+    }
+
+    #[test]
+    fn test_peers_to_prune() {
+        let (network_state, peer_ids) = network_state(&[
+            (Direction::Outbound, -10.0),
+            (Direction::Inbound, -5.0),
+            (Direction::Outbound, 0.0),
+            (Direction::Inbound, 5.0),
+            (Direction::Inbound, 10.0),
+        ]);
+
+        assert!(network_state.peers_to_prune(5, 1).is_empty());
+        assert_eq!(
+            network_state.peers_to_prune(3, 1),
+            vec![peer_ids[0], peer_ids[1]]
+        );
+        // The lowest scoring peer is kept to fill the outbound quota
+        assert_eq!(
+            network_state.peers_to_prune(3, 2),
+            vec![peer_ids[1], peer_ids[3]]
+        );
+    }
+    #[test]
+    fn test_goodbye_is_recorded_until_reconnect() {
+        let (network_state, peer_ids) = network_state(&[(Direction::Outbound, 0.0)]);
+        let peer_id = peer_ids[0];
+
+        network_state.record_goodbye(peer_id, Goodbye::TooManyPeers);
+        let peer = network_state.peer_table.read()[&peer_id].clone();
+        assert_eq!(peer.goodbye, Some(Goodbye::TooManyPeers));
+        assert_eq!(peer.state, ConnectionState::Disconnecting);
+        assert!(network_state.connected_peers().is_empty());
+
+        network_state.upsert_peer(
+            peer_id,
+            None,
+            ConnectionState::Connected,
+            Direction::Inbound,
+            None,
+        );
+        assert_eq!(network_state.peer_table.read()[&peer_id].goodbye, None);
+    }
+
+    #[test]
+    fn test_meta_data_refresh_and_subnet_peers() {
+        let (network_state, peer_ids) =
+            network_state(&[(Direction::Outbound, 0.0), (Direction::Inbound, 0.0)]);
+        let kind = GossipTopicKind::BeaconAttestation(3);
+        assert!(network_state.is_meta_data_outdated(&peer_ids[0], 0));
+        assert!(!network_state.is_meta_data_outdated(&PeerId::random(), 1));
+        assert_eq!(network_state.subnet_peer_count(kind), 0);
+
+        let mut meta_data = GetMetaDataV3 {
+            seq_number: 2,
+            ..Default::default()
+        };
+        meta_data.attnets.set(3, true).unwrap();
+        for peer_id in &peer_ids {
+            network_state
+                .peer_table
+                .write()
+                .entry(*peer_id)
+                .and_modify(|peer| peer.meta_data = Some(meta_data.clone()));
+        }
+        assert!(!network_state.is_meta_data_outdated(&peer_ids[0], 2));
+        assert!(network_state.is_meta_data_outdated(&peer_ids[0], 3));
+        assert_eq!(network_state.subnet_peer_count(kind), 2);
+        assert_eq!(
+            network_state.subnet_peer_count(GossipTopicKind::SyncCommittee(3)),
+            0
+        );
+
+        // Only connected peers serve a subnet
+        network_state.record_goodbye(peer_ids[1], Goodbye::TooManyPeers);
+        assert_eq!(network_state.subnet_peer_count(kind), 1);
+    }
+}
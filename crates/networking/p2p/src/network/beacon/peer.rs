@@ -2,10 +2,12 @@ use std::time::Instant;
 
 use discv5::Enr;
 use libp2p::{Multiaddr, PeerId};
+use ream_consensus_misc::constants::beacon::CUSTODY_REQUIREMENT;
+use ream_discv5::config::CUSTODY_GROUP_COUNT_ENR_KEY;
 
 use crate::{
     network::peer::{ConnectionState, Direction},
-    req_resp::beacon::messages::{meta_data::GetMetaDataV2, status::Status},
+    req_resp::beacon::messages::{goodbye::Goodbye, meta_data::GetMetaDataV3, status::Status},
 };
 
 #[derive(Clone, Debug)]
@@ -30,7 +32,13 @@ pub struct CachedPeer {
 
     pub status: Option<Status>,
 
-    pub meta_data: Option<GetMetaDataV2>,
+    pub meta_data: Option<GetMetaDataV3>,
+
+    /// Gossipsub score of the peer, if it has one
+    pub gossipsub_score: Option<f64>,
+
+    /// The reason the peer gave in its Goodbye, if it said goodbye since it last connected
+    pub goodbye: Option<Goodbye>,
 }
 
 impl CachedPeer {
@@ -50,9 +58,26 @@ impl CachedPeer {
             enr,
             status: None,
             meta_data: None,
+            gossipsub_score: None,
+            goodbye: None,
         }
     }
 
+    /// The number of custody groups the peer custodies, as announced in its metadata, or else in
+    /// its ENR. A peer which announced neither custodies the minimum required.
+    pub fn custody_group_count(&self) -> u64 {
+        self.meta_data
+            .as_ref()
+            .map(|meta_data| meta_data.custody_group_count)
+            .or_else(|| {
+                self.enr
+                    .as_ref()?
+                    .get_decodable::<u64>(CUSTODY_GROUP_COUNT_ENR_KEY)?
+                    .ok()
+            })
+            .unwrap_or(CUSTODY_REQUIREMENT)
+    }
+
     /// Update the last seen timestamp
     pub fn update_last_seen(&mut self) {
         self.last_seen = Instant::now();
@@ -1,18 +1,30 @@
 use std::{fs, path::PathBuf};
 
 use anyhow::anyhow;
+use ream_consensus_misc::constants::beacon::CUSTODY_REQUIREMENT;
 use ssz::Decode;
 
-use crate::req_resp::beacon::messages::meta_data::GetMetaDataV2;
+use crate::req_resp::beacon::messages::meta_data::{GetMetaDataV2, GetMetaDataV3};
 
 pub const META_DATA_FILE_NAME: &str = "meta_data.ssz";
 
-pub fn read_meta_data_from_disk(path: PathBuf) -> anyhow::Result<GetMetaDataV2> {
+pub fn read_meta_data_from_disk(path: PathBuf) -> anyhow::Result<GetMetaDataV3> {
     let meta_data_path = path.join(META_DATA_FILE_NAME);
     if !meta_data_path.exists() {
-        return Ok(GetMetaDataV2::default());
+        return Ok(GetMetaDataV3 {
+            custody_group_count: CUSTODY_REQUIREMENT,
+            ..Default::default()
+        });
     }
 
-    GetMetaDataV2::from_ssz_bytes(&fs::read(meta_data_path)?)
+    // Meta data written before `metadata/3` was supported is stored as `metadata/2`
+    let bytes = fs::read(meta_data_path)?;
+    GetMetaDataV3::from_ssz_bytes(&bytes)
+        .or_else(|_| {
+            GetMetaDataV2::from_ssz_bytes(&bytes).map(|meta_data| GetMetaDataV3 {
+                custody_group_count: CUSTODY_REQUIREMENT,
+                ..meta_data.into()
+            })
+        })
         .map_err(|err| anyhow!("Failed to decode meta data: {err:?}"))
 }
@@ -0,0 +1,115 @@
+//! The peers a network was connected to are saved in the database, so they can be dialed right
+//! after a restart instead of waiting for discovery to find peers again.
+
+use std::{collections::HashSet, str};
+
+use discv5::Enr;
+use ream_storage::tables::known_peers::{KnownPeer, KnownPeersTable};
+use tracing::{info, warn};
+
+use crate::{
+    constants::{KNOWN_PEER_EXPIRY, MAX_KNOWN_PEERS},
+    network::misc::unix_timestamp,
+};
+
+/// Loads the ENRs of the saved peers, the best peer first.
+pub fn load_known_peers(known_peers: &KnownPeersTable) -> Vec<Enr> {
+    let peers = match known_peers.get_all() {
+        Ok(peers) => peers,
+        Err(err) => {
+            warn!("Failed to load known peers: {err:?}");
+            return vec![];
+        }
+    };
+    let enrs = peers.iter().filter_map(decode_enr).collect::<Vec<_>>();
+    info!("Loaded {} known peers", enrs.len());
+    enrs
+}
+
+/// Saves the ENRs and scores of the connected peers. The peers saved before are kept until they
+/// expire, so peers which are disconnected for a while, e.g. around a restart, aren't forgotten.
+pub fn save_known_peers(
+    known_peers: &KnownPeersTable,
+    connected_peers: impl IntoIterator<Item = (Enr, f64)>,
+) {
+    let now = unix_timestamp();
+    let mut node_ids = HashSet::new();
+    let mut peers = vec![];
+    for (enr, score) in connected_peers {
+        if node_ids.insert(enr.node_id()) {
+            peers.push(KnownPeer::new(enr.to_base64().into_bytes(), score, now));
+        }
+    }
+
+    match known_peers.get_all() {
+        Ok(saved_peers) => {
+            for peer in saved_peers {
+                if now.saturating_sub(peer.last_connected) < KNOWN_PEER_EXPIRY.as_secs()
+                    && let Some(enr) = decode_enr(&peer)
+                    && node_ids.insert(enr.node_id())
+                {
+                    peers.push(peer);
+                }
+            }
+        }
+        Err(err) => warn!("Failed to load known peers: {err:?}"),
+    }
+
+    if let Err(err) = known_peers.replace(peers, MAX_KNOWN_PEERS) {
+        warn!("Failed to save known peers: {err:?}");
+    }
+}
+
+fn decode_enr(peer: &KnownPeer) -> Option<Enr> {
+    str::from_utf8(&peer.enr)
+        .ok()?
+        .parse()
+        .inspect_err(|err| warn!("Ignoring invalid ENR of known peer: {err}"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use discv5::enr::CombinedKey;
+    use ream_storage::db::{ReamDB, config::DBConfig};
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn enr() -> Enr {
+        Enr::builder()
+            .build(&CombinedKey::generate_secp256k1())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_known_peers_survive_a_restart() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_known_peers_survive_a_restart")?;
+        let known_peers = ReamDB::new(tmp_dir.path().to_path_buf(), DBConfig::default())?
+            .init_lean_db()?
+            .known_peers_provider();
+        let (peer_1, peer_2, peer_3) = (enr(), enr(), enr());
+
+        save_known_peers(&known_peers, [(peer_1.clone(), 1.0), (peer_2.clone(), 5.0)]);
+        assert_eq!(
+            load_known_peers(&known_peers),
+            vec![peer_2.clone(), peer_1.clone()]
+        );
+
+        // Peers which are disconnected are kept, with the score they had
+        save_known_peers(&known_peers, [(peer_3.clone(), 2.0), (peer_1.clone(), 0.0)]);
+        assert_eq!(load_known_peers(&known_peers), vec![peer_2, peer_3, peer_1]);
+
+        // Peers which expired are dropped
+        let expired_peer = KnownPeer::new(
+            enr().to_base64().into_bytes(),
+            10.0,
+            unix_timestamp() - KNOWN_PEER_EXPIRY.as_secs(),
+        );
+        known_peers.replace(vec![expired_peer], MAX_KNOWN_PEERS)?;
+        assert_eq!(load_known_peers(&known_peers).len(), 1);
+        save_known_peers(&known_peers, []);
+        assert!(load_known_peers(&known_peers).is_empty());
+        Ok(())
+    }
+}
@@ -1,35 +1,58 @@
+pub mod peer;
+pub mod sync;
+
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     net::IpAddr,
     num::{NonZeroU8, NonZeroUsize},
+    path::Path,
     sync::Arc,
+    time::Duration,
 };
 
-use alloy_primitives::hex;
+use alloy_primitives::{B256, hex};
 use anyhow::anyhow;
-use discv5::multiaddr::Protocol;
+use delay_map::HashMapDelay;
+use discv5::{Enr, multiaddr::Protocol};
 use futures::StreamExt;
 use libp2p::{
     Multiaddr, SwarmBuilder,
     connection_limits::{self, ConnectionLimits},
     gossipsub::{Event as GossipsubEvent, IdentTopic, MessageAuthenticity},
     identify,
-    swarm::{Config, NetworkBehaviour, Swarm, SwarmEvent},
+    swarm::{Config, ConnectionId, NetworkBehaviour, Swarm, SwarmEvent},
 };
 use libp2p_identity::{Keypair, PeerId, secp256k1};
-use parking_lot::Mutex;
+use peer::LeanPeerTable;
 use ream_chain_lean::{
     lean_chain::LeanChainReader, messages::LeanChainServiceMessage, p2p_request::LeanP2PRequest,
 };
+use ream_consensus_lean::{block::SignedBlock, checkpoint::Checkpoint};
+use ream_discv5::{
+    config::{DiscoveryChain, DiscoveryConfig, discv5_listen_config},
+    discovery::{Discovery, DiscoveryOutEvent, QueryType},
+};
 use ream_executor::ReamExecutor;
+use ream_network_spec::networks::lean_network_spec;
+use ream_storage::tables::{field::Field, known_peers::KnownPeersTable, table::Table};
 use ssz::Encode;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use sync::LeanSyncer;
+use tokio::{
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    time::interval,
+};
 use tracing::{info, trace, warn};
+use tree_hash::TreeHash;
 
-use super::peer::ConnectionState;
+use super::peer::{ConnectionState, Direction};
 use crate::{
-    bootnodes::Bootnodes,
+    bootnodes::{Bootnodes, to_multiaddrs},
+    constants::{
+        KNOWN_PEERS_SAVE_INTERVAL, LEAN_MAX_REDIAL_ATTEMPTS, LEAN_MAX_REQUEST_BLOCKS,
+        LEAN_MIN_PEER_COUNT, LEAN_REDIAL_BASE_DELAY, LEAN_REDIAL_MAX_DELAY,
+        PEER_MAINTENANCE_INTERVAL,
+    },
     gossipsub::{
         GossipsubBehaviour,
         lean::{
@@ -38,14 +61,30 @@ use crate::{
         },
         snappy::SnappyTransform,
     },
-    network::misc::Executor,
-    req_resp::{Chain, ReqResp, ReqRespMessage},
+    network::{
+        known_peers::{load_known_peers, save_known_peers},
+        misc::{Executor, peer_id_from_enr},
+    },
+    req_resp::{
+        Chain, ReqResp, ReqRespMessage,
+        error::ReqRespError,
+        handler::{ReqRespMessageError, ReqRespMessageReceived, RespMessage},
+        lean::messages::{
+            LeanRequestMessage, LeanResponseMessage,
+            blocks::{LeanBlocksByRangeV1Request, LeanBlocksByRootV1Request},
+            status::LeanStatus,
+        },
+        messages::{RequestMessage, ResponseMessage},
+    },
 };
 
 #[derive(NetworkBehaviour)]
 pub(crate) struct ReamBehaviour {
     pub identify: identify::Behaviour,
 
+    /// The discovery domain: discv5
+    pub discovery: Discovery,
+
     /// The request-response domain
     pub req_resp: ReqResp,
 
@@ -70,21 +109,55 @@ pub struct LeanNetworkConfig {
     pub gossipsub_config: LeanGossipsubConfig,
     pub socket_address: IpAddr,
     pub socket_port: u16,
-    pub private_key_path: Option<std::path::PathBuf>,
+    pub discovery_port: u16,
+    pub disable_discovery: bool,
+    /// The libp2p key of the node, a new one is generated if not set
+    pub private_key: Option<secp256k1::SecretKey>,
+}
+
+/// Parses a hex encoded secp256k1 private key, as written by `generate_private_key`.
+pub fn parse_private_key(private_key_hex: &str) -> anyhow::Result<secp256k1::SecretKey> {
+    let private_key_bytes = hex::decode(private_key_hex.trim())
+        .map_err(|err| anyhow!("failed to decode hex private key: {err}"))?;
+    secp256k1::SecretKey::try_from_bytes(private_key_bytes)
+        .map_err(|err| anyhow!("failed to decode secp256k1 secret key from bytes: {err}"))
+}
+
+/// Reads a hex encoded secp256k1 private key from `path`.
+pub fn load_private_key(path: &Path) -> anyhow::Result<secp256k1::SecretKey> {
+    let private_key_hex = fs::read_to_string(path)
+        .map_err(|err| anyhow!("failed to read secret key file {}: {err}", path.display()))?;
+    parse_private_key(&private_key_hex)
+        .map_err(|err| anyhow!("invalid secret key file {}: {err}", path.display()))
 }
 
 /// NetworkService is responsible for the following:
-/// 1. Peer management. (We will connect with static peers for PQ devnet.)
+/// 1. Peer management. Peers are dialed from the bootnodes and discv5, and dropped peers are
+///    redialed with exponential backoff, until they fail too many redials in a row.
 /// 2. Gossiping blocks and votes.
+/// 3. Serving blocks to peers and catching up with peers which are ahead of the local head.
 ///
 /// TBD: It will be best if we reuse the existing NetworkManagerService for the beacon node.
 pub struct LeanNetworkService {
     lean_chain: LeanChainReader,
     network_config: Arc<LeanNetworkConfig>,
     swarm: Swarm<ReamBehaviour>,
-    peer_table: Arc<Mutex<HashMap<PeerId, ConnectionState>>>,
+    peer_table: Arc<LeanPeerTable>,
+    genesis_hash: B256,
     chain_message_sender: UnboundedSender<LeanChainServiceMessage>,
     outbound_p2p_request: UnboundedReceiver<LeanP2PRequest>,
+    /// The address each connected or connecting peer we dialed was dialed at, to redial it when it
+    /// drops. Dropped peers keep their address in the redial queue until they are forgotten.
+    peer_addresses: HashMap<PeerId, Multiaddr>,
+    redial_attempts: HashMap<PeerId, u32>,
+    redial_queue: HashMapDelay<PeerId, Multiaddr>,
+    /// The ENR of each peer found through the bootnodes, discovery or the saved peers
+    peer_enrs: HashMap<PeerId, Enr>,
+    known_peers: KnownPeersTable,
+    syncer: LeanSyncer,
+    /// The roots of the blocks requested by each in-flight `lean_blocks_by_root` request
+    block_root_requests: HashMap<u64, HashSet<B256>>,
+    request_id: u64,
 }
 
 impl LeanNetworkService {
@@ -104,24 +177,9 @@ impl LeanNetworkService {
             connection_limits::Behaviour::new(limits)
         };
 
-        let local_key = if let Some(ref path) = network_config.private_key_path {
-            let private_key_hex = fs::read_to_string(path).map_err(|err| {
-                anyhow!("failed to read secret key file {}: {err}", path.display())
-            })?;
-            let private_key_bytes = hex::decode(private_key_hex.trim()).map_err(|err| {
-                anyhow!(
-                    "failed to decode hex from private key file {}: {err}",
-                    path.display()
-                )
-            })?;
-            let private_key =
-                secp256k1::SecretKey::try_from_bytes(private_key_bytes).map_err(|err| {
-                    anyhow!("failed to decode secp256k1 secret key from bytes: {err}")
-                })?;
-
-            Keypair::from(secp256k1::Keypair::from(private_key))
-        } else {
-            Keypair::generate_secp256k1()
+        let local_key = match &network_config.private_key {
+            Some(private_key) => Keypair::from(secp256k1::Keypair::from(private_key.clone())),
+            None => Keypair::generate_secp256k1(),
         };
 
         let gossipsub = {
@@ -136,6 +194,28 @@ impl LeanNetworkService {
             .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?
         };
 
+        let discovery = {
+            let discv5_config = discv5::ConfigBuilder::new(discv5_listen_config(
+                network_config.socket_address,
+                None,
+                network_config.discovery_port,
+            ))
+            .build();
+            let discovery_config = DiscoveryConfig {
+                discv5_config,
+                chain: DiscoveryChain::Lean {
+                    genesis_time: lean_network_spec().genesis_time,
+                },
+                socket_address: network_config.socket_address,
+                socket_port: network_config.socket_port,
+                quic_port: Some(network_config.socket_port),
+                discovery_port: network_config.discovery_port,
+                disable_discovery: network_config.disable_discovery,
+                ..DiscoveryConfig::default()
+            };
+            Discovery::new(local_key.clone(), &discovery_config).await?
+        };
+
         let identify = {
             let local_public_key = local_key.public();
             let identify_config =
@@ -148,6 +228,7 @@ impl LeanNetworkService {
 
         let behaviour = {
             ReamBehaviour {
+                discovery,
                 req_resp: ReqResp::new(Chain::Lean),
                 gossipsub,
                 identify,
@@ -169,13 +250,27 @@ impl LeanNetworkService {
                 .build()
         };
 
+        let (genesis_hash, known_peers) = {
+            let lean_chain = lean_chain.read().await;
+            let known_peers = lean_chain.store.lock().await.known_peers_provider();
+            (lean_chain.genesis_hash, known_peers)
+        };
         let mut lean_network_service = LeanNetworkService {
             lean_chain,
             network_config: network_config.clone(),
             swarm,
-            peer_table: Arc::new(Mutex::new(HashMap::new())),
+            peer_table: Arc::new(LeanPeerTable::default()),
+            genesis_hash,
             chain_message_sender,
             outbound_p2p_request,
+            peer_addresses: HashMap::new(),
+            redial_attempts: HashMap::new(),
+            redial_queue: HashMapDelay::new(LEAN_REDIAL_BASE_DELAY),
+            peer_enrs: HashMap::new(),
+            known_peers,
+            syncer: LeanSyncer::default(),
+            block_root_requests: HashMap::new(),
+            request_id: 0,
         };
 
         let mut multi_addr: Multiaddr = lean_network_service.network_config.socket_address.into();
@@ -211,7 +306,22 @@ impl LeanNetworkService {
             self.lean_chain.read().await.head
         );
 
+        for enr in bootnodes.to_enrs_lean() {
+            self.record_enr(enr.clone());
+            self.swarm.behaviour_mut().discovery.add_enr(enr);
+        }
         self.connect_to_peers(bootnodes.to_multiaddrs_lean()).await;
+
+        // The peers of the previous run are dialed before discovery finds any
+        let known_peers = load_known_peers(&self.known_peers);
+        for enr in &known_peers {
+            self.record_enr(enr.clone());
+            self.swarm.behaviour_mut().discovery.add_enr(enr.clone());
+        }
+        self.connect_to_peers(to_multiaddrs(&known_peers)).await;
+
+        let mut peer_maintenance_interval = interval(PEER_MAINTENANCE_INTERVAL);
+        let mut known_peers_interval = interval(KNOWN_PEERS_SAVE_INTERVAL);
         loop {
             tokio::select! {
                 Some(item) = self.outbound_p2p_request.recv() => {
@@ -256,6 +366,9 @@ impl LeanNetworkService {
                                 info!("broadcasted vote for slot {}", signed_vote.message.slot);
                             }
                         }
+                        LeanP2PRequest::RequestBlocksByRoot(block_roots) => {
+                            self.request_blocks_by_root(block_roots);
+                        }
                     }
                 }
 
@@ -264,6 +377,19 @@ impl LeanNetworkService {
                         info!("Swarm event: {event:?}");
                     }
                 }
+
+                Some(Ok((peer_id, address))) = self.redial_queue.next() => {
+                    self.redial_peer(peer_id, address);
+                }
+
+                _ = peer_maintenance_interval.tick() => {
+                    self.maintain_peers();
+                    self.request_statuses().await;
+                }
+
+                _ = known_peers_interval.tick() => {
+                    self.save_known_peers();
+                }
             }
         }
     }
@@ -277,20 +403,54 @@ impl LeanNetworkService {
                 self.handle_gossipsub_event(gossipsub_event)
             }
             SwarmEvent::Behaviour(ReamBehaviourEvent::ReqResp(req_resp_event)) => {
-                self.handle_request_response_event(req_resp_event)
+                self.handle_request_response_event(req_resp_event).await
             }
-            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
-                self.peer_table
-                    .lock()
-                    .insert(peer_id, ConnectionState::Connected);
+            SwarmEvent::Behaviour(ReamBehaviourEvent::Discovery(
+                DiscoveryOutEvent::DiscoveredPeers { peers },
+            )) => {
+                let enrs = peers.into_keys().collect::<Vec<_>>();
+                for enr in &enrs {
+                    self.record_enr(enr.clone());
+                }
+                self.connect_to_peers(to_multiaddrs(&enrs)).await;
+                None
+            }
+            SwarmEvent::ConnectionEstablished {
+                peer_id, endpoint, ..
+            } => {
+                let direction = if endpoint.is_dialer() {
+                    Direction::Outbound
+                } else {
+                    Direction::Inbound
+                };
+                self.peer_table.update_connection(
+                    peer_id,
+                    ConnectionState::Connected,
+                    direction,
+                    endpoint.get_remote_address().clone(),
+                );
+                self.redial_attempts.remove(&peer_id);
+                self.redial_queue.remove(&peer_id);
 
                 info!("Connected to peer: {peer_id:?}");
+                if endpoint.is_dialer() {
+                    match local_status(&self.lean_chain, self.genesis_hash).await {
+                        Ok(local_status) => {
+                            self.send_request(peer_id, LeanRequestMessage::Status(local_status));
+                        }
+                        Err(err) => warn!("Failed to get the local status: {err:?}"),
+                    }
+                }
                 None
             }
             SwarmEvent::ConnectionClosed { peer_id, .. } => {
                 self.peer_table
-                    .lock()
-                    .insert(peer_id, ConnectionState::Disconnected);
+                    .update_state(peer_id, ConnectionState::Disconnected);
+                self.syncer.remove_peer(&peer_id);
+                // The address is only kept while the peer is redialed
+                if let Some(address) = self.peer_addresses.remove(&peer_id) {
+                    self.schedule_redial(peer_id, address);
+                }
 
                 info!("Disconnected from peer: {peer_id:?}");
                 Some(ReamNetworkEvent::PeerDisconnected(peer_id))
@@ -305,6 +465,15 @@ impl LeanNetworkService {
             }
             SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
                 warn!("Failed to connect to {peer_id:?}: {error:?}");
+                if let Some(peer_id) = peer_id
+                    && !self.swarm.is_connected(&peer_id)
+                {
+                    self.peer_table
+                        .update_state(peer_id, ConnectionState::Disconnected);
+                    if let Some(address) = self.peer_addresses.remove(&peer_id) {
+                        self.schedule_redial(peer_id, address);
+                    }
+                }
                 None
             }
             _ => None,
@@ -312,7 +481,13 @@ impl LeanNetworkService {
     }
 
     fn handle_gossipsub_event(&mut self, event: GossipsubEvent) -> Option<ReamNetworkEvent> {
-        if let GossipsubEvent::Message { message, .. } = event {
+        if let GossipsubEvent::Message {
+            propagation_source,
+            message,
+            ..
+        } = event
+        {
+            self.peer_table.update_last_seen(propagation_source);
             match LeanGossipsubMessage::decode(&message.topic, &message.data) {
                 Ok(LeanGossipsubMessage::Block(signed_block)) => {
                     let slot = signed_block.message.slot;
@@ -348,11 +523,418 @@ impl LeanNetworkService {
         None
     }
 
-    fn handle_request_response_event(
+    async fn handle_request_response_event(
         &mut self,
-        _event: ReqRespMessage,
+        event: ReqRespMessage,
     ) -> Option<ReamNetworkEvent> {
-        None
+        let ReqRespMessage {
+            peer_id,
+            connection_id,
+            message,
+        } = event;
+
+        let message = match message {
+            Ok(message) => message,
+            Err(ReqRespMessageError::Outbound { request_id, err }) => {
+                if let Some(block_roots) = self.block_root_requests.remove(&request_id) {
+                    warn!("Request for blocks {block_roots:?} to peer {peer_id:?} failed: {err:?}");
+                } else if self.syncer.on_request_failed(request_id).is_some() {
+                    warn!("Range request to peer {peer_id:?} failed: {err:?}");
+                    self.sync().await;
+                }
+                return None;
+            }
+            Err(ReqRespMessageError::Inbound { stream_id, err }) => {
+                trace!(?peer_id, ?stream_id, "Inbound request failed: {err:?}");
+                return None;
+            }
+        };
+        self.peer_table.update_last_seen(peer_id);
+
+        match message {
+            ReqRespMessageReceived::Request { stream_id, message } => {
+                let RequestMessage::Lean(message) = *message else {
+                    warn!(
+                        "Received unexpected Beacon request message: {message:?} from peer: {peer_id:?}"
+                    );
+                    return None;
+                };
+
+                match message {
+                    LeanRequestMessage::Status(status) => {
+                        trace!(?peer_id, ?stream_id, ?status, "Received Status request");
+                        match local_status(&self.lean_chain, self.genesis_hash).await {
+                            Ok(local_status) => {
+                                self.send_response(
+                                    peer_id,
+                                    connection_id,
+                                    stream_id,
+                                    LeanResponseMessage::Status(local_status),
+                                );
+                                self.send_end_of_stream(peer_id, connection_id, stream_id);
+                            }
+                            Err(err) => self.send_error(
+                                peer_id,
+                                connection_id,
+                                stream_id,
+                                ReqRespError::Anyhow(err),
+                            ),
+                        }
+
+                        self.handle_peer_status(peer_id, status).await;
+                        Some(ReamNetworkEvent::Status(peer_id))
+                    }
+                    LeanRequestMessage::BlocksByRange(request) => {
+                        trace!(
+                            ?peer_id,
+                            ?stream_id,
+                            ?request,
+                            "Received BlocksByRange request"
+                        );
+                        match blocks_by_range(&self.lean_chain, &request).await {
+                            Ok(blocks) => {
+                                for block in blocks {
+                                    self.send_response(
+                                        peer_id,
+                                        connection_id,
+                                        stream_id,
+                                        LeanResponseMessage::BlocksByRange(Arc::new(block)),
+                                    );
+                                }
+                                self.send_end_of_stream(peer_id, connection_id, stream_id);
+                            }
+                            Err(err) => self.send_error(peer_id, connection_id, stream_id, err),
+                        }
+                        None
+                    }
+                    LeanRequestMessage::BlocksByRoot(request) => {
+                        trace!(?peer_id, ?stream_id, "Received BlocksByRoot request");
+                        let lean_block_provider = self
+                            .lean_chain
+                            .read()
+                            .await
+                            .store
+                            .lock()
+                            .await
+                            .lean_block_provider();
+                        for block_root in request.inner {
+                            let Ok(Some(block)) = lean_block_provider.get(block_root) else {
+                                trace!("No block found for root {block_root}");
+                                continue;
+                            };
+                            self.send_response(
+                                peer_id,
+                                connection_id,
+                                stream_id,
+                                LeanResponseMessage::BlocksByRoot(Arc::new(block)),
+                            );
+                        }
+                        self.send_end_of_stream(peer_id, connection_id, stream_id);
+                        None
+                    }
+                }
+            }
+            ReqRespMessageReceived::Response {
+                request_id,
+                message,
+            } => {
+                let ResponseMessage::Lean(message) = *message else {
+                    warn!(
+                        "Received unexpected Beacon response message: {message:?} from peer: {peer_id:?}"
+                    );
+                    return None;
+                };
+
+                match message.as_ref() {
+                    LeanResponseMessage::Status(status) => {
+                        trace!(
+                            ?peer_id,
+                            ?request_id,
+                            "Received Status response: head_slot: {}",
+                            status.head_slot
+                        );
+                        self.handle_peer_status(peer_id, status.clone()).await;
+                    }
+                    LeanResponseMessage::BlocksByRange(signed_block) => {
+                        if !self
+                            .syncer
+                            .on_block(request_id, signed_block.as_ref().clone())
+                        {
+                            trace!(?peer_id, ?request_id, "Received unrequested block");
+                        }
+                    }
+                    LeanResponseMessage::BlocksByRoot(signed_block) => {
+                        let block_root = signed_block.message.tree_hash_root();
+                        if !self
+                            .block_root_requests
+                            .get_mut(&request_id)
+                            .is_some_and(|block_roots| block_roots.remove(&block_root))
+                        {
+                            trace!(
+                                ?peer_id,
+                                ?request_id,
+                                "Received unrequested block {block_root}"
+                            );
+                            return None;
+                        }
+
+                        if let Err(err) =
+                            self.chain_message_sender
+                                .send(LeanChainServiceMessage::ProcessBlock {
+                                    signed_block: signed_block.as_ref().clone(),
+                                    is_trusted: false,
+                                    need_gossip: false,
+                                })
+                        {
+                            warn!("failed to send requested block {block_root} to chain: {err:?}");
+                        }
+                    }
+                }
+                None
+            }
+            ReqRespMessageReceived::EndOfStream { request_id } => {
+                if self.block_root_requests.remove(&request_id).is_some() {
+                    return None;
+                }
+
+                match self.syncer.on_end_of_stream(request_id) {
+                    Some(Ok(blocks)) => {
+                        info!("Synced {} blocks from peer {peer_id:?}", blocks.len());
+                        for signed_block in blocks {
+                            let slot = signed_block.message.slot;
+                            if let Err(err) = self.chain_message_sender.send(
+                                LeanChainServiceMessage::ProcessBlock {
+                                    signed_block,
+                                    is_trusted: false,
+                                    need_gossip: false,
+                                },
+                            ) {
+                                warn!(
+                                    "failed to send synced block for slot {slot} to chain: {err:?}"
+                                );
+                            }
+                        }
+                        self.sync().await;
+                    }
+                    Some(Err(err)) => {
+                        warn!("Peer {peer_id:?} served a range which can't be synced: {err:?}");
+                        self.sync().await;
+                    }
+                    None => {}
+                }
+                None
+            }
+        }
+    }
+
+    /// Records the Status of a peer for sync, or disconnects the peer if it is on another chain.
+    async fn handle_peer_status(&mut self, peer_id: PeerId, status: LeanStatus) {
+        let is_compatible = status.genesis_hash == self.genesis_hash;
+        self.peer_table.update_status(peer_id, status.clone());
+
+        if !is_compatible {
+            warn!(
+                "Disconnecting peer {peer_id:?} with genesis {}, expected {}",
+                status.genesis_hash, self.genesis_hash
+            );
+            self.forget_peer(&peer_id);
+            self.syncer.remove_peer(&peer_id);
+            if self.swarm.disconnect_peer_id(peer_id).is_err() {
+                self.peer_table
+                    .update_state(peer_id, ConnectionState::Disconnected);
+            } else {
+                self.peer_table
+                    .update_state(peer_id, ConnectionState::Disconnecting);
+            }
+            return;
+        }
+
+        self.syncer.update_peer_status(peer_id, status);
+        self.sync().await;
+    }
+
+    /// Requests the next missing range of blocks, if a peer is ahead of the local head. The synced
+    /// ranges whose blocks were imported since are confirmed first, and the syncer is rewound if a
+    /// range didn't extend the synced chain or wasn't imported in time.
+    async fn sync(&mut self) {
+        if let Some(fork_root) = self.syncer.take_fork_root() {
+            match common_ancestor(&self.lean_chain, fork_root).await {
+                Ok(checkpoint) => {
+                    info!(
+                        "Restarting sync after block {} at slot {}",
+                        checkpoint.root, checkpoint.slot
+                    );
+                    self.syncer.rewind(checkpoint);
+                }
+                Err(err) => {
+                    warn!("Failed to find where to restart sync from: {err:?}");
+                    return;
+                }
+            }
+        }
+
+        while let Some(block_root) = self.syncer.pending_import() {
+            match is_block_imported(&self.lean_chain, block_root).await {
+                Ok(true) => self.syncer.on_range_imported(),
+                Ok(false) => {
+                    let Some(peer_id) = self.syncer.overdue_import() else {
+                        break;
+                    };
+                    warn!(
+                        "Blocks synced from peer {peer_id:?} up to {block_root} weren't imported, restarting sync from the finalized checkpoint"
+                    );
+                    self.syncer.remove_peer(&peer_id);
+                    match finalized_checkpoint(&self.lean_chain).await {
+                        Ok(finalized) => self.syncer.rewind(finalized),
+                        Err(err) => {
+                            warn!("Failed to get the finalized checkpoint: {err:?}");
+                            return;
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!("Failed to check whether block {block_root} was imported: {err:?}");
+                    return;
+                }
+            }
+        }
+
+        let local_head = match local_head(&self.lean_chain).await {
+            Ok(local_head) => local_head,
+            Err(err) => {
+                warn!("Failed to get the local head: {err:?}");
+                return;
+            }
+        };
+        let Some(range_request) = self.syncer.next_range_request(local_head) else {
+            return;
+        };
+
+        trace!(
+            "Requesting blocks from slot {} to peer {:?}",
+            range_request.request.start_slot, range_request.peer_id
+        );
+        if let Some(request_id) = self.send_request(
+            range_request.peer_id,
+            LeanRequestMessage::BlocksByRange(range_request.request.clone()),
+        ) {
+            self.syncer.start_range_request(request_id, range_request);
+        } else {
+            self.syncer.remove_peer(&range_request.peer_id);
+        }
+    }
+
+    /// Requests the blocks which aren't requested yet from the peer with the highest head.
+    fn request_blocks_by_root(&mut self, block_roots: Vec<B256>) {
+        let block_roots = block_roots
+            .into_iter()
+            .filter(|block_root| {
+                !self
+                    .block_root_requests
+                    .values()
+                    .any(|requested_roots| requested_roots.contains(block_root))
+            })
+            .collect::<Vec<_>>();
+        if block_roots.is_empty() {
+            return;
+        }
+
+        let Some(peer_id) = self
+            .peer_table
+            .connected_peers()
+            .into_iter()
+            .max_by_key(|peer| peer.status.as_ref().map(|status| status.head_slot))
+            .map(|peer| peer.peer_id)
+        else {
+            warn!("No peer to request blocks {block_roots:?} from");
+            return;
+        };
+
+        trace!("Requesting blocks {block_roots:?} from peer {peer_id:?}");
+        if let Some(request_id) = self.send_request(
+            peer_id,
+            LeanRequestMessage::BlocksByRoot(LeanBlocksByRootV1Request::new(block_roots.clone())),
+        ) {
+            self.block_root_requests
+                .insert(request_id, block_roots.into_iter().collect());
+        }
+    }
+
+    /// Requests the Status of every connected peer, to learn whether the local node is behind.
+    async fn request_statuses(&mut self) {
+        let local_status = match local_status(&self.lean_chain, self.genesis_hash).await {
+            Ok(local_status) => local_status,
+            Err(err) => {
+                warn!("Failed to get the local status: {err:?}");
+                return;
+            }
+        };
+        for peer in self.peer_table.connected_peers() {
+            self.send_request(
+                peer.peer_id,
+                LeanRequestMessage::Status(local_status.clone()),
+            );
+        }
+    }
+
+    fn request_id(&mut self) -> u64 {
+        let request_id = self.request_id;
+        self.request_id += 1;
+        request_id
+    }
+
+    fn send_request(&mut self, peer_id: PeerId, message: LeanRequestMessage) -> Option<u64> {
+        if !self.swarm.is_connected(&peer_id) {
+            return None;
+        }
+
+        let request_id = self.request_id();
+        self.swarm.behaviour_mut().req_resp.send_request(
+            peer_id,
+            request_id,
+            RequestMessage::Lean(message),
+        );
+
+        Some(request_id)
+    }
+
+    fn send_response(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        message: LeanResponseMessage,
+    ) {
+        self.swarm.behaviour_mut().req_resp.send_response(
+            peer_id,
+            connection_id,
+            stream_id,
+            RespMessage::Response(Box::new(ResponseMessage::Lean(Arc::new(message)))),
+        );
+    }
+
+    fn send_end_of_stream(&mut self, peer_id: PeerId, connection_id: ConnectionId, stream_id: u64) {
+        self.swarm.behaviour_mut().req_resp.send_response(
+            peer_id,
+            connection_id,
+            stream_id,
+            RespMessage::EndOfStream,
+        );
+    }
+
+    fn send_error(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        error: ReqRespError,
+    ) {
+        self.swarm.behaviour_mut().req_resp.send_response(
+            peer_id,
+            connection_id,
+            stream_id,
+            RespMessage::Error(error),
+        );
     }
 
     async fn connect_to_peers(&mut self, peers: Vec<Multiaddr>) {
@@ -363,20 +945,120 @@ impl LeanNetworkService {
                 .find(|protocol| matches!(protocol, Protocol::P2p(_)))
                 && peer_id != self.local_peer_id()
             {
+                // Dial a peer with multiple addresses only once, and never a peer on another chain
+                if self.peer_table.get(&peer_id).is_some_and(|peer| {
+                    matches!(
+                        peer.state,
+                        ConnectionState::Connected | ConnectionState::Connecting
+                    ) || peer
+                        .status
+                        .as_ref()
+                        .is_some_and(|status| status.genesis_hash != self.genesis_hash)
+                }) {
+                    continue;
+                }
+
                 if let Err(err) = self.swarm.dial(peer.clone()) {
                     warn!("Failed to dial peer: {err:?}");
                     continue;
                 }
 
                 info!("Dialing peer: {peer_id:?}",);
-                self.peer_table
-                    .lock()
-                    .insert(peer_id, ConnectionState::Connecting);
+                self.peer_table.update_connection(
+                    peer_id,
+                    ConnectionState::Connecting,
+                    Direction::Outbound,
+                    peer.clone(),
+                );
+                self.peer_addresses.insert(peer_id, peer);
             }
         }
     }
 
-    pub fn peer_table(&self) -> Arc<Mutex<HashMap<PeerId, ConnectionState>>> {
+    /// Schedules a redial of a peer we dialed before, backing off exponentially while it fails. A
+    /// peer which failed `LEAN_MAX_REDIAL_ATTEMPTS` redials in a row is forgotten, and only dialed
+    /// again once discovery finds it.
+    fn schedule_redial(&mut self, peer_id: PeerId, address: Multiaddr) {
+        if self.redial_queue.contains_key(&peer_id) {
+            return;
+        }
+
+        let attempt = self.redial_attempts.entry(peer_id).or_default();
+        if *attempt >= LEAN_MAX_REDIAL_ATTEMPTS {
+            info!("Giving up on redialing peer {peer_id:?} after {attempt} attempts");
+            self.forget_peer(&peer_id);
+            return;
+        }
+        let delay = redial_delay(*attempt);
+        *attempt += 1;
+        trace!("Redialing peer {peer_id:?} in {delay:?}");
+        self.redial_queue.insert_at(peer_id, address, delay);
+    }
+
+    /// Drops the address of a peer and its pending redial, so it isn't redialed anymore.
+    fn forget_peer(&mut self, peer_id: &PeerId) {
+        self.peer_addresses.remove(peer_id);
+        self.redial_attempts.remove(peer_id);
+        self.redial_queue.remove(peer_id);
+    }
+
+    fn redial_peer(&mut self, peer_id: PeerId, address: Multiaddr) {
+        if self.swarm.is_connected(&peer_id) {
+            self.peer_addresses.insert(peer_id, address);
+            return;
+        }
+
+        info!("Redialing peer: {peer_id:?}");
+        if let Err(err) = self.swarm.dial(address.clone()) {
+            warn!("Failed to redial peer: {err:?}");
+            self.schedule_redial(peer_id, address);
+            return;
+        }
+        self.peer_table.update_connection(
+            peer_id,
+            ConnectionState::Connecting,
+            Direction::Outbound,
+            address.clone(),
+        );
+        self.peer_addresses.insert(peer_id, address);
+    }
+
+    /// Discovers more peers while connected to fewer than the minimum peer count, and updates the
+    /// peer metrics.
+    fn maintain_peers(&mut self) {
+        self.peer_table.update_metrics();
+        let peer_count = self.peer_table.connected_peers().len();
+        if peer_count < LEAN_MIN_PEER_COUNT {
+            trace!("Peer count is below minimum: {peer_count}, discovering more peers");
+            self.swarm
+                .behaviour_mut()
+                .discovery
+                .discover_peers(QueryType::Peers, 16);
+        }
+    }
+
+    fn record_enr(&mut self, enr: Enr) {
+        if let Some(peer_id) = peer_id_from_enr(&enr) {
+            self.peer_enrs.insert(peer_id, enr);
+        }
+    }
+
+    /// Saves the connected peers whose ENR is known, with their gossipsub score.
+    fn save_known_peers(&self) {
+        let gossipsub = &self.swarm.behaviour().gossipsub;
+        let connected_peers = self
+            .peer_table
+            .connected_peers()
+            .into_iter()
+            .filter_map(|peer| {
+                let enr = self.peer_enrs.get(&peer.peer_id)?.clone();
+                Some((enr, gossipsub.peer_score(&peer.peer_id).unwrap_or_default()))
+            })
+            .collect::<Vec<_>>();
+        save_known_peers(&self.known_peers, connected_peers);
+    }
+
+    pub fn peer_table(&self) -> Arc<LeanPeerTable> {
         self.peer_table.clone()
     }
 
@@ -385,6 +1067,131 @@ impl LeanNetworkService {
     }
 }
 
+/// The delay before the redial following `attempt` failed redials.
+fn redial_delay(attempt: u32) -> Duration {
+    LEAN_REDIAL_BASE_DELAY
+        .saturating_mul(2_u32.saturating_pow(attempt))
+        .min(LEAN_REDIAL_MAX_DELAY)
+}
+
+async fn local_head(lean_chain: &LeanChainReader) -> anyhow::Result<Checkpoint> {
+    let lean_chain = lean_chain.read().await;
+    let head_block = lean_chain
+        .store
+        .lock()
+        .await
+        .lean_block_provider()
+        .get(lean_chain.head)?
+        .ok_or_else(|| anyhow!("Head block not found: {}", lean_chain.head))?;
+    Ok(Checkpoint {
+        root: lean_chain.head,
+        slot: head_block.message.slot,
+    })
+}
+
+async fn is_block_imported(lean_chain: &LeanChainReader, block_root: B256) -> anyhow::Result<bool> {
+    Ok(lean_chain
+        .read()
+        .await
+        .store
+        .lock()
+        .await
+        .lean_block_provider()
+        .get(block_root)?
+        .is_some())
+}
+
+async fn finalized_checkpoint(lean_chain: &LeanChainReader) -> anyhow::Result<Checkpoint> {
+    Ok(lean_chain
+        .read()
+        .await
+        .store
+        .lock()
+        .await
+        .latest_finalized_provider()
+        .get()?)
+}
+
+/// The block the chain of a peer shares with the local chain, given the parent of a range
+/// which didn't extend the synced chain: the parent itself if it was imported, otherwise the
+/// finalized checkpoint.
+async fn common_ancestor(
+    lean_chain: &LeanChainReader,
+    parent_root: B256,
+) -> anyhow::Result<Checkpoint> {
+    let parent_block = lean_chain
+        .read()
+        .await
+        .store
+        .lock()
+        .await
+        .lean_block_provider()
+        .get(parent_root)?;
+    match parent_block {
+        Some(parent_block) => Ok(Checkpoint {
+            root: parent_root,
+            slot: parent_block.message.slot,
+        }),
+        None => finalized_checkpoint(lean_chain).await,
+    }
+}
+
+async fn local_status(
+    lean_chain: &LeanChainReader,
+    genesis_hash: B256,
+) -> anyhow::Result<LeanStatus> {
+    let head = local_head(lean_chain).await?;
+    let finalized = finalized_checkpoint(lean_chain).await?;
+    Ok(LeanStatus {
+        genesis_hash,
+        finalized_root: finalized.root,
+        finalized_slot: finalized.slot,
+        head_root: head.root,
+        head_slot: head.slot,
+    })
+}
+
+/// Returns the blocks in the requested range, skipping empty slots.
+async fn blocks_by_range(
+    lean_chain: &LeanChainReader,
+    request: &LeanBlocksByRangeV1Request,
+) -> Result<Vec<SignedBlock>, ReqRespError> {
+    if request.count == 0 {
+        return Err(ReqRespError::InvalidData(
+            "Requested range is empty".to_string(),
+        ));
+    }
+
+    let (lean_block_provider, slot_index_provider) = {
+        let lean_chain = lean_chain.read().await;
+        let db = lean_chain.store.lock().await;
+        (db.lean_block_provider(), db.slot_index_provider())
+    };
+    let end_slot = request
+        .start_slot
+        .saturating_add(request.count.min(LEAN_MAX_REQUEST_BLOCKS));
+    slot_index_provider
+        .get_range(request.start_slot..end_slot)
+        .map_err(|err| {
+            anyhow!(
+                "Failed to get block roots from slot {}: {err}",
+                request.start_slot
+            )
+        })?
+        .into_iter()
+        .map(|(slot, block_root)| {
+            lean_block_provider
+                .get(block_root)
+                .map_err(|err| anyhow!("Failed to get block at slot {slot}: {err}"))?
+                .ok_or_else(|| {
+                    ReqRespError::ResourceUnavailable(format!(
+                        "No block found for root {block_root}"
+                    ))
+                })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::{net::Ipv4Addr, sync::Once, time::Duration};
@@ -393,7 +1200,7 @@ mod tests {
     use libp2p::{Multiaddr, multiaddr::Protocol};
     use ream_chain_lean::lean_chain::LeanChain;
     use ream_network_spec::networks::{LeanNetworkSpec, set_lean_network_spec};
-    use ream_storage::db::ReamDB;
+    use ream_storage::db::{ReamDB, config::DBConfig};
     use ream_sync::rwlock::Writer;
     use tempdir::TempDir;
     use tokio::sync::{Mutex, mpsc};
@@ -414,7 +1221,8 @@ mod tests {
         let temp_dir = TempDir::new("lean_node_test").unwrap();
         let temp_path = temp_dir.path().to_path_buf();
 
-        let ream_db = ReamDB::new(temp_path).expect("unable to init Ream Database");
+        let ream_db =
+            ReamDB::new(temp_path, DBConfig::default()).expect("unable to init Ream Database");
         let lean_db = ream_db
             .init_lean_db()
             .expect("unable to init Ream Lean Database");
@@ -439,7 +1247,9 @@ mod tests {
             gossipsub_config: LeanGossipsubConfig::default(),
             socket_address: Ipv4Addr::new(127, 0, 0, 1).into(),
             socket_port,
-            private_key_path: None,
+            discovery_port: socket_port + 100,
+            disable_discovery: true,
+            private_key: None,
         });
         let (sender, _receiver) = mpsc::unbounded_channel::<LeanChainServiceMessage>();
         let (_outbound_request_sender_unused, outbound_request_receiver) =
@@ -504,4 +1314,11 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_redial_delay() {
+        assert_eq!(redial_delay(0), LEAN_REDIAL_BASE_DELAY);
+        assert_eq!(redial_delay(3), LEAN_REDIAL_BASE_DELAY * 8);
+        assert_eq!(redial_delay(30), LEAN_REDIAL_MAX_DELAY);
+    }
 }
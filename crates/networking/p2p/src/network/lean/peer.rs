@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use libp2p::{Multiaddr, PeerId};
+use parking_lot::Mutex;
+use ream_metrics::{LEAN_PEERS, set_int_gauge_vec};
+use serde::Serialize;
+
+use crate::{
+    network::{
+        misc::unix_timestamp,
+        peer::{ConnectionState, Direction, PeerCount},
+    },
+    req_resp::lean::messages::status::LeanStatus,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LeanPeer {
+    /// libp2p peer ID
+    pub peer_id: PeerId,
+
+    /// Last known multiaddress observed for the peer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen_p2p_address: Option<Multiaddr>,
+
+    /// Current known connection state
+    pub state: ConnectionState,
+
+    /// Direction of the most recent connection (inbound/outbound)
+    pub direction: Direction,
+
+    /// Unix timestamp in seconds of the last connection change or message of the peer
+    pub last_seen: u64,
+
+    /// The Status the peer sent last, with its head and finalized checkpoint
+    pub status: Option<LeanStatus>,
+}
+
+impl LeanPeer {
+    pub fn new(peer_id: PeerId, state: ConnectionState) -> Self {
+        LeanPeer {
+            peer_id,
+            last_seen_p2p_address: None,
+            state,
+            direction: Direction::Unknown,
+            last_seen: unix_timestamp(),
+            status: None,
+        }
+    }
+}
+
+/// The peers known to the lean network service, which the lean API reads.
+#[derive(Debug, Default)]
+pub struct LeanPeerTable {
+    peers: Mutex<HashMap<PeerId, LeanPeer>>,
+}
+
+impl LeanPeerTable {
+    pub fn update_state(&self, peer_id: PeerId, state: ConnectionState) {
+        self.peers
+            .lock()
+            .entry(peer_id)
+            .and_modify(|peer| {
+                peer.state = state;
+                peer.last_seen = unix_timestamp();
+            })
+            .or_insert_with(|| LeanPeer::new(peer_id, state));
+    }
+
+    /// Like [Self::update_state], for a connection whose direction and address are known, as
+    /// when it is dialed or established.
+    pub fn update_connection(
+        &self,
+        peer_id: PeerId,
+        state: ConnectionState,
+        direction: Direction,
+        address: Multiaddr,
+    ) {
+        let mut peers = self.peers.lock();
+        let peer = peers
+            .entry(peer_id)
+            .or_insert_with(|| LeanPeer::new(peer_id, state));
+        peer.state = state;
+        peer.direction = direction;
+        peer.last_seen_p2p_address = Some(address);
+        peer.last_seen = unix_timestamp();
+    }
+
+    /// Records the Status a known peer sent.
+    pub fn update_status(&self, peer_id: PeerId, status: LeanStatus) {
+        if let Some(peer) = self.peers.lock().get_mut(&peer_id) {
+            peer.status = Some(status);
+            peer.last_seen = unix_timestamp();
+        }
+    }
+
+    /// Records that a known peer sent a message.
+    pub fn update_last_seen(&self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.lock().get_mut(&peer_id) {
+            peer.last_seen = unix_timestamp();
+        }
+    }
+
+    pub fn get(&self, peer_id: &PeerId) -> Option<LeanPeer> {
+        self.peers.lock().get(peer_id).cloned()
+    }
+
+    pub fn peers(&self) -> Vec<LeanPeer> {
+        self.peers.lock().values().cloned().collect()
+    }
+
+    pub fn connected_peers(&self) -> Vec<LeanPeer> {
+        self.peers
+            .lock()
+            .values()
+            .filter(|peer| peer.state == ConnectionState::Connected)
+            .cloned()
+            .collect()
+    }
+
+    pub fn peer_count(&self) -> PeerCount {
+        let mut peer_count = PeerCount::default();
+        for peer in self.peers.lock().values() {
+            match peer.state {
+                ConnectionState::Connected => peer_count.connected += 1,
+                ConnectionState::Connecting => peer_count.connecting += 1,
+                ConnectionState::Disconnected => peer_count.disconnected += 1,
+                ConnectionState::Disconnecting => peer_count.disconnecting += 1,
+            }
+        }
+        peer_count
+    }
+
+    /// Counts the peers per direction and state for the metrics.
+    pub fn update_metrics(&self) {
+        let mut peers_by_state: HashMap<(Direction, ConnectionState), i64> = HashMap::new();
+        for peer in self.peers.lock().values() {
+            *peers_by_state
+                .entry((peer.direction, peer.state))
+                .or_default() += 1;
+        }
+        for direction in Direction::ALL {
+            for state in ConnectionState::ALL {
+                set_int_gauge_vec(
+                    &LEAN_PEERS,
+                    peers_by_state
+                        .get(&(direction, state))
+                        .copied()
+                        .unwrap_or_default(),
+                    &[direction.name(), state.name()],
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_table_tracks_connections() {
+        let peer_table = LeanPeerTable::default();
+        let (dialed_peer, inbound_peer) = (PeerId::random(), PeerId::random());
+        let address: Multiaddr = "/ip4/127.0.0.1/udp/9000/quic-v1".parse().unwrap();
+
+        peer_table.update_connection(
+            dialed_peer,
+            ConnectionState::Connecting,
+            Direction::Outbound,
+            address.clone(),
+        );
+        peer_table.update_state(inbound_peer, ConnectionState::Connected);
+        let peer = peer_table.get(&dialed_peer).unwrap();
+        assert_eq!(peer.direction, Direction::Outbound);
+        assert_eq!(peer.last_seen_p2p_address, Some(address));
+        assert_eq!(
+            peer_table.get(&inbound_peer).unwrap().direction,
+            Direction::Unknown
+        );
+
+        // The address of a dial is kept once the connection state changes
+        peer_table.update_state(dialed_peer, ConnectionState::Disconnected);
+        let peer = peer_table.get(&dialed_peer).unwrap();
+        assert_eq!(peer.state, ConnectionState::Disconnected);
+        assert!(peer.last_seen_p2p_address.is_some());
+
+        let peer_count = peer_table.peer_count();
+        assert_eq!((peer_count.connected, peer_count.disconnected), (1, 1));
+        assert_eq!(
+            peer_table
+                .connected_peers()
+                .iter()
+                .map(|peer| peer.peer_id)
+                .collect::<Vec<_>>(),
+            vec![inbound_peer]
+        );
+
+        // Only known peers are updated
+        peer_table.update_last_seen(PeerId::random());
+        assert_eq!(peer_table.peers().len(), 2);
+    }
+}
@@ -0,0 +1,504 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use libp2p_identity::PeerId;
+use ream_consensus_lean::{block::SignedBlock, checkpoint::Checkpoint};
+use tree_hash::TreeHash;
+
+use crate::{
+    constants::{LEAN_BLOCKS_PER_RANGE_REQUEST, LEAN_MAX_PENDING_RANGES, LEAN_SYNC_IMPORT_TIMEOUT},
+    req_resp::lean::messages::{blocks::LeanBlocksByRangeV1Request, status::LeanStatus},
+};
+
+/// A `lean_blocks_by_range` request which is about to be sent to a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeRequest {
+    pub peer_id: PeerId,
+    pub request: LeanBlocksByRangeV1Request,
+}
+
+#[derive(Debug)]
+struct ActiveRangeRequest {
+    peer_id: PeerId,
+    request_id: u64,
+    request: LeanBlocksByRangeV1Request,
+    blocks: Vec<SignedBlock>,
+}
+
+/// A verified range which was handed to the chain and isn't known to be imported yet.
+#[derive(Debug)]
+struct PendingImport {
+    peer_id: PeerId,
+    /// The last block of the range
+    last_block: Checkpoint,
+    handed_over: Instant,
+}
+
+/// LeanSyncer catches the lean node up with the heads its peers report in their Status.
+///
+/// The missing blocks are downloaded one range at a time from the peer with the highest head.
+/// Every range has to extend the range before it, or the anchor, so that the chain service
+/// receives the blocks in order and with their parents known. The anchor only moves to a range
+/// once its blocks are imported. A range which doesn't extend the synced chain, or whose blocks
+/// aren't imported in time, rewinds the syncer to the last block it has in common with the peer,
+/// or to the finalized checkpoint.
+#[derive(Debug, Default)]
+pub struct LeanSyncer {
+    peer_statuses: HashMap<PeerId, LeanStatus>,
+    active_request: Option<ActiveRangeRequest>,
+    /// The last imported block, which the synced ranges extend
+    anchor: Option<Checkpoint>,
+    /// The local head the anchor was last compared against
+    local_head: Option<Checkpoint>,
+    /// The first slot of the next range, which is past the anchor if the ranges after it were
+    /// empty or are waiting to be imported
+    next_slot: u64,
+    /// The ranges handed to the chain, oldest first
+    pending_imports: VecDeque<PendingImport>,
+    /// The parent of a range which didn't extend the synced chain, which the syncer has to be
+    /// rewound to
+    fork_root: Option<B256>,
+}
+
+impl LeanSyncer {
+    pub fn update_peer_status(&mut self, peer_id: PeerId, status: LeanStatus) {
+        self.peer_statuses.insert(peer_id, status);
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.peer_statuses.remove(peer_id);
+        if self
+            .active_request
+            .as_ref()
+            .is_some_and(|active_request| active_request.peer_id == *peer_id)
+        {
+            self.active_request = None;
+        }
+    }
+
+    pub fn is_syncing(&self) -> bool {
+        self.active_request.is_some()
+    }
+
+    /// Returns the next range to download, if a peer is ahead of the synced chain and no range is
+    /// being downloaded.
+    pub fn next_range_request(&mut self, local_head: Checkpoint) -> Option<RangeRequest> {
+        if self.active_request.is_some()
+            || self.fork_root.is_some()
+            || self.pending_imports.len() >= LEAN_MAX_PENDING_RANGES
+        {
+            return None;
+        }
+
+        // The anchor follows the local head when blocks are imported outside of sync, but not
+        // back to a head the syncer was rewound from
+        let head_moved = self.local_head.as_ref() != Some(&local_head);
+        if head_moved {
+            self.local_head = Some(local_head.clone());
+        }
+        if self.pending_imports.is_empty()
+            && self
+                .anchor
+                .as_ref()
+                .is_none_or(|anchor| head_moved && local_head.slot > anchor.slot)
+        {
+            self.next_slot = local_head.slot + 1;
+            self.anchor = Some(local_head);
+        }
+
+        let (peer_id, status) = self
+            .peer_statuses
+            .iter()
+            .max_by_key(|(_, status)| status.head_slot)?;
+        if status.head_slot < self.next_slot {
+            return None;
+        }
+
+        let count = (status.head_slot - self.next_slot + 1).min(LEAN_BLOCKS_PER_RANGE_REQUEST);
+        Some(RangeRequest {
+            peer_id: *peer_id,
+            request: LeanBlocksByRangeV1Request::new(self.next_slot, count),
+        })
+    }
+
+    pub fn start_range_request(&mut self, request_id: u64, range_request: RangeRequest) {
+        self.active_request = Some(ActiveRangeRequest {
+            peer_id: range_request.peer_id,
+            request_id,
+            request: range_request.request,
+            blocks: vec![],
+        });
+    }
+
+    /// Buffers a block of the active range, returning false if `request_id` isn't the active
+    /// range request.
+    pub fn on_block(&mut self, request_id: u64, signed_block: SignedBlock) -> bool {
+        match &mut self.active_request {
+            Some(active_request) if active_request.request_id == request_id => {
+                active_request.blocks.push(signed_block);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Completes the active range, returning its blocks once they are verified to extend the
+    /// synced chain, which then wait to be imported. A range whose first block doesn't build on the
+    /// synced chain rewinds the syncer to that block's parent. A peer which served an invalid range
+    /// is not synced from again until it sends a new Status.
+    pub fn on_end_of_stream(
+        &mut self,
+        request_id: u64,
+    ) -> Option<anyhow::Result<Vec<SignedBlock>>> {
+        if self
+            .active_request
+            .as_ref()
+            .is_none_or(|active_request| active_request.request_id != request_id)
+        {
+            return None;
+        }
+        let active_request = self.active_request.take()?;
+        let tip = self.tip();
+
+        if let Some(first_block) = active_request.blocks.first()
+            && first_block.message.parent_root != tip.root
+            && first_block.message.slot > tip.slot
+        {
+            self.fork_root = Some(first_block.message.parent_root);
+            return Some(Err(anyhow!(
+                "Block at slot {} has parent {}, which isn't the synced block {} at slot {}",
+                first_block.message.slot,
+                first_block.message.parent_root,
+                tip.root,
+                tip.slot
+            )));
+        }
+
+        if let Err(err) = verify_range(&tip, &active_request.request, &active_request.blocks) {
+            self.peer_statuses.remove(&active_request.peer_id);
+            return Some(Err(err));
+        }
+
+        self.next_slot = active_request.request.start_slot + active_request.request.count;
+        if let Some(last_block) = active_request.blocks.last() {
+            self.pending_imports.push_back(PendingImport {
+                peer_id: active_request.peer_id,
+                last_block: Checkpoint {
+                    root: last_block.message.tree_hash_root(),
+                    slot: last_block.message.slot,
+                },
+                handed_over: Instant::now(),
+            });
+        }
+        Some(Ok(active_request.blocks))
+    }
+
+    /// Drops the active range after its request failed, returning the peer it was requested from.
+    pub fn on_request_failed(&mut self, request_id: u64) -> Option<PeerId> {
+        if self
+            .active_request
+            .as_ref()
+            .is_none_or(|active_request| active_request.request_id != request_id)
+        {
+            return None;
+        }
+        let active_request = self.active_request.take()?;
+        self.peer_statuses.remove(&active_request.peer_id);
+        Some(active_request.peer_id)
+    }
+
+    /// The last block of the oldest range waiting to be imported.
+    pub fn pending_import(&self) -> Option<B256> {
+        self.pending_imports
+            .front()
+            .map(|pending_import| pending_import.last_block.root)
+    }
+
+    /// Moves the anchor to the oldest range waiting to be imported, after its blocks were
+    /// imported.
+    pub fn on_range_imported(&mut self) {
+        if let Some(pending_import) = self.pending_imports.pop_front() {
+            self.anchor = Some(pending_import.last_block);
+        }
+    }
+
+    /// Whether the oldest range waiting to be imported was handed to the chain too long ago to
+    /// still be imported, returning the peer which served it.
+    pub fn overdue_import(&self) -> Option<PeerId> {
+        self.pending_imports
+            .front()
+            .filter(|pending_import| {
+                pending_import.handed_over.elapsed() > LEAN_SYNC_IMPORT_TIMEOUT
+            })
+            .map(|pending_import| pending_import.peer_id)
+    }
+
+    /// The parent of a range which didn't extend the synced chain, which the syncer has to be
+    /// rewound to.
+    pub fn take_fork_root(&mut self) -> Option<B256> {
+        self.fork_root.take()
+    }
+
+    /// Restarts syncing after `checkpoint`, dropping the ranges which weren't imported.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.next_slot = checkpoint.slot + 1;
+        self.anchor = Some(checkpoint);
+        self.pending_imports.clear();
+        self.fork_root = None;
+    }
+
+    /// The block the next range has to extend.
+    fn tip(&self) -> Checkpoint {
+        self.pending_imports
+            .back()
+            .map(|pending_import| pending_import.last_block.clone())
+            .or_else(|| self.anchor.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Verifies that `blocks` are in the requested range in ascending slot order, and that each block
+/// is the child of the one before it, starting from the `anchor`.
+pub fn verify_range(
+    anchor: &Checkpoint,
+    request: &LeanBlocksByRangeV1Request,
+    blocks: &[SignedBlock],
+) -> anyhow::Result<()> {
+    let end_slot = request.start_slot + request.count;
+    let mut parent_root = anchor.root;
+    let mut parent_slot = anchor.slot;
+    for signed_block in blocks {
+        let block = &signed_block.message;
+        ensure!(
+            (request.start_slot..end_slot).contains(&block.slot),
+            "Block at slot {} is outside of the requested range {}..{end_slot}",
+            block.slot,
+            request.start_slot
+        );
+        ensure!(
+            block.slot > parent_slot,
+            "Block at slot {} is not after its parent at slot {parent_slot}",
+            block.slot
+        );
+        ensure!(
+            block.parent_root == parent_root,
+            "Block at slot {} has parent {}, expected {parent_root}",
+            block.slot,
+            block.parent_root
+        );
+
+        parent_root = block.tree_hash_root();
+        parent_slot = block.slot;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::FixedBytes;
+    use ream_consensus_lean::block::Block;
+
+    use super::*;
+
+    fn block_chain(parent_root: B256, slots: &[u64]) -> Vec<SignedBlock> {
+        let mut parent_root = parent_root;
+        slots
+            .iter()
+            .map(|slot| {
+                let signed_block = SignedBlock {
+                    message: Block {
+                        slot: *slot,
+                        parent_root,
+                        ..Default::default()
+                    },
+                    signature: FixedBytes::ZERO,
+                };
+                parent_root = signed_block.message.tree_hash_root();
+                signed_block
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_range() {
+        let anchor = Checkpoint {
+            root: B256::repeat_byte(1),
+            slot: 4,
+        };
+        let request = LeanBlocksByRangeV1Request::new(5, 4);
+
+        let blocks = block_chain(anchor.root, &[5, 7, 8]);
+        assert!(verify_range(&anchor, &request, &blocks).is_ok());
+        assert!(verify_range(&anchor, &request, &[]).is_ok());
+
+        // Not extending the anchor
+        let blocks = block_chain(B256::repeat_byte(2), &[5, 7, 8]);
+        assert!(verify_range(&anchor, &request, &blocks).is_err());
+
+        // Out of the requested range
+        let blocks = block_chain(anchor.root, &[5, 7, 9]);
+        assert!(verify_range(&anchor, &request, &blocks).is_err());
+
+        // A broken parent link
+        let mut blocks = block_chain(anchor.root, &[5, 7, 8]);
+        blocks.remove(1);
+        assert!(verify_range(&anchor, &request, &blocks).is_err());
+    }
+
+    #[test]
+    fn test_next_range_request() {
+        let mut syncer = LeanSyncer::default();
+        let local_head = Checkpoint {
+            root: B256::repeat_byte(1),
+            slot: 10,
+        };
+        assert_eq!(syncer.next_range_request(local_head.clone()), None);
+
+        let peer_id = PeerId::random();
+        syncer.update_peer_status(
+            peer_id,
+            LeanStatus {
+                head_slot: 10 + LEAN_BLOCKS_PER_RANGE_REQUEST * 2,
+                ..Default::default()
+            },
+        );
+        let range_request = syncer
+            .next_range_request(local_head.clone())
+            .expect("Peer is ahead");
+        assert_eq!(range_request.peer_id, peer_id);
+        assert_eq!(
+            range_request.request,
+            LeanBlocksByRangeV1Request::new(11, LEAN_BLOCKS_PER_RANGE_REQUEST)
+        );
+
+        // No range is requested while one is being downloaded
+        syncer.start_range_request(0, range_request);
+        assert!(syncer.is_syncing());
+        assert_eq!(syncer.next_range_request(local_head.clone()), None);
+
+        // The next range starts after the downloaded one, even if the local head hasn't moved
+        let blocks = block_chain(local_head.root, &[11, 12]);
+        for block in blocks {
+            assert!(syncer.on_block(0, block));
+        }
+        assert_eq!(
+            syncer.on_end_of_stream(0).map(|blocks| blocks.is_ok()),
+            Some(true)
+        );
+        let range_request = syncer
+            .next_range_request(local_head)
+            .expect("Peer is still ahead");
+        assert_eq!(
+            range_request.request,
+            LeanBlocksByRangeV1Request::new(
+                11 + LEAN_BLOCKS_PER_RANGE_REQUEST,
+                LEAN_BLOCKS_PER_RANGE_REQUEST
+            )
+        );
+
+        // A failed request drops the peer
+        syncer.start_range_request(1, range_request);
+        assert_eq!(syncer.on_request_failed(1), Some(peer_id));
+        assert_eq!(syncer.next_range_request(Checkpoint::default()), None);
+    }
+
+    #[test]
+    fn test_anchor_moves_after_import() {
+        let mut syncer = LeanSyncer::default();
+        let local_head = Checkpoint {
+            root: B256::repeat_byte(1),
+            slot: 10,
+        };
+        let peer_id = PeerId::random();
+        syncer.update_peer_status(
+            peer_id,
+            LeanStatus {
+                head_slot: 10 + LEAN_BLOCKS_PER_RANGE_REQUEST * 2,
+                ..Default::default()
+            },
+        );
+
+        let range_request = syncer
+            .next_range_request(local_head.clone())
+            .expect("Peer is ahead");
+        syncer.start_range_request(0, range_request);
+        let blocks = block_chain(local_head.root, &[11, 12]);
+        let last_root = blocks[1].message.tree_hash_root();
+        for block in blocks {
+            assert!(syncer.on_block(0, block));
+        }
+        assert!(matches!(syncer.on_end_of_stream(0), Some(Ok(_))));
+
+        // The anchor stays until the range is imported, but the next range extends it
+        assert_eq!(syncer.anchor, Some(local_head.clone()));
+        assert_eq!(syncer.pending_import(), Some(last_root));
+        let range_request = syncer
+            .next_range_request(local_head.clone())
+            .expect("Peer is still ahead");
+        syncer.start_range_request(1, range_request);
+        for block in block_chain(last_root, &[11 + LEAN_BLOCKS_PER_RANGE_REQUEST]) {
+            assert!(syncer.on_block(1, block));
+        }
+        assert!(matches!(syncer.on_end_of_stream(1), Some(Ok(_))));
+
+        syncer.on_range_imported();
+        assert_eq!(
+            syncer.anchor,
+            Some(Checkpoint {
+                root: last_root,
+                slot: 12,
+            })
+        );
+        assert!(syncer.pending_import().is_some());
+        assert_eq!(syncer.overdue_import(), None);
+    }
+
+    #[test]
+    fn test_fork_rewinds_syncer() {
+        let mut syncer = LeanSyncer::default();
+        let local_head = Checkpoint {
+            root: B256::repeat_byte(1),
+            slot: 10,
+        };
+        let peer_id = PeerId::random();
+        syncer.update_peer_status(
+            peer_id,
+            LeanStatus {
+                head_slot: 20,
+                ..Default::default()
+            },
+        );
+
+        let range_request = syncer
+            .next_range_request(local_head.clone())
+            .expect("Peer is ahead");
+        syncer.start_range_request(0, range_request);
+        let fork_root = B256::repeat_byte(2);
+        for block in block_chain(fork_root, &[11, 12]) {
+            assert!(syncer.on_block(0, block));
+        }
+        assert!(matches!(syncer.on_end_of_stream(0), Some(Err(_))));
+
+        // Nothing is requested until the syncer is rewound, and the peer is kept
+        assert_eq!(syncer.next_range_request(local_head.clone()), None);
+        assert_eq!(syncer.take_fork_root(), Some(fork_root));
+        syncer.rewind(Checkpoint {
+            root: B256::repeat_byte(3),
+            slot: 6,
+        });
+
+        // The unchanged local head doesn't move the anchor back
+        let range_request = syncer
+            .next_range_request(local_head)
+            .expect("Peer is ahead of the rewound syncer");
+        assert_eq!(range_request.peer_id, peer_id);
+        assert_eq!(
+            range_request.request,
+            LeanBlocksByRangeV1Request::new(7, 14)
+        );
+    }
+}
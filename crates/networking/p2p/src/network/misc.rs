@@ -1,16 +1,24 @@
-use std::{io, pin::Pin, time::Duration};
+use std::{
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use discv5::Enr;
 use enr::CombinedPublicKey;
+use futures::future::Either;
 use libp2p::{
-    Transport,
+    Multiaddr, Transport,
     core::{
         muxing::StreamMuxerBox,
         transport::Boxed,
         upgrade::{SelectUpgrade, Version},
     },
     dns::Transport as DnsTransport,
+    multiaddr::Protocol,
     noise::Config as NoiseConfig,
+    quic::{Config as QuicConfig, tokio::Transport as QuicTransport},
     tcp::{Config as TcpConfig, tokio::Transport as TcpTransport},
     yamux,
 };
@@ -27,7 +35,12 @@ impl libp2p::swarm::Executor for Executor {
     }
 }
 
-pub fn build_transport(local_private_key: Keypair) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
+/// Builds the TCP transport secured with noise and multiplexed with yamux or mplex, and, if
+/// `enable_quic` is set, the QUIC transport next to it.
+pub fn build_transport(
+    local_private_key: Keypair,
+    enable_quic: bool,
+) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
     // mplex config
     let mut mplex_config = MplexConfig::new();
     mplex_config.set_max_buffer_size(256);
@@ -40,7 +53,17 @@ pub fn build_transport(local_private_key: Keypair) -> io::Result<Boxed<(PeerId,
         .authenticate(NoiseConfig::new(&local_private_key).expect("Noise disabled"))
         .multiplex(SelectUpgrade::new(yamux_config, mplex_config))
         .timeout(Duration::from_secs(10));
-    let transport = tcp.boxed();
+    let transport = if enable_quic {
+        let quic = QuicTransport::new(QuicConfig::new(&local_private_key));
+        tcp.or_transport(quic)
+            .map(|output, _| match output {
+                Either::Left((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+                Either::Right((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+            })
+            .boxed()
+    } else {
+        tcp.boxed()
+    };
 
     let transport = DnsTransport::system(transport)?.boxed();
 
@@ -59,3 +82,26 @@ pub fn peer_id_from_enr(enr: &Enr) -> Option<PeerId> {
         _ => None,
     }
 }
+
+/// Returns the IP address and TCP port of a multiaddr such as `/ip4/1.2.3.4/tcp/9000`.
+pub fn tcp_socket_address_from_multiaddr(multiaddr: &Multiaddr) -> Option<SocketAddr> {
+    let mut ip = None;
+    let mut tcp_port = None;
+    for protocol in multiaddr.iter() {
+        match protocol {
+            Protocol::Ip4(address) => ip = Some(address.into()),
+            Protocol::Ip6(address) => ip = Some(address.into()),
+            Protocol::Tcp(port) => tcp_port = Some(port),
+            _ => {}
+        }
+    }
+    Some(SocketAddr::new(ip?, tcp_port?))
+}
+
+/// The current unix timestamp in seconds.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -1,4 +1,5 @@
 pub mod beacon;
+pub mod known_peers;
 pub mod lean;
 pub mod misc;
 pub mod peer;
@@ -9,7 +9,25 @@ pub enum ConnectionState {
     Disconnecting,
 }
 
-#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+impl ConnectionState {
+    pub const ALL: [ConnectionState; 4] = [
+        ConnectionState::Connected,
+        ConnectionState::Connecting,
+        ConnectionState::Disconnected,
+        ConnectionState::Disconnecting,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectionState::Connected => "connected",
+            ConnectionState::Connecting => "connecting",
+            ConnectionState::Disconnected => "disconnected",
+            ConnectionState::Disconnecting => "disconnecting",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
     Inbound,
@@ -17,6 +35,18 @@ pub enum Direction {
     Unknown,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 3] = [Direction::Inbound, Direction::Outbound, Direction::Unknown];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Inbound => "inbound",
+            Direction::Outbound => "outbound",
+            Direction::Unknown => "unknown",
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, Serialize)]
 pub struct PeerCount {
     #[serde(with = "serde_utils::quoted_u64")]
@@ -0,0 +1,26 @@
+use ream_consensus_beacon::data_column_sidecar::DataColumnsByRootIdentifier;
+use ssz_derive::{Decode, Encode};
+use ssz_types::{VariableList, typenum::U128};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct DataColumnSidecarsByRangeV1Request {
+    pub start_slot: u64,
+    pub count: u64,
+    pub columns: VariableList<u64, U128>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+#[ssz(struct_behaviour = "transparent")]
+pub struct DataColumnSidecarsByRootV1Request {
+    pub inner: VariableList<DataColumnsByRootIdentifier, U128>,
+}
+
+/// Will panic if columns of over 128 blocks are requested
+impl DataColumnSidecarsByRootV1Request {
+    pub fn new(identifiers: Vec<DataColumnsByRootIdentifier>) -> Self {
+        Self {
+            inner: VariableList::new(identifiers)
+                .expect("Too many data column identifiers were requested"),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use ssz::{Decode, Encode};
 
+/// The reason a peer gives before it disconnects.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Goodbye {
     ClientShutdown,
@@ -13,6 +14,14 @@ pub enum Goodbye {
     UnspecifiedGoodbye(u64),
 }
 
+impl Goodbye {
+    /// Whether the peer disconnects because it doesn't want to be connected to us, rather than
+    /// because it is shutting down.
+    pub fn rejects_us(&self) -> bool {
+        !matches!(self, Goodbye::ClientShutdown)
+    }
+}
+
 impl From<Goodbye> for u64 {
     fn from(reason: Goodbye) -> u64 {
         match reason {
@@ -4,9 +4,42 @@ use ssz_types::BitVector;
 
 use crate::req_resp::configurations::{AttestationSubnetCount, SyncCommitteeSubnetCount};
 
+/// The `metadata/2` message, for peers that don't support `metadata/3` yet.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 pub struct GetMetaDataV2 {
     pub seq_number: u64,
     pub attnets: BitVector<AttestationSubnetCount>,
     pub syncnets: BitVector<SyncCommitteeSubnetCount>,
 }
+
+/// The `metadata/3` message, which is how a peer's metadata is tracked regardless of the version
+/// it was exchanged with.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct GetMetaDataV3 {
+    pub seq_number: u64,
+    pub attnets: BitVector<AttestationSubnetCount>,
+    pub syncnets: BitVector<SyncCommitteeSubnetCount>,
+    pub custody_group_count: u64,
+}
+
+impl From<GetMetaDataV2> for GetMetaDataV3 {
+    /// A `metadata/2` peer doesn't advertise any custody groups.
+    fn from(meta_data: GetMetaDataV2) -> Self {
+        Self {
+            seq_number: meta_data.seq_number,
+            attnets: meta_data.attnets,
+            syncnets: meta_data.syncnets,
+            custody_group_count: 0,
+        }
+    }
+}
+
+impl From<&GetMetaDataV3> for GetMetaDataV2 {
+    fn from(meta_data: &GetMetaDataV3) -> Self {
+        Self {
+            seq_number: meta_data.seq_number,
+            attnets: meta_data.attnets.clone(),
+            syncnets: meta_data.syncnets.clone(),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod blob_sidecars;
 pub mod blocks;
+pub mod data_column_sidecars;
 pub mod goodbye;
 pub mod meta_data;
 pub mod ping;
@@ -9,12 +10,17 @@ use std::sync::Arc;
 
 use blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request};
 use blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request};
+use data_column_sidecars::{DataColumnSidecarsByRangeV1Request, DataColumnSidecarsByRootV1Request};
 use goodbye::Goodbye;
-use meta_data::GetMetaDataV2;
+use meta_data::{GetMetaDataV2, GetMetaDataV3};
 use ping::Ping;
-use ream_consensus_beacon::{blob_sidecar::BlobSidecar, electra::beacon_block::SignedBeaconBlock};
+use ream_consensus_beacon::{
+    blob_sidecar::BlobSidecar, data_column_sidecar::DataColumnSidecar,
+    electra::beacon_block::SignedBeaconBlock,
+};
+use ssz::Encode as _;
 use ssz_derive::{Decode, Encode};
-use status::Status;
+use status::{Status, StatusV1};
 
 use super::protocol_id::BeaconSupportedProtocol;
 use crate::req_resp::protocol_id::{ProtocolId, SupportedProtocol};
@@ -22,7 +28,7 @@ use crate::req_resp::protocol_id::{ProtocolId, SupportedProtocol};
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 #[ssz(enum_behaviour = "transparent")]
 pub enum BeaconRequestMessage {
-    MetaData(Arc<GetMetaDataV2>),
+    MetaData(Arc<GetMetaDataV3>),
     Goodbye(Goodbye),
     Status(Status),
     Ping(Ping),
@@ -30,20 +36,43 @@ pub enum BeaconRequestMessage {
     BeaconBlocksByRoot(BeaconBlocksByRootV2Request),
     BlobSidecarsByRange(BlobSidecarsByRangeV1Request),
     BlobSidecarsByRoot(BlobSidecarsByRootV1Request),
+    DataColumnSidecarsByRange(DataColumnSidecarsByRangeV1Request),
+    DataColumnSidecarsByRoot(DataColumnSidecarsByRootV1Request),
 }
 
 impl BeaconRequestMessage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BeaconRequestMessage::MetaData(_) => "metadata",
+            BeaconRequestMessage::Goodbye(_) => "goodbye",
+            BeaconRequestMessage::Status(_) => "status",
+            BeaconRequestMessage::Ping(_) => "ping",
+            BeaconRequestMessage::BeaconBlocksByRange(_) => "beacon_blocks_by_range",
+            BeaconRequestMessage::BeaconBlocksByRoot(_) => "beacon_blocks_by_root",
+            BeaconRequestMessage::BlobSidecarsByRange(_) => "blob_sidecars_by_range",
+            BeaconRequestMessage::BlobSidecarsByRoot(_) => "blob_sidecars_by_root",
+            BeaconRequestMessage::DataColumnSidecarsByRange(_) => "data_column_sidecars_by_range",
+            BeaconRequestMessage::DataColumnSidecarsByRoot(_) => "data_column_sidecars_by_root",
+        }
+    }
+
     pub fn supported_protocols(&self) -> Vec<ProtocolId> {
         match self {
-            BeaconRequestMessage::MetaData(_) => vec![ProtocolId::new(SupportedProtocol::Beacon(
-                BeaconSupportedProtocol::StatusV1,
-            ))],
+            BeaconRequestMessage::MetaData(_) => vec![
+                ProtocolId::new(SupportedProtocol::Beacon(
+                    BeaconSupportedProtocol::GetMetaDataV3,
+                )),
+                ProtocolId::new(SupportedProtocol::Beacon(
+                    BeaconSupportedProtocol::GetMetaDataV2,
+                )),
+            ],
             BeaconRequestMessage::Goodbye(_) => vec![ProtocolId::new(SupportedProtocol::Beacon(
                 BeaconSupportedProtocol::GoodbyeV1,
             ))],
-            BeaconRequestMessage::Status(_) => vec![ProtocolId::new(SupportedProtocol::Beacon(
-                BeaconSupportedProtocol::StatusV1,
-            ))],
+            BeaconRequestMessage::Status(_) => vec![
+                ProtocolId::new(SupportedProtocol::Beacon(BeaconSupportedProtocol::StatusV2)),
+                ProtocolId::new(SupportedProtocol::Beacon(BeaconSupportedProtocol::StatusV1)),
+            ],
             BeaconRequestMessage::Ping(_) => vec![ProtocolId::new(SupportedProtocol::Beacon(
                 BeaconSupportedProtocol::PingV1,
             ))],
@@ -67,6 +96,27 @@ impl BeaconRequestMessage {
                     BeaconSupportedProtocol::BlobSidecarsByRootV1,
                 ))]
             }
+            BeaconRequestMessage::DataColumnSidecarsByRange(_) => {
+                vec![ProtocolId::new(SupportedProtocol::Beacon(
+                    BeaconSupportedProtocol::DataColumnSidecarsByRangeV1,
+                ))]
+            }
+            BeaconRequestMessage::DataColumnSidecarsByRoot(_) => {
+                vec![ProtocolId::new(SupportedProtocol::Beacon(
+                    BeaconSupportedProtocol::DataColumnSidecarsByRootV1,
+                ))]
+            }
+        }
+    }
+
+    /// Encode the message with the schema of the negotiated `protocol`, which can be older than
+    /// the one the message is tracked as.
+    pub fn as_ssz_bytes_for_protocol(&self, protocol: BeaconSupportedProtocol) -> Vec<u8> {
+        match (self, protocol) {
+            (BeaconRequestMessage::Status(status), BeaconSupportedProtocol::StatusV1) => {
+                StatusV1::from(status).as_ssz_bytes()
+            }
+            (message, _) => message.as_ssz_bytes(),
         }
     }
 }
@@ -74,7 +124,7 @@ impl BeaconRequestMessage {
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 #[ssz(enum_behaviour = "transparent")]
 pub enum BeaconResponseMessage {
-    MetaData(Arc<GetMetaDataV2>),
+    MetaData(Arc<GetMetaDataV3>),
     Goodbye(Goodbye),
     Status(Status),
     Ping(Ping),
@@ -82,4 +132,50 @@ pub enum BeaconResponseMessage {
     BeaconBlocksByRoot(SignedBeaconBlock),
     BlobSidecarsByRange(BlobSidecar),
     BlobSidecarsByRoot(BlobSidecar),
+    DataColumnSidecarsByRange(DataColumnSidecar),
+    DataColumnSidecarsByRoot(DataColumnSidecar),
+}
+
+impl BeaconResponseMessage {
+    /// The slot of the block the response belongs to, which determines the fork of its context
+    /// bytes.
+    pub fn slot(&self) -> Option<u64> {
+        match self {
+            BeaconResponseMessage::BeaconBlocksByRange(signed_block)
+            | BeaconResponseMessage::BeaconBlocksByRoot(signed_block) => {
+                Some(signed_block.message.slot.as_u64())
+            }
+            BeaconResponseMessage::BlobSidecarsByRange(blob_sidecar)
+            | BeaconResponseMessage::BlobSidecarsByRoot(blob_sidecar) => {
+                Some(blob_sidecar.signed_block_header.message.slot.as_u64())
+            }
+            BeaconResponseMessage::DataColumnSidecarsByRange(data_column_sidecar)
+            | BeaconResponseMessage::DataColumnSidecarsByRoot(data_column_sidecar) => Some(
+                data_column_sidecar
+                    .signed_block_header
+                    .message
+                    .slot
+                    .as_u64(),
+            ),
+            BeaconResponseMessage::MetaData(_)
+            | BeaconResponseMessage::Goodbye(_)
+            | BeaconResponseMessage::Status(_)
+            | BeaconResponseMessage::Ping(_) => None,
+        }
+    }
+
+    /// Encode the message with the schema of the negotiated `protocol`, which can be older than
+    /// the one the message is tracked as.
+    pub fn as_ssz_bytes_for_protocol(&self, protocol: BeaconSupportedProtocol) -> Vec<u8> {
+        match (self, protocol) {
+            (BeaconResponseMessage::Status(status), BeaconSupportedProtocol::StatusV1) => {
+                StatusV1::from(status).as_ssz_bytes()
+            }
+            (
+                BeaconResponseMessage::MetaData(meta_data),
+                BeaconSupportedProtocol::GetMetaDataV2,
+            ) => GetMetaDataV2::from(meta_data.as_ref()).as_ssz_bytes(),
+            (message, _) => message.as_ssz_bytes(),
+        }
+    }
 }
@@ -1,6 +1,8 @@
 use alloy_primitives::{B256, aliases::B32};
 use ssz_derive::{Decode, Encode};
 
+/// The `status/2` message, which is how a peer's status is tracked regardless of the version it
+/// was exchanged with.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct Status {
     pub fork_digest: B32,
@@ -8,4 +10,43 @@ pub struct Status {
     pub finalized_epoch: u64,
     pub head_root: B256,
     pub head_slot: u64,
+    /// The slot of the earliest block the peer is able to serve.
+    pub earliest_available_slot: u64,
+}
+
+/// The `status/1` message, for peers that don't support `status/2` yet.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct StatusV1 {
+    pub fork_digest: B32,
+    pub finalized_root: B256,
+    pub finalized_epoch: u64,
+    pub head_root: B256,
+    pub head_slot: u64,
+}
+
+impl From<StatusV1> for Status {
+    /// A `status/1` peer doesn't report which blocks it can serve, so it is assumed to serve all
+    /// of them.
+    fn from(status: StatusV1) -> Self {
+        Self {
+            fork_digest: status.fork_digest,
+            finalized_root: status.finalized_root,
+            finalized_epoch: status.finalized_epoch,
+            head_root: status.head_root,
+            head_slot: status.head_slot,
+            earliest_available_slot: 0,
+        }
+    }
+}
+
+impl From<&Status> for StatusV1 {
+    fn from(status: &Status) -> Self {
+        Self {
+            fork_digest: status.fork_digest,
+            finalized_root: status.finalized_root,
+            finalized_epoch: status.finalized_epoch,
+            head_root: status.head_root,
+            head_slot: status.head_slot,
+        }
+    }
 }
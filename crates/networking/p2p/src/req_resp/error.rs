@@ -13,6 +13,9 @@ pub enum ReqRespError {
     #[error("Invalid data {0}")]
     InvalidData(String),
 
+    #[error("Resource unavailable {0}")]
+    ResourceUnavailable(String),
+
     #[error("Incomplete stream")]
     IncompleteStream,
 
@@ -66,6 +66,7 @@ impl RespMessage {
                 | ReqRespError::IoError(_) => Some(ResponseCode::ServerError),
                 ReqRespError::InvalidData(_) => Some(ResponseCode::InvalidRequest),
                 ReqRespError::Disconnected
+                | ReqRespError::ResourceUnavailable(_)
                 | ReqRespError::StreamTimedOut
                 | ReqRespError::TokioTimedOut(_) => Some(ResponseCode::ResourceUnavailable),
             },
@@ -15,7 +15,9 @@ use libp2p::{
     bytes::{Buf, BufMut},
     core::UpgradeInfo,
 };
-use ream_consensus_misc::constants::beacon::genesis_validators_root;
+use ream_consensus_misc::{
+    constants::beacon::genesis_validators_root, misc::compute_epoch_at_slot,
+};
 use ream_network_spec::networks::beacon_network_spec;
 use snap::{read::FrameDecoder, write::FrameEncoder};
 use ssz::{Decode, Encode};
@@ -31,7 +33,7 @@ use unsigned_varint::codec::Uvi;
 
 use super::{
     beacon::{
-        messages::{BeaconRequestMessage, meta_data::GetMetaDataV2},
+        messages::{BeaconRequestMessage, meta_data::GetMetaDataV3},
         protocol_id::BeaconSupportedProtocol,
     },
     handler::RespMessage,
@@ -42,13 +44,20 @@ use crate::{
         beacon::messages::{
             blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request},
             blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request},
+            data_column_sidecars::{
+                DataColumnSidecarsByRangeV1Request, DataColumnSidecarsByRootV1Request,
+            },
             goodbye::Goodbye,
             ping::Ping,
-            status::Status,
+            status::{Status, StatusV1},
         },
         error::ReqRespError,
         lean::{
-            messages::{LeanRequestMessage, blocks::LeanBlocksByRootV1Request, status::LeanStatus},
+            messages::{
+                LeanRequestMessage,
+                blocks::{LeanBlocksByRangeV1Request, LeanBlocksByRootV1Request},
+                status::LeanStatus,
+            },
             protocol_id::LeanSupportedProtocol,
         },
         messages::RequestMessage,
@@ -84,18 +93,18 @@ where
 
             let socket = Framed::new(
                 Box::pin(timed_socket),
-                InboundSSZSnappyCodec {
-                    protocol: info.clone(),
-                },
+                InboundSSZSnappyCodec::new(info.clone()),
             );
 
             match info.protocol {
-                SupportedProtocol::Beacon(BeaconSupportedProtocol::GetMetaDataV2) => Ok((
-                    RequestMessage::Beacon(BeaconRequestMessage::MetaData(
-                        GetMetaDataV2::default().into(),
-                    )),
-                    socket,
-                )),
+                SupportedProtocol::Beacon(beacon_protocol) if beacon_protocol.is_meta_data() => {
+                    Ok((
+                        RequestMessage::Beacon(BeaconRequestMessage::MetaData(
+                            GetMetaDataV3::default().into(),
+                        )),
+                        socket,
+                    ))
+                }
                 _ => match timeout(Duration::from_secs(15), socket.into_future()).await {
                     Ok((Some(Ok(message)), stream)) => Ok((message, stream)),
                     Ok((Some(Err(err)), _)) => Err(err),
@@ -123,6 +132,12 @@ pub struct InboundSSZSnappyCodec {
     protocol: ProtocolId,
 }
 
+impl InboundSSZSnappyCodec {
+    pub fn new(protocol: ProtocolId) -> Self {
+        Self { protocol }
+    }
+}
+
 impl Encoder<RespMessage> for InboundSSZSnappyCodec {
     type Error = ReqRespError;
 
@@ -131,8 +146,16 @@ impl Encoder<RespMessage> for InboundSSZSnappyCodec {
         let response_code = item.as_response_code().expect("EndOfStream cannot be sent");
         dst.put_u8(u8::from(response_code));
 
+        // The context bytes are the fork digest of the fork the response's block belongs to
+        let context_slot = match &item {
+            RespMessage::Response(message) => message.slot(),
+            _ => None,
+        };
+
         let bytes = match item {
-            RespMessage::Response(messages) => messages.as_ssz_bytes(),
+            RespMessage::Response(messages) => {
+                messages.as_ssz_bytes_for_protocol(&self.protocol.protocol)
+            }
             RespMessage::Error(req_resp_error) => {
                 VariableList::<u8, U256>::from(req_resp_error.to_string().as_bytes().to_vec())
                     .as_ssz_bytes()
@@ -151,7 +174,12 @@ impl Encoder<RespMessage> for InboundSSZSnappyCodec {
         }
 
         if self.protocol.protocol.has_context_bytes() && response_code == ResponseCode::Success {
-            dst.extend(beacon_network_spec().fork_digest(genesis_validators_root()));
+            let fork_digest = match context_slot {
+                Some(slot) => beacon_network_spec()
+                    .fork_digest_at_epoch(compute_epoch_at_slot(slot), genesis_validators_root()),
+                None => beacon_network_spec().fork_digest(genesis_validators_root()),
+            };
+            dst.extend(fork_digest);
         }
 
         Uvi::<usize>::default().encode(bytes.len(), dst)?;
@@ -170,11 +198,11 @@ impl Decoder for InboundSSZSnappyCodec {
     type Error = ReqRespError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if self.protocol.protocol
-            == SupportedProtocol::Beacon(BeaconSupportedProtocol::GetMetaDataV2)
+        if let SupportedProtocol::Beacon(beacon_protocol) = self.protocol.protocol
+            && beacon_protocol.is_meta_data()
         {
             return Ok(Some(RequestMessage::Beacon(
-                BeaconRequestMessage::MetaData(GetMetaDataV2::default().into()),
+                BeaconRequestMessage::MetaData(GetMetaDataV3::default().into()),
             )));
         }
 
@@ -183,6 +211,16 @@ impl Decoder for InboundSSZSnappyCodec {
             None => return Ok(None),
         };
 
+        // The length-prefix is within the expected size bounds derived from the payload SSZ
+        // type or MAX_PAYLOAD_SIZE, whichever is smaller.
+        if length > max_message_size() as usize {
+            return Err(ReqRespError::Anyhow(anyhow::anyhow!(
+                "Message size exceeds maximum: {} > {}",
+                length,
+                max_message_size()
+            )));
+        }
+
         let mut decoder = FrameDecoder::new(Cursor::new(&src));
         let mut buf: Vec<u8> = vec![0; length];
         let result = match decoder.read_exact(&mut buf) {
@@ -195,6 +233,11 @@ impl Decoder for InboundSSZSnappyCodec {
                                 Goodbye::from_ssz_bytes(&buf).map_err(ReqRespError::from)?,
                             ),
                             BeaconSupportedProtocol::StatusV1 => BeaconRequestMessage::Status(
+                                StatusV1::from_ssz_bytes(&buf)
+                                    .map_err(ReqRespError::from)?
+                                    .into(),
+                            ),
+                            BeaconSupportedProtocol::StatusV2 => BeaconRequestMessage::Status(
                                 Status::from_ssz_bytes(&buf).map_err(ReqRespError::from)?,
                             ),
                             BeaconSupportedProtocol::PingV1 => BeaconRequestMessage::Ping(
@@ -224,9 +267,22 @@ impl Decoder for InboundSSZSnappyCodec {
                                         .map_err(ReqRespError::from)?,
                                 )
                             }
-                            BeaconSupportedProtocol::GetMetaDataV2 => {
+                            BeaconSupportedProtocol::DataColumnSidecarsByRangeV1 => {
+                                BeaconRequestMessage::DataColumnSidecarsByRange(
+                                    DataColumnSidecarsByRangeV1Request::from_ssz_bytes(&buf)
+                                        .map_err(ReqRespError::from)?,
+                                )
+                            }
+                            BeaconSupportedProtocol::DataColumnSidecarsByRootV1 => {
+                                BeaconRequestMessage::DataColumnSidecarsByRoot(
+                                    DataColumnSidecarsByRootV1Request::from_ssz_bytes(&buf)
+                                        .map_err(ReqRespError::from)?,
+                                )
+                            }
+                            BeaconSupportedProtocol::GetMetaDataV2
+                            | BeaconSupportedProtocol::GetMetaDataV3 => {
                                 return Err(ReqRespError::InvalidData(
-                                    "GetMetaDataV2 is already handled above".to_string(),
+                                    "GetMetaData is already handled above".to_string(),
                                 ));
                             }
                         };
@@ -237,6 +293,12 @@ impl Decoder for InboundSSZSnappyCodec {
                             LeanSupportedProtocol::StatusV1 => LeanRequestMessage::Status(
                                 LeanStatus::from_ssz_bytes(&buf).map_err(ReqRespError::from)?,
                             ),
+                            LeanSupportedProtocol::BlocksByRangeV1 => {
+                                LeanRequestMessage::BlocksByRange(
+                                    LeanBlocksByRangeV1Request::from_ssz_bytes(&buf)
+                                        .map_err(ReqRespError::from)?,
+                                )
+                            }
                             LeanSupportedProtocol::BlocksByRootV1 => {
                                 LeanRequestMessage::BlocksByRoot(
                                     LeanBlocksByRootV1Request::from_ssz_bytes(&buf)
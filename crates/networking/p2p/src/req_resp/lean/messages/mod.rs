@@ -3,7 +3,7 @@ pub mod status;
 
 use std::sync::Arc;
 
-use blocks::LeanBlocksByRootV1Request;
+use blocks::{LeanBlocksByRangeV1Request, LeanBlocksByRootV1Request};
 use ream_consensus_lean::block::SignedBlock;
 use ssz_derive::{Decode, Encode};
 use status::LeanStatus;
@@ -15,6 +15,7 @@ use crate::req_resp::protocol_id::{ProtocolId, SupportedProtocol};
 #[ssz(enum_behaviour = "transparent")]
 pub enum LeanRequestMessage {
     Status(LeanStatus),
+    BlocksByRange(LeanBlocksByRangeV1Request),
     BlocksByRoot(LeanBlocksByRootV1Request),
 }
 
@@ -24,6 +25,11 @@ impl LeanRequestMessage {
             LeanRequestMessage::Status(_) => vec![ProtocolId::new(SupportedProtocol::Lean(
                 LeanSupportedProtocol::StatusV1,
             ))],
+            LeanRequestMessage::BlocksByRange(_) => {
+                vec![ProtocolId::new(SupportedProtocol::Lean(
+                    LeanSupportedProtocol::BlocksByRangeV1,
+                ))]
+            }
             LeanRequestMessage::BlocksByRoot(_) => {
                 vec![ProtocolId::new(SupportedProtocol::Lean(
                     LeanSupportedProtocol::BlocksByRootV1,
@@ -37,5 +43,6 @@ impl LeanRequestMessage {
 #[ssz(enum_behaviour = "transparent")]
 pub enum LeanResponseMessage {
     Status(LeanStatus),
+    BlocksByRange(Arc<SignedBlock>),
     BlocksByRoot(Arc<SignedBlock>),
 }
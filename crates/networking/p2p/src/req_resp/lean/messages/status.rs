@@ -1,8 +1,11 @@
 use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 pub struct LeanStatus {
+    /// The root of the genesis block, which peers on the same chain share
+    pub genesis_hash: B256,
     pub finalized_root: B256,
     pub finalized_slot: u64,
     pub head_root: B256,
@@ -1,5 +1,6 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LeanSupportedProtocol {
+    BlocksByRangeV1,
     BlocksByRootV1,
     StatusV1,
 }
@@ -7,6 +8,7 @@ pub enum LeanSupportedProtocol {
 impl LeanSupportedProtocol {
     pub fn message_name(&self) -> &str {
         match self {
+            LeanSupportedProtocol::BlocksByRangeV1 => "lean_blocks_by_range",
             LeanSupportedProtocol::BlocksByRootV1 => "lean_blocks_by_root",
             LeanSupportedProtocol::StatusV1 => "status",
         }
@@ -14,6 +16,7 @@ impl LeanSupportedProtocol {
 
     pub fn schema_version(&self) -> &str {
         match self {
+            LeanSupportedProtocol::BlocksByRangeV1 => "1",
             LeanSupportedProtocol::BlocksByRootV1 => "1",
             LeanSupportedProtocol::StatusV1 => "1",
         }
@@ -21,6 +24,7 @@ impl LeanSupportedProtocol {
 
     pub fn has_context_bytes(&self) -> bool {
         match self {
+            LeanSupportedProtocol::BlocksByRangeV1 => false,
             LeanSupportedProtocol::BlocksByRootV1 => false,
             LeanSupportedProtocol::StatusV1 => false,
         }
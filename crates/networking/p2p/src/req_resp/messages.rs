@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
+use ssz::Encode as _;
 use ssz_derive::{Decode, Encode};
 
 use super::{
     beacon::messages::{BeaconRequestMessage, BeaconResponseMessage},
     lean::messages::{LeanRequestMessage, LeanResponseMessage},
-    protocol_id::ProtocolId,
+    protocol_id::{ProtocolId, SupportedProtocol},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
@@ -22,6 +23,15 @@ impl RequestMessage {
             RequestMessage::Lean(request_message) => request_message.supported_protocols(),
         }
     }
+
+    pub fn as_ssz_bytes_for_protocol(&self, protocol: &SupportedProtocol) -> Vec<u8> {
+        match (self, protocol) {
+            (RequestMessage::Beacon(request_message), SupportedProtocol::Beacon(protocol)) => {
+                request_message.as_ssz_bytes_for_protocol(*protocol)
+            }
+            (message, _) => message.as_ssz_bytes(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
@@ -30,3 +40,21 @@ pub enum ResponseMessage {
     Beacon(Arc<BeaconResponseMessage>),
     Lean(Arc<LeanResponseMessage>),
 }
+
+impl ResponseMessage {
+    pub fn slot(&self) -> Option<u64> {
+        match self {
+            ResponseMessage::Beacon(response_message) => response_message.slot(),
+            ResponseMessage::Lean(_) => None,
+        }
+    }
+
+    pub fn as_ssz_bytes_for_protocol(&self, protocol: &SupportedProtocol) -> Vec<u8> {
+        match (self, protocol) {
+            (ResponseMessage::Beacon(response_message), SupportedProtocol::Beacon(protocol)) => {
+                response_message.as_ssz_bytes_for_protocol(*protocol)
+            }
+            (message, _) => message.as_ssz_bytes(),
+        }
+    }
+}
@@ -13,12 +13,15 @@ use futures::{
     prelude::{AsyncRead, AsyncWrite},
 };
 use libp2p::{OutboundUpgrade, bytes::Buf, core::UpgradeInfo};
-use ream_consensus_beacon::{blob_sidecar::BlobSidecar, electra::beacon_block::SignedBeaconBlock};
+use ream_consensus_beacon::{
+    blob_sidecar::BlobSidecar, data_column_sidecar::DataColumnSidecar,
+    electra::beacon_block::SignedBeaconBlock, versioned::VersionedSignedBeaconBlock,
+};
 use ream_consensus_lean::block::SignedBlock;
-use ream_consensus_misc::constants::beacon::genesis_validators_root;
+use ream_consensus_misc::{constants::beacon::genesis_validators_root, fork_name::ForkName};
 use ream_network_spec::networks::beacon_network_spec;
 use snap::{read::FrameDecoder, write::FrameEncoder};
-use ssz::{Decode, Encode};
+use ssz::Decode;
 use ssz_types::{VariableList, typenum::U256};
 use tokio_util::{
     codec::{Decoder, Encoder, Framed},
@@ -32,7 +35,10 @@ use crate::{
     req_resp::{
         beacon::{
             messages::{
-                BeaconResponseMessage, meta_data::GetMetaDataV2, ping::Ping, status::Status,
+                BeaconResponseMessage,
+                meta_data::{GetMetaDataV2, GetMetaDataV3},
+                ping::Ping,
+                status::{Status, StatusV1},
             },
             protocol_id::BeaconSupportedProtocol,
         },
@@ -66,15 +72,7 @@ where
     type Future = Pin<Box<dyn Future<Output = Result<Self::Output, Self::Error>> + Send>>;
 
     fn upgrade_outbound(self, socket: S, protocol: ProtocolId) -> Self::Future {
-        let mut socket = Framed::new(
-            socket.compat(),
-            OutboundSSZSnappyCodec {
-                protocol,
-                current_response_code: None,
-                context_bytes: None,
-                length: None,
-            },
-        );
+        let mut socket = Framed::new(socket.compat(), OutboundSSZSnappyCodec::new(protocol));
 
         async {
             socket.send(self.request).await?;
@@ -103,13 +101,24 @@ pub struct OutboundSSZSnappyCodec {
     length: Option<usize>,
 }
 
+impl OutboundSSZSnappyCodec {
+    pub fn new(protocol: ProtocolId) -> Self {
+        Self {
+            protocol,
+            current_response_code: None,
+            context_bytes: None,
+            length: None,
+        }
+    }
+}
+
 impl Encoder<RequestMessage> for OutboundSSZSnappyCodec {
     type Error = ReqRespError;
 
     fn encode(&mut self, item: RequestMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
         let bytes = match item {
             RequestMessage::Beacon(BeaconRequestMessage::MetaData(_)) => return Ok(()),
-            message => message.as_ssz_bytes(),
+            message => message.as_ssz_bytes_for_protocol(&self.protocol.protocol),
         };
 
         // The length-prefix is within the expected size bounds derived from the payload SSZ type or
@@ -157,13 +166,21 @@ impl Decoder for OutboundSSZSnappyCodec {
             src.advance(B32::len_bytes());
         }
 
-        if let Some(context_bytes) = self.context_bytes
-            && context_bytes != beacon_network_spec().fork_digest(genesis_validators_root())
-        {
-            return Ok(Some(RespMessage::Error(ReqRespError::InvalidData(
-                "Invalid context bytes, we only support Electra".to_string(),
-            ))));
-        }
+        let fork_name = match self.context_bytes {
+            Some(context_bytes) => {
+                match beacon_network_spec()
+                    .fork_name_from_digest(context_bytes, genesis_validators_root())
+                {
+                    Some(fork_name) => Some(fork_name),
+                    None => {
+                        return Ok(Some(RespMessage::Error(ReqRespError::InvalidData(
+                            format!("Context bytes {context_bytes} don't match any scheduled fork"),
+                        ))));
+                    }
+                }
+            }
+            None => None,
+        };
 
         let length = match self.length {
             Some(cached_length) => cached_length,
@@ -206,12 +223,26 @@ impl Decoder for OutboundSSZSnappyCodec {
                                 }
                                 BeaconSupportedProtocol::GetMetaDataV2 => {
                                     BeaconResponseMessage::MetaData(
-                                        GetMetaDataV2::from_ssz_bytes(&buf)
+                                        GetMetaDataV3::from(
+                                            GetMetaDataV2::from_ssz_bytes(&buf)
+                                                .map_err(ReqRespError::from)?,
+                                        )
+                                        .into(),
+                                    )
+                                }
+                                BeaconSupportedProtocol::GetMetaDataV3 => {
+                                    BeaconResponseMessage::MetaData(
+                                        GetMetaDataV3::from_ssz_bytes(&buf)
                                             .map_err(ReqRespError::from)?
                                             .into(),
                                     )
                                 }
                                 BeaconSupportedProtocol::StatusV1 => BeaconResponseMessage::Status(
+                                    StatusV1::from_ssz_bytes(&buf)
+                                        .map_err(ReqRespError::from)?
+                                        .into(),
+                                ),
+                                BeaconSupportedProtocol::StatusV2 => BeaconResponseMessage::Status(
                                     Status::from_ssz_bytes(&buf).map_err(ReqRespError::from)?,
                                 ),
                                 BeaconSupportedProtocol::PingV1 => BeaconResponseMessage::Ping(
@@ -219,13 +250,13 @@ impl Decoder for OutboundSSZSnappyCodec {
                                 ),
                                 BeaconSupportedProtocol::BeaconBlocksByRangeV2 => {
                                     BeaconResponseMessage::BeaconBlocksByRange(
-                                        SignedBeaconBlock::from_ssz_bytes(&buf)
+                                        decode_signed_block(&buf, fork_name)
                                             .map_err(ReqRespError::from)?,
                                     )
                                 }
                                 BeaconSupportedProtocol::BeaconBlocksByRootV2 => {
                                     BeaconResponseMessage::BeaconBlocksByRoot(
-                                        SignedBeaconBlock::from_ssz_bytes(&buf)
+                                        decode_signed_block(&buf, fork_name)
                                             .map_err(ReqRespError::from)?,
                                     )
                                 }
@@ -241,6 +272,18 @@ impl Decoder for OutboundSSZSnappyCodec {
                                             .map_err(ReqRespError::from)?,
                                     )
                                 }
+                                BeaconSupportedProtocol::DataColumnSidecarsByRangeV1 => {
+                                    BeaconResponseMessage::DataColumnSidecarsByRange(
+                                        DataColumnSidecar::from_ssz_bytes(&buf)
+                                            .map_err(ReqRespError::from)?,
+                                    )
+                                }
+                                BeaconSupportedProtocol::DataColumnSidecarsByRootV1 => {
+                                    BeaconResponseMessage::DataColumnSidecarsByRoot(
+                                        DataColumnSidecar::from_ssz_bytes(&buf)
+                                            .map_err(ReqRespError::from)?,
+                                    )
+                                }
                             };
                             Ok(Some(RespMessage::Response(Box::new(
                                 ResponseMessage::Beacon(response_message.into()),
@@ -251,6 +294,12 @@ impl Decoder for OutboundSSZSnappyCodec {
                                 LeanSupportedProtocol::StatusV1 => LeanResponseMessage::Status(
                                     LeanStatus::from_ssz_bytes(&buf).map_err(ReqRespError::from)?,
                                 ),
+                                LeanSupportedProtocol::BlocksByRangeV1 => {
+                                    LeanResponseMessage::BlocksByRange(Arc::new(
+                                        SignedBlock::from_ssz_bytes(&buf)
+                                            .map_err(ReqRespError::from)?,
+                                    ))
+                                }
                                 LeanSupportedProtocol::BlocksByRootV1 => {
                                     LeanResponseMessage::BlocksByRoot(Arc::new(
                                         SignedBlock::from_ssz_bytes(&buf)
@@ -296,3 +345,16 @@ impl Decoder for OutboundSSZSnappyCodec {
         result
     }
 }
+
+/// Decodes a block with the container of the fork its context bytes name, or of the fork active
+/// at its slot for protocols without context bytes.
+fn decode_signed_block(
+    bytes: &[u8],
+    fork_name: Option<ForkName>,
+) -> Result<SignedBeaconBlock, ssz::DecodeError> {
+    let versioned_block = match fork_name {
+        Some(fork_name) => VersionedSignedBeaconBlock::from_ssz_bytes_by_fork(bytes, fork_name)?,
+        None => VersionedSignedBeaconBlock::from_ssz_bytes(bytes)?,
+    };
+    Ok(versioned_block.into_inner())
+}
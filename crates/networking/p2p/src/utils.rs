@@ -1,8 +1,9 @@
 use std::cmp::max;
 
 use discv5::Enr;
+use ream_discv5::config::{QUIC_ENR_KEY, QUIC6_ENR_KEY};
 
-use crate::constants::{MAX_PAYLOAD_SIZE, QUIC_ENR_KEY};
+use crate::constants::MAX_PAYLOAD_SIZE;
 
 /// Worst-case compressed length for a given payload of size n when using snappy:
 /// https://github.com/google/snappy/blob/32ded457c0b1fe78ceb8397632c416568d6714a0/snappy.cc#L218C1-L218C47
@@ -20,3 +21,8 @@ pub fn max_message_size() -> u64 {
 pub fn quic_from_enr(enr: &Enr) -> Option<u16> {
     enr.get_decodable(QUIC_ENR_KEY).and_then(Result::ok)
 }
+
+/// The IPv6 QUIC port of ENR record if it is defined.
+pub fn quic6_from_enr(enr: &Enr) -> Option<u16> {
+    enr.get_decodable(QUIC6_ENR_KEY).and_then(Result::ok)
+}
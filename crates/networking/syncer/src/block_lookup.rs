@@ -0,0 +1,191 @@
+use std::{collections::HashSet, sync::Arc};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail, ensure};
+use libp2p::PeerId;
+use parking_lot::Mutex;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_beacon::{
+    blob_sidecar::BlobIdentifier, electra::beacon_block::SignedBeaconBlock,
+};
+use ream_executor::ReamExecutor;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_p2p::network::beacon::channel::P2PMessage;
+use ream_polynomial_commitments::handlers::verify_blob_sidecars;
+use ream_storage::tables::table::Table;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{info, warn};
+use tree_hash::TreeHash;
+
+use crate::block_range::peer_range_downloader::{
+    PeerBlobIdentifierDownloader, PeerRootsDownloader,
+};
+
+/// The most unknown ancestors looked up for a block. Longer gaps are left to the range sync.
+const MAX_LOOKUP_DEPTH: usize = 32;
+const MAX_CONCURRENT_LOOKUPS: usize = 8;
+
+/// Resolves the blocks which gossip messages build on or vote for but which we haven't seen, e.g.
+/// after missing a few gossip messages, by requesting them and their unknown ancestors by root
+/// from the peer which sent the message. The gossip blocks waiting on them are kept as orphan
+/// blocks by the beacon chain, which imports them once their parents are.
+#[derive(Clone)]
+pub struct BlockLookups {
+    beacon_chain: Arc<BeaconChain>,
+    p2p_sender: UnboundedSender<P2PMessage>,
+    executor: ReamExecutor,
+    /// The roots of the blocks being looked up
+    in_progress: Arc<Mutex<HashSet<B256>>>,
+}
+
+impl BlockLookups {
+    pub fn new(
+        beacon_chain: Arc<BeaconChain>,
+        p2p_sender: UnboundedSender<P2PMessage>,
+        executor: ReamExecutor,
+    ) -> Self {
+        Self {
+            beacon_chain,
+            p2p_sender,
+            executor,
+            in_progress: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Looks up the unknown block with `block_root` and its unknown ancestors, and imports them in
+    /// order.
+    pub fn lookup_block(&self, block_root: B256, peer_id: PeerId) {
+        {
+            let mut in_progress = self.in_progress.lock();
+            if in_progress.len() >= MAX_CONCURRENT_LOOKUPS || !in_progress.insert(block_root) {
+                return;
+            }
+        }
+
+        info!("Looking up unknown block {block_root} from peer {peer_id}");
+        let lookups = self.clone();
+        self.executor.spawn(async move {
+            if let Err(err) = lookups.lookup(block_root, peer_id).await {
+                warn!("Failed to look up block {block_root} from peer {peer_id}: {err:?}");
+            }
+            lookups.in_progress.lock().remove(&block_root);
+        });
+    }
+
+    async fn lookup(&self, block_root: B256, peer_id: PeerId) -> anyhow::Result<()> {
+        // Walk back from the unknown block until a block whose parent we know
+        let mut blocks = vec![];
+        let mut root = block_root;
+        while !self.is_known(root).await? {
+            ensure!(
+                blocks.len() < MAX_LOOKUP_DEPTH,
+                "More than {MAX_LOOKUP_DEPTH} unknown ancestors, leaving them to the range sync"
+            );
+            let block = self.download_block(root, peer_id).await?;
+            root = block.message.parent_root;
+            blocks.push(block);
+        }
+
+        for block in blocks.into_iter().rev() {
+            self.download_blobs(&block, peer_id).await?;
+            let slot = block.message.slot;
+            self.beacon_chain.process_block(block).await?;
+            info!("Imported looked up block at slot {slot}");
+        }
+        Ok(())
+    }
+
+    async fn is_known(&self, block_root: B256) -> anyhow::Result<bool> {
+        Ok(self
+            .beacon_chain
+            .store
+            .lock()
+            .await
+            .db
+            .beacon_block_provider()
+            .get(block_root)?
+            .is_some())
+    }
+
+    async fn download_block(
+        &self,
+        block_root: B256,
+        peer_id: PeerId,
+    ) -> anyhow::Result<SignedBeaconBlock> {
+        PeerRootsDownloader::start(
+            peer_id,
+            self.p2p_sender.clone(),
+            self.executor.clone(),
+            vec![block_root],
+        )
+        .await
+        .map_err(|err| anyhow!("Failed to join the block download: {err}"))???
+        .into_iter()
+        .find(|block| block.message.tree_hash_root() == block_root)
+        .ok_or_else(|| anyhow!("Peer {peer_id} didn't send block {block_root}"))
+    }
+
+    /// Downloads and stores the blobs of the block which we don't have yet, if the block is within
+    /// the blob retention window.
+    async fn download_blobs(
+        &self,
+        block: &SignedBeaconBlock,
+        peer_id: PeerId,
+    ) -> anyhow::Result<()> {
+        if block.message.slot < beacon_network_spec().slot_n_days_ago(17) {
+            return Ok(());
+        }
+
+        let block_root = block.message.tree_hash_root();
+        let mut missing_blob_identifiers = vec![];
+        {
+            let store = self.beacon_chain.store.lock().await;
+            for index in 0..block.message.body.blob_kzg_commitments.len() as u64 {
+                let blob_identifier = BlobIdentifier::new(block_root, index);
+                if store
+                    .db
+                    .blobs_and_proofs_provider()
+                    .get(blob_identifier)?
+                    .is_none()
+                {
+                    missing_blob_identifiers.push(blob_identifier);
+                }
+            }
+        }
+        if missing_blob_identifiers.is_empty() {
+            return Ok(());
+        }
+
+        let blob_sidecars = PeerBlobIdentifierDownloader::start(
+            peer_id,
+            self.p2p_sender.clone(),
+            self.executor.clone(),
+            missing_blob_identifiers.clone(),
+        )
+        .await
+        .map_err(|err| anyhow!("Failed to join the blob download: {err}"))???;
+        verify_blob_sidecars(&blob_sidecars)?;
+
+        let store = self.beacon_chain.store.lock().await;
+        for blob_sidecar in blob_sidecars {
+            let blob_identifier = BlobIdentifier::new(
+                blob_sidecar.signed_block_header.message.tree_hash_root(),
+                blob_sidecar.index,
+            );
+            if !missing_blob_identifiers.contains(&blob_identifier) {
+                bail!("Peer {peer_id} sent the unrequested blob sidecar {blob_identifier:?}");
+            }
+            missing_blob_identifiers.retain(|identifier| *identifier != blob_identifier);
+            store
+                .db
+                .blobs_and_proofs_provider()
+                .insert(blob_identifier, blob_sidecar.into())?;
+        }
+        ensure!(
+            missing_blob_identifiers.is_empty(),
+            "Peer {peer_id} didn't send {} blob sidecars of block {block_root}",
+            missing_blob_identifiers.len()
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
 
 use alloy_primitives::B256;
 use anyhow::{bail, ensure};
@@ -10,7 +13,11 @@ use ream_network_spec::networks::beacon_network_spec;
 use ssz::Encode;
 use tree_hash::TreeHash;
 
-use super::{MAX_BLOCKS_PER_REQUEST, peer_range_downloader::Range};
+use super::{MAX_BLOCKS_PER_REQUEST, MAX_RANGES_IN_FLIGHT, peer_range_downloader::Range};
+
+/// How long the downloaded blocks may not build on the imported chain before the segment is
+/// considered stalled
+const SEGMENT_STALL_TIMEOUT: Duration = Duration::from_secs(120);
 
 pub struct BlockAndBlobBundle {
     pub block: SignedBeaconBlock,
@@ -29,10 +36,18 @@ impl BlockAndBlobBundle {
 pub struct BlockCache {
     blocks_and_blobs: HashMap<B256, BlockAndBlobBundle>,
     current_cache_size: u64,
-    initial_parent_root: B256,
+    /// The roots of the blocks handed to the import worker which no other handed over block
+    /// builds on yet, one of which the next blocks to import build on
+    imported_tips: HashSet<B256>,
     block_ranges_to_retry: Vec<Range>,
-    initial_slot: u64,
+    block_ranges_in_progress: HashSet<Range>,
+    /// The slot of the highest block handed to the import worker
+    imported_slot: u64,
+    /// The last slot of the highest range whose download completed
+    completed_slot: u64,
     next_start_slot: u64,
+    /// When blocks were last handed to the import worker
+    last_import: Instant,
     block_roots_in_progress: HashSet<B256>,
     blob_identifiers_in_progress: HashSet<BlobIdentifier>,
 }
@@ -42,10 +57,13 @@ impl BlockCache {
         Self {
             blocks_and_blobs: HashMap::new(),
             current_cache_size: 0,
-            initial_parent_root,
+            imported_tips: HashSet::from([initial_parent_root]),
             block_ranges_to_retry: vec![],
-            initial_slot: next_start_slot,
+            block_ranges_in_progress: HashSet::new(),
+            imported_slot: next_start_slot,
+            completed_slot: next_start_slot,
             next_start_slot,
+            last_import: Instant::now(),
             block_roots_in_progress: HashSet::new(),
             blob_identifiers_in_progress: HashSet::new(),
         }
@@ -69,6 +87,10 @@ impl BlockCache {
         }
 
         for block in blocks {
+            // Blocks which were imported already, e.g. downloaded again by root, are dropped
+            if block.message.slot <= self.imported_slot {
+                continue;
+            }
             self.current_cache_size += block.as_ssz_bytes().len() as u64;
             self.blocks_and_blobs.insert(
                 block.message.tree_hash_root(),
@@ -99,6 +121,23 @@ impl BlockCache {
         Ok(())
     }
 
+    pub fn insert_block_range_in_progress(&mut self, range: Range) {
+        self.block_ranges_in_progress.insert(range);
+    }
+
+    pub fn remove_block_range_in_progress(&mut self, range: &Range) {
+        self.block_ranges_in_progress.remove(range);
+    }
+
+    /// Records that the blocks of `range` were downloaded, which may be none if all of its slots
+    /// are empty.
+    pub fn complete_block_range(&mut self, range: &Range) {
+        self.block_ranges_in_progress.remove(range);
+        self.completed_slot = self
+            .completed_slot
+            .max(range.start_slot + range.count.saturating_sub(1));
+    }
+
     pub fn extend_block_roots_in_progress(&mut self, block_roots: &[B256]) {
         self.block_roots_in_progress.extend(block_roots);
     }
@@ -137,14 +176,33 @@ impl BlockCache {
             .sum()
     }
 
+    pub fn imported_slot(&self) -> u64 {
+        self.imported_slot
+    }
+
+    /// The number of blocks to request in the next range, which is zero once
+    /// [MAX_RANGES_IN_FLIGHT] ranges are downloading, or the ranges requested reach that many
+    /// ranges ahead of both the imported blocks and the completed downloads. Completed downloads
+    /// move the window along runs of empty slots, where no block is imported.
     pub fn estimated_blocks_to_fetch(&self) -> u64 {
-        if self.next_start_slot.saturating_sub(self.initial_slot) > 30 {
+        let window_start = self.imported_slot.max(self.completed_slot);
+        if self.block_ranges_in_progress.len() as u64 >= MAX_RANGES_IN_FLIGHT
+            || self.next_start_slot.saturating_sub(window_start)
+                >= MAX_RANGES_IN_FLIGHT * MAX_BLOCKS_PER_REQUEST
+        {
             return 0;
         }
 
         MAX_BLOCKS_PER_REQUEST
     }
 
+    /// Whether downloaded blocks haven't built on the imported chain for
+    /// [SEGMENT_STALL_TIMEOUT], e.g. because the imported chain is on a fork the peers don't
+    /// serve. The segment has to be restarted from a block the peers agree on then.
+    pub fn is_stalled(&self) -> bool {
+        !self.blocks_and_blobs.is_empty() && self.last_import.elapsed() >= SEGMENT_STALL_TIMEOUT
+    }
+
     pub fn push_retry_range(&mut self, range: Range) {
         self.block_ranges_to_retry.push(range);
     }
@@ -154,14 +212,12 @@ impl BlockCache {
             Some(range) => return DataToFetch::BlockRange(range),
             None => {
                 let estimated_blocks_to_fetch = self.estimated_blocks_to_fetch();
-                if estimated_blocks_to_fetch > 0 && self.next_start_slot <= finalized_slot {
+                if estimated_blocks_to_fetch > 0 && self.next_start_slot < finalized_slot {
                     let blocks_to_fill = estimated_blocks_to_fetch
                         .min(MAX_BLOCKS_PER_REQUEST.min(finalized_slot - self.next_start_slot));
+                    let range = Range::new(self.next_start_slot + 1, blocks_to_fill);
                     self.next_start_slot += blocks_to_fill;
-                    return DataToFetch::BlockRange(Range::new(
-                        self.next_start_slot,
-                        blocks_to_fill,
-                    ));
+                    return DataToFetch::BlockRange(range);
                 }
             }
         }
@@ -183,27 +239,67 @@ impl BlockCache {
             return DataToFetch::MissingBlobIdentifiers(blob_identifiers_left_to_fetch);
         }
 
-        if missing_block_roots_len > 0 || missing_blob_identifiers_len > 0 {
+        if missing_block_roots_len > 0
+            || missing_blob_identifiers_len > 0
+            || !self.block_ranges_in_progress.is_empty()
+        {
+            return DataToFetch::DownloadsInProgress;
+        }
+
+        if self.next_start_slot < finalized_slot {
+            // The requested ranges are as far ahead of the imported blocks as allowed
             return DataToFetch::DownloadsInProgress;
         }
 
         DataToFetch::Finished
     }
 
-    /// Return the blocks in sorted order to be processed.
-    pub fn get_blocks_and_blobs(mut self) -> anyhow::Result<Vec<BlockAndBlobBundle>> {
-        let missing_block_roots = self.get_missing_block_roots();
-        if !missing_block_roots.is_empty() {
-            bail!("Missing block roots: {}", missing_block_roots.len());
-        } else {
-            let mut blocks_and_blobs = self
-                .blocks_and_blobs
-                .drain()
-                .map(|(_, block)| block)
-                .collect::<Vec<_>>();
-            blocks_and_blobs.sort_by_key(|block| block.block.message.slot);
-            Ok(blocks_and_blobs)
+    /// Removes the downloaded blocks which build on the imported blocks and returns them ordered
+    /// by slot to be imported, leaving out the blocks whose blobs haven't all been downloaded yet
+    /// and their descendants. Competing blocks building on the same parent are all returned.
+    pub fn pop_importable_blocks(&mut self) -> Vec<BlockAndBlobBundle> {
+        let slot_17_days_ago = beacon_network_spec().slot_n_days_ago(17);
+        let mut children: HashMap<B256, Vec<B256>> = HashMap::new();
+        for (block_root, bundle) in &self.blocks_and_blobs {
+            children
+                .entry(bundle.block.message.parent_root)
+                .or_default()
+                .push(*block_root);
+        }
+
+        let mut importable_blocks = vec![];
+        let mut parent_roots = self.imported_tips.iter().copied().collect::<Vec<_>>();
+        while let Some(parent_root) = parent_roots.pop() {
+            for block_root in children.remove(&parent_root).unwrap_or_default() {
+                let Some(bundle) = self.blocks_and_blobs.get(&block_root) else {
+                    continue;
+                };
+                let message = &bundle.block.message;
+                if message.slot >= slot_17_days_ago
+                    && bundle.blobs.len() < message.body.blob_kzg_commitments.len()
+                {
+                    continue;
+                }
+                let Some(bundle) = self.blocks_and_blobs.remove(&block_root) else {
+                    continue;
+                };
+                self.current_cache_size = self
+                    .current_cache_size
+                    .saturating_sub(bundle.block.as_ssz_bytes().len() as u64);
+                self.imported_tips.remove(&parent_root);
+                self.imported_tips.insert(block_root);
+                self.imported_slot = self.imported_slot.max(bundle.block.message.slot.as_u64());
+                parent_roots.push(block_root);
+                importable_blocks.push(bundle);
+            }
         }
+
+        // Blocks have higher slots than their parents, so parents are imported first
+        importable_blocks.sort_by_key(|bundle| bundle.block.message.slot);
+        if !importable_blocks.is_empty() {
+            self.last_import = Instant::now();
+        }
+        importable_blocks
     }
 
     fn get_missing_block_roots(&self) -> Vec<B256> {
@@ -212,7 +308,9 @@ impl BlockCache {
             if !self
                 .blocks_and_blobs
                 .contains_key(&block.block.message.parent_root)
-                && block.block.message.parent_root != self.initial_parent_root
+                && !self
+                    .imported_tips
+                    .contains(&block.block.message.parent_root)
             {
                 missing_roots.push(block.block.message.parent_root);
             }
@@ -268,3 +366,107 @@ impl std::fmt::Display for DataToFetch {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_beacon::electra::beacon_block::BeaconBlock;
+    use ream_consensus_misc::slot_epoch::Slot;
+    use ream_network_spec::networks::initialize_test_network_spec;
+
+    use super::*;
+
+    fn block(slot: u64, parent_root: B256) -> SignedBeaconBlock {
+        SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: Slot::new(slot),
+                parent_root,
+                ..Default::default()
+            },
+            signature: Default::default(),
+        }
+    }
+
+    fn slots(bundles: &[BlockAndBlobBundle]) -> Vec<u64> {
+        bundles
+            .iter()
+            .map(|bundle| bundle.block.message.slot.as_u64())
+            .collect()
+    }
+
+    #[test]
+    fn test_downloads_continue_over_empty_slots() {
+        initialize_test_network_spec();
+        let finalized_slot = 10 * MAX_RANGES_IN_FLIGHT * MAX_BLOCKS_PER_REQUEST;
+        let mut block_cache = BlockCache::new(B256::ZERO, 0);
+
+        let mut requested_ranges = vec![];
+        loop {
+            match block_cache.data_to_fetch(finalized_slot) {
+                DataToFetch::BlockRange(range) => {
+                    block_cache.insert_block_range_in_progress(range);
+                    requested_ranges.push(range);
+                }
+                DataToFetch::DownloadsInProgress => {
+                    // Every slot is empty, so the peers return no blocks and nothing is imported
+                    assert!(
+                        !requested_ranges.is_empty(),
+                        "The downloads stalled at slot {}",
+                        block_cache.next_start_slot
+                    );
+                    for range in requested_ranges.drain(..) {
+                        block_cache.add_blocks(vec![], true).unwrap();
+                        block_cache.complete_block_range(&range);
+                    }
+                }
+                DataToFetch::Finished => break,
+                data_to_fetch => panic!("Unexpected data to fetch: {data_to_fetch}"),
+            }
+        }
+
+        assert_eq!(block_cache.next_start_slot, finalized_slot);
+        assert_eq!(block_cache.imported_slot(), 0);
+    }
+
+    #[test]
+    fn test_ranges_in_flight_are_bounded() {
+        initialize_test_network_spec();
+        let mut block_cache = BlockCache::new(B256::ZERO, 0);
+
+        for _ in 0..MAX_RANGES_IN_FLIGHT {
+            let DataToFetch::BlockRange(range) = block_cache.data_to_fetch(1000) else {
+                panic!("Expected a block range to fetch");
+            };
+            block_cache.insert_block_range_in_progress(range);
+        }
+        assert_eq!(
+            block_cache.data_to_fetch(1000),
+            DataToFetch::DownloadsInProgress
+        );
+    }
+
+    #[test]
+    fn test_competing_blocks_are_all_imported() {
+        initialize_test_network_spec();
+        let mut block_cache = BlockCache::new(B256::ZERO, 0);
+        let first_child = block(1, B256::ZERO);
+        let second_child = block(2, B256::ZERO);
+        let first_child_root = first_child.message.tree_hash_root();
+        let second_child_root = second_child.message.tree_hash_root();
+        block_cache
+            .add_blocks(
+                vec![first_child, second_child, block(3, first_child_root)],
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(slots(&block_cache.pop_importable_blocks()), vec![1, 2, 3]);
+        assert_eq!(block_cache.block_count(), 0);
+
+        // Blocks building on either branch stay importable
+        block_cache
+            .add_blocks(vec![block(4, second_child_root)], false)
+            .unwrap();
+        assert_eq!(slots(&block_cache.pop_importable_blocks()), vec![4]);
+        assert_eq!(block_cache.imported_slot(), 4);
+    }
+}
@@ -1,12 +1,12 @@
 mod block_cache;
 mod peer_manager;
-mod peer_range_downloader;
+pub(crate) mod peer_range_downloader;
 
 use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use alloy_primitives::B256;
@@ -26,21 +26,38 @@ use ream_p2p::{
     network::beacon::{channel::P2PMessage, network_state::NetworkState},
     req_resp::MAX_CONCURRENT_REQUESTS,
 };
-use ream_storage::tables::table::Table;
-use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle, time::sleep};
-use tracing::{info, warn};
+use ream_polynomial_commitments::handlers::verify_blob_sidecars;
+use ream_storage::tables::{field::Field, table::Table};
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+    time::sleep,
+};
+use tracing::{debug, info, warn};
+use tree_hash::TreeHash;
 
-use crate::block_range::peer_range_downloader::{PeerRangeDownloader, Range};
+use crate::{
+    block_range::peer_range_downloader::{PeerRangeDownloader, Range},
+    sync_progress::{SyncProgress, SyncProgressSnapshot},
+};
 
 const MAX_BLOBS_PER_REQUEST: usize = 6;
 const MAX_BLOCKS_PER_REQUEST: u64 = 10;
+/// The number of block ranges downloaded at once ahead of the imported blocks
+const MAX_RANGES_IN_FLIGHT: u64 = 4;
 const SLEEP_DURATION: Duration = Duration::from_secs(5);
+/// How often the syncer checks on the downloads in progress while it can't request more
+const DOWNLOADS_IN_PROGRESS_SLEEP_DURATION: Duration = Duration::from_secs(1);
+const SYNC_PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(10);
 
 pub struct BlockRangeSyncer {
     pub beacon_chain: Arc<BeaconChain>,
     pub peer_manager: PeerManager,
     pub p2p_sender: UnboundedSender<P2PMessage>,
     pub executor: ReamExecutor,
+    pub sync_progress: Arc<SyncProgress>,
+    /// The slot to resync from on the next start instead of the highest synced slot
+    start_slot: Option<u64>,
 }
 
 impl BlockRangeSyncer {
@@ -49,15 +66,25 @@ impl BlockRangeSyncer {
         p2p_sender: UnboundedSender<P2PMessage>,
         network_state: Arc<NetworkState>,
         executor: ReamExecutor,
+        sync_progress: Arc<SyncProgress>,
     ) -> Self {
         Self {
             beacon_chain,
+            peer_manager: PeerManager::new(network_state, p2p_sender.clone()),
             p2p_sender,
-            peer_manager: PeerManager::new(network_state),
             executor,
+            sync_progress,
+            start_slot: None,
         }
     }
 
+    /// Resyncs from the block at `start_slot` on the next start, e.g. to recover from a bad
+    /// range of blocks.
+    pub fn with_start_slot(mut self, start_slot: u64) -> Self {
+        self.start_slot = Some(start_slot);
+        self
+    }
+
     pub async fn is_synced_to_finalized_slot(&self) -> bool {
         let finalized_slot = self.peer_manager.finalized_slot();
         let latest_synced_slot = self
@@ -77,39 +104,96 @@ impl BlockRangeSyncer {
     pub fn start(mut self) -> JoinHandle<anyhow::Result<anyhow::Result<BlockRangeSyncer>>> {
         let executor = self.executor.clone();
         executor.spawn(async move {
-            let Some(latest_synced_root) = self
-                .beacon_chain
-                .store
-                .lock()
-                .await
-                .db
-                .slot_index_provider()
-                .get_highest_root()
-                .map_err(|err| anyhow!("Failed to get highest root: {err}"))?
-            else {
-                bail!("No synced root found in the database");
-            };
-
-            let Some(latest_synced_slot) = self
-                .beacon_chain
-                .store
-                .lock()
-                .await
-                .db
-                .slot_index_provider()
-                .get_highest_slot()
-                .map_err(|err| anyhow!("Failed to get highest slot: {err}"))?
-            else {
-                bail!("No synced slot found in the database");
+            let (latest_synced_root, latest_synced_slot) = match self.start_slot.take() {
+                Some(start_slot) => {
+                    let Some(start_root) = self
+                        .beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .db
+                        .slot_index_provider()
+                        .get(start_slot)
+                        .map_err(|err| anyhow!("Failed to get root at slot {start_slot}: {err}"))?
+                    else {
+                        bail!("No block found at slot {start_slot} to resync from");
+                    };
+                    info!("Resyncing from slot {start_slot}");
+                    (start_root, start_slot)
+                }
+                None => {
+                    let Some(latest_synced_root) = self
+                        .beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .db
+                        .slot_index_provider()
+                        .get_highest_root()
+                        .map_err(|err| anyhow!("Failed to get highest root: {err}"))?
+                    else {
+                        bail!("No synced root found in the database");
+                    };
+
+                    let Some(latest_synced_slot) = self
+                        .beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .db
+                        .slot_index_provider()
+                        .get_highest_slot()
+                        .map_err(|err| anyhow!("Failed to get highest slot: {err}"))?
+                    else {
+                        bail!("No synced slot found in the database");
+                    };
+                    (latest_synced_root, latest_synced_slot)
+                }
             };
 
-            // phase 1: download majority of blocks from ranges
-            let mut block_cache =
-                BlockCache::new(latest_synced_root, latest_synced_slot);
+            // Download the ranges concurrently while the import worker imports the blocks which
+            // build on the imported chain as soon as they and their blobs are downloaded
+            let mut block_cache = BlockCache::new(latest_synced_root, latest_synced_slot);
+            self.sync_progress.start(latest_synced_slot);
+            let (import_sender, import_handle) = spawn_import_worker(
+                self.beacon_chain.clone(),
+                self.sync_progress.clone(),
+                &self.executor,
+            );
             let mut task_handles = vec![];
+            let mut last_progress_log = Instant::now();
             loop {
                 poll_ready_tasks(&mut task_handles, &mut block_cache, &mut self.peer_manager)?;
 
+                let importable_blocks = block_cache.pop_importable_blocks();
+                if !importable_blocks.is_empty() && import_sender.send(importable_blocks).is_err() {
+                    join_import_worker(import_handle).await?;
+                    bail!("The block import worker stopped before the blocks were synced");
+                }
+
+                if block_cache.is_stalled() {
+                    // The downloaded blocks don't build on the imported chain, so download the
+                    // chain again from the finalized block, which the peers agree on
+                    let finalized_checkpoint = self
+                        .beacon_chain
+                        .store
+                        .lock()
+                        .await
+                        .db
+                        .finalized_checkpoint_provider()
+                        .get()
+                        .map_err(|err| anyhow!("Failed to get finalized checkpoint: {err}"))?;
+                    warn!(
+                        "{} downloaded blocks don't build on the imported chain, restarting from the finalized checkpoint at epoch {}",
+                        block_cache.block_count(),
+                        finalized_checkpoint.epoch,
+                    );
+                    block_cache = BlockCache::new(
+                        finalized_checkpoint.root,
+                        finalized_checkpoint.epoch.start_slot().as_u64(),
+                    );
+                }
+
                 let finalized_slot = match self.peer_manager.finalized_slot() {
                     Some(finalized_slot) => finalized_slot,
                     None => {
@@ -120,8 +204,14 @@ impl BlockRangeSyncer {
                     }
                 };
 
+                self.sync_progress.set_target_slot(finalized_slot);
+                if last_progress_log.elapsed() >= SYNC_PROGRESS_LOG_INTERVAL {
+                    log_sync_progress(self.sync_progress.snapshot());
+                    last_progress_log = Instant::now();
+                }
+
                 let data_to_fetch = block_cache.data_to_fetch(finalized_slot);
-                info!(
+                debug!(
                     "Forward sync status: Downloaded Blocks {}, Downloaded Blobs {}/{}, Stage {data_to_fetch}",
                     block_cache.block_count(),
                     block_cache.downloaded_blob_count(),
@@ -130,13 +220,15 @@ impl BlockRangeSyncer {
 
                 match data_to_fetch {
                     DataToFetch::BlockRange(range) => {
-                        let Some(peer) = self.peer_manager.fetch_idle_peer() else {
+                        let Some(peer) = self.peer_manager.fetch_idle_peer_serving_slot(range.start_slot) else {
+                            block_cache.push_retry_range(range);
                             self.peer_manager.update_peer_set();
-                            info!("No idle peers available for block range sync.");
+                            info!("No idle peers available for block range sync from slot {}.", range.start_slot);
                             sleep(SLEEP_DURATION).await;
                             continue;
                         };
 
+                        block_cache.insert_block_range_in_progress(range);
                         task_handles.push(DownloadTask::new_block_range(
                             PeerRangeDownloader::start(
                                 peer.peer_id,
@@ -195,25 +287,52 @@ impl BlockRangeSyncer {
                     }
                     DataToFetch::DownloadsInProgress => {
                         info!("Waiting for ongoing downloads to complete... {}", self.peer_manager.peer_counts());
-                        sleep(Duration::from_secs(10)).await;
+                        sleep(DOWNLOADS_IN_PROGRESS_SLEEP_DURATION).await;
                     }
                     DataToFetch::Finished => break,
                 }
             }
 
-            info!("Block range sync completed a segment successfully with {} blocks and {} blobs.",
-                block_cache.block_count(),
-                block_cache.downloaded_blob_count(),
-            );
-
-            // execute all the blocks downloaded
-            for BlockAndBlobBundle { block, blobs } in block_cache.get_blocks_and_blobs()?  {
-                info!("Processing block with slot {}",
-                    block.message.slot,
+            drop(import_sender);
+            join_import_worker(import_handle).await?;
+            self.sync_progress.finish();
+            if block_cache.block_count() > 0 {
+                warn!(
+                    "{} downloaded blocks don't build on the imported chain",
+                    block_cache.block_count()
                 );
+            }
+
+            info!("Block range sync imported the blocks up to slot {}.", block_cache.imported_slot());
+
+            Ok(self)
+        })
+    }
+}
+
+/// Imports the segments of downloaded blocks in the order they are sent, writing each segment to
+/// disk together.
+fn spawn_import_worker(
+    beacon_chain: Arc<BeaconChain>,
+    sync_progress: Arc<SyncProgress>,
+    executor: &ReamExecutor,
+) -> (
+    UnboundedSender<Vec<BlockAndBlobBundle>>,
+    JoinHandle<anyhow::Result<anyhow::Result<()>>>,
+) {
+    let (import_sender, mut import_receiver) = mpsc::unbounded_channel::<Vec<BlockAndBlobBundle>>();
+    let import_handle = executor.spawn(async move {
+        while let Some(segment) = import_receiver.recv().await {
+            let write_batch = beacon_chain.store.lock().await.db.write_batch();
+            let segment_slot = segment
+                .last()
+                .map(|bundle| bundle.block.message.slot.as_u64())
+                .unwrap_or_default();
+            let mut blocks = Vec::with_capacity(segment.len());
+            for BlockAndBlobBundle { block, blobs } in segment {
+                info!("Processing block with slot {}", block.message.slot);
                 for (blob_identifier, blob_sidecar) in blobs {
-                    if let Err(err) = self
-                        .beacon_chain
+                    if let Err(err) = beacon_chain
                         .store
                         .lock()
                         .await
@@ -224,15 +343,57 @@ impl BlockRangeSyncer {
                         warn!("Failed to insert blob into database: {err}");
                     }
                 }
+                blocks.push(block);
+            }
 
-                self.beacon_chain.process_block(block).await?;
+            // Competing blocks don't build on each other, so their signatures are verified on
+            // import instead of in a batch
+            let is_chain = blocks
+                .windows(2)
+                .all(|pair| pair[1].message.parent_root == pair[0].message.tree_hash_root());
+            if is_chain {
+                beacon_chain.process_block_segment(blocks).await?;
+            } else {
+                for block in blocks {
+                    beacon_chain.process_block(block).await?;
+                }
             }
+            write_batch.flush()?;
+            sync_progress.set_current_slot(segment_slot);
+        }
+        Ok(())
+    });
+    (import_sender, import_handle)
+}
 
-            info!("All blocks processed successfully.");
+fn log_sync_progress(progress: SyncProgressSnapshot) {
+    let eta = match progress.eta() {
+        Some(eta) => {
+            let seconds = eta.as_secs();
+            format!(
+                "{}h {}m {}s",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        }
+        None => "unknown".to_string(),
+    };
+    info!(
+        "Synced {}/{} slots, {:.2} slots/s, ETA {eta}",
+        progress.current_slot.saturating_sub(progress.start_slot),
+        progress.target_slot.saturating_sub(progress.start_slot),
+        progress.slots_per_second,
+    );
+}
 
-            Ok(self)
-        })
-    }
+/// Waits for the import worker to import the segments sent to it.
+async fn join_import_worker(
+    import_handle: JoinHandle<anyhow::Result<anyhow::Result<()>>>,
+) -> anyhow::Result<()> {
+    import_handle
+        .await
+        .map_err(|err| anyhow!("Failed to join the block import worker: {err}"))??
 }
 
 pub enum DownloadTask {
@@ -321,6 +482,7 @@ fn poll_ready_tasks(
                             Ok(blocks) => blocks,
                             Err(err) => {
                                 warn!("Failed to fetch blocks from peer: {err:?}");
+                                block_cache.remove_block_range_in_progress(range);
                                 block_cache.push_retry_range(*range);
                                 continue;
                             }
@@ -329,6 +491,7 @@ fn poll_ready_tasks(
                         let blocks = match blocks {
                             Ok(blocks) => blocks,
                             Err(err) => {
+                                block_cache.remove_block_range_in_progress(range);
                                 block_cache.push_retry_range(*range);
                                 peer_manager
                                     .ban_peer(peer_id, format!("Failed to fetch blocks: {err:?}"));
@@ -336,22 +499,25 @@ fn poll_ready_tasks(
                             }
                         };
 
+                        // A range of empty slots has no blocks, and a block missing from a range
+                        // is still found by root from the parent root of the block after it
                         if blocks.is_empty() {
-                            warn!("Received empty block range from peer: {peer_id}");
-                            block_cache.push_retry_range(*range);
-                            peer_manager
-                                .ban_peer(peer_id, "Received empty block range".to_string());
-                            continue;
+                            debug!("Received empty block range {range:?} from peer: {peer_id}");
                         }
 
                         if let Err(err) = block_cache.add_blocks(blocks, true) {
                             warn!("Failed to add downloaded blocks to cache: {err:?}");
+                            block_cache.remove_block_range_in_progress(range);
                             block_cache.push_retry_range(*range);
+                            continue;
                         }
+                        block_cache.complete_block_range(range);
                     }
                     Poll::Ready(Err(err)) => {
                         warn!("Forward fill task failed: {err}");
                         indexes_to_remove.push(index);
+                        block_cache.remove_block_range_in_progress(range);
+                        block_cache.push_retry_range(*range);
                     }
                     Poll::Pending => {}
                 }
@@ -445,6 +611,30 @@ fn poll_ready_tasks(
                             continue;
                         }
 
+                        if let Some(blob_sidecar) = blob_sidecars.iter().find(|blob_sidecar| {
+                            !blob_identifiers.contains(&BlobIdentifier::new(
+                                blob_sidecar.signed_block_header.message.tree_hash_root(),
+                                blob_sidecar.index,
+                            ))
+                        }) {
+                            warn!(
+                                "Received unrequested blob sidecar with index {} from peer: {peer_id}",
+                                blob_sidecar.index
+                            );
+                            peer_manager
+                                .ban_peer(peer_id, "Received unrequested blob sidecar".to_string());
+                            continue;
+                        }
+
+                        if let Err(err) = verify_blob_sidecars(&blob_sidecars) {
+                            warn!("Received invalid blob sidecars from peer {peer_id}: {err:?}");
+                            peer_manager.ban_peer(
+                                peer_id,
+                                format!("Received invalid blob sidecars: {err:?}"),
+                            );
+                            continue;
+                        }
+
                         if let Err(err) = block_cache.add_blobs(blob_sidecars) {
                             warn!("Failed to add downloaded blobs to cache: {err:?}");
                         }
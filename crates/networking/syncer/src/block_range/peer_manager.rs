@@ -2,7 +2,11 @@ use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use libp2p::PeerId;
 use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
-use ream_p2p::network::beacon::{network_state::NetworkState, peer::CachedPeer};
+use ream_p2p::{
+    network::beacon::{channel::P2PMessage, network_state::NetworkState, peer::CachedPeer},
+    req_resp::beacon::messages::goodbye::Goodbye,
+};
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::warn;
 
 #[derive(Debug, Clone)]
@@ -19,15 +23,17 @@ pub struct PeerInfo {
 
 pub struct PeerManager {
     network_state: Arc<NetworkState>,
+    p2p_sender: UnboundedSender<P2PMessage>,
     peers: HashMap<PeerId, PeerInfo>,
     banned_peers: HashMap<PeerId, Instant>,
     ban_reasons: HashMap<PeerId, String>,
 }
 
 impl PeerManager {
-    pub fn new(network_state: Arc<NetworkState>) -> Self {
+    pub fn new(network_state: Arc<NetworkState>, p2p_sender: UnboundedSender<P2PMessage>) -> Self {
         Self {
             network_state,
+            p2p_sender,
             peers: HashMap::new(),
             banned_peers: HashMap::new(),
             ban_reasons: HashMap::new(),
@@ -52,8 +58,15 @@ impl PeerManager {
             .retain(|peer_id, _| connected_peers.iter().any(|peer| peer.peer_id == *peer_id));
     }
 
-    /// Bans a peer
+    /// Bans a peer, which is told it misbehaved and disconnected
     pub fn ban_peer(&mut self, peer_id: &PeerId, reason: String) {
+        warn!("Banning peer {peer_id} from syncing: {reason}");
+        if let Err(err) = self.p2p_sender.send(P2PMessage::DisconnectPeer {
+            peer_id: *peer_id,
+            reason: Goodbye::FaultOrError,
+        }) {
+            warn!("Failed to send disconnect peer message: {err}");
+        }
         self.ban_reasons.insert(*peer_id, reason);
         if let Some(peer_info) = self.peers.remove(peer_id) {
             self.banned_peers
@@ -76,6 +89,27 @@ impl PeerManager {
         None
     }
 
+    /// Fetches an idle peer that is able to serve blocks from `start_slot` onwards, based on the
+    /// `earliest_available_slot` from its status.
+    ///
+    /// Will set the peer status to `Downloading` if an idle peer is found.
+    pub fn fetch_idle_peer_serving_slot(&mut self, start_slot: u64) -> Option<CachedPeer> {
+        for peer_info in self.peers.values_mut() {
+            let can_serve_slot = peer_info
+                .peer
+                .status
+                .as_ref()
+                .is_some_and(|status| status.earliest_available_slot <= start_slot);
+            if let PeerStatus::Idle = peer_info.peer_status
+                && can_serve_slot
+            {
+                peer_info.peer_status = PeerStatus::Downloading;
+                return Some(peer_info.peer.clone());
+            }
+        }
+        None
+    }
+
     pub fn peer_counts(&self) -> String {
         let total_peers = self.peers.len();
         let idle_peers = self
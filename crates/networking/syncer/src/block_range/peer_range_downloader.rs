@@ -17,7 +17,7 @@ use tokio::{
 };
 use tracing::info;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Range {
     pub start_slot: u64,
     pub count: u64,
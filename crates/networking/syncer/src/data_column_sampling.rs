@@ -0,0 +1,297 @@
+use std::{collections::HashMap, sync::Arc};
+
+use alloy_primitives::{B256, U256};
+use anyhow::{anyhow, bail};
+use libp2p::PeerId;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_beacon::{
+    custody::get_custody_columns,
+    data_column_sidecar::{DataColumnSidecar, DataColumnsByRootIdentifier},
+    electra::beacon_block::SignedBeaconBlock,
+};
+use ream_consensus_misc::{
+    constants::beacon::{NUMBER_OF_COLUMNS, SAMPLES_PER_SLOT},
+    fork_name::ForkName,
+};
+use ream_executor::ReamExecutor;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_p2p::{
+    network::beacon::{
+        channel::{P2PCallbackResponse, P2PMessage, P2PRequest},
+        network_state::NetworkState,
+    },
+    req_resp::beacon::messages::BeaconResponseMessage,
+};
+#[cfg(feature = "peerdas")]
+use ream_polynomial_commitments::handlers::blob_to_kzg_commitment;
+use ream_polynomial_commitments::handlers::verify_data_column_sidecar_kzg_proofs;
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+};
+use tracing::{error, info, warn};
+
+pub struct PeerDataColumnDownloader;
+
+impl PeerDataColumnDownloader {
+    pub fn start(
+        peer_id: PeerId,
+        p2p_sender: UnboundedSender<P2PMessage>,
+        executor: ReamExecutor,
+        identifiers: Vec<DataColumnsByRootIdentifier>,
+    ) -> JoinHandle<anyhow::Result<anyhow::Result<Vec<DataColumnSidecar>>>> {
+        executor.spawn(async move {
+            let mut data_column_sidecars = vec![];
+            let (callback, mut rx) = mpsc::channel(100);
+            p2p_sender
+                .send(P2PMessage::Request(P2PRequest::DataColumnIdentifiers {
+                    peer_id,
+                    identifiers,
+                    callback,
+                }))
+                .expect("Failed to send data column identifiers request");
+
+            while let Some(response) = rx.recv().await {
+                match response {
+                    Ok(P2PCallbackResponse::ResponseMessage(message)) => {
+                        if let BeaconResponseMessage::DataColumnSidecarsByRoot(
+                            data_column_sidecar,
+                        ) = message.as_ref().clone()
+                        {
+                            info!(
+                                "Received data column sidecar response with index {}",
+                                data_column_sidecar.index
+                            );
+                            data_column_sidecars.push(data_column_sidecar);
+                        }
+                    }
+                    Ok(P2PCallbackResponse::EndOfStream) => {
+                        info!("End of data column roots request stream received.");
+                        break;
+                    }
+                    Ok(P2PCallbackResponse::Disconnected) => {
+                        bail!("Peer disconnected while receiving data column sidecars.");
+                    }
+                    Ok(P2PCallbackResponse::Timeout) => {
+                        bail!("Data column identifiers request timed out.");
+                    }
+                    Err(err) => {
+                        info!(
+                            "Error receiving data columns from data column roots request: {err:?}"
+                        );
+                    }
+                }
+            }
+
+            Ok(data_column_sidecars)
+        })
+    }
+}
+
+/// Samples the data columns of blocks from connected peers, so the availability of a block's blobs
+/// can be checked without downloading all of them.
+#[derive(Clone)]
+pub struct DataColumnSampler {
+    beacon_chain: Arc<BeaconChain>,
+    network_state: Arc<NetworkState>,
+    p2p_sender: UnboundedSender<P2PMessage>,
+    executor: ReamExecutor,
+    custody_columns: Arc<Vec<u64>>,
+}
+
+impl DataColumnSampler {
+    pub fn new(
+        beacon_chain: Arc<BeaconChain>,
+        network_state: Arc<NetworkState>,
+        p2p_sender: UnboundedSender<P2PMessage>,
+        executor: ReamExecutor,
+    ) -> anyhow::Result<Self> {
+        let node_id = U256::from_be_bytes(network_state.local_enr.read().node_id().raw());
+        let custody_group_count = network_state.meta_data.read().custody_group_count;
+        let custody_columns = get_custody_columns(node_id, custody_group_count)?;
+        Ok(Self {
+            beacon_chain,
+            network_state,
+            p2p_sender,
+            executor,
+            custody_columns: Arc::new(custody_columns),
+        })
+    }
+
+    /// The columns this node is responsible for custodying.
+    pub fn custody_columns(&self) -> &[u64] {
+        &self.custody_columns
+    }
+
+    /// Whether the availability of the block's data is checked by sampling its columns, which is
+    /// the case for blocks with blobs from Fulu on.
+    pub fn should_sample(signed_block: &SignedBeaconBlock) -> bool {
+        !signed_block.message.body.blob_kzg_commitments.is_empty()
+            && beacon_network_spec().fork_name_at_slot(signed_block.message.slot.as_u64())
+                >= ForkName::Fulu
+    }
+
+    /// Samples the columns of the block in the background, and imports the block once they have
+    /// all been received. Blobs the execution engine already has make sampling unnecessary. A
+    /// block whose sampling fails waits for its blobs like any other block.
+    pub fn sample_and_import(&self, signed_block: SignedBeaconBlock) {
+        let sampler = self.clone();
+        self.executor.spawn(async move {
+            let block_root = signed_block.message.block_root();
+            #[cfg(feature = "peerdas")]
+            let fetched = sampler
+                .fetch_blobs_from_execution_engine(&signed_block)
+                .await
+                .unwrap_or_else(|err| {
+                    warn!("Failed to fetch the blobs of block {block_root}: {err:?}");
+                    false
+                });
+            #[cfg(not(feature = "peerdas"))]
+            let fetched = false;
+            let sampled = fetched
+                || sampler.sample(block_root).await.unwrap_or_else(|err| {
+                    warn!("Failed to sample the data columns of block {block_root}: {err:?}");
+                    false
+                });
+            let result = if sampled {
+                sampler
+                    .beacon_chain
+                    .process_sampled_block(signed_block)
+                    .await
+            } else {
+                warn!("Block {block_root} couldn't be sampled, waiting for its blobs instead");
+                sampler
+                    .beacon_chain
+                    .process_block_when_available(signed_block)
+                    .await
+            };
+            if let Err(err) = result {
+                error!("Failed to process sampled block {block_root}: {err:?}");
+            }
+        });
+    }
+
+    /// Fetch the block's blobs with `engine_getBlobsV2` and return whether the execution engine
+    /// had all of them, each matching its commitment in the block.
+    #[cfg(feature = "peerdas")]
+    pub async fn fetch_blobs_from_execution_engine(
+        &self,
+        signed_block: &SignedBeaconBlock,
+    ) -> anyhow::Result<bool> {
+        let Some(execution_engine) = &self.beacon_chain.execution_engine else {
+            return Ok(false);
+        };
+        let blob_kzg_commitments = &signed_block.message.body.blob_kzg_commitments;
+        let blob_versioned_hashes = blob_kzg_commitments
+            .iter()
+            .map(|commitment| commitment.calculate_versioned_hash())
+            .collect::<Vec<_>>();
+        let Some(blobs_and_proofs) = execution_engine
+            .engine_get_blobs_v2(blob_versioned_hashes)
+            .await?
+        else {
+            return Ok(false);
+        };
+        if blobs_and_proofs.len() != blob_kzg_commitments.len() {
+            return Ok(false);
+        }
+
+        for (blob_and_proof, commitment) in blobs_and_proofs.iter().zip(blob_kzg_commitments.iter())
+        {
+            if blob_to_kzg_commitment(&blob_and_proof.blob)? != *commitment {
+                warn!(
+                    "Execution engine returned a blob not matching commitment {}",
+                    commitment.calculate_versioned_hash()
+                );
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Pick `SAMPLES_PER_SLOT` distinct columns at random.
+    pub fn select_sample_columns() -> Vec<u64> {
+        let mut columns = rand::seq::index::sample(
+            &mut rand::rng(),
+            NUMBER_OF_COLUMNS as usize,
+            SAMPLES_PER_SLOT as usize,
+        )
+        .into_iter()
+        .map(|column| column as u64)
+        .collect::<Vec<_>>();
+        columns.sort_unstable();
+        columns
+    }
+
+    /// Request the sampled columns of `block_root` from the connected peers custodying them and
+    /// return whether every sampled column was received and verified.
+    pub async fn sample(&self, block_root: B256) -> anyhow::Result<bool> {
+        let sample_columns = Self::select_sample_columns();
+
+        let mut columns_by_peer: HashMap<PeerId, Vec<u64>> = HashMap::new();
+        let peers = self
+            .network_state
+            .connected_peers()
+            .into_iter()
+            .filter_map(|peer| {
+                let node_id = U256::from_be_bytes(peer.enr.as_ref()?.node_id().raw());
+                let custody_columns =
+                    get_custody_columns(node_id, peer.custody_group_count()).ok()?;
+                Some((peer.peer_id, custody_columns))
+            })
+            .collect::<Vec<_>>();
+        for column in &sample_columns {
+            let Some((peer_id, _)) = peers
+                .iter()
+                .find(|(_, custody_columns)| custody_columns.contains(column))
+            else {
+                warn!("No connected peer custodies column {column} of block {block_root}");
+                return Ok(false);
+            };
+            columns_by_peer.entry(*peer_id).or_default().push(*column);
+        }
+
+        let mut task_handles = vec![];
+        for (peer_id, columns) in columns_by_peer {
+            task_handles.push(PeerDataColumnDownloader::start(
+                peer_id,
+                self.p2p_sender.clone(),
+                self.executor.clone(),
+                vec![DataColumnsByRootIdentifier::new(block_root, columns)?],
+            ));
+        }
+
+        let mut received_columns = vec![];
+        for task_handle in task_handles {
+            let data_column_sidecars = match task_handle.await {
+                Ok(Ok(Ok(data_column_sidecars))) => data_column_sidecars,
+                Ok(Ok(Err(err))) => {
+                    warn!("Failed to sample data columns of block {block_root}: {err:?}");
+                    continue;
+                }
+                Ok(Err(err)) => return Err(err),
+                Err(err) => return Err(anyhow!("Data column download task failed: {err:?}")),
+            };
+
+            for data_column_sidecar in data_column_sidecars {
+                if data_column_sidecar.block_root() != block_root
+                    || !data_column_sidecar.verify_data_column_sidecar()
+                    || !data_column_sidecar.verify_data_column_sidecar_inclusion_proof()
+                    || !verify_data_column_sidecar_kzg_proofs(&data_column_sidecar).unwrap_or(false)
+                {
+                    warn!(
+                        "Received invalid data column sidecar {} for block {block_root}",
+                        data_column_sidecar.index
+                    );
+                    continue;
+                }
+                received_columns.push(data_column_sidecar.index);
+            }
+        }
+
+        Ok(sample_columns
+            .iter()
+            .all(|column| received_columns.contains(column)))
+    }
+}
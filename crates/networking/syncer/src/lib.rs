@@ -1 +1,4 @@
+pub mod block_lookup;
 pub mod block_range;
+pub mod data_column_sampling;
+pub mod sync_progress;
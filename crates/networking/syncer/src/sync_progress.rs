@@ -0,0 +1,111 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use parking_lot::RwLock;
+
+/// The time over which the sync speed is averaged
+const SYNC_SPEED_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default)]
+struct SyncProgressState {
+    is_syncing: bool,
+    start_slot: u64,
+    current_slot: u64,
+    target_slot: u64,
+    /// When the slots in the speed window were imported, the oldest first
+    samples: VecDeque<(Instant, u64)>,
+}
+
+/// The progress of the block range sync, which the syncing endpoint and the sync log report.
+#[derive(Debug, Default)]
+pub struct SyncProgress {
+    state: RwLock<SyncProgressState>,
+}
+
+/// A snapshot of the [SyncProgress].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncProgressSnapshot {
+    pub is_syncing: bool,
+    /// The slot of the latest block imported when the sync started
+    pub start_slot: u64,
+    /// The slot of the latest block imported by the sync
+    pub current_slot: u64,
+    /// The finalized slot of the peers, which the sync imports the blocks up to
+    pub target_slot: u64,
+    /// The imported slots per second over the last minute
+    pub slots_per_second: f64,
+}
+
+impl SyncProgressSnapshot {
+    /// The number of slots the sync still has to import.
+    pub fn head_distance(&self) -> u64 {
+        self.target_slot.saturating_sub(self.current_slot)
+    }
+
+    /// The time left until the sync reaches the target slot at the current speed.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.slots_per_second <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(
+            self.head_distance() as f64 / self.slots_per_second,
+        ))
+    }
+}
+
+impl SyncProgress {
+    pub fn start(&self, start_slot: u64) {
+        let mut state = self.state.write();
+        state.is_syncing = true;
+        state.start_slot = start_slot;
+        state.current_slot = start_slot;
+        state.target_slot = state.target_slot.max(start_slot);
+        state.samples = VecDeque::from([(Instant::now(), start_slot)]);
+    }
+
+    pub fn set_target_slot(&self, target_slot: u64) {
+        self.state.write().target_slot = target_slot;
+    }
+
+    /// Records that the blocks up to `current_slot` were imported.
+    pub fn set_current_slot(&self, current_slot: u64) {
+        let mut state = self.state.write();
+        let now = Instant::now();
+        state.current_slot = current_slot;
+        state.samples.push_back((now, current_slot));
+        while state.samples.len() > 2
+            && state
+                .samples
+                .front()
+                .is_some_and(|(time, _)| now.duration_since(*time) > SYNC_SPEED_WINDOW)
+        {
+            state.samples.pop_front();
+        }
+    }
+
+    pub fn finish(&self) {
+        self.state.write().is_syncing = false;
+    }
+
+    pub fn snapshot(&self) -> SyncProgressSnapshot {
+        let state = self.state.read();
+        let slots_per_second = match (state.samples.front(), state.samples.back()) {
+            (Some((start_time, start_slot)), Some((end_time, end_slot)))
+                if end_time > start_time =>
+            {
+                end_slot.saturating_sub(*start_slot) as f64
+                    / end_time.duration_since(*start_time).as_secs_f64()
+            }
+            _ => 0.0,
+        };
+        SyncProgressSnapshot {
+            is_syncing: state.is_syncing,
+            start_slot: state.start_slot,
+            current_slot: state.current_slot,
+            target_slot: state.target_slot,
+            slots_per_second,
+        }
+    }
+}
@@ -0,0 +1,8 @@
+fn main() {
+    // The guests are built for their zkVM target with the toolchain of the backend, so only the
+    // backends which are enabled require it
+    #[cfg(feature = "risc0")]
+    risc0_build::embed_methods();
+    #[cfg(feature = "sp1")]
+    sp1_build::build_program("guest/sp1");
+}
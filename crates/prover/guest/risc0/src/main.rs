@@ -0,0 +1,19 @@
+//! The RISC Zero guest of the lean state transition program. It reads the SSZ encoded
+//! [LeanProgramInput] and commits the SSZ encoded output to the journal.
+
+use std::io::Read;
+
+use ream_prover_program::{LeanProgramInput, execute_lean_state_transition};
+use risc0_zkvm::guest::env;
+use ssz::{Decode, Encode};
+
+fn main() {
+    let mut input = vec![];
+    env::stdin()
+        .read_to_end(&mut input)
+        .expect("Failed to read the program input");
+    let input =
+        LeanProgramInput::from_ssz_bytes(&input).expect("Failed to decode the program input");
+    let output = execute_lean_state_transition(input).expect("Invalid lean state transition");
+    env::commit_slice(&output.as_ssz_bytes());
+}
@@ -0,0 +1,15 @@
+//! The SP1 guest of the lean state transition program. It reads the SSZ encoded
+//! [LeanProgramInput] and commits the SSZ encoded output.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use ream_prover_program::{LeanProgramInput, execute_lean_state_transition};
+use ssz::{Decode, Encode};
+
+pub fn main() {
+    let input = LeanProgramInput::from_ssz_bytes(&sp1_zkvm::io::read_vec())
+        .expect("Failed to decode the program input");
+    let output = execute_lean_state_transition(input).expect("Invalid lean state transition");
+    sp1_zkvm::io::commit_slice(&output.as_ssz_bytes());
+}
@@ -0,0 +1,117 @@
+//! The lean state transition program, which the zkVM guests of every backend run and the host
+//! side shares the SSZ input and output of.
+
+use alloy_primitives::B256;
+use anyhow::Context;
+use ream_consensus_lean::{block::SignedBlock, state::LeanState};
+use ssz_derive::{Decode, Encode};
+use tree_hash::TreeHash;
+
+/// The input of the lean state transition program, which the guest reads SSZ encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct LeanProgramInput {
+    pub pre_state: LeanState,
+    pub signed_block: SignedBlock,
+}
+
+/// The public output of the lean state transition program, which the guest commits SSZ encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct LeanProgramOutput {
+    pub pre_state_root: B256,
+    pub block_root: B256,
+    pub post_state_root: B256,
+}
+
+/// The lean state transition program run by the zkVM guests.
+///
+/// The block's state root is checked against the transitioned state, so a proof of the program
+/// attests that the block is a valid transition from the pre-state.
+pub fn execute_lean_state_transition(input: LeanProgramInput) -> anyhow::Result<LeanProgramOutput> {
+    let LeanProgramInput {
+        mut pre_state,
+        signed_block,
+    } = input;
+    let pre_state_root = pre_state.tree_hash_root();
+
+    pre_state
+        .state_transition(&signed_block, true, true)
+        .context("Failed to run the lean state transition")?;
+
+    Ok(LeanProgramOutput {
+        pre_state_root,
+        block_root: signed_block.message.tree_hash_root(),
+        post_state_root: pre_state.tree_hash_root(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::FixedBytes;
+    use ream_consensus_lean::block::{Block, BlockHeader};
+
+    use super::*;
+
+    fn genesis_state() -> LeanState {
+        let mut state = LeanState::new(4, 0);
+        let genesis_block = Block {
+            state_root: state.tree_hash_root(),
+            ..Default::default()
+        };
+        state.latest_block_header = BlockHeader::from(genesis_block);
+        state
+    }
+
+    #[test]
+    fn test_execute_lean_state_transition() {
+        let pre_state = genesis_state();
+        let mut block = Block {
+            slot: 1,
+            proposer_index: 1,
+            parent_root: pre_state.latest_block_header.tree_hash_root(),
+            ..Default::default()
+        };
+
+        // Compute the state root of the block like a proposer does
+        let mut post_state = pre_state.clone();
+        post_state
+            .state_transition(
+                &SignedBlock {
+                    message: block.clone(),
+                    signature: FixedBytes::ZERO,
+                },
+                true,
+                false,
+            )
+            .expect("Block is valid");
+        block.state_root = post_state.tree_hash_root();
+
+        let signed_block = SignedBlock {
+            message: block,
+            signature: FixedBytes::ZERO,
+        };
+        let output = execute_lean_state_transition(LeanProgramInput {
+            pre_state: pre_state.clone(),
+            signed_block: signed_block.clone(),
+        })
+        .expect("Transition is valid");
+        assert_eq!(
+            output,
+            LeanProgramOutput {
+                pre_state_root: pre_state.tree_hash_root(),
+                block_root: signed_block.message.tree_hash_root(),
+                post_state_root: post_state.tree_hash_root(),
+            }
+        );
+
+        // A block committing to another state root is rejected
+        let mut signed_block = signed_block;
+        signed_block.message.state_root = B256::repeat_byte(1);
+        assert!(
+            execute_lean_state_transition(LeanProgramInput {
+                pre_state,
+                signed_block,
+            })
+            .is_err()
+        );
+    }
+}
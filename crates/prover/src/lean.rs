@@ -0,0 +1,10 @@
+pub use ream_prover_program::{LeanProgramInput, LeanProgramOutput, execute_lean_state_transition};
+use ssz_derive::{Decode, Encode};
+
+/// A proof of the lean state transition program, as generated by a zkVM backend.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct LeanProof {
+    pub output: LeanProgramOutput,
+    /// The backend specific serialization of the proof
+    pub proof: Vec<u8>,
+}
@@ -0,0 +1,7 @@
+pub mod lean;
+pub mod prover;
+#[cfg(feature = "risc0")]
+pub mod risc0;
+pub mod service;
+#[cfg(feature = "sp1")]
+pub mod sp1;
@@ -0,0 +1,88 @@
+use crate::lean::{LeanProgramInput, LeanProgramOutput, LeanProof};
+
+/// A zkVM backend proving the lean state transition program.
+///
+/// Each backend runs its own guest build of
+/// [execute_lean_state_transition](crate::lean::execute_lean_state_transition), so the backends
+/// can be swapped without changing the host side.
+pub trait Prover {
+    /// Runs the guest on `input` without proving it, returning the output it commits.
+    fn execute(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProgramOutput>;
+
+    /// Proves the transition of `input`, returning the output committed by the guest together
+    /// with its proof.
+    fn prove(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProof>;
+
+    /// Verifies that `proof` attests to its output.
+    fn verify(&self, proof: &LeanProof) -> anyhow::Result<()>;
+}
+
+impl<P: Prover + ?Sized> Prover for Box<P> {
+    fn execute(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProgramOutput> {
+        (**self).execute(input)
+    }
+
+    fn prove(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProof> {
+        (**self).prove(input)
+    }
+
+    fn verify(&self, proof: &LeanProof) -> anyhow::Result<()> {
+        (**self).verify(proof)
+    }
+}
+
+#[cfg(all(test, feature = "risc0", feature = "sp1"))]
+mod tests {
+    use alloy_primitives::FixedBytes;
+    use ream_consensus_lean::{
+        block::{Block, BlockHeader, SignedBlock},
+        state::LeanState,
+    };
+    use tree_hash::TreeHash;
+
+    use super::*;
+    use crate::{lean::execute_lean_state_transition, risc0::Risc0Prover, sp1::Sp1Prover};
+
+    fn input() -> LeanProgramInput {
+        let mut pre_state = LeanState::new(4, 0);
+        pre_state.latest_block_header = BlockHeader::from(Block {
+            state_root: pre_state.tree_hash_root(),
+            ..Default::default()
+        });
+
+        let mut signed_block = SignedBlock {
+            message: Block {
+                slot: 1,
+                proposer_index: 1,
+                parent_root: pre_state.latest_block_header.tree_hash_root(),
+                ..Default::default()
+            },
+            signature: FixedBytes::ZERO,
+        };
+        let mut post_state = pre_state.clone();
+        post_state
+            .state_transition(&signed_block, true, false)
+            .expect("Block is valid");
+        signed_block.message.state_root = post_state.tree_hash_root();
+
+        LeanProgramInput {
+            pre_state,
+            signed_block,
+        }
+    }
+
+    #[test]
+    fn test_guests_match_host_execution() {
+        let input = input();
+        let expected = execute_lean_state_transition(input.clone()).expect("Transition is valid");
+
+        assert_eq!(
+            Sp1Prover::new().execute(&input).expect("SP1 guest runs"),
+            expected
+        );
+        assert_eq!(
+            Risc0Prover.execute(&input).expect("RISC Zero guest runs"),
+            expected
+        );
+    }
+}
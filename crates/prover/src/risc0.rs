@@ -0,0 +1,66 @@
+use anyhow::{anyhow, ensure};
+use bincode::config::standard;
+use risc0_zkvm::{ExecutorEnv, ProverOpts, Receipt, default_executor, default_prover};
+use ssz::{Decode, Encode};
+
+use crate::{
+    lean::{LeanProgramInput, LeanProgramOutput, LeanProof},
+    prover::Prover,
+};
+
+// The RISC Zero guest of the lean state transition program, built from `guest/risc0`, with its
+// image ID: `REAM_PROVER_GUEST_RISC0_ELF` and `REAM_PROVER_GUEST_RISC0_ID`.
+include!(concat!(env!("OUT_DIR"), "/methods.rs"));
+
+/// Proves the lean state transition with RISC Zero. The proofs are succinct, so they have the same
+/// size regardless of the number of cycles of the block.
+///
+/// Where the proofs are generated is configured through the `RISC0_PROVER` environment variable,
+/// e.g. `local`, `ipc` or `bonsai`.
+#[derive(Default)]
+pub struct Risc0Prover;
+
+fn executor_env(input: &LeanProgramInput) -> anyhow::Result<ExecutorEnv<'static>> {
+    ExecutorEnv::builder()
+        .write_slice(&input.as_ssz_bytes())
+        .build()
+}
+
+fn decode_output(journal: &[u8]) -> anyhow::Result<LeanProgramOutput> {
+    LeanProgramOutput::from_ssz_bytes(journal)
+        .map_err(|err| anyhow!("Failed to decode the committed output: {err:?}"))
+}
+
+impl Prover for Risc0Prover {
+    fn execute(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProgramOutput> {
+        let session =
+            default_executor().execute(executor_env(input)?, REAM_PROVER_GUEST_RISC0_ELF)?;
+        decode_output(&session.journal.bytes)
+    }
+
+    fn prove(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProof> {
+        let receipt = default_prover()
+            .prove_with_opts(
+                executor_env(input)?,
+                REAM_PROVER_GUEST_RISC0_ELF,
+                &ProverOpts::succinct(),
+            )?
+            .receipt;
+
+        Ok(LeanProof {
+            output: decode_output(&receipt.journal.bytes)?,
+            proof: bincode::serde::encode_to_vec(&receipt, standard())?,
+        })
+    }
+
+    fn verify(&self, lean_proof: &LeanProof) -> anyhow::Result<()> {
+        let (receipt, _): (Receipt, _) =
+            bincode::serde::decode_from_slice(&lean_proof.proof, standard())?;
+        ensure!(
+            receipt.journal.bytes == lean_proof.output.as_ssz_bytes(),
+            "Proof commits to another output"
+        );
+
+        Ok(receipt.verify(REAM_PROVER_GUEST_RISC0_ID)?)
+    }
+}
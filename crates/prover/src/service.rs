@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use ream_storage::{db::lean::LeanDB, tables::table::Table};
+use ssz::Encode;
+use tokio::sync::{Semaphore, mpsc};
+use tracing::{info, warn};
+use tree_hash::TreeHash;
+
+use crate::{lean::LeanProgramInput, prover::Prover};
+
+/// ProverService proves the lean blocks it is sent, caching the proofs by block root in the
+/// database where the RPC serves them from.
+///
+/// Proof generation is CPU bound and slow, so the proofs are generated on blocking threads, at
+/// most `num_workers` at a time. Further jobs wait in the channel.
+pub struct ProverService<P> {
+    prover: Arc<P>,
+    db: LeanDB,
+    receiver: mpsc::UnboundedReceiver<LeanProgramInput>,
+    workers: Arc<Semaphore>,
+}
+
+impl<P: Prover + Send + Sync + 'static> ProverService<P> {
+    pub fn new(
+        prover: P,
+        db: LeanDB,
+        receiver: mpsc::UnboundedReceiver<LeanProgramInput>,
+        num_workers: usize,
+    ) -> Self {
+        ProverService {
+            prover: Arc::new(prover),
+            db,
+            receiver,
+            workers: Arc::new(Semaphore::new(num_workers)),
+        }
+    }
+
+    pub async fn start(mut self) -> anyhow::Result<()> {
+        info!(
+            "ProverService started with {} worker(s)",
+            self.workers.available_permits()
+        );
+
+        while let Some(input) = self.receiver.recv().await {
+            let block_root = input.signed_block.message.tree_hash_root();
+            let lean_proof_provider = self.db.lean_proof_provider();
+            if lean_proof_provider.get(block_root)?.is_some() {
+                continue;
+            }
+
+            let permit = self
+                .workers
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|err| anyhow!("Prover workers are closed: {err:?}"))?;
+            let prover = self.prover.clone();
+            tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                let slot = input.signed_block.message.slot;
+                info!("Proving block {block_root} at slot {slot}");
+
+                match prover.prove(&input) {
+                    Ok(lean_proof) => {
+                        if let Err(err) =
+                            lean_proof_provider.insert(block_root, lean_proof.as_ssz_bytes())
+                        {
+                            warn!("Failed to store the proof of block {block_root}: {err:?}");
+                        } else {
+                            info!("Proved block {block_root} at slot {slot}");
+                        }
+                    }
+                    Err(err) => warn!("Failed to prove block {block_root}: {err:?}"),
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, ensure};
+use bincode::config::standard;
+use sp1_sdk::{
+    EnvProver, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+    include_elf,
+};
+use ssz::{Decode, Encode};
+
+use crate::{
+    lean::{LeanProgramInput, LeanProgramOutput, LeanProof},
+    prover::Prover,
+};
+
+/// The SP1 guest of the lean state transition program, built from `guest/sp1`.
+pub const LEAN_GUEST_ELF: &[u8] = include_elf!("ream-prover-guest-sp1");
+
+/// Proves the lean state transition with SP1. The proofs are compressed, so they have the same
+/// size regardless of the number of cycles of the block.
+///
+/// Where the proofs are generated is configured through the `SP1_PROVER` environment variable,
+/// e.g. `cpu`, `cuda` or `network`.
+pub struct Sp1Prover {
+    client: EnvProver,
+    proving_key: SP1ProvingKey,
+    verifying_key: SP1VerifyingKey,
+}
+
+impl Sp1Prover {
+    pub fn new() -> Self {
+        let client = ProverClient::from_env();
+        let (proving_key, verifying_key) = client.setup(LEAN_GUEST_ELF);
+        Sp1Prover {
+            client,
+            proving_key,
+            verifying_key,
+        }
+    }
+}
+
+impl Default for Sp1Prover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn stdin(input: &LeanProgramInput) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+    stdin.write_vec(input.as_ssz_bytes());
+    stdin
+}
+
+fn decode_output(public_values: &[u8]) -> anyhow::Result<LeanProgramOutput> {
+    LeanProgramOutput::from_ssz_bytes(public_values)
+        .map_err(|err| anyhow!("Failed to decode the committed output: {err:?}"))
+}
+
+impl Prover for Sp1Prover {
+    fn execute(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProgramOutput> {
+        let (public_values, _) = self.client.execute(LEAN_GUEST_ELF, &stdin(input)).run()?;
+        decode_output(public_values.as_slice())
+    }
+
+    fn prove(&self, input: &LeanProgramInput) -> anyhow::Result<LeanProof> {
+        let proof = self
+            .client
+            .prove(&self.proving_key, &stdin(input))
+            .compressed()
+            .run()?;
+        Ok(LeanProof {
+            output: decode_output(proof.public_values.as_slice())?,
+            proof: bincode::serde::encode_to_vec(&proof, standard())?,
+        })
+    }
+
+    fn verify(&self, lean_proof: &LeanProof) -> anyhow::Result<()> {
+        let (proof, _): (SP1ProofWithPublicValues, _) =
+            bincode::serde::decode_from_slice(&lean_proof.proof, standard())?;
+        ensure!(
+            proof.public_values.as_slice() == lean_proof.output.as_ssz_bytes(),
+            "Proof commits to another output"
+        );
+
+        Ok(self.client.verify(&proof, &self.verifying_key)?)
+    }
+}
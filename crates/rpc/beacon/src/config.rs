@@ -1,17 +1,27 @@
 use std::net::{IpAddr, SocketAddr};
 
+use crate::handlers::admin::AdminToken;
+
 #[derive(Debug, Clone)]
 pub struct RpcServerConfig {
     pub http_socket_address: SocketAddr,
     pub http_allow_origin: bool,
+    /// The token authenticating requests to the admin API, which is disabled without one
+    pub admin_token: Option<AdminToken>,
 }
 
 impl RpcServerConfig {
     /// Creates a new instance from CLI arguments
-    pub fn new(http_address: IpAddr, http_port: u16, http_allow_origin: bool) -> Self {
+    pub fn new(
+        http_address: IpAddr,
+        http_port: u16,
+        http_allow_origin: bool,
+        admin_token: Option<AdminToken>,
+    ) -> Self {
         Self {
             http_socket_address: SocketAddr::new(http_address, http_port),
             http_allow_origin,
+            admin_token,
         }
     }
 }
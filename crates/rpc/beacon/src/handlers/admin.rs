@@ -0,0 +1,240 @@
+use std::{str::FromStr, sync::Arc};
+
+use actix_web::{
+    Error, HttpResponse, Responder,
+    body::MessageBody,
+    delete,
+    dev::{ServiceRequest, ServiceResponse},
+    get,
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    post, put,
+    web::{Data, Json, Path},
+};
+use libp2p::{Multiaddr, PeerId};
+use ream_api_types_beacon::responses::DataResponse;
+use ream_api_types_common::error::ApiError;
+use ream_network_manager::{admin::AdminSender, p2p_sender::P2PSender};
+use ream_p2p::req_resp::beacon::messages::goodbye::Goodbye;
+use ream_storage::cache::ShufflingCache;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+/// The handle to change the log filter of the node at runtime.
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// The bearer token which authenticates requests to the admin API.
+#[derive(Debug, Clone)]
+pub struct AdminToken(String);
+
+impl AdminToken {
+    /// Creates the token from the contents of the token file, or returns `None` if it is empty,
+    /// since an empty token would authenticate any request with an empty bearer token.
+    pub fn new(token: &str) -> Option<Self> {
+        let token = token.trim();
+        (!token.is_empty()).then(|| Self(token.to_string()))
+    }
+
+    /// Compares in constant time, so the token can't be guessed from the response times. Every
+    /// byte of the expected token is compared, whatever the length of `token`.
+    fn matches(&self, token: &str) -> bool {
+        let (expected, token) = (self.0.as_bytes(), token.as_bytes());
+        let difference = expected
+            .iter()
+            .enumerate()
+            .fold(0, |difference, (index, byte)| {
+                difference | (byte ^ token.get(index).copied().unwrap_or_default())
+            });
+        difference == 0 && expected.len() == token.len()
+    }
+}
+
+/// Rejects the requests to `ream/v1/admin` without the admin token in the `Authorization`
+/// header, and all of them if the node was started without an admin token.
+pub async fn authorize_admin(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let admin_token = request
+        .app_data::<Data<Option<AdminToken>>>()
+        .and_then(|admin_token| admin_token.get_ref().clone());
+    let Some(admin_token) = admin_token else {
+        return Err(ApiError::NotFound(
+            "The admin API is disabled, start the node with --admin-token-file".to_string(),
+        )
+        .into());
+    };
+
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "));
+    if !token.is_some_and(|token| admin_token.matches(token)) {
+        return Err(ApiError::Unauthorized.into());
+    }
+
+    next.call(request).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddPeerRequest {
+    pub address: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResyncRequest {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogFilter {
+    pub filter: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueDepths {
+    /// The gossip and req/resp messages waiting to be handled by the network manager
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub network_events: u64,
+    /// The admin commands waiting to be handled by the network manager
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub admin_commands: u64,
+    /// The P2P messages waiting to be handled by the network worker
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub p2p_messages: u64,
+    /// The requests sent to peers which haven't been answered yet
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub pending_requests: u64,
+}
+
+fn parse_peer_id(peer_id: &str) -> Result<PeerId, ApiError> {
+    PeerId::from_str(peer_id)
+        .map_err(|err| ApiError::BadRequest(format!("Invalid PeerId format: {peer_id}, {err:?}")))
+}
+
+/// Called by `ream/v1/admin/peers` to dial a peer at a multiaddr.
+#[post("/peers")]
+pub async fn post_peer(
+    p2p_sender: Data<P2PSender>,
+    request: Json<AddPeerRequest>,
+) -> Result<impl Responder, ApiError> {
+    let address = Multiaddr::from_str(&request.address).map_err(|err| {
+        ApiError::BadRequest(format!("Invalid multiaddr: {}, {err:?}", request.address))
+    })?;
+    p2p_sender.dial_peer(address);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Called by `ream/v1/admin/peers/{peer_id}` to disconnect from a peer.
+#[delete("/peers/{peer_id}")]
+pub async fn delete_peer(
+    p2p_sender: Data<P2PSender>,
+    peer_id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    p2p_sender.disconnect_peer(parse_peer_id(&peer_id)?, Goodbye::ClientShutdown);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Called by `ream/v1/admin/peers/{peer_id}/ban` to disconnect from a peer and refuse its
+/// connections until the node restarts.
+#[post("/peers/{peer_id}/ban")]
+pub async fn post_ban_peer(
+    p2p_sender: Data<P2PSender>,
+    peer_id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    p2p_sender.ban_peer(parse_peer_id(&peer_id)?);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Called by `ream/v1/admin/resync` to restart the range sync from the block at a slot.
+#[post("/resync")]
+pub async fn post_resync(
+    admin_sender: Data<AdminSender>,
+    request: Json<ResyncRequest>,
+) -> Result<impl Responder, ApiError> {
+    admin_sender.resync(request.slot).map_err(|err| {
+        error!("Failed to trigger resync: {err:?}");
+        ApiError::InternalError(format!("Failed to trigger resync: {err:?}"))
+    })?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Called by `ream/v1/admin/caches/flush` to clear the shuffling cache and the caches of seen
+/// gossip messages.
+#[post("/caches/flush")]
+pub async fn post_flush_caches(
+    admin_sender: Data<AdminSender>,
+    shuffling_cache: Data<Arc<ShufflingCache>>,
+) -> Result<impl Responder, ApiError> {
+    shuffling_cache.clear().await;
+    admin_sender.flush_caches().map_err(|err| {
+        error!("Failed to flush caches: {err:?}");
+        ApiError::InternalError(format!("Failed to flush caches: {err:?}"))
+    })?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+fn reloadable_log_filter(
+    log_filter_handle: &Data<Option<LogFilterHandle>>,
+) -> Result<&LogFilterHandle, ApiError> {
+    log_filter_handle
+        .get_ref()
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("The log filter can't be changed at runtime".to_string()))
+}
+
+/// Called by `ream/v1/admin/log_level` to get the current log filter.
+#[get("/log_level")]
+pub async fn get_log_level(
+    log_filter_handle: Data<Option<LogFilterHandle>>,
+) -> Result<impl Responder, ApiError> {
+    let filter = reloadable_log_filter(&log_filter_handle)?
+        .with_current(|filter| filter.to_string())
+        .map_err(|err| ApiError::InternalError(format!("Failed to read the log filter: {err}")))?;
+    Ok(HttpResponse::Ok().json(DataResponse::new(LogFilter { filter })))
+}
+
+/// Called by `ream/v1/admin/log_level` to replace the log filter, which takes the same directives
+/// as `RUST_LOG`.
+#[put("/log_level")]
+pub async fn put_log_level(
+    log_filter_handle: Data<Option<LogFilterHandle>>,
+    request: Json<LogFilter>,
+) -> Result<impl Responder, ApiError> {
+    let filter = EnvFilter::builder()
+        .parse(&request.filter)
+        .map_err(|err| ApiError::BadRequest(format!("Invalid log filter: {err}")))?;
+    reloadable_log_filter(&log_filter_handle)?
+        .reload(filter)
+        .map_err(|err| ApiError::InternalError(format!("Failed to set the log filter: {err}")))?;
+    info!("Log filter set to {}", request.filter);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Called by `ream/v1/admin/queues` to get the number of items waiting in the internal queues.
+#[get("/queues")]
+pub async fn get_queues(
+    admin_sender: Data<AdminSender>,
+    p2p_sender: Data<P2PSender>,
+) -> Result<impl Responder, ApiError> {
+    let manager_queue_depths = admin_sender.queue_depths().await.map_err(|err| {
+        ApiError::InternalError(format!(
+            "Failed to get the queue depths of the manager: {err:?}"
+        ))
+    })?;
+    let network_queue_depths = p2p_sender.queue_depths().await.map_err(|err| {
+        ApiError::InternalError(format!(
+            "Failed to get the queue depths of the network: {err:?}"
+        ))
+    })?;
+
+    Ok(HttpResponse::Ok().json(DataResponse::new(QueueDepths {
+        network_events: manager_queue_depths.network_events as u64,
+        admin_commands: manager_queue_depths.admin_commands as u64,
+        p2p_messages: network_queue_depths.p2p_messages as u64,
+        pending_requests: network_queue_depths.pending_requests as u64,
+    })))
+}
@@ -1,18 +1,26 @@
+use std::collections::BTreeSet;
+
 use actix_web::{
-    HttpResponse, Responder, get,
+    HttpRequest, HttpResponse, Responder, get,
     web::{Data, Path},
 };
 use actix_web_lab::extract::Query;
 use ream_api_types_beacon::{query::BlobSidecarQuery, responses::BeaconVersionedResponse};
 use ream_api_types_common::{error::ApiError, id::ID};
 use ream_consensus_beacon::blob_sidecar::BlobIdentifier;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_rpc_common::content_type::{accepts_ssz, ssz_response};
 use ream_storage::{db::beacon::BeaconDB, tables::table::Table};
+use ssz::Encode;
 use tree_hash::TreeHash;
 
 use crate::handlers::block::get_beacon_block_from_id;
 
+/// Called by `/eth/v1/beacon/blob_sidecars/{block_id}` to get the blob sidecars of a block,
+/// optionally filtered by the `indices` query parameter.
 #[get("/beacon/blob_sidecars/{block_id}")]
 pub async fn get_blob_sidecars(
+    http_request: HttpRequest,
     db: Data<BeaconDB>,
     block_id: Path<ID>,
     query: Query<BlobSidecarQuery>,
@@ -20,18 +28,17 @@ pub async fn get_blob_sidecars(
     let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
     let block_root = beacon_block.message.tree_hash_root();
 
-    let indices = if let Some(indices) = &query.indices {
-        let max_index = beacon_block.message.body.blob_kzg_commitments.len() as u64;
-        for index in indices {
-            if index >= &max_index {
+    let blob_count = beacon_block.message.body.blob_kzg_commitments.len() as u64;
+    let indices = match &query.indices {
+        Some(indices) => {
+            if let Some(index) = indices.iter().find(|index| **index >= blob_count) {
                 return Err(ApiError::BadRequest(format!(
-                    "Invalid blob index: {index}, max index is {max_index}"
+                    "Invalid blob index: {index}, the block only has {blob_count} blobs"
                 )));
             }
+            indices.iter().copied().collect::<BTreeSet<_>>()
         }
-        indices
-    } else {
-        &(0..beacon_block.message.body.blob_kzg_commitments.len() as u64).collect()
+        None => (0..blob_count).collect(),
     };
 
     let mut blob_sidecars = vec![];
@@ -39,7 +46,7 @@ pub async fn get_blob_sidecars(
     for index in indices {
         let blob_and_proof = db
             .blobs_and_proofs_provider()
-            .get(BlobIdentifier::new(block_root, *index))
+            .get(BlobIdentifier::new(block_root, index))
             .map_err(|err| {
                 ApiError::InternalError(format!(
                     "Failed to get blob and proof for index: {index}, error: {err:?}"
@@ -50,7 +57,7 @@ pub async fn get_blob_sidecars(
             )))?;
         blob_sidecars.push(
             beacon_block
-                .blob_sidecar(blob_and_proof, *index)
+                .blob_sidecar(blob_and_proof, index)
                 .map_err(|err| {
                     ApiError::InternalError(format!(
                         "Failed to create blob sidecar for index: {index}, error: {err:?}"
@@ -59,5 +66,11 @@ pub async fn get_blob_sidecars(
         );
     }
 
+    if accepts_ssz(&http_request) {
+        return Ok(ssz_response(
+            blob_sidecars.as_ssz_bytes(),
+            beacon_network_spec().fork_name_at_slot(beacon_block.message.slot.as_u64()),
+        ));
+    }
     Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(blob_sidecars)))
 }
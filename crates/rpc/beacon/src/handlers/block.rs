@@ -1,30 +1,55 @@
+use std::sync::Arc;
+
 use actix_web::{
     HttpRequest, HttpResponse, Responder, get, post,
-    web::{Data, Json, Path},
+    web::{Data, Json, Path, Query},
 };
 use alloy_primitives::B256;
 use ream_api_types_beacon::{
+    block::{BroadcastValidation, SignedBlockContents},
     id::ValidatorID,
+    query::BroadcastValidationQuery,
     responses::{
-        BeaconResponse, BeaconVersionedResponse, DataResponse, RootResponse, SSZ_CONTENT_TYPE,
+        BeaconResponse, BeaconVersionedResponse, DataResponse, ETH_CONSENSUS_VERSION_HEADER,
+        RootResponse, VERSION,
     },
 };
 use ream_api_types_common::{error::ApiError, id::ID};
+use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_consensus_beacon::{
-    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    blob_sidecar::{BlobIdentifier, BlobSidecar, compute_subnet_for_blob_sidecar},
+    electra::{
+        beacon_block::SignedBeaconBlock, beacon_state::BeaconState,
+        blinded_beacon_block::SignedBlindedBeaconBlock,
+    },
+    execution_engine::rpc_types::get_blobs::{Blob, BlobAndProofV1},
     genesis::Genesis,
+    polynomial_commitments::kzg_proof::KZGProof,
 };
 use ream_consensus_misc::constants::beacon::{
-    WHISTLEBLOWER_REWARD_QUOTIENT, genesis_validators_root,
+    GENESIS_SLOT, WHISTLEBLOWER_REWARD_QUOTIENT, genesis_validators_root,
 };
+use ream_network_manager::p2p_sender::P2PSender;
 use ream_network_spec::networks::beacon_network_spec;
+use ream_p2p::{
+    gossipsub::beacon::topics::{GossipTopic, GossipTopicKind},
+    network::beacon::channel::GossipMessage,
+};
+use ream_polynomial_commitments::handlers::verify_blob_sidecars;
+use ream_rpc_common::content_type::{
+    JsonOrSsz, accepts_ssz, ensure_consensus_version, ssz_response,
+};
 use ream_storage::{
     db::beacon::BeaconDB,
     tables::{field::Field, table::Table},
 };
+use ream_validator_beacon::builder::{
+    blobs::ExecutionPayloadAndBlobsBundle, builder_client::BuilderClient,
+};
 use serde::{Deserialize, Serialize};
 use ssz::Encode;
 use tracing::error;
+use tree_hash::TreeHash;
 
 use crate::handlers::state::get_state_from_id;
 
@@ -72,12 +97,11 @@ pub async fn get_block_root_from_id(block_id: ID, db: &BeaconDB) -> Result<B256,
 
             Ok(Some(justified_checkpoint.root))
         }
-        ID::Head | ID::Genesis => {
-            return Err(ApiError::NotFound(format!(
-                "This ID type is currently not supported: {block_id:?}"
-            )));
-        }
-        ID::Slot(slot) => db.slot_index_provider().get(slot),
+        ID::Head => db.head_root_provider().get().map(Some),
+        ID::Genesis => db.slot_index_provider().get(GENESIS_SLOT),
+        ID::Slot(slot) => Ok(db.get_canonical_block_root(slot).map_err(|err| {
+            ApiError::InternalError(format!("Failed to get block by slot, error: {err:?}"))
+        })?),
         ID::Root(root) => Ok(Some(root)),
     }
     .map_err(|err| {
@@ -175,9 +199,11 @@ pub async fn get_block_attestations(
 ) -> Result<impl Responder, ApiError> {
     let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
 
-    Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(
-        beacon_block.message.body.attestations,
-    )))
+    Ok(HttpResponse::Ok()
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
+        .json(BeaconVersionedResponse::new(
+            beacon_block.message.body.attestations,
+        )))
 }
 
 /// Called by `/blocks/<block_id>/root` to get the Tree hash of the Block.
@@ -234,12 +260,21 @@ pub async fn get_block_rewards(
 /// Called by `/blocks/<block_id>` to get the Beacon Block.
 #[get("/beacon/blocks/{block_id}")]
 pub async fn get_block_from_id(
+    http_request: HttpRequest,
     db: Data<BeaconDB>,
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
     let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
 
-    Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(beacon_block)))
+    if accepts_ssz(&http_request) {
+        return Ok(ssz_response(
+            beacon_block.as_ssz_bytes(),
+            beacon_network_spec().fork_name_at_slot(beacon_block.message.slot.as_u64()),
+        ));
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
+        .json(BeaconVersionedResponse::new(beacon_block)))
 }
 
 #[post("/beacon/rewards/sync_committee/{block_id}")]
@@ -296,7 +331,9 @@ pub async fn post_sync_committee_rewards(
     Ok(HttpResponse::Ok().json(BeaconResponse::new(reward_data)))
 }
 
-#[get("/beacon/blind_block/{block_id}")]
+/// Called by `/eth/v1/beacon/blinded_blocks/{block_id}` to get the block with its execution
+/// payload replaced by the payload header.
+#[get("/beacon/blinded_blocks/{block_id}")]
 pub async fn get_blind_block(
     http_request: HttpRequest,
     db: Data<BeaconDB>,
@@ -304,14 +341,330 @@ pub async fn get_blind_block(
 ) -> Result<impl Responder, ApiError> {
     let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
     let blinded_beacon_block = beacon_block.as_signed_blinded_beacon_block();
-    match http_request
-        .headers()
-        .get(SSZ_CONTENT_TYPE)
-        .and_then(|header| header.to_str().ok())
+
+    if accepts_ssz(&http_request) {
+        return Ok(ssz_response(
+            blinded_beacon_block.as_ssz_bytes(),
+            beacon_network_spec().fork_name_at_slot(beacon_block.message.slot.as_u64()),
+        ));
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
+        .json(BeaconVersionedResponse::new(blinded_beacon_block)))
+}
+
+/// Run the checks requested by `broadcast_validation` against the block's parent state advanced to
+/// the block's slot.
+async fn validate_published_block(
+    db: &BeaconDB,
+    beacon_chain: &BeaconChain,
+    signed_block: &SignedBeaconBlock,
+    broadcast_validation: BroadcastValidation,
+) -> Result<(), ApiError> {
+    let block = &signed_block.message;
+
     {
-        Some(SSZ_CONTENT_TYPE) => Ok(HttpResponse::Ok()
-            .content_type(SSZ_CONTENT_TYPE)
-            .body(blinded_beacon_block.as_ssz_bytes())),
-        _ => Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(blinded_beacon_block))),
+        let store = beacon_chain.store.lock().await;
+        let current_slot = store.get_current_slot().map_err(|err| {
+            ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
+        })?;
+        if block.slot > current_slot {
+            return Err(ApiError::BadRequest(
+                "Block is from a future slot".to_string(),
+            ));
+        }
+
+        let finalized_checkpoint =
+            store
+                .db
+                .finalized_checkpoint_provider()
+                .get()
+                .map_err(|err| {
+                    ApiError::InternalError(format!(
+                        "Failed to get finalized checkpoint, error: {err:?}"
+                    ))
+                })?;
+        if block.slot <= finalized_checkpoint.epoch.start_slot() {
+            return Err(ApiError::BadRequest(
+                "Block is not from a slot greater than the latest finalized slot".to_string(),
+            ));
+        }
+    }
+
+    let parent_block = db
+        .beacon_block_provider()
+        .get(block.parent_root)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get parent block, error: {err:?}"))
+        })?
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("Parent block {} is unknown", block.parent_root))
+        })?;
+    if block.slot <= parent_block.message.slot {
+        return Err(ApiError::BadRequest(
+            "Block is not from a higher slot than its parent".to_string(),
+        ));
+    }
+
+    let mut state = db
+        .beacon_state_provider()
+        .get(block.parent_root)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get parent state, error: {err:?}"))
+        })?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Parent state {} not found", block.parent_root))
+        })?;
+    state.process_slots(block.slot.as_u64()).map_err(|err| {
+        ApiError::InternalError(format!("Failed to process slots, error: {err:?}"))
+    })?;
+
+    let proposer_index = state.get_beacon_proposer_index(None).map_err(|err| {
+        ApiError::InternalError(format!(
+            "Failed to get beacon proposer index, error: {err:?}"
+        ))
+    })?;
+    if block.proposer_index != proposer_index {
+        return Err(ApiError::BadRequest(format!(
+            "Block proposer index {} is not the expected proposer {proposer_index}",
+            block.proposer_index
+        )));
+    }
+
+    match state.verify_block_header_signature(&signed_block.signed_header()) {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(ApiError::BadRequest("Invalid block signature".to_string()));
+        }
+        Err(err) => {
+            return Err(ApiError::BadRequest(format!(
+                "Signature verification failed: {err:?}"
+            )));
+        }
+    }
+
+    if broadcast_validation == BroadcastValidation::Gossip {
+        return Ok(());
+    }
+
+    state
+        .process_block(block, &beacon_chain.execution_engine)
+        .await
+        .map_err(|err| {
+            ApiError::BadRequest(format!("Block failed the state transition: {err:?}"))
+        })?;
+    if block.state_root != state.tree_hash_root() {
+        return Err(ApiError::BadRequest(
+            "Block state root does not match the post state".to_string(),
+        ));
+    }
+
+    if broadcast_validation == BroadcastValidation::ConsensusAndEquivocation {
+        let known_block_root =
+            db.slot_index_provider()
+                .get(block.slot.as_u64())
+                .map_err(|err| {
+                    ApiError::InternalError(format!("Failed to get block root, error: {err:?}"))
+                })?;
+        if let Some(known_block_root) = known_block_root
+            && known_block_root != block.block_root()
+            && let Some(known_block) =
+                db.beacon_block_provider()
+                    .get(known_block_root)
+                    .map_err(|err| {
+                        ApiError::InternalError(format!("Failed to get block, error: {err:?}"))
+                    })?
+            && known_block.message.proposer_index == block.proposer_index
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Block equivocates block {known_block_root} of proposer {} at slot {}",
+                block.proposer_index, block.slot
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the blob sidecars of `signed_block` from the blobs its commitments commit to and their
+/// KZG proofs, which are checked against the commitments.
+fn build_blob_sidecars(
+    signed_block: &SignedBeaconBlock,
+    blobs: Vec<Blob>,
+    kzg_proofs: Vec<KZGProof>,
+) -> Result<Vec<BlobSidecar>, ApiError> {
+    let commitment_count = signed_block.message.body.blob_kzg_commitments.len();
+    if blobs.len() != commitment_count || kzg_proofs.len() != commitment_count {
+        return Err(ApiError::BadRequest(format!(
+            "The block commits to {commitment_count} blobs, but {} blobs and {} proofs were given",
+            blobs.len(),
+            kzg_proofs.len()
+        )));
+    }
+
+    let blob_sidecars = signed_block
+        .get_blob_sidecars(blobs, kzg_proofs)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to build blob sidecars, error: {err:?}"))
+        })?;
+    verify_blob_sidecars(&blob_sidecars)
+        .map_err(|err| ApiError::BadRequest(format!("Invalid blobs, error: {err:?}")))?;
+    Ok(blob_sidecars)
+}
+
+/// Validate, gossip and import a block published through the API, together with its blob
+/// sidecars. The sidecars are gossiped after the block, and stored before it is imported. A block
+/// which passes validation but fails to import is still broadcast and answered with
+/// `202 Accepted`.
+async fn publish_block(
+    db: &BeaconDB,
+    beacon_chain: &BeaconChain,
+    p2p_sender: &P2PSender,
+    broadcast_validation: BroadcastValidation,
+    signed_block: SignedBeaconBlock,
+    blob_sidecars: Vec<BlobSidecar>,
+) -> Result<HttpResponse, ApiError> {
+    let block_root = signed_block.message.block_root();
+
+    let is_known = db
+        .beacon_block_provider()
+        .get(block_root)
+        .map_err(|err| ApiError::InternalError(format!("Failed to get block, error: {err:?}")))?
+        .is_some();
+    if is_known {
+        return Ok(HttpResponse::Ok().finish());
+    }
+
+    validate_published_block(db, beacon_chain, &signed_block, broadcast_validation).await?;
+
+    p2p_sender.send_gossip(GossipMessage {
+        topic: GossipTopic {
+            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
+            kind: GossipTopicKind::BeaconBlock,
+        },
+        data: signed_block.as_ssz_bytes(),
+    });
+
+    for blob_sidecar in blob_sidecars {
+        p2p_sender.send_gossip(GossipMessage {
+            topic: GossipTopic {
+                fork: beacon_network_spec().fork_digest(genesis_validators_root()),
+                kind: GossipTopicKind::BlobSidecar(compute_subnet_for_blob_sidecar(
+                    blob_sidecar.index,
+                )),
+            },
+            data: blob_sidecar.as_ssz_bytes(),
+        });
+        db.blobs_and_proofs_provider()
+            .insert(
+                BlobIdentifier::new(block_root, blob_sidecar.index),
+                BlobAndProofV1::from(blob_sidecar),
+            )
+            .map_err(|err| {
+                ApiError::InternalError(format!("Failed to store blob sidecar, error: {err:?}"))
+            })?;
+    }
+
+    let slot = signed_block.message.slot;
+    if let Err(err) = beacon_chain.process_block(signed_block).await {
+        error!("Failed to import published block {block_root} at slot {slot}, error: {err:?}");
+        return Ok(HttpResponse::Accepted().finish());
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Called by `/eth/v2/beacon/blocks` to publish a signed block with its blobs and their KZG
+/// proofs. The block is checked according to the `broadcast_validation` query parameter and the
+/// proofs against the commitments of the block, then the block and its blob sidecars are gossiped
+/// and imported.
+#[post("/beacon/blocks")]
+pub async fn post_block(
+    http_request: HttpRequest,
+    db: Data<BeaconDB>,
+    beacon_chain: Data<Arc<BeaconChain>>,
+    p2p_sender: Data<P2PSender>,
+    query: Query<BroadcastValidationQuery>,
+    signed_block_contents: JsonOrSsz<SignedBlockContents>,
+) -> Result<impl Responder, ApiError> {
+    ensure_consensus_version(&http_request)?;
+    let SignedBlockContents {
+        signed_block,
+        kzg_proofs,
+        blobs,
+    } = signed_block_contents.into_inner();
+    let blob_sidecars = build_blob_sidecars(&signed_block, blobs, kzg_proofs)?;
+    publish_block(
+        &db,
+        &beacon_chain,
+        &p2p_sender,
+        query.broadcast_validation,
+        signed_block,
+        blob_sidecars,
+    )
+    .await
+}
+
+/// Called by `/eth/v2/beacon/blinded_blocks` to publish a signed blinded block. The block is
+/// submitted to the MEV relay whose bid it was built from, which reveals the execution payload,
+/// falling back to the local payload of the slot, and the reconstructed block is then published
+/// like a full block, together with the blob sidecars built from the revealed blobs.
+#[post("/beacon/blinded_blocks")]
+pub async fn post_blinded_block(
+    http_request: HttpRequest,
+    db: Data<BeaconDB>,
+    beacon_chain: Data<Arc<BeaconChain>>,
+    p2p_sender: Data<P2PSender>,
+    builder_client: Data<Option<Arc<BuilderClient>>>,
+    query: Query<BroadcastValidationQuery>,
+    signed_blinded_block: JsonOrSsz<SignedBlindedBeaconBlock>,
+) -> Result<impl Responder, ApiError> {
+    ensure_consensus_version(&http_request)?;
+    let Some(builder_client) = builder_client.as_ref() else {
+        return Err(ApiError::BadRequest(
+            "No MEV relay is configured to unblind the block".to_string(),
+        ));
+    };
+
+    let signed_blinded_block = signed_blinded_block.into_inner();
+    let ExecutionPayloadAndBlobsBundle {
+        execution_payload,
+        blobs_bundle,
+    } = builder_client
+        .reveal_payload(
+            &signed_blinded_block,
+            beacon_chain.execution_engine.as_ref(),
+        )
+        .await
+        .map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to reveal the execution payload of the blinded block, error: {err:?}"
+            ))
+        })?;
+    let signed_block = signed_blinded_block
+        .unblind(execution_payload)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to unblind block, error: {err:?}"))
+        })?;
+
+    if blobs_bundle.commitments[..] != signed_block.message.body.blob_kzg_commitments[..] {
+        return Err(ApiError::InternalError(
+            "The revealed blobs don't match the commitments of the block".to_string(),
+        ));
     }
+    let blob_sidecars = build_blob_sidecars(
+        &signed_block,
+        blobs_bundle.blobs.to_vec(),
+        blobs_bundle.proofs.to_vec(),
+    )?;
+
+    publish_block(
+        &db,
+        &beacon_chain,
+        &p2p_sender,
+        query.broadcast_validation,
+        signed_block,
+        blob_sidecars,
+    )
+    .await
 }
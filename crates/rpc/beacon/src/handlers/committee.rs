@@ -1,4 +1,4 @@
-use std::vec;
+use std::sync::Arc;
 
 use actix_web::{
     HttpResponse, Responder, get,
@@ -9,8 +9,11 @@ use ream_api_types_beacon::{
     responses::BeaconResponse,
 };
 use ream_api_types_common::{error::ApiError, id::ID};
-use ream_consensus_misc::{constants::beacon::SLOTS_PER_EPOCH, misc::compute_start_slot_at_epoch};
-use ream_storage::db::beacon::BeaconDB;
+use ream_consensus_misc::{
+    constants::beacon::SLOTS_PER_EPOCH,
+    misc::{compute_epoch_at_slot, compute_start_slot_at_epoch},
+};
+use ream_storage::{cache::ShufflingCache, db::beacon::BeaconDB};
 use serde::Serialize;
 
 use super::state::get_state_from_id;
@@ -44,38 +47,53 @@ pub async fn get_committees(
     index: Query<IndexQuery>,
     slot: Query<SlotQuery>,
     db: Data<BeaconDB>,
+    shuffling_cache: Data<Arc<ShufflingCache>>,
 ) -> Result<impl Responder, ApiError> {
     let state = get_state_from_id(state_id.into_inner(), &db).await?;
-    let epoch = epoch.epoch.unwrap_or(state.get_current_epoch());
-    let committees_per_slot = state.get_committee_count_per_slot(epoch);
+    let current_epoch = state.get_current_epoch();
+    let epoch = epoch.epoch.unwrap_or(current_epoch);
+    if epoch + 1 < current_epoch || epoch > current_epoch + 1 {
+        return Err(ApiError::BadRequest(format!(
+            "State at epoch {current_epoch} has no committees for epoch {epoch}"
+        )));
+    }
 
-    let slots: Vec<u64> = match slot.slot {
-        Some(slot) => vec![slot],
+    let shuffling = shuffling_cache
+        .get_or_compute(&state, epoch)
+        .await
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to compute shuffling, error: {err:?}"))
+        })?;
+
+    let slots = match slot.slot {
+        Some(slot) if compute_epoch_at_slot(slot) != epoch => {
+            return Err(ApiError::BadRequest(format!(
+                "Slot {slot} is not in epoch {epoch}"
+            )));
+        }
+        Some(slot) => slot..slot + 1,
         None => {
             let start_slot = compute_start_slot_at_epoch(epoch);
-            (start_slot..(start_slot + SLOTS_PER_EPOCH)).collect()
+            start_slot..start_slot + SLOTS_PER_EPOCH
         }
     };
-
-    let indices: Vec<u64> = match index.index {
-        Some(index) => vec![index],
-        None => (0..(committees_per_slot * SLOTS_PER_EPOCH)).collect(),
+    let indices = match index.index {
+        Some(index) if index >= shuffling.committees_per_slot => {
+            return Err(ApiError::BadRequest(format!(
+                "Committee index {index} is out of range, there are {} committees per slot",
+                shuffling.committees_per_slot
+            )));
+        }
+        Some(index) => index..index + 1,
+        None => 0..shuffling.committees_per_slot,
     };
 
-    let mut result: Vec<CommitteeData> = Vec::with_capacity(slots.len() * indices.len());
-
-    for slot in &slots {
-        for index in &indices {
-            let committee = state.get_beacon_committee(*slot, *index).map_err(|err| {
-                ApiError::NotFound(format!(
-                    "Committee with slot: {slot} and index: {index} not found {err:?}"
-                ))
-            })?;
-            result.push(CommitteeData {
-                index: *index,
-                slot: *slot,
-                validators: committee,
-            });
+    let mut result = vec![];
+    for slot in slots {
+        for index in indices.clone() {
+            if let Some(committee) = shuffling.committee(slot, index) {
+                result.push(CommitteeData::new(index, slot, committee.to_vec()));
+            }
         }
     }
 
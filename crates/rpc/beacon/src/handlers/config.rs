@@ -66,5 +66,10 @@ pub async fn get_config_deposit_contract() -> Result<impl Responder, ApiError> {
 /// Called by `config/fork_schedule` to get fork schedule
 #[get("config/fork_schedule")]
 pub async fn get_fork_schedule() -> Result<impl Responder, ApiError> {
-    Ok(HttpResponse::Ok().json(DataResponse::new(beacon_network_spec().fork_schedule())))
+    Ok(HttpResponse::Ok().json(DataResponse::new(
+        beacon_network_spec()
+            .fork_schedule()
+            .scheduled()
+            .collect::<Vec<_>>(),
+    )))
 }
@@ -1,30 +1,45 @@
 use std::{collections::HashSet, sync::Arc};
 
 use actix_web::{
-    HttpResponse, Responder, get,
+    HttpRequest, HttpResponse, Responder, get,
     web::{Data, Path},
 };
 use hashbrown::HashMap;
 use ream_api_types_beacon::responses::{
-    BeaconHeadResponse, BeaconResponse, DataResponse, ForkChoiceNode, ForkChoiceResponse,
-    ForkChoiceValidity,
+    BeaconHeadResponse, BeaconVersionedResponse, DataResponse, ETH_CONSENSUS_VERSION_HEADER,
+    ForkChoiceNode, ForkChoiceResponse, ForkChoiceValidity, VERSION,
 };
 use ream_api_types_common::{error::ApiError, id::ID};
 use ream_fork_choice::store::{BlockWithEpochInfo, Store};
+use ream_network_spec::networks::beacon_network_spec;
 use ream_operation_pool::OperationPool;
+use ream_rpc_common::content_type::{accepts_ssz, ssz_response};
 use ream_storage::{db::beacon::BeaconDB, tables::field::Field};
 use serde_json::json;
+use ssz::Encode;
 
 use crate::handlers::state::get_state_from_id;
 
+/// Called by `/eth/v2/debug/beacon/states/{state_id}` to get the full state, which is what
+/// checkpoint sync downloads the anchor state of another node with. The states which were deleted
+/// when the finalized chain was moved to the freezer are regenerated.
 #[get("/debug/beacon/states/{state_id}")]
 pub async fn get_debug_beacon_state(
+    http_request: HttpRequest,
     db: Data<BeaconDB>,
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(
-        get_state_from_id(state_id.into_inner(), &db).await?,
-    )))
+    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+
+    if accepts_ssz(&http_request) {
+        return Ok(ssz_response(
+            state.as_ssz_bytes(),
+            beacon_network_spec().fork_name_at_slot(state.slot.as_u64()),
+        ));
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
+        .json(BeaconVersionedResponse::new(state)))
 }
 
 #[get("/debug/beacon/heads")]
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use actix_web::{
     HttpResponse, Responder, get, post,
     web::{Data, Json, Path},
@@ -7,8 +9,8 @@ use ream_api_types_beacon::{
     responses::DutiesResponse,
 };
 use ream_api_types_common::{error::ApiError, id::ID};
-use ream_consensus_misc::{constants::beacon::SLOTS_PER_EPOCH, misc::compute_start_slot_at_epoch};
-use ream_storage::db::beacon::BeaconDB;
+use ream_consensus_misc::slot_epoch::{Epoch, Slot};
+use ream_storage::{cache::ShufflingCache, db::beacon::BeaconDB};
 
 use crate::handlers::state::get_state_from_id;
 
@@ -17,18 +19,17 @@ pub async fn get_proposer_duties(
     db: Data<BeaconDB>,
     epoch: Path<u64>,
 ) -> Result<impl Responder, ApiError> {
-    let epoch = epoch.into_inner();
-    let state = get_state_from_id(ID::Slot(compute_start_slot_at_epoch(epoch)), &db).await?;
+    let epoch = Epoch::new(epoch.into_inner());
+    let start_slot = epoch.start_slot();
+    let state = get_state_from_id(ID::Slot(start_slot.as_u64()), &db).await?;
     let dependent_root = state
-        .get_block_root_at_slot(compute_start_slot_at_epoch(epoch) - 1)
+        .get_block_root_at_slot((start_slot - 1).as_u64())
         .map_err(|err| ApiError::BadRequest(format!("Failed to get dependent root {err:?}")))?;
 
-    let start_slot = compute_start_slot_at_epoch(epoch);
-    let end_slot = start_slot + SLOTS_PER_EPOCH;
     let mut duties = vec![];
-    for slot in start_slot..end_slot {
+    for slot in epoch.slots() {
         let validator_index = state
-            .get_beacon_proposer_index(Some(slot))
+            .get_beacon_proposer_index(Some(slot.as_u64()))
             .map_err(|err| ApiError::BadRequest(err.to_string()))?;
         let Some(validator) = state.validators.get(validator_index as usize) else {
             return Err(ApiError::ValidatorNotFound(format!("{validator_index}")));
@@ -45,33 +46,32 @@ pub async fn get_proposer_duties(
 #[post("/validator/duties/attester/{epoch}")]
 pub async fn get_attester_duties(
     db: Data<BeaconDB>,
+    shuffling_cache: Data<Arc<ShufflingCache>>,
     epoch: Path<u64>,
     validator_indices: Json<Vec<u64>>,
 ) -> Result<impl Responder, ApiError> {
-    let epoch = epoch.into_inner();
-    let state = get_state_from_id(ID::Slot(compute_start_slot_at_epoch(epoch)), &db).await?;
+    let epoch = Epoch::new(epoch.into_inner());
+    let start_slot = epoch.start_slot();
+    let state = get_state_from_id(ID::Slot(start_slot.as_u64()), &db).await?;
     let dependent_root = state
-        .get_block_root_at_slot(compute_start_slot_at_epoch(epoch) - 1)
+        .get_block_root_at_slot((start_slot - 1).as_u64())
         .map_err(|err| ApiError::BadRequest(format!("Failed to get dependent root {err:?}")))?;
 
-    let validator_indices = validator_indices.into_inner();
-    let committees_at_slot = state.get_committee_count_per_slot(epoch);
+    let shuffling = shuffling_cache
+        .get_or_compute(&state, epoch.as_u64())
+        .await
+        .map_err(|err| ApiError::BadRequest(format!("Failed to compute shuffling: {err}")))?;
     let mut duties = vec![];
 
-    for validator_index in validator_indices {
+    for validator_index in validator_indices.into_inner() {
         let Some(validator) = state.validators.get(validator_index as usize) else {
             return Err(ApiError::ValidatorNotFound(format!(
                 "Validator with index {validator_index} not found in state at epoch {epoch}"
             )));
         };
 
-        if let Some((committee, committee_index, slot)) = state
-            .get_committee_assignment(epoch, validator_index)
-            .map_err(|err| {
-                ApiError::BadRequest(format!(
-                    "Failed to get committee assignment for validator {validator_index}: {err}"
-                ))
-            })?
+        if let Some((committee, committee_index, slot)) =
+            shuffling.committee_assignment(validator_index)
         {
             let validator_committee_index = committee
                 .iter()
@@ -84,9 +84,10 @@ pub async fn get_attester_duties(
                 public_key: validator.public_key.clone(),
                 validator_index,
                 committee_index,
-                committees_at_slot,
+                committee_length: committee.len() as u64,
+                committees_at_slot: shuffling.committees_per_slot,
                 validator_committee_index: validator_committee_index as u64,
-                slot,
+                slot: Slot::new(slot),
             });
         }
     }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use actix_web::{
     HttpResponse, Responder, get,
     web::{Data, Path, Query},
@@ -8,12 +10,17 @@ use ream_api_types_beacon::{
     responses::BeaconResponse,
 };
 use ream_api_types_common::{error::ApiError, id::ID};
+use ream_consensus_beacon::electra::beacon_block::SignedBeaconBlock;
 use ream_consensus_misc::beacon_block_header::SignedBeaconBlockHeader;
-use ream_storage::{db::beacon::BeaconDB, tables::table::Table};
+use ream_fork_choice::store::Store;
+use ream_operation_pool::OperationPool;
+use ream_storage::{
+    db::beacon::BeaconDB,
+    tables::{multimap_table::MultimapTable, table::Table},
+};
 use serde::{Deserialize, Serialize};
-use tree_hash::TreeHash;
 
-use super::block::get_beacon_block_from_id;
+use super::block::{get_beacon_block_from_id, get_block_root_from_id};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HeaderData {
@@ -32,52 +39,82 @@ impl HeaderData {
     }
 }
 
+/// Resolves whether blocks are part of the chain of the fork choice head.
+struct CanonicalChain {
+    store: Store,
+    head: B256,
+}
+
+impl CanonicalChain {
+    fn new(db: &BeaconDB, operation_pool: &Arc<OperationPool>) -> Result<Self, ApiError> {
+        let store = Store::new(db.clone(), operation_pool.clone());
+        let head = store.get_head().map_err(|err| {
+            ApiError::InternalError(format!("Failed to get head, error: {err:?}"))
+        })?;
+        Ok(Self { store, head })
+    }
+
+    fn header_data(&self, block: &SignedBeaconBlock) -> Result<HeaderData, ApiError> {
+        let root = block.message.block_root();
+        let canonical = self
+            .store
+            .get_ancestor(self.head, block.message.slot.as_u64())
+            .map_err(|err| {
+                ApiError::InternalError(format!("Failed to get ancestor, error: {err:?}"))
+            })?
+            == root;
+        Ok(HeaderData::new(root, canonical, block.signed_header()))
+    }
+}
+
+fn get_block(db: &BeaconDB, block_root: B256) -> Result<Option<SignedBeaconBlock>, ApiError> {
+    db.beacon_block_provider()
+        .get(block_root)
+        .map_err(|err| ApiError::InternalError(format!("Failed to get block, error: {err:?}")))
+}
+
 /// Called using `/eth/v1/beacon/headers`
-/// Optional paramaters `slot` and/or `parent_root`
+/// Optional paramaters `slot` and/or `parent_root`. Without a `parent_root` the header of the
+/// canonical block at `slot` (or of the head) is returned, otherwise the headers of every known
+/// child of `parent_root`.
 #[get("/beacon/headers")]
 pub async fn get_headers(
     db: Data<BeaconDB>,
+    operation_pool: Data<Arc<OperationPool>>,
     slot: Query<SlotQuery>,
     parent_root: Query<ParentRootQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let (header, root) = match (slot.slot, parent_root.parent_root) {
-        (None, None) => get_header_from_slot(None, &db).await?,
-        (None, Some(parent_root)) => {
-            // get parent block to have access to `slot`
-            let parent_block = db
-                .beacon_block_provider()
-                .get(parent_root)
-                .map_err(|err| {
-                    ApiError::InternalError(format!("Failed to get headers, error: {err:?}"))
-                })?
-                .ok_or_else(|| ApiError::NotFound(String::from("Unable to fetch parent block")))?;
-
-            // fetch block header at `slot+1`
-            let (child_header, child_block_root) =
-                get_header_from_slot(Some(parent_block.message.slot + 1), &db).await?;
+    let canonical_chain = CanonicalChain::new(&db, &operation_pool)?;
 
-            if child_header.message.parent_root != parent_root {
-                return Err(ApiError::NotFound(format!(
-                    "Header with parent root :{parent_root:?}"
-                )))?;
-            }
-
-            (child_header, child_block_root)
-        }
-        (Some(slot), None) => get_header_from_slot(Some(slot), &db).await?,
-        (Some(slot), Some(parent_root)) => {
-            let (header, root) = get_header_from_slot(Some(slot), &db).await?;
-            if header.message.parent_root == parent_root {
-                (header, root)
-            } else {
-                return Err(ApiError::NotFound(format!(
-                    "Header at slot: {slot} with parent root: {parent_root:?} not found"
-                )))?;
-            }
+    let blocks = match parent_root.parent_root {
+        Some(parent_root) => db
+            .parent_root_index_multimap_provider()
+            .get(parent_root)
+            .map_err(|err| {
+                ApiError::InternalError(format!("Failed to get child blocks, error: {err:?}"))
+            })?
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|block_root| get_block(&db, block_root).transpose())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|block| slot.slot.is_none_or(|slot| block.message.slot == slot))
+            .collect(),
+        None => {
+            let block_root = match slot.slot {
+                Some(slot) => get_block_root_from_id(ID::Slot(slot), &db).await?,
+                None => canonical_chain.head,
+            };
+            get_block(&db, block_root)?.into_iter().collect::<Vec<_>>()
         }
     };
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(HeaderData::new(root, true, header))))
+    let headers = blocks
+        .iter()
+        .map(|block| canonical_chain.header_data(block))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(HttpResponse::Ok().json(BeaconResponse::new(headers)))
 }
 
 /// Called using `/eth/v1/beacon/headers/{block_id}`
@@ -85,36 +122,10 @@ pub async fn get_headers(
 pub async fn get_headers_from_block(
     block_id: Path<ID>,
     db: Data<BeaconDB>,
+    operation_pool: Data<Arc<OperationPool>>,
 ) -> Result<impl Responder, ApiError> {
     let block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
-    let header = block.signed_header();
-
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(HeaderData::new(
-        header.message.tree_hash_root(),
-        true,
-        header,
-    ))))
-}
-
-pub async fn get_header_from_slot(
-    slot: Option<u64>,
-    db: &BeaconDB,
-) -> Result<(SignedBeaconBlockHeader, B256), ApiError> {
-    let slot = match slot {
-        Some(slot) => slot,
-        None => db
-            .slot_index_provider()
-            .get_highest_slot()
-            .map_err(|err| {
-                ApiError::InternalError(format!("Failed to get headers, error: {err:?}"))
-            })?
-            .ok_or_else(|| ApiError::NotFound(String::from("Unable to fetch latest slot")))?,
-    };
-
-    let beacon_block = get_beacon_block_from_id(ID::Slot(slot), db).await?;
-
-    let header = beacon_block.signed_header();
-    let root = header.tree_hash_root();
+    let canonical_chain = CanonicalChain::new(&db, &operation_pool)?;
 
-    Ok((header, root))
+    Ok(HttpResponse::Ok().json(BeaconResponse::new(canonical_chain.header_data(&block)?)))
 }
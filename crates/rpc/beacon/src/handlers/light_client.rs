@@ -4,8 +4,7 @@ use actix_web::{
 };
 use alloy_primitives::B256;
 use ream_api_types_beacon::responses::{
-    DataVersionedResponse, ETH_CONSENSUS_VERSION_HEADER, JSON_CONTENT_TYPE, SSZ_CONTENT_TYPE,
-    VERSION,
+    DataVersionedResponse, ETH_CONSENSUS_VERSION_HEADER, JSON_CONTENT_TYPE, VERSION,
 };
 use ream_api_types_common::error::ApiError;
 use ream_consensus_misc::constants::beacon::{EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SLOTS_PER_EPOCH};
@@ -14,6 +13,8 @@ use ream_light_client::{
     header::LightClientHeader, optimistic_update::LightClientOptimisticUpdate,
     update::LightClientUpdate,
 };
+use ream_network_spec::networks::beacon_network_spec;
+use ream_rpc_common::content_type::{accepts_ssz, ssz_response};
 use ream_storage::{
     db::beacon::BeaconDB,
     tables::{field::Field, table::Table},
@@ -263,23 +264,21 @@ pub async fn get_light_client_finality_update(
             })?
             .into(),
         sync_aggregate: head_block.message.body.sync_aggregate,
-        signature_slot: head_block.message.slot,
+        signature_slot: head_block.message.slot.as_u64(),
     };
 
     // Check Accept header for response format
-    let response = match http_request
-        .headers()
-        .get("accept")
-        .and_then(|header| header.to_str().ok())
-    {
-        Some(SSZ_CONTENT_TYPE) => HttpResponse::Ok()
-            .content_type(SSZ_CONTENT_TYPE)
-            .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
-            .body(finality_update.as_ssz_bytes()),
-        _ => HttpResponse::Ok()
+    let response = if accepts_ssz(&http_request) {
+        ssz_response(
+            finality_update.as_ssz_bytes(),
+            beacon_network_spec()
+                .fork_name_at_slot(finality_update.attested_header.beacon.slot.as_u64()),
+        )
+    } else {
+        HttpResponse::Ok()
             .content_type(JSON_CONTENT_TYPE)
             .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
-            .json(DataVersionedResponse::new(finality_update)),
+            .json(DataVersionedResponse::new(finality_update))
     };
 
     Ok(response)
@@ -337,23 +336,21 @@ pub async fn get_light_client_optimistic_update(
     let optimistic_update = LightClientOptimisticUpdate {
         attested_header,
         sync_aggregate: head_block.message.body.sync_aggregate,
-        signature_slot: head_block.message.slot,
+        signature_slot: head_block.message.slot.as_u64(),
     };
 
     // Check Accept header for response format
-    let response = match http_request
-        .headers()
-        .get("accept")
-        .and_then(|header| header.to_str().ok())
-    {
-        Some(SSZ_CONTENT_TYPE) => HttpResponse::Ok()
-            .content_type(SSZ_CONTENT_TYPE)
-            .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
-            .body(optimistic_update.as_ssz_bytes()),
-        _ => HttpResponse::Ok()
+    let response = if accepts_ssz(&http_request) {
+        ssz_response(
+            optimistic_update.as_ssz_bytes(),
+            beacon_network_spec()
+                .fork_name_at_slot(optimistic_update.attested_header.beacon.slot.as_u64()),
+        )
+    } else {
+        HttpResponse::Ok()
             .content_type(JSON_CONTENT_TYPE)
             .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
-            .json(DataVersionedResponse::new(optimistic_update)),
+            .json(DataVersionedResponse::new(optimistic_update))
     };
 
     Ok(response)
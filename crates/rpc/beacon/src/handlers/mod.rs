@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod blob_sidecar;
 pub mod block;
 pub mod committee;
@@ -10,6 +11,10 @@ pub mod light_client;
 pub mod peers;
 pub mod pool;
 pub mod prepare_beacon_proposer;
+pub mod produce_block;
+pub mod register_validator;
 pub mod state;
+pub mod subscriptions;
 pub mod syncing;
 pub mod validator;
+pub mod validator_monitor;
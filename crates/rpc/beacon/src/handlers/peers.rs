@@ -38,6 +38,7 @@ pub async fn get_peer(
         state: cached_peer.state,
         direction: cached_peer.direction,
         enr: cached_peer.enr,
+        gossipsub_score: cached_peer.gossipsub_score,
     })))
 }
 
@@ -75,4 +76,8 @@ pub struct Peer {
     /// Ethereum Node Record (ENR), if known
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enr: Option<Enr>,
+
+    /// Gossipsub score of the peer, if it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gossipsub_score: Option<f64>,
 }
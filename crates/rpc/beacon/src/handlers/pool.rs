@@ -1,26 +1,63 @@
 use std::sync::Arc;
 
 use actix_web::{
-    HttpResponse, Responder, get, post,
-    web::{Data, Json},
+    HttpRequest, HttpResponse, Responder, get, post,
+    web::{Data, Query},
+};
+use ream_api_types_beacon::{
+    query::PoolAttestationQuery,
+    responses::{DataResponse, DataVersionedResponse},
 };
-use ream_api_types_beacon::responses::{DataResponse, DataVersionedResponse};
 use ream_api_types_common::{error::ApiError, id::ID};
 use ream_consensus_beacon::{
-    attester_slashing::AttesterSlashing, bls_to_execution_change::SignedBLSToExecutionChange,
-    proposer_slashing::ProposerSlashing, voluntary_exit::SignedVoluntaryExit,
+    attestation::compute_subnet_for_attestation, attester_slashing::AttesterSlashing,
+    bls_to_execution_change::SignedBLSToExecutionChange, electra::beacon_state::BeaconState,
+    proposer_slashing::ProposerSlashing, single_attestation::SingleAttestation,
+    voluntary_exit::SignedVoluntaryExit,
 };
-use ream_network_manager::service::NetworkManagerService;
+use ream_network_manager::p2p_sender::P2PSender;
 use ream_operation_pool::OperationPool;
 use ream_p2p::{
     gossipsub::beacon::topics::{GossipTopic, GossipTopicKind},
     network::beacon::channel::GossipMessage,
 };
+use ream_rpc_common::content_type::{JsonOrSsz, ensure_consensus_version};
 use ream_storage::db::beacon::BeaconDB;
 use ssz::Encode;
 
 use crate::handlers::state::get_state_from_id;
 
+/// The state at the highest known slot, which submitted operations are validated against.
+pub async fn get_head_state(db: &BeaconDB) -> Result<BeaconState, ApiError> {
+    let highest_slot = db
+        .slot_index_provider()
+        .get_highest_slot()
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
+        })?
+        .ok_or(ApiError::NotFound(
+            "Failed to find highest slot".to_string(),
+        ))?;
+    get_state_from_id(ID::Slot(highest_slot), db).await
+}
+
+/// Gossip an operation which was newly inserted into the pool. Operations which were already
+/// known have been gossiped before and are not republished.
+fn publish_operation(
+    p2p_sender: &P2PSender,
+    beacon_state: &BeaconState,
+    kind: GossipTopicKind,
+    data: Vec<u8>,
+) {
+    p2p_sender.send_gossip(GossipMessage {
+        topic: GossipTopic {
+            fork: beacon_state.fork.current_version,
+            kind,
+        },
+        data,
+    });
+}
+
 /// GET /eth/v1/beacon/pool/bls_to_execution_changes
 #[get("/beacon/pool/bls_to_execution_changes")]
 pub async fn get_bls_to_execution_changes(
@@ -36,41 +73,30 @@ pub async fn get_bls_to_execution_changes(
 pub async fn post_bls_to_execution_changes(
     db: Data<BeaconDB>,
     operation_pool: Data<Arc<OperationPool>>,
-    network_manager: Data<NetworkManagerService>,
-    signed_bls_to_execution_change: Json<SignedBLSToExecutionChange>,
+    p2p_sender: Data<P2PSender>,
+    signed_bls_to_execution_change: JsonOrSsz<SignedBLSToExecutionChange>,
 ) -> Result<impl Responder, ApiError> {
-    let highest_slot = db
-        .slot_index_provider()
-        .get_highest_slot()
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
-        })?
-        .ok_or(ApiError::NotFound(
-            "Failed to find highest slot".to_string(),
-        ))?;
-    let beacon_state = get_state_from_id(ID::Slot(highest_slot), &db).await?;
-
     let signed_bls_to_execution_change = signed_bls_to_execution_change.into_inner();
+    let beacon_state = get_head_state(&db).await?;
 
     beacon_state
-    .validate_bls_to_execution_change(&signed_bls_to_execution_change)
-    .map_err(|err| {
-        ApiError::BadRequest(format!(
-            "Invalid bls_to_execution_change, it will never pass validation so it's rejected: {err:?}"
-        ))
-    })?;
-
-    network_manager
-        .as_ref()
-        .p2p_sender
-        .send_gossip(GossipMessage {
-            topic: GossipTopic {
-                fork: beacon_state.fork.current_version,
-                kind: GossipTopicKind::BlsToExecutionChange,
-            },
-            data: signed_bls_to_execution_change.as_ssz_bytes(),
-        });
-    operation_pool.insert_signed_bls_to_execution_change(signed_bls_to_execution_change);
+        .validate_bls_to_execution_change(&signed_bls_to_execution_change)
+        .map_err(|err| {
+            ApiError::BadRequest(format!(
+                "Invalid bls_to_execution_change, it will never pass validation so it's rejected: {err:?}"
+            ))
+        })?;
+
+    let ssz_bytes = signed_bls_to_execution_change.as_ssz_bytes();
+    if operation_pool.insert_signed_bls_to_execution_change(signed_bls_to_execution_change) {
+        publish_operation(
+            &p2p_sender,
+            &beacon_state,
+            GossipTopicKind::BlsToExecutionChange,
+            ssz_bytes,
+        );
+    }
+
     Ok(HttpResponse::Ok())
 }
 
@@ -89,21 +115,11 @@ pub async fn get_voluntary_exits(
 pub async fn post_voluntary_exits(
     db: Data<BeaconDB>,
     operation_pool: Data<Arc<OperationPool>>,
-    network_manager: Data<NetworkManagerService>,
-    signed_voluntary_exit: Json<SignedVoluntaryExit>,
+    p2p_sender: Data<P2PSender>,
+    signed_voluntary_exit: JsonOrSsz<SignedVoluntaryExit>,
 ) -> Result<impl Responder, ApiError> {
-    let highest_slot = db
-        .slot_index_provider()
-        .get_highest_slot()
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
-        })?
-        .ok_or(ApiError::NotFound(
-            "Failed to find highest slot".to_string(),
-        ))?;
-    let beacon_state = get_state_from_id(ID::Slot(highest_slot), &db).await?;
-
     let signed_voluntary_exit = signed_voluntary_exit.into_inner();
+    let beacon_state = get_head_state(&db).await?;
 
     beacon_state
         .validate_voluntary_exit(&signed_voluntary_exit)
@@ -113,18 +129,79 @@ pub async fn post_voluntary_exits(
             ))
         })?;
 
-    network_manager
-        .as_ref()
-        .p2p_sender
-        .send_gossip(GossipMessage {
-            topic: GossipTopic {
-                fork: beacon_state.fork.current_version,
-                kind: GossipTopicKind::VoluntaryExit,
-            },
-            data: signed_voluntary_exit.as_ssz_bytes(),
-        });
-
-    operation_pool.insert_signed_voluntary_exit(signed_voluntary_exit);
+    let ssz_bytes = signed_voluntary_exit.as_ssz_bytes();
+    if operation_pool.insert_signed_voluntary_exit(signed_voluntary_exit) {
+        publish_operation(
+            &p2p_sender,
+            &beacon_state,
+            GossipTopicKind::VoluntaryExit,
+            ssz_bytes,
+        );
+    }
+
+    Ok(HttpResponse::Ok())
+}
+
+/// GET /eth/v2/beacon/pool/attestations
+#[get("/beacon/pool/attestations")]
+pub async fn get_pool_attestations(
+    operation_pool: Data<Arc<OperationPool>>,
+    query: Query<PoolAttestationQuery>,
+) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok().json(DataVersionedResponse::new(
+        operation_pool.get_attestations(query.slot, query.committee_index),
+    )))
+}
+
+/// POST /eth/v2/beacon/pool/attestations
+///
+/// Every attestation is validated on its own, the valid ones are pooled and gossiped even if
+/// others in the request fail.
+#[post("/beacon/pool/attestations")]
+pub async fn post_pool_attestations(
+    http_request: HttpRequest,
+    db: Data<BeaconDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    p2p_sender: Data<P2PSender>,
+    single_attestations: JsonOrSsz<Vec<SingleAttestation>>,
+) -> Result<impl Responder, ApiError> {
+    ensure_consensus_version(&http_request)?;
+    let beacon_state = get_head_state(&db).await?;
+
+    let mut failures = vec![];
+    for (index, single_attestation) in single_attestations.into_inner().into_iter().enumerate() {
+        let attestation = match beacon_state.single_attestation_to_attestation(&single_attestation)
+        {
+            Ok(attestation) => attestation,
+            Err(err) => {
+                failures.push(format!("{index}: {err}"));
+                continue;
+            }
+        };
+
+        if operation_pool.insert_attestation(attestation) {
+            let data = &single_attestation.data;
+            let subnet_id = compute_subnet_for_attestation(
+                beacon_state.get_committee_count_per_slot(data.target.epoch.as_u64()),
+                data.slot.as_u64(),
+                single_attestation.committee_index,
+            );
+            publish_operation(
+                &p2p_sender,
+                &beacon_state,
+                GossipTopicKind::BeaconAttestation(subnet_id),
+                single_attestation.as_ssz_bytes(),
+            );
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Some attestations failed validation: {}",
+            failures.join(", ")
+        )));
+    }
+
     Ok(HttpResponse::Ok())
 }
 
@@ -143,72 +220,53 @@ pub async fn get_attester_slashings(
 pub async fn post_attester_slashings(
     db: Data<BeaconDB>,
     operation_pool: Data<Arc<OperationPool>>,
-    network_manager: Data<Arc<NetworkManagerService>>,
-    attester_slashing: Json<AttesterSlashing>,
+    p2p_sender: Data<P2PSender>,
+    attester_slashing: JsonOrSsz<AttesterSlashing>,
 ) -> Result<impl Responder, ApiError> {
     let attester_slashing = attester_slashing.into_inner();
-
-    let highest_slot = db
-        .slot_index_provider()
-        .get_highest_slot()
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
-        })?
-        .ok_or(ApiError::NotFound(
-            "Failed to find highest slot".to_string(),
-        ))?;
-    let beacon_state = get_state_from_id(ID::Slot(highest_slot), &db).await?;
+    let beacon_state = get_head_state(&db).await?;
 
     beacon_state
         .get_slashable_attester_indices(&attester_slashing)
         .map_err(|err| {
-            ApiError::BadRequest(
-                format!("Invalid attester slashing, it will never pass validation so it's rejected, err: {err:?}"),
-            )
+            ApiError::BadRequest(format!(
+                "Invalid attester slashing, it will never pass validation so it's rejected: {err:?}"
+            ))
         })?;
-    network_manager.p2p_sender.send_gossip(GossipMessage {
-        topic: GossipTopic {
-            fork: beacon_state.fork.current_version,
-            kind: GossipTopicKind::AttesterSlashing,
-        },
-        data: attester_slashing.as_ssz_bytes(),
-    });
 
-    operation_pool.insert_attester_slashing(attester_slashing);
+    let ssz_bytes = attester_slashing.as_ssz_bytes();
+    if operation_pool.insert_attester_slashing(attester_slashing) {
+        publish_operation(
+            &p2p_sender,
+            &beacon_state,
+            GossipTopicKind::AttesterSlashing,
+            ssz_bytes,
+        );
+    }
 
     Ok(HttpResponse::Ok())
 }
 
-/// GET /eth/v2/beacon/pool/proposer_slashings
-#[get("/beacon/pool/prposer_slashings")]
+/// GET /eth/v1/beacon/pool/proposer_slashings
+#[get("/beacon/pool/proposer_slashings")]
 pub async fn get_proposer_slashings(
     operation_pool: Data<Arc<OperationPool>>,
 ) -> Result<impl Responder, ApiError> {
-    Ok(HttpResponse::Ok().json(DataVersionedResponse::new(
-        operation_pool.get_all_proposer_slahsings(),
+    Ok(HttpResponse::Ok().json(DataResponse::new(
+        operation_pool.get_all_proposer_slashings(),
     )))
 }
 
-/// POST /eth/v2/beacon/pool/proposer_slashing
+/// POST /eth/v1/beacon/pool/proposer_slashings
 #[post("/beacon/pool/proposer_slashings")]
 pub async fn post_proposer_slashings(
     db: Data<BeaconDB>,
     operation_pool: Data<Arc<OperationPool>>,
-    network_manager: Data<Arc<NetworkManagerService>>,
-    proposer_slashing: Json<ProposerSlashing>,
+    p2p_sender: Data<P2PSender>,
+    proposer_slashing: JsonOrSsz<ProposerSlashing>,
 ) -> Result<impl Responder, ApiError> {
     let proposer_slashing = proposer_slashing.into_inner();
-
-    let highest_slot = db
-        .slot_index_provider()
-        .get_highest_slot()
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
-        })?
-        .ok_or(ApiError::NotFound(
-            "Failed to find highest slot".to_string(),
-        ))?;
-    let beacon_state = get_state_from_id(ID::Slot(highest_slot), &db).await?;
+    let beacon_state = get_head_state(&db).await?;
 
     beacon_state
         .validate_proposer_slashing(&proposer_slashing)
@@ -218,16 +276,15 @@ pub async fn post_proposer_slashings(
             ))
         })?;
 
-    network_manager.p2p_sender.send_gossip(GossipMessage {
-        topic: {
-            GossipTopic {
-                fork: beacon_state.fork.current_version,
-                kind: GossipTopicKind::ProposerSlashing,
-            }
-        },
-        data: proposer_slashing.as_ssz_bytes(),
-    });
-    operation_pool.insert_proposer_slashing(proposer_slashing);
+    let ssz_bytes = proposer_slashing.as_ssz_bytes();
+    if operation_pool.insert_proposer_slashing(proposer_slashing) {
+        publish_operation(
+            &p2p_sender,
+            &beacon_state,
+            GossipTopicKind::ProposerSlashing,
+            ssz_bytes,
+        );
+    }
 
     Ok(HttpResponse::Ok())
 }
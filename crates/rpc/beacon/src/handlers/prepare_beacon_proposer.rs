@@ -10,6 +10,12 @@ use ream_fork_choice::store::Store;
 use ream_operation_pool::OperationPool;
 use ream_storage::db::beacon::BeaconDB;
 
+use crate::handlers::pool::get_head_state;
+
+/// POST /eth/v1/validator/prepare_beacon_proposer
+///
+/// The fee recipients are only accepted if every validator index is known, as the preparations of
+/// a request are applied together.
 #[post("/validator/prepare_beacon_proposer")]
 pub async fn prepare_beacon_proposer(
     db: Data<BeaconDB>,
@@ -22,6 +28,20 @@ pub async fn prepare_beacon_proposer(
         return Err(ApiError::BadRequest("Empty request body".to_string()));
     }
 
+    let validator_count = get_head_state(&db).await?.validators.len() as u64;
+    let failures = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.validator_index >= validator_count)
+        .map(|(index, item)| format!("{index}: unknown validator {}", item.validator_index))
+        .collect::<Vec<_>>();
+    if !failures.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Some preparations failed validation: {}",
+            failures.join(", ")
+        )));
+    }
+
     // Create a store instance to get the current epoch
     let store = Store::new(db.get_ref().clone(), operation_pool.get_ref().clone());
     let current_epoch = store
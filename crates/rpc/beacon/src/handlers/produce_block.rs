@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, get,
+    web::{Data, Path, Query},
+};
+use alloy_primitives::{Address, B256, U256};
+use ream_api_types_beacon::{
+    block::{FullBlockData, ProduceBlockData, ProduceBlockResponse},
+    query::ProduceBlockQuery,
+    responses::{ETH_CONSENSUS_VERSION_HEADER, SSZ_CONTENT_TYPE},
+};
+use ream_api_types_common::error::ApiError;
+use ream_bls::BLSSignature;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_beacon::{
+    electra::{
+        beacon_block::BeaconBlock, beacon_block_body::BeaconBlockBody, beacon_state::BeaconState,
+    },
+    sync_aggregate::SyncAggregate,
+};
+use ream_consensus_misc::{
+    constants::beacon::{MAX_BLS_TO_EXECUTION_CHANGES, MAX_VOLUNTARY_EXITS},
+    slot_epoch::Slot,
+};
+use ream_execution_engine::ExecutionEngine;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_operation_pool::OperationPool;
+use ream_rpc_common::content_type::accepts_ssz;
+use ream_storage::{
+    db::beacon::BeaconDB,
+    tables::{field::Field, table::Table},
+};
+use ream_validator_beacon::{
+    block_packing::{pack_attestations, pack_slashings},
+    execution_requests::prepare_execution_payload,
+};
+use ssz::Encode;
+use tree_hash::TreeHash;
+
+const WEI_PER_GWEI: u64 = 1_000_000_000;
+
+/// The hash of the execution block in `block_root`, which is zero for blocks before the merge or
+/// which aren't stored.
+fn get_execution_block_hash(db: &BeaconDB, block_root: B256) -> Result<B256, ApiError> {
+    Ok(db
+        .beacon_block_provider()
+        .get(block_root)
+        .map_err(|err| ApiError::InternalError(format!("Failed to get block, error: {err:?}")))?
+        .map(|block| block.message.body.execution_payload.block_hash)
+        .unwrap_or_default())
+}
+
+/// Produce an unsigned block for `slot` on top of the head. The execution payload is built by the
+/// execution engine, and the operations of the pool worth the most are packed into the block.
+#[get("/validator/blocks/{slot}")]
+pub async fn produce_block_v3(
+    http_request: HttpRequest,
+    db: Data<BeaconDB>,
+    beacon_chain: Data<Arc<BeaconChain>>,
+    operation_pool: Data<Arc<OperationPool>>,
+    slot: Path<u64>,
+    query: Query<ProduceBlockQuery>,
+) -> Result<impl Responder, ApiError> {
+    let slot = slot.into_inner();
+    let ProduceBlockQuery {
+        randao_reveal,
+        graffiti,
+    } = query.into_inner();
+    let execution_engine = beacon_chain.execution_engine.clone().ok_or_else(|| {
+        ApiError::InternalError("No execution engine to build the payload with".to_string())
+    })?;
+
+    let parent_root = {
+        let store = beacon_chain.store.lock().await;
+        if store.is_syncing().map_err(|err| {
+            ApiError::InternalError(format!("Failed to check syncing status, err: {err:?}"))
+        })? {
+            return Err(ApiError::UnderSyncing);
+        }
+
+        let current_slot = store.get_current_slot().map_err(|err| {
+            ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
+        })?;
+        if slot > current_slot + 1 {
+            return Err(ApiError::InvalidParameter(format!(
+                "Slot {slot} is too far ahead of the current slot {current_slot}"
+            )));
+        }
+
+        let head_root = store.get_head().map_err(|err| {
+            ApiError::InternalError(format!("Failed to get head, error: {err:?}"))
+        })?;
+        store.get_proposer_head(head_root, slot).map_err(|err| {
+            ApiError::InternalError(format!("Failed to get proposer head, error: {err:?}"))
+        })?
+    };
+
+    let mut state = db
+        .beacon_state_provider()
+        .get(parent_root)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get parent state, error: {err:?}"))
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("Parent state {parent_root} not found")))?;
+    if slot <= state.slot {
+        return Err(ApiError::InvalidParameter(format!(
+            "Slot {slot} is not after the slot {} of the parent block",
+            state.slot
+        )));
+    }
+    state.process_slots(slot).map_err(|err| {
+        ApiError::InternalError(format!("Failed to process slots, error: {err:?}"))
+    })?;
+    let proposer_index = state.get_beacon_proposer_index(None).map_err(|err| {
+        ApiError::InternalError(format!(
+            "Failed to get beacon proposer index, error: {err:?}"
+        ))
+    })?;
+
+    let justified_checkpoint = db.justified_checkpoint_provider().get().map_err(|err| {
+        ApiError::InternalError(format!(
+            "Failed to get justified checkpoint, error: {err:?}"
+        ))
+    })?;
+    let finalized_checkpoint = db.finalized_checkpoint_provider().get().map_err(|err| {
+        ApiError::InternalError(format!(
+            "Failed to get finalized checkpoint, error: {err:?}"
+        ))
+    })?;
+    let payload_id = prepare_execution_payload(
+        state.clone(),
+        get_execution_block_hash(&db, justified_checkpoint.root)?,
+        get_execution_block_hash(&db, finalized_checkpoint.root)?,
+        operation_pool
+            .get_proposer_preparation(proposer_index)
+            .unwrap_or(Address::ZERO),
+        execution_engine.clone(),
+    )
+    .await
+    .map_err(|err| ApiError::InternalError(format!("Failed to prepare payload, error: {err:?}")))?
+    .payload_id
+    .ok_or_else(|| {
+        ApiError::InternalError("Execution engine didn't start building a payload".to_string())
+    })?;
+    let built_payload = execution_engine
+        .get_payload(payload_id, parent_root)
+        .await
+        .map_err(|err| ApiError::InternalError(format!("Failed to get payload, error: {err:?}")))?;
+
+    let attestations = pack_attestations(&state, operation_pool.get_attestations(None, None))
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to pack attestations, error: {err:?}"))
+        })?;
+    let (mut proposer_slashings, mut attester_slashings) = pack_slashings(
+        &state,
+        operation_pool.get_all_proposer_slashings(),
+        operation_pool.get_all_attester_slashings(),
+    );
+
+    // The operations are processed one after the other on a scratch state, so an operation which
+    // an earlier one of the block made invalid, like the exit of a slashed validator, is left out
+    let mut scratch_state = state.clone();
+    proposer_slashings.retain(|slashing| scratch_state.process_proposer_slashing(slashing).is_ok());
+    attester_slashings.retain(|slashing| scratch_state.process_attester_slashing(slashing).is_ok());
+    let voluntary_exits = operation_pool
+        .get_signed_voluntary_exits()
+        .into_iter()
+        .filter(|exit| scratch_state.process_voluntary_exit(exit).is_ok())
+        .take(MAX_VOLUNTARY_EXITS)
+        .collect::<Vec<_>>();
+    let bls_to_execution_changes = operation_pool
+        .get_signed_bls_to_execution_changes()
+        .into_iter()
+        .filter(|change| {
+            scratch_state
+                .process_bls_to_execution_change(change)
+                .is_ok()
+        })
+        .take(MAX_BLS_TO_EXECUTION_CHANGES)
+        .collect::<Vec<_>>();
+
+    let mut block = BeaconBlock {
+        slot: Slot::new(slot),
+        proposer_index,
+        parent_root,
+        state_root: B256::ZERO,
+        body: BeaconBlockBody {
+            randao_reveal,
+            eth1_data: state.eth1_data.clone(),
+            graffiti: graffiti.unwrap_or_default(),
+            proposer_slashings: proposer_slashings.into(),
+            attester_slashings: attester_slashings.into(),
+            attestations: attestations.into(),
+            deposits: Default::default(),
+            voluntary_exits: voluntary_exits.into(),
+            sync_aggregate: SyncAggregate {
+                sync_committee_bits: Default::default(),
+                sync_committee_signature: BLSSignature::infinity(),
+            },
+            execution_payload: built_payload.execution_payload,
+            bls_to_execution_changes: bls_to_execution_changes.into(),
+            blob_kzg_commitments: built_payload.blobs_bundle.commitments,
+            execution_requests: built_payload.execution_requests,
+        },
+    };
+
+    // The payload was just built by the execution engine, so it isn't sent back to it
+    let mut post_state = state.clone();
+    post_state
+        .process_block(&block, &None::<ExecutionEngine>)
+        .await
+        .map_err(|err| {
+            ApiError::BadRequest(format!("Block failed the state transition: {err:?}"))
+        })?;
+    block.state_root = post_state.tree_hash_root();
+
+    let balance = |state: &BeaconState| state.balances.get(proposer_index as usize).copied();
+    let consensus_block_value = balance(&post_state)
+        .unwrap_or_default()
+        .saturating_sub(balance(&state).unwrap_or_default())
+        .saturating_mul(WEI_PER_GWEI);
+    let execution_payload_value =
+        U256::from_be_bytes(built_payload.block_value.0).saturating_to::<u64>();
+    let block_data = FullBlockData {
+        block,
+        kzg_proofs: built_payload.blobs_bundle.proofs.to_vec(),
+        blobs: built_payload.blobs_bundle.blobs.to_vec(),
+    };
+
+    let fork_name = beacon_network_spec().fork_name_at_slot(slot);
+    let mut response = HttpResponse::Ok();
+    response
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, fork_name.to_string()))
+        .insert_header(("Eth-Execution-Payload-Blinded", "false"))
+        .insert_header((
+            "Eth-Execution-Payload-Value",
+            execution_payload_value.to_string(),
+        ))
+        .insert_header((
+            "Eth-Consensus-Block-Value",
+            consensus_block_value.to_string(),
+        ));
+    if accepts_ssz(&http_request) {
+        return Ok(response
+            .content_type(SSZ_CONTENT_TYPE)
+            .body(block_data.as_ssz_bytes()));
+    }
+    Ok(response.json(ProduceBlockResponse {
+        version: fork_name.to_string(),
+        execution_payload_blinded: false,
+        execution_payload_value,
+        consensus_block_value,
+        data: ProduceBlockData::Full(block_data),
+    }))
+}
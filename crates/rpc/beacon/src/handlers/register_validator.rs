@@ -0,0 +1,76 @@
+use std::{collections::HashSet, sync::Arc};
+
+use actix_web::{HttpResponse, Responder, post, web::Data};
+use ream_api_types_common::error::ApiError;
+use ream_rpc_common::content_type::JsonOrSsz;
+use ream_storage::db::beacon::BeaconDB;
+use ream_validator_beacon::builder::{
+    builder_client::BuilderClient, validator_registration::SignedValidatorRegistrationV1,
+    verify::verify_registration_signature,
+};
+use tracing::debug;
+
+use crate::handlers::pool::get_head_state;
+
+/// POST /eth/v1/validator/register_validator
+///
+/// Every registration must be signed by a validator of the head state. The registrations are
+/// forwarded to the MEV relay, which is only sent the registrations it doesn't know yet. Without a
+/// relay there is nobody to register with, so the registrations are only validated.
+#[post("/validator/register_validator")]
+pub async fn register_validator(
+    db: Data<BeaconDB>,
+    builder_client: Data<Option<Arc<BuilderClient>>>,
+    signed_registrations: JsonOrSsz<Vec<SignedValidatorRegistrationV1>>,
+) -> Result<impl Responder, ApiError> {
+    let signed_registrations = signed_registrations.into_inner();
+    let beacon_state = get_head_state(&db).await?;
+
+    let requested_public_keys = signed_registrations
+        .iter()
+        .map(|registration| &registration.message.public_key)
+        .collect::<HashSet<_>>();
+    let known_public_keys = beacon_state
+        .validators
+        .iter()
+        .filter(|validator| requested_public_keys.contains(&validator.public_key))
+        .map(|validator| &validator.public_key)
+        .collect::<HashSet<_>>();
+
+    let mut failures = vec![];
+    for (index, registration) in signed_registrations.iter().enumerate() {
+        if !known_public_keys.contains(&registration.message.public_key) {
+            failures.push(format!("{index}: unknown validator"));
+            continue;
+        }
+        match verify_registration_signature(registration) {
+            Ok(true) => {}
+            Ok(false) => failures.push(format!("{index}: invalid signature")),
+            Err(err) => failures.push(format!("{index}: {err}")),
+        }
+    }
+    if !failures.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Some registrations failed validation: {}",
+            failures.join(", ")
+        )));
+    }
+
+    let Some(builder_client) = builder_client.as_ref() else {
+        debug!(
+            "Received {} validator registrations without a MEV relay to forward them to",
+            signed_registrations.len()
+        );
+        return Ok(HttpResponse::Ok());
+    };
+    builder_client
+        .register_validators(signed_registrations)
+        .await
+        .map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to register the validators with the MEV relay, error: {err:?}"
+            ))
+        })?;
+
+    Ok(HttpResponse::Ok())
+}
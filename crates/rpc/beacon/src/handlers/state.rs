@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use actix_web::{
     HttpResponse, Responder, get,
     web::{Data, Path, Query},
@@ -8,11 +10,13 @@ use ream_api_types_beacon::{
     responses::{BeaconResponse, BeaconVersionedResponse},
 };
 use ream_api_types_common::{error::ApiError, id::ID};
-use ream_consensus_beacon::electra::beacon_state::BeaconState;
+use ream_consensus_beacon::electra::beacon_state::{BeaconState, ValidatorsAndBalances};
 use ream_consensus_misc::{
-    checkpoint::Checkpoint, constants::beacon::SYNC_COMMITTEE_SIZE,
+    checkpoint::Checkpoint,
+    constants::beacon::{GENESIS_SLOT, SYNC_COMMITTEE_SIZE},
     misc::compute_sync_committee_period,
 };
+use ream_execution_engine::ExecutionEngine;
 use ream_storage::{
     db::beacon::BeaconDB,
     tables::{field::Field, table::Table},
@@ -65,8 +69,9 @@ struct SyncCommitteeResponse {
     pub validator_aggregates: Vec<QuotedU64Vec>,
 }
 
-pub async fn get_state_from_id(state_id: ID, db: &BeaconDB) -> Result<BeaconState, ApiError> {
-    let block_root = match state_id {
+/// Resolve `state_id` to the root of the block whose post state it names.
+pub async fn get_state_block_root_from_id(state_id: ID, db: &BeaconDB) -> Result<B256, ApiError> {
+    match state_id {
         ID::Finalized => {
             let finalized_checkpoint = db.finalized_checkpoint_provider().get().map_err(|err| {
                 ApiError::InternalError(format!(
@@ -85,21 +90,95 @@ pub async fn get_state_from_id(state_id: ID, db: &BeaconDB) -> Result<BeaconStat
 
             Ok(Some(justified_checkpoint.root))
         }
-        ID::Head | ID::Genesis => {
-            return Err(ApiError::NotFound(format!(
-                "This ID type is currently not supported: {state_id:?}"
-            )));
-        }
-        ID::Slot(slot) => db.slot_index_provider().get(slot),
+        ID::Head => db.head_root_provider().get().map(Some),
+        ID::Genesis => db.slot_index_provider().get(GENESIS_SLOT),
+        ID::Slot(slot) => Ok(db.get_canonical_block_root(slot).map_err(|err| {
+            ApiError::InternalError(format!("Failed to get block by slot, error: {err:?}"))
+        })?),
         ID::Root(root) => db.state_root_index_provider().get(root),
     }
     .map_err(|err| ApiError::InternalError(format!("Failed to get headers, error: {err:?}")))?
-    .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))?;
+    .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))
+}
+
+pub async fn get_state_from_id(state_id: ID, db: &BeaconDB) -> Result<BeaconState, ApiError> {
+    let block_root = get_state_block_root_from_id(state_id.clone(), db).await?;
+
+    let state = db.beacon_state_provider().get(block_root).map_err(|err| {
+        ApiError::InternalError(format!("Failed to get block by block_root, error: {err:?}"))
+    })?;
+    match state {
+        Some(state) => Some(state),
+        None => regenerate_state(block_root, db).await?,
+    }
+    .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))
+}
+
+/// Rebuild the post state of the finalized block `block_root`, which was deleted when the block
+/// was moved to the freezer, by applying the blocks since the closest earlier state which was
+/// kept, such as the state of the previous restore point.
+async fn regenerate_state(
+    block_root: B256,
+    db: &BeaconDB,
+) -> Result<Option<BeaconState>, ApiError> {
+    let beacon_block_provider = db.beacon_block_provider();
+    let beacon_state_provider = db.beacon_state_provider();
+
+    let mut blocks = vec![];
+    let mut root = block_root;
+    let mut state = loop {
+        let Some(block) = beacon_block_provider.get(root).map_err(|err| {
+            ApiError::InternalError(format!("Failed to get block {root}, error: {err:?}"))
+        })?
+        else {
+            return Ok(None);
+        };
+        root = block.message.parent_root;
+        blocks.push(block);
+        if let Some(state) = beacon_state_provider.get(root).map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to get state of block {root}, error: {err:?}"
+            ))
+        })? {
+            break state;
+        }
+    };
+
+    // The blocks were verified when they were imported, so only the state root is checked
+    for block in blocks.iter().rev() {
+        state
+            .state_transition(block, false, &None::<ExecutionEngine>)
+            .await
+            .map_err(|err| {
+                ApiError::InternalError(format!(
+                    "Failed to apply block {} while regenerating the state of block {block_root}, error: {err:?}",
+                    block.message.block_root()
+                ))
+            })?;
+    }
+    let expected_state_root = blocks[0].message.state_root;
+    if state.tree_hash_root() != expected_state_root {
+        return Err(ApiError::InternalError(format!(
+            "Regenerated state of block {block_root} doesn't have state root {expected_state_root}"
+        )));
+    }
+    Ok(Some(state))
+}
+
+/// Like [get_state_from_id], but only decodes the validator registry and the balances of the
+/// state.
+pub async fn get_validators_and_balances_from_id(
+    state_id: ID,
+    db: &BeaconDB,
+) -> Result<ValidatorsAndBalances, ApiError> {
+    let block_root = get_state_block_root_from_id(state_id.clone(), db).await?;
 
     db.beacon_state_provider()
-        .get(block_root)
+        .get_validators_and_balances(block_root)
         .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get block by block_root, error: {err:?}"))
+            ApiError::InternalError(format!(
+                "Failed to get validators and balances by block_root, error: {err:?}"
+            ))
         })?
         .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))
 }
@@ -236,17 +315,32 @@ pub async fn get_sync_committees(
         )));
     };
 
+    // Resolve every member with a single pass over the registry
+    let mut member_indices = sync_committee
+        .public_keys
+        .iter()
+        .map(|public_key| (public_key, None))
+        .collect::<HashMap<_, _>>();
+    for (index, validator) in state.validators.iter().enumerate() {
+        if let Some(member_index) = member_indices.get_mut(&validator.public_key) {
+            member_index.get_or_insert(index as u64);
+        }
+    }
     let validators = sync_committee
         .public_keys
         .iter()
-        .filter_map(|public_key| {
-            state
-                .validators
-                .iter()
-                .position(|validator| validator.public_key == *public_key)
-                .map(|position| position as u64)
+        .map(|public_key| {
+            member_indices
+                .get(public_key)
+                .copied()
+                .flatten()
+                .ok_or_else(|| {
+                    ApiError::InternalError(format!(
+                        "Sync committee member {public_key:?} is not in the validator registry"
+                    ))
+                })
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, _>>()?;
 
     let validator_aggregates = validators
         .chunks_exact((SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT) as usize)
@@ -254,7 +348,7 @@ pub async fn get_sync_committees(
         .collect::<Vec<QuotedU64Vec>>();
 
     Ok(
-        HttpResponse::Ok().json(BeaconVersionedResponse::new(SyncCommitteeResponse {
+        HttpResponse::Ok().json(BeaconResponse::new(SyncCommitteeResponse {
             validators,
             validator_aggregates,
         })),
@@ -0,0 +1,134 @@
+use actix_web::{
+    HttpResponse, Responder, post,
+    web::{Data, Json},
+};
+use ream_api_types_beacon::committee::{BeaconCommitteeSubscription, SyncCommitteeSubscription};
+use ream_api_types_common::error::ApiError;
+use ream_consensus_beacon::attestation::compute_subnet_for_attestation;
+use ream_consensus_misc::constants::beacon::{
+    MAX_COMMITTEES_PER_SLOT, SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
+};
+use ream_network_manager::p2p_sender::P2PSender;
+use ream_p2p::{
+    gossipsub::beacon::topics::GossipTopicKind, network::beacon::channel::SubnetSubscription,
+};
+use ream_storage::db::beacon::BeaconDB;
+use ream_validator_beacon::constants::SYNC_COMMITTEE_SUBNET_COUNT;
+
+use crate::handlers::pool::get_head_state;
+
+fn validation_error(failures: Vec<String>) -> Result<(), ApiError> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    Err(ApiError::BadRequest(format!(
+        "Some subscriptions failed validation: {}",
+        failures.join(", ")
+    )))
+}
+
+/// POST /eth/v1/validator/beacon_committee_subscriptions
+///
+/// The aggregators need the attestations of their committee, so the node subscribes to the
+/// subnet of the committee until the end of the slot of the duty. The other validators only
+/// publish their attestations, which doesn't need a subscription.
+#[post("/validator/beacon_committee_subscriptions")]
+pub async fn post_beacon_committee_subscriptions(
+    db: Data<BeaconDB>,
+    p2p_sender: Data<P2PSender>,
+    subscriptions: Json<Vec<BeaconCommitteeSubscription>>,
+) -> Result<impl Responder, ApiError> {
+    let subscriptions = subscriptions.into_inner();
+    let validator_count = get_head_state(&db).await?.validators.len() as u64;
+
+    let mut failures = vec![];
+    for (index, subscription) in subscriptions.iter().enumerate() {
+        if subscription.validator_index >= validator_count {
+            failures.push(format!(
+                "{index}: unknown validator {}",
+                subscription.validator_index
+            ));
+        } else if subscription.committees_at_slot == 0
+            || subscription.committees_at_slot > MAX_COMMITTEES_PER_SLOT
+        {
+            failures.push(format!(
+                "{index}: invalid committees at slot {}",
+                subscription.committees_at_slot
+            ));
+        } else if subscription.committee_index >= subscription.committees_at_slot {
+            failures.push(format!(
+                "{index}: committee index {} out of range",
+                subscription.committee_index
+            ));
+        }
+    }
+    validation_error(failures)?;
+
+    p2p_sender.subscribe_subnets(
+        subscriptions
+            .iter()
+            .filter(|subscription| subscription.is_aggregator)
+            .map(|subscription| SubnetSubscription {
+                kind: GossipTopicKind::BeaconAttestation(compute_subnet_for_attestation(
+                    subscription.committees_at_slot,
+                    subscription.slot.as_u64(),
+                    subscription.committee_index,
+                )),
+                until_slot: subscription.slot.as_u64() + 1,
+            })
+            .collect(),
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// POST /eth/v1/validator/sync_committee_subscriptions
+///
+/// Any member of a sync committee may be selected as an aggregator, so the node subscribes to the
+/// subnets of the members until `until_epoch` starts.
+#[post("/validator/sync_committee_subscriptions")]
+pub async fn post_sync_committee_subscriptions(
+    db: Data<BeaconDB>,
+    p2p_sender: Data<P2PSender>,
+    subscriptions: Json<Vec<SyncCommitteeSubscription>>,
+) -> Result<impl Responder, ApiError> {
+    let subscriptions = subscriptions.into_inner();
+    let validator_count = get_head_state(&db).await?.validators.len() as u64;
+
+    let mut failures = vec![];
+    for (index, subscription) in subscriptions.iter().enumerate() {
+        if subscription.validator_index >= validator_count {
+            failures.push(format!(
+                "{index}: unknown validator {}",
+                subscription.validator_index
+            ));
+        } else if let Some(sync_committee_index) = subscription
+            .sync_committee_indices
+            .iter()
+            .find(|sync_committee_index| **sync_committee_index >= SYNC_COMMITTEE_SIZE)
+        {
+            failures.push(format!(
+                "{index}: sync committee index {sync_committee_index} out of range"
+            ));
+        }
+    }
+    validation_error(failures)?;
+
+    let subnet_size = SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT;
+    p2p_sender.subscribe_subnets(
+        subscriptions
+            .iter()
+            .flat_map(|subscription| {
+                subscription
+                    .sync_committee_indices
+                    .iter()
+                    .map(|sync_committee_index| SubnetSubscription {
+                        kind: GossipTopicKind::SyncCommittee(sync_committee_index / subnet_size),
+                        until_slot: subscription.until_epoch * SLOTS_PER_EPOCH,
+                    })
+            })
+            .collect(),
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
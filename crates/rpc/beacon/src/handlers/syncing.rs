@@ -1,17 +1,23 @@
 use std::sync::Arc;
 
-use actix_web::{HttpResponse, Responder, get, web::Data};
+use actix_web::{
+    HttpResponse, Responder, get,
+    http::StatusCode,
+    web::{Data, Query},
+};
 use ream_api_types_beacon::{
+    query::HealthQuery,
     responses::{DataResponse, EXECUTION_OPTIMISTIC},
     sync::SyncStatus,
 };
 use ream_api_types_common::error::ApiError;
-use ream_execution_engine::ExecutionEngine;
+use ream_consensus_misc::slot_epoch::Slot;
+use ream_execution_engine::{ExecutionEngine, health::ExecutionEngineStatus};
 use ream_fork_choice::store::Store;
 use ream_operation_pool::OperationPool;
 use ream_storage::{db::beacon::BeaconDB, tables::table::Table};
+use ream_syncer::sync_progress::SyncProgress;
 use serde::{Deserialize, Serialize};
-use tracing::error;
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct Syncing {
@@ -19,7 +25,7 @@ pub struct Syncing {
 }
 
 impl Syncing {
-    pub fn new(head_slot: u64, sync_distance: u64, el_offline: bool, is_syncing: bool) -> Self {
+    pub fn new(head_slot: Slot, sync_distance: u64, el_offline: bool, is_syncing: bool) -> Self {
         Self {
             sync_status: SyncStatus {
                 head_slot,
@@ -32,16 +38,14 @@ impl Syncing {
     }
 }
 
-/// Called by `eth/v1/node/syncing` to get the Node Version.
-#[get("/node/syncing")]
-pub async fn get_syncing_status(
-    db: Data<BeaconDB>,
-    operation_pool: Data<Arc<OperationPool>>,
-    execution_engine: Data<Option<ExecutionEngine>>,
-) -> Result<impl Responder, ApiError> {
+/// Returns the head slot and how many slots it is behind the current slot.
+fn get_sync_distance(
+    db: &BeaconDB,
+    operation_pool: &Arc<OperationPool>,
+) -> Result<(Slot, u64), ApiError> {
     let store = Store {
-        db: db.get_ref().clone(),
-        operation_pool: operation_pool.get_ref().clone(),
+        db: db.clone(),
+        operation_pool: operation_pool.clone(),
     };
 
     // get head_slot
@@ -63,25 +67,71 @@ pub async fn get_syncing_status(
         ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
     })?;
 
-    let sync_distance = current_slot.saturating_sub(head_slot);
-
-    // get el_offline
-    let el_offline = match &**execution_engine {
-        Some(execution_engine) => match execution_engine.eth_chain_id().await {
-            Ok(_) => false,
-            Err(err) => {
-                error!("Execution engine is offline or erroring, error: {err:?}");
-                true
-            }
-        },
-        None => true,
-    };
+    Ok((
+        head_slot,
+        current_slot.saturating_sub(head_slot.as_u64()).as_u64(),
+    ))
+}
+
+/// Whether the block range sync is running or the head is more than a slot behind.
+fn is_syncing(sync_progress: &SyncProgress, sync_distance: u64) -> bool {
+    sync_progress.snapshot().is_syncing || sync_distance > 1
+}
+
+fn execution_engine_status(execution_engine: &Option<ExecutionEngine>) -> ExecutionEngineStatus {
+    match execution_engine {
+        Some(execution_engine) => execution_engine.status(),
+        None => ExecutionEngineStatus::Offline,
+    }
+}
+
+/// Called by `eth/v1/node/syncing` to get the sync status of the node.
+#[get("/node/syncing")]
+pub async fn get_syncing_status(
+    db: Data<BeaconDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    execution_engine: Data<Option<ExecutionEngine>>,
+    sync_progress: Data<Arc<SyncProgress>>,
+) -> Result<impl Responder, ApiError> {
+    let (head_slot, sync_distance) = get_sync_distance(&db, &operation_pool)?;
 
     Ok(HttpResponse::Ok().json(DataResponse::new(Syncing::new(
         head_slot,
         sync_distance,
-        el_offline,
-        // get is_syncing
-        sync_distance > 1,
+        execution_engine_status(&execution_engine) == ExecutionEngineStatus::Offline,
+        is_syncing(&sync_progress, sync_distance),
     ))))
 }
+
+/// Called by `eth/v1/node/health` to get the health of the node: 200 if it is ready, the
+/// `syncing_status` (206 by default) while it or its execution client is syncing, and 503 if its
+/// execution client is offline.
+#[get("/node/health")]
+pub async fn get_health(
+    db: Data<BeaconDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    execution_engine: Data<Option<ExecutionEngine>>,
+    sync_progress: Data<Arc<SyncProgress>>,
+    query: Query<HealthQuery>,
+) -> Result<impl Responder, ApiError> {
+    let syncing_status = match query.syncing_status {
+        Some(syncing_status) => StatusCode::from_u16(syncing_status).map_err(|_| {
+            ApiError::BadRequest(format!("Invalid syncing status code: {syncing_status}"))
+        })?,
+        None => StatusCode::PARTIAL_CONTENT,
+    };
+
+    let execution_engine_status = execution_engine_status(&execution_engine);
+    if execution_engine_status == ExecutionEngineStatus::Offline {
+        return Ok(HttpResponse::ServiceUnavailable().finish());
+    }
+
+    let (_, sync_distance) = get_sync_distance(&db, &operation_pool)?;
+    if is_syncing(&sync_progress, sync_distance)
+        || execution_engine_status == ExecutionEngineStatus::Syncing
+    {
+        return Ok(HttpResponse::build(syncing_status).finish());
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -17,41 +17,79 @@ use ream_consensus_beacon::{
     electra::beacon_state::BeaconState, sync_committe_selection::SyncCommitteeSelection,
 };
 use ream_consensus_misc::{
-    attestation_data::AttestationData, constants::beacon::SLOTS_PER_EPOCH, validator::Validator,
+    attestation_data::AttestationData, constants::beacon::SLOTS_PER_EPOCH, slot_epoch::Slot,
+    validator::Validator,
 };
 use ream_fork_choice::store::Store;
 use ream_operation_pool::OperationPool;
+use ream_rpc_common::json_stream::stream_beacon_response;
 use ream_storage::{db::beacon::BeaconDB, tables::field::Field};
 use serde::Serialize;
 
-use super::state::get_state_from_id;
+use super::state::{get_state_from_id, get_validators_and_balances_from_id};
 
 ///  For slots in Electra and later, this AttestationData must have a committee_index of 0.
 const ELECTRA_COMMITTEE_INDEX: u64 = 0;
 const MAX_VALIDATOR_COUNT: usize = 100;
 
-fn build_validator_balances(
-    validators: &[(Validator, u64)],
-    filter_ids: Option<&Vec<ValidatorID>>,
-) -> Vec<ValidatorBalance> {
-    // Turn the optional Vec<ValidatorID> into an optional HashSet for O(1) lookups
-    let filtered_ids = filter_ids.map(|ids| ids.iter().collect::<HashSet<_>>());
-
-    validators
-        .iter()
-        .enumerate()
-        .filter(|(idx, (validator, _))| match &filtered_ids {
-            Some(ids) => {
-                ids.contains(&ValidatorID::Index(*idx as u64))
-                    || ids.contains(&ValidatorID::Address(validator.public_key.clone()))
+/// Indices of the validators named by `validator_ids`, in ascending order. Ids which don't match
+/// a validator are skipped, and every validator is returned if no ids are given.
+fn resolve_validator_indices(
+    validators: &[Validator],
+    validator_ids: Option<&[ValidatorID]>,
+) -> Vec<usize> {
+    let Some(validator_ids) = validator_ids.filter(|ids| !ids.is_empty()) else {
+        return (0..validators.len()).collect();
+    };
+
+    let mut indices = HashSet::new();
+    let mut public_keys = HashSet::new();
+    for validator_id in validator_ids {
+        match validator_id {
+            ValidatorID::Index(index) if (*index as usize) < validators.len() => {
+                indices.insert(*index as usize);
             }
-            None => true,
-        })
-        .map(|(idx, (_, balance))| ValidatorBalance {
-            index: idx as u64,
-            balance: *balance,
+            ValidatorID::Index(_) => {}
+            ValidatorID::Address(public_key) => {
+                public_keys.insert(public_key);
+            }
+        }
+    }
+
+    // Only scan the registry when public keys have to be resolved
+    if !public_keys.is_empty() {
+        indices.extend(
+            validators
+                .iter()
+                .enumerate()
+                .filter(|(_, validator)| public_keys.contains(&validator.public_key))
+                .map(|(index, _)| index),
+        );
+    }
+
+    let mut indices = indices.into_iter().collect::<Vec<_>>();
+    indices.sort_unstable();
+    indices
+}
+
+async fn get_validator_balances(
+    state_id: ID,
+    validator_ids: Option<&[ValidatorID]>,
+    db: &BeaconDB,
+) -> Result<HttpResponse, ApiError> {
+    let (validators, balances) = get_validators_and_balances_from_id(state_id, db).await?;
+
+    let validator_balances = resolve_validator_indices(&validators, validator_ids)
+        .into_iter()
+        .filter_map(|index| {
+            Some(ValidatorBalance {
+                index: index as u64,
+                balance: *balances.get(index)?,
+            })
         })
-        .collect()
+        .collect();
+
+    Ok(stream_beacon_response(validator_balances))
 }
 
 #[get("/beacon/states/{state_id}/validator/{validator_id}")]
@@ -294,30 +332,24 @@ pub async fn post_validator_identities_from_state(
     state_id: Path<ID>,
     validator_ids: Json<Vec<ValidatorID>>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-
-    let validator_ids_set: HashSet<ValidatorID> = validator_ids.into_inner().into_iter().collect();
-
-    let validator_identities: Vec<ValidatorIdentity> = state
-        .validators
-        .iter()
-        .enumerate()
-        .filter_map(|(index, validator)| {
-            if validator_ids_set.contains(&ValidatorID::Index(index as u64))
-                || validator_ids_set.contains(&ValidatorID::Address(validator.public_key.clone()))
-            {
-                Some(ValidatorIdentity {
+    let (validators, _) = get_validators_and_balances_from_id(state_id.into_inner(), &db).await?;
+
+    let validator_identities =
+        resolve_validator_indices(&validators, Some(validator_ids.as_slice()))
+            .into_iter()
+            .map(|index| {
+                let validator = &validators[index];
+                ValidatorIdentity {
                     index: index as u64,
                     public_key: validator.public_key.clone(),
                     activation_epoch: validator.activation_epoch,
-                })
-            } else {
-                None
-            }
-        })
-        .collect();
+                }
+            })
+            .collect();
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(validator_identities)))
+    Ok(stream_beacon_response::<ValidatorIdentity>(
+        validator_identities,
+    ))
 }
 
 #[get("/beacon/states/{state_id}/validator_balances")]
@@ -326,36 +358,16 @@ pub async fn get_validator_balances_from_state(
     query: Query<IdQuery>,
     db: Data<BeaconDB>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-    Ok(
-        HttpResponse::Ok().json(BeaconResponse::new(build_validator_balances(
-            &state
-                .validators
-                .into_iter()
-                .zip(state.balances.into_iter())
-                .collect::<Vec<_>>(),
-            query.id.as_ref(),
-        ))),
-    )
+    get_validator_balances(state_id.into_inner(), query.id.as_deref(), &db).await
 }
 
 #[post("/beacon/states/{state_id}/validator_balances")]
 pub async fn post_validator_balances_from_state(
     state_id: Path<ID>,
-    body: Json<IdQuery>,
+    validator_ids: Json<Vec<ValidatorID>>,
     db: Data<BeaconDB>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-    Ok(
-        HttpResponse::Ok().json(BeaconResponse::new(build_validator_balances(
-            &state
-                .validators
-                .into_iter()
-                .zip(state.balances.into_iter())
-                .collect::<Vec<_>>(),
-            body.id.as_ref(),
-        ))),
-    )
+    get_validator_balances(state_id.into_inner(), Some(validator_ids.as_slice()), &db).await
 }
 
 #[derive(Debug, Serialize)]
@@ -456,7 +468,7 @@ pub async fn get_attestation_data(
 
     if slot > current_slot + 1 {
         return Err(ApiError::InvalidParameter(format!(
-            "Slot {slot:?} is too far ahead of the current slot {current_slot:?}"
+            "Slot {slot} is too far ahead of the current slot {current_slot}"
         )));
     }
 
@@ -480,7 +492,7 @@ pub async fn get_attestation_data(
         })?;
 
     Ok(HttpResponse::Ok().json(DataResponse::new(AttestationData {
-        slot,
+        slot: Slot::new(slot),
         index: ELECTRA_COMMITTEE_INDEX,
         beacon_block_root,
         source: source_checkpoint,
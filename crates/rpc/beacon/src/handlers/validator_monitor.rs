@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, get, web::Data};
+use ream_api_types_beacon::responses::DataResponse;
+use ream_api_types_common::error::ApiError;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+
+/// Called by `ream/v1/validator_monitor` to get the performance of the monitored validators.
+#[get("/validator_monitor")]
+pub async fn get_validator_monitor(
+    beacon_chain: Data<Arc<BeaconChain>>,
+) -> Result<impl Responder, ApiError> {
+    let validator_monitor = beacon_chain.validator_monitor.lock().await;
+    if !validator_monitor.is_enabled() {
+        return Err(ApiError::NotFound(
+            "No validators are monitored, start the node with --monitor-validators".to_string(),
+        ));
+    }
+
+    Ok(HttpResponse::Ok().json(DataResponse::new(validator_monitor.summaries())))
+}
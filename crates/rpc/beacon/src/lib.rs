@@ -6,27 +6,48 @@ use std::sync::Arc;
 
 use actix_web::web::Data;
 use config::RpcServerConfig;
+use ream_chain_beacon::beacon_chain::BeaconChain;
 use ream_execution_engine::ExecutionEngine;
+use ream_network_manager::{admin::AdminSender, p2p_sender::P2PSender};
 use ream_operation_pool::OperationPool;
 use ream_p2p::network::beacon::network_state::NetworkState;
 use ream_rpc_common::server::start_rpc_server;
-use ream_storage::db::beacon::BeaconDB;
+use ream_storage::{cache::ShufflingCache, db::beacon::BeaconDB};
+use ream_syncer::sync_progress::SyncProgress;
+use ream_validator_beacon::builder::builder_client::BuilderClient;
 
-use crate::routes::register_routers;
+use crate::{handlers::admin::LogFilterHandle, routes::register_routers};
 
 /// Start the Beacon API server.
+#[allow(clippy::too_many_arguments)]
 pub async fn start_server(
     server_config: RpcServerConfig,
     db: BeaconDB,
     network_state: Arc<NetworkState>,
     operation_pool: Arc<OperationPool>,
     execution_engine: Option<ExecutionEngine>,
+    p2p_sender: P2PSender,
+    beacon_chain: Arc<BeaconChain>,
+    builder_client: Option<Arc<BuilderClient>>,
+    admin_sender: AdminSender,
+    sync_progress: Arc<SyncProgress>,
+    log_filter_handle: Option<LogFilterHandle>,
 ) -> std::io::Result<()> {
+    let shuffling_cache = Arc::new(ShufflingCache::default());
+    let admin_token = server_config.admin_token;
     let server = start_rpc_server(server_config.http_socket_address, move |cfg| {
         cfg.app_data(Data::new(db.clone()))
             .app_data(Data::new(network_state.clone()))
             .app_data(Data::new(operation_pool.clone()))
             .app_data(Data::new(execution_engine.clone()))
+            .app_data(Data::new(p2p_sender.clone()))
+            .app_data(Data::new(shuffling_cache.clone()))
+            .app_data(Data::new(beacon_chain.clone()))
+            .app_data(Data::new(builder_client.clone()))
+            .app_data(Data::new(admin_sender.clone()))
+            .app_data(Data::new(sync_progress.clone()))
+            .app_data(Data::new(log_filter_handle.clone()))
+            .app_data(Data::new(admin_token.clone()))
             .configure(register_routers);
     })?;
 
@@ -4,7 +4,7 @@ use crate::handlers::{
     blob_sidecar::get_blob_sidecars,
     block::{
         get_blind_block, get_block_attestations, get_block_from_id, get_block_rewards,
-        get_block_root, get_genesis, post_sync_committee_rewards,
+        get_block_root, get_genesis, post_blinded_block, post_block, post_sync_committee_rewards,
     },
     committee::get_committees,
     header::{get_headers, get_headers_from_block},
@@ -13,9 +13,10 @@ use crate::handlers::{
         get_light_client_optimistic_update, get_light_client_updates,
     },
     pool::{
-        get_attester_slashings, get_bls_to_execution_changes, get_proposer_slashings,
-        get_voluntary_exits, post_attester_slashings, post_bls_to_execution_changes,
-        post_proposer_slashings, post_voluntary_exits,
+        get_attester_slashings, get_bls_to_execution_changes, get_pool_attestations,
+        get_proposer_slashings, get_voluntary_exits, post_attester_slashings,
+        post_bls_to_execution_changes, post_pool_attestations, post_proposer_slashings,
+        post_voluntary_exits,
     },
     state::{
         get_pending_consolidations, get_pending_deposits, get_pending_partial_withdrawals,
@@ -70,6 +71,10 @@ pub fn register_beacon_routes(cfg: &mut ServiceConfig) {
 pub fn register_beacon_routes_v2(cfg: &mut ServiceConfig) {
     cfg.service(get_block_attestations)
         .service(get_block_from_id)
+        .service(post_block)
+        .service(post_blinded_block)
         .service(get_attester_slashings)
-        .service(post_attester_slashings);
+        .service(post_attester_slashings)
+        .service(get_pool_attestations)
+        .service(post_pool_attestations);
 }
@@ -4,6 +4,7 @@ pub mod beacon;
 pub mod config;
 pub mod debug;
 pub mod node;
+pub mod ream;
 pub mod validator;
 
 pub fn get_v1_routes(config: &mut ServiceConfig) {
@@ -25,6 +26,19 @@ pub fn get_v2_routes(config: &mut ServiceConfig) {
     );
 }
 
+pub fn get_v3_routes(config: &mut ServiceConfig) {
+    config.service(scope("/eth/v3").configure(validator::register_validator_routes_v3));
+}
+
+/// Routes of the `ream` namespace, which are specific to ream and not part of the Beacon API.
+pub fn get_ream_v1_routes(config: &mut ServiceConfig) {
+    config.service(scope("/ream/v1").configure(ream::register_ream_routes));
+}
+
 pub fn register_routers(config: &mut ServiceConfig) {
-    config.configure(get_v1_routes).configure(get_v2_routes);
+    config
+        .configure(get_v1_routes)
+        .configure(get_v2_routes)
+        .configure(get_v3_routes)
+        .configure(get_ream_v1_routes);
 }
@@ -4,7 +4,7 @@ use ream_rpc_common::handlers::version::get_version;
 use crate::handlers::{
     identity::get_identity,
     peers::{get_peer, get_peer_count},
-    syncing::get_syncing_status,
+    syncing::{get_health, get_syncing_status},
 };
 
 pub fn register_node_routes(cfg: &mut ServiceConfig) {
@@ -12,5 +12,6 @@ pub fn register_node_routes(cfg: &mut ServiceConfig) {
         .service(get_peer)
         .service(get_peer_count)
         .service(get_syncing_status)
+        .service(get_health)
         .service(get_identity);
 }
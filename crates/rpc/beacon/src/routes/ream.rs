@@ -0,0 +1,27 @@
+use actix_web::{
+    middleware::from_fn,
+    web::{ServiceConfig, scope},
+};
+
+use crate::handlers::{
+    admin::{
+        authorize_admin, delete_peer, get_log_level, get_queues, post_ban_peer, post_flush_caches,
+        post_peer, post_resync, put_log_level,
+    },
+    validator_monitor::get_validator_monitor,
+};
+
+pub fn register_ream_routes(cfg: &mut ServiceConfig) {
+    cfg.service(get_validator_monitor).service(
+        scope("/admin")
+            .wrap(from_fn(authorize_admin))
+            .service(post_peer)
+            .service(delete_peer)
+            .service(post_ban_peer)
+            .service(post_resync)
+            .service(post_flush_caches)
+            .service(get_log_level)
+            .service(put_log_level)
+            .service(get_queues),
+    );
+}
@@ -3,6 +3,9 @@ use actix_web::web::ServiceConfig;
 use crate::handlers::{
     duties::{get_attester_duties, get_proposer_duties},
     prepare_beacon_proposer::prepare_beacon_proposer,
+    produce_block::produce_block_v3,
+    register_validator::register_validator,
+    subscriptions::{post_beacon_committee_subscriptions, post_sync_committee_subscriptions},
     validator::get_attestation_data,
 };
 
@@ -10,5 +13,12 @@ pub fn register_validator_routes(config: &mut ServiceConfig) {
     config.service(get_proposer_duties);
     config.service(get_attester_duties);
     config.service(prepare_beacon_proposer);
+    config.service(register_validator);
+    config.service(post_beacon_committee_subscriptions);
+    config.service(post_sync_committee_subscriptions);
     config.service(get_attestation_data);
 }
+
+pub fn register_validator_routes_v3(config: &mut ServiceConfig) {
+    config.service(produce_block_v3);
+}
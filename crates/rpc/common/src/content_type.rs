@@ -0,0 +1,154 @@
+use std::{future::Future, ops::Deref, pin::Pin};
+
+use actix_web::{
+    FromRequest, HttpRequest, HttpResponse,
+    dev::Payload,
+    error::ErrorBadRequest,
+    http::header::{Accept, CONTENT_TYPE, Header},
+    web::Bytes,
+};
+use ream_api_types_beacon::responses::{
+    ETH_CONSENSUS_VERSION_HEADER, JSON_CONTENT_TYPE, SSZ_CONTENT_TYPE, VERSION,
+};
+use ream_api_types_common::error::ApiError;
+use ream_consensus_misc::fork_name::ForkName;
+use serde::de::DeserializeOwned;
+use ssz::Decode;
+
+/// Whether the client ranks `application/octet-stream` above `application/json` in its `Accept`
+/// header. Clients without an `Accept` header get JSON.
+pub fn accepts_ssz(http_request: &HttpRequest) -> bool {
+    let Ok(accept) = Accept::parse(http_request) else {
+        return false;
+    };
+
+    accept
+        .ranked()
+        .into_iter()
+        .find(|mime| {
+            matches!(
+                mime.essence_str(),
+                SSZ_CONTENT_TYPE | JSON_CONTENT_TYPE | "*/*"
+            )
+        })
+        .is_some_and(|mime| mime.essence_str() == SSZ_CONTENT_TYPE)
+}
+
+/// Whether the request body is SSZ encoded according to its `Content-Type` header.
+pub fn is_ssz_body(http_request: &HttpRequest) -> bool {
+    http_request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|header| header.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with(SSZ_CONTENT_TYPE))
+}
+
+/// Reject requests whose `Eth-Consensus-Version` header is missing or names a fork other than the
+/// one the node serves.
+pub fn ensure_consensus_version(http_request: &HttpRequest) -> Result<(), ApiError> {
+    let version = http_request
+        .headers()
+        .get(ETH_CONSENSUS_VERSION_HEADER)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("Missing {ETH_CONSENSUS_VERSION_HEADER} header"))
+        })?
+        .to_str()
+        .map_err(|err| {
+            ApiError::BadRequest(format!(
+                "Invalid {ETH_CONSENSUS_VERSION_HEADER} header: {err}"
+            ))
+        })?;
+    if !version.eq_ignore_ascii_case(VERSION) {
+        return Err(ApiError::BadRequest(format!(
+            "Unsupported consensus version {version}, expected {VERSION}"
+        )));
+    }
+    Ok(())
+}
+
+/// A raw SSZ response carrying the `Eth-Consensus-Version` header of `fork_name`, the fork the
+/// returned object belongs to.
+pub fn ssz_response(ssz_bytes: Vec<u8>, fork_name: ForkName) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(SSZ_CONTENT_TYPE)
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, fork_name.to_string()))
+        .body(ssz_bytes)
+}
+
+/// A raw SSZ response, for the APIs which aren't versioned by fork like the lean API.
+pub fn unversioned_ssz_response(ssz_bytes: Vec<u8>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(SSZ_CONTENT_TYPE)
+        .body(ssz_bytes)
+}
+
+/// Extracts a request body which is either JSON or, when the `Content-Type` is
+/// `application/octet-stream`, SSZ encoded.
+#[derive(Debug)]
+pub struct JsonOrSsz<T>(pub T);
+
+impl<T> JsonOrSsz<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for JsonOrSsz<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned + Decode + 'static> FromRequest for JsonOrSsz<T> {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(http_request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let is_ssz = is_ssz_body(http_request);
+        let body = Bytes::from_request(http_request, payload);
+
+        Box::pin(async move {
+            let body = body.await?;
+            if is_ssz {
+                T::from_ssz_bytes(&body)
+                    .map(JsonOrSsz)
+                    .map_err(|err| ErrorBadRequest(format!("Invalid SSZ body: {err:?}")))
+            } else {
+                serde_json::from_slice(&body)
+                    .map(JsonOrSsz)
+                    .map_err(|err| ErrorBadRequest(format!("Invalid JSON body: {err}")))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::header::ACCEPT, test::TestRequest};
+
+    use super::*;
+
+    #[test]
+    fn test_accepts_ssz() {
+        let accepts = |accept: &str| {
+            accepts_ssz(
+                &TestRequest::default()
+                    .insert_header((ACCEPT, accept))
+                    .to_http_request(),
+            )
+        };
+
+        assert!(accepts(SSZ_CONTENT_TYPE));
+        assert!(accepts(
+            "application/octet-stream;q=1.0,application/json;q=0.9"
+        ));
+        assert!(!accepts(
+            "application/octet-stream;q=0.5,application/json;q=0.9"
+        ));
+        assert!(!accepts(JSON_CONTENT_TYPE));
+        assert!(!accepts("*/*"));
+        assert!(!accepts_ssz(&TestRequest::default().to_http_request()));
+    }
+}
@@ -0,0 +1,69 @@
+use actix_web::{HttpResponse, web::Bytes};
+use futures::stream;
+use ream_api_types_beacon::responses::{BeaconResponse, JSON_CONTENT_TYPE};
+use serde::Serialize;
+
+/// Number of items serialized into each chunk of a streamed response.
+const STREAM_CHUNK_SIZE: usize = 1024;
+
+/// A [BeaconResponse] whose `data` list is serialized lazily, chunk by chunk, while the response
+/// is written, instead of building the whole JSON body in memory first.
+pub fn stream_beacon_response<T: Serialize + 'static>(data: Vec<T>) -> HttpResponse {
+    let (prefix, suffix) = match serde_json::to_string(&BeaconResponse::new(Vec::<()>::new())) {
+        // The envelope ends with the empty `data` list, `[]}`
+        Ok(envelope) if envelope.ends_with("[]}") => {
+            let data_start = envelope.len() - 2;
+            (
+                envelope[..data_start].to_string(),
+                envelope[data_start..].to_string(),
+            )
+        }
+        _ => return HttpResponse::InternalServerError().body("Failed to serialize response"),
+    };
+
+    let mut items = data.into_iter().peekable();
+    let mut is_first_chunk = true;
+    let chunks = std::iter::from_fn(move || {
+        items.peek()?;
+
+        let mut chunk = vec![];
+        for item in items.by_ref().take(STREAM_CHUNK_SIZE) {
+            if !is_first_chunk || !chunk.is_empty() {
+                chunk.push(b',');
+            }
+            if let Err(err) = serde_json::to_writer(&mut chunk, &item) {
+                return Some(Err(err));
+            }
+        }
+        is_first_chunk = false;
+        Some(Ok(Bytes::from(chunk)))
+    });
+
+    HttpResponse::Ok()
+        .content_type(JSON_CONTENT_TYPE)
+        .streaming(stream::iter(
+            std::iter::once(Ok::<_, serde_json::Error>(Bytes::from(prefix)))
+                .chain(chunks)
+                .chain(std::iter::once(Ok(Bytes::from(suffix)))),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_stream_beacon_response() {
+        let data = (0..STREAM_CHUNK_SIZE as u64 + 2).collect::<Vec<_>>();
+        let body = to_bytes(stream_beacon_response(data.clone()).into_body())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            body,
+            serde_json::to_vec(&BeaconResponse::new(data)).unwrap()
+        );
+    }
+}
@@ -1,2 +1,4 @@
+pub mod content_type;
 pub mod handlers;
+pub mod json_stream;
 pub mod server;
@@ -1,32 +1,35 @@
 use actix_web::{
-    HttpResponse, Responder, get,
+    HttpRequest, HttpResponse, Responder, get,
     web::{Data, Path},
 };
+use alloy_primitives::B256;
 use ream_api_types_common::{error::ApiError, id::ID};
-use ream_chain_lean::lean_chain::LeanChainReader;
+use ream_chain_lean::lean_chain::{LeanChain, LeanChainReader};
 use ream_consensus_lean::block::Block;
+use ream_rpc_common::content_type::{accepts_ssz, unversioned_ssz_response};
 use ream_storage::tables::{field::Field, table::Table};
+use ssz::Encode;
 
 // GET /lean/v0/blocks/{block_id}
 #[get("/blocks/{block_id}")]
 pub async fn get_block(
+    http_request: HttpRequest,
     block_id: Path<ID>,
     lean_chain: Data<LeanChainReader>,
 ) -> Result<impl Responder, ApiError> {
-    Ok(HttpResponse::Ok().json(
-        get_block_by_id(block_id.into_inner(), lean_chain)
-            .await?
-            .ok_or_else(|| ApiError::NotFound("Block not found".to_string()))?,
-    ))
+    let block = get_block_by_id(block_id.into_inner(), lean_chain)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Block not found".to_string()))?;
+
+    if accepts_ssz(&http_request) {
+        return Ok(unversioned_ssz_response(block.as_ssz_bytes()));
+    }
+    Ok(HttpResponse::Ok().json(block))
 }
 
-// Retrieve a block from the lean chain by its block ID.
-pub async fn get_block_by_id(
-    block_id: ID,
-    lean_chain: Data<LeanChainReader>,
-) -> Result<Option<Block>, ApiError> {
-    let lean_chain = lean_chain.read().await;
-    let block_root = match block_id {
+// Resolve a block ID to the root of the block it refers to.
+pub async fn get_block_root_by_id(block_id: ID, lean_chain: &LeanChain) -> Result<B256, ApiError> {
+    match block_id {
         ID::Finalized => lean_chain
             .store
             .lock()
@@ -50,7 +53,16 @@ pub async fn get_block_by_id(
             .await
             .map_err(|err| ApiError::InternalError(format!("No block for slot {slot}: {err:?}"))),
         ID::Root(root) => Ok(root),
-    };
+    }
+}
+
+// Retrieve a block from the lean chain by its block ID.
+pub async fn get_block_by_id(
+    block_id: ID,
+    lean_chain: Data<LeanChainReader>,
+) -> Result<Option<Block>, ApiError> {
+    let lean_chain = lean_chain.read().await;
+    let block_root = get_block_root_by_id(block_id, &lean_chain).await;
 
     let provider = lean_chain.store.clone().lock().await.lean_block_provider();
     provider
@@ -0,0 +1,61 @@
+use actix_web::{HttpResponse, Responder, get, web::Data};
+use ream_api_types_common::error::ApiError;
+use ream_api_types_lean::fork_choice::{ForkChoiceNode, ForkChoiceResponse};
+use ream_chain_lean::lean_chain::LeanChainReader;
+use ream_storage::tables::field::Field;
+
+// GET /lean/v0/debug/fork_choice
+#[get("/debug/fork_choice")]
+pub async fn get_debug_fork_choice(
+    lean_chain: Data<LeanChainReader>,
+) -> Result<impl Responder, ApiError> {
+    let lean_chain = lean_chain.read().await;
+    let fork_choice = lean_chain.get_fork_choice().await.map_err(|err| {
+        ApiError::InternalError(format!("Failed to compute fork choice, error: {err:?}"))
+    })?;
+
+    let (blocks, justified, finalized) =
+        {
+            let db = lean_chain.store.lock().await;
+            let blocks = db.lean_block_provider().get_all_blocks().map_err(|err| {
+                ApiError::InternalError(format!("Failed to get blocks, error: {err:?}"))
+            })?;
+            let justified = db.latest_justified_provider().get().map_err(|err| {
+                ApiError::InternalError(format!("No latest justified hash: {err:?}"))
+            })?;
+            let finalized = db.latest_finalized_provider().get().map_err(|err| {
+                ApiError::InternalError(format!("No latest finalized hash: {err:?}"))
+            })?;
+            (blocks, justified, finalized)
+        };
+
+    let mut fork_choice_nodes = blocks
+        .into_iter()
+        .map(|(block_root, signed_block)| ForkChoiceNode {
+            slot: signed_block.message.slot,
+            block_root,
+            parent_root: signed_block.message.parent_root,
+            proposer_index: signed_block.message.proposer_index,
+            weight: fork_choice
+                .vote_weights
+                .get(&block_root)
+                .copied()
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    fork_choice_nodes.sort_by_key(|node| (node.slot, node.block_root));
+
+    let mut children = fork_choice.children_map;
+    for child_roots in children.values_mut() {
+        child_roots.sort();
+    }
+
+    Ok(HttpResponse::Ok().json(ForkChoiceResponse {
+        head: fork_choice.head,
+        safe_target: lean_chain.safe_target,
+        justified,
+        finalized,
+        fork_choice_nodes,
+        children,
+    }))
+}
@@ -0,0 +1,26 @@
+use actix_web::{HttpResponse, Responder, get, web::Data};
+use ream_api_types_common::error::ApiError;
+use ream_api_types_lean::finality::Finality;
+use ream_chain_lean::lean_chain::LeanChainReader;
+use ream_storage::tables::field::Field;
+
+// GET /lean/v0/finality
+#[get("/finality")]
+pub async fn get_finality(lean_chain: Data<LeanChainReader>) -> Result<impl Responder, ApiError> {
+    let lean_chain = lean_chain.read().await;
+    let db = lean_chain.store.lock().await;
+
+    let justified = db
+        .latest_justified_provider()
+        .get()
+        .map_err(|err| ApiError::InternalError(format!("No latest justified hash: {err:?}")))?;
+    let finalized = db
+        .latest_finalized_provider()
+        .get()
+        .map_err(|err| ApiError::InternalError(format!("No latest finalized hash: {err:?}")))?;
+
+    Ok(HttpResponse::Ok().json(Finality {
+        justified,
+        finalized,
+    }))
+}
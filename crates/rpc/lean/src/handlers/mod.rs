@@ -1,4 +1,9 @@
 pub mod block;
 pub mod block_header;
+pub mod debug;
+pub mod finality;
 pub mod head;
 pub mod peer;
+pub mod proof;
+pub mod state;
+pub mod vote;
@@ -0,0 +1,47 @@
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, get,
+    web::{Data, Path},
+};
+use alloy_primitives::B256;
+use ream_api_types_common::error::ApiError;
+use ream_api_types_lean::proof::Proof;
+use ream_chain_lean::lean_chain::LeanChainReader;
+use ream_prover::lean::LeanProof;
+use ream_rpc_common::content_type::{accepts_ssz, unversioned_ssz_response};
+use ream_storage::tables::table::Table;
+use ssz::Decode;
+
+// GET /lean/v0/proofs/{block_root}
+#[get("/proofs/{block_root}")]
+pub async fn get_proof(
+    http_request: HttpRequest,
+    block_root: Path<B256>,
+    lean_chain: Data<LeanChainReader>,
+) -> Result<impl Responder, ApiError> {
+    let block_root = block_root.into_inner();
+    let lean_proof_provider = lean_chain
+        .read()
+        .await
+        .store
+        .lock()
+        .await
+        .lean_proof_provider();
+    let proof_bytes = lean_proof_provider
+        .get(block_root)
+        .map_err(|err| ApiError::InternalError(format!("DB error: {err}")))?
+        .ok_or_else(|| ApiError::NotFound(format!("Proof not found for block {block_root}")))?;
+
+    if accepts_ssz(&http_request) {
+        return Ok(unversioned_ssz_response(proof_bytes));
+    }
+
+    let lean_proof = LeanProof::from_ssz_bytes(&proof_bytes).map_err(|err| {
+        ApiError::InternalError(format!("Failed to decode proof, error: {err:?}"))
+    })?;
+    Ok(HttpResponse::Ok().json(Proof {
+        block_root,
+        pre_state_root: lean_proof.output.pre_state_root,
+        post_state_root: lean_proof.output.post_state_root,
+        proof: lean_proof.proof.into(),
+    }))
+}
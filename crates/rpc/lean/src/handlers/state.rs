@@ -0,0 +1,59 @@
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, get,
+    web::{Data, Path},
+};
+use ream_api_types_common::{error::ApiError, id::ID};
+use ream_chain_lean::lean_chain::LeanChainReader;
+use ream_consensus_lean::state::LeanState;
+use ream_rpc_common::content_type::{accepts_ssz, unversioned_ssz_response};
+use ream_storage::tables::table::Table;
+use ssz::Encode;
+
+use super::block::get_block_root_by_id;
+
+// GET /lean/v0/states/{state_id}
+#[get("/states/{state_id}")]
+pub async fn get_state(
+    http_request: HttpRequest,
+    state_id: Path<ID>,
+    lean_chain: Data<LeanChainReader>,
+) -> Result<impl Responder, ApiError> {
+    let state = get_state_by_id(state_id.into_inner(), lean_chain)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("State not found".to_string()))?;
+
+    if accepts_ssz(&http_request) {
+        return Ok(unversioned_ssz_response(state.as_ssz_bytes()));
+    }
+    Ok(HttpResponse::Ok().json(state))
+}
+
+// Retrieve a post state of a block from the lean chain by its state ID. A root is a state root,
+// while the other IDs refer to the post state of the block they identify.
+pub async fn get_state_by_id(
+    state_id: ID,
+    lean_chain: Data<LeanChainReader>,
+) -> Result<Option<LeanState>, ApiError> {
+    let lean_chain = lean_chain.read().await;
+    let block_root = match state_id {
+        ID::Root(state_root) => {
+            let block_root = lean_chain
+                .store
+                .lock()
+                .await
+                .state_root_index_provider()
+                .get(state_root)
+                .map_err(|err| ApiError::InternalError(format!("DB error: {err}")))?;
+            match block_root {
+                Some(block_root) => block_root,
+                None => return Ok(None),
+            }
+        }
+        state_id => get_block_root_by_id(state_id, &lean_chain).await?,
+    };
+
+    let provider = lean_chain.store.lock().await.lean_state_provider();
+    provider
+        .get(block_root)
+        .map_err(|err| ApiError::InternalError(format!("DB error: {err}")))
+}
@@ -0,0 +1,52 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{Data, Path, Query},
+};
+use ream_api_types_common::error::ApiError;
+use ream_api_types_lean::vote::VoteQuery;
+use ream_chain_lean::lean_chain::LeanChainReader;
+
+// GET /lean/v0/votes?slot={slot}
+#[get("/votes")]
+pub async fn get_votes(
+    query: Query<VoteQuery>,
+    lean_chain: Data<LeanChainReader>,
+) -> Result<impl Responder, ApiError> {
+    let lean_chain = lean_chain.read().await;
+    let db = lean_chain.store.lock().await;
+
+    let votes = db
+        .known_votes_provider()
+        .get_votes_by_slot(query.slot)
+        .map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to get votes of slot {}: {err:?}",
+                query.slot
+            ))
+        })?;
+
+    Ok(HttpResponse::Ok().json(votes))
+}
+
+// GET /lean/v0/validators/{validator_id}/latest_vote
+#[get("/validators/{validator_id}/latest_vote")]
+pub async fn get_latest_vote(
+    validator_id: Path<u64>,
+    lean_chain: Data<LeanChainReader>,
+) -> Result<impl Responder, ApiError> {
+    let validator_id = validator_id.into_inner();
+    let lean_chain = lean_chain.read().await;
+    let db = lean_chain.store.lock().await;
+
+    let vote = db
+        .known_votes_provider()
+        .get_latest_vote(validator_id)
+        .map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to get the latest vote of validator {validator_id}: {err:?}"
+            ))
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("No vote of validator {validator_id}")))?;
+
+    Ok(HttpResponse::Ok().json(vote))
+}
@@ -0,0 +1,8 @@
+use actix_web::web::ServiceConfig;
+
+use crate::handlers::debug::get_debug_fork_choice;
+
+/// Creates and returns all `/debug` routes.
+pub fn register_debug_routes(cfg: &mut ServiceConfig) {
+    cfg.service(get_debug_fork_choice);
+}
@@ -1,10 +1,23 @@
 use actix_web::web::ServiceConfig;
 
-use crate::handlers::{block::get_block, block_header::get_block_header, head::get_head};
+use crate::handlers::{
+    block::get_block,
+    block_header::get_block_header,
+    finality::get_finality,
+    head::get_head,
+    proof::get_proof,
+    state::get_state,
+    vote::{get_latest_vote, get_votes},
+};
 
 /// Creates and returns all `/lean` routes.
 pub fn register_lean_routes(cfg: &mut ServiceConfig) {
     cfg.service(get_head)
         .service(get_block)
-        .service(get_block_header);
+        .service(get_block_header)
+        .service(get_finality)
+        .service(get_proof)
+        .service(get_state)
+        .service(get_votes)
+        .service(get_latest_vote);
 }
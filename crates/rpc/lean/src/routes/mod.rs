@@ -1,3 +1,4 @@
+pub mod debug;
 pub mod lean;
 pub mod node;
 use actix_web::web::{ServiceConfig, scope};
@@ -6,6 +7,7 @@ pub fn get_v0_routes(config: &mut ServiceConfig) {
     config.service(
         scope("/lean/v0")
             .configure(lean::register_lean_routes)
+            .configure(debug::register_debug_routes)
             .configure(node::register_node_routes),
     );
 }
@@ -1,23 +1,32 @@
-use std::num::NonZeroUsize;
+use std::{num::NonZeroUsize, sync::Arc};
 
-use alloy_primitives::FixedBytes;
+use alloy_primitives::{B256, FixedBytes};
 use lru::LruCache;
 use ream_bls::{BLSSignature, PublicKey};
-use ream_consensus_beacon::bls_to_execution_change::BLSToExecutionChange;
-use ream_consensus_misc::constants::beacon::SYNC_COMMITTEE_SIZE;
-use tokio::sync::RwLock;
+use ream_consensus_beacon::{
+    bls_to_execution_change::BLSToExecutionChange, electra::beacon_state::BeaconState,
+    shuffling::EpochShuffling,
+};
+use ream_consensus_misc::{
+    constants::beacon::{DOMAIN_BEACON_ATTESTER, SYNC_COMMITTEE_SIZE},
+    slot_epoch::{Epoch, Slot},
+};
+use tokio::sync::{Mutex, RwLock};
 const LRU_CACHE_SIZE: usize = 64;
+const SHUFFLING_CACHE_SIZE: usize = 16;
+const HOT_STATE_CACHE_SIZE: usize = 4;
+const PROPOSER_STATE_CACHE_SIZE: usize = 4;
 
 #[derive(Debug, Hash, PartialEq, Eq, Default, Clone)]
 pub struct AddressSlotIdentifier {
     pub address: PublicKey,
-    pub slot: u64,
+    pub slot: Slot,
 }
 
 #[derive(Debug, Hash, Eq, PartialEq, Default)]
 pub struct AtestationKey {
     pub attestation_subnet_id: u64,
-    pub target_epoch: u64,
+    pub target_epoch: Epoch,
     pub participating_validator_index: u64,
 }
 
@@ -44,10 +53,16 @@ pub struct CacheSyncCommitteeContribution {
 /// In-memory LRU cache.
 #[derive(Debug)]
 pub struct CachedDB {
+    /// The roots of the gossip blocks accepted so far, so copies of them are ignored cheaply
+    pub seen_blocks: RwLock<LruCache<B256, ()>>,
+    /// Parent states advanced to the start of the epoch of a gossip block, keyed by parent root
+    /// and that slot, so the epoch transition is only processed once for the blocks of an epoch
+    pub proposer_states: RwLock<LruCache<(B256, u64), Arc<BeaconState>>>,
     pub seen_proposer_signature: RwLock<LruCache<AddressSlotIdentifier, BLSSignature>>,
     pub seen_bls_to_execution_signature:
         RwLock<LruCache<AddressSlotIdentifier, BLSToExecutionChange>>,
     pub seen_blob_sidecars: RwLock<LruCache<(u64, u64, u64), ()>>,
+    pub seen_data_column_sidecars: RwLock<LruCache<(u64, u64, u64), ()>>,
     pub seen_attestations: RwLock<LruCache<AtestationKey, ()>>,
     pub seen_bls_to_execution_change: RwLock<LruCache<AddressValidaterIndexIdentifier, ()>>,
     pub seen_sync_messages: RwLock<LruCache<SyncCommitteeKey, ()>>,
@@ -60,6 +75,14 @@ pub struct CachedDB {
 impl CachedDB {
     pub fn new() -> Self {
         Self {
+            seen_blocks: LruCache::new(
+                NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
+            proposer_states: LruCache::new(
+                NonZeroUsize::new(PROPOSER_STATE_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
             seen_proposer_signature: LruCache::new(
                 NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
             )
@@ -72,6 +95,10 @@ impl CachedDB {
                 NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
             )
             .into(),
+            seen_data_column_sidecars: LruCache::new(
+                NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
             seen_attestations: LruCache::new(
                 NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
             )
@@ -102,6 +129,26 @@ impl CachedDB {
             .into(),
         }
     }
+
+    /// Forgets every gossip message seen so far.
+    pub async fn clear(&self) {
+        self.seen_blocks.write().await.clear();
+        self.proposer_states.write().await.clear();
+        self.seen_proposer_signature.write().await.clear();
+        self.seen_bls_to_execution_signature.write().await.clear();
+        self.seen_blob_sidecars.write().await.clear();
+        self.seen_data_column_sidecars.write().await.clear();
+        self.seen_attestations.write().await.clear();
+        self.seen_bls_to_execution_change.write().await.clear();
+        self.seen_sync_messages.write().await.clear();
+        self.seen_sync_committee_contributions.write().await.clear();
+        self.seen_voluntary_exit.write().await.clear();
+        self.seen_proposer_slashings.write().await.clear();
+        self.prior_seen_attester_slashing_indices
+            .write()
+            .await
+            .clear();
+    }
 }
 
 impl Default for CachedDB {
@@ -109,3 +156,92 @@ impl Default for CachedDB {
         Self::new()
     }
 }
+
+/// Epoch shufflings keyed by epoch and seed, shared by everything that needs beacon committees
+/// so that each shuffling is only computed once.
+#[derive(Debug)]
+pub struct ShufflingCache {
+    shufflings: Mutex<LruCache<(u64, B256), Arc<EpochShuffling>>>,
+}
+
+impl ShufflingCache {
+    pub fn new() -> Self {
+        Self {
+            shufflings: LruCache::new(
+                NonZeroUsize::new(SHUFFLING_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
+        }
+    }
+
+    /// The shuffling of `epoch` as seen from `state`, computing it if it isn't cached yet.
+    pub async fn get_or_compute(
+        &self,
+        state: &BeaconState,
+        epoch: u64,
+    ) -> anyhow::Result<Arc<EpochShuffling>> {
+        let key = (epoch, state.get_seed(epoch, DOMAIN_BEACON_ATTESTER));
+        if let Some(shuffling) = self.shufflings.lock().await.get(&key) {
+            return Ok(shuffling.clone());
+        }
+
+        let shuffling = Arc::new(state.compute_epoch_shuffling(epoch)?);
+        self.shufflings.lock().await.put(key, shuffling.clone());
+        Ok(shuffling)
+    }
+
+    pub async fn clear(&self) {
+        self.shufflings.lock().await.clear();
+    }
+}
+
+impl Default for ShufflingCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The most recently used states, which are read by every block import and most API requests.
+/// Reading a state from here saves decoding it from the database.
+#[derive(Debug)]
+pub struct HotStateCache {
+    states: parking_lot::Mutex<LruCache<B256, BeaconState>>,
+}
+
+impl HotStateCache {
+    pub fn new() -> Self {
+        Self {
+            states: LruCache::new(
+                NonZeroUsize::new(HOT_STATE_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
+        }
+    }
+
+    pub fn get(&self, block_root: &B256) -> Option<BeaconState> {
+        self.states.lock().get(block_root).cloned()
+    }
+
+    pub fn put(&self, block_root: B256, state: BeaconState) {
+        self.states.lock().put(block_root, state);
+    }
+
+    pub fn remove(&self, block_root: &B256) {
+        self.states.lock().pop(block_root);
+    }
+
+    /// The block roots of the cached states, most recently used first.
+    pub fn block_roots(&self) -> Vec<B256> {
+        self.states
+            .lock()
+            .iter()
+            .map(|(block_root, _)| *block_root)
+            .collect()
+    }
+}
+
+impl Default for HotStateCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    fs,
+    ops::Bound,
+    path::Path,
+};
+
+use libmdbx::{
+    DatabaseOptions, Mode, NoWriteMap, ReadWriteOptions, SyncMode, TableFlags, WriteFlags,
+};
+use tracing::warn;
+
+use super::{Database, KeyValue, TableDefinition, TableKind};
+use crate::{
+    db::config::{CommitBuffer, DBConfig, DBDurability},
+    errors::StoreError,
+};
+
+/// The directory of the MDBX database in the data directory
+pub const MDBX_FOLDER_NAME: &str = "mdbx";
+
+const MDBX_MAX_TABLES: u64 = 64;
+
+/// The upper bound of the size of the database file
+///
+/// 16 TiB
+const MDBX_MAX_SIZE: isize = 16 << 40;
+
+/// Stores every table in an MDBX table. Integer keys are compared as native integers, which are
+/// the little endian keys of the tables on the supported platforms, and multimap tables are
+/// tables with sorted duplicates.
+///
+/// MDBX reads go through the page cache of the OS, so the configured cache size is not used.
+pub struct MdbxDatabase {
+    db: libmdbx::Database<NoWriteMap>,
+    /// MDBX syncs its commits by the sync mode it is opened with, so write batches don't change
+    /// them.
+    commit_buffer: CommitBuffer,
+}
+
+impl Debug for MdbxDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MdbxDatabase").finish_non_exhaustive()
+    }
+}
+
+fn table_flags(table: TableDefinition) -> TableFlags {
+    match table.kind {
+        TableKind::Bytes => TableFlags::empty(),
+        TableKind::Integer => TableFlags::INTEGER_KEY,
+        TableKind::Multimap => TableFlags::DUP_SORT,
+    }
+}
+
+impl MdbxDatabase {
+    pub fn open(data_dir: &Path, config: &DBConfig) -> Result<Self, StoreError> {
+        let path = data_dir.join(MDBX_FOLDER_NAME);
+        fs::create_dir_all(&path)?;
+
+        let sync_mode = match config.durability {
+            DBDurability::Immediate | DBDurability::Paranoid => SyncMode::Durable,
+            DBDurability::Eventual => SyncMode::SafeNoSync,
+        };
+        let db = libmdbx::Database::open_with_options(
+            &path,
+            DatabaseOptions {
+                max_tables: Some(MDBX_MAX_TABLES),
+                mode: Mode::ReadWrite(ReadWriteOptions {
+                    sync_mode,
+                    max_size: Some(MDBX_MAX_SIZE),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )?;
+        if config.compact_on_startup {
+            warn!("MDBX reuses the pages of deleted data itself, skipping the compaction");
+        }
+
+        Ok(Self {
+            db,
+            commit_buffer: CommitBuffer::new(config),
+        })
+    }
+}
+
+impl Database for MdbxDatabase {
+    fn create_tables(&self, tables: &[TableDefinition]) -> Result<(), StoreError> {
+        let txn = self.db.begin_rw_txn()?;
+        for table in tables {
+            txn.create_table(Some(table.name), table_flags(*table))?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn get(&self, table: TableDefinition, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        let txn = self.db.begin_ro_txn()?;
+        let mdbx_table = txn.open_table(Some(table.name))?;
+        Ok(txn.get::<Vec<u8>>(&mdbx_table, key)?)
+    }
+
+    fn get_all(&self, table: TableDefinition, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        Ok(self
+            .range(table, Bound::Included(key), Bound::Included(key), None)?
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect())
+    }
+
+    fn insert(&self, table: TableDefinition, entries: &[KeyValue]) -> Result<(), StoreError> {
+        let txn = self.db.begin_rw_txn()?;
+        let mdbx_table = txn.open_table(Some(table.name))?;
+        for (key, value) in entries {
+            txn.put(&mdbx_table, key, value, WriteFlags::empty())?;
+        }
+        drop(mdbx_table);
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn remove(&self, table: TableDefinition, keys: &[Vec<u8>]) -> Result<(), StoreError> {
+        let txn = self.db.begin_rw_txn()?;
+        let mdbx_table = txn.open_table(Some(table.name))?;
+        for key in keys {
+            // Without a value, all values of the key are removed from a table with duplicates
+            txn.del(&mdbx_table, key, None)?;
+        }
+        drop(mdbx_table);
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn first(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError> {
+        let txn = self.db.begin_ro_txn()?;
+        let mdbx_table = txn.open_table(Some(table.name))?;
+        Ok(txn.cursor(&mdbx_table)?.first::<Vec<u8>, Vec<u8>>()?)
+    }
+
+    fn last(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError> {
+        let txn = self.db.begin_ro_txn()?;
+        let mdbx_table = txn.open_table(Some(table.name))?;
+        Ok(txn.cursor(&mdbx_table)?.last::<Vec<u8>, Vec<u8>>()?)
+    }
+
+    fn range(
+        &self,
+        table: TableDefinition,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<KeyValue>, StoreError> {
+        let txn = self.db.begin_ro_txn()?;
+        let mdbx_table = txn.open_table(Some(table.name))?;
+        let mut cursor = txn.cursor(&mdbx_table)?;
+        let mut entry = match start {
+            Bound::Included(key) | Bound::Excluded(key) => {
+                cursor.set_range::<Vec<u8>, Vec<u8>>(key)?
+            }
+            Bound::Unbounded => cursor.first::<Vec<u8>, Vec<u8>>()?,
+        };
+
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut entries = vec![];
+        while let Some((key, value)) = entry {
+            if entries.len() == limit {
+                break;
+            }
+            let past_end = match end {
+                Bound::Included(end) => table.compare_keys(&key, end) == Ordering::Greater,
+                Bound::Excluded(end) => table.compare_keys(&key, end) != Ordering::Less,
+                Bound::Unbounded => false,
+            };
+            if past_end {
+                break;
+            }
+            let excluded = matches!(start, Bound::Excluded(start)
+                if table.compare_keys(&key, start) == Ordering::Equal);
+            if !excluded {
+                entries.push((key, value));
+            }
+            entry = cursor.next::<Vec<u8>, Vec<u8>>()?;
+        }
+        Ok(entries)
+    }
+
+    fn len(&self, table: TableDefinition) -> Result<u64, StoreError> {
+        let txn = self.db.begin_ro_txn()?;
+        let mdbx_table = txn.open_table(Some(table.name))?;
+        Ok(txn.table_stat(&mdbx_table)?.entries() as u64)
+    }
+
+    fn flush(&self) -> Result<(), StoreError> {
+        self.db.sync(true)?;
+        Ok(())
+    }
+
+    fn commit_buffer(&self) -> &CommitBuffer {
+        &self.commit_buffer
+    }
+}
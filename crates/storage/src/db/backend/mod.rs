@@ -0,0 +1,146 @@
+//! The key-value stores the tables of ream can be kept in.
+//!
+//! Tables only see ordered maps of SSZ encoded keys and values through the [Database] trait, so
+//! the backend is picked when the node starts. redb is always available, rocksdb and libmdbx are
+//! behind the `rocksdb` and `mdbx` cargo features.
+
+#[cfg(feature = "mdbx")]
+pub mod mdbx;
+pub mod redb;
+#[cfg(feature = "rocksdb")]
+pub mod rocksdb;
+
+use std::{cmp::Ordering, fmt::Debug, ops::Bound, path::Path, sync::Arc};
+
+use ssz::{Decode, Encode};
+
+use super::config::{CommitBuffer, DBBackend, DBConfig};
+use crate::errors::StoreError;
+
+pub type KeyValue = (Vec<u8>, Vec<u8>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    /// Keys are ordered byte by byte
+    Bytes,
+    /// Keys are little endian `u64`s, ordered by their value
+    Integer,
+    /// Every key maps to a set of values
+    Multimap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableDefinition {
+    pub name: &'static str,
+    pub kind: TableKind,
+}
+
+impl TableDefinition {
+    pub const fn new(name: &'static str, kind: TableKind) -> Self {
+        Self { name, kind }
+    }
+
+    /// Orders two keys of the table the same way in every backend.
+    pub fn compare_keys(&self, key_1: &[u8], key_2: &[u8]) -> Ordering {
+        match self.kind {
+            TableKind::Integer => match (key_1.try_into(), key_2.try_into()) {
+                (Ok(key_1), Ok(key_2)) => u64::from_le_bytes(key_1).cmp(&u64::from_le_bytes(key_2)),
+                _ => key_1.cmp(key_2),
+            },
+            TableKind::Bytes | TableKind::Multimap => key_1.cmp(key_2),
+        }
+    }
+}
+
+/// An ordered key-value store holding the tables of ream.
+///
+/// The entries of multimap tables are returned as one `(key, value)` pair per value.
+pub trait Database: Debug + Send + Sync {
+    /// Creates the tables which don't exist yet.
+    fn create_tables(&self, tables: &[TableDefinition]) -> Result<(), StoreError>;
+
+    fn get(&self, table: TableDefinition, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError>;
+
+    /// Returns all values of a key in a multimap table, in ascending order.
+    fn get_all(&self, table: TableDefinition, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError>;
+
+    /// Inserts the entries in a single commit. Inserting into a multimap table adds the value to
+    /// the values of the key.
+    fn insert(&self, table: TableDefinition, entries: &[KeyValue]) -> Result<(), StoreError>;
+
+    /// Removes the keys, with all their values in a multimap table, in a single commit.
+    fn remove(&self, table: TableDefinition, keys: &[Vec<u8>]) -> Result<(), StoreError>;
+
+    fn first(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError>;
+
+    fn last(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError>;
+
+    /// Returns the entries with keys between `start` and `end` in ascending order, at most
+    /// `limit` of them.
+    fn range(
+        &self,
+        table: TableDefinition,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<KeyValue>, StoreError>;
+
+    fn len(&self, table: TableDefinition) -> Result<u64, StoreError>;
+
+    /// Writes the commits which are only kept in memory so far to disk.
+    fn flush(&self) -> Result<(), StoreError>;
+
+    /// The durability settings of the database, and the write batches which are alive.
+    fn commit_buffer(&self) -> &CommitBuffer;
+}
+
+impl dyn Database {
+    pub fn get_value<V: Decode>(
+        &self,
+        table: TableDefinition,
+        key: &[u8],
+    ) -> Result<Option<V>, StoreError> {
+        Ok(self
+            .get(table, key)?
+            .map(|value| V::from_ssz_bytes(&value))
+            .transpose()?)
+    }
+
+    pub fn insert_value<V: Encode>(
+        &self,
+        table: TableDefinition,
+        key: &[u8],
+        value: &V,
+    ) -> Result<(), StoreError> {
+        self.insert(table, &[(key.to_vec(), value.as_ssz_bytes())])
+    }
+
+    pub fn all_values<V: Decode>(&self, table: TableDefinition) -> Result<Vec<V>, StoreError> {
+        self.range(table, Bound::Unbounded, Bound::Unbounded, None)?
+            .into_iter()
+            .map(|(_, value)| Ok(V::from_ssz_bytes(&value)?))
+            .collect()
+    }
+}
+
+/// Opens the database of the backend in the data directory, which must have been built with the
+/// backend's feature.
+pub fn open(
+    data_dir: &Path,
+    backend: DBBackend,
+    config: &DBConfig,
+) -> Result<Arc<dyn Database>, StoreError> {
+    match backend {
+        DBBackend::Redb => Ok(Arc::new(self::redb::RedbDatabase::open(data_dir, config)?)),
+        #[cfg(feature = "rocksdb")]
+        DBBackend::RocksDB => Ok(Arc::new(self::rocksdb::RocksDatabase::open(
+            data_dir, config,
+        )?)),
+        #[cfg(not(feature = "rocksdb"))]
+        DBBackend::RocksDB => Err(StoreError::BackendNotEnabled(backend.to_string())),
+        #[cfg(feature = "mdbx")]
+        DBBackend::Mdbx => Ok(Arc::new(self::mdbx::MdbxDatabase::open(data_dir, config)?)),
+        #[cfg(not(feature = "mdbx"))]
+        DBBackend::Mdbx => Err(StoreError::BackendNotEnabled(backend.to_string())),
+    }
+}
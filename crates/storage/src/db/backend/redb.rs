@@ -0,0 +1,542 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{self, Debug},
+    marker::PhantomData,
+    ops::Bound,
+    path::Path,
+};
+
+use alloy_primitives::B256;
+use ream_consensus_beacon::{
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    fork_choice::latest_message::LatestMessage,
+};
+use ream_consensus_lean::{
+    block::SignedBlock, checkpoint::Checkpoint as LeanCheckpoint, state::LeanState,
+    vote::SignedVote,
+};
+use ream_consensus_misc::checkpoint::Checkpoint;
+use redb::{
+    Builder, Durability, Key, MultimapTableDefinition, ReadTransaction, ReadableMultimapTable,
+    ReadableTable, ReadableTableMetadata, TypeName, Value, WriteTransaction,
+};
+use tracing::info;
+
+use super::{Database, KeyValue, TableDefinition};
+use crate::{
+    db::{
+        REDB_FILE,
+        config::{CommitBuffer, DBConfig, DBDurability},
+    },
+    errors::StoreError,
+    tables::{
+        beacon::{
+            beacon_block::BEACON_BLOCK_TABLE, beacon_state::BEACON_STATE_TABLE,
+            block_timeliness::BLOCK_TIMELINESS_TABLE, checkpoint_states::CHECKPOINT_STATES_TABLE,
+            equivocating_indices::EQUIVOCATING_INDICES_FIELD,
+            finalized_checkpoint::FINALIZED_CHECKPOINT_FIELD, frozen_slot::FROZEN_SLOT_FIELD,
+            genesis_time::GENESIS_TIME_FIELD, head_root::HEAD_ROOT_FIELD,
+            hot_state_roots::HOT_STATE_ROOTS_FIELD,
+            justified_checkpoint::JUSTIFIED_CHECKPOINT_FIELD,
+            latest_messages::LATEST_MESSAGES_TABLE,
+            parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE,
+            proposer_boost_root::PROPOSER_BOOST_ROOT_FIELD, slot_index::SLOT_INDEX_TABLE,
+            state_root_index::STATE_ROOT_INDEX_TABLE, time::TIME_FIELD,
+            unrealized_finalized_checkpoint::UNREALIZED_FINALIZED_CHECKPOINT_FIELD,
+            unrealized_justifications::UNREALIZED_JUSTIFICATIONS_TABLE,
+            unrealized_justified_checkpoint::UNREALIZED_JUSTIFED_CHECKPOINT_FIELD,
+        },
+        known_peers::{BEACON_KNOWN_PEERS_TABLE, KnownPeer, LEAN_KNOWN_PEERS_TABLE},
+        lean::{
+            known_votes::KNOWN_VOTES_TABLE, latest_finalized::LATEST_FINALIZED_FIELD,
+            latest_justified::LATEST_JUSTIFIED_FIELD, lean_block::LEAN_BLOCK_TABLE,
+            lean_proof::LEAN_PROOF_TABLE, lean_state::LEAN_STATE_TABLE,
+            safe_target::SAFE_TARGET_FIELD, slot_index::LEAN_SLOT_INDEX_TABLE,
+            state_root_index::LEAN_STATE_ROOT_INDEX_TABLE,
+        },
+        ssz_encoder::SSZEncoding,
+    },
+};
+
+/// The raw bytes of keys and values stored as `T`.
+///
+/// redb checks the types of a table when it's opened, so the tables are opened with the types
+/// they were created with, and only read and written as bytes.
+#[derive(Debug)]
+struct RawBytes<T>(PhantomData<T>);
+
+impl<T: Value> Value for RawBytes<T> {
+    type SelfType<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    type AsBytes<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    fn fixed_width() -> Option<usize> {
+        T::fixed_width()
+    }
+
+    fn from_bytes<'a>(data: &'a [u8]) -> Self::SelfType<'a>
+    where
+        Self: 'a,
+    {
+        data
+    }
+
+    fn as_bytes<'a, 'b: 'a>(value: &'a Self::SelfType<'b>) -> Self::AsBytes<'a>
+    where
+        Self: 'a,
+        Self: 'b,
+    {
+        value
+    }
+
+    fn type_name() -> TypeName {
+        T::type_name()
+    }
+}
+
+impl<T: Key> Key for RawBytes<T> {
+    fn compare(data1: &[u8], data2: &[u8]) -> Ordering {
+        T::compare(data1, data2)
+    }
+}
+
+trait RedbTable: Send + Sync {
+    fn create(&self, write_txn: &WriteTransaction) -> Result<(), StoreError>;
+
+    fn get_all(&self, read_txn: &ReadTransaction, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError>;
+
+    fn insert(&self, write_txn: &WriteTransaction, entries: &[KeyValue]) -> Result<(), StoreError>;
+
+    fn remove(&self, write_txn: &WriteTransaction, keys: &[Vec<u8>]) -> Result<(), StoreError>;
+
+    fn first(&self, read_txn: &ReadTransaction) -> Result<Option<KeyValue>, StoreError>;
+
+    fn last(&self, read_txn: &ReadTransaction) -> Result<Option<KeyValue>, StoreError>;
+
+    fn range(
+        &self,
+        read_txn: &ReadTransaction,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<KeyValue>, StoreError>;
+
+    fn len(&self, read_txn: &ReadTransaction) -> Result<u64, StoreError>;
+}
+
+struct RawTable<K: Key + 'static, V: Value + 'static>(
+    redb::TableDefinition<'static, RawBytes<K>, RawBytes<V>>,
+);
+
+impl<K, V> RedbTable for RawTable<K, V>
+where
+    K: Key + Send + Sync + 'static,
+    V: Value + Send + Sync + 'static,
+{
+    fn create(&self, write_txn: &WriteTransaction) -> Result<(), StoreError> {
+        write_txn.open_table(self.0)?;
+        Ok(())
+    }
+
+    fn get_all(&self, read_txn: &ReadTransaction, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        let table = read_txn.open_table(self.0)?;
+        Ok(table
+            .get(key)?
+            .map(|value| value.value().to_vec())
+            .into_iter()
+            .collect())
+    }
+
+    fn insert(&self, write_txn: &WriteTransaction, entries: &[KeyValue]) -> Result<(), StoreError> {
+        let mut table = write_txn.open_table(self.0)?;
+        for (key, value) in entries {
+            table.insert(key.as_slice(), value.as_slice())?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, write_txn: &WriteTransaction, keys: &[Vec<u8>]) -> Result<(), StoreError> {
+        let mut table = write_txn.open_table(self.0)?;
+        for key in keys {
+            table.remove(key.as_slice())?;
+        }
+        Ok(())
+    }
+
+    fn first(&self, read_txn: &ReadTransaction) -> Result<Option<KeyValue>, StoreError> {
+        let table = read_txn.open_table(self.0)?;
+        Ok(table
+            .first()?
+            .map(|(key, value)| (key.value().to_vec(), value.value().to_vec())))
+    }
+
+    fn last(&self, read_txn: &ReadTransaction) -> Result<Option<KeyValue>, StoreError> {
+        let table = read_txn.open_table(self.0)?;
+        Ok(table
+            .last()?
+            .map(|(key, value)| (key.value().to_vec(), value.value().to_vec())))
+    }
+
+    fn range(
+        &self,
+        read_txn: &ReadTransaction,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<KeyValue>, StoreError> {
+        let table = read_txn.open_table(self.0)?;
+        table
+            .range::<&[u8]>((start, end))?
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|entry| {
+                let (key, value) = entry?;
+                Ok((key.value().to_vec(), value.value().to_vec()))
+            })
+            .collect()
+    }
+
+    fn len(&self, read_txn: &ReadTransaction) -> Result<u64, StoreError> {
+        let table = read_txn.open_table(self.0)?;
+        Ok(table.len()?)
+    }
+}
+
+struct RawMultimapTable<K: Key + 'static, V: Key + 'static>(
+    MultimapTableDefinition<'static, RawBytes<K>, RawBytes<V>>,
+);
+
+impl<K, V> RedbTable for RawMultimapTable<K, V>
+where
+    K: Key + Send + Sync + 'static,
+    V: Key + Send + Sync + 'static,
+{
+    fn create(&self, write_txn: &WriteTransaction) -> Result<(), StoreError> {
+        write_txn.open_multimap_table(self.0)?;
+        Ok(())
+    }
+
+    fn get_all(&self, read_txn: &ReadTransaction, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        let table = read_txn.open_multimap_table(self.0)?;
+        table
+            .get(key)?
+            .map(|value| Ok(value?.value().to_vec()))
+            .collect()
+    }
+
+    fn insert(&self, write_txn: &WriteTransaction, entries: &[KeyValue]) -> Result<(), StoreError> {
+        let mut table = write_txn.open_multimap_table(self.0)?;
+        for (key, value) in entries {
+            table.insert(key.as_slice(), value.as_slice())?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, write_txn: &WriteTransaction, keys: &[Vec<u8>]) -> Result<(), StoreError> {
+        let mut table = write_txn.open_multimap_table(self.0)?;
+        for key in keys {
+            table.remove_all(key.as_slice())?;
+        }
+        Ok(())
+    }
+
+    fn first(&self, read_txn: &ReadTransaction) -> Result<Option<KeyValue>, StoreError> {
+        let entries = self.range(read_txn, Bound::Unbounded, Bound::Unbounded, Some(1))?;
+        Ok(entries.into_iter().next())
+    }
+
+    fn last(&self, read_txn: &ReadTransaction) -> Result<Option<KeyValue>, StoreError> {
+        let table = read_txn.open_multimap_table(self.0)?;
+        let Some(entry) = table.iter()?.next_back() else {
+            return Ok(None);
+        };
+        let (key, mut values) = entry?;
+        Ok(values
+            .next_back()
+            .transpose()?
+            .map(|value| (key.value().to_vec(), value.value().to_vec())))
+    }
+
+    fn range(
+        &self,
+        read_txn: &ReadTransaction,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<KeyValue>, StoreError> {
+        let table = read_txn.open_multimap_table(self.0)?;
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut entries = vec![];
+        for entry in table.range::<&[u8]>((start, end))? {
+            let (key, values) = entry?;
+            for value in values {
+                if entries.len() == limit {
+                    return Ok(entries);
+                }
+                entries.push((key.value().to_vec(), value?.value().to_vec()));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn len(&self, read_txn: &ReadTransaction) -> Result<u64, StoreError> {
+        let table = read_txn.open_multimap_table(self.0)?;
+        Ok(table.len()?)
+    }
+}
+
+fn table<K, V>(definition: TableDefinition) -> (&'static str, Box<dyn RedbTable>)
+where
+    K: Key + Send + Sync + 'static,
+    V: Value + Send + Sync + 'static,
+{
+    (
+        definition.name,
+        Box::new(RawTable::<K, V>(redb::TableDefinition::new(
+            definition.name,
+        ))),
+    )
+}
+
+fn multimap_table<K, V>(definition: TableDefinition) -> (&'static str, Box<dyn RedbTable>)
+where
+    K: Key + Send + Sync + 'static,
+    V: Key + Send + Sync + 'static,
+{
+    (
+        definition.name,
+        Box::new(RawMultimapTable::<K, V>(MultimapTableDefinition::new(
+            definition.name,
+        ))),
+    )
+}
+
+/// The types every table is stored with in redb.
+fn redb_tables() -> HashMap<&'static str, Box<dyn RedbTable>> {
+    HashMap::from([
+        table::<SSZEncoding<B256>, SSZEncoding<SignedBeaconBlock>>(BEACON_BLOCK_TABLE),
+        table::<u64, SSZEncoding<KnownPeer>>(BEACON_KNOWN_PEERS_TABLE),
+        table::<SSZEncoding<B256>, SSZEncoding<BeaconState>>(BEACON_STATE_TABLE),
+        table::<SSZEncoding<B256>, SSZEncoding<bool>>(BLOCK_TIMELINESS_TABLE),
+        table::<SSZEncoding<Checkpoint>, SSZEncoding<BeaconState>>(CHECKPOINT_STATES_TABLE),
+        table::<&str, Vec<u64>>(EQUIVOCATING_INDICES_FIELD),
+        table::<&str, SSZEncoding<Checkpoint>>(FINALIZED_CHECKPOINT_FIELD),
+        table::<&str, u64>(FROZEN_SLOT_FIELD),
+        table::<&str, u64>(GENESIS_TIME_FIELD),
+        table::<&str, SSZEncoding<B256>>(HEAD_ROOT_FIELD),
+        table::<&str, SSZEncoding<Vec<B256>>>(HOT_STATE_ROOTS_FIELD),
+        table::<&str, SSZEncoding<Checkpoint>>(JUSTIFIED_CHECKPOINT_FIELD),
+        table::<u64, SSZEncoding<LatestMessage>>(LATEST_MESSAGES_TABLE),
+        multimap_table::<SSZEncoding<B256>, SSZEncoding<B256>>(PARENT_ROOT_INDEX_MULTIMAP_TABLE),
+        table::<&str, SSZEncoding<B256>>(PROPOSER_BOOST_ROOT_FIELD),
+        table::<u64, SSZEncoding<B256>>(SLOT_INDEX_TABLE),
+        table::<SSZEncoding<B256>, SSZEncoding<B256>>(STATE_ROOT_INDEX_TABLE),
+        table::<&str, u64>(TIME_FIELD),
+        table::<&str, SSZEncoding<Checkpoint>>(UNREALIZED_FINALIZED_CHECKPOINT_FIELD),
+        table::<SSZEncoding<B256>, SSZEncoding<Checkpoint>>(UNREALIZED_JUSTIFICATIONS_TABLE),
+        table::<&str, SSZEncoding<Checkpoint>>(UNREALIZED_JUSTIFED_CHECKPOINT_FIELD),
+        table::<u64, SSZEncoding<SignedVote>>(KNOWN_VOTES_TABLE),
+        table::<&str, SSZEncoding<LeanCheckpoint>>(LATEST_FINALIZED_FIELD),
+        table::<&str, SSZEncoding<LeanCheckpoint>>(LATEST_JUSTIFIED_FIELD),
+        table::<SSZEncoding<B256>, SSZEncoding<SignedBlock>>(LEAN_BLOCK_TABLE),
+        table::<SSZEncoding<B256>, SSZEncoding<Vec<u8>>>(LEAN_PROOF_TABLE),
+        table::<u64, SSZEncoding<KnownPeer>>(LEAN_KNOWN_PEERS_TABLE),
+        table::<SSZEncoding<B256>, SSZEncoding<LeanState>>(LEAN_STATE_TABLE),
+        table::<&str, SSZEncoding<B256>>(SAFE_TARGET_FIELD),
+        table::<u64, SSZEncoding<B256>>(LEAN_SLOT_INDEX_TABLE),
+        table::<SSZEncoding<B256>, SSZEncoding<B256>>(LEAN_STATE_ROOT_INDEX_TABLE),
+    ])
+}
+
+pub struct RedbDatabase {
+    db: redb::Database,
+    tables: HashMap<&'static str, Box<dyn RedbTable>>,
+    commit_buffer: CommitBuffer,
+}
+
+/// Sets the redb durability of a commit, which is only kept in memory without a durability.
+/// A paranoid commit is an immediate one with a two-phase commit.
+fn set_redb_durability(write_txn: &mut WriteTransaction, durability: Option<DBDurability>) {
+    write_txn.set_durability(match durability {
+        Some(DBDurability::Immediate | DBDurability::Paranoid) => Durability::Immediate,
+        Some(DBDurability::Eventual) => Durability::Eventual,
+        None => Durability::None,
+    });
+    write_txn.set_two_phase_commit(durability == Some(DBDurability::Paranoid));
+}
+
+impl Debug for RedbDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedbDatabase")
+            .field("db", &self.db)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedbDatabase {
+    pub fn open(data_dir: &Path, config: &DBConfig) -> Result<Self, StoreError> {
+        let mut db = Builder::new()
+            .set_cache_size(config.cache_size)
+            .create(data_dir.join(REDB_FILE))?;
+        if config.compact_on_startup {
+            info!("Compacting the database...");
+            db.compact()?;
+        }
+
+        Ok(Self {
+            db,
+            tables: redb_tables(),
+            commit_buffer: CommitBuffer::new(config),
+        })
+    }
+
+    fn table(&self, table: TableDefinition) -> Result<&dyn RedbTable, StoreError> {
+        self.tables
+            .get(table.name)
+            .map(|table| table.as_ref())
+            .ok_or_else(|| StoreError::UnknownTable(table.name.to_string()))
+    }
+}
+
+impl Database for RedbDatabase {
+    fn create_tables(&self, tables: &[TableDefinition]) -> Result<(), StoreError> {
+        let write_txn = self.db.begin_write()?;
+        for table in tables {
+            self.table(*table)?.create(&write_txn)?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    fn get(&self, table: TableDefinition, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        Ok(self.get_all(table, key)?.into_iter().next())
+    }
+
+    fn get_all(&self, table: TableDefinition, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        self.table(table)?.get_all(&read_txn, key)
+    }
+
+    fn insert(&self, table: TableDefinition, entries: &[KeyValue]) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        set_redb_durability(&mut write_txn, self.commit_buffer.commit_durability());
+        self.table(table)?.insert(&write_txn, entries)?;
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    fn remove(&self, table: TableDefinition, keys: &[Vec<u8>]) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        set_redb_durability(&mut write_txn, self.commit_buffer.commit_durability());
+        self.table(table)?.remove(&write_txn, keys)?;
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    fn first(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        self.table(table)?.first(&read_txn)
+    }
+
+    fn last(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        self.table(table)?.last(&read_txn)
+    }
+
+    fn range(
+        &self,
+        table: TableDefinition,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<KeyValue>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        self.table(table)?.range(&read_txn, start, end, limit)
+    }
+
+    fn len(&self, table: TableDefinition) -> Result<u64, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        self.table(table)?.len(&read_txn)
+    }
+
+    /// A durable commit also writes all the commits before it to disk.
+    fn flush(&self) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        set_redb_durability(&mut write_txn, Some(self.commit_buffer.durability()));
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    fn commit_buffer(&self) -> &CommitBuffer {
+        &self.commit_buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::{
+        db::config::WriteBatch,
+        tables::{beacon::BEACON_TABLES, lean::LEAN_TABLES},
+    };
+
+    #[test]
+    fn test_every_table_is_registered() {
+        let tables = redb_tables();
+        let definitions = BEACON_TABLES
+            .iter()
+            .chain(LEAN_TABLES.iter())
+            .collect::<Vec<_>>();
+        for table in &definitions {
+            assert!(
+                tables.contains_key(table.name),
+                "Table {} is not registered",
+                table.name
+            );
+        }
+        // A table registered twice or one which isn't defined anymore makes the registry differ in
+        // size
+        assert_eq!(tables.len(), definitions.len());
+    }
+
+    #[test]
+    fn test_write_batch_only_buffers_its_database() -> anyhow::Result<()> {
+        let config = DBConfig {
+            max_write_buffer: 2,
+            ..Default::default()
+        };
+        let tmp_dir_1 = TempDir::new("test_write_batch_only_buffers_its_database_1")?;
+        let tmp_dir_2 = TempDir::new("test_write_batch_only_buffers_its_database_2")?;
+        let db_1: Arc<dyn Database> = Arc::new(RedbDatabase::open(tmp_dir_1.path(), &config)?);
+        let db_2 = RedbDatabase::open(tmp_dir_2.path(), &config)?;
+
+        let write_batch = WriteBatch::new(db_1.clone());
+        assert_eq!(db_1.commit_buffer().commit_durability(), None);
+        // The buffered commits are written to disk every `max_write_buffer` commits
+        assert_eq!(
+            db_1.commit_buffer().commit_durability(),
+            Some(DBDurability::Immediate)
+        );
+        assert_eq!(
+            db_2.commit_buffer().commit_durability(),
+            Some(DBDurability::Immediate)
+        );
+
+        write_batch.flush()?;
+        assert_eq!(
+            db_1.commit_buffer().commit_durability(),
+            Some(DBDurability::Immediate)
+        );
+        Ok(())
+    }
+}
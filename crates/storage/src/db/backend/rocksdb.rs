@@ -0,0 +1,285 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    ops::Bound,
+    path::Path,
+    sync::Arc,
+};
+
+use rocksdb::{
+    BlockBasedOptions, BoundColumnFamily, Cache, ColumnFamilyDescriptor, DBWithThreadMode,
+    Direction, IteratorMode, MultiThreaded, Options, WriteBatch, WriteOptions,
+};
+use tracing::info;
+
+use super::{Database, KeyValue, TableDefinition, TableKind};
+use crate::{
+    db::config::{CommitBuffer, DBConfig, DBDurability},
+    errors::StoreError,
+    tables::{beacon::BEACON_TABLES, lean::LEAN_TABLES},
+};
+
+/// The directory of the RocksDB database in the data directory
+pub const ROCKSDB_FOLDER_NAME: &str = "rocksdb";
+
+const INTEGER_KEY_COMPARATOR: &str = "ream.u64_le";
+
+/// Stores every table in a column family.
+///
+/// RocksDB has no multimap tables, so every value of a multimap table is appended to its key,
+/// which is prefixed with its length, and stored with an empty value.
+pub struct RocksDatabase {
+    db: DBWithThreadMode<MultiThreaded>,
+    cache: Cache,
+    commit_buffer: CommitBuffer,
+}
+
+impl Debug for RocksDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RocksDatabase")
+            .field("path", &self.db.path())
+            .finish_non_exhaustive()
+    }
+}
+
+fn column_family_options(table: TableDefinition, cache: &Cache) -> Options {
+    let mut block_options = BlockBasedOptions::default();
+    block_options.set_block_cache(cache);
+    let mut options = Options::default();
+    options.set_block_based_table_factory(&block_options);
+    if table.kind == TableKind::Integer {
+        options.set_comparator(
+            INTEGER_KEY_COMPARATOR,
+            Box::new(move |key_1: &[u8], key_2: &[u8]| table.compare_keys(key_1, key_2)),
+        );
+    }
+    options
+}
+
+fn multimap_prefix(key: &[u8]) -> Vec<u8> {
+    let mut prefix = (key.len() as u32).to_be_bytes().to_vec();
+    prefix.extend_from_slice(key);
+    prefix
+}
+
+fn split_multimap_key(entry_key: &[u8]) -> Result<KeyValue, StoreError> {
+    let invalid_entry = || StoreError::DecodeError("Invalid multimap entry".to_string());
+    let (length, rest) = entry_key.split_at_checked(4).ok_or_else(invalid_entry)?;
+    let length = u32::from_be_bytes(length.try_into().map_err(|_| invalid_entry())?) as usize;
+    let (key, value) = rest.split_at_checked(length).ok_or_else(invalid_entry)?;
+    Ok((key.to_vec(), value.to_vec()))
+}
+
+impl RocksDatabase {
+    pub fn open(data_dir: &Path, config: &DBConfig) -> Result<Self, StoreError> {
+        let cache = Cache::new_lru_cache(config.cache_size);
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let tables = [BEACON_TABLES.as_slice(), LEAN_TABLES.as_slice()].concat();
+        let column_families = tables.iter().map(|table| {
+            ColumnFamilyDescriptor::new(table.name, column_family_options(*table, &cache))
+        });
+        let db = DBWithThreadMode::<MultiThreaded>::open_cf_descriptors(
+            &options,
+            data_dir.join(ROCKSDB_FOLDER_NAME),
+            column_families,
+        )?;
+
+        if config.compact_on_startup {
+            info!("Compacting the database...");
+            for table in &tables {
+                if let Some(column_family) = db.cf_handle(table.name) {
+                    db.compact_range_cf(&column_family, None::<&[u8]>, None::<&[u8]>);
+                }
+            }
+        }
+
+        Ok(Self {
+            db,
+            cache,
+            commit_buffer: CommitBuffer::new(config),
+        })
+    }
+
+    fn column_family(
+        &self,
+        table: TableDefinition,
+    ) -> Result<Arc<BoundColumnFamily<'_>>, StoreError> {
+        self.db
+            .cf_handle(table.name)
+            .ok_or_else(|| StoreError::UnknownTable(table.name.to_string()))
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), StoreError> {
+        let mut write_options = WriteOptions::default();
+        write_options.set_sync(matches!(
+            self.commit_buffer.commit_durability(),
+            Some(DBDurability::Immediate | DBDurability::Paranoid)
+        ));
+        self.db.write_opt(batch, &write_options)?;
+        Ok(())
+    }
+
+    fn entries<'a>(
+        &'a self,
+        table: TableDefinition,
+        mode: IteratorMode,
+    ) -> Result<impl Iterator<Item = Result<KeyValue, StoreError>> + 'a, StoreError> {
+        let column_family = self.column_family(table)?;
+        Ok(self.db.iterator_cf(&column_family, mode).map(move |entry| {
+            let (key, value) = entry?;
+            match table.kind {
+                TableKind::Multimap => split_multimap_key(&key),
+                TableKind::Bytes | TableKind::Integer => Ok((key.into_vec(), value.into_vec())),
+            }
+        }))
+    }
+}
+
+impl Database for RocksDatabase {
+    fn create_tables(&self, tables: &[TableDefinition]) -> Result<(), StoreError> {
+        for table in tables {
+            if self.db.cf_handle(table.name).is_none() {
+                self.db
+                    .create_cf(table.name, &column_family_options(*table, &self.cache))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get(&self, table: TableDefinition, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        match table.kind {
+            TableKind::Multimap => Ok(self.get_all(table, key)?.into_iter().next()),
+            TableKind::Bytes | TableKind::Integer => {
+                Ok(self.db.get_cf(&self.column_family(table)?, key)?)
+            }
+        }
+    }
+
+    fn get_all(&self, table: TableDefinition, key: &[u8]) -> Result<Vec<Vec<u8>>, StoreError> {
+        if table.kind != TableKind::Multimap {
+            return Ok(self.get(table, key)?.into_iter().collect());
+        }
+
+        let prefix = multimap_prefix(key);
+        let column_family = self.column_family(table)?;
+        let mut values = vec![];
+        for entry in self.db.iterator_cf(
+            &column_family,
+            IteratorMode::From(&prefix, Direction::Forward),
+        ) {
+            let (entry_key, _) = entry?;
+            let Some(value) = entry_key.strip_prefix(prefix.as_slice()) else {
+                break;
+            };
+            values.push(value.to_vec());
+        }
+        Ok(values)
+    }
+
+    fn insert(&self, table: TableDefinition, entries: &[KeyValue]) -> Result<(), StoreError> {
+        let column_family = self.column_family(table)?;
+        let mut batch = WriteBatch::default();
+        for (key, value) in entries {
+            match table.kind {
+                TableKind::Multimap => batch.put_cf(
+                    &column_family,
+                    [multimap_prefix(key), value.clone()].concat(),
+                    b"",
+                ),
+                TableKind::Bytes | TableKind::Integer => batch.put_cf(&column_family, key, value),
+            }
+        }
+
+        self.write(batch)
+    }
+
+    fn remove(&self, table: TableDefinition, keys: &[Vec<u8>]) -> Result<(), StoreError> {
+        let column_family = self.column_family(table)?;
+        let mut batch = WriteBatch::default();
+        for key in keys {
+            match table.kind {
+                TableKind::Multimap => {
+                    let prefix = multimap_prefix(key);
+                    for value in self.get_all(table, key)? {
+                        batch.delete_cf(&column_family, [prefix.clone(), value].concat());
+                    }
+                }
+                TableKind::Bytes | TableKind::Integer => batch.delete_cf(&column_family, key),
+            }
+        }
+        self.write(batch)
+    }
+
+    fn first(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError> {
+        self.entries(table, IteratorMode::Start)?.next().transpose()
+    }
+
+    fn last(&self, table: TableDefinition) -> Result<Option<KeyValue>, StoreError> {
+        self.entries(table, IteratorMode::End)?.next().transpose()
+    }
+
+    fn range(
+        &self,
+        table: TableDefinition,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<KeyValue>, StoreError> {
+        let start_key = match (start, table.kind) {
+            (Bound::Included(key) | Bound::Excluded(key), TableKind::Multimap) => {
+                Some(multimap_prefix(key))
+            }
+            (Bound::Included(key) | Bound::Excluded(key), _) => Some(key.to_vec()),
+            (Bound::Unbounded, _) => None,
+        };
+        let mode = match &start_key {
+            Some(key) => IteratorMode::From(key, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut entries = vec![];
+        for entry in self.entries(table, mode)? {
+            if entries.len() == limit {
+                break;
+            }
+            let (key, value) = entry?;
+            if let Bound::Excluded(start) = start
+                && table.compare_keys(&key, start) == Ordering::Equal
+            {
+                continue;
+            }
+            let past_end = match end {
+                Bound::Included(end) => table.compare_keys(&key, end) == Ordering::Greater,
+                Bound::Excluded(end) => table.compare_keys(&key, end) != Ordering::Less,
+                Bound::Unbounded => false,
+            };
+            if past_end {
+                break;
+            }
+            entries.push((key, value));
+        }
+        Ok(entries)
+    }
+
+    fn len(&self, table: TableDefinition) -> Result<u64, StoreError> {
+        let mut len = 0;
+        for entry in self.entries(table, IteratorMode::Start)? {
+            entry?;
+            len += 1;
+        }
+        Ok(len)
+    }
+
+    fn flush(&self) -> Result<(), StoreError> {
+        self.db.flush_wal(true)?;
+        Ok(())
+    }
+
+    fn commit_buffer(&self) -> &CommitBuffer {
+        &self.commit_buffer
+    }
+}
@@ -1,41 +1,102 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{collections::HashSet, ops::Range, path::PathBuf, sync::Arc, vec};
 
+use alloy_primitives::B256;
 use anyhow::anyhow;
-use ream_consensus_beacon::electra::beacon_state::BeaconState;
-use redb::Database;
-
-use crate::tables::{
-    beacon::{
-        beacon_block::BeaconBlockTable, beacon_state::BeaconStateTable,
-        blobs_and_proofs::BlobsAndProofsTable, block_timeliness::BlockTimelinessTable,
-        checkpoint_states::CheckpointStatesTable, equivocating_indices::EquivocatingIndicesField,
-        finalized_checkpoint::FinalizedCheckpointField, genesis_time::GenesisTimeField,
-        justified_checkpoint::JustifiedCheckpointField, latest_messages::LatestMessagesTable,
-        parent_root_index::ParentRootIndexMultimapTable,
-        proposer_boost_root::ProposerBoostRootField, slot_index::SlotIndexTable,
-        state_root_index::StateRootIndexTable, time::TimeField,
-        unrealized_finalized_checkpoint::UnrealizedFinalizedCheckpointField,
-        unrealized_justifications::UnrealizedJustificationsTable,
-        unrealized_justified_checkpoint::UnrealizedJustifiedCheckpointField,
+use ream_consensus_beacon::electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState};
+use ream_consensus_misc::{checkpoint::Checkpoint, constants::beacon::GENESIS_SLOT};
+use tracing::warn;
+
+use crate::{
+    cache::HotStateCache,
+    db::{backend::Database, config::WriteBatch},
+    errors::StoreError,
+    freezer::Freezer,
+    tables::{
+        beacon::{
+            beacon_block::{BEACON_BLOCK_TABLE, BeaconBlockTable},
+            beacon_state::{BEACON_STATE_TABLE, BeaconStateTable},
+            blobs_and_proofs::BlobsAndProofsTable,
+            block_timeliness::BlockTimelinessTable,
+            checkpoint_states::CheckpointStatesTable,
+            equivocating_indices::EquivocatingIndicesField,
+            finalized_checkpoint::FinalizedCheckpointField,
+            frozen_slot::FrozenSlotField,
+            genesis_time::GenesisTimeField,
+            head_root::HeadRootField,
+            hot_state_roots::HotStateRootsField,
+            justified_checkpoint::JustifiedCheckpointField,
+            latest_messages::LatestMessagesTable,
+            parent_root_index::{PARENT_ROOT_INDEX_MULTIMAP_TABLE, ParentRootIndexMultimapTable},
+            proposer_boost_root::ProposerBoostRootField,
+            slot_index::SlotIndexTable,
+            state_root_index::StateRootIndexTable,
+            time::TimeField,
+            unrealized_finalized_checkpoint::UnrealizedFinalizedCheckpointField,
+            unrealized_justifications::UnrealizedJustificationsTable,
+            unrealized_justified_checkpoint::UnrealizedJustifiedCheckpointField,
+        },
+        field::Field,
+        known_peers::{BEACON_KNOWN_PEERS_TABLE, KnownPeersTable},
+        multimap_table::MultimapTable,
+        table::Table,
     },
-    table::Table,
 };
 
+/// The number of slots moved to the freezer in one go
+const FREEZER_MIGRATION_SLOTS: u64 = 8_192;
+
+/// The blocks of the canonical chain in a range of slots, in ascending slot order. The blocks are
+/// read from the database one at a time.
+pub struct CanonicalBlocks {
+    beacon_block_provider: BeaconBlockTable,
+    block_roots: vec::IntoIter<(u64, B256)>,
+}
+
+impl Iterator for CanonicalBlocks {
+    type Item = anyhow::Result<SignedBeaconBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (slot, block_root) = self.block_roots.next()?;
+        Some(match self.beacon_block_provider.get(block_root) {
+            Ok(Some(block)) => Ok(block),
+            Ok(None) => Err(anyhow!(
+                "Block {block_root} at slot {slot} is missing from the database"
+            )),
+            Err(err) => Err(err.into()),
+        })
+    }
+}
+
+/// The blocks of the finalized chain in a range of at most `FREEZER_MIGRATION_SLOTS` slots, which
+/// are moved to the freezer in one go.
+struct FinalizedChunk {
+    start_slot: u64,
+    last_block_root: B256,
+    /// The first block after the chunk, so the children of its blocks which aren't on the
+    /// finalized chain can be told apart.
+    next_block_root: B256,
+}
+
 #[derive(Clone, Debug)]
 pub struct BeaconDB {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
     pub data_dir: PathBuf,
+    pub hot_state_cache: Arc<HotStateCache>,
+    pub freezer: Arc<Freezer>,
 }
 
 impl BeaconDB {
     pub fn beacon_block_provider(&self) -> BeaconBlockTable {
         BeaconBlockTable {
             db: self.db.clone(),
+            freezer: self.freezer.clone(),
         }
     }
     pub fn beacon_state_provider(&self) -> BeaconStateTable {
         BeaconStateTable {
             db: self.db.clone(),
+            hot_state_cache: self.hot_state_cache.clone(),
+            freezer: self.freezer.clone(),
         }
     }
 
@@ -105,6 +166,12 @@ impl BeaconDB {
         }
     }
 
+    pub fn frozen_slot_provider(&self) -> FrozenSlotField {
+        FrozenSlotField {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn genesis_time_provider(&self) -> GenesisTimeField {
         GenesisTimeField {
             db: self.db.clone(),
@@ -117,6 +184,18 @@ impl BeaconDB {
         }
     }
 
+    pub fn head_root_provider(&self) -> HeadRootField {
+        HeadRootField {
+            db: self.db.clone(),
+        }
+    }
+
+    pub fn hot_state_roots_provider(&self) -> HotStateRootsField {
+        HotStateRootsField {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn equivocating_indices_provider(&self) -> EquivocatingIndicesField {
         EquivocatingIndicesField {
             db: self.db.clone(),
@@ -135,6 +214,19 @@ impl BeaconDB {
         }
     }
 
+    pub fn known_peers_provider(&self) -> KnownPeersTable {
+        KnownPeersTable {
+            db: self.db.clone(),
+            table: BEACON_KNOWN_PEERS_TABLE,
+        }
+    }
+
+    /// Keeps the commits of all tables in memory until the returned batch is flushed or dropped,
+    /// for bulk imports.
+    pub fn write_batch(&self) -> WriteBatch {
+        WriteBatch::new(self.db.clone())
+    }
+
     pub fn is_initialized(&self) -> bool {
         match self.slot_index_provider().get_highest_slot() {
             Ok(Some(slot)) => slot > 0,
@@ -155,4 +247,521 @@ impl BeaconDB {
 
         Ok(state)
     }
+
+    /// Returns the root of the block at `slot` on the chain of `block_root`, or of the latest block
+    /// before `slot` if the slot is empty. Only the slots and parent roots of the blocks are
+    /// decoded.
+    pub fn get_ancestor(&self, block_root: B256, slot: u64) -> anyhow::Result<Option<B256>> {
+        let beacon_block_provider = self.beacon_block_provider();
+        let mut block_root = block_root;
+        while let Some((block_slot, parent_root)) =
+            beacon_block_provider.get_slot_and_parent_root(block_root)?
+        {
+            if block_slot <= slot {
+                return Ok(Some(block_root));
+            }
+            block_root = parent_root;
+        }
+        Ok(None)
+    }
+
+    /// Returns the `(slot, block_root)` pairs of the blocks of the canonical chain in `slots`, in
+    /// ascending slot order.
+    ///
+    /// Finalized blocks can't be reorged, and the slot index of the finalized slots is rewritten
+    /// to the finalized chain on finalization, so they are read from the slot index. The chain
+    /// after the finalized block is walked back from the head chosen by fork choice through the
+    /// parent roots of the blocks.
+    pub fn get_canonical_block_roots(&self, slots: Range<u64>) -> anyhow::Result<Vec<(u64, B256)>> {
+        let finalized_slot = self.get_finalized_slot()?;
+
+        let finalized_slots = slots.start..slots.end.min(finalized_slot + 1);
+        let mut block_roots = if finalized_slots.is_empty() {
+            vec![]
+        } else {
+            self.slot_index_provider().get_range(finalized_slots)?
+        };
+
+        let unfinalized_start = slots.start.max(finalized_slot + 1);
+        let head_root = match self.head_root_provider().get() {
+            Ok(head_root) => Some(head_root),
+            Err(StoreError::FieldNotInitilized) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if unfinalized_start < slots.end
+            && let Some(head_root) = head_root
+        {
+            let beacon_block_provider = self.beacon_block_provider();
+            let mut unfinalized_block_roots = vec![];
+            let mut block_root = head_root;
+            while let Some((slot, parent_root)) =
+                beacon_block_provider.get_slot_and_parent_root(block_root)?
+                && slot >= unfinalized_start
+            {
+                if slot < slots.end {
+                    unfinalized_block_roots.push((slot, block_root));
+                }
+                block_root = parent_root;
+            }
+            block_roots.extend(unfinalized_block_roots.into_iter().rev());
+        }
+
+        Ok(block_roots)
+    }
+
+    /// Returns the root of the block of the canonical chain at `slot`, if the slot isn't empty.
+    pub fn get_canonical_block_root(&self, slot: u64) -> anyhow::Result<Option<B256>> {
+        Ok(self
+            .get_canonical_block_roots(slot..slot + 1)?
+            .pop()
+            .map(|(_, block_root)| block_root))
+    }
+
+    /// Returns the block of the canonical chain at `slot`, if the slot isn't empty.
+    pub fn get_block_by_slot(&self, slot: u64) -> anyhow::Result<Option<SignedBeaconBlock>> {
+        let Some(block_root) = self.get_canonical_block_root(slot)? else {
+            return Ok(None);
+        };
+        Ok(self.beacon_block_provider().get(block_root)?)
+    }
+
+    /// Iterates over the blocks of the canonical chain in `slots`, in ascending slot order.
+    pub fn iter_canonical_blocks(&self, slots: Range<u64>) -> anyhow::Result<CanonicalBlocks> {
+        Ok(CanonicalBlocks {
+            beacon_block_provider: self.beacon_block_provider(),
+            block_roots: self.get_canonical_block_roots(slots)?.into_iter(),
+        })
+    }
+
+    /// Records the finalized checkpoint, and rewrites the slot index of the newly finalized slots
+    /// to the chain of the finalized block. A block of a fork which was imported after the
+    /// canonical block of its slot replaces it in the slot index, so the entries of the finalized
+    /// slots which aren't on the finalized chain are removed.
+    pub fn update_finalized_checkpoint(
+        &self,
+        finalized_checkpoint: Checkpoint,
+    ) -> anyhow::Result<()> {
+        let previous_finalized_slot = self.get_finalized_slot()?;
+        let beacon_block_provider = self.beacon_block_provider();
+        let slot_index_provider = self.slot_index_provider();
+
+        let mut finalized_slot = None;
+        let mut canonical_slots = HashSet::new();
+        let mut block_root = finalized_checkpoint.root;
+        while let Some((slot, parent_root)) =
+            beacon_block_provider.get_slot_and_parent_root(block_root)?
+            && slot > previous_finalized_slot
+        {
+            finalized_slot.get_or_insert(slot);
+            if slot_index_provider.get(slot)? != Some(block_root) {
+                slot_index_provider.insert(slot, block_root)?;
+            }
+            canonical_slots.insert(slot);
+            block_root = parent_root;
+        }
+
+        if let Some(finalized_slot) = finalized_slot {
+            let fork_slots = slot_index_provider
+                .get_range(previous_finalized_slot + 1..finalized_slot + 1)?
+                .into_iter()
+                .map(|(slot, _)| slot)
+                .filter(|slot| !canonical_slots.contains(slot))
+                .collect::<Vec<_>>();
+            slot_index_provider.remove(&fork_slots)?;
+        }
+
+        // The slot index is rewritten first, so that it is rewritten again after a crash
+        self.finalized_checkpoint_provider()
+            .insert(finalized_checkpoint)?;
+        Ok(())
+    }
+
+    /// Moves the blocks of the finalized chain before the finalized block to the freezer, with the
+    /// first state after every `restore_point_slots` slots, and deletes them and the other states
+    /// of these blocks from the database. The blocks of the forks which branch off the migrated
+    /// blocks can never become canonical, so they and their states are deleted. Returns the number
+    /// of blocks moved.
+    pub fn migrate_to_freezer(&self, restore_point_slots: u64) -> anyhow::Result<usize> {
+        let frozen_slot = match self.frozen_slot_provider().get() {
+            Ok(frozen_slot) => frozen_slot,
+            Err(StoreError::FieldNotInitilized) => GENESIS_SLOT,
+            Err(err) => return Err(err.into()),
+        };
+        let finalized_root = match self.finalized_checkpoint_provider().get() {
+            Ok(finalized_checkpoint) => finalized_checkpoint.root,
+            Err(StoreError::FieldNotInitilized) => return Ok(0),
+            Err(err) => return Err(err.into()),
+        };
+        // The finalized block and its state stay in the database, as the anchor of fork choice
+        let Some((finalized_slot, _)) = self
+            .beacon_block_provider()
+            .get_slot_and_parent_root(finalized_root)?
+        else {
+            return Ok(0);
+        };
+
+        let chunks = self.get_finalized_chunks(frozen_slot, finalized_root)?;
+        let mut last_restore_point = self.freezer.states.last_slot();
+        let mut migrated_blocks = 0;
+        for (index, chunk) in chunks.iter().enumerate() {
+            let block_roots = self.get_chain(chunk.last_block_root, chunk.start_slot)?;
+
+            for &(slot, block_root) in &block_roots {
+                // Values which were moved before a crash are only deleted again
+                if !self.freezer.blocks.contains(&block_root)
+                    && let Some(block_bytes) =
+                        self.db.get(BEACON_BLOCK_TABLE, block_root.as_slice())?
+                {
+                    self.freezer.blocks.append(slot, block_root, &block_bytes)?;
+                }
+
+                let is_restore_point = last_restore_point.is_none_or(|restore_point| {
+                    slot / restore_point_slots > restore_point / restore_point_slots
+                });
+                if is_restore_point
+                    && let Some(state_bytes) =
+                        self.db.get(BEACON_STATE_TABLE, block_root.as_slice())?
+                {
+                    self.freezer.states.append(slot, block_root, &state_bytes)?;
+                    last_restore_point = Some(slot);
+                }
+            }
+            self.freezer.sync()?;
+
+            let next_block_roots = block_roots
+                .iter()
+                .skip(1)
+                .map(|&(_, block_root)| block_root)
+                .chain([chunk.next_block_root]);
+            let mut fork_roots = vec![];
+            for (&(_, block_root), next_block_root) in block_roots.iter().zip(next_block_roots) {
+                fork_roots.extend(
+                    self.parent_root_index_multimap_provider()
+                        .get(block_root)?
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|child_root| *child_root != next_block_root),
+                );
+            }
+            self.delete_forks(fork_roots)?;
+
+            let keys = block_roots
+                .iter()
+                .map(|(_, block_root)| block_root.to_vec())
+                .collect::<Vec<_>>();
+            self.db.remove(BEACON_BLOCK_TABLE, &keys)?;
+            self.db.remove(BEACON_STATE_TABLE, &keys)?;
+            for (_, block_root) in &block_roots {
+                self.hot_state_cache.remove(block_root);
+            }
+            self.frozen_slot_provider().insert(
+                chunks
+                    .get(index + 1)
+                    .map_or(finalized_slot, |chunk| chunk.start_slot),
+            )?;
+
+            migrated_blocks += block_roots.len();
+        }
+        if frozen_slot < finalized_slot {
+            self.frozen_slot_provider().insert(finalized_slot)?;
+        }
+
+        Ok(migrated_blocks)
+    }
+
+    /// Splits the finalized chain from `frozen_slot` up to the finalized block, exclusive, into
+    /// chunks of `FREEZER_MIGRATION_SLOTS` slots, in ascending slot order. The chain is walked
+    /// back once through the parent roots of the blocks, and only the ends of the chunks are
+    /// kept, so that a long chain isn't held in memory.
+    fn get_finalized_chunks(
+        &self,
+        frozen_slot: u64,
+        finalized_root: B256,
+    ) -> anyhow::Result<Vec<FinalizedChunk>> {
+        let beacon_block_provider = self.beacon_block_provider();
+        let Some((_, mut block_root)) =
+            beacon_block_provider.get_slot_and_parent_root(finalized_root)?
+        else {
+            return Ok(vec![]);
+        };
+
+        let mut chunks: Vec<FinalizedChunk> = vec![];
+        let mut next_block_root = finalized_root;
+        while let Some((slot, parent_root)) =
+            beacon_block_provider.get_slot_and_parent_root(block_root)?
+            && slot >= frozen_slot
+        {
+            if chunks.last().is_none_or(|chunk| slot < chunk.start_slot) {
+                chunks.push(FinalizedChunk {
+                    start_slot: frozen_slot
+                        + (slot - frozen_slot) / FREEZER_MIGRATION_SLOTS * FREEZER_MIGRATION_SLOTS,
+                    last_block_root: block_root,
+                    next_block_root,
+                });
+            }
+            next_block_root = block_root;
+            block_root = parent_root;
+        }
+        chunks.reverse();
+        Ok(chunks)
+    }
+
+    /// Returns the `(slot, block_root)` pairs of the chain of `block_root` from `start_slot`, in
+    /// ascending slot order.
+    fn get_chain(&self, block_root: B256, start_slot: u64) -> anyhow::Result<Vec<(u64, B256)>> {
+        let beacon_block_provider = self.beacon_block_provider();
+        let mut block_roots = vec![];
+        let mut block_root = block_root;
+        while let Some((slot, parent_root)) =
+            beacon_block_provider.get_slot_and_parent_root(block_root)?
+            && slot >= start_slot
+        {
+            block_roots.push((slot, block_root));
+            block_root = parent_root;
+        }
+        block_roots.reverse();
+        Ok(block_roots)
+    }
+
+    /// Deletes the blocks of `fork_roots` and all of their descendants with their states, and
+    /// removes them from the slot and parent root indexes.
+    fn delete_forks(&self, fork_roots: Vec<B256>) -> anyhow::Result<()> {
+        let beacon_block_provider = self.beacon_block_provider();
+        let parent_root_index_provider = self.parent_root_index_multimap_provider();
+        let slot_index_provider = self.slot_index_provider();
+
+        let mut pending_roots = fork_roots;
+        let mut keys = vec![];
+        let mut indexed_slots = vec![];
+        while let Some(block_root) = pending_roots.pop() {
+            if let Some((slot, _)) = beacon_block_provider.get_slot_and_parent_root(block_root)?
+                && slot_index_provider.get(slot)? == Some(block_root)
+            {
+                indexed_slots.push(slot);
+            }
+            pending_roots.extend(
+                parent_root_index_provider
+                    .get(block_root)?
+                    .unwrap_or_default(),
+            );
+            self.hot_state_cache.remove(&block_root);
+            keys.push(block_root.to_vec());
+        }
+
+        slot_index_provider.remove(&indexed_slots)?;
+        self.db.remove(PARENT_ROOT_INDEX_MULTIMAP_TABLE, &keys)?;
+        self.db.remove(BEACON_BLOCK_TABLE, &keys)?;
+        self.db.remove(BEACON_STATE_TABLE, &keys)?;
+        Ok(())
+    }
+
+    /// Returns the slot of the finalized block, or the genesis slot before the first
+    /// finalization.
+    fn get_finalized_slot(&self) -> anyhow::Result<u64> {
+        let finalized_root = match self.finalized_checkpoint_provider().get() {
+            Ok(finalized_checkpoint) => finalized_checkpoint.root,
+            Err(StoreError::FieldNotInitilized) => return Ok(GENESIS_SLOT),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(self
+            .beacon_block_provider()
+            .get_slot_and_parent_root(finalized_root)?
+            .map_or(GENESIS_SLOT, |(slot, _)| slot))
+    }
+
+    /// Records which states are in the hot state cache, so they can be loaded back on the next
+    /// startup. Called on shutdown.
+    pub fn persist_hot_state_roots(&self) -> anyhow::Result<()> {
+        Ok(self
+            .hot_state_roots_provider()
+            .insert(self.hot_state_cache.block_roots())?)
+    }
+
+    /// Loads the states which were in the hot state cache on the last shutdown back into it, so
+    /// the first block imports and API requests after a restart don't have to decode them from
+    /// the database. Returns the number of states loaded.
+    pub fn warm_up_hot_state_cache(&self) -> anyhow::Result<usize> {
+        let block_roots = match self.hot_state_roots_provider().get() {
+            Ok(block_roots) => block_roots,
+            Err(StoreError::FieldNotInitilized) => return Ok(0),
+            Err(err) => return Err(err.into()),
+        };
+        // Only a clean shutdown records the cached states, so they aren't loaded again after a
+        // crash when they may be outdated.
+        self.hot_state_roots_provider().insert(vec![])?;
+
+        let mut loaded_states = 0;
+        // Loaded least recently used first, so that the cache ends up in the same order
+        for block_root in block_roots.into_iter().rev() {
+            match self.beacon_state_provider().get(block_root)? {
+                Some(_) => loaded_states += 1,
+                None => warn!("The hot state of block {block_root} is no longer in the database"),
+            }
+        }
+        Ok(loaded_states)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::BLSSignature;
+    use ream_consensus_beacon::electra::beacon_block::{BeaconBlock, SignedBeaconBlock};
+    use ream_consensus_misc::{
+        checkpoint::Checkpoint,
+        slot_epoch::{Epoch, Slot},
+    };
+    use tempdir::TempDir;
+    use tree_hash::TreeHash;
+
+    use super::BeaconDB;
+    use crate::{
+        db::{ReamDB, config::DBConfig},
+        tables::{
+            beacon::{beacon_block::BEACON_BLOCK_TABLE, beacon_state::BEACON_STATE_TABLE},
+            field::Field,
+            table::Table,
+        },
+    };
+
+    fn insert_block(db: &BeaconDB, slot: u64, parent_root: B256) -> anyhow::Result<B256> {
+        let block = SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: Slot::new(slot),
+                parent_root,
+                ..Default::default()
+            },
+            signature: BLSSignature::default(),
+        };
+        let block_root = block.message.tree_hash_root();
+        db.beacon_block_provider().insert(block_root, block)?;
+        Ok(block_root)
+    }
+
+    #[test]
+    fn test_canonical_chain_skips_forks() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_canonical_chain_skips_forks")?;
+        let db =
+            ReamDB::new(tmp_dir.path().to_path_buf(), DBConfig::default())?.init_beacon_db()?;
+
+        let genesis_root = insert_block(&db, 0, B256::ZERO)?;
+        let block_1_root = insert_block(&db, 1, genesis_root)?;
+        let block_3_root = insert_block(&db, 3, block_1_root)?;
+        // The blocks of a fork, which replace the canonical block in the slot index and have the
+        // highest slot
+        let fork_root = insert_block(&db, 2, genesis_root)?;
+        let fork_4_root = insert_block(&db, 4, fork_root)?;
+        assert_eq!(db.slot_index_provider().get(2)?, Some(fork_root));
+        assert_eq!(
+            db.slot_index_provider().get_highest_root()?,
+            Some(fork_4_root)
+        );
+        db.head_root_provider().insert(block_3_root)?;
+
+        let canonical_chain = vec![(0, genesis_root), (1, block_1_root), (3, block_3_root)];
+        assert_eq!(db.get_canonical_block_roots(0..5)?, canonical_chain);
+        assert_eq!(db.get_canonical_block_root(2)?, None);
+        assert_eq!(db.get_canonical_block_root(4)?, None);
+        assert_eq!(db.get_ancestor(block_3_root, 2)?, Some(block_1_root));
+        assert_eq!(db.get_ancestor(fork_root, 1)?, Some(genesis_root));
+
+        db.update_finalized_checkpoint(Checkpoint {
+            epoch: Epoch::new(0),
+            root: block_1_root,
+        })?;
+        assert_eq!(db.get_canonical_block_roots(0..5)?, canonical_chain);
+        assert_eq!(db.get_canonical_block_roots(1..3)?, vec![(1, block_1_root)]);
+
+        // The fork block is removed from the slot index once its slot is finalized
+        db.update_finalized_checkpoint(Checkpoint {
+            epoch: Epoch::new(1),
+            root: block_3_root,
+        })?;
+        assert_eq!(db.slot_index_provider().get(2)?, None);
+        assert_eq!(db.slot_index_provider().get(3)?, Some(block_3_root));
+        assert_eq!(db.get_canonical_block_roots(0..5)?, canonical_chain);
+
+        let block_slots = db
+            .iter_canonical_blocks(1..4)?
+            .map(|block| Ok(block?.message.slot.as_u64()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(block_slots, vec![1, 3]);
+        assert_eq!(
+            db.get_block_by_slot(3)?
+                .map(|block| block.message.slot.as_u64()),
+            Some(3)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_to_freezer() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_migrate_to_freezer")?;
+        let db =
+            ReamDB::new(tmp_dir.path().to_path_buf(), DBConfig::default())?.init_beacon_db()?;
+
+        let mut block_roots = vec![];
+        let mut parent_root = B256::ZERO;
+        for slot in 0..4 {
+            parent_root = insert_block(&db, slot, parent_root)?;
+            db.db.insert(
+                BEACON_STATE_TABLE,
+                &[(parent_root.to_vec(), format!("state {slot}").into_bytes())],
+            )?;
+            block_roots.push((slot, parent_root));
+        }
+        // A fork which branches off the finalized chain, with a block after the finalized block
+        let fork_root = insert_block(&db, 2, block_roots[0].1)?;
+        let fork_4_root = insert_block(&db, 4, fork_root)?;
+        for fork_root in [fork_root, fork_4_root] {
+            db.db.insert(
+                BEACON_STATE_TABLE,
+                &[(fork_root.to_vec(), b"fork state".to_vec())],
+            )?;
+        }
+        db.update_finalized_checkpoint(Checkpoint {
+            epoch: Epoch::new(0),
+            root: block_roots[3].1,
+        })?;
+
+        assert_eq!(db.migrate_to_freezer(2)?, 3);
+        assert_eq!(db.migrate_to_freezer(2)?, 0);
+        assert_eq!(db.freezer.last_frozen_slot(), Some(2));
+
+        for &(slot, block_root) in &block_roots[..3] {
+            assert_eq!(db.db.get(BEACON_BLOCK_TABLE, block_root.as_slice())?, None);
+            assert_eq!(
+                db.beacon_block_provider()
+                    .get(block_root)?
+                    .map(|block| block.message.slot.as_u64()),
+                Some(slot)
+            );
+        }
+        assert_eq!(db.get_canonical_block_roots(0..4)?, block_roots);
+
+        // The fork blocks and their states are deleted
+        for fork_root in [fork_root, fork_4_root] {
+            assert_eq!(db.beacon_block_provider().get(fork_root)?, None);
+            assert_eq!(db.db.get(BEACON_STATE_TABLE, fork_root.as_slice())?, None);
+        }
+        assert_eq!(db.slot_index_provider().get(2)?, Some(block_roots[2].1));
+        assert_eq!(db.slot_index_provider().get(4)?, None);
+
+        // The first state of every 2 slots is kept, the other states are deleted
+        let frozen_state = |index: usize| db.freezer.states.get(&block_roots[index].1);
+        assert_eq!(frozen_state(0)?, Some(b"state 0".to_vec()));
+        assert_eq!(frozen_state(1)?, None);
+        assert_eq!(frozen_state(2)?, Some(b"state 2".to_vec()));
+        assert_eq!(
+            db.db.get(BEACON_STATE_TABLE, block_roots[1].1.as_slice())?,
+            None
+        );
+        assert_eq!(
+            db.db.get(BEACON_STATE_TABLE, block_roots[3].1.as_slice())?,
+            Some(b"state 3".to_vec())
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,198 @@
+//! Tuning of the database, and the batching of commits during bulk imports.
+//!
+//! Every table commits its writes in its own transaction, which is written to disk before the
+//! commit returns. While a [WriteBatch] of a database is alive, the commits to that database are
+//! only kept in memory instead, and they are written to disk together once the batch is flushed or
+//! after `max_write_buffer` commits.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use anyhow::bail;
+use tracing::warn;
+
+use super::{REDB_CACHE_SIZE, backend::Database};
+
+/// The number of commits a write batch keeps in memory before writing them to disk.
+pub const DEFAULT_MAX_WRITE_BUFFER: usize = 4_096;
+
+/// The durability of the commits outside of a write batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DBDurability {
+    /// Commits are written to disk before they return
+    #[default]
+    Immediate,
+    /// Commits are written to disk eventually, which is faster but loses the latest commits on a
+    /// crash
+    Eventual,
+    /// Like `Immediate`, but also protects against partially written pages on a crash
+    Paranoid,
+}
+
+impl FromStr for DBDurability {
+    type Err = anyhow::Error;
+
+    fn from_str(durability: &str) -> Result<Self, Self::Err> {
+        match durability {
+            "immediate" => Ok(DBDurability::Immediate),
+            "eventual" => Ok(DBDurability::Eventual),
+            "paranoid" => Ok(DBDurability::Paranoid),
+            _ => bail!("Invalid durability {durability}, expected immediate, eventual or paranoid"),
+        }
+    }
+}
+
+impl Display for DBDurability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DBDurability::Immediate => write!(f, "immediate"),
+            DBDurability::Eventual => write!(f, "eventual"),
+            DBDurability::Paranoid => write!(f, "paranoid"),
+        }
+    }
+}
+
+/// The key-value store the tables are kept in. `rocksdb` and `mdbx` need ream to be built with
+/// the feature of the same name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DBBackend {
+    #[default]
+    Redb,
+    RocksDB,
+    Mdbx,
+}
+
+impl FromStr for DBBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(backend: &str) -> Result<Self, Self::Err> {
+        match backend {
+            "redb" => Ok(DBBackend::Redb),
+            "rocksdb" => Ok(DBBackend::RocksDB),
+            "mdbx" => Ok(DBBackend::Mdbx),
+            _ => bail!("Invalid database backend {backend}, expected redb, rocksdb or mdbx"),
+        }
+    }
+}
+
+impl Display for DBBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DBBackend::Redb => write!(f, "redb"),
+            DBBackend::RocksDB => write!(f, "rocksdb"),
+            DBBackend::Mdbx => write!(f, "mdbx"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DBConfig {
+    pub backend: DBBackend,
+    /// The size of the cache of the database in bytes
+    pub cache_size: usize,
+    /// The number of commits a write batch keeps in memory before writing them to disk
+    pub max_write_buffer: usize,
+    pub durability: DBDurability,
+    /// Compact the database file when opening it, which releases the space of deleted data
+    pub compact_on_startup: bool,
+}
+
+impl Default for DBConfig {
+    fn default() -> Self {
+        Self {
+            backend: DBBackend::Redb,
+            cache_size: REDB_CACHE_SIZE,
+            max_write_buffer: DEFAULT_MAX_WRITE_BUFFER,
+            durability: DBDurability::Immediate,
+            compact_on_startup: false,
+        }
+    }
+}
+
+/// Picks the durability of the commits of one database, which are only kept in memory while a
+/// write batch of the database is alive.
+#[derive(Debug)]
+pub struct CommitBuffer {
+    durability: DBDurability,
+    max_write_buffer: usize,
+    active_write_batches: AtomicUsize,
+    buffered_commits: AtomicUsize,
+}
+
+impl CommitBuffer {
+    pub(crate) fn new(config: &DBConfig) -> Self {
+        Self {
+            durability: config.durability,
+            max_write_buffer: config.max_write_buffer.max(1),
+            active_write_batches: AtomicUsize::new(0),
+            buffered_commits: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn durability(&self) -> DBDurability {
+        self.durability
+    }
+
+    /// The durability of the next commit, or `None` if it is only kept in memory because a write
+    /// batch is alive and its buffer isn't full.
+    pub(crate) fn commit_durability(&self) -> Option<DBDurability> {
+        if self.active_write_batches.load(Ordering::Acquire) == 0 {
+            return Some(self.durability);
+        }
+        let buffered_commits = self.buffered_commits.fetch_add(1, Ordering::AcqRel) + 1;
+        if buffered_commits >= self.max_write_buffer {
+            self.buffered_commits.store(0, Ordering::Release);
+            return Some(self.durability);
+        }
+        None
+    }
+
+    fn start_write_batch(&self) {
+        self.active_write_batches.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn end_write_batch(&self) {
+        self.active_write_batches.fetch_sub(1, Ordering::AcqRel);
+        self.buffered_commits.store(0, Ordering::Release);
+    }
+}
+
+/// Keeps the commits of all tables of its database in memory while it's alive, e.g. while
+/// importing a segment of blocks during sync where a disk write per commit would dominate the
+/// import time. The commits are written to disk when the batch is flushed or dropped.
+pub struct WriteBatch {
+    db: Arc<dyn Database>,
+    flushed: bool,
+}
+
+impl WriteBatch {
+    pub(crate) fn new(db: Arc<dyn Database>) -> Self {
+        db.commit_buffer().start_write_batch();
+        Self { db, flushed: false }
+    }
+
+    /// Writes the commits made during the batch to disk.
+    pub fn flush(mut self) -> anyhow::Result<()> {
+        self.flushed = true;
+        self.db.commit_buffer().end_write_batch();
+        Ok(self.db.flush()?)
+    }
+}
+
+impl Drop for WriteBatch {
+    fn drop(&mut self) {
+        if self.flushed {
+            return;
+        }
+        self.db.commit_buffer().end_write_batch();
+        if let Err(err) = self.db.flush() {
+            warn!("Failed to write the buffered commits of a write batch: {err:?}");
+        }
+    }
+}
@@ -1,16 +1,21 @@
 use std::sync::Arc;
 
-use redb::Database;
-
-use crate::tables::lean::{
-    known_votes::KnownVotesTable, latest_finalized::LatestFinalizedField,
-    latest_justified::LatestJustifiedField, lean_block::LeanBlockTable, lean_state::LeanStateTable,
-    slot_index::SlotIndexTable, state_root_index::StateRootIndexTable,
+use crate::{
+    db::backend::Database,
+    tables::{
+        known_peers::{KnownPeersTable, LEAN_KNOWN_PEERS_TABLE},
+        lean::{
+            known_votes::KnownVotesTable, latest_finalized::LatestFinalizedField,
+            latest_justified::LatestJustifiedField, lean_block::LeanBlockTable,
+            lean_proof::LeanProofTable, lean_state::LeanStateTable, safe_target::SafeTargetField,
+            slot_index::SlotIndexTable, state_root_index::StateRootIndexTable,
+        },
+    },
 };
 
 #[derive(Clone, Debug)]
 pub struct LeanDB {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl LeanDB {
@@ -25,6 +30,12 @@ impl LeanDB {
         }
     }
 
+    pub fn lean_proof_provider(&self) -> LeanProofTable {
+        LeanProofTable {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn slot_index_provider(&self) -> SlotIndexTable {
         SlotIndexTable {
             db: self.db.clone(),
@@ -54,4 +65,17 @@ impl LeanDB {
             db: self.db.clone(),
         }
     }
+
+    pub fn safe_target_provider(&self) -> SafeTargetField {
+        SafeTargetField {
+            db: self.db.clone(),
+        }
+    }
+
+    pub fn known_peers_provider(&self) -> KnownPeersTable {
+        KnownPeersTable {
+            db: self.db.clone(),
+            table: LEAN_KNOWN_PEERS_TABLE,
+        }
+    }
 }
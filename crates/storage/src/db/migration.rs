@@ -0,0 +1,146 @@
+use std::{mem, ops::Bound, sync::Arc};
+
+use tracing::info;
+
+use super::{
+    backend::{Database, TableDefinition, TableKind},
+    config::WriteBatch,
+};
+
+/// The size of the entries written to the target database in a single commit.
+const MIGRATION_COMMIT_BYTES: usize = 64 * 1_024 * 1_024;
+
+/// The most entries read from the source database at once.
+const MIGRATION_MAX_PAGE_ENTRIES: usize = 4_096;
+
+/// Copies the tables of one database into another, e.g. to move a node to another backend.
+/// Returns the number of entries copied.
+///
+/// The entries are read in pages and written in commits of [MIGRATION_COMMIT_BYTES]. A page is
+/// sized from the entries read so far to about a quarter of a commit, so small entries are read
+/// thousands at a time while a table of beacon states, which can take hundreds of megabytes each,
+/// is read one state at a time.
+pub fn migrate(
+    source: &dyn Database,
+    target: Arc<dyn Database>,
+    tables: &[TableDefinition],
+) -> anyhow::Result<u64> {
+    target.create_tables(tables)?;
+    let write_batch = WriteBatch::new(target.clone());
+
+    let mut copied_entries = 0;
+    for table in tables {
+        let mut last_key: Option<Vec<u8>> = None;
+        let mut page_entries = 1;
+        let mut pending_entries = vec![];
+        let mut pending_bytes = 0;
+        loop {
+            let start = match &last_key {
+                Some(key) => Bound::Excluded(key.as_slice()),
+                None => Bound::Unbounded,
+            };
+            let page = source.range(*table, start, Bound::Unbounded, Some(page_entries))?;
+            let Some((page_last_key, _)) = page.last() else {
+                break;
+            };
+            last_key = Some(page_last_key.clone());
+
+            let page_len = page.len();
+            let mut page_bytes = 0;
+            for (key, value) in page {
+                page_bytes += key.len() + value.len();
+                match table.kind {
+                    // A page can end between the values of a key, so the values of multimap
+                    // tables are read by key
+                    TableKind::Multimap => {
+                        if pending_entries
+                            .last()
+                            .is_some_and(|(pending_key, _)| *pending_key == key)
+                        {
+                            continue;
+                        }
+                        for value in source.get_all(*table, &key)? {
+                            pending_bytes += key.len() + value.len();
+                            pending_entries.push((key.clone(), value));
+                        }
+                    }
+                    TableKind::Bytes | TableKind::Integer => {
+                        pending_bytes += key.len() + value.len();
+                        pending_entries.push((key, value));
+                    }
+                }
+
+                if pending_bytes >= MIGRATION_COMMIT_BYTES {
+                    copied_entries += pending_entries.len() as u64;
+                    target.insert(*table, &mem::take(&mut pending_entries))?;
+                    pending_bytes = 0;
+                }
+            }
+            page_entries = (MIGRATION_COMMIT_BYTES / 4 / (page_bytes / page_len).max(1))
+                .clamp(1, MIGRATION_MAX_PAGE_ENTRIES);
+        }
+        if !pending_entries.is_empty() {
+            copied_entries += pending_entries.len() as u64;
+            target.insert(*table, &pending_entries)?;
+        }
+        info!("Migrated table {}", table.name);
+    }
+
+    write_batch.flush()?;
+    Ok(copied_entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::{
+        db::{backend::redb::RedbDatabase, config::DBConfig},
+        tables::beacon::{
+            parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE, slot_index::SLOT_INDEX_TABLE,
+        },
+    };
+
+    #[test]
+    fn test_migrate() -> anyhow::Result<()> {
+        let source_dir = TempDir::new("test_migrate_source")?;
+        let target_dir = TempDir::new("test_migrate_target")?;
+        let config = DBConfig::default();
+        let source = RedbDatabase::open(source_dir.path(), &config)?;
+        let target: Arc<dyn Database> = Arc::new(RedbDatabase::open(target_dir.path(), &config)?);
+        let tables = [SLOT_INDEX_TABLE, PARENT_ROOT_INDEX_MULTIMAP_TABLE];
+        source.create_tables(&tables)?;
+
+        // Enough entries to be read over several pages
+        let slot_entries = (0..10_000u64)
+            .map(|slot| (slot.to_le_bytes().to_vec(), vec![slot as u8; 32]))
+            .collect::<Vec<_>>();
+        source.insert(SLOT_INDEX_TABLE, &slot_entries)?;
+        let parent_entries = (0..3_000u64)
+            .flat_map(|parent| {
+                let parent_root = [vec![0; 24], parent.to_be_bytes().to_vec()].concat();
+                (0..3u8).map(move |child| (parent_root.clone(), vec![child; 32]))
+            })
+            .collect::<Vec<_>>();
+        source.insert(PARENT_ROOT_INDEX_MULTIMAP_TABLE, &parent_entries)?;
+
+        let source_entries = tables
+            .iter()
+            .map(|table| source.range(*table, Bound::Unbounded, Bound::Unbounded, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let copied_entries = migrate(&source, target.clone(), &tables)?;
+
+        assert_eq!(
+            copied_entries,
+            source_entries.iter().map(Vec::len).sum::<usize>() as u64
+        );
+        for (table, entries) in tables.iter().zip(source_entries) {
+            assert_eq!(
+                target.range(*table, Bound::Unbounded, Bound::Unbounded, None)?,
+                entries
+            );
+        }
+        Ok(())
+    }
+}
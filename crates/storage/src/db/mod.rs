@@ -1,37 +1,25 @@
+pub mod backend;
 pub mod beacon;
+pub mod config;
 pub mod lean;
+pub mod migration;
 
 use std::{fs, io, path::PathBuf, sync::Arc};
 
-use anyhow::Result;
+use anyhow::{Result, ensure};
+use backend::Database;
 use beacon::BeaconDB;
+use config::{DBBackend, DBConfig};
 use lean::LeanDB;
-use redb::{Builder, Database};
 use tracing::info;
 
 use crate::{
+    cache::HotStateCache,
     errors::StoreError,
+    freezer::Freezer,
     tables::{
-        beacon::{
-            beacon_block::BEACON_BLOCK_TABLE, beacon_state::BEACON_STATE_TABLE,
-            blobs_and_proofs::BLOB_FOLDER_NAME, block_timeliness::BLOCK_TIMELINESS_TABLE,
-            checkpoint_states::CHECKPOINT_STATES_TABLE,
-            equivocating_indices::EQUIVOCATING_INDICES_FIELD,
-            finalized_checkpoint::FINALIZED_CHECKPOINT_FIELD, genesis_time::GENESIS_TIME_FIELD,
-            justified_checkpoint::JUSTIFIED_CHECKPOINT_FIELD,
-            latest_messages::LATEST_MESSAGES_TABLE,
-            parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE,
-            proposer_boost_root::PROPOSER_BOOST_ROOT_FIELD, slot_index::SLOT_INDEX_TABLE,
-            state_root_index::STATE_ROOT_INDEX_TABLE, time::TIME_FIELD,
-            unrealized_finalized_checkpoint::UNREALIZED_FINALIZED_CHECKPOINT_FIELD,
-            unrealized_justifications::UNREALIZED_JUSTIFICATIONS_TABLE,
-            unrealized_justified_checkpoint::UNREALIZED_JUSTIFED_CHECKPOINT_FIELD,
-        },
-        lean::{
-            latest_finalized::LATEST_FINALIZED_FIELD, latest_justified::LATEST_JUSTIFIED_FIELD,
-            lean_block::LEAN_BLOCK_TABLE, lean_state::LEAN_STATE_TABLE,
-            slot_index::LEAN_SLOT_INDEX_TABLE, state_root_index::LEAN_STATE_ROOT_INDEX_TABLE,
-        },
+        beacon::{BEACON_TABLES, blobs_and_proofs::BLOB_FOLDER_NAME},
+        lean::LEAN_TABLES,
     },
 };
 
@@ -44,67 +32,54 @@ pub const REDB_CACHE_SIZE: usize = 1_024 * 1_024 * 1_024;
 
 #[derive(Clone, Debug)]
 pub struct ReamDB {
-    db: Arc<Database>,
+    db: Arc<dyn Database>,
+    backend: DBBackend,
     data_dir: PathBuf,
 }
 
 impl ReamDB {
-    pub fn new(data_dir: PathBuf) -> Result<Self, StoreError> {
-        let db = Builder::new()
-            .set_cache_size(REDB_CACHE_SIZE)
-            .create(data_dir.join(REDB_FILE))?;
+    pub fn new(data_dir: PathBuf, config: DBConfig) -> Result<Self, StoreError> {
+        let db = backend::open(&data_dir, config.backend, &config)?;
 
         Ok(ReamDB {
-            db: Arc::new(db),
+            db,
+            backend: config.backend,
             data_dir,
         })
     }
 
     pub fn init_beacon_db(&self) -> Result<BeaconDB, StoreError> {
-        let write_txn = self.db.begin_write()?;
-
-        write_txn.open_table(BEACON_BLOCK_TABLE)?;
-        write_txn.open_table(BEACON_STATE_TABLE)?;
-        write_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
-        write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        write_txn.open_table(EQUIVOCATING_INDICES_FIELD)?;
-        write_txn.open_table(FINALIZED_CHECKPOINT_FIELD)?;
-        write_txn.open_table(GENESIS_TIME_FIELD)?;
-        write_txn.open_table(JUSTIFIED_CHECKPOINT_FIELD)?;
-        write_txn.open_table(LATEST_MESSAGES_TABLE)?;
-        write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
-        write_txn.open_table(PROPOSER_BOOST_ROOT_FIELD)?;
-        write_txn.open_table(SLOT_INDEX_TABLE)?;
-        write_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
-        write_txn.open_table(TIME_FIELD)?;
-        write_txn.open_table(UNREALIZED_FINALIZED_CHECKPOINT_FIELD)?;
-        write_txn.open_table(UNREALIZED_JUSTIFICATIONS_TABLE)?;
-        write_txn.open_table(UNREALIZED_JUSTIFED_CHECKPOINT_FIELD)?;
-        write_txn.commit()?;
+        self.db.create_tables(&BEACON_TABLES)?;
 
         fs::create_dir_all(self.data_dir.join(BLOB_FOLDER_NAME))?;
 
         Ok(BeaconDB {
             db: self.db.clone(),
             data_dir: self.data_dir.clone(),
+            hot_state_cache: Arc::new(HotStateCache::new()),
+            freezer: Arc::new(Freezer::open(&self.data_dir)?),
         })
     }
 
     pub fn init_lean_db(&self) -> Result<LeanDB, StoreError> {
-        let write_txn = self.db.begin_write()?;
-
-        write_txn.open_table(LATEST_FINALIZED_FIELD)?;
-        write_txn.open_table(LATEST_JUSTIFIED_FIELD)?;
-        write_txn.open_table(LEAN_BLOCK_TABLE)?;
-        write_txn.open_table(LEAN_STATE_TABLE)?;
-        write_txn.open_table(LEAN_SLOT_INDEX_TABLE)?;
-        write_txn.open_table(LEAN_STATE_ROOT_INDEX_TABLE)?;
-        write_txn.commit()?;
+        self.db.create_tables(&LEAN_TABLES)?;
 
         Ok(LeanDB {
             db: self.db.clone(),
         })
     }
+
+    /// Copies all tables into the database of another backend in the same data directory.
+    /// Returns the number of entries copied.
+    pub fn migrate_to(&self, backend: DBBackend, config: &DBConfig) -> anyhow::Result<u64> {
+        ensure!(
+            backend != self.backend,
+            "The database already uses the {backend} backend"
+        );
+        let target = backend::open(&self.data_dir, backend, config)?;
+        let tables = [BEACON_TABLES.as_slice(), LEAN_TABLES.as_slice()].concat();
+        migration::migrate(self.db.as_ref(), target, &tables)
+    }
 }
 
 pub fn reset_db(db_path: &PathBuf) -> anyhow::Result<()> {
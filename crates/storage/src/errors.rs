@@ -16,6 +16,20 @@ pub enum StoreError {
 
     #[error("SnappyError not found {0}")]
     SnappyError(#[from] snap::Error),
+
+    #[error("Table {0} is not known to the database")]
+    UnknownTable(String),
+
+    #[error("ream was built without the {0} database backend, rebuild it with `--features {0}`")]
+    BackendNotEnabled(String),
+
+    #[cfg(feature = "rocksdb")]
+    #[error("RocksDB error: {0}")]
+    RocksDB(#[from] rocksdb::Error),
+
+    #[cfg(feature = "mdbx")]
+    #[error("MDBX error: {0}")]
+    Mdbx(#[from] libmdbx::Error),
 }
 
 impl From<redb::Error> for StoreError {
@@ -54,6 +68,12 @@ impl From<redb::DatabaseError> for StoreError {
     }
 }
 
+impl From<redb::CompactionError> for StoreError {
+    fn from(err: redb::CompactionError) -> Self {
+        StoreError::Redb(Box::new(err.into()))
+    }
+}
+
 impl From<ssz::DecodeError> for StoreError {
     fn from(value: ssz::DecodeError) -> Self {
         StoreError::DecodeError(format!("{value:?}"))
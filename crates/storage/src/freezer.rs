@@ -0,0 +1,274 @@
+//! Append-only storage of the finalized chain.
+//!
+//! Finalized blocks and states can't change anymore, so they are moved out of the database into
+//! flat files, in slot order, which keeps the database small and makes reading a range of
+//! finalized blocks sequential. Every kind of value is kept in a data file of snappy compressed
+//! SSZ values and an index file of fixed size entries pointing into it. A value is written to the
+//! data file before its index entry, so a value without an index entry after a crash is dropped
+//! when the files are opened again.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::B256;
+use parking_lot::{Mutex, RwLock};
+use snap::raw::{Decoder, Encoder};
+
+use crate::errors::StoreError;
+
+pub const FREEZER_FOLDER_NAME: &str = "beacon_freezer";
+
+/// block_root (32 bytes) | slot (8 bytes) | offset (8 bytes) | length (8 bytes)
+const INDEX_ENTRY_SIZE: usize = 56;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IndexEntry {
+    slot: u64,
+    offset: u64,
+    length: u64,
+}
+
+impl IndexEntry {
+    fn end(&self) -> u64 {
+        self.offset + self.length
+    }
+}
+
+fn encode_index_entry(block_root: B256, entry: IndexEntry) -> [u8; INDEX_ENTRY_SIZE] {
+    let mut bytes = [0; INDEX_ENTRY_SIZE];
+    bytes[..32].copy_from_slice(block_root.as_slice());
+    bytes[32..40].copy_from_slice(&entry.slot.to_le_bytes());
+    bytes[40..48].copy_from_slice(&entry.offset.to_le_bytes());
+    bytes[48..].copy_from_slice(&entry.length.to_le_bytes());
+    bytes
+}
+
+fn decode_index_entry(bytes: &[u8]) -> (B256, IndexEntry) {
+    let read_u64 = |position: usize| {
+        u64::from_le_bytes(
+            bytes[position..position + 8]
+                .try_into()
+                .expect("Index entries are 56 bytes long"),
+        )
+    };
+    (
+        B256::from_slice(&bytes[..32]),
+        IndexEntry {
+            slot: read_u64(32),
+            offset: read_u64(40),
+            length: read_u64(48),
+        },
+    )
+}
+
+#[derive(Debug)]
+struct FreezerWriter {
+    data_file: File,
+    index_file: File,
+    data_len: u64,
+    last_slot: Option<u64>,
+}
+
+/// The values of one kind, keyed by the root of their block.
+#[derive(Debug)]
+pub(crate) struct FreezerFile {
+    data_path: PathBuf,
+    writer: Mutex<FreezerWriter>,
+    index: RwLock<HashMap<B256, IndexEntry>>,
+}
+
+impl FreezerFile {
+    fn open(freezer_dir: &Path, name: &str) -> Result<Self, StoreError> {
+        let data_path = freezer_dir.join(format!("{name}.ssz_snappy"));
+        let index_path = freezer_dir.join(format!("{name}.index"));
+        let data_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&data_path)?;
+        let mut index_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(&index_path)?;
+
+        let mut index_bytes = vec![];
+        index_file.read_to_end(&mut index_bytes)?;
+        let data_file_len = data_file.metadata()?.len();
+
+        let mut index = HashMap::new();
+        let mut data_len = 0;
+        let mut last_slot = None;
+        let mut valid_entries = 0;
+        for entry_bytes in index_bytes.chunks_exact(INDEX_ENTRY_SIZE) {
+            let (block_root, entry) = decode_index_entry(entry_bytes);
+            if entry.offset != data_len || entry.end() > data_file_len {
+                break;
+            }
+            data_len = entry.end();
+            last_slot = Some(entry.slot);
+            index.insert(block_root, entry);
+            valid_entries += 1;
+        }
+
+        // Drop what was written after the last complete value
+        index_file.set_len((valid_entries * INDEX_ENTRY_SIZE) as u64)?;
+        data_file.set_len(data_len)?;
+
+        Ok(Self {
+            data_path,
+            writer: Mutex::new(FreezerWriter {
+                data_file,
+                index_file,
+                data_len,
+                last_slot,
+            }),
+            index: RwLock::new(index),
+        })
+    }
+
+    pub(crate) fn contains(&self, block_root: &B256) -> bool {
+        self.index.read().contains_key(block_root)
+    }
+
+    /// The slot of the last value, which is the highest slot as values are appended in slot
+    /// order.
+    pub(crate) fn last_slot(&self) -> Option<u64> {
+        self.writer.lock().last_slot
+    }
+
+    /// Returns the SSZ encoded value of the block.
+    pub(crate) fn get(&self, block_root: &B256) -> Result<Option<Vec<u8>>, StoreError> {
+        let Some(entry) = self.index.read().get(block_root).copied() else {
+            return Ok(None);
+        };
+
+        let mut data_file = File::open(&self.data_path)?;
+        data_file.seek(SeekFrom::Start(entry.offset))?;
+        let mut bytes = vec![0; entry.length as usize];
+        data_file.read_exact(&mut bytes)?;
+        Ok(Some(Decoder::new().decompress_vec(&bytes)?))
+    }
+
+    /// Appends the SSZ encoded value of the block at `slot`, which must not be lower than the slot
+    /// of the last value.
+    pub(crate) fn append(
+        &self,
+        slot: u64,
+        block_root: B256,
+        ssz_bytes: &[u8],
+    ) -> Result<(), StoreError> {
+        let bytes = Encoder::new().compress_vec(ssz_bytes)?;
+        let mut writer = self.writer.lock();
+        let entry = IndexEntry {
+            slot,
+            offset: writer.data_len,
+            length: bytes.len() as u64,
+        };
+        writer.data_file.write_all(&bytes)?;
+        writer
+            .index_file
+            .write_all(&encode_index_entry(block_root, entry))?;
+        writer.data_len = entry.end();
+        writer.last_slot = Some(slot);
+        self.index.write().insert(block_root, entry);
+        Ok(())
+    }
+
+    fn sync(&self) -> Result<(), StoreError> {
+        let writer = self.writer.lock();
+        writer.data_file.sync_data()?;
+        writer.index_file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// The finalized blocks and the finalized states at restore points.
+pub struct Freezer {
+    freezer_dir: PathBuf,
+    pub(crate) blocks: FreezerFile,
+    pub(crate) states: FreezerFile,
+}
+
+impl Debug for Freezer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Freezer")
+            .field("freezer_dir", &self.freezer_dir)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Freezer {
+    pub fn open(data_dir: &Path) -> Result<Self, StoreError> {
+        let freezer_dir = data_dir.join(FREEZER_FOLDER_NAME);
+        fs::create_dir_all(&freezer_dir)?;
+
+        Ok(Self {
+            blocks: FreezerFile::open(&freezer_dir, "blocks")?,
+            states: FreezerFile::open(&freezer_dir, "states")?,
+            freezer_dir,
+        })
+    }
+
+    /// The slot of the last frozen block.
+    pub fn last_frozen_slot(&self) -> Option<u64> {
+        self.blocks.last_slot()
+    }
+
+    /// Writes the appended values to disk.
+    pub(crate) fn sync(&self) -> Result<(), StoreError> {
+        self.blocks.sync()?;
+        self.states.sync()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::OpenOptions;
+
+    use alloy_primitives::B256;
+    use tempdir::TempDir;
+
+    use super::{FREEZER_FOLDER_NAME, Freezer};
+    use crate::errors::StoreError;
+
+    #[test]
+    fn test_freezer_drops_incomplete_values() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_freezer_drops_incomplete_values")?;
+        let freezer = Freezer::open(tmp_dir.path())?;
+        freezer.blocks.append(1, B256::repeat_byte(1), b"block 1")?;
+        freezer.blocks.append(2, B256::repeat_byte(2), b"block 2")?;
+        freezer.sync()?;
+        drop(freezer);
+
+        // A value written without its index entry before a crash
+        let data_file = OpenOptions::new().append(true).open(
+            tmp_dir
+                .path()
+                .join(FREEZER_FOLDER_NAME)
+                .join("blocks.ssz_snappy"),
+        )?;
+        data_file.set_len(data_file.metadata()?.len() + 10)?;
+
+        let freezer = Freezer::open(tmp_dir.path())?;
+        assert_eq!(freezer.last_frozen_slot(), Some(2));
+        assert_eq!(
+            freezer.blocks.get(&B256::repeat_byte(1))?,
+            Some(b"block 1".to_vec())
+        );
+        assert!(!freezer.blocks.contains(&B256::repeat_byte(3)));
+
+        freezer.blocks.append(3, B256::repeat_byte(3), b"block 3")?;
+        assert_eq!(
+            freezer.blocks.get(&B256::repeat_byte(3))?,
+            Some(b"block 3".to_vec())
+        );
+        assert_eq!(freezer.states.get(&B256::repeat_byte(3))?, None);
+
+        Ok(())
+    }
+}
@@ -2,4 +2,5 @@ pub mod cache;
 pub mod db;
 pub mod dir;
 pub mod errors;
+pub mod freezer;
 pub mod tables;
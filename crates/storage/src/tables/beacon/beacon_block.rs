@@ -2,7 +2,8 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus_beacon::electra::beacon_block::SignedBeaconBlock;
-use redb::{Database, Durability, TableDefinition};
+use ream_metrics::{DB_OPERATION_TIME, start_timer_vec, stop_timer};
+use ssz::Decode;
 use tree_hash::TreeHash;
 
 use super::{
@@ -10,21 +11,45 @@ use super::{
     state_root_index::StateRootIndexTable,
 };
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{multimap_table::MultimapTable, ssz_encoder::SSZEncoding, table::Table},
+    freezer::Freezer,
+    tables::{multimap_table::MultimapTable, table::Table},
 };
 
 /// Table definition for the Beacon Block table
 ///
 /// Key: block_id
 /// Value: BeaconBlock
-pub(crate) const BEACON_BLOCK_TABLE: TableDefinition<
-    SSZEncoding<B256>,
-    SSZEncoding<SignedBeaconBlock>,
-> = TableDefinition::new("beacon_block");
+pub(crate) const BEACON_BLOCK_TABLE: TableDefinition =
+    TableDefinition::new("beacon_block", TableKind::Bytes);
 
+/// Finalized blocks are read from the freezer once they are moved there.
 pub struct BeaconBlockTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
+    pub freezer: Arc<Freezer>,
+}
+
+impl BeaconBlockTable {
+    fn get_bytes(&self, block_root: B256) -> Result<Option<Vec<u8>>, StoreError> {
+        match self.db.get(BEACON_BLOCK_TABLE, block_root.as_slice())? {
+            Some(block_bytes) => Ok(Some(block_bytes)),
+            None => self.freezer.blocks.get(&block_root),
+        }
+    }
+
+    /// Read only the slot and the parent root of the block, without decoding its body.
+    pub fn get_slot_and_parent_root(
+        &self,
+        block_root: B256,
+    ) -> Result<Option<(u64, B256)>, StoreError> {
+        let Some(block_bytes) = self.get_bytes(block_root)? else {
+            return Ok(None);
+        };
+        Ok(Some(SignedBeaconBlock::decode_slot_and_parent_root(
+            &block_bytes,
+        )?))
+    }
 }
 
 impl Table for BeaconBlockTable {
@@ -33,20 +58,23 @@ impl Table for BeaconBlockTable {
     type Value = SignedBeaconBlock;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(BEACON_BLOCK_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        let timer = start_timer_vec(&DB_OPERATION_TIME, &["beacon_block", "read"]);
+        let value = self
+            .get_bytes(key)?
+            .map(|block_bytes| SignedBeaconBlock::from_ssz_bytes(&block_bytes))
+            .transpose()?;
+        stop_timer(timer);
+        Ok(value)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
+        let timer = start_timer_vec(&DB_OPERATION_TIME, &["beacon_block", "write"]);
         // insert entry to slot_index table
         let block_root = value.message.tree_hash_root();
         let slot_index_table = SlotIndexTable {
             db: self.db.clone(),
         };
-        slot_index_table.insert(value.message.slot, block_root)?;
+        slot_index_table.insert(value.message.slot.as_u64(), block_root)?;
 
         // insert entry to state root index table
         let state_root_index_table = StateRootIndexTable {
@@ -58,12 +86,9 @@ impl Table for BeaconBlockTable {
             db: self.db.clone(),
         };
         parent_root_index_table.insert(value.message.parent_root, block_root)?;
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(BEACON_BLOCK_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
+        self.db
+            .insert_value(BEACON_BLOCK_TABLE, key.as_slice(), &value)?;
+        stop_timer(timer);
         Ok(())
     }
 }
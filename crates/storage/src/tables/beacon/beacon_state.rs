@@ -1,23 +1,51 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use ream_consensus_beacon::electra::beacon_state::BeaconState;
-use redb::{Database, Durability, TableDefinition};
+use ream_consensus_beacon::electra::beacon_state::{BeaconState, ValidatorsAndBalances};
+use ream_metrics::{DB_OPERATION_TIME, start_timer_vec, stop_timer};
+use ssz::Decode;
 
 use crate::{
+    cache::HotStateCache,
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    freezer::Freezer,
+    tables::table::Table,
 };
 
 /// Table definition for the Beacon State table
 ///
 /// Key: block_root
 /// Value: BeaconState
-pub(crate) const BEACON_STATE_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<BeaconState>> =
-    TableDefinition::new("beacon_state");
+pub(crate) const BEACON_STATE_TABLE: TableDefinition =
+    TableDefinition::new("beacon_state", TableKind::Bytes);
 
+/// The states at restore points are read from the freezer once they are moved there, the other
+/// finalized states are deleted.
 pub struct BeaconStateTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
+    pub hot_state_cache: Arc<HotStateCache>,
+    pub freezer: Arc<Freezer>,
+}
+
+impl BeaconStateTable {
+    /// Read only the validator registry and the balances of the state at `block_root`, without
+    /// decoding the rest of the state.
+    pub fn get_validators_and_balances(
+        &self,
+        block_root: B256,
+    ) -> Result<Option<ValidatorsAndBalances>, StoreError> {
+        let state_bytes = match self.db.get(BEACON_STATE_TABLE, block_root.as_slice())? {
+            Some(state_bytes) => state_bytes,
+            None => match self.freezer.states.get(&block_root)? {
+                Some(state_bytes) => state_bytes,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(BeaconState::decode_validators_and_balances(
+            &state_bytes,
+        )?))
+    }
 }
 
 impl Table for BeaconStateTable {
@@ -26,20 +54,30 @@ impl Table for BeaconStateTable {
     type Value = BeaconState;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
+        if let Some(state) = self.hot_state_cache.get(&key) {
+            return Ok(Some(state));
+        }
 
-        let table = read_txn.open_table(BEACON_STATE_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        let timer = start_timer_vec(&DB_OPERATION_TIME, &["beacon_state", "read"]);
+        let value = match self.db.get(BEACON_STATE_TABLE, key.as_slice())? {
+            Some(state_bytes) => Some(state_bytes),
+            None => self.freezer.states.get(&key)?,
+        }
+        .map(|state_bytes| BeaconState::from_ssz_bytes(&state_bytes))
+        .transpose()?;
+        stop_timer(timer);
+        if let Some(state) = &value {
+            self.hot_state_cache.put(key, state.clone());
+        }
+        Ok(value)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(BEACON_STATE_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
+        let timer = start_timer_vec(&DB_OPERATION_TIME, &["beacon_state", "write"]);
+        self.db
+            .insert_value(BEACON_STATE_TABLE, key.as_slice(), &value)?;
+        stop_timer(timer);
+        self.hot_state_cache.put(key, value);
         Ok(())
     }
 }
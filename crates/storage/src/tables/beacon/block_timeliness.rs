@@ -1,22 +1,22 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, TableDefinition};
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    tables::table::Table,
 };
 
 /// Table definition for the Block Timeliness table
 ///
 /// Key: block_timeliness
 /// Value: bool
-pub(crate) const BLOCK_TIMELINESS_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<bool>> =
-    TableDefinition::new("beacon_block_timeliness");
+pub(crate) const BLOCK_TIMELINESS_TABLE: TableDefinition =
+    TableDefinition::new("beacon_block_timeliness", TableKind::Bytes);
 
 pub struct BlockTimelinessTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Table for BlockTimelinessTable {
@@ -25,20 +25,11 @@ impl Table for BlockTimelinessTable {
     type Value = bool;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        self.db.get_value(BLOCK_TIMELINESS_TABLE, key.as_slice())
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(BLOCK_TIMELINESS_TABLE, key.as_slice(), &value)
     }
 }
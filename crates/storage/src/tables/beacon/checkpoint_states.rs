@@ -2,24 +2,23 @@ use std::sync::Arc;
 
 use ream_consensus_beacon::electra::beacon_state::BeaconState;
 use ream_consensus_misc::checkpoint::Checkpoint;
-use redb::{Database, Durability, TableDefinition};
+use ssz::Encode;
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    tables::table::Table,
 };
 
 /// Table definition for the Checkpoint States table
 ///
 /// Key: checkpoint_states
 /// Value: BeaconState
-pub(crate) const CHECKPOINT_STATES_TABLE: TableDefinition<
-    SSZEncoding<Checkpoint>,
-    SSZEncoding<BeaconState>,
-> = TableDefinition::new("beacon_checkpoint_states");
+pub(crate) const CHECKPOINT_STATES_TABLE: TableDefinition =
+    TableDefinition::new("beacon_checkpoint_states", TableKind::Bytes);
 
 pub struct CheckpointStatesTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Table for CheckpointStatesTable {
@@ -28,20 +27,12 @@ impl Table for CheckpointStatesTable {
     type Value = BeaconState;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        self.db
+            .get_value(CHECKPOINT_STATES_TABLE, &key.as_ssz_bytes())
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(CHECKPOINT_STATES_TABLE, &key.as_ssz_bytes(), &value)
     }
 }
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
+    errors::StoreError,
+    tables::field::Field,
+};
+
+/// Table definition for the Frozen_Slot table
+///
+/// Value: u64
+pub(crate) const FROZEN_SLOT_FIELD: TableDefinition =
+    TableDefinition::new("beacon_frozen_slot", TableKind::Bytes);
+
+const FROZEN_SLOT_KEY: &str = "frozen_slot_key";
+
+/// The slot up to which the finalized blocks and states were moved to the freezer, exclusive.
+pub struct FrozenSlotField {
+    pub db: Arc<dyn Database>,
+}
+
+impl Field for FrozenSlotField {
+    type Value = u64;
+
+    fn get(&self) -> Result<u64, StoreError> {
+        self.db
+            .get_value(FROZEN_SLOT_FIELD, FROZEN_SLOT_KEY.as_bytes())?
+            .ok_or(StoreError::FieldNotInitilized)
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        self.db
+            .insert_value(FROZEN_SLOT_FIELD, FROZEN_SLOT_KEY.as_bytes(), &value)
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+
+use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
+    errors::StoreError,
+    tables::field::Field,
+};
+
+/// Table definition for the Head_Root table
+///
+/// Value: Root
+pub(crate) const HEAD_ROOT_FIELD: TableDefinition =
+    TableDefinition::new("beacon_head_root", TableKind::Bytes);
+
+const HEAD_ROOT_KEY: &str = "head_root_key";
+
+/// The head of the chain chosen by fork choice when the last block was imported.
+pub struct HeadRootField {
+    pub db: Arc<dyn Database>,
+}
+
+impl Field for HeadRootField {
+    type Value = B256;
+
+    fn get(&self) -> Result<B256, StoreError> {
+        self.db
+            .get_value(HEAD_ROOT_FIELD, HEAD_ROOT_KEY.as_bytes())?
+            .ok_or(StoreError::FieldNotInitilized)
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        self.db
+            .insert_value(HEAD_ROOT_FIELD, HEAD_ROOT_KEY.as_bytes(), &value)
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+
+use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
+    errors::StoreError,
+    tables::field::Field,
+};
+
+/// Table definition for the Hot_State_Roots table
+///
+/// Value: Vec<B256>
+pub(crate) const HOT_STATE_ROOTS_FIELD: TableDefinition =
+    TableDefinition::new("beacon_hot_state_roots", TableKind::Bytes);
+
+const HOT_STATE_ROOTS_KEY: &str = "hot_state_roots_key";
+
+/// The block roots of the states in the hot state cache on the last shutdown, most recently used
+/// first, which are loaded back into the cache on startup.
+pub struct HotStateRootsField {
+    pub db: Arc<dyn Database>,
+}
+
+impl Field for HotStateRootsField {
+    type Value = Vec<B256>;
+
+    fn get(&self) -> Result<Self::Value, StoreError> {
+        self.db
+            .get_value(HOT_STATE_ROOTS_FIELD, HOT_STATE_ROOTS_KEY.as_bytes())?
+            .ok_or(StoreError::FieldNotInitilized)
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        self.db.insert_value(
+            HOT_STATE_ROOTS_FIELD,
+            HOT_STATE_ROOTS_KEY.as_bytes(),
+            &value,
+        )
+    }
+}
@@ -1,22 +1,22 @@
 use std::sync::Arc;
 
 use ream_consensus_beacon::fork_choice::latest_message::LatestMessage;
-use redb::{Database, Durability, TableDefinition};
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    tables::table::Table,
 };
 
 /// Table definition for the Latest Message table
 ///
 /// Key: latest_messages
 /// Value: LatestMessage
-pub(crate) const LATEST_MESSAGES_TABLE: TableDefinition<u64, SSZEncoding<LatestMessage>> =
-    TableDefinition::new("beacon_latest_messages");
+pub(crate) const LATEST_MESSAGES_TABLE: TableDefinition =
+    TableDefinition::new("beacon_latest_messages", TableKind::Integer);
 
 pub struct LatestMessagesTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Table for LatestMessagesTable {
@@ -25,20 +25,11 @@ impl Table for LatestMessagesTable {
     type Value = LatestMessage;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(LATEST_MESSAGES_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        self.db.get_value(LATEST_MESSAGES_TABLE, &key.to_le_bytes())
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(LATEST_MESSAGES_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(LATEST_MESSAGES_TABLE, &key.to_le_bytes(), &value)
     }
 }
@@ -5,7 +5,10 @@ pub mod block_timeliness;
 pub mod checkpoint_states;
 pub mod equivocating_indices;
 pub mod finalized_checkpoint;
+pub mod frozen_slot;
 pub mod genesis_time;
+pub mod head_root;
+pub mod hot_state_roots;
 pub mod justified_checkpoint;
 pub mod latest_messages;
 pub mod parent_root_index;
@@ -16,3 +19,51 @@ pub mod time;
 pub mod unrealized_finalized_checkpoint;
 pub mod unrealized_justifications;
 pub mod unrealized_justified_checkpoint;
+
+use beacon_block::BEACON_BLOCK_TABLE;
+use beacon_state::BEACON_STATE_TABLE;
+use block_timeliness::BLOCK_TIMELINESS_TABLE;
+use checkpoint_states::CHECKPOINT_STATES_TABLE;
+use equivocating_indices::EQUIVOCATING_INDICES_FIELD;
+use finalized_checkpoint::FINALIZED_CHECKPOINT_FIELD;
+use frozen_slot::FROZEN_SLOT_FIELD;
+use genesis_time::GENESIS_TIME_FIELD;
+use head_root::HEAD_ROOT_FIELD;
+use hot_state_roots::HOT_STATE_ROOTS_FIELD;
+use justified_checkpoint::JUSTIFIED_CHECKPOINT_FIELD;
+use latest_messages::LATEST_MESSAGES_TABLE;
+use parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE;
+use proposer_boost_root::PROPOSER_BOOST_ROOT_FIELD;
+use slot_index::SLOT_INDEX_TABLE;
+use state_root_index::STATE_ROOT_INDEX_TABLE;
+use time::TIME_FIELD;
+use unrealized_finalized_checkpoint::UNREALIZED_FINALIZED_CHECKPOINT_FIELD;
+use unrealized_justifications::UNREALIZED_JUSTIFICATIONS_TABLE;
+use unrealized_justified_checkpoint::UNREALIZED_JUSTIFED_CHECKPOINT_FIELD;
+
+use crate::{db::backend::TableDefinition, tables::known_peers::BEACON_KNOWN_PEERS_TABLE};
+
+/// The tables of the beacon node
+pub(crate) const BEACON_TABLES: [TableDefinition; 21] = [
+    BEACON_BLOCK_TABLE,
+    BEACON_KNOWN_PEERS_TABLE,
+    BEACON_STATE_TABLE,
+    BLOCK_TIMELINESS_TABLE,
+    CHECKPOINT_STATES_TABLE,
+    EQUIVOCATING_INDICES_FIELD,
+    FINALIZED_CHECKPOINT_FIELD,
+    FROZEN_SLOT_FIELD,
+    GENESIS_TIME_FIELD,
+    HEAD_ROOT_FIELD,
+    HOT_STATE_ROOTS_FIELD,
+    JUSTIFIED_CHECKPOINT_FIELD,
+    LATEST_MESSAGES_TABLE,
+    PARENT_ROOT_INDEX_MULTIMAP_TABLE,
+    PROPOSER_BOOST_ROOT_FIELD,
+    SLOT_INDEX_TABLE,
+    STATE_ROOT_INDEX_TABLE,
+    TIME_FIELD,
+    UNREALIZED_FINALIZED_CHECKPOINT_FIELD,
+    UNREALIZED_JUSTIFICATIONS_TABLE,
+    UNREALIZED_JUSTIFED_CHECKPOINT_FIELD,
+];
@@ -1,24 +1,23 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, MultimapTableDefinition};
+use ssz::Decode;
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{multimap_table::MultimapTable, ssz_encoder::SSZEncoding},
+    tables::multimap_table::MultimapTable,
 };
 
 /// Table definition for the Parent Root Index Multimap table
 ///
 /// Key: ParentRoot
 /// Value: BlockRoot's
-pub(crate) const PARENT_ROOT_INDEX_MULTIMAP_TABLE: MultimapTableDefinition<
-    SSZEncoding<B256>,
-    SSZEncoding<B256>,
-> = MultimapTableDefinition::new("beacon_parent_root_index_multimap");
+pub(crate) const PARENT_ROOT_INDEX_MULTIMAP_TABLE: TableDefinition =
+    TableDefinition::new("beacon_parent_root_index_multimap", TableKind::Multimap);
 
 pub struct ParentRootIndexMultimapTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl MultimapTable for ParentRootIndexMultimapTable {
@@ -29,24 +28,17 @@ impl MultimapTable for ParentRootIndexMultimapTable {
     type InsertValue = B256;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::GetValue>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
-        let result = table.get(key)?;
-        let mut values = vec![];
-        for value in result {
-            values.push(value?.value());
-        }
+        let values = self
+            .db
+            .get_all(PARENT_ROOT_INDEX_MULTIMAP_TABLE, key.as_slice())?
+            .iter()
+            .map(|value| B256::from_ssz_bytes(value))
+            .collect::<Result<_, _>>()?;
         Ok(Some(values))
     }
 
     fn insert(&self, key: Self::Key, value: Self::InsertValue) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(PARENT_ROOT_INDEX_MULTIMAP_TABLE, key.as_slice(), &value)
     }
 }
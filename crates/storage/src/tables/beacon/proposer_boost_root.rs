@@ -1,45 +1,42 @@
 use std::sync::Arc;
 
 use alloy_primitives::{B256, FixedBytes};
-use redb::{Database, Durability, TableDefinition};
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{field::Field, ssz_encoder::SSZEncoding},
+    tables::field::Field,
 };
 
 /// Table definition for the Proposer_Boost_Root table
 ///
 /// Value: Root
-pub(crate) const PROPOSER_BOOST_ROOT_FIELD: TableDefinition<&str, SSZEncoding<B256>> =
-    TableDefinition::new("beacon_proposer_boost_root");
+pub(crate) const PROPOSER_BOOST_ROOT_FIELD: TableDefinition =
+    TableDefinition::new("beacon_proposer_boost_root", TableKind::Bytes);
 
 const PROPOSER_BOOST_ROOT_KEY: &str = "proposer_boost_root_key";
 
 pub struct ProposerBoostRootField {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Field for ProposerBoostRootField {
     type Value = B256;
 
     fn get(&self) -> Result<FixedBytes<32>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(PROPOSER_BOOST_ROOT_FIELD)?;
-        let result = table
-            .get(PROPOSER_BOOST_ROOT_KEY)?
-            .ok_or(StoreError::FieldNotInitilized)?;
-        Ok(result.value())
+        self.db
+            .get_value(
+                PROPOSER_BOOST_ROOT_FIELD,
+                PROPOSER_BOOST_ROOT_KEY.as_bytes(),
+            )?
+            .ok_or(StoreError::FieldNotInitilized)
     }
 
     fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(PROPOSER_BOOST_ROOT_FIELD)?;
-        table.insert(PROPOSER_BOOST_ROOT_KEY, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db.insert_value(
+            PROPOSER_BOOST_ROOT_FIELD,
+            PROPOSER_BOOST_ROOT_KEY.as_bytes(),
+            &value,
+        )
     }
 }
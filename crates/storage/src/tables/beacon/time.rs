@@ -1,38 +1,34 @@
 use std::sync::Arc;
 
-use redb::{Database, Durability, TableDefinition};
-
-use crate::{errors::StoreError, tables::field::Field};
+use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
+    errors::StoreError,
+    tables::field::Field,
+};
 
 /// Table definition for the Time table
 ///
 /// Value: u64
-pub(crate) const TIME_FIELD: TableDefinition<&str, u64> = TableDefinition::new("beacon_time");
+pub(crate) const TIME_FIELD: TableDefinition =
+    TableDefinition::new("beacon_time", TableKind::Bytes);
 
 const TIME_KEY: &str = "time_key";
 
 pub struct TimeField {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Field for TimeField {
     type Value = u64;
 
     fn get(&self) -> Result<u64, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(TIME_FIELD)?;
-        let result = table.get(TIME_KEY)?.ok_or(StoreError::FieldNotInitilized)?;
-        Ok(result.value())
+        self.db
+            .get_value(TIME_FIELD, TIME_KEY.as_bytes())?
+            .ok_or(StoreError::FieldNotInitilized)
     }
 
     fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(TIME_FIELD)?;
-        table.insert(TIME_KEY, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(TIME_FIELD, TIME_KEY.as_bytes(), &value)
     }
 }
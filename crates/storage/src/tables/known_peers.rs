@@ -0,0 +1,126 @@
+use std::{ops::Bound, sync::Arc};
+
+use ssz::Encode;
+use ssz_derive::{Decode, Encode};
+
+use crate::{
+    db::backend::{Database, KeyValue, TableDefinition, TableKind},
+    errors::StoreError,
+};
+
+/// Table definition for the Known Peers table of the beacon network
+///
+/// Key: rank of the peer, the best peer first
+/// Value: [KnownPeer]
+pub(crate) const BEACON_KNOWN_PEERS_TABLE: TableDefinition =
+    TableDefinition::new("beacon_known_peers", TableKind::Integer);
+
+/// Table definition for the Known Peers table of the lean network
+///
+/// Key: rank of the peer, the best peer first
+/// Value: [KnownPeer]
+pub(crate) const LEAN_KNOWN_PEERS_TABLE: TableDefinition =
+    TableDefinition::new("lean_known_peers", TableKind::Integer);
+
+/// A peer the node was connected to, which is dialed again after a restart.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct KnownPeer {
+    /// The text encoding of the ENR of the peer
+    pub enr: Vec<u8>,
+    /// The bits of the score of the peer when it was saved
+    score_bits: u64,
+    /// Unix timestamp in seconds of when the peer was saved while connected
+    pub last_connected: u64,
+}
+
+impl KnownPeer {
+    pub fn new(enr: Vec<u8>, score: f64, last_connected: u64) -> Self {
+        Self {
+            enr,
+            score_bits: score.to_bits(),
+            last_connected,
+        }
+    }
+
+    pub fn score(&self) -> f64 {
+        f64::from_bits(self.score_bits)
+    }
+}
+
+/// The peers a network was connected to when it last saved them.
+#[derive(Clone)]
+pub struct KnownPeersTable {
+    pub db: Arc<dyn Database>,
+    pub(crate) table: TableDefinition,
+}
+
+impl KnownPeersTable {
+    /// Get the saved peers, the best peer first.
+    pub fn get_all(&self) -> Result<Vec<KnownPeer>, StoreError> {
+        self.db.all_values(self.table)
+    }
+
+    /// Replace the saved peers with the `max_peers` best of `peers`, ranked by their score.
+    pub fn replace(&self, mut peers: Vec<KnownPeer>, max_peers: usize) -> Result<(), StoreError> {
+        peers.sort_by(|peer_1, peer_2| peer_2.score().total_cmp(&peer_1.score()));
+        peers.truncate(max_peers);
+
+        let keys = self
+            .db
+            .range(self.table, Bound::Unbounded, Bound::Unbounded, None)?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        self.db.remove(self.table, &keys)?;
+
+        let entries = (0_u64..)
+            .zip(peers)
+            .map(|(rank, peer)| (rank.to_le_bytes().to_vec(), peer.as_ssz_bytes()))
+            .collect::<Vec<KeyValue>>();
+        self.db.insert(self.table, &entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::db::{ReamDB, config::DBConfig};
+
+    #[test]
+    fn test_known_peers_are_replaced_and_ranked() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_known_peers_are_replaced_and_ranked")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf(), DBConfig::default())?;
+        let (beacon_peers, lean_peers) = (
+            db.init_beacon_db()?.known_peers_provider(),
+            db.init_lean_db()?.known_peers_provider(),
+        );
+
+        beacon_peers.replace(
+            vec![
+                KnownPeer::new(b"enr:a".to_vec(), -1.5, 1),
+                KnownPeer::new(b"enr:b".to_vec(), 10.0, 2),
+                KnownPeer::new(b"enr:c".to_vec(), 0.0, 3),
+                KnownPeer::new(b"enr:d".to_vec(), -2.0, 4),
+            ],
+            3,
+        )?;
+        assert_eq!(
+            beacon_peers
+                .get_all()?
+                .into_iter()
+                .map(|peer| peer.enr)
+                .collect::<Vec<_>>(),
+            vec![b"enr:b".to_vec(), b"enr:c".to_vec(), b"enr:a".to_vec()]
+        );
+        assert!(lean_peers.get_all()?.is_empty());
+
+        // Peers which aren't saved again are forgotten
+        beacon_peers.replace(vec![KnownPeer::new(b"enr:e".to_vec(), 1.0, 5)], 3)?;
+        let peers = beacon_peers.get_all()?;
+        assert_eq!(peers.len(), 1);
+        assert_eq!((peers[0].score(), peers[0].last_connected), (1.0, 5));
+        Ok(())
+    }
+}
@@ -2,41 +2,31 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus_lean::{block::SignedBlock, vote::SignedVote};
-use redb::{Database, Durability, ReadableTable, ReadableTableMetadata, TableDefinition};
+use ssz::{Decode, Encode};
 
-use crate::{errors::StoreError, tables::ssz_encoder::SSZEncoding};
+use crate::{
+    db::backend::{Database, KeyValue, TableDefinition, TableKind},
+    errors::StoreError,
+};
 
 /// Table definition for the Known Votes table
 ///
 /// Key: index (u64, acts like position in an append-only array)
 /// Value: [SignedVote]
-pub(crate) const KNOWN_VOTES_TABLE: TableDefinition<u64, SSZEncoding<SignedVote>> =
-    TableDefinition::new("known_votes");
+pub(crate) const KNOWN_VOTES_TABLE: TableDefinition =
+    TableDefinition::new("known_votes", TableKind::Integer);
 
 pub struct KnownVotesTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl KnownVotesTable {
     /// Append a vote to the end of the table.
     /// Returns the index at which it was inserted.
     pub fn append(&self, value: SignedVote) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-
-        let mut table = write_txn.open_table(KNOWN_VOTES_TABLE)?;
-
-        // Compute next index
-        let next_index = match table.last()? {
-            Some((k, _)) => k.value() + 1,
-            None => 0,
-        };
-
-        table.insert(next_index, value)?;
-
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        let next_index = self.next_index()?;
+        self.db
+            .insert_value(KNOWN_VOTES_TABLE, &next_index.to_le_bytes(), &value)
     }
 
     /// Append multiple votes in a single transaction.
@@ -45,72 +35,53 @@ impl KnownVotesTable {
         &self,
         values: impl IntoIterator<Item = SignedVote>,
     ) -> Result<u64, StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-
-        let mut table = write_txn.open_table(KNOWN_VOTES_TABLE)?;
-
-        // Find the next free index
-        let mut next_index = match table.last()? {
-            Some((k, _)) => k.value() + 1,
-            None => 0,
-        };
-
-        let start_index = next_index;
-
-        for value in values {
-            table.insert(next_index, value)?;
-            next_index += 1;
-        }
+        let start_index = self.next_index()?;
 
-        drop(table);
-        write_txn.commit()?;
+        let entries = (start_index..)
+            .zip(values)
+            .map(|(index, value)| (index.to_le_bytes().to_vec(), value.as_ssz_bytes()))
+            .collect::<Vec<KeyValue>>();
+        self.db.insert(KNOWN_VOTES_TABLE, &entries)?;
 
         Ok(start_index)
     }
 
     /// Check if a given vote exists in the append-only array.
     pub fn contains(&self, value: &SignedVote) -> Result<bool, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(KNOWN_VOTES_TABLE)?;
-
-        for entry in table.iter()? {
-            let (_, v) = entry?;
-            if &v.value() == value {
-                return Ok(true);
-            }
-        }
-
-        Ok(false)
+        Ok(self.get_all_votes()?.contains(value))
     }
 
     /// Return number of votes (like `Vec::len`)
     pub fn len(&self) -> Result<u64, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(KNOWN_VOTES_TABLE)?;
-        Ok(table.len()?)
+        self.db.len(KNOWN_VOTES_TABLE)
     }
 
     /// Returns if there are no known votes
     pub fn is_empty(&self) -> Result<bool, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(KNOWN_VOTES_TABLE)?;
-        Ok(table.len()? == 0)
+        Ok(self.len()? == 0)
     }
 
     /// Get all votes.
     pub fn get_all_votes(&self) -> Result<Vec<SignedVote>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(KNOWN_VOTES_TABLE)?;
-
-        let mut votes = Vec::with_capacity(table.len()? as usize);
+        self.db.all_values(KNOWN_VOTES_TABLE)
+    }
 
-        for entry in table.iter()? {
-            let (_, v) = entry?;
-            votes.push(v.value());
-        }
+    /// Get the votes cast for `slot`.
+    pub fn get_votes_by_slot(&self, slot: u64) -> Result<Vec<SignedVote>, StoreError> {
+        Ok(self
+            .get_all_votes()?
+            .into_iter()
+            .filter(|vote| vote.message.slot == slot)
+            .collect())
+    }
 
-        Ok(votes)
+    /// Get the vote of `validator_id` for the highest slot.
+    pub fn get_latest_vote(&self, validator_id: u64) -> Result<Option<SignedVote>, StoreError> {
+        Ok(self
+            .get_all_votes()?
+            .into_iter()
+            .filter(|vote| vote.validator_id == validator_id)
+            .max_by_key(|vote| vote.message.slot))
     }
 
     /// Get all votes whose `source.root` matches `state.latest_justified.root`
@@ -120,22 +91,21 @@ impl KnownVotesTable {
         justified_root: B256,
         new_block: &SignedBlock,
     ) -> Result<Vec<SignedVote>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(KNOWN_VOTES_TABLE)?;
-
-        let mut result = Vec::new();
-
-        for entry in table.iter()? {
-            let (_, v) = entry?;
-            let vote = v.value();
-
-            if vote.message.source.root == justified_root
-                && !new_block.message.body.attestations.contains(&vote)
-            {
-                result.push(vote);
-            }
-        }
+        Ok(self
+            .get_all_votes()?
+            .into_iter()
+            .filter(|vote| {
+                vote.message.source.root == justified_root
+                    && !new_block.message.body.attestations.contains(vote)
+            })
+            .collect())
+    }
 
-        Ok(result)
+    /// The index after the last vote.
+    fn next_index(&self) -> Result<u64, StoreError> {
+        Ok(match self.db.last(KNOWN_VOTES_TABLE)? {
+            Some((index, _)) => u64::from_ssz_bytes(&index)? + 1,
+            None => 0,
+        })
     }
 }
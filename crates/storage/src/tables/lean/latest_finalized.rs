@@ -1,45 +1,42 @@
 use std::sync::Arc;
 
 use ream_consensus_lean::checkpoint::Checkpoint;
-use redb::{Database, Durability, TableDefinition};
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{field::Field, ssz_encoder::SSZEncoding},
+    tables::field::Field,
 };
 
 /// Table definition for the Latest Finalized table
 ///
 /// Value: [Checkpoint]
-pub const LATEST_FINALIZED_FIELD: TableDefinition<&str, SSZEncoding<Checkpoint>> =
-    TableDefinition::new("lean_latest_finalized");
+pub const LATEST_FINALIZED_FIELD: TableDefinition =
+    TableDefinition::new("lean_latest_finalized", TableKind::Bytes);
 
 const LATEST_FINALIZED_FIELD_KEY: &str = "latest_finalized_key";
 
 pub struct LatestFinalizedField {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Field for LatestFinalizedField {
     type Value = Checkpoint;
 
     fn get(&self) -> Result<Checkpoint, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(LATEST_FINALIZED_FIELD)?;
-        let result = table
-            .get(LATEST_FINALIZED_FIELD_KEY)?
-            .ok_or(StoreError::FieldNotInitilized)?;
-        Ok(result.value())
+        self.db
+            .get_value(
+                LATEST_FINALIZED_FIELD,
+                LATEST_FINALIZED_FIELD_KEY.as_bytes(),
+            )?
+            .ok_or(StoreError::FieldNotInitilized)
     }
 
     fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(LATEST_FINALIZED_FIELD)?;
-        table.insert(LATEST_FINALIZED_FIELD_KEY, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db.insert_value(
+            LATEST_FINALIZED_FIELD,
+            LATEST_FINALIZED_FIELD_KEY.as_bytes(),
+            &value,
+        )
     }
 }
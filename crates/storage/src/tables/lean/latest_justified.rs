@@ -1,11 +1,11 @@
 use std::sync::Arc;
 
 use ream_consensus_lean::checkpoint::Checkpoint;
-use redb::{Database, Durability, TableDefinition};
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{field::Field, ssz_encoder::SSZEncoding},
+    tables::field::Field,
 };
 
 /// Table definition for the Latest Justified table
@@ -14,35 +14,32 @@ use crate::{
 ///
 /// NOTE: This table enables O(1) access to the latest justified checkpoint, deviates from
 /// the original spec which derives it from state dictionary each time it is needed.
-pub const LATEST_JUSTIFIED_FIELD: TableDefinition<&str, SSZEncoding<Checkpoint>> =
-    TableDefinition::new("lean_latest_justified");
+pub const LATEST_JUSTIFIED_FIELD: TableDefinition =
+    TableDefinition::new("lean_latest_justified", TableKind::Bytes);
 
 const LATEST_JUSTIFIED_FIELD_KEY: &str = "latest_justified_key";
 
 pub struct LatestJustifiedField {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Field for LatestJustifiedField {
     type Value = Checkpoint;
 
     fn get(&self) -> Result<Checkpoint, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(LATEST_JUSTIFIED_FIELD)?;
-        let result = table
-            .get(LATEST_JUSTIFIED_FIELD_KEY)?
-            .ok_or(StoreError::FieldNotInitilized)?;
-        Ok(result.value())
+        self.db
+            .get_value(
+                LATEST_JUSTIFIED_FIELD,
+                LATEST_JUSTIFIED_FIELD_KEY.as_bytes(),
+            )?
+            .ok_or(StoreError::FieldNotInitilized)
     }
 
     fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(LATEST_JUSTIFIED_FIELD)?;
-        table.insert(LATEST_JUSTIFIED_FIELD_KEY, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db.insert_value(
+            LATEST_JUSTIFIED_FIELD,
+            LATEST_JUSTIFIED_FIELD_KEY.as_bytes(),
+            &value,
+        )
     }
 }
@@ -1,25 +1,26 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, ops::Bound, sync::Arc};
 
 use alloy_primitives::B256;
 use ream_consensus_lean::block::SignedBlock;
-use redb::{Database, Durability, ReadableTable, TableDefinition};
+use ssz::Decode;
 use tree_hash::TreeHash;
 
 use super::{slot_index::SlotIndexTable, state_root_index::StateRootIndexTable};
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    tables::table::Table,
 };
 
 /// Table definition for the Lean Block table
 ///
 /// Key: block_id
 /// Value: [SignedBlock]
-pub(crate) const LEAN_BLOCK_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<SignedBlock>> =
-    TableDefinition::new("lean_block");
+pub(crate) const LEAN_BLOCK_TABLE: TableDefinition =
+    TableDefinition::new("lean_block", TableKind::Bytes);
 
 pub struct LeanBlockTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Table for LeanBlockTable {
@@ -28,11 +29,7 @@ impl Table for LeanBlockTable {
     type Value = SignedBlock;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(LEAN_BLOCK_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        self.db.get_value(LEAN_BLOCK_TABLE, key.as_slice())
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
@@ -49,13 +46,8 @@ impl Table for LeanBlockTable {
         };
         state_root_index_table.insert(value.message.state_root, block_root)?;
 
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(LEAN_BLOCK_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(LEAN_BLOCK_TABLE, key.as_slice(), &value)
     }
 }
 
@@ -64,20 +56,27 @@ impl LeanBlockTable {
         matches!(self.get(key), Ok(Some(_)))
     }
 
+    pub fn get_all_blocks(&self) -> Result<Vec<(B256, SignedBlock)>, StoreError> {
+        self.db
+            .range(LEAN_BLOCK_TABLE, Bound::Unbounded, Bound::Unbounded, None)?
+            .into_iter()
+            .map(|(hash, block)| {
+                Ok((
+                    B256::from_ssz_bytes(&hash)?,
+                    SignedBlock::from_ssz_bytes(&block)?,
+                ))
+            })
+            .collect()
+    }
+
     pub fn get_children_map(
         &self,
         min_score: u64,
         vote_weights: &HashMap<B256, u64>,
     ) -> Result<HashMap<B256, Vec<B256>>, StoreError> {
         let mut children_map = HashMap::<B256, Vec<B256>>::new();
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(LEAN_BLOCK_TABLE)?;
-
-        for entry in table.iter()? {
-            let (hash_entry, block_entry) = entry?;
-            let hash: B256 = hash_entry.value();
-            let block: SignedBlock = block_entry.value();
 
+        for (hash, block) in self.get_all_blocks()? {
             if block.message.parent_root != B256::ZERO
                 && *vote_weights.get(&hash).unwrap_or(&0) >= min_score
             {
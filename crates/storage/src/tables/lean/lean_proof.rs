@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+
+use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
+    errors::StoreError,
+    tables::table::Table,
+};
+
+/// Table definition for the Lean Proof table
+///
+/// Key: block_root
+/// Value: SSZ encoded proof of the block's state transition
+pub(crate) const LEAN_PROOF_TABLE: TableDefinition =
+    TableDefinition::new("lean_proof", TableKind::Bytes);
+
+pub struct LeanProofTable {
+    pub db: Arc<dyn Database>,
+}
+
+impl Table for LeanProofTable {
+    type Key = B256;
+
+    type Value = Vec<u8>;
+
+    fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
+        self.db.get_value(LEAN_PROOF_TABLE, key.as_slice())
+    }
+
+    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
+        self.db
+            .insert_value(LEAN_PROOF_TABLE, key.as_slice(), &value)
+    }
+}
@@ -2,22 +2,22 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus_lean::state::LeanState;
-use redb::{Database, Durability, TableDefinition};
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    tables::table::Table,
 };
 
 /// Table definition for the Lean State table
 ///
 /// Key: block_root
 /// Value: [LeanState]
-pub(crate) const LEAN_STATE_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<LeanState>> =
-    TableDefinition::new("lean_state");
+pub(crate) const LEAN_STATE_TABLE: TableDefinition =
+    TableDefinition::new("lean_state", TableKind::Bytes);
 
 pub struct LeanStateTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Table for LeanStateTable {
@@ -26,20 +26,11 @@ impl Table for LeanStateTable {
     type Value = LeanState;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(LEAN_STATE_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        self.db.get_value(LEAN_STATE_TABLE, key.as_slice())
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(LEAN_STATE_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(LEAN_STATE_TABLE, key.as_slice(), &value)
     }
 }
@@ -2,6 +2,34 @@ pub mod known_votes;
 pub mod latest_finalized;
 pub mod latest_justified;
 pub mod lean_block;
+pub mod lean_proof;
 pub mod lean_state;
+pub mod safe_target;
 pub mod slot_index;
 pub mod state_root_index;
+
+use known_votes::KNOWN_VOTES_TABLE;
+use latest_finalized::LATEST_FINALIZED_FIELD;
+use latest_justified::LATEST_JUSTIFIED_FIELD;
+use lean_block::LEAN_BLOCK_TABLE;
+use lean_proof::LEAN_PROOF_TABLE;
+use lean_state::LEAN_STATE_TABLE;
+use safe_target::SAFE_TARGET_FIELD;
+use slot_index::LEAN_SLOT_INDEX_TABLE;
+use state_root_index::LEAN_STATE_ROOT_INDEX_TABLE;
+
+use crate::{db::backend::TableDefinition, tables::known_peers::LEAN_KNOWN_PEERS_TABLE};
+
+/// The tables of the lean node
+pub(crate) const LEAN_TABLES: [TableDefinition; 10] = [
+    KNOWN_VOTES_TABLE,
+    LEAN_KNOWN_PEERS_TABLE,
+    LATEST_FINALIZED_FIELD,
+    LATEST_JUSTIFIED_FIELD,
+    LEAN_BLOCK_TABLE,
+    LEAN_PROOF_TABLE,
+    LEAN_STATE_TABLE,
+    SAFE_TARGET_FIELD,
+    LEAN_SLOT_INDEX_TABLE,
+    LEAN_STATE_ROOT_INDEX_TABLE,
+];
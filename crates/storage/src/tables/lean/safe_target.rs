@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+
+use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
+    errors::StoreError,
+    tables::field::Field,
+};
+
+/// Table definition for the Safe Target table
+///
+/// Value: block root of the safe target
+///
+/// NOTE: The safe target is persisted so that a restarted node votes for the same target as
+/// before it stopped, instead of falling back to genesis until it has seen enough new votes.
+pub const SAFE_TARGET_FIELD: TableDefinition =
+    TableDefinition::new("lean_safe_target", TableKind::Bytes);
+
+const SAFE_TARGET_FIELD_KEY: &str = "safe_target_key";
+
+pub struct SafeTargetField {
+    pub db: Arc<dyn Database>,
+}
+
+impl Field for SafeTargetField {
+    type Value = B256;
+
+    fn get(&self) -> Result<B256, StoreError> {
+        self.db
+            .get_value(SAFE_TARGET_FIELD, SAFE_TARGET_FIELD_KEY.as_bytes())?
+            .ok_or(StoreError::FieldNotInitilized)
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        self.db
+            .insert_value(SAFE_TARGET_FIELD, SAFE_TARGET_FIELD_KEY.as_bytes(), &value)
+    }
+}
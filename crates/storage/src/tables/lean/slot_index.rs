@@ -1,22 +1,26 @@
-use std::sync::Arc;
+use std::{
+    ops::{Bound, Range},
+    sync::Arc,
+};
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, ReadableTable, TableDefinition};
+use ssz::Decode;
 
 use crate::{
+    db::backend::{Database, KeyValue, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    tables::table::Table,
 };
 
 /// Table definition for the Slot Index table
 ///
 /// Key: slot number
 /// Value: block_root
-pub(crate) const LEAN_SLOT_INDEX_TABLE: TableDefinition<u64, SSZEncoding<B256>> =
-    TableDefinition::new("lean_slot_index");
+pub(crate) const LEAN_SLOT_INDEX_TABLE: TableDefinition =
+    TableDefinition::new("lean_slot_index", TableKind::Integer);
 
 pub struct SlotIndexTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Table for SlotIndexTable {
@@ -25,46 +29,65 @@ impl Table for SlotIndexTable {
     type Value = B256;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(LEAN_SLOT_INDEX_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        self.db.get_value(LEAN_SLOT_INDEX_TABLE, &key.to_le_bytes())
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(LEAN_SLOT_INDEX_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(LEAN_SLOT_INDEX_TABLE, &key.to_le_bytes(), &value)
     }
 }
 
+fn decode_entry((slot, block_root): KeyValue) -> Result<(u64, B256), StoreError> {
+    Ok((
+        u64::from_ssz_bytes(&slot)?,
+        B256::from_ssz_bytes(&block_root)?,
+    ))
+}
+
 impl SlotIndexTable {
     pub fn get_oldest_slot(&self) -> Result<Option<u64>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(LEAN_SLOT_INDEX_TABLE)?;
-        Ok(table.first()?.map(|result| result.0.value()))
+        Ok(self.get_oldest_entry()?.map(|(slot, _)| slot))
     }
 
     pub fn get_oldest_root(&self) -> Result<Option<B256>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(LEAN_SLOT_INDEX_TABLE)?;
-        Ok(table.first()?.map(|result| result.1.value()))
+        Ok(self.get_oldest_entry()?.map(|(_, block_root)| block_root))
     }
 
     pub fn get_highest_slot(&self) -> Result<Option<u64>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(LEAN_SLOT_INDEX_TABLE)?;
-        Ok(table.last()?.map(|result| result.0.value()))
+        Ok(self.get_highest_entry()?.map(|(slot, _)| slot))
+    }
+
+    /// Returns the `(slot, block_root)` pairs of the slots in `slots` which have a block, in
+    /// ascending slot order.
+    pub fn get_range(&self, slots: Range<u64>) -> Result<Vec<(u64, B256)>, StoreError> {
+        self.db
+            .range(
+                LEAN_SLOT_INDEX_TABLE,
+                Bound::Included(slots.start.to_le_bytes().as_slice()),
+                Bound::Excluded(slots.end.to_le_bytes().as_slice()),
+                None,
+            )?
+            .into_iter()
+            .map(decode_entry)
+            .collect()
     }
 
     pub fn get_highest_root(&self) -> Result<Option<B256>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(LEAN_SLOT_INDEX_TABLE)?;
-        Ok(table.last()?.map(|result| result.1.value()))
+        Ok(self.get_highest_entry()?.map(|(_, block_root)| block_root))
+    }
+
+    fn get_oldest_entry(&self) -> Result<Option<(u64, B256)>, StoreError> {
+        self.db
+            .first(LEAN_SLOT_INDEX_TABLE)?
+            .map(decode_entry)
+            .transpose()
+    }
+
+    fn get_highest_entry(&self) -> Result<Option<(u64, B256)>, StoreError> {
+        self.db
+            .last(LEAN_SLOT_INDEX_TABLE)?
+            .map(decode_entry)
+            .transpose()
     }
 }
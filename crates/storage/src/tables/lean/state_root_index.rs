@@ -1,24 +1,22 @@
 use std::sync::Arc;
 
 use alloy_primitives::B256;
-use redb::{Database, Durability, TableDefinition};
 
 use crate::{
+    db::backend::{Database, TableDefinition, TableKind},
     errors::StoreError,
-    tables::{ssz_encoder::SSZEncoding, table::Table},
+    tables::table::Table,
 };
 
 /// Table definition for the State Root Index table
 ///
 /// Key: state_root
 /// Value: block_root
-pub(crate) const LEAN_STATE_ROOT_INDEX_TABLE: TableDefinition<
-    SSZEncoding<B256>,
-    SSZEncoding<B256>,
-> = TableDefinition::new("lean_state_root_index");
+pub(crate) const LEAN_STATE_ROOT_INDEX_TABLE: TableDefinition =
+    TableDefinition::new("lean_state_root_index", TableKind::Bytes);
 
 pub struct StateRootIndexTable {
-    pub db: Arc<Database>,
+    pub db: Arc<dyn Database>,
 }
 
 impl Table for StateRootIndexTable {
@@ -27,20 +25,12 @@ impl Table for StateRootIndexTable {
     type Value = B256;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(LEAN_STATE_ROOT_INDEX_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        self.db
+            .get_value(LEAN_STATE_ROOT_INDEX_TABLE, key.as_slice())
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(LEAN_STATE_ROOT_INDEX_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
-        Ok(())
+        self.db
+            .insert_value(LEAN_STATE_ROOT_INDEX_TABLE, key.as_slice(), &value)
     }
 }
@@ -1,5 +1,6 @@
 pub mod beacon;
 pub mod field;
+pub mod known_peers;
 pub mod lean;
 pub mod multimap_table;
 pub mod ssz_encoder;
@@ -0,0 +1,132 @@
+//! Round-trips the Beacon API types through JSON responses shaped like the ones of other clients,
+//! so a quoted integer or a hex encoding which doesn't match the spec fails here instead of
+//! breaking interop with validator clients.
+
+use std::{fs, path::PathBuf};
+
+use ream_api_types_beacon::{
+    committee::BeaconCommitteeSubscription,
+    duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
+    responses::{
+        BeaconResponse, DataResponse, DutiesResponse, ForkChoiceResponse,
+        SyncCommitteeDutiesResponse,
+    },
+    sync::SyncStatus,
+    validator::{ValidatorBalance, ValidatorData, ValidatorStatus},
+};
+use ream_consensus_misc::constants::beacon::FAR_FUTURE_EPOCH;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+const PATH_TO_FIXTURES_FOLDER: &str = "./tests/assets/conformance";
+
+/// Deserializes a fixture, and checks that serializing it again gives back the same JSON.
+fn assert_round_trip<T: Serialize + DeserializeOwned>(file_name: &str) -> anyhow::Result<T> {
+    let file_contents = fs::read_to_string(PathBuf::from(PATH_TO_FIXTURES_FOLDER).join(file_name))?;
+    let original_json: Value = serde_json::from_str(&file_contents)?;
+
+    let value: T = serde_json::from_value(original_json.clone())?;
+    assert_eq!(
+        serde_json::to_value(&value)?,
+        original_json,
+        "{file_name} doesn't round-trip"
+    );
+
+    Ok(value)
+}
+
+#[test]
+fn test_validators() -> anyhow::Result<()> {
+    let response = assert_round_trip::<BeaconResponse<Vec<ValidatorData>>>("validators.json")?;
+
+    let statuses = response
+        .data
+        .iter()
+        .map(|validator| validator.status)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        vec![
+            ValidatorStatus::ActiveOngoing,
+            ValidatorStatus::ActiveExiting,
+            ValidatorStatus::WithdrawalDone
+        ]
+    );
+    assert_eq!(response.data[0].validator.exit_epoch, FAR_FUTURE_EPOCH);
+    assert_eq!(response.data[1].balance, 32_000_932_107);
+
+    Ok(())
+}
+
+#[test]
+fn test_validator() -> anyhow::Result<()> {
+    let response = assert_round_trip::<BeaconResponse<ValidatorData>>("validator.json")?;
+    assert!(response.finalized);
+    assert_eq!(response.data.index, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_validator_balances() -> anyhow::Result<()> {
+    let response =
+        assert_round_trip::<BeaconResponse<Vec<ValidatorBalance>>>("validator_balances.json")?;
+    assert_eq!(response.data.len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_attester_duties() -> anyhow::Result<()> {
+    let response = assert_round_trip::<DutiesResponse<AttesterDuty>>("attester_duties.json")?;
+    assert_eq!(response.data[1].committee_index, 41);
+    assert_eq!(response.data[1].slot, 2188853);
+
+    Ok(())
+}
+
+#[test]
+fn test_proposer_duties() -> anyhow::Result<()> {
+    let response = assert_round_trip::<DutiesResponse<ProposerDuty>>("proposer_duties.json")?;
+    assert_eq!(response.data.len(), 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_sync_committee_duties() -> anyhow::Result<()> {
+    let response =
+        assert_round_trip::<SyncCommitteeDutiesResponse<SyncCommitteeDuty>>("sync_duties.json")?;
+    assert_eq!(
+        response.data[0].validator_sync_committee_indices,
+        vec![3, 131]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_syncing() -> anyhow::Result<()> {
+    let response = assert_round_trip::<DataResponse<SyncStatus>>("syncing.json")?;
+    assert!(!response.data.is_syncing);
+
+    Ok(())
+}
+
+#[test]
+fn test_fork_choice() -> anyhow::Result<()> {
+    let response = assert_round_trip::<ForkChoiceResponse>("fork_choice.json")?;
+    assert_eq!(response.fork_choice_nodes[0].weight, 1_048_576_000_000_000);
+
+    Ok(())
+}
+
+#[test]
+fn test_beacon_committee_subscriptions() -> anyhow::Result<()> {
+    let subscriptions = assert_round_trip::<Vec<BeaconCommitteeSubscription>>(
+        "beacon_committee_subscriptions.json",
+    )?;
+    assert!(subscriptions[1].is_aggregator);
+
+    Ok(())
+}
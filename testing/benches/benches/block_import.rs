@@ -0,0 +1,156 @@
+use std::sync::LazyLock;
+
+use benches::{
+    allocations::{AllocatedBytes, CountingAllocator},
+    assets_path, initialize_network_spec,
+    recorded_chain::RecordedChain,
+};
+use criterion::{
+    BatchSize, Criterion, Throughput, criterion_group, criterion_main, measurement::Measurement,
+};
+use ream_consensus_beacon::{
+    electra::beacon_state::BeaconState, execution_engine::mock_engine::MockExecutionEngine,
+};
+use ream_fork_choice::{
+    handlers::{on_block, on_tick},
+    store::{Store, get_forkchoice_store},
+};
+use ream_network_spec::networks::beacon_network_spec;
+use ream_storage::db::{ReamDB, config::DBConfig};
+use tempdir::TempDir;
+use tokio::runtime::{Builder, Runtime};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+struct Fixture {
+    chain: RecordedChain,
+    /// The state before every block of the chain
+    pre_states: Vec<BeaconState>,
+    runtime: Runtime,
+}
+
+static FIXTURE: LazyLock<Fixture> = LazyLock::new(|| {
+    let chain = RecordedChain::load(&assets_path()).expect("Failed to load the recorded chain");
+    initialize_network_spec(&chain.anchor_state).expect("Failed to initialize the network spec");
+    let runtime = Builder::new_current_thread()
+        .build()
+        .expect("Failed to build the runtime");
+    let pre_states = runtime
+        .block_on(chain.pre_states())
+        .expect("The recorded blocks don't apply to the anchor state");
+
+    Fixture {
+        chain,
+        pre_states,
+        runtime,
+    }
+});
+
+/// Initializes a fork choice store at the anchor of the recorded chain. The store lives in the
+/// returned directory.
+fn anchor_store(chain: &RecordedChain) -> (Store, TempDir) {
+    let data_dir = TempDir::new("ream_bench").expect("Failed to create the data directory");
+    let db = ReamDB::new(data_dir.path().to_path_buf(), DBConfig::default())
+        .expect("Failed to open the database")
+        .init_beacon_db()
+        .expect("Failed to initialize the beacon database");
+    let store = get_forkchoice_store(
+        chain.anchor_state.clone(),
+        chain.anchor_block.message.clone(),
+        db,
+    )
+    .expect("Failed to initialize the fork choice store");
+
+    (store, data_dir)
+}
+
+/// Imports the recorded blocks into `store`, advancing its time to the slot of every block.
+fn import_blocks(fixture: &Fixture, store: &mut Store) {
+    let execution_engine = Some(MockExecutionEngine::new());
+    let seconds_per_slot = beacon_network_spec().seconds_per_slot;
+    for block in &fixture.chain.blocks {
+        on_tick(
+            store,
+            fixture.chain.anchor_state.genesis_time
+                + block.message.slot.as_u64() * seconds_per_slot,
+        )
+        .expect("Failed to advance the store time");
+        fixture
+            .runtime
+            .block_on(on_block(store, block, &execution_engine, false, true))
+            .expect("The fork choice rejected a recorded block");
+    }
+}
+
+fn state_transition<M: Measurement>(c: &mut Criterion<M>, measurement_name: &str) {
+    let fixture = &*FIXTURE;
+    let execution_engine = Some(MockExecutionEngine::new());
+    let mut group = c.benchmark_group(format!("{measurement_name}/state_transition"));
+    for (block, pre_state) in fixture.chain.blocks.iter().zip(&fixture.pre_states) {
+        group.bench_function(block.message.slot.to_string(), |b| {
+            b.iter_batched(
+                || pre_state.clone(),
+                |mut state| {
+                    fixture
+                        .runtime
+                        .block_on(state.state_transition(block, true, &execution_engine))
+                        .expect("State transition failed");
+                    state
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn fork_choice<M: Measurement>(c: &mut Criterion<M>, measurement_name: &str) {
+    let fixture = &*FIXTURE;
+    let mut group = c.benchmark_group(format!("{measurement_name}/fork_choice"));
+
+    group.throughput(Throughput::Elements(fixture.chain.blocks.len() as u64));
+    group.bench_function("on_block", |b| {
+        b.iter_batched(
+            || anchor_store(&fixture.chain),
+            |(mut store, data_dir)| {
+                import_blocks(fixture, &mut store);
+                (store, data_dir)
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    let (mut store, _data_dir) = anchor_store(&fixture.chain);
+    import_blocks(fixture, &mut store);
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("get_head", |b| {
+        b.iter(|| store.get_head().expect("Failed to get the head"))
+    });
+
+    group.finish();
+}
+
+fn time(c: &mut Criterion) {
+    state_transition(c, "time");
+    fork_choice(c, "time");
+}
+
+fn allocations(c: &mut Criterion<AllocatedBytes>) {
+    state_transition(c, "allocations");
+    fork_choice(c, "allocations");
+}
+
+criterion_group! {
+    name = time_benches;
+    config = Criterion::default().sample_size(10);
+    targets = time
+}
+
+criterion_group! {
+    name = allocation_benches;
+    config = Criterion::default().with_measurement(AllocatedBytes).sample_size(10);
+    targets = allocations
+}
+
+criterion_main!(time_benches, allocation_benches);
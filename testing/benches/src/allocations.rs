@@ -0,0 +1,116 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use criterion::{
+    Throughput,
+    measurement::{Measurement, ValueFormatter},
+};
+
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Forwards to the system allocator and counts the bytes allocated by all threads.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::alloc`
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::alloc_zeroed`
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // Growing a buffer copies it, so the whole new size counts
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::realloc`
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::dealloc`
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+/// The bytes allocated since the program started, if it installed [CountingAllocator] as its
+/// global allocator.
+pub fn allocated_bytes() -> u64 {
+    ALLOCATED_BYTES.load(Ordering::Relaxed)
+}
+
+/// A criterion measurement of the bytes a benchmark allocates instead of the time it takes.
+pub struct AllocatedBytes;
+
+impl Measurement for AllocatedBytes {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        allocated_bytes()
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        allocated_bytes() - start
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &BytesFormatter
+    }
+}
+
+struct BytesFormatter;
+
+impl ValueFormatter for BytesFormatter {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let (denominator, unit) = match typical_value {
+            value if value < 1024.0 => (1.0, "B"),
+            value if value < 1024.0 * 1024.0 => (1024.0, "KiB"),
+            value if value < 1024.0 * 1024.0 * 1024.0 => (1024.0 * 1024.0, "MiB"),
+            _ => (1024.0 * 1024.0 * 1024.0, "GiB"),
+        };
+        for value in values {
+            *value /= denominator;
+        }
+        unit
+    }
+
+    /// Reports the bytes allocated per element, e.g. per imported block.
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let elements = match throughput {
+            Throughput::Elements(elements) => *elements as f64,
+            _ => 1.0,
+        };
+        for value in values.iter_mut() {
+            *value /= elements;
+        }
+        self.scale_values(typical_value / elements, values)
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+}
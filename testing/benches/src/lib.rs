@@ -0,0 +1,43 @@
+//! Benchmarks which replay a recorded sequence of blocks through the state transition and the
+//! fork choice, so changes to the block import path can be compared against a fixed workload.
+//!
+//! The blocks and states are read from [DEFAULT_ASSETS_PATH], or from the directory set in
+//! [ASSETS_PATH_ENV] with the network set in [NETWORK_ENV].
+
+pub mod allocations;
+pub mod recorded_chain;
+
+use std::{env, path::PathBuf};
+
+use anyhow::anyhow;
+use ream_consensus_beacon::electra::beacon_state::BeaconState;
+use ream_consensus_misc::constants::beacon::set_genesis_validator_root;
+use ream_network_spec::{cli::beacon_network_parser, networks::set_beacon_network_spec};
+
+/// Blocks and states recorded from sepolia, which the gossip validation tests use as well.
+pub const DEFAULT_ASSETS_PATH: &str = "../gossip-validation/tests/assets/sepolia";
+
+/// Overrides the directory of the recorded blocks and states.
+pub const ASSETS_PATH_ENV: &str = "REAM_BENCH_ASSETS";
+
+/// Overrides the network the blocks were recorded from, either by name or as a path to a config.
+pub const NETWORK_ENV: &str = "REAM_BENCH_NETWORK";
+
+pub fn assets_path() -> PathBuf {
+    env::var(ASSETS_PATH_ENV)
+        .unwrap_or_else(|_| DEFAULT_ASSETS_PATH.to_string())
+        .into()
+}
+
+/// Sets the network spec the recorded blocks belong to, and the genesis validators root of the
+/// anchor state.
+///
+/// # Panics
+///
+/// Panics if it is called more than once.
+pub fn initialize_network_spec(anchor_state: &BeaconState) -> anyhow::Result<()> {
+    let network = env::var(NETWORK_ENV).unwrap_or_else(|_| "sepolia".to_string());
+    set_beacon_network_spec(beacon_network_parser(&network).map_err(|err| anyhow!(err))?);
+    set_genesis_validator_root(anchor_state.genesis_validators_root);
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, ensure};
+use ream_consensus_beacon::{
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    execution_engine::mock_engine::MockExecutionEngine,
+};
+use snap::raw::Decoder;
+use ssz::Decode;
+
+/// A chain of blocks recorded from a live network, built on a block whose post state is recorded
+/// as well.
+pub struct RecordedChain {
+    pub anchor_state: BeaconState,
+    pub anchor_block: SignedBeaconBlock,
+    /// The blocks following the anchor block, in order of their slot
+    pub blocks: Vec<SignedBeaconBlock>,
+}
+
+impl RecordedChain {
+    /// Loads the blocks from `blocks/slot_<slot>.ssz_snappy` and the post states of blocks from
+    /// `states/slot_<slot>.ssz_snappy` in `path`. The anchor is the earliest block whose post state
+    /// is recorded, and every later block must build on the one before it.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let block_paths = files_by_slot(&path.join("blocks"))?;
+        let state_paths = files_by_slot(&path.join("states"))?;
+
+        let Some((anchor_slot, anchor_state_path)) = state_paths
+            .into_iter()
+            .find(|(slot, _)| block_paths.contains_key(slot))
+        else {
+            bail!("No block with a recorded post state in {path:?}");
+        };
+        let anchor_state: BeaconState = read_ssz_snappy(&anchor_state_path)?;
+
+        let mut blocks = block_paths
+            .range(anchor_slot..)
+            .map(|(_, block_path)| read_ssz_snappy::<SignedBeaconBlock>(block_path))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let anchor_block = blocks.remove(0);
+        ensure!(
+            anchor_block.message.state_root == anchor_state.state_root(),
+            "The state of slot {anchor_slot} isn't the post state of its block"
+        );
+
+        let mut parent_root = anchor_block.message.block_root();
+        for block in &blocks {
+            ensure!(
+                block.message.parent_root == parent_root,
+                "The block of slot {} doesn't build on {parent_root}",
+                block.message.slot
+            );
+            parent_root = block.message.block_root();
+        }
+
+        Ok(Self {
+            anchor_state,
+            anchor_block,
+            blocks,
+        })
+    }
+
+    /// Replays the blocks on the anchor state, verifying their signatures and state roots, and
+    /// returns the state before every block.
+    pub async fn pre_states(&self) -> anyhow::Result<Vec<BeaconState>> {
+        let execution_engine = Some(MockExecutionEngine::new());
+        let mut state = self.anchor_state.clone();
+        let mut pre_states = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            pre_states.push(state.clone());
+            state
+                .state_transition(block, true, &execution_engine)
+                .await?;
+        }
+        Ok(pre_states)
+    }
+}
+
+/// Returns the files named `slot_<slot>.ssz_snappy` in `directory` by their slot.
+fn files_by_slot(directory: &Path) -> anyhow::Result<BTreeMap<u64, PathBuf>> {
+    let mut files = BTreeMap::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let slot = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix("slot_"))
+            .and_then(|file_name| file_name.strip_suffix(".ssz_snappy"))
+            .and_then(|slot| slot.parse().ok());
+        if let Some(slot) = slot {
+            files.insert(slot, path);
+        }
+    }
+    Ok(files)
+}
+
+fn read_ssz_snappy<T: Decode>(path: &Path) -> anyhow::Result<T> {
+    let ssz_snappy = fs::read(path)?;
+    let ssz = Decoder::new().decompress_vec(&ssz_snappy)?;
+    T::from_ssz_bytes(&ssz).map_err(|err| anyhow!("Failed to decode {path:?}: {err:?}"))
+}
@@ -18,7 +18,7 @@ macro_rules! test_fork_choice {
                 };
                 use ream_network_spec::networks::initialize_test_network_spec;
                 use ream_storage::{
-                    db::{ReamDB, beacon::BeaconDB},
+                    db::{ReamDB, beacon::BeaconDB, config::DBConfig},
                     tables::{table::Table, field::Field},
                     dir::setup_data_dir
                 };
@@ -130,7 +130,7 @@ macro_rules! test_fork_choice {
 
                         let ream_dir = setup_data_dir("ream", None, true).expect("Failed to create data dir");
 
-                        let ream_db = ReamDB::new(ream_dir).expect("unable to init Ream Database");
+                        let ream_db = ReamDB::new(ream_dir, DBConfig::default()).expect("unable to init Ream Database");
                         let beacon_db = ream_db.init_beacon_db().expect("count not find reabdb");
                         let mut store = get_forkchoice_store(anchor_state, anchor_block, beacon_db)
                             .expect("get_forkchoice_store failed");
@@ -163,7 +163,7 @@ macro_rules! test_fork_choice {
                                         }
                                     }
 
-                                    assert_eq!(on_block(&mut store, &block, &mock_engine, true).await.is_ok(), blocks.valid.unwrap_or(true), "Unexpected result on on_block");
+                                    assert_eq!(on_block(&mut store, &block, &mock_engine, true, true).await.is_ok(), blocks.valid.unwrap_or(true), "Unexpected result on on_block");
                                 }
                                 ForkChoiceStep::Attestation(attestations) => {
                                     let attestation_path =
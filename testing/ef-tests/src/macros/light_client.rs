@@ -0,0 +1,225 @@
+#[macro_export]
+macro_rules! test_light_client_update_ranking {
+    () => {
+        #[cfg(test)]
+        mod tests_light_client_update_ranking {
+            use std::path::Path;
+
+            use ream_light_client::update::LightClientUpdate;
+            use rstest::rstest;
+            use serde::Deserialize;
+
+            use super::*;
+
+            #[derive(Debug, Deserialize)]
+            struct Meta {
+                updates_count: usize,
+            }
+
+            #[rstest]
+            fn test_light_client_update_ranking() {
+                let base_path = Path::new(
+                    "mainnet/tests/mainnet/electra/light_client/update_ranking/pyspec_tests",
+                );
+
+                // The vectors are only generated for the minimal preset so far
+                if !base_path.exists() {
+                    println!("Skipping, no test cases at {base_path:?}");
+                    return;
+                }
+
+                for entry in std::fs::read_dir(base_path).unwrap() {
+                    let entry = entry.unwrap();
+                    let case_dir = entry.path();
+
+                    if !case_dir.is_dir() {
+                        continue;
+                    }
+
+                    let case_name = case_dir.file_name().unwrap().to_str().unwrap();
+                    println!("Testing case: {case_name}");
+
+                    let meta: Meta = {
+                        let content = std::fs::read_to_string(case_dir.join("meta.yaml"))
+                            .expect("Failed to read meta.yaml");
+                        serde_yaml::from_str(&content).expect("Failed to parse meta.yaml")
+                    };
+
+                    let updates = (0..meta.updates_count)
+                        .map(|index| {
+                            utils::read_ssz_snappy::<LightClientUpdate>(
+                                &case_dir.join(format!("updates_{index}.ssz_snappy")),
+                            )
+                            .expect("cannot find test asset (updates_<index>.ssz_snappy)")
+                        })
+                        .collect::<Vec<_>>();
+
+                    // The updates are sorted from the best to the worst
+                    for (index, update) in updates.iter().enumerate() {
+                        for worse_update in &updates[index + 1..] {
+                            assert!(
+                                !worse_update.is_better_update(update),
+                                "Update ranking mismatch in {case_name}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_light_client_sync {
+    () => {
+        #[cfg(test)]
+        mod tests_light_client_sync {
+            use std::path::Path;
+
+            use alloy_primitives::{B256, aliases::B32};
+            use ream_light_client::{
+                bootstrap::LightClientBootstrap, header::LightClientHeader,
+                store::LightClientStore, update::LightClientUpdate,
+            };
+            use rstest::rstest;
+            use serde::Deserialize;
+            use tree_hash::TreeHash;
+
+            use super::*;
+
+            #[derive(Debug, Deserialize)]
+            struct Meta {
+                genesis_validators_root: B256,
+                trusted_block_root: B256,
+            }
+
+            #[derive(Debug, Deserialize)]
+            #[serde(rename_all = "UPPERCASE")]
+            struct Config {
+                #[serde(with = "ream_network_spec::b32_hex")]
+                electra_fork_version: B32,
+            }
+
+            #[derive(Debug, Deserialize)]
+            struct HeaderCheck {
+                slot: u64,
+                beacon_root: B256,
+                execution_root: B256,
+            }
+
+            #[derive(Debug, Deserialize)]
+            struct Checks {
+                finalized_header: HeaderCheck,
+                optimistic_header: HeaderCheck,
+            }
+
+            #[derive(Debug, Deserialize)]
+            struct ProcessUpdate {
+                update: String,
+                current_slot: u64,
+                checks: Checks,
+            }
+
+            #[derive(Debug, Deserialize)]
+            struct ForceUpdate {
+                current_slot: u64,
+                checks: Checks,
+            }
+
+            #[derive(Debug, Deserialize)]
+            #[serde(rename_all = "snake_case")]
+            enum Step {
+                ProcessUpdate(ProcessUpdate),
+                ForceUpdate(ForceUpdate),
+                UpgradeStore(serde_yaml::Value),
+            }
+
+            fn check_header(header: &LightClientHeader, expected: &HeaderCheck) {
+                assert_eq!(header.beacon.slot, expected.slot);
+                assert_eq!(header.beacon.tree_hash_root(), expected.beacon_root);
+                assert_eq!(header.execution.tree_hash_root(), expected.execution_root);
+            }
+
+            fn check_store(store: &LightClientStore, checks: &Checks) {
+                check_header(&store.finalized_header, &checks.finalized_header);
+                check_header(&store.optimistic_header, &checks.optimistic_header);
+            }
+
+            #[rstest]
+            fn test_light_client_sync() {
+                let base_path =
+                    Path::new("mainnet/tests/mainnet/electra/light_client/sync/pyspec_tests");
+
+                // The vectors are only generated for the minimal preset so far
+                if !base_path.exists() {
+                    println!("Skipping, no test cases at {base_path:?}");
+                    return;
+                }
+
+                for entry in std::fs::read_dir(base_path).unwrap() {
+                    let entry = entry.unwrap();
+                    let case_dir = entry.path();
+
+                    if !case_dir.is_dir() {
+                        continue;
+                    }
+
+                    let case_name = case_dir.file_name().unwrap().to_str().unwrap();
+                    println!("Testing case: {case_name}");
+
+                    let meta: Meta = {
+                        let content = std::fs::read_to_string(case_dir.join("meta.yaml"))
+                            .expect("Failed to read meta.yaml");
+                        serde_yaml::from_str(&content).expect("Failed to parse meta.yaml")
+                    };
+                    let config: Config = {
+                        let content = std::fs::read_to_string(case_dir.join("config.yaml"))
+                            .expect("Failed to read config.yaml");
+                        serde_yaml::from_str(&content).expect("Failed to parse config.yaml")
+                    };
+                    let steps: Vec<Step> = {
+                        let content = std::fs::read_to_string(case_dir.join("steps.yaml"))
+                            .expect("Failed to read steps.yaml");
+                        serde_yaml::from_str(&content).expect("Failed to parse steps.yaml")
+                    };
+
+                    let bootstrap: LightClientBootstrap =
+                        utils::read_ssz_snappy(&case_dir.join("bootstrap.ssz_snappy"))
+                            .expect("cannot find test asset (bootstrap.ssz_snappy)");
+                    let mut store = LightClientStore::new(meta.trusted_block_root, bootstrap)
+                        .expect("Failed to initialize the light client store");
+
+                    for step in steps {
+                        match step {
+                            Step::ProcessUpdate(step) => {
+                                let update: LightClientUpdate = utils::read_ssz_snappy(
+                                    &case_dir.join(format!("{}.ssz_snappy", step.update)),
+                                )
+                                .expect("cannot find test asset (update)");
+                                store
+                                    .process_light_client_update(
+                                        update,
+                                        step.current_slot,
+                                        config.electra_fork_version,
+                                        meta.genesis_validators_root,
+                                    )
+                                    .expect("process_light_client_update failed");
+                                check_store(&store, &step.checks);
+                            }
+                            Step::ForceUpdate(step) => {
+                                store
+                                    .process_light_client_store_force_update(step.current_slot)
+                                    .expect("process_light_client_store_force_update failed");
+                                check_store(&store, &step.checks);
+                            }
+                            // Electra is the only fork the light client supports
+                            Step::UpgradeStore(_) => {
+                                panic!("Unexpected store upgrade in {case_name}")
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
@@ -1,5 +1,6 @@
 pub mod epoch_processing;
 pub mod fork_choice;
+pub mod light_client;
 pub mod merkle_proof;
 pub mod operations;
 pub mod rewards;
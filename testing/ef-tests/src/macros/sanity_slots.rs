@@ -48,7 +48,7 @@ macro_rules! test_sanity_slots {
                     let expected_post =
                         utils::read_ssz_snappy::<BeaconState>(&case_dir.join("post.ssz_snappy"));
 
-                    let result = state.process_slots(state.slot + slot);
+                    let result = state.process_slots((state.slot + slot).as_u64());
 
                     match (result, expected_post) {
                         (Ok(_), Ok(expected)) => {
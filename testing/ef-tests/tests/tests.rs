@@ -1,9 +1,9 @@
 #![cfg(feature = "ef-tests")]
 
 use ef_tests::{
-    test_consensus_type, test_epoch_processing, test_fork_choice, test_merkle_proof,
-    test_merkle_proof_impl, test_operation, test_rewards, test_sanity_blocks, test_sanity_slots,
-    test_shuffling, utils,
+    test_consensus_type, test_epoch_processing, test_fork_choice, test_light_client_sync,
+    test_light_client_update_ranking, test_merkle_proof, test_merkle_proof_impl, test_operation,
+    test_rewards, test_sanity_blocks, test_sanity_slots, test_shuffling, utils,
 };
 use ream_consensus_beacon::{
     attestation::Attestation,
@@ -39,6 +39,11 @@ use ream_consensus_misc::{
     indexed_attestation::IndexedAttestation, misc::compute_shuffled_index,
     signing_data::SigningData, validator::Validator,
 };
+use ream_light_client::{
+    bootstrap::LightClientBootstrap, finality_update::LightClientFinalityUpdate,
+    header::LightClientHeader, optimistic_update::LightClientOptimisticUpdate,
+    update::LightClientUpdate,
+};
 use ream_merkle::is_valid_normalized_merkle_branch;
 
 // General consensus types
@@ -82,6 +87,13 @@ test_consensus_type!(PendingPartialWithdrawal);
 test_consensus_type!(SingleAttestation);
 test_consensus_type!(WithdrawalRequest);
 
+// Light client types
+test_consensus_type!(LightClientBootstrap);
+test_consensus_type!(LightClientFinalityUpdate);
+test_consensus_type!(LightClientHeader);
+test_consensus_type!(LightClientOptimisticUpdate);
+test_consensus_type!(LightClientUpdate);
+
 // Testing operations
 test_operation!(attestation, Attestation, "attestation", process_attestation);
 test_operation!(
@@ -185,6 +197,10 @@ test_fork_choice!(get_proposer_head);
 test_fork_choice!(on_block);
 test_fork_choice!(should_override_forkchoice_update);
 
+// Testing light_client
+test_light_client_sync!();
+test_light_client_update_ranking!();
+
 // Testing merkle_proof
 test_merkle_proof!(
     "light_client",
@@ -10,15 +10,15 @@ mod tests {
         bls_to_execution_change::BLSToExecutionChange,
         electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
     };
-    use ream_consensus_misc::checkpoint::Checkpoint;
+    use ream_consensus_misc::{checkpoint::Checkpoint, slot_epoch::Slot};
     use ream_network_manager::gossipsub::validate::{
         beacon_block::validate_gossip_beacon_block, result::ValidationResult,
     };
-    use ream_network_spec::networks::initialize_test_network_spec;
+    use ream_network_spec::networks::{beacon_network_spec, initialize_test_network_spec};
     use ream_operation_pool::OperationPool;
     use ream_storage::{
         cache::{AddressSlotIdentifier, CachedDB},
-        db::{ReamDB, beacon::BeaconDB},
+        db::{ReamDB, beacon::BeaconDB, config::DBConfig},
         tables::{field::Field, table::Table},
     };
     use snap::raw::Decoder;
@@ -31,7 +31,8 @@ mod tests {
     pub async fn db_setup() -> (BeaconChain, CachedDB, B256) {
         let temp_dir = TempDir::new("ream_gossip_test").unwrap();
         let temp_path = temp_dir.path().to_path_buf();
-        let ream_db = ReamDB::new(temp_path).expect("unable to init Ream Database");
+        let ream_db =
+            ReamDB::new(temp_path, DBConfig::default()).expect("unable to init Ream Database");
         let mut db = ream_db.init_beacon_db().unwrap();
 
         let ancestor_beacon_block = read_ssz_snappy_file::<SignedBeaconBlock>(
@@ -90,7 +91,7 @@ mod tests {
         parent_beacon_state: BeaconState,
     ) {
         let ancestor_checkpoint = Checkpoint {
-            epoch: ancestor_beacon_block.message.slot / 32,
+            epoch: ancestor_beacon_block.message.slot.epoch(),
             root: ancestor_beacon_block.message.block_root(),
         };
         db.beacon_block_provider()
@@ -100,7 +101,7 @@ mod tests {
             )
             .unwrap();
 
-        let slot = parent_beacon_block.message.slot;
+        let slot = parent_beacon_block.message.slot.as_u64();
         db.finalized_checkpoint_provider()
             .insert(ancestor_checkpoint)
             .unwrap();
@@ -156,7 +157,7 @@ mod tests {
                 .unwrap()
         );
 
-        let result =
+        let (result, _) =
             validate_gossip_beacon_block(&beacon_chain, &cached_db, &incoming_beacon_block)
                 .await
                 .unwrap();
@@ -173,10 +174,10 @@ mod tests {
             "./assets/sepolia/blocks/slot_8084250.ssz_snappy",
         )
         .unwrap();
-        let future_slot = beacon_chain.store.lock().await.get_current_slot().unwrap() + 10;
+        let future_slot = Slot::new(beacon_network_spec().current_slot() + 10);
         incoming_beacon_block.message.slot = future_slot;
 
-        let result =
+        let (result, _) =
             validate_gossip_beacon_block(&beacon_chain, &cached_db, &incoming_beacon_block)
                 .await
                 .unwrap();
@@ -195,7 +196,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = validate_gossip_beacon_block(&beacon_chain, &cached_db, &ancestor_block)
+        let (result, _) = validate_gossip_beacon_block(&beacon_chain, &cached_db, &ancestor_block)
             .await
             .unwrap();
         assert!(
@@ -216,7 +217,7 @@ mod tests {
         // Mutate proposer index to a very high index
         incoming_beacon_block.message.proposer_index = 999999;
 
-        let result =
+        let (result, _) =
             validate_gossip_beacon_block(&beacon_chain, &cached_db, &incoming_beacon_block)
                 .await
                 .unwrap();
@@ -255,7 +256,7 @@ mod tests {
             );
         }
 
-        let result =
+        let (result, _) =
             validate_gossip_beacon_block(&beacon_chain, &cached_db, &incoming_beacon_block)
                 .await
                 .unwrap();
@@ -297,7 +298,7 @@ mod tests {
             );
         }
 
-        let result =
+        let (result, _) =
             validate_gossip_beacon_block(&beacon_chain, &cached_db, &incoming_beacon_block)
                 .await
                 .unwrap();
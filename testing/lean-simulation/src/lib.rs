@@ -0,0 +1,11 @@
+//! Deterministic simulation of a lean chain network.
+//!
+//! A [simulation::Simulation] runs several lean nodes in a single task. Every node is a
+//! [ream_chain_lean::service::LeanChainService] with its own database, whose ticks come from a
+//! virtual clock and whose gossip and Req/Resp requests go through an in-memory
+//! [network::SimulatedNetwork]. The network delays, drops and partitions messages with a seeded
+//! random number generator, so a run only depends on its [simulation::SimulationConfig].
+
+pub mod network;
+pub mod node;
+pub mod simulation;
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, time::Duration};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Conditions of the links between the nodes, which apply to every message independently.
+#[derive(Debug, Clone)]
+pub struct NetworkConditions {
+    /// The shortest time a message takes to arrive
+    pub min_latency: Duration,
+    /// The longest time a message takes to arrive
+    pub max_latency: Duration,
+    /// The probability that a message is lost
+    pub drop_probability: f64,
+}
+
+impl Default for NetworkConditions {
+    fn default() -> Self {
+        Self {
+            min_latency: Duration::from_millis(10),
+            max_latency: Duration::from_millis(100),
+            drop_probability: 0.0,
+        }
+    }
+}
+
+/// An in-memory network between the nodes of a simulation, identified by their index.
+///
+/// Every random decision is drawn from a generator seeded at creation, so the same sequence of
+/// transmissions always has the same outcome.
+#[derive(Debug)]
+pub struct SimulatedNetwork {
+    conditions: NetworkConditions,
+    /// The group of every node while the network is partitioned
+    partition: Option<HashMap<usize, usize>>,
+    rng: ChaCha8Rng,
+}
+
+impl SimulatedNetwork {
+    pub fn new(seed: u64, conditions: NetworkConditions) -> Self {
+        Self {
+            conditions,
+            partition: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn set_conditions(&mut self, conditions: NetworkConditions) {
+        self.conditions = conditions;
+    }
+
+    /// Splits the nodes into groups which can't reach each other. Nodes which aren't in any of
+    /// the groups can't reach anyone.
+    pub fn partition(&mut self, groups: &[&[usize]]) {
+        self.partition = Some(
+            groups
+                .iter()
+                .enumerate()
+                .flat_map(|(group, nodes)| nodes.iter().map(move |node| (*node, group)))
+                .collect(),
+        );
+    }
+
+    /// Reconnects all nodes after [SimulatedNetwork::partition].
+    pub fn heal(&mut self) {
+        self.partition = None;
+    }
+
+    pub fn is_connected(&self, from: usize, to: usize) -> bool {
+        match &self.partition {
+            Some(groups) => matches!(
+                (groups.get(&from), groups.get(&to)),
+                (Some(from_group), Some(to_group)) if from_group == to_group
+            ),
+            None => true,
+        }
+    }
+
+    /// Returns how long a message from `from` to `to` takes to arrive, or None if it is lost.
+    pub fn transmit(&mut self, from: usize, to: usize) -> Option<Duration> {
+        if !self.is_connected(from, to) {
+            return None;
+        }
+
+        if self.conditions.drop_probability > 0.0
+            && self.rng.random_bool(self.conditions.drop_probability)
+        {
+            return None;
+        }
+
+        let min_latency = self.conditions.min_latency.as_nanos() as u64;
+        let max_latency = self.conditions.max_latency.as_nanos() as u64;
+        Some(Duration::from_nanos(
+            self.rng
+                .random_range(min_latency..=max_latency.max(min_latency)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_isolates_groups() {
+        let mut network = SimulatedNetwork::new(0, NetworkConditions::default());
+        network.partition(&[&[0, 1], &[2]]);
+
+        assert!(network.transmit(0, 1).is_some());
+        assert!(network.transmit(1, 2).is_none());
+        assert!(network.transmit(3, 0).is_none());
+
+        network.heal();
+        assert!(network.transmit(1, 2).is_some());
+        assert!(network.transmit(3, 0).is_some());
+    }
+
+    #[test]
+    fn test_transmit_is_deterministic() {
+        let conditions = NetworkConditions {
+            drop_probability: 0.5,
+            ..Default::default()
+        };
+        let transmissions = |seed| {
+            let mut network = SimulatedNetwork::new(seed, conditions.clone());
+            (0..64).map(|_| network.transmit(0, 1)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(transmissions(7), transmissions(7));
+        assert!(
+            transmissions(7)
+                .iter()
+                .all(|latency| latency
+                    .is_none_or(|latency| (conditions.min_latency..=conditions.max_latency)
+                        .contains(&latency)))
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use alloy_primitives::{B256, FixedBytes};
+use anyhow::{Context, anyhow};
+use ream_chain_lean::{
+    lean_chain::{LeanChain, LeanChainReader},
+    messages::LeanChainServiceMessage,
+    p2p_request::LeanP2PRequest,
+    service::LeanChainService,
+};
+use ream_consensus_lean::{
+    block::{Block, SignedBlock},
+    checkpoint::Checkpoint,
+    state::LeanState,
+    vote::SignedVote,
+};
+use ream_slot_clock::SlotTime;
+use ream_storage::{
+    db::{ReamDB, config::DBConfig},
+    tables::{field::Field, table::Table},
+};
+use ream_sync::rwlock::Writer;
+use tempdir::TempDir;
+use tokio::sync::{mpsc, oneshot};
+
+/// A lean node of a simulation, running a [LeanChainService] and the duties of its validators.
+///
+/// The node only acts when the simulation hands it a tick or a message, and the requests it makes
+/// to the network are collected with [SimulatedNode::take_outbound].
+pub struct SimulatedNode {
+    validator_ids: Vec<u64>,
+    service: LeanChainService,
+    chain_sender: mpsc::UnboundedSender<LeanChainServiceMessage>,
+    outbound: mpsc::UnboundedReceiver<LeanP2PRequest>,
+    lean_chain: LeanChainReader,
+    // Removed once the node is dropped
+    _data_dir: TempDir,
+}
+
+impl SimulatedNode {
+    pub async fn new(
+        validator_ids: Vec<u64>,
+        genesis_block: Block,
+        genesis_state: LeanState,
+    ) -> anyhow::Result<Self> {
+        let data_dir = TempDir::new("lean_simulation")?;
+        let lean_db =
+            ReamDB::new(data_dir.path().to_path_buf(), DBConfig::default())?.init_lean_db()?;
+        let genesis_block = SignedBlock {
+            message: genesis_block,
+            signature: FixedBytes::default(),
+        };
+        let (lean_chain_writer, lean_chain) =
+            Writer::new(LeanChain::new(genesis_block, genesis_state, lean_db));
+
+        let (chain_sender, chain_receiver) = mpsc::unbounded_channel();
+        let (outbound_sender, outbound) = mpsc::unbounded_channel();
+        let service = LeanChainService::new(
+            lean_chain_writer,
+            chain_receiver,
+            chain_sender.clone(),
+            outbound_sender,
+            None,
+            None,
+        )
+        .await;
+
+        Ok(Self {
+            validator_ids,
+            service,
+            chain_sender,
+            outbound,
+            lean_chain,
+            _data_dir: data_dir,
+        })
+    }
+
+    pub fn validator_ids(&self) -> &[u64] {
+        &self.validator_ids
+    }
+
+    /// Performs the work of an interval: the one of the chain service, then the duties of the
+    /// validators like the `ValidatorService` does.
+    pub async fn on_tick(&mut self, slot_time: SlotTime) -> anyhow::Result<()> {
+        self.service.handle_tick(slot_time).await?;
+        match slot_time.interval {
+            0 => self.propose_block(slot_time.slot).await?,
+            1 => self.vote(slot_time.slot).await?,
+            _ => {}
+        }
+        self.service.handle_queued_messages().await;
+        Ok(())
+    }
+
+    /// Hands a message which arrived from the network to the chain service.
+    pub async fn receive(&mut self, message: LeanChainServiceMessage) -> anyhow::Result<()> {
+        self.chain_sender.send(message)?;
+        self.service.handle_queued_messages().await;
+        Ok(())
+    }
+
+    /// Takes the gossip and Req/Resp requests the node made since the last call.
+    pub fn take_outbound(&mut self) -> Vec<LeanP2PRequest> {
+        let mut requests = vec![];
+        while let Ok(request) = self.outbound.try_recv() {
+            requests.push(request);
+        }
+        requests
+    }
+
+    pub async fn head(&self) -> B256 {
+        self.lean_chain.read().await.head
+    }
+
+    pub async fn latest_justified(&self) -> anyhow::Result<Checkpoint> {
+        let lean_chain = self.lean_chain.read().await;
+        Ok(lean_chain
+            .store
+            .lock()
+            .await
+            .latest_justified_provider()
+            .get()?)
+    }
+
+    pub async fn latest_finalized(&self) -> anyhow::Result<Checkpoint> {
+        let lean_chain = self.lean_chain.read().await;
+        Ok(lean_chain
+            .store
+            .lock()
+            .await
+            .latest_finalized_provider()
+            .get()?)
+    }
+
+    pub async fn block(&self, root: B256) -> anyhow::Result<Option<SignedBlock>> {
+        let lean_chain = self.lean_chain.read().await;
+        Ok(lean_chain
+            .store
+            .lock()
+            .await
+            .lean_block_provider()
+            .get(root)?)
+    }
+
+    /// Returns the root of the block at `slot` in the chain of `root`, or None if the chain has no
+    /// block at that slot.
+    pub async fn ancestor_at(&self, mut root: B256, slot: u64) -> anyhow::Result<Option<B256>> {
+        loop {
+            let block = self
+                .block(root)
+                .await?
+                .ok_or_else(|| anyhow!("Block not found: {root}"))?;
+            if block.message.slot == slot {
+                return Ok(Some(root));
+            }
+            if block.message.slot < slot {
+                return Ok(None);
+            }
+            root = block.message.parent_root;
+        }
+    }
+
+    async fn propose_block(&mut self, slot: u64) -> anyhow::Result<()> {
+        let proposer_index = slot % self.lean_chain.read().await.num_validators;
+        if !self.validator_ids.contains(&proposer_index) {
+            return Ok(());
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        self.chain_sender
+            .send(LeanChainServiceMessage::ProduceBlock { slot, sender })?;
+        self.service.handle_queued_messages().await;
+        let block = receiver
+            .await
+            .with_context(|| format!("Validator {proposer_index} failed to produce a block"))?;
+
+        self.chain_sender
+            .send(LeanChainServiceMessage::ProcessBlock {
+                signed_block: SignedBlock {
+                    message: block,
+                    signature: FixedBytes::default(),
+                },
+                is_trusted: true,
+                need_gossip: true,
+            })?;
+        Ok(())
+    }
+
+    async fn vote(&mut self, slot: u64) -> anyhow::Result<()> {
+        let vote = self.lean_chain.read().await.build_vote(slot).await?;
+        for validator_id in &self.validator_ids {
+            self.chain_sender
+                .send(LeanChainServiceMessage::ProcessVote {
+                    signed_vote: SignedVote {
+                        validator_id: *validator_id,
+                        message: vote.clone(),
+                        signature: FixedBytes::default(),
+                    },
+                    is_trusted: true,
+                    need_gossip: true,
+                })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
+
+use alloy_primitives::B256;
+use anyhow::ensure;
+use ream_chain_lean::{
+    genesis::generate_genesis, messages::LeanChainServiceMessage, p2p_request::LeanP2PRequest,
+};
+use ream_consensus_misc::constants::lean::{INTERVALS_PER_SLOT, SLOT_OFFSET};
+use ream_slot_clock::SlotClock;
+use tree_hash::TreeHash;
+
+use crate::{
+    network::{NetworkConditions, SimulatedNetwork},
+    node::SimulatedNode,
+};
+
+/// The genesis time of every simulated chain. Only the time since genesis matters.
+const GENESIS_TIME: u64 = 0;
+
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub num_nodes: usize,
+    /// The validators are assigned to the nodes in turn
+    pub num_validators: u64,
+    pub seconds_per_slot: u64,
+    /// Seeds the random decisions of the network
+    pub seed: u64,
+    pub network_conditions: NetworkConditions,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            num_nodes: 4,
+            num_validators: 4,
+            seconds_per_slot: 4,
+            seed: 0,
+            network_conditions: NetworkConditions::default(),
+        }
+    }
+}
+
+enum Event {
+    /// The start of the next interval
+    Tick,
+    /// A message arrives at a node. Gossiped messages carry their ID, so every node handles them
+    /// only once like gossipsub does.
+    Deliver {
+        to: usize,
+        message_id: Option<B256>,
+        message: Box<LeanChainServiceMessage>,
+    },
+}
+
+/// Runs lean nodes against a virtual clock and a [SimulatedNetwork].
+///
+/// Events are handled one after the other in the order of their time, and in the order they were
+/// scheduled for the same time. The nodes relay the gossip they receive, so messages also reach
+/// nodes through others while the direct link drops them.
+pub struct Simulation {
+    clock: SlotClock,
+    nodes: Vec<SimulatedNode>,
+    network: SimulatedNetwork,
+    events: BTreeMap<(Duration, u64), Event>,
+    next_sequence: u64,
+    /// The gossip messages every node has seen, by node index and message ID
+    seen_messages: HashSet<(usize, B256)>,
+}
+
+impl Simulation {
+    pub async fn new(config: SimulationConfig) -> anyhow::Result<Self> {
+        ensure!(config.num_nodes > 0, "A simulation needs at least one node");
+        ensure!(
+            config.num_validators > 0,
+            "A simulation needs at least one validator"
+        );
+
+        let (genesis_block, genesis_state) = generate_genesis(config.num_validators, GENESIS_TIME);
+        let mut nodes = Vec::with_capacity(config.num_nodes);
+        for index in 0..config.num_nodes {
+            let validator_ids = (0..config.num_validators)
+                .filter(|validator_id| *validator_id as usize % config.num_nodes == index)
+                .collect();
+            nodes.push(
+                SimulatedNode::new(validator_ids, genesis_block.clone(), genesis_state.clone())
+                    .await?,
+            );
+        }
+
+        let clock = SlotClock::manual(
+            GENESIS_TIME,
+            config.seconds_per_slot,
+            INTERVALS_PER_SLOT,
+            Duration::from_secs(GENESIS_TIME),
+        )
+        .with_slot_offset(SLOT_OFFSET);
+        let mut simulation = Self {
+            clock,
+            nodes,
+            network: SimulatedNetwork::new(config.seed, config.network_conditions),
+            events: BTreeMap::new(),
+            next_sequence: 0,
+            seen_messages: HashSet::new(),
+        };
+        simulation.schedule(simulation.clock.genesis_time(), Event::Tick);
+
+        Ok(simulation)
+    }
+
+    pub fn nodes(&self) -> &[SimulatedNode] {
+        &self.nodes
+    }
+
+    pub fn network(&mut self) -> &mut SimulatedNetwork {
+        &mut self.network
+    }
+
+    /// The current slot of the virtual clock.
+    pub fn current_slot(&self) -> u64 {
+        self.clock
+            .current_slot()
+            .expect("The virtual clock starts at genesis")
+    }
+
+    /// Runs the simulation until the start of `slot`, checking after every slot that the nodes
+    /// haven't finalized conflicting checkpoints.
+    pub async fn run_until_slot(&mut self, slot: u64) -> anyhow::Result<()> {
+        let end = self.clock.start_of(slot);
+        while let Some(entry) = self.events.first_entry()
+            && entry.key().0 < end
+        {
+            let ((time, _), event) = entry.remove_entry();
+            self.clock.set_now(time);
+
+            match event {
+                Event::Tick => {
+                    let slot_time = self
+                        .clock
+                        .current_slot_time()
+                        .expect("The virtual clock starts at genesis");
+                    for index in 0..self.nodes.len() {
+                        self.nodes[index].on_tick(slot_time).await?;
+                        self.dispatch_outbound(index).await?;
+                    }
+                    if slot_time.interval == 0 {
+                        self.check_safety().await?;
+                    }
+                    self.schedule(time + self.clock.interval_duration(), Event::Tick);
+                }
+                Event::Deliver {
+                    to,
+                    message_id,
+                    message,
+                } => {
+                    if let Some(message_id) = message_id
+                        && !self.seen_messages.insert((to, message_id))
+                    {
+                        continue;
+                    }
+                    self.nodes[to].receive(*message).await?;
+                    self.dispatch_outbound(to).await?;
+                }
+            }
+        }
+        self.clock.set_now(end.max(self.clock.now()));
+
+        Ok(())
+    }
+
+    /// Checks that the finalized checkpoints of all nodes are on a single chain, i.e. the one of
+    /// the lower slot is an ancestor of the other.
+    pub async fn check_safety(&self) -> anyhow::Result<()> {
+        let mut finalized = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            finalized.push(node.latest_finalized().await?);
+        }
+
+        for (index, checkpoint) in finalized.iter().enumerate() {
+            // The checkpoints of the genesis state have a zero root, and all nodes start from the
+            // same genesis block
+            if checkpoint.slot == 0 {
+                continue;
+            }
+            for (other_index, other_checkpoint) in finalized.iter().enumerate() {
+                if index == other_index || checkpoint.slot > other_checkpoint.slot {
+                    continue;
+                }
+                let ancestor = self.nodes[other_index]
+                    .ancestor_at(other_checkpoint.root, checkpoint.slot)
+                    .await?;
+                ensure!(
+                    ancestor == Some(checkpoint.root),
+                    "Node {index} finalized {checkpoint:?}, which conflicts with {other_checkpoint:?} finalized by node {other_index}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn schedule(&mut self, time: Duration, event: Event) {
+        self.events.insert((time, self.next_sequence), event);
+        self.next_sequence += 1;
+    }
+
+    /// Sends the gossip and Req/Resp requests of a node over the network.
+    async fn dispatch_outbound(&mut self, from: usize) -> anyhow::Result<()> {
+        for request in self.nodes[from].take_outbound() {
+            match request {
+                LeanP2PRequest::GossipBlock(signed_block) => {
+                    self.publish(from, signed_block.message.tree_hash_root(), || {
+                        LeanChainServiceMessage::ProcessBlock {
+                            signed_block: signed_block.clone(),
+                            is_trusted: false,
+                            need_gossip: true,
+                        }
+                    });
+                }
+                LeanP2PRequest::GossipVote(signed_vote) => {
+                    self.publish(from, signed_vote.tree_hash_root(), || {
+                        LeanChainServiceMessage::ProcessVote {
+                            signed_vote: signed_vote.clone(),
+                            is_trusted: false,
+                            need_gossip: true,
+                        }
+                    });
+                }
+                LeanP2PRequest::RequestBlocksByRoot(block_roots) => {
+                    for block_root in block_roots {
+                        self.request_block(from, block_root).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn publish(
+        &mut self,
+        from: usize,
+        message_id: B256,
+        message: impl Fn() -> LeanChainServiceMessage,
+    ) {
+        self.seen_messages.insert((from, message_id));
+        for to in 0..self.nodes.len() {
+            if to == from || self.seen_messages.contains(&(to, message_id)) {
+                continue;
+            }
+            if let Some(latency) = self.network.transmit(from, to) {
+                self.schedule(
+                    self.clock.now() + latency,
+                    Event::Deliver {
+                        to,
+                        message_id: Some(message_id),
+                        message: Box::new(message()),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Requests a block from the first reachable peer which has it. The block is lost if either
+    /// the request or the response is.
+    async fn request_block(&mut self, from: usize, block_root: B256) -> anyhow::Result<()> {
+        for offset in 1..self.nodes.len() {
+            let peer = (from + offset) % self.nodes.len();
+            if !self.network.is_connected(from, peer) {
+                continue;
+            }
+            let Some(signed_block) = self.nodes[peer].block(block_root).await? else {
+                continue;
+            };
+
+            if let (Some(request_latency), Some(response_latency)) = (
+                self.network.transmit(from, peer),
+                self.network.transmit(peer, from),
+            ) {
+                self.schedule(
+                    self.clock.now() + request_latency + response_latency,
+                    Event::Deliver {
+                        to: from,
+                        message_id: None,
+                        message: Box::new(LeanChainServiceMessage::ProcessBlock {
+                            signed_block,
+                            is_trusted: false,
+                            need_gossip: false,
+                        }),
+                    },
+                );
+            }
+            break;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use lean_simulation::{
+    network::NetworkConditions,
+    simulation::{Simulation, SimulationConfig},
+};
+
+async fn justified_slots(simulation: &Simulation) -> anyhow::Result<Vec<u64>> {
+    let mut slots = vec![];
+    for node in simulation.nodes() {
+        slots.push(node.latest_justified().await?.slot);
+    }
+    Ok(slots)
+}
+
+async fn finalized_slots(simulation: &Simulation) -> anyhow::Result<Vec<u64>> {
+    let mut slots = vec![];
+    for node in simulation.nodes() {
+        slots.push(node.latest_finalized().await?.slot);
+    }
+    Ok(slots)
+}
+
+#[tokio::test]
+async fn test_healthy_network_finalizes() -> anyhow::Result<()> {
+    let mut simulation = Simulation::new(SimulationConfig::default()).await?;
+    simulation.run_until_slot(24).await?;
+
+    for (index, finalized_slot) in finalized_slots(&simulation).await?.into_iter().enumerate() {
+        assert!(finalized_slot > 0, "Node {index} hasn't finalized anything");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_runs_are_deterministic() -> anyhow::Result<()> {
+    let config = SimulationConfig {
+        seed: 42,
+        network_conditions: NetworkConditions {
+            min_latency: Duration::from_millis(50),
+            max_latency: Duration::from_millis(1500),
+            drop_probability: 0.2,
+        },
+        ..Default::default()
+    };
+
+    let mut heads = vec![];
+    for _ in 0..2 {
+        let mut simulation = Simulation::new(config.clone()).await?;
+        simulation.run_until_slot(16).await?;
+
+        let mut run_heads = vec![];
+        for node in simulation.nodes() {
+            run_heads.push(node.head().await);
+        }
+        heads.push(run_heads);
+    }
+
+    assert_eq!(heads[0], heads[1]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_justifies_despite_latency_and_drops() -> anyhow::Result<()> {
+    for seed in 0..4 {
+        let mut simulation = Simulation::new(SimulationConfig {
+            seed,
+            network_conditions: NetworkConditions {
+                min_latency: Duration::from_millis(50),
+                max_latency: Duration::from_millis(500),
+                drop_probability: 0.1,
+            },
+            ..Default::default()
+        })
+        .await?;
+        simulation.run_until_slot(32).await?;
+
+        for (index, justified_slot) in justified_slots(&simulation).await?.into_iter().enumerate() {
+            assert!(
+                justified_slot > 0,
+                "Node {index} hasn't justified anything with seed {seed}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_majority_partition_keeps_justifying() -> anyhow::Result<()> {
+    let mut simulation = Simulation::new(SimulationConfig::default()).await?;
+    simulation.run_until_slot(8).await?;
+    let justified_before = justified_slots(&simulation).await?;
+
+    // 3 of 4 validators reach the 2/3 majority on their own
+    simulation.network().partition(&[&[0, 1, 2], &[3]]);
+    simulation.run_until_slot(20).await?;
+    let justified_during = justified_slots(&simulation).await?;
+    for (index, (during, before)) in justified_during
+        .iter()
+        .zip(&justified_before)
+        .enumerate()
+        .take(3)
+    {
+        assert!(
+            during > before,
+            "Node {index} stopped justifying in the majority partition"
+        );
+    }
+
+    simulation.network().heal();
+    simulation.run_until_slot(36).await?;
+    assert!(
+        justified_slots(&simulation).await?[3] > justified_during[3],
+        "The minority node didn't catch up with the majority"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_even_split_recovers_after_healing() -> anyhow::Result<()> {
+    let mut simulation = Simulation::new(SimulationConfig::default()).await?;
+    simulation.run_until_slot(8).await?;
+
+    // Neither half has a 2/3 majority
+    simulation.network().partition(&[&[0, 1], &[2, 3]]);
+    simulation.run_until_slot(16).await?;
+    let justified_at_heal = justified_slots(&simulation).await?;
+
+    simulation.network().heal();
+    simulation.run_until_slot(40).await?;
+    for (index, justified_slot) in justified_slots(&simulation).await?.into_iter().enumerate() {
+        assert!(
+            justified_slot > justified_at_heal[index],
+            "Node {index} didn't justify after the partition healed"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,2 @@
+pub mod ssz_static;
+pub mod state_transition;
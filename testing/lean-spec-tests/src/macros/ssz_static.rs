@@ -0,0 +1,53 @@
+#[macro_export]
+macro_rules! test_lean_consensus_type {
+    ($struct_name:ident) => {
+        paste::paste! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod [<tests_ $struct_name>] {
+                use super::*;
+                use serde_yaml::Value;
+                use snap::raw::Decoder;
+                use std::{path::Path, str::FromStr};
+                use tree_hash::TreeHash;
+                use ssz::Decode;
+                use ssz::Encode;
+
+                #[test]
+                fn test_type() {
+                    let base_path = format!("fixtures/ssz_static/{}", stringify!($struct_name));
+
+                    for case_dir in utils::case_dirs(Path::new(&base_path)).expect("cannot find test asset") {
+                        println!("Testing case: {}", case_dir.display());
+
+                        // Read and parse hash root
+                        let hash_root = {
+                            let hash_root_content = std::fs::read_to_string(case_dir.join("roots.yaml"))
+                                .expect("cannot find test asset");
+                            let value: Value = serde_yaml::from_str(&hash_root_content).unwrap();
+                            alloy_primitives::B256::from_str(value.get("root").unwrap().as_str().unwrap())
+                                .unwrap()
+                        };
+
+                        // Deserialize the struct
+                        let content = {
+                            let value = std::fs::read_to_string(case_dir.join("value.yaml"))
+                                .expect("cannot find test asset");
+                            serde_yaml::from_str::<$struct_name>(&value).unwrap()
+                        };
+
+                        // Read and decompress SSZ snappy file
+                        let ssz_snappy = std::fs::read(case_dir.join("serialized.ssz_snappy")).expect("cannot find test asset");
+                        let mut decoder = Decoder::new();
+                        let ssz = decoder.decompress_vec(&ssz_snappy).unwrap();
+
+                        // Perform the assertions
+                        assert_eq!(ssz, content.as_ssz_bytes());
+                        assert_eq!(content, $struct_name::from_ssz_bytes(&ssz).unwrap());
+                        assert_eq!(hash_root, content.tree_hash_root());
+                    }
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,85 @@
+#[macro_export]
+macro_rules! test_lean_state_transition {
+    ($operation_name:ident, $path:expr) => {
+        paste::paste! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod $operation_name {
+                use std::{fs, path::Path};
+
+                use serde_yaml;
+
+                use super::*;
+
+                #[derive(Debug, serde::Deserialize)]
+                struct MetaData {
+                    blocks_count: usize,
+                }
+
+                #[test]
+                fn $operation_name() {
+                    let base_path = format!("fixtures/{}", $path);
+
+                    for case_dir in utils::case_dirs(Path::new(&base_path)).expect("cannot find test asset") {
+                        let case_name = case_dir.file_name().unwrap().to_str().unwrap();
+                        println!("Testing case: {}", case_name);
+
+                        let meta: MetaData = {
+                            let meta_path = case_dir.join("meta.yaml");
+                            let content =
+                                fs::read_to_string(meta_path).expect("Failed to read meta.yaml");
+                            serde_yaml::from_str(&content).expect("Failed to parse meta.yaml")
+                        };
+
+                        let mut state: LeanState =
+                            utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                                .expect("cannot find test asset (pre.ssz_snappy)");
+
+                        let mut result: Result<(), String> = Ok(());
+
+                        for i in 0..meta.blocks_count {
+                            let signed_block: SignedBlock =
+                                utils::read_ssz_snappy(&case_dir.join(format!("blocks_{i}.ssz_snappy")))
+                                    .expect(&format!("cannot find test asset (blocks_{i}.ssz_snappy)"));
+
+                            result = state
+                                .state_transition(&signed_block, true, true)
+                                .map_err(|err| err.to_string());
+                            if result.is_err() {
+                                break;
+                            }
+                        }
+
+                        let expected_post = utils::read_ssz_snappy::<LeanState>(&case_dir.join("post.ssz_snappy"));
+
+                        match (result, expected_post) {
+                            (Ok(_), Ok(expected)) => {
+                                assert_eq!(
+                                    state, expected,
+                                    "Post state mismatch in case {}",
+                                    case_name
+                                );
+                            }
+                            (Ok(_), Err(_)) => {
+                                panic!("Test case {} should have failed but succeeded", case_name);
+                            }
+                            (Err(err), Ok(_)) => {
+                                panic!(
+                                    "Test case {} should have succeeded but failed, err={:?}",
+                                    case_name, err
+                                );
+                            }
+                            (Err(_), Err(_)) => {
+                                // Expected: invalid blocks result in an error and no post state.
+                                println!(
+                                    "Test case {} failed as expected, no post state available.",
+                                    case_name
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
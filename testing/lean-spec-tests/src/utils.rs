@@ -0,0 +1,24 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use snap::raw::Decoder;
+
+pub fn read_ssz_snappy<T: ssz::Decode>(path: &Path) -> anyhow::Result<T> {
+    let ssz_snappy = std::fs::read(path)?;
+    let mut decoder = Decoder::new();
+    let ssz = decoder.decompress_vec(&ssz_snappy)?;
+    T::from_ssz_bytes(&ssz).map_err(|err| anyhow!("Failed to decode SSZ: {:?}", err))
+}
+
+/// Returns the case directories under `path`, sorted by name.
+pub fn case_dirs(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut case_dirs = vec![];
+    for entry in std::fs::read_dir(path)? {
+        let case_dir = entry?.path();
+        if case_dir.is_dir() {
+            case_dirs.push(case_dir);
+        }
+    }
+    case_dirs.sort();
+    Ok(case_dirs)
+}
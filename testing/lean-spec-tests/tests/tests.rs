@@ -0,0 +1,24 @@
+#![cfg(feature = "lean-spec-tests")]
+
+use lean_spec_tests::{test_lean_consensus_type, test_lean_state_transition, utils};
+use ream_consensus_lean::{
+    block::{Block, BlockBody, BlockHeader, SignedBlock},
+    checkpoint::Checkpoint,
+    config::Config,
+    state::LeanState,
+    vote::{SignedVote, Vote},
+};
+
+// Lean consensus types
+test_lean_consensus_type!(Block);
+test_lean_consensus_type!(BlockBody);
+test_lean_consensus_type!(BlockHeader);
+test_lean_consensus_type!(Checkpoint);
+test_lean_consensus_type!(Config);
+test_lean_consensus_type!(LeanState);
+test_lean_consensus_type!(SignedBlock);
+test_lean_consensus_type!(SignedVote);
+test_lean_consensus_type!(Vote);
+
+// Testing the state transition
+test_lean_state_transition!(state_transition, "state_transition");
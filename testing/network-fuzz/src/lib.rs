@@ -0,0 +1,87 @@
+//! Property tests feeding peer controlled bytes to the decoders of the networking layer.
+//!
+//! Every test binary installs [TrackingAllocator] as its global allocator, so the tests can assert
+//! that malformed input neither panics nor makes the decoders allocate more than the input
+//! justifies.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+thread_local! {
+    static LARGEST_ALLOCATION: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+fn record_allocation(size: usize) {
+    // The thread local is gone while the thread is torn down, those allocations aren't tracked
+    let _ = LARGEST_ALLOCATION.try_with(|largest| {
+        if let Some(largest_size) = largest.get()
+            && size > largest_size
+        {
+            largest.set(Some(size));
+        }
+    });
+}
+
+/// Forwards to the system allocator and records the largest allocation made on the current
+/// thread while [largest_allocation] runs.
+pub struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::alloc`
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::alloc_zeroed`
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation(new_size);
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::realloc`
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::dealloc`
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+/// Runs `f` and returns its output together with the size of the largest allocation it made.
+///
+/// Only allocations made on the current thread are counted, so the test binary must install
+/// [TrackingAllocator] as its global allocator and `f` must not hand work to other threads.
+pub fn largest_allocation<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    LARGEST_ALLOCATION.with(|largest| largest.set(Some(0)));
+    let output = f();
+    let largest_size = LARGEST_ALLOCATION.with(|largest| largest.replace(None));
+    (output, largest_size.unwrap_or_default())
+}
+
+/// The most an SSZ decoder may allocate at once for `input_len` bytes of input.
+///
+/// Decoded values can take more memory than their encoding, e.g. a list of variable size items
+/// reserves space for every item up front, so this is a generous multiple of the input plus some
+/// headroom for small inputs.
+pub fn ssz_allocation_limit(input_len: usize) -> usize {
+    4 * input_len + 64 * 1024
+}
+
+/// Prefixes `payload` with `length` encoded as an unsigned LEB128 varint, which is how both the
+/// Req/Resp chunks and the raw snappy blocks announce the length of the uncompressed data.
+pub fn length_prefixed(mut length: u64, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![];
+    while length >= 0x80 {
+        bytes.push(length as u8 | 0x80);
+        length >>= 7;
+    }
+    bytes.push(length as u8);
+    bytes.extend_from_slice(payload);
+    bytes
+}
@@ -0,0 +1,98 @@
+use libp2p::gossipsub::{DataTransform, RawMessage, TopicHash};
+use network_fuzz::{TrackingAllocator, largest_allocation, length_prefixed, ssz_allocation_limit};
+use proptest::{collection::vec, prelude::*};
+use ream_consensus_misc::constants::beacon::genesis_validators_root;
+use ream_network_spec::networks::{beacon_network_spec, initialize_test_network_spec};
+use ream_p2p::{
+    gossipsub::{
+        beacon::{
+            message::GossipsubMessage,
+            topics::{GossipTopic, GossipTopicKind},
+        },
+        snappy::SnappyTransform,
+    },
+    utils::max_message_size,
+};
+use snap::raw::Encoder;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+const TOPIC_KINDS: [GossipTopicKind; 13] = [
+    GossipTopicKind::BeaconBlock,
+    GossipTopicKind::AggregateAndProof,
+    GossipTopicKind::VoluntaryExit,
+    GossipTopicKind::ProposerSlashing,
+    GossipTopicKind::AttesterSlashing,
+    GossipTopicKind::BeaconAttestation(0),
+    GossipTopicKind::SyncCommittee(0),
+    GossipTopicKind::SyncCommitteeContributionAndProof,
+    GossipTopicKind::BlsToExecutionChange,
+    GossipTopicKind::LightClientFinalityUpdate,
+    GossipTopicKind::LightClientOptimisticUpdate,
+    GossipTopicKind::BlobSidecar(0),
+    GossipTopicKind::DataColumnSidecar(0),
+];
+
+fn raw_message(data: Vec<u8>) -> RawMessage {
+    RawMessage {
+        source: None,
+        data,
+        sequence_number: None,
+        topic: TopicHash::from_raw("fuzz"),
+        signature: None,
+        key: None,
+        validated: false,
+    }
+}
+
+/// Decompresses `data` like an incoming gossip message and decodes it as a message of every
+/// topic, asserting that nothing allocates more than the largest message a peer may gossip.
+fn decode_all(data: Vec<u8>) -> Result<(), TestCaseError> {
+    initialize_test_network_spec();
+    let transform = SnappyTransform::new(max_message_size() as usize);
+    let (message, largest_size) =
+        largest_allocation(|| transform.inbound_transform(raw_message(data)));
+    prop_assert!(
+        largest_size <= max_message_size() as usize,
+        "Decompression allocated {largest_size} bytes"
+    );
+    let Ok(message) = message else {
+        return Ok(());
+    };
+    prop_assert!(message.data.len() <= max_message_size() as usize);
+
+    let fork = beacon_network_spec().fork_digest(genesis_validators_root());
+    for kind in TOPIC_KINDS {
+        let topic = TopicHash::from(GossipTopic { fork, kind });
+        let (_, largest_size) =
+            largest_allocation(|| GossipsubMessage::decode(&topic, &message.data));
+        prop_assert!(
+            largest_size <= ssz_allocation_limit(message.data.len()),
+            "Decoding {kind:?} allocated {largest_size} bytes for {} bytes",
+            message.data.len()
+        );
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_decode_arbitrary_compressed_bytes(data in vec(any::<u8>(), 0..4096)) {
+        decode_all(data)?;
+    }
+
+    #[test]
+    fn test_decode_arbitrary_uncompressed_bytes(data in vec(any::<u8>(), 0..4096)) {
+        let compressed = Encoder::new().compress_vec(&data).expect("Failed to compress");
+        decode_all(compressed)?;
+    }
+
+    #[test]
+    fn test_decode_arbitrary_decompressed_length(
+        length in any::<u64>(),
+        payload in vec(any::<u8>(), 0..1024),
+    ) {
+        decode_all(length_prefixed(length, &payload))?;
+    }
+}
@@ -0,0 +1,68 @@
+use libp2p::bytes::BytesMut;
+use network_fuzz::{TrackingAllocator, largest_allocation, length_prefixed};
+use proptest::{collection::vec, prelude::*};
+use ream_network_spec::networks::initialize_test_network_spec;
+use ream_p2p::{
+    req_resp::{
+        Chain,
+        inbound_protocol::InboundSSZSnappyCodec,
+        outbound_protocol::OutboundSSZSnappyCodec,
+        protocol_id::{ProtocolId, SupportedProtocol},
+    },
+    utils::max_message_size,
+};
+use tokio_util::codec::Decoder;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn protocols() -> Vec<ProtocolId> {
+    [Chain::Beacon, Chain::Lean]
+        .into_iter()
+        .flat_map(SupportedProtocol::supported_protocols)
+        .collect()
+}
+
+/// Decodes `bytes` as a request and as a response of every protocol, asserting that no decoder
+/// allocates more than the largest message a peer is allowed to send.
+fn decode_all(bytes: &[u8]) -> Result<(), TestCaseError> {
+    initialize_test_network_spec();
+    for protocol in protocols() {
+        let mut src = BytesMut::from(bytes);
+        let (_, largest_size) =
+            largest_allocation(|| InboundSSZSnappyCodec::new(protocol.clone()).decode(&mut src));
+        prop_assert!(
+            largest_size <= max_message_size() as usize,
+            "{} request allocated {largest_size} bytes",
+            protocol.protocol_id
+        );
+
+        let mut src = BytesMut::from(bytes);
+        let (_, largest_size) =
+            largest_allocation(|| OutboundSSZSnappyCodec::new(protocol.clone()).decode(&mut src));
+        prop_assert!(
+            largest_size <= max_message_size() as usize,
+            "{} response allocated {largest_size} bytes",
+            protocol.protocol_id
+        );
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_decode_arbitrary_bytes(bytes in vec(any::<u8>(), 0..4096)) {
+        decode_all(&bytes)?;
+    }
+
+    #[test]
+    fn test_decode_arbitrary_length_prefix(
+        length in any::<u64>(),
+        payload in vec(any::<u8>(), 0..1024),
+    ) {
+        // As a request, and as a successful response without and with context bytes
+        decode_all(&length_prefixed(length, &payload))?;
+        decode_all(&[&[0], length_prefixed(length, &payload).as_slice()].concat())?;
+        decode_all(&[&[0; 5], length_prefixed(length, &payload).as_slice()].concat())?;
+    }
+}
@@ -0,0 +1,81 @@
+use std::{fs, path::Path, sync::LazyLock};
+
+use network_fuzz::{TrackingAllocator, largest_allocation, ssz_allocation_limit};
+use proptest::{collection::vec, option, prelude::*};
+use ream_consensus_beacon::electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState};
+use snap::raw::Decoder;
+use ssz::Decode;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+const ASSETS_PATH: &str = "../gossip-validation/tests/assets/sepolia";
+
+fn read_asset(path: &str) -> Vec<u8> {
+    let ssz_snappy = fs::read(Path::new(ASSETS_PATH).join(path)).expect("Failed to read asset");
+    Decoder::new()
+        .decompress_vec(&ssz_snappy)
+        .expect("Failed to decompress asset")
+}
+
+static BLOCK_BYTES: LazyLock<Vec<u8>> =
+    LazyLock::new(|| read_asset("blocks/slot_8084248.ssz_snappy"));
+
+static STATE_BYTES: LazyLock<Vec<u8>> =
+    LazyLock::new(|| read_asset("states/slot_8084248.ssz_snappy"));
+
+/// XORs the bytes at the given positions and truncates the result, which keeps most of a valid
+/// encoding intact so the decoder gets past the first offsets.
+fn mutate(bytes: &[u8], flips: &[(usize, u8)], truncate_at: Option<usize>) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    if !bytes.is_empty() {
+        for (position, mask) in flips {
+            let length = bytes.len();
+            bytes[position % length] ^= mask;
+        }
+    }
+    if let Some(truncate_at) = truncate_at {
+        bytes.truncate(truncate_at % (bytes.len() + 1));
+    }
+    bytes
+}
+
+fn decode<T: Decode>(bytes: &[u8]) -> Result<(), TestCaseError> {
+    let (_, largest_size) = largest_allocation(|| T::from_ssz_bytes(bytes));
+    prop_assert!(
+        largest_size <= ssz_allocation_limit(bytes.len()),
+        "Decoding {} allocated {largest_size} bytes for {} bytes",
+        std::any::type_name::<T>(),
+        bytes.len()
+    );
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_decode_arbitrary_bytes(bytes in vec(any::<u8>(), 0..4096)) {
+        decode::<SignedBeaconBlock>(&bytes)?;
+        decode::<BeaconState>(&bytes)?;
+    }
+
+    #[test]
+    fn test_decode_mutated_block(
+        flips in vec((any::<usize>(), any::<u8>()), 1..16),
+        truncate_at in option::of(any::<usize>()),
+    ) {
+        decode::<SignedBeaconBlock>(&mutate(&BLOCK_BYTES, &flips, truncate_at))?;
+    }
+}
+
+proptest! {
+    // Decoding a state takes a while
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn test_decode_mutated_state(
+        flips in vec((any::<usize>(), any::<u8>()), 1..16),
+        truncate_at in option::of(any::<usize>()),
+    ) {
+        decode::<BeaconState>(&mutate(&STATE_BYTES, &flips, truncate_at))?;
+    }
+}